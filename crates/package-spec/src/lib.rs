@@ -2,6 +2,8 @@ use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+use url::Url;
+
 use nom::combinator::all_consuming;
 use nom::Err;
 use oro_node_semver::{Version, VersionReq as Range};
@@ -26,6 +28,9 @@ pub enum PackageSpec {
     Dir {
         path: PathBuf,
     },
+    Link {
+        path: PathBuf,
+    },
     Alias {
         name: String,
         spec: Box<PackageSpec>,
@@ -36,6 +41,9 @@ pub enum PackageSpec {
         requested: Option<VersionSpec>,
     },
     Git(GitInfo),
+    Remote {
+        url: Url,
+    },
 }
 
 impl PackageSpec {
@@ -43,7 +51,7 @@ impl PackageSpec {
         use PackageSpec::*;
         match self {
             Alias { spec, .. } => spec.is_npm(),
-            Dir { .. } | Git(..) => false,
+            Dir { .. } | Link { .. } | Git(..) | Remote { .. } => false,
             Npm { .. } => true,
         }
     }
@@ -70,7 +78,9 @@ impl fmt::Display for PackageSpec {
         use PackageSpec::*;
         match self {
             Dir { path } => write!(f, "{}", path.display()),
+            Link { path } => write!(f, "link:{}", path.display()),
             Git(info) => write!(f, "{}", info),
+            Remote { url } => write!(f, "{}", url),
             Npm {
                 ref scope,
                 ref name,
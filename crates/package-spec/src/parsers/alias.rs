@@ -6,7 +6,7 @@ use nom::sequence::{preceded, tuple};
 use nom::IResult;
 
 use crate::error::SpecParseError;
-use crate::parsers::{git, npm, path, util};
+use crate::parsers::{git, npm, path, remote, util};
 use crate::PackageSpec;
 
 // alias_spec := [ [ '@' ], not('/')+ '/' ] not('@/')+ '@' prefixed-package-arg
@@ -38,7 +38,7 @@ pub(crate) fn alias_spec<'a>(
     )(input)
 }
 
-/// prefixed_package-arg := ( "npm:" npm-pkg ) | ( [ "file:" ] path )
+/// prefixed_package-arg := ( "npm:" npm-pkg ) | ( [ "file:" ] path ) | link | remote
 fn prefixed_package_spec<'a>(
     input: &'a str,
 ) -> IResult<&'a str, PackageSpec, SpecParseError<&'a str>> {
@@ -47,6 +47,8 @@ fn prefixed_package_spec<'a>(
         alt((
             // Paths don't need to be prefixed, but they can be.
             preceded(opt(tag("file:")), path::path_spec),
+            path::link_spec,
+            remote::remote_spec,
             git::git_spec,
             preceded(tag("npm:"), npm::npm_spec),
         )),
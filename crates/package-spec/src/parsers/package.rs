@@ -6,10 +6,10 @@ use nom::sequence::preceded;
 use nom::IResult;
 
 use crate::error::SpecParseError;
-use crate::parsers::{alias, git, npm, path};
+use crate::parsers::{alias, git, npm, path, remote};
 use crate::PackageSpec;
 
-/// package-spec := alias | ( [ "npm:" ] npm-pkg ) | ( [ "file:" ] path ) | git-pkg
+/// package-spec := alias | ( [ "npm:" ] npm-pkg ) | ( [ "file:" ] path ) | link | remote | git-pkg
 pub(crate) fn package_spec<'a>(
     input: &'a str,
 ) -> IResult<&'a str, PackageSpec, SpecParseError<&'a str>> {
@@ -18,6 +18,8 @@ pub(crate) fn package_spec<'a>(
         alt((
             alias::alias_spec,
             preceded(opt(tag("file:")), path::path_spec),
+            path::link_spec,
+            remote::remote_spec,
             git::git_spec,
             preceded(opt(tag("npm:")), npm::npm_spec),
         )),
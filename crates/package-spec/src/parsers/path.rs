@@ -24,6 +24,19 @@ pub(crate) fn path_spec<'a>(
     )(input)
 }
 
+/// link := 'link:' ( relative-dir | absolute-dir )
+pub(crate) fn link_spec<'a>(
+    input: &'a str,
+) -> IResult<&'a str, PackageSpec, SpecParseError<&'a str>> {
+    context(
+        "link spec",
+        map(
+            preceded(tag("link:"), alt((relative_path, absolute_path))),
+            |p| PackageSpec::Link { path: p },
+        ),
+    )(input)
+}
+
 /// relative-path := [ '.' ] '.' [path-sep] .*
 fn relative_path<'a>(input: &'a str) -> IResult<&'a str, PathBuf, SpecParseError<&'a str>> {
     context(
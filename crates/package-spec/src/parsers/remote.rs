@@ -0,0 +1,26 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case as tag;
+use nom::combinator::{map, map_res, peek, rest};
+use nom::error::context;
+use nom::sequence::preceded;
+use nom::IResult;
+use url::Url;
+
+use crate::error::SpecParseError;
+use crate::PackageSpec;
+
+/// remote := ( 'https://' | 'http://' ) .*
+pub(crate) fn remote_spec<'a>(
+    input: &'a str,
+) -> IResult<&'a str, PackageSpec, SpecParseError<&'a str>> {
+    context(
+        "remote tarball",
+        map(
+            preceded(
+                peek(alt((tag("https://"), tag("http://")))),
+                map_res(rest, Url::parse),
+            ),
+            |url| PackageSpec::Remote { url },
+        ),
+    )(input)
+}
@@ -134,3 +134,18 @@ fn spaces() -> Result<()> {
     assert!(res.is_err());
     Ok(())
 }
+
+#[test]
+fn link() -> Result<()> {
+    let res = parse("foo@link:../hey")?;
+    assert_eq!(
+        res,
+        PackageSpec::Alias {
+            name: "foo".into(),
+            spec: Box::new(PackageSpec::Link {
+                path: PathBuf::from("../hey"),
+            })
+        }
+    );
+    Ok(())
+}
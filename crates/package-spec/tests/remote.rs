@@ -0,0 +1,35 @@
+use oro_package_spec::{PackageSpec, PackageSpecError};
+use url::Url;
+
+type Result<T> = std::result::Result<T, PackageSpecError>;
+
+fn parse(input: &str) -> Result<PackageSpec> {
+    input.parse()
+}
+
+#[test]
+fn remote_tarball() -> Result<()> {
+    let res = parse("https://example.com/foo-1.0.0.tgz")?;
+    assert_eq!(
+        res,
+        PackageSpec::Remote {
+            url: Url::parse("https://example.com/foo-1.0.0.tgz").unwrap(),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn named_remote_tarball() -> Result<()> {
+    let res = parse("foo@http://example.com/foo-1.0.0.tgz")?;
+    assert_eq!(
+        res,
+        PackageSpec::Alias {
+            name: "foo".into(),
+            spec: Box::new(PackageSpec::Remote {
+                url: Url::parse("http://example.com/foo-1.0.0.tgz").unwrap(),
+            })
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,321 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check the locked packages against the npm security advisories.
+//!
+//! Every registry package of volt.lock is sent to the bulk advisory endpoint of the
+//! configured registry in a single request (`name -> [versions]`), and the advisories that
+//! come back are matched against the locked versions locally.
+
+use crate::{config::VoltConfig, model::lock_file::LockFile, registry::RegistryClient};
+
+use miette::{IntoDiagnostic, Result};
+use node_semver::{Range, Version};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+/// The bulk advisory endpoint, relative to the registry.
+pub const ADVISORIES_PATH: &str = "-/npm/v1/security/advisories/bulk";
+
+/// Most chains found for each vulnerability.
+pub const MAX_CHAINS: usize = 100;
+
+/// Severities in increasing order, as reported by the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "clap", derive(clap::ArgEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self {
+            Self::Info => "info",
+            Self::Low => "low",
+            Self::Moderate => "moderate",
+            Self::High => "high",
+            Self::Critical => "critical",
+        };
+
+        write!(f, "{}", severity)
+    }
+}
+
+/// An advisory returned by the registry for a package name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Advisory {
+    pub id: u64,
+    pub url: String,
+    pub title: String,
+    pub severity: Severity,
+    /// Range of the affected versions
+    pub vulnerable_versions: String,
+}
+
+/// A locked package affected by an advisory, with the chains of packages that pull it
+/// into the project, from a dependency of the project down to the package (at most
+/// [`MAX_CHAINS`] of them).
+#[derive(Debug, Clone, Serialize)]
+pub struct Vulnerability {
+    pub name: String,
+    pub version: String,
+    pub advisory: Advisory,
+    pub chains: Vec<Vec<String>>,
+}
+
+/// Fetch the advisories of every registry package in the lock file, keyed by package name.
+pub async fn fetch_advisories(
+    config: &VoltConfig,
+    lock_file: &LockFile,
+) -> Result<HashMap<String, Vec<Advisory>>> {
+    let mut packages: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+
+    for package in lock_file.dependencies.values() {
+        // git, local and tarball url packages aren't known to the registry
+        if package.is_registry() {
+            packages
+                .entry(&package.name)
+                .or_default()
+                .push(&package.version);
+        }
+    }
+
+    if packages.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let client = RegistryClient::new(config)?;
+
+    client
+        .send(
+            client
+                .request(Method::POST, ADVISORIES_PATH)
+                .json(&packages),
+        )
+        .await?
+        .json()
+        .await
+        .into_diagnostic()
+}
+
+/// Match the advisories against the locked versions, most severe first.
+///
+/// `direct` are the project's dependencies (`name -> range`), where the chains end.
+pub fn find_vulnerabilities(
+    lock_file: &LockFile,
+    advisories: &HashMap<String, Vec<Advisory>>,
+    direct: &BTreeMap<&String, &String>,
+) -> Vec<Vulnerability> {
+    let mut vulnerabilities = vec![];
+
+    for (key, package) in &lock_file.dependencies {
+        if !package.is_registry() {
+            continue;
+        }
+
+        let version = match package.version.parse::<Version>() {
+            Ok(version) => version,
+            Err(_) => continue,
+        };
+
+        for advisory in advisories.get(&package.name).into_iter().flatten() {
+            let affected = advisory
+                .vulnerable_versions
+                .parse::<Range>()
+                .map_or(false, |range| version.satisfies(&range));
+
+            if affected {
+                vulnerabilities.push(Vulnerability {
+                    name: package.name.clone(),
+                    version: package.version.clone(),
+                    advisory: advisory.clone(),
+                    chains: lock_file
+                        .chains(key, direct, MAX_CHAINS)
+                        .into_iter()
+                        .map(|chain| chain.into_iter().rev().collect())
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    vulnerabilities.sort_by(|a, b| {
+        b.advisory
+            .severity
+            .cmp(&a.advisory.severity)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.version.cmp(&b.version))
+    });
+
+    vulnerabilities
+}
+
+/// The upgrade fixing every advisory of a package.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fix {
+    /// A version within the caret range of the installed one
+    Compatible(Version),
+    /// A version only found past a major bump, installed with `--force`
+    Major(Version),
+    /// Every published version is affected
+    Unavailable,
+}
+
+/// The lowest published version above `current` affected by none of `advisories`.
+pub fn find_fix(current: &Version, versions: &[Version], advisories: &[&Advisory]) -> Fix {
+    let ranges: Vec<Range> = advisories
+        .iter()
+        .filter_map(|advisory| advisory.vulnerable_versions.parse().ok())
+        .collect();
+
+    let fixed = versions
+        .iter()
+        .filter(|version| *version > current && version.pre_release.is_empty())
+        .filter(|version| !ranges.iter().any(|range| version.satisfies(range)))
+        .min();
+
+    let compatible: Range = match format!("^{}", current).parse() {
+        Ok(range) => range,
+        Err(_) => return Fix::Unavailable,
+    };
+
+    match fixed {
+        Some(version) if version.satisfies(&compatible) => Fix::Compatible(version.clone()),
+        Some(version) => Fix::Major(version.clone()),
+        None => Fix::Unavailable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_fix, find_vulnerabilities, Advisory, Fix, Severity};
+    use crate::model::lock_file::{LockFile, LockedPackage};
+
+    use std::collections::{BTreeMap, HashMap};
+
+    fn package(name: &str, version: &str, dependencies: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            dependencies: dependencies
+                .iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn advisory(severity: Severity, vulnerable_versions: &str) -> Advisory {
+        Advisory {
+            id: 1,
+            url: String::new(),
+            title: String::new(),
+            severity,
+            vulnerable_versions: vulnerable_versions.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_advisories_to_chains() {
+        let mut lock_file = LockFile::new("volt.lock", false);
+
+        for package in [
+            package("mkdirp", "0.5.1", &[("minimist", "0.0.8")]),
+            package("minimist", "0.0.8", &[]),
+            package("minimist", "1.2.6", &[]),
+            package("ms", "2.0.0", &[]),
+        ] {
+            lock_file
+                .dependencies
+                .insert(format!("{}@{}", package.name, package.version), package);
+        }
+
+        let advisories = HashMap::from([
+            (
+                "minimist".to_string(),
+                vec![advisory(Severity::Critical, "<0.2.4")],
+            ),
+            ("ms".to_string(), vec![advisory(Severity::Low, "<2.0.0")]),
+            (
+                "mkdirp".to_string(),
+                vec![advisory(Severity::Low, "<1.0.0")],
+            ),
+        ]);
+
+        let (mkdirp, minimist) = ("mkdirp".to_string(), "minimist".to_string());
+        let (mkdirp_range, minimist_range) = ("^0.5.1".to_string(), "^1.2.0".to_string());
+        let direct = BTreeMap::from([(&mkdirp, &mkdirp_range), (&minimist, &minimist_range)]);
+
+        let vulnerabilities = find_vulnerabilities(&lock_file, &advisories, &direct);
+
+        assert_eq!(vulnerabilities.len(), 2);
+
+        // the most severe first, only the affected version of minimist
+        assert_eq!(vulnerabilities[0].name, "minimist");
+        assert_eq!(vulnerabilities[0].version, "0.0.8");
+        assert_eq!(
+            vulnerabilities[0].chains,
+            vec![vec![
+                "mkdirp@0.5.1".to_string(),
+                "minimist@0.0.8".to_string()
+            ]]
+        );
+
+        assert_eq!(vulnerabilities[1].name, "mkdirp");
+    }
+
+    #[test]
+    fn finds_the_lowest_fix() {
+        let versions: Vec<_> = ["0.0.8", "0.2.3", "0.2.4", "1.2.5", "1.2.6", "2.0.0-beta.1"]
+            .iter()
+            .map(|version| version.parse().unwrap())
+            .collect();
+
+        let pollution = advisory(Severity::Critical, "<0.2.4");
+        let regression = advisory(Severity::High, ">=1.0.0 <1.2.6");
+
+        assert_eq!(
+            find_fix(&"0.2.3".parse().unwrap(), &versions, &[&pollution]),
+            Fix::Compatible("0.2.4".parse().unwrap())
+        );
+        assert_eq!(
+            find_fix(&"0.0.8".parse().unwrap(), &versions, &[&pollution]),
+            Fix::Major("0.2.4".parse().unwrap())
+        );
+        assert_eq!(
+            find_fix(&"1.2.5".parse().unwrap(), &versions, &[&regression]),
+            Fix::Compatible("1.2.6".parse().unwrap())
+        );
+        assert_eq!(
+            find_fix(
+                &"1.2.6".parse().unwrap(),
+                &versions,
+                &[&advisory(Severity::Low, "*")]
+            ),
+            Fix::Unavailable
+        );
+    }
+}
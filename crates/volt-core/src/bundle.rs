@@ -0,0 +1,215 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Snapshots of the dependencies of a project, in one file.
+//!
+//! A bundle is a gzipped tarball of the project's `package.json` and `volt.lock` with the
+//! tarball of every registry package they resolve, laid out as a [vendored](crate::vendor)
+//! project:
+//!
+//! ```text
+//! package/package.json
+//! package/volt.lock
+//! package/vendor/react-18.2.0.tgz
+//! ```
+//!
+//! Restoring it writes those files back into a project, which is then installed from
+//! `vendor` alone, so the same tree is installed on a machine without any network. The
+//! same lock file always makes the same bundle, byte for byte.
+
+use crate::{
+    config::VoltConfig,
+    diff::read_tarball,
+    install::Resolution,
+    io::pack_files,
+    source::TarballDirectory,
+    utils::{decompress_gzip, errors::FilesystemError},
+    vendor::{vendor, VENDOR_DIRECTORY},
+};
+
+use miette::Result;
+
+use std::path::{Path, PathBuf};
+
+/// Pack the `package.json` and `volt.lock` of the project with the tarballs of the
+/// registry packages of `resolution`, from the source of `config`.
+pub async fn create_bundle(config: &VoltConfig, resolution: &Resolution) -> Result<Vec<u8>> {
+    let staging = tempfile::tempdir().map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: std::env::temp_dir().display().to_string(),
+    })?;
+
+    let mut files = vec![];
+
+    for (source, name) in [
+        (config.cwd()?.join("package.json"), "package.json"),
+        (config.lockfile()?, VoltConfig::VOLT_LOCK),
+    ] {
+        let target = staging.path().join(name);
+
+        std::fs::copy(&source, &target).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: source.display().to_string(),
+        })?;
+
+        files.push(PathBuf::from(name));
+    }
+
+    let directory = staging.path().join(VENDOR_DIRECTORY);
+
+    vendor(config, resolution, &directory).await?;
+
+    for package in resolution.tree.values() {
+        let name = TarballDirectory::file_name(package);
+
+        if directory.join(&name).exists() {
+            files.push(Path::new(VENDOR_DIRECTORY).join(name));
+        }
+    }
+
+    pack_files(staging.path(), &files)
+}
+
+/// Write the files of `bundle` into the project of `config`, replacing its `package.json`,
+/// `volt.lock` and vendored tarballs; the number of tarballs.
+pub fn restore_bundle(config: &VoltConfig, bundle: &[u8]) -> Result<usize> {
+    let files = read_tarball(&decompress_gzip(bundle)?)?;
+
+    if !files.contains_key("package.json") || !files.contains_key(VoltConfig::VOLT_LOCK) {
+        miette::bail!("not a volt bundle: it has no package.json or volt.lock");
+    }
+
+    let cwd = config.cwd()?;
+    let vendor_directory = config.vendor_directory()?;
+
+    // tarballs of packages the bundle doesn't have would otherwise be resolved too
+    if vendor_directory.exists() {
+        std::fs::remove_dir_all(&vendor_directory).map_err(|e| FilesystemError::Remove {
+            source: e,
+            path: vendor_directory.display().to_string(),
+        })?;
+    }
+
+    std::fs::create_dir_all(&vendor_directory).map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: vendor_directory.display().to_string(),
+    })?;
+
+    let mut tarballs = 0;
+
+    for (relative, contents) in files {
+        let path = match relative.split_once('/') {
+            None if relative == "package.json" || relative == VoltConfig::VOLT_LOCK => {
+                cwd.join(&relative)
+            }
+            Some((VENDOR_DIRECTORY, name)) if !name.contains('/') && name.ends_with(".tgz") => {
+                tarballs += 1;
+
+                vendor_directory.join(name)
+            }
+            _ => continue,
+        };
+
+        std::fs::write(&path, contents).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+    }
+
+    Ok(tarballs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_bundle, restore_bundle};
+    use crate::{
+        config::VoltConfig,
+        install::Resolution,
+        io::pack_files,
+        source::{FixtureSource, PackageSource},
+    };
+
+    use futures::executor::block_on;
+    use std::path::PathBuf;
+
+    fn tarball(manifest: serde_json::Value) -> Vec<u8> {
+        let directory = tempfile::tempdir().unwrap();
+
+        std::fs::write(directory.path().join("package.json"), manifest.to_string()).unwrap();
+
+        pack_files(directory.path(), &[PathBuf::from("package.json")]).unwrap()
+    }
+
+    #[test]
+    fn restores_a_project_from_its_bundle() {
+        let mut fixtures = FixtureSource::default();
+
+        fixtures
+            .add(tarball(
+                serde_json::json!({ "name": "ms", "version": "2.1.3" }),
+            ))
+            .unwrap();
+
+        let project = tempfile::tempdir().unwrap();
+
+        std::fs::write(
+            project.path().join("package.json"),
+            r#"{ "dependencies": { "ms": "^2.1.0" } }"#,
+        )
+        .unwrap();
+        std::fs::write(project.path().join("volt.lock"), "locked").unwrap();
+
+        let mut config = VoltConfig::new(project.path());
+        config.set_source(fixtures.clone());
+
+        let response = block_on(fixtures.resolve(&config, &"ms@^2.1.0".parse().unwrap())).unwrap();
+
+        let resolution = Resolution {
+            direct: vec!["ms@2.1.3".to_string()],
+            tree: response.tree,
+            ..Resolution::default()
+        };
+
+        let bundle = block_on(create_bundle(&config, &resolution)).unwrap();
+
+        assert_eq!(
+            bundle,
+            block_on(create_bundle(&config, &resolution)).unwrap()
+        );
+
+        let restored = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(restored.path().join("vendor")).unwrap();
+        std::fs::write(restored.path().join("vendor/left-pad-1.3.0.tgz"), "").unwrap();
+
+        let tarballs = restore_bundle(&VoltConfig::new(restored.path()), &bundle).unwrap();
+
+        assert_eq!(tarballs, 1);
+        assert_eq!(
+            std::fs::read_to_string(restored.path().join("volt.lock")).unwrap(),
+            "locked"
+        );
+        assert!(restored.path().join("package.json").exists());
+        assert!(restored.path().join("vendor/ms-2.1.3.tgz").exists());
+        assert!(!restored.path().join("vendor/left-pad-1.3.0.tgz").exists());
+
+        assert!(restore_bundle(
+            &VoltConfig::new(restored.path()),
+            &tarball(serde_json::json!({}))
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,490 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Inspect and clean the package store.
+//!
+//! The store is a cacache directory: every installed package has an index entry
+//! (`pkg::<name>::<version>::<integrity>`) pointing at a file map of `path -> hash`, and
+//! every file is kept once under its hash in `content-v2`. Git packages keep their packed
+//! tarball under `git::<source>`. Content that no entry refers to anymore is garbage.
+//!
+//! Installs record the entries each project uses in `projects.json` next to the store, so
+//! entries no project uses anymore can be found once the projects are gone or changed.
+
+use crate::{
+    config::VoltConfig,
+    git::tarball_key,
+    install::Resolution,
+    io::directory_size,
+    lock::{LockMode, ProcessLock},
+    utils::errors::FilesystemError,
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde::{Deserialize, Serialize};
+use ssri::Integrity;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// The directories of the store cacache owns; the store may be `~/.volt` itself, so
+/// nothing else in it is touched.
+const STORE_DIRECTORIES: [&str; 4] = ["content-v2", "index-v5", "tmp", "partial"];
+
+/// A package (or git tarball) in the store.
+#[derive(Debug)]
+pub struct StoreEntry {
+    /// The index key
+    pub key: String,
+    /// Name of the package, or the source of a git tarball
+    pub name: String,
+    /// Version of the package, empty for git tarballs
+    pub version: String,
+    /// Hash of what the key points at (the file map, or the tarball)
+    pub integrity: Integrity,
+}
+
+impl StoreEntry {
+    fn from_key(key: String, integrity: Integrity) -> Option<Self> {
+        if let Some(package) = key.strip_prefix("pkg::") {
+            // scoped names and versions have no `::`, integrities and urls may
+            let mut parts = package.splitn(3, "::");
+
+            let name = parts.next()?.to_string();
+            let version = parts.next()?.to_string();
+
+            Some(Self {
+                key,
+                name,
+                version,
+                integrity,
+            })
+        } else {
+            let name = key.strip_prefix("git::")?.to_string();
+
+            Some(Self {
+                key,
+                name,
+                version: String::new(),
+                integrity,
+            })
+        }
+    }
+
+    /// Whether this is a package's file map (and not a git tarball).
+    pub fn is_package(&self) -> bool {
+        self.key.starts_with("pkg::")
+    }
+
+    /// `name@version`, or the git source.
+    pub fn spec(&self) -> String {
+        if self.is_package() {
+            format!("{}@{}", self.name, self.version)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// The hashes of the files of a package, `None` if the file map can't be read.
+    pub fn files(&self, store: &Path) -> Option<HashMap<PathBuf, Integrity>> {
+        if !self.is_package() {
+            return Some(HashMap::new());
+        }
+
+        let map = cacache::read_hash_sync(store, &self.integrity).ok()?;
+
+        serde_json::from_slice(&map).ok()
+    }
+
+    /// Whether the content of the entry, and of every file it maps, is there and matches
+    /// its hash.
+    pub fn is_intact(&self, store: &Path) -> bool {
+        // reading by hash checks the content against it
+        cacache::read_hash_sync(store, &self.integrity).is_ok()
+            && self.files(store).map_or(false, |files| {
+                files
+                    .values()
+                    .all(|integrity| cacache::read_hash_sync(store, integrity).is_ok())
+            })
+    }
+
+    /// Size on disk of the content the entry refers to; files shared with other entries
+    /// count for each of them.
+    pub fn size(&self, store: &Path) -> u64 {
+        std::iter::once(self.integrity.clone())
+            .chain(self.files(store).unwrap_or_default().into_values())
+            .map(|integrity| file_size(&content_path(store, &integrity)))
+            .sum()
+    }
+}
+
+/// The entries of the store, sorted by key.
+pub fn entries(store: &Path) -> Result<Vec<StoreEntry>> {
+    // the index is append-only: deleted and rewritten keys are listed too, so each key is
+    // looked up again for its latest entry
+    let keys: BTreeSet<String> = cacache::list_sync(store)
+        .filter_map(|metadata| metadata.ok())
+        .map(|metadata| metadata.key)
+        .collect();
+
+    let mut entries = vec![];
+
+    for key in keys {
+        if let Some(metadata) = cacache::metadata_sync(store, &key).into_diagnostic()? {
+            entries.extend(StoreEntry::from_key(key, metadata.integrity));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// What `verify` found.
+#[derive(Debug, Default)]
+pub struct Verified {
+    /// Entries checked
+    pub checked: usize,
+    /// Keys of the entries with missing or corrupt content, removed from the index
+    pub corrupt: Vec<String>,
+    /// What was garbage-collected afterwards
+    pub collected: Collected,
+}
+
+/// Re-hash the content of every entry, remove the entries that don't match and collect
+/// the content left without an entry.
+pub fn verify(store: &Path) -> Result<Verified> {
+    let mut verified = Verified::default();
+
+    for entry in entries(store)? {
+        verified.checked += 1;
+
+        if !entry.is_intact(store) {
+            tracing::warn!("{} is corrupt, removing it from the store", entry.spec());
+
+            cacache::remove_sync(store, &entry.key).into_diagnostic()?;
+
+            verified.corrupt.push(entry.key);
+        }
+    }
+
+    verified.collected = collect_garbage(store)?;
+
+    Ok(verified)
+}
+
+/// What was removed from the store.
+#[derive(Debug, Default)]
+pub struct Collected {
+    /// Files of `content-v2` removed
+    pub files: usize,
+    /// Their size
+    pub bytes: u64,
+}
+
+/// Remove the content no entry refers to.
+pub fn collect_garbage(store: &Path) -> Result<Collected> {
+    let mut referenced = HashSet::new();
+
+    for entry in entries(store)? {
+        referenced.insert(content_path(store, &entry.integrity));
+
+        referenced.extend(
+            entry
+                .files(store)
+                .unwrap_or_default()
+                .values()
+                .map(|integrity| content_path(store, integrity)),
+        );
+    }
+
+    let mut collected = Collected::default();
+
+    for file in content_files(&store.join("content-v2"))? {
+        if !referenced.contains(&file) {
+            let bytes = file_size(&file);
+
+            std::fs::remove_file(&file).map_err(|e| FilesystemError::Remove {
+                source: e,
+                path: file.display().to_string(),
+            })?;
+
+            collected.files += 1;
+            collected.bytes += bytes;
+        }
+    }
+
+    Ok(collected)
+}
+
+/// What `clean` removed.
+#[derive(Debug, Default)]
+pub struct Cleaned {
+    /// The `name@version` of the packages removed (empty when the whole store is)
+    pub packages: Vec<String>,
+    /// Size of the content removed
+    pub bytes: u64,
+}
+
+/// Remove the versions of `name` from the store, or everything in it when `None`.
+pub fn clean(store: &Path, name: Option<&str>) -> Result<Cleaned> {
+    let name = match name {
+        Some(name) => name,
+        None => {
+            let mut bytes = 0;
+
+            for directory in STORE_DIRECTORIES.map(|directory| store.join(directory)) {
+                if directory.exists() {
+                    bytes += directory_size(&directory);
+
+                    std::fs::remove_dir_all(&directory).map_err(|e| FilesystemError::Remove {
+                        source: e,
+                        path: directory.display().to_string(),
+                    })?;
+                }
+            }
+
+            return Ok(Cleaned {
+                packages: vec![],
+                bytes,
+            });
+        }
+    };
+
+    let mut packages = vec![];
+
+    for entry in entries(store)? {
+        if entry.name == name {
+            cacache::remove_sync(store, &entry.key).into_diagnostic()?;
+
+            packages.push(entry.spec());
+        }
+    }
+
+    // files shared with other packages stay
+    let bytes = if packages.is_empty() {
+        0
+    } else {
+        collect_garbage(store)?.bytes
+    };
+
+    Ok(Cleaned { packages, bytes })
+}
+
+/// The store entries each project installed last: project directory -> keys.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Projects(pub BTreeMap<PathBuf, BTreeSet<String>>);
+
+impl Projects {
+    /// Read the projects index of a store (empty if there's none yet).
+    pub fn load(store: &Path) -> Result<Self> {
+        let path = store.join("projects.json");
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        // an unreadable index only loses track of projects, which install again
+        Ok(serde_json::from_str(&contents).unwrap_or_default())
+    }
+
+    pub fn save(&self, store: &Path) -> Result<()> {
+        let path = store.join("projects.json");
+
+        std::fs::create_dir_all(store).map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: store.display().to_string(),
+        })?;
+
+        std::fs::write(&path, serde_json::to_string_pretty(self).into_diagnostic()?).map_err(
+            |e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Record the store entries the packages of `resolution` installed in the project use,
+/// replacing what the project used before.
+pub async fn record_project(config: &VoltConfig, resolution: &Resolution) -> Result<()> {
+    let store = config.store()?;
+    let project = config.cwd()?;
+
+    // installs of other projects record theirs at the same time
+    let _lock = if config.locking() {
+        Some(
+            ProcessLock::acquire(
+                &store.join("projects.lock"),
+                LockMode::Exclusive,
+                std::time::Duration::from_secs(config.settings().lock_timeout),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    let mut projects = Projects::load(&store)?;
+
+    let keys = resolution
+        .tree
+        .values()
+        .filter(|package| !package.is_link())
+        .flat_map(|package| {
+            let tarball = package.is_git().then(|| tarball_key(&package.tarball));
+
+            std::iter::once(package.cacache_key()).chain(tarball)
+        })
+        .collect();
+
+    let project = project
+        .canonicalize()
+        .unwrap_or_else(|_| project.to_path_buf());
+
+    projects.0.insert(project, keys);
+
+    projects.save(&store)
+}
+
+/// What `collect_unreferenced` removed.
+#[derive(Debug, Default)]
+pub struct Unreferenced {
+    /// Projects forgotten because their directory is gone
+    pub projects: Vec<PathBuf>,
+    /// The `name@version` of the entries no project uses
+    pub packages: Vec<String>,
+    /// Size of the content removed with them
+    pub bytes: u64,
+}
+
+/// Forget the projects that don't exist anymore, then remove the entries no remaining
+/// project uses (and their content). With `dry_run`, only report them.
+pub fn collect_unreferenced(store: &Path, dry_run: bool) -> Result<Unreferenced> {
+    let mut projects = Projects::load(store)?;
+    let mut unreferenced = Unreferenced::default();
+
+    projects.0.retain(|project, _| {
+        let exists = project.join("node_modules").exists();
+
+        if !exists {
+            unreferenced.projects.push(project.clone());
+        }
+
+        exists
+    });
+
+    let referenced: HashSet<&String> = projects.0.values().flatten().collect();
+
+    for entry in entries(store)? {
+        if referenced.contains(&entry.key) {
+            continue;
+        }
+
+        if dry_run {
+            unreferenced.bytes += entry.size(store);
+        } else {
+            cacache::remove_sync(store, &entry.key).into_diagnostic()?;
+        }
+
+        unreferenced.packages.push(entry.spec());
+    }
+
+    if !dry_run {
+        projects.save(store)?;
+
+        unreferenced.bytes = collect_garbage(store)?.bytes;
+    }
+
+    Ok(unreferenced)
+}
+
+/// `content-v2/sha512/ba/da/55deadbeef...`, as cacache lays it out.
+fn content_path(store: &Path, integrity: &Integrity) -> PathBuf {
+    let (algorithm, hex) = integrity.to_hex();
+
+    store
+        .join("content-v2")
+        .join(algorithm.to_string())
+        .join(&hex[0..2])
+        .join(&hex[2..4])
+        .join(&hex[4..])
+}
+
+fn content_files(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    if !directory.is_dir() {
+        return Ok(files);
+    }
+
+    let entries = std::fs::read_dir(directory).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: directory.display().to_string(),
+    })?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            files.extend(content_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+fn file_size(path: &Path) -> u64 {
+    path.metadata().map_or(0, |metadata| metadata.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StoreEntry;
+
+    #[test]
+    fn parses_store_keys() {
+        let integrity = "sha512-deadbeef".parse().unwrap();
+
+        let entry = StoreEntry::from_key(
+            "pkg::@babel/core::7.17.8::sha512-abc==".to_string(),
+            integrity,
+        )
+        .unwrap();
+
+        assert_eq!(entry.spec(), "@babel/core@7.17.8");
+
+        let integrity = "sha512-deadbeef".parse().unwrap();
+
+        let entry = StoreEntry::from_key(
+            "git::git+https://github.com/a/b.git#0123abc".to_string(),
+            integrity,
+        )
+        .unwrap();
+
+        assert!(!entry.is_package());
+        assert_eq!(entry.spec(), "git+https://github.com/a/b.git#0123abc");
+    }
+}
@@ -16,5 +16,6 @@
 
 pub mod create_templates;
 pub mod init_data;
+pub mod init_template;
 pub mod meta;
 pub mod package_manager;
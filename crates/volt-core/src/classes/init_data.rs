@@ -15,9 +15,9 @@
 */
 
 use serde::{Deserialize, Serialize};
-use serde_json::to_string_pretty;
+use serde_json::{to_string_pretty, Map, Value};
 
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 #[derive(Debug, PartialEq)]
 pub enum License {
@@ -149,21 +149,44 @@ pub struct InitData {
     pub description: Option<String>,
     pub main: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub scripts: Option<BTreeMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
     pub license: License,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub private: Option<bool>,
+    /// Fields of an existing package.json that `volt init` doesn't prompt for
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl InitData {
+    /// Keep every field of an existing package.json that isn't set by `volt init`.
+    pub fn with_existing(mut self, existing: Map<String, Value>) -> Self {
+        self.extra = Map::new();
+
+        if let Ok(Value::Object(own)) = serde_json::to_value(&self) {
+            self.extra = existing
+                .into_iter()
+                .filter(|(key, _)| !own.contains_key(key))
+                .collect();
+        }
+
+        self
+    }
+
     pub fn into_string(self) -> String {
-        to_string_pretty(&self).expect("Valid serialization state")
+        let mut data = to_string_pretty(&self).expect("Valid serialization state");
+        data.push('\n');
+        data
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::classes::init_data::License;
+    use crate::classes::init_data::License;
 
     #[test]
     fn check_serialization_is_correct() {
@@ -0,0 +1,256 @@
+/*
+    Copyright 2021, 2022 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+use serde_json::Value;
+
+use std::fmt;
+
+/// Project templates built into `volt init --template`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitTemplate {
+    NodeCli,
+    Library,
+    TypeScript,
+}
+
+impl InitTemplate {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::NodeCli => "node-cli",
+            Self::Library => "library",
+            Self::TypeScript => "typescript",
+        }
+    }
+
+    pub const OPTIONS: [&'static str; 3] = [
+        Self::NodeCli.as_str(),
+        Self::Library.as_str(),
+        Self::TypeScript.as_str(),
+    ];
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Self::NodeCli),
+            1 => Some(Self::Library),
+            2 => Some(Self::TypeScript),
+            _ => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::OPTIONS
+            .iter()
+            .position(|&option| option == name)
+            .and_then(Self::from_index)
+    }
+
+    /// Files of the template as `(relative path, contents)`.
+    ///
+    /// A `package.json` entry holds the fields merged into the generated package.json.
+    pub const fn files(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::NodeCli => &[
+                (
+                    "package.json",
+                    r#"{
+  "main": "bin/cli.js",
+  "bin": {
+    "{{name}}": "bin/cli.js"
+  }
+}"#,
+                ),
+                (
+                    "bin/cli.js",
+                    r#"#!/usr/bin/env node
+
+const args = process.argv.slice(2);
+
+console.log("Hello from {{name}}!", args);
+"#,
+                ),
+                (
+                    "README.md",
+                    "# {{name}}\n\nA command line tool by {{author}}.\n",
+                ),
+                (".gitignore", "node_modules/\n"),
+            ],
+            Self::Library => &[
+                (
+                    "package.json",
+                    r#"{
+  "main": "src/index.js",
+  "files": [
+    "src"
+  ]
+}"#,
+                ),
+                (
+                    "src/index.js",
+                    r#"/**
+ * {{name}}
+ */
+module.exports = function hello(name) {
+  return `Hello, ${name}!`;
+};
+"#,
+                ),
+                ("README.md", "# {{name}}\n\nA library by {{author}}.\n"),
+                (".gitignore", "node_modules/\n"),
+            ],
+            Self::TypeScript => &[
+                (
+                    "package.json",
+                    r#"{
+  "main": "dist/index.js",
+  "types": "dist/index.d.ts",
+  "files": [
+    "dist"
+  ],
+  "scripts": {
+    "build": "tsc",
+    "prepare": "tsc"
+  },
+  "devDependencies": {
+    "typescript": "^4.6.0"
+  }
+}"#,
+                ),
+                (
+                    "tsconfig.json",
+                    r#"{
+  "compilerOptions": {
+    "target": "es2019",
+    "module": "commonjs",
+    "declaration": true,
+    "outDir": "dist",
+    "strict": true,
+    "esModuleInterop": true
+  },
+  "include": ["src"]
+}
+"#,
+                ),
+                (
+                    "src/index.ts",
+                    r#"/**
+ * {{name}}
+ */
+export function hello(name: string): string {
+  return `Hello, ${name}!`;
+}
+"#,
+                ),
+                (
+                    "README.md",
+                    "# {{name}}\n\nA TypeScript library by {{author}}.\n",
+                ),
+                (".gitignore", "node_modules/\ndist/\n"),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for InitTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Replace the `{{name}}` and `{{author}}` placeholders of a template file, in one pass so
+/// that a name or author holding a placeholder is written as is.
+pub fn substitute_placeholders(contents: &str, name: &str, author: &str) -> String {
+    let mut substituted = String::with_capacity(contents.len());
+    let mut rest = contents;
+
+    while let Some(start) = rest.find("{{") {
+        substituted.push_str(&rest[..start]);
+
+        let placeholder = &rest[start..];
+
+        if let Some(after) = placeholder.strip_prefix("{{name}}") {
+            substituted.push_str(name);
+            rest = after;
+        } else if let Some(after) = placeholder.strip_prefix("{{author}}") {
+            substituted.push_str(author);
+            rest = after;
+        } else {
+            substituted.push_str("{{");
+            rest = &placeholder[2..];
+        }
+    }
+
+    substituted.push_str(rest);
+
+    substituted
+}
+
+/// Replace the placeholders of the strings and keys in a field of a template's
+/// package.json; they are escaped when the package.json is written, unlike in the text of
+/// a file.
+pub fn substitute_value_placeholders(value: Value, name: &str, author: &str) -> Value {
+    match value {
+        Value::String(string) => Value::String(substitute_placeholders(&string, name, author)),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| substitute_value_placeholders(value, name, author))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        substitute_placeholders(&key, name, author),
+                        substitute_value_placeholders(value, name, author),
+                    )
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{substitute_placeholders, substitute_value_placeholders};
+
+    use serde_json::json;
+
+    #[test]
+    fn substitutes_placeholders_once() {
+        assert_eq!(
+            substitute_placeholders("# {{name}}\n\nby {{author}}, {{other}}", "app", "Jo"),
+            "# app\n\nby Jo, {{other}}"
+        );
+
+        // what replaced a placeholder isn't substituted again
+        assert_eq!(
+            substitute_placeholders("{{author}} {{name}}", "{{author}}", "{{name}}"),
+            "{{name}} {{author}}"
+        );
+    }
+
+    #[test]
+    fn substitutes_placeholders_in_json_values() {
+        let bin = json!({ "{{name}}": "bin/{{name}}.js", "keywords": ["{{author}}", 1] });
+
+        assert_eq!(
+            substitute_value_placeholders(bin, "cli", "Jo \"J\" <jo@example.com>"),
+            json!({ "cli": "bin/cli.js", "keywords": ["Jo \"J\" <jo@example.com>", 1] })
+        );
+    }
+}
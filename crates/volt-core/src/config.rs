@@ -0,0 +1,412 @@
+/*
+Copyright 2021 Volt Contributors
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+*/
+
+//! The options of a run of volt and the settings it reads.
+
+use crate::{
+    connections::Connections,
+    linker::NodeLinker,
+    reporter::{JsonReporter, PlainReporter, Reporter},
+    resolution_cache::CachedSource,
+    settings::Settings,
+    source::{PackageSource, RegistrySource, TarballDirectory},
+    summary::InstallStats,
+    throttle::Throttle,
+    utils::errors::{FilesystemError, ResolutionError, VoltError},
+    vendor::VENDOR_DIRECTORY,
+};
+
+use miette::IntoDiagnostic;
+use once_cell::sync::OnceCell;
+use reqwest::{Certificate, Client, Proxy};
+use std::{env, fmt, path::PathBuf, sync::Arc};
+use tracing::Level;
+
+/// Where volt runs and how: the command line flags of the cli, set directly when volt is
+/// embedded, and the settings read by [`VoltConfig::load_settings`].
+#[derive(Debug, Clone, Default)]
+pub struct VoltConfig {
+    /// Directory of the project (defaults to the current directory)
+    pub cwd: Option<PathBuf>,
+
+    /// Print progress and results as JSON lines, for tools (also enabled by `VOLT_JSON=1`)
+    pub json: bool,
+
+    /// Log more: `1` for debug messages, `2` for everything
+    pub verbose: u8,
+
+    /// Only log errors
+    pub quiet: bool,
+
+    /// Layout of node_modules, over the `node-linker` setting
+    pub node_linker: Option<NodeLinker>,
+
+    /// Packages to hoist, over the `hoist-pattern` setting (`*`, `*eslint*`, `!@types/*`)
+    pub hoist_pattern: Vec<String>,
+
+    /// Don't wait for other volt processes using node_modules or the store
+    pub no_lock: bool,
+
+    /// Resolve versions published more recently than the `minimum-release-age` setting
+    pub allow_fresh: bool,
+
+    /// Fail installs of packages without a registry signature, over the
+    /// `require-signatures` setting
+    pub require_signatures: bool,
+
+    /// Install registry packages only from the `vendor` directory, over the `vendored`
+    /// setting
+    pub vendored: bool,
+
+    /// Check the contents of the packages an install keeps in node_modules against the
+    /// store, not only that their files are there
+    pub check_files: bool,
+
+    /// Directory of the package store, over the `store-dir` setting (relative to the
+    /// project)
+    pub store_dir: Option<PathBuf>,
+
+    /// Most bytes of tarballs downloaded per second, over the `network-limit` setting
+    /// (`0` doesn't limit them)
+    pub network_limit: Option<u64>,
+
+    settings: Arc<Settings>,
+
+    /// The http client of the run, made on first use and shared by the configurations
+    /// cloned from this one
+    http_client: Arc<OnceCell<Client>>,
+
+    /// The hosts the http client resolved and requested, kept when it's made again
+    connections: Arc<Connections>,
+
+    /// What the installs of the run did, shared like the http client
+    stats: Arc<InstallStats>,
+
+    /// The bandwidth shared by the downloads of the run, with a `network-limit`
+    throttle: Option<Arc<Throttle>>,
+
+    reporter: ReporterFactory,
+
+    source: Source,
+}
+
+/// Makes the reporter of each command; plain lines on stderr unless one is set.
+#[derive(Clone, Default)]
+struct ReporterFactory(Option<Arc<dyn Fn() -> Arc<dyn Reporter> + Send + Sync>>);
+
+impl fmt::Debug for ReporterFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "ReporterFactory(custom)"
+        } else {
+            "ReporterFactory(plain)"
+        })
+    }
+}
+
+/// Where registry packages are resolved and downloaded from; the registry unless one is set.
+#[derive(Clone, Default)]
+struct Source(Option<Arc<dyn PackageSource>>);
+
+impl fmt::Debug for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(source) => write!(f, "Source({})", source.describe()),
+            None => f.write_str("Source(registry)"),
+        }
+    }
+}
+
+impl VoltConfig {
+    pub const _OS: &'static str = env::consts::OS;
+    pub const VOLT_HOME: &'static str = ".volt";
+    pub const VOLT_LOCK: &'static str = "volt.lock";
+
+    /// The configuration of a run in the project in `cwd`, before its settings are loaded
+    pub fn new(cwd: impl Into<PathBuf>) -> Self {
+        Self {
+            cwd: Some(cwd.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn home(&self) -> miette::Result<PathBuf> {
+        Ok(dirs::home_dir().ok_or(VoltError::GetHomeDirError)?)
+    }
+
+    /// Return the current directory (defaults to `.` if not provided)
+    pub fn cwd(&self) -> miette::Result<PathBuf> {
+        Ok(self.cwd.to_owned().unwrap_or({
+            env::current_dir().map_err(|e| VoltError::EnvironmentError {
+                env: "CURRENT_DIRECTORY".to_string(),
+                source: e,
+            })?
+        }))
+    }
+
+    /// Path to the volt lockfile (defaults to `./volt.lock`)
+    pub fn lockfile(&self) -> miette::Result<PathBuf> {
+        Ok(self.cwd()?.join(Self::VOLT_LOCK))
+    }
+
+    /// Path to the `node_modules` directory (defaults to `./node_modules`)
+    pub fn node_modules(&self) -> miette::Result<PathBuf> {
+        Ok(self.cwd()?.join("node_modules"))
+    }
+
+    /// Path to the config directory (defaults to `~/.volt`, or the `VOLT_HOME` environment
+    /// variable, relative to the project); the logs, global packages and the store go in
+    /// it unless they're set elsewhere
+    pub fn volt_home(&self) -> miette::Result<PathBuf> {
+        match env::var_os("VOLT_HOME").filter(|home| !home.is_empty()) {
+            Some(home) => Ok(self.cwd()?.join(home)),
+            None => Ok(self.home()?.join(Self::VOLT_HOME)),
+        }
+    }
+
+    /// Path to the vendored tarballs of the project (`./vendor`)
+    pub fn vendor_directory(&self) -> miette::Result<PathBuf> {
+        Ok(self.cwd()?.join(VENDOR_DIRECTORY))
+    }
+
+    /// Path to the prefix global packages are installed into (defaults to `~/.volt/global`)
+    pub fn global_prefix(&self) -> miette::Result<PathBuf> {
+        Ok(self.volt_home()?.join("global"))
+    }
+
+    /// Path to the directory holding the executables of global packages (defaults to `~/.volt/global/bin`)
+    pub fn global_bin(&self) -> miette::Result<PathBuf> {
+        Ok(self.global_prefix()?.join("bin"))
+    }
+
+    /// Path to the package store and the metadata cache in it (defaults to `~/.volt`, or
+    /// `--store-dir`, or the `store-dir` setting)
+    pub fn store(&self) -> miette::Result<PathBuf> {
+        match (&self.store_dir, &self.settings.store_dir) {
+            (Some(store_dir), _) => Ok(self.cwd()?.join(store_dir)),
+            (None, Some(store_dir)) => Ok(store_dir.clone()),
+            (None, None) => self.volt_home(),
+        }
+    }
+
+    /// Read the settings of the user, the project and the environment
+    pub fn load_settings(&mut self) -> miette::Result<()> {
+        let mut settings = Settings::load(&self.home()?, &self.volt_home()?, &self.cwd()?)?;
+
+        if let Some(node_linker) = self.node_linker {
+            settings.node_linker = node_linker;
+        }
+
+        if !self.hoist_pattern.is_empty() {
+            settings.hoist_pattern = self.hoist_pattern.clone();
+        }
+
+        if self.allow_fresh {
+            settings.minimum_release_age = None;
+        }
+
+        if self.require_signatures {
+            settings.verify_signatures = true;
+            settings.require_signatures = true;
+        }
+
+        if self.vendored {
+            settings.vendored = true;
+        }
+
+        if let Some(network_limit) = self.network_limit {
+            settings.network_limit = Some(network_limit).filter(|rate| *rate > 0);
+        }
+
+        // nothing may reach the registry: the tarballs were checked when they were vendored
+        if settings.vendored {
+            settings.verify_signatures = false;
+            settings.minimum_release_age = None;
+
+            let directory = self.vendor_directory()?;
+
+            if !directory.is_dir() {
+                return Err(ResolutionError::NoVendorDirectory {
+                    path: directory.display().to_string(),
+                }
+                .into());
+            }
+
+            self.set_source(TarballDirectory::open(&directory)?);
+        }
+
+        self.throttle = settings
+            .network_limit
+            .map(|rate| Arc::new(Throttle::new(rate)));
+        self.settings = Arc::new(settings);
+        self.http_client = Arc::default();
+
+        Ok(())
+    }
+
+    /// The settings read by [`VoltConfig::load_settings`]
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// The http client, going through the proxy and trusting the certificates configured;
+    /// one per run, so its connections and the addresses of the hosts it resolved are
+    /// reused by every request
+    ///
+    /// Without proxy settings, the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment
+    /// variables apply.
+    pub fn http_client(&self) -> miette::Result<Client> {
+        self.http_client
+            .get_or_try_init(|| self.build_http_client())
+            .cloned()
+    }
+
+    fn build_http_client(&self) -> miette::Result<Client> {
+        let settings = self.settings.clone();
+
+        let mut builder = Client::builder()
+            .use_rustls_tls()
+            .danger_accept_invalid_certs(!settings.strict_ssl)
+            .dns_resolver(Arc::new(self.connections.dns()));
+
+        if let Some(timeout) = settings.fetch_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        if settings.proxy.is_some() || settings.https_proxy.is_some() {
+            builder = builder.proxy(Proxy::custom(move |url| {
+                if settings.bypasses_proxy(url.host_str().unwrap_or_default()) {
+                    return None;
+                }
+
+                match url.scheme() {
+                    "https" => settings
+                        .https_proxy
+                        .clone()
+                        .or_else(|| settings.proxy.clone()),
+                    _ => settings.proxy.clone(),
+                }
+            }));
+        }
+
+        if let Some(cafile) = &self.settings.cafile {
+            let pem = std::fs::read(cafile).map_err(|e| FilesystemError::Read {
+                source: e,
+                path: cafile.display().to_string(),
+            })?;
+
+            let invalid = || VoltError::InvalidCertificate {
+                path: cafile.display().to_string(),
+            };
+
+            // rustls skips what isn't a certificate instead of failing
+            if !String::from_utf8_lossy(&pem).contains("-----BEGIN CERTIFICATE-----") {
+                return Err(invalid().into());
+            }
+
+            let certificate = Certificate::from_pem(&pem).map_err(|_| invalid())?;
+
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        builder.build().into_diagnostic()
+    }
+
+    /// How the requests of the run shared connections, for `--verbose`
+    pub fn connections(&self) -> Arc<Connections> {
+        Arc::clone(&self.connections)
+    }
+
+    /// What the installs of the run did so far, for their summary
+    pub fn stats(&self) -> &InstallStats {
+        &self.stats
+    }
+
+    /// The limit of the bandwidth of downloads, shared by the configurations cloned from
+    /// this one; `None` without a `network-limit`
+    pub fn throttle(&self) -> Option<&Throttle> {
+        self.throttle.as_deref()
+    }
+
+    /// Whether node_modules and the store are locked against other volt processes
+    pub fn locking(&self) -> bool {
+        !self.no_lock
+    }
+
+    /// Whether output is JSON lines instead of text
+    pub fn json(&self) -> bool {
+        self.json || env::var("VOLT_JSON").map_or(false, |value| value == "1" || value == "true")
+    }
+
+    /// The most detailed level logged to the terminal
+    pub fn log_level(&self) -> Level {
+        match (self.quiet, self.verbose) {
+            (true, _) => Level::ERROR,
+            (false, 0) => Level::INFO,
+            (false, 1) => Level::DEBUG,
+            (false, _) => Level::TRACE,
+        }
+    }
+
+    /// Whether only errors are printed
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Report the progress of every command with the reporters `reporter` makes, unless
+    /// the output is JSON
+    pub fn set_reporter<F>(&mut self, reporter: F)
+    where
+        F: Fn() -> Arc<dyn Reporter> + Send + Sync + 'static,
+    {
+        self.reporter = ReporterFactory(Some(Arc::new(reporter)));
+    }
+
+    /// A new progress reporter for the current output
+    pub fn reporter(&self) -> Arc<dyn Reporter> {
+        if self.json() {
+            return Arc::new(JsonReporter);
+        }
+
+        match &self.reporter.0 {
+            Some(reporter) => reporter(),
+            None => Arc::new(PlainReporter::default()),
+        }
+    }
+
+    /// Resolve and download registry packages from `source` instead of the registry
+    pub fn set_source(&mut self, source: impl PackageSource + 'static) {
+        self.source = Source(Some(Arc::new(source)));
+    }
+
+    /// Where registry packages are resolved and downloaded from; the registry, with its
+    /// resolutions cached, unless a source is set
+    pub fn source(&self) -> Arc<dyn PackageSource> {
+        match &self.source.0 {
+            Some(source) => source.clone(),
+            None => Arc::new(CachedSource::new(RegistrySource)),
+        }
+    }
+
+    /// The same configuration, running from another directory, with stats of its own
+    pub fn with_cwd(&self, cwd: PathBuf) -> Self {
+        Self {
+            cwd: Some(cwd),
+            stats: Arc::default(),
+            ..self.clone()
+        }
+    }
+}
@@ -0,0 +1,219 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Share few connections between the metadata requests to a registry.
+//!
+//! Registries speak HTTP/2, but whether one does is only known once a connection to it
+//! is up, so a burst of requests to a registry nothing was sent to yet would open a
+//! connection each. The first metadata request to a host goes alone: once it's answered
+//! over HTTP/2, the others are multiplexed over its connection, and over HTTP/1.1 at most
+//! [`HTTP1_CONNECTIONS`] of them are sent at once.
+//!
+//! What the requests to each host did is logged at the end of a run with `--verbose`.
+
+use crate::dns::DnsCache;
+
+use once_cell::sync::OnceCell;
+use reqwest::{Response, Url, Version};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Most metadata requests sent at once to a host answering over HTTP/1.1, which takes a
+/// connection for each.
+pub const HTTP1_CONNECTIONS: usize = 16;
+
+/// The hosts requested during a run, shared by the configurations cloned from its own.
+#[derive(Debug, Default)]
+pub struct Connections {
+    dns: DnsCache,
+    hosts: Mutex<BTreeMap<String, Arc<Host>>>,
+}
+
+#[derive(Debug)]
+struct Host {
+    /// The protocol of the first answer
+    version: OnceCell<Version>,
+    /// Held by the request finding out the protocol
+    first: Arc<AsyncMutex<()>>,
+    http1: Arc<Semaphore>,
+    requests: AtomicUsize,
+}
+
+impl Default for Host {
+    fn default() -> Self {
+        Self {
+            version: OnceCell::new(),
+            first: Arc::default(),
+            http1: Arc::new(Semaphore::new(HTTP1_CONNECTIONS)),
+            requests: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// The turn of a metadata request to a host, held until it's answered.
+pub struct Turn {
+    host: Arc<Host>,
+    _first: Option<OwnedMutexGuard<()>>,
+    _connection: Option<OwnedSemaphorePermit>,
+}
+
+impl Turn {
+    /// The request was answered with `response`, over the protocol of the host.
+    pub fn answered(self, response: &Response) {
+        let _ = self.host.version.set(response.version());
+    }
+}
+
+impl Connections {
+    /// The cache the http client of the run resolves hosts through.
+    pub fn dns(&self) -> DnsCache {
+        self.dns.clone()
+    }
+
+    /// Wait for the turn of a metadata request to `url`: right away once its host is
+    /// known to multiplex requests.
+    pub async fn turn(&self, url: &str) -> Turn {
+        self.dns.register(url);
+
+        let host = self.host(&host_of(url));
+
+        host.requests.fetch_add(1, Ordering::Relaxed);
+
+        let mut first = None;
+
+        // a request that failed leaves the protocol to find to the next one
+        if host.version.get().is_none() {
+            let guard = Arc::clone(&host.first).lock_owned().await;
+
+            if host.version.get().is_none() {
+                first = Some(guard);
+            }
+        }
+
+        let connection = if first.is_some() || host.version.get() == Some(&Version::HTTP_2) {
+            None
+        } else {
+            Arc::clone(&host.http1).acquire_owned().await.ok()
+        };
+
+        Turn {
+            host,
+            _first: first,
+            _connection: connection,
+        }
+    }
+
+    /// What the requests to each host did, by host.
+    pub fn stats(&self) -> Vec<HostStats> {
+        let hosts = match self.hosts.lock() {
+            Ok(hosts) => hosts.clone(),
+            Err(_) => return vec![],
+        };
+
+        hosts
+            .into_iter()
+            .map(|(name, host)| HostStats {
+                connections: self.dns.connections(&name),
+                requests: host.requests.load(Ordering::Relaxed),
+                version: host.version.get().copied(),
+                host: name,
+            })
+            .collect()
+    }
+
+    fn host(&self, name: &str) -> Arc<Host> {
+        let mut hosts = match self.hosts.lock() {
+            Ok(hosts) => hosts,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        Arc::clone(hosts.entry(name.to_string()).or_default())
+    }
+}
+
+/// The metadata requests sent to a host, and the connections opened to it for every
+/// request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostStats {
+    pub host: String,
+    pub requests: usize,
+    pub connections: usize,
+    /// `None` when no request was answered
+    pub version: Option<Version>,
+}
+
+impl fmt::Display for HostStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} metadata request{} over {}, {} connection{} opened",
+            self.host,
+            self.requests,
+            if self.requests == 1 { "" } else { "s" },
+            match self.version {
+                Some(Version::HTTP_2) => "HTTP/2",
+                Some(Version::HTTP_10) => "HTTP/1.0",
+                Some(_) => "HTTP/1.1",
+                None => "no answer",
+            },
+            self.connections,
+            if self.connections == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// The host of `url` (`registry.npmjs.org`), as the resolver sees it.
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{host_of, HostStats};
+
+    use reqwest::Version;
+
+    #[test]
+    fn describes_the_requests_to_a_host() {
+        assert_eq!(
+            host_of("https://registry.npmjs.org/@types%2fnode"),
+            "registry.npmjs.org"
+        );
+
+        let stats = HostStats {
+            host: "registry.npmjs.org".to_string(),
+            requests: 412,
+            connections: 1,
+            version: Some(Version::HTTP_2),
+        };
+
+        assert_eq!(
+            stats.to_string(),
+            "registry.npmjs.org: 412 metadata requests over HTTP/2, 1 connection opened"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Reduce the versions of packages locked more than once.
+//!
+//! The lock file only records the exact version every dependency resolved to, so the
+//! ranges dependents ask for are read from their registry documents. Every dependency on
+//! a duplicated package is then moved to the locked version satisfying the most of them
+//! (the highest of those that tie), and versions nothing uses anymore are dropped along
+//! with their own dependencies. Only locked versions are considered: nothing new is
+//! downloaded.
+
+use crate::{
+    config::VoltConfig, install::Resolution, model::lock_file::LockFile, registry::RegistryClient,
+    utils::voltapi::dependency_target,
+};
+
+use futures::{stream, StreamExt, TryStreamExt};
+use miette::Result;
+use node_semver::{Range, Version};
+use serde_json::Value;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A dependency on a package locked in several versions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    /// Key of the dependent, or `None` for the project itself
+    pub dependent: Option<String>,
+    /// Name the dependency is declared as (an alias, or the package name)
+    pub name: String,
+    /// Name of the package depended on
+    pub target: String,
+    /// The range asked for, `None` when it isn't a semver range (a tag, a url)
+    pub range: Option<String>,
+    /// The locked version it resolves to
+    pub version: String,
+}
+
+/// The registry packages locked in more than one version: name -> versions, lowest first.
+pub fn duplicates(lock_file: &LockFile) -> BTreeMap<String, Vec<String>> {
+    let mut versions: BTreeMap<String, Vec<Version>> = BTreeMap::new();
+
+    for package in lock_file.dependencies.values() {
+        if let (true, Ok(version)) = (package.is_registry(), package.version.parse()) {
+            versions
+                .entry(package.name.clone())
+                .or_default()
+                .push(version);
+        }
+    }
+
+    versions
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(name, mut versions)| {
+            versions.sort();
+
+            (name, versions.iter().map(ToString::to_string).collect())
+        })
+        .collect()
+}
+
+/// Every dependency on the duplicated packages, with the range it asks for: from
+/// `dependencies` (`name -> range`) for the project, and from the registry for packages.
+pub async fn edges(
+    config: &VoltConfig,
+    lock_file: &LockFile,
+    dependencies: &BTreeMap<String, String>,
+    duplicates: &BTreeMap<String, Vec<String>>,
+) -> Result<Vec<Edge>> {
+    let mut edges = vec![];
+
+    for (name, range) in dependencies {
+        let (target, range) = dependency_target(name, range)?;
+
+        if duplicates.contains_key(target) {
+            if let Some(package) = lock_file.find(target, range) {
+                edges.push(Edge {
+                    dependent: None,
+                    name: name.clone(),
+                    target: target.to_string(),
+                    range: range.parse::<Range>().ok().map(|_| range.to_string()),
+                    version: package.version.clone(),
+                });
+            }
+        }
+    }
+
+    // the dependents whose ranges are needed, by name so each document is fetched once
+    let mut dependents: BTreeMap<&str, Vec<(&String, &String, &String)>> = BTreeMap::new();
+
+    for (key, package) in &lock_file.dependencies {
+        for (name, version) in package.edges() {
+            if duplicates.contains_key(dependency_target(name, version)?.0) {
+                dependents
+                    .entry(&package.name)
+                    .or_default()
+                    .push((key, name, version));
+            }
+        }
+    }
+
+    let client = RegistryClient::new(config)?;
+
+    // packages from git, urls or the filesystem have no document to read ranges from
+    let registry: Vec<&str> = dependents
+        .keys()
+        .copied()
+        .filter(|name| {
+            lock_file
+                .dependencies
+                .values()
+                .any(|package| package.name == *name && package.is_registry())
+        })
+        .collect();
+
+    let requests: Vec<_> = registry
+        .iter()
+        .map(|name| {
+            let client = &client;
+
+            async move { Ok::<_, miette::Report>((*name, client.packument(name).await?)) }
+        })
+        .collect();
+
+    let documents: BTreeMap<&str, Value> = stream::iter(requests)
+        .buffer_unordered(config.settings().concurrency)
+        .try_collect()
+        .await?;
+
+    for (dependent, locked) in dependents {
+        for (key, name, version) in locked {
+            let package = &lock_file.dependencies[key];
+
+            let manifest =
+                &documents.get(dependent).unwrap_or(&Value::Null)["versions"][&package.version];
+
+            let range = ["dependencies", "optionalDependencies"]
+                .iter()
+                .find_map(|field| manifest[field][name.as_str()].as_str())
+                .and_then(|range| Some(dependency_target(name, range).ok()?.1))
+                .filter(|range| range.parse::<Range>().is_ok());
+
+            let (target, version) = dependency_target(name, version)?;
+
+            edges.push(Edge {
+                dependent: Some(key.clone()),
+                name: name.clone(),
+                target: target.to_string(),
+                range: range.map(ToString::to_string),
+                version: version.to_string(),
+            });
+        }
+    }
+
+    Ok(edges)
+}
+
+/// The version every edge should use: the locked version satisfying the most edges not
+/// placed yet, then the same for the rest, until every edge has one. Edges without a
+/// range keep their version.
+pub fn plan(duplicates: &BTreeMap<String, Vec<String>>, edges: &[Edge]) -> Vec<String> {
+    let mut planned: Vec<Option<String>> = vec![None; edges.len()];
+
+    for (name, versions) in duplicates {
+        let versions: Vec<Version> = versions.iter().filter_map(|v| v.parse().ok()).collect();
+
+        let satisfies = |edge: &Edge, version: &Version| match &edge.range {
+            Some(range) => range
+                .parse::<Range>()
+                .map_or(false, |range| version.satisfies(&range)),
+            None => edge.version == version.to_string(),
+        };
+
+        loop {
+            let open: Vec<usize> = (0..edges.len())
+                .filter(|&i| planned[i].is_none() && edges[i].target == *name)
+                .collect();
+
+            // highest first, so that ties go to the highest version
+            let best = versions
+                .iter()
+                .rev()
+                .map(|version| {
+                    let count = open
+                        .iter()
+                        .filter(|&&i| satisfies(&edges[i], version))
+                        .count();
+
+                    (count, version)
+                })
+                .fold(
+                    None,
+                    |best: Option<(usize, &Version)>, candidate| match best {
+                        Some(best) if best.0 >= candidate.0 => Some(best),
+                        _ => Some(candidate),
+                    },
+                );
+
+            match best {
+                Some((count, version)) if count > 0 => {
+                    for &i in &open {
+                        if satisfies(&edges[i], version) {
+                            planned[i] = Some(version.to_string());
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    planned
+        .into_iter()
+        .zip(edges)
+        .map(|(version, edge)| version.unwrap_or_else(|| edge.version.clone()))
+        .collect()
+}
+
+/// Point every edge at its planned version and drop the packages nothing reaches anymore
+/// from the project's `dependencies`; returns the keys of the dropped packages.
+pub fn apply(
+    lock_file: &mut LockFile,
+    dependencies: &BTreeMap<String, String>,
+    edges: &[Edge],
+    planned: &[String],
+) -> Vec<String> {
+    let mut used: BTreeSet<String> = BTreeSet::new();
+
+    for (edge, version) in edges.iter().zip(planned) {
+        used.insert(format!("{}@{}", edge.target, version));
+
+        let dependent = match edge
+            .dependent
+            .as_ref()
+            .and_then(|key| lock_file.dependencies.get_mut(key))
+        {
+            Some(dependent) => dependent,
+            None => continue,
+        };
+
+        let value = if edge.name == edge.target {
+            version.clone()
+        } else {
+            format!("npm:{}@{}", edge.target, version)
+        };
+
+        for map in [
+            &mut dependent.dependencies,
+            &mut dependent.optional_dependencies,
+        ] {
+            if let Some(locked) = map.get_mut(&edge.name) {
+                *locked = value.clone();
+            }
+        }
+    }
+
+    // versions no edge uses anymore go first, so the project's ranges find the others
+    let unused: Vec<String> = lock_file
+        .dependencies
+        .iter()
+        .filter(|(key, package)| {
+            edges.iter().any(|edge| edge.target == package.name) && !used.contains(*key)
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in &unused {
+        lock_file.dependencies.remove(key);
+    }
+
+    let reachable: BTreeSet<String> = Resolution::from_lock_file(lock_file, dependencies)
+        .map(|resolution| resolution.tree.into_keys().collect())
+        .unwrap_or_else(|| lock_file.dependencies.keys().cloned().collect());
+
+    let mut removed = unused;
+
+    lock_file.dependencies.retain(|key, _| {
+        let keep = reachable.contains(key);
+
+        if !keep {
+            removed.push(key.clone());
+        }
+
+        keep
+    });
+
+    removed.sort();
+
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan, Edge};
+
+    use std::collections::BTreeMap;
+
+    fn edge(dependent: &str, range: &str, version: &str) -> Edge {
+        Edge {
+            dependent: Some(dependent.to_string()),
+            name: "ms".to_string(),
+            target: "ms".to_string(),
+            range: Some(range.to_string()),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn moves_edges_to_the_version_most_satisfy() {
+        let duplicates = BTreeMap::from([(
+            "ms".to_string(),
+            vec![
+                "2.0.0".to_string(),
+                "2.1.1".to_string(),
+                "2.1.3".to_string(),
+                "3.0.0".to_string(),
+            ],
+        )]);
+
+        let edges = [
+            edge("a@1.0.0", "^2.0.0", "2.0.0"),
+            edge("b@1.0.0", "~2.1.0", "2.1.1"),
+            edge("c@1.0.0", "^2.1.2", "2.1.3"),
+            edge("d@1.0.0", "^3.0.0", "3.0.0"),
+        ];
+
+        assert_eq!(
+            plan(&duplicates, &edges),
+            ["2.1.3", "2.1.3", "2.1.3", "3.0.0"]
+        );
+    }
+}
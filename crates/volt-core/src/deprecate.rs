@@ -0,0 +1,109 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Deprecate published versions.
+//!
+//! The message is set as the `deprecated` field of each version in the package document,
+//! which is sent back whole (`PUT /<name>`) with the revision it was read at. An empty
+//! message lifts the deprecation.
+
+use crate::{registry::RegistryClient, reporter::Reporter, utils::errors::ResolutionError};
+
+use miette::Result;
+use node_semver::{Range, Version};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+/// Deprecate the versions of `name` that satisfy `range` with `message`; the versions
+/// changed.
+pub async fn deprecate(
+    client: &RegistryClient,
+    name: &str,
+    range: &Range,
+    message: &str,
+    reporter: &dyn Reporter,
+) -> Result<Vec<String>> {
+    let mut packument = client.packument(name).await?;
+
+    let versions = mark_deprecated(&mut packument, range, message);
+
+    if versions.is_empty() {
+        return Err(ResolutionError::NoMatchingVersion {
+            name: name.to_string(),
+            requested: range.to_string(),
+        }
+        .into());
+    }
+
+    client
+        .send_mutation(reporter, |client| {
+            Ok(client
+                .authenticated(Method::PUT, &RegistryClient::package_path(name))?
+                .json(&packument))
+        })
+        .await?;
+
+    Ok(versions)
+}
+
+/// Set `message` as the deprecation of the versions of `packument` in `range`; the
+/// versions, oldest first.
+fn mark_deprecated(packument: &mut Value, range: &Range, message: &str) -> Vec<String> {
+    let mut marked = vec![];
+
+    if let Some(versions) = packument["versions"].as_object_mut() {
+        for (version, manifest) in versions.iter_mut() {
+            let satisfies = version
+                .parse::<Version>()
+                .map_or(false, |parsed| parsed.satisfies(range));
+
+            if satisfies {
+                manifest["deprecated"] = json!(message);
+                marked.push(version.clone());
+            }
+        }
+    }
+
+    marked.sort_by_key(|version| version.parse::<Version>().ok());
+    marked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mark_deprecated;
+
+    use serde_json::json;
+
+    #[test]
+    fn marks_the_versions_in_the_range() {
+        let mut packument = json!({
+            "_id": "a",
+            "_rev": "4-abc",
+            "versions": {
+                "1.0.0": { "version": "1.0.0" },
+                "1.2.0": { "version": "1.2.0" },
+                "2.0.0": { "version": "2.0.0" },
+            },
+        });
+
+        let marked = mark_deprecated(&mut packument, &"^1".parse().unwrap(), "use 2.x");
+
+        assert_eq!(marked, ["1.0.0", "1.2.0"]);
+        assert_eq!(packument["versions"]["1.2.0"]["deprecated"], "use 2.x");
+        assert!(packument["versions"]["2.0.0"].get("deprecated").is_none());
+        assert_eq!(packument["_rev"], "4-abc");
+    }
+}
@@ -0,0 +1,579 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Compare two published versions of a package: the files of their tarballs as unified
+//! diffs, and what changed in the dependencies and scripts of their package.json.
+
+use crate::{
+    config::VoltConfig,
+    hashing::verify,
+    utils::{
+        decompress_gzip,
+        errors::{IntegrityError, NetworkError, ResolutionError},
+        package::NpmPackage,
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use std::{collections::BTreeMap, io::Read, path::Component};
+
+/// Unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Past this many edits, a file is shown as replaced as a whole rather than searched for
+/// the shortest diff.
+const MAX_EDITS: isize = 4096;
+
+/// The package.json fields summarized, in the order they are shown.
+pub const SUMMARIZED: [&str; 4] = [
+    "dependencies",
+    "optionalDependencies",
+    "peerDependencies",
+    "scripts",
+];
+
+/// Scripts that run when the package is installed.
+pub const INSTALL_SCRIPTS: [&str; 3] = ["preinstall", "install", "postinstall"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A file that differs between the two versions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDiff {
+    /// Path in the package, without the tarball's top directory
+    pub path: String,
+    pub change: FileChange,
+    pub added: usize,
+    pub removed: usize,
+    /// The unified diff, `None` for binary files
+    #[serde(skip)]
+    pub patch: Option<String>,
+}
+
+/// An entry of a package.json field that was added, removed or changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestChange {
+    pub field: String,
+    pub name: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Download the tarball of a published version and read its files, keyed by their path
+/// in the package.
+pub async fn fetch_files(
+    config: &VoltConfig,
+    package: &NpmPackage,
+    version: &str,
+) -> Result<BTreeMap<String, Vec<u8>>> {
+    let dist = &package
+        .versions
+        .get(version)
+        .ok_or_else(|| ResolutionError::NoMatchingVersion {
+            name: package.name.clone(),
+            requested: version.to_string(),
+        })?
+        .dist;
+
+    let url = &dist.tarball;
+
+    let request_error = |source| NetworkError::Request {
+        url: url.to_string(),
+        source,
+    };
+
+    let response = config
+        .http_client()?
+        .get(url)
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    if !response.status().is_success() {
+        return Err(NetworkError::Download {
+            package: format!("{}@{}", package.name, version),
+            url: url.to_string(),
+            status: response.status().as_u16(),
+        }
+        .into());
+    }
+
+    let tarball = response.bytes().await.map_err(request_error)?;
+
+    if let Some(actual) = verify(&tarball, &dist.integrity)? {
+        return Err(IntegrityError::Checksum {
+            package: format!("{}@{}", package.name, version),
+            tarball: url.to_string(),
+            expected: dist.integrity.clone(),
+            actual,
+        }
+        .into());
+    }
+
+    read_tarball(&decompress_gzip(&tarball)?)
+}
+
+/// The files of an uncompressed tarball. Most tarballs keep them under `package/`, but
+/// any top directory is stripped.
+pub fn read_tarball(tar: &[u8]) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+
+    for entry in tar::Archive::new(tar).entries().into_diagnostic()? {
+        let mut entry = entry.into_diagnostic()?;
+
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path().into_diagnostic()?;
+
+        let components: Vec<String> = path
+            .components()
+            .skip(1)
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+
+        if components.is_empty() {
+            continue;
+        }
+
+        let mut contents = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut contents).into_diagnostic()?;
+
+        files.insert(components.join("/"), contents);
+    }
+
+    Ok(files)
+}
+
+/// The files that differ between `old` and `new`, sorted by path.
+pub fn diff_files(
+    old: &BTreeMap<String, Vec<u8>>,
+    new: &BTreeMap<String, Vec<u8>>,
+) -> Vec<FileDiff> {
+    let mut paths: Vec<&String> = old.keys().chain(new.keys()).collect();
+
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (before, after) = (old.get(path), new.get(path));
+
+            let change = match (before, after) {
+                (Some(before), Some(after)) if before == after => return None,
+                (Some(_), Some(_)) => FileChange::Modified,
+                (None, _) => FileChange::Added,
+                (_, None) => FileChange::Removed,
+            };
+
+            let (patch, added, removed) = match (text(before), text(after)) {
+                (Some(before), Some(after)) => {
+                    let (hunks, added, removed) = unified_diff(before, after);
+
+                    let header = format!(
+                        "--- {}\n+++ {}\n",
+                        before_name(path, change),
+                        after_name(path, change)
+                    );
+
+                    (Some(header + &hunks), added, removed)
+                }
+                _ => (None, 0, 0),
+            };
+
+            Some(FileDiff {
+                path: path.clone(),
+                change,
+                added,
+                removed,
+                patch,
+            })
+        })
+        .collect()
+}
+
+/// The contents of a file as text, `None` for binary files. A missing file is empty.
+fn text(contents: Option<&Vec<u8>>) -> Option<&str> {
+    match contents {
+        Some(contents) if contents.contains(&0) => None,
+        Some(contents) => std::str::from_utf8(contents).ok(),
+        None => Some(""),
+    }
+}
+
+fn before_name(path: &str, change: FileChange) -> String {
+    match change {
+        FileChange::Added => "/dev/null".to_string(),
+        _ => format!("a/{}", path),
+    }
+}
+
+fn after_name(path: &str, change: FileChange) -> String {
+    match change {
+        FileChange::Removed => "/dev/null".to_string(),
+        _ => format!("b/{}", path),
+    }
+}
+
+/// The dependencies and scripts that were added, removed or changed between two
+/// package.json documents.
+pub fn manifest_changes(old: &Value, new: &Value) -> Vec<ManifestChange> {
+    let entries = |manifest: &Value, field: &str| -> BTreeMap<String, String> {
+        manifest[field]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| {
+                let value = value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), ToString::to_string);
+
+                (name.clone(), value)
+            })
+            .collect()
+    };
+
+    let mut changes = vec![];
+
+    for field in SUMMARIZED {
+        let (before, after) = (entries(old, field), entries(new, field));
+
+        let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+
+        names.sort();
+        names.dedup();
+
+        for name in names {
+            let (from, to) = (before.get(name), after.get(name));
+
+            if from != to {
+                changes.push(ManifestChange {
+                    field: field.to_string(),
+                    name: name.clone(),
+                    from: from.cloned(),
+                    to: to.cloned(),
+                });
+            }
+        }
+    }
+
+    changes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    /// Lines at these indexes of the old and new text are the same
+    Equal(usize, usize),
+    /// The line at this index of the old text was removed
+    Delete(usize),
+    /// The line at this index of the new text was added
+    Insert(usize),
+}
+
+/// The hunks of a unified diff from `old` to `new`, with the number of added and removed
+/// lines.
+pub fn unified_diff(old: &str, new: &str) -> (String, usize, usize) {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let edits = edits(&old, &new);
+
+    let added = edits
+        .iter()
+        .filter(|edit| matches!(edit, Edit::Insert(_)))
+        .count();
+    let removed = edits
+        .iter()
+        .filter(|edit| matches!(edit, Edit::Delete(_)))
+        .count();
+
+    let mut output = String::new();
+
+    for (start, end) in hunks(&edits) {
+        let edits = &edits[start..end];
+
+        let (old_start, new_start) = position(&edits[0]);
+        let old_lines = edits
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Insert(_)))
+            .count();
+        let new_lines = edits
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Delete(_)))
+            .count();
+
+        // an empty side starts at the line before the hunk, like in GNU diff
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_lines > 0),
+            old_lines,
+            new_start + usize::from(new_lines > 0),
+            new_lines
+        ));
+
+        for edit in edits {
+            let (sign, line) = match *edit {
+                Edit::Equal(index, _) => (' ', old[index]),
+                Edit::Delete(index) => ('-', old[index]),
+                Edit::Insert(index) => ('+', new[index]),
+            };
+
+            output.push(sign);
+            output.push_str(line.strip_suffix('\n').unwrap_or(line));
+            output.push('\n');
+
+            if !line.ends_with('\n') {
+                output.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+
+    (output, added, removed)
+}
+
+/// Where an edit starts in the old and new texts, as line indexes.
+fn position(edit: &Edit) -> (usize, usize) {
+    match *edit {
+        Edit::Equal(old, new) => (old, new),
+        // hunks only start with a change at the very start of both texts
+        Edit::Delete(_) | Edit::Insert(_) => (0, 0),
+    }
+}
+
+/// The ranges of edits shown as hunks: each change with `CONTEXT` lines around it, the
+/// changes closer than that merged into one hunk.
+fn hunks(edits: &[Edit]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = vec![];
+
+    for (index, _) in edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Equal(..)))
+    {
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + CONTEXT + 1).min(edits.len());
+
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    hunks
+}
+
+/// The shortest edit script from `old` to `new`, found with Myers' algorithm.
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+
+    // the furthest `x` reached on each diagonal `k = x - y` after `d` edits, indexed by
+    // `k + d`
+    let mut trace: Vec<Vec<isize>> = vec![];
+
+    for d in 0..=(n + m) {
+        if d > MAX_EDITS {
+            return replaced(old.len(), new.len());
+        }
+
+        let mut furthest = vec![0; (2 * d + 1) as usize];
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = match trace.last() {
+                None => 0,
+                Some(previous) => {
+                    let at = |k: isize| previous[(k + d - 1) as usize];
+
+                    if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                        at(k + 1)
+                    } else {
+                        at(k - 1) + 1
+                    }
+                }
+            };
+
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            furthest[(k + d) as usize] = x;
+
+            if x >= n && y >= m {
+                trace.push(furthest);
+
+                return backtrack(&trace, n, m);
+            }
+        }
+
+        trace.push(furthest);
+    }
+
+    unreachable!("an edit script is at most as long as both texts")
+}
+
+/// Follow the trace of [`edits`] back from the end of both texts.
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Edit> {
+    let (mut x, mut y) = (n, m);
+    let mut edits = vec![];
+
+    for d in (1..trace.len() as isize).rev() {
+        let previous = &trace[(d - 1) as usize];
+        let at = |k: isize| previous[(k + d - 1) as usize];
+
+        let k = x - y;
+
+        let previous_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+
+        let previous_x = at(previous_k);
+        let previous_y = previous_x - previous_k;
+
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Equal(x as usize, y as usize));
+        }
+
+        if x == previous_x {
+            y -= 1;
+            edits.push(Edit::Insert(y as usize));
+        } else {
+            x -= 1;
+            edits.push(Edit::Delete(x as usize));
+        }
+    }
+
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        edits.push(Edit::Equal(x as usize, y as usize));
+    }
+
+    edits.reverse();
+
+    edits
+}
+
+/// Every old line removed and every new line added.
+fn replaced(old: usize, new: usize) -> Vec<Edit> {
+    (0..old)
+        .map(Edit::Delete)
+        .chain((0..new).map(Edit::Insert))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_files, manifest_changes, unified_diff, FileChange, ManifestChange};
+
+    use serde_json::json;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn diffs_versions() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nC\nd\ne\nf\ng\nh\ni\nj\nk";
+
+        let (patch, added, removed) = unified_diff(old, new);
+
+        assert_eq!((added, removed), (2, 1));
+        assert_eq!(
+            patch,
+            "@@ -1,6 +1,6 @@\n a\n b\n-c\n+C\n d\n e\n f\n\
+             @@ -8,3 +8,4 @@\n h\n i\n j\n+k\n\\ No newline at end of file\n"
+        );
+
+        let files = |entries: &[(&str, &[u8])]| {
+            entries
+                .iter()
+                .map(|(path, contents)| (path.to_string(), contents.to_vec()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let diffs = diff_files(
+            &files(&[
+                ("index.js", b"1\n"),
+                ("logo.png", b"\x89PNG\0"),
+                ("same", b""),
+            ]),
+            &files(&[("index.js", b"2\n"), ("lib.js", b"3\n"), ("same", b"")]),
+        );
+
+        let summary: Vec<_> = diffs
+            .iter()
+            .map(|diff| (diff.path.as_str(), diff.change, diff.patch.is_some()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                ("index.js", FileChange::Modified, true),
+                ("lib.js", FileChange::Added, true),
+                ("logo.png", FileChange::Removed, false),
+            ]
+        );
+        assert_eq!(
+            diffs[1].patch.as_deref(),
+            Some("--- /dev/null\n+++ b/lib.js\n@@ -0,0 +1,1 @@\n+3\n")
+        );
+
+        let changes = manifest_changes(
+            &json!({ "dependencies": { "a": "^1.0.0", "b": "^1.0.0" } }),
+            &json!({
+                "dependencies": { "a": "^2.0.0", "b": "^1.0.0" },
+                "scripts": { "postinstall": "node setup.js" },
+            }),
+        );
+
+        assert_eq!(
+            changes,
+            [
+                ManifestChange {
+                    field: "dependencies".into(),
+                    name: "a".into(),
+                    from: Some("^1.0.0".into()),
+                    to: Some("^2.0.0".into()),
+                },
+                ManifestChange {
+                    field: "scripts".into(),
+                    name: "postinstall".into(),
+                    from: None,
+                    to: Some("node setup.js".into()),
+                },
+            ]
+        );
+    }
+}
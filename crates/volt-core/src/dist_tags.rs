@@ -0,0 +1,141 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The dist-tags of packages: names (`latest`, `next`, `beta`) pointing to published
+//! versions.
+//!
+//! They are listed with `GET /-/package/<name>/dist-tags` and changed with `PUT` and
+//! `DELETE` on `/-/package/<name>/dist-tags/<tag>`, which need a token, and a one-time
+//! password for accounts with two-factor authentication. Installs of `name@tag` resolve
+//! the tag from the abbreviated package document, which the metadata cache keeps.
+
+use crate::{
+    config::VoltConfig,
+    registry::RegistryClient,
+    reporter::Reporter,
+    utils::{
+        errors::{ResolutionError, VoltError},
+        package::DistTags,
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use node_semver::Range;
+use reqwest::Method;
+
+/// The dist-tags of `name` and the versions they point to.
+pub async fn list(client: &RegistryClient, name: &str) -> Result<DistTags> {
+    let request = client.request(Method::GET, &path(name, None));
+
+    client.send(request).await?.json().await.into_diagnostic()
+}
+
+/// Point `tag` to `version` of `name`.
+pub async fn add(
+    client: &RegistryClient,
+    name: &str,
+    version: &str,
+    tag: &str,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    validate_tag(tag)?;
+
+    client
+        .send_mutation(reporter, |client| {
+            Ok(client
+                .authenticated(Method::PUT, &path(name, Some(tag)))?
+                .json(version))
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Remove `tag` from `name`; `latest` can only be moved.
+pub async fn remove(
+    client: &RegistryClient,
+    name: &str,
+    tag: &str,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    if tag == "latest" {
+        miette::bail!(
+            "the `latest` dist-tag can't be removed, only moved with `volt dist-tag add`"
+        );
+    }
+
+    client
+        .send_mutation(reporter, |client| {
+            client.authenticated(Method::DELETE, &path(name, Some(tag)))
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// The version `tag` of `name` points to on the configured registry.
+pub async fn tag_version(config: &VoltConfig, name: &str, tag: &str) -> Result<String> {
+    let client = RegistryClient::new(config)?;
+
+    client
+        .dist_tags(name)
+        .await
+        .and_then(|dist_tags| dist_tags.get(tag).cloned())
+        .ok_or_else(|| {
+            ResolutionError::UnknownDistTag {
+                name: name.to_string(),
+                tag: tag.to_string(),
+            }
+            .into()
+        })
+}
+
+/// Refuse tags that specs would read as a version or a range (`1.2`, `v2`, `>=3`).
+pub fn validate_tag(tag: &str) -> Result<()> {
+    if tag.is_empty() || tag.parse::<Range>().is_ok() {
+        return Err(VoltError::InvalidDistTag {
+            tag: tag.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+fn path(name: &str, tag: Option<&str>) -> String {
+    let tags = format!("-/package/{}/dist-tags", RegistryClient::package_path(name));
+
+    match tag {
+        Some(tag) => format!("{}/{}", tags, tag),
+        None => tags,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_tag;
+
+    #[test]
+    fn refuses_tags_read_as_ranges() {
+        assert!(validate_tag("next").is_ok());
+        assert!(validate_tag("beta-2").is_ok());
+
+        assert!(validate_tag("").is_err());
+        assert!(validate_tag("1.2").is_err());
+        assert!(validate_tag("v2").is_err());
+        assert!(validate_tag(">=3").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Run the executable of a package without adding it to the project, like `npx`.
+//!
+//! The packages are installed into a temporary prefix that is removed after the run. Their
+//! files still come from the store, so running the same package again doesn't download it.
+
+use crate::{
+    config::VoltConfig,
+    install::{install, resolve},
+    lifecycle::path_with_bins,
+    shim::bin_entries,
+    utils::{
+        errors::{ScriptError, VoltError},
+        voltapi::VoltPackage,
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+use tempfile::TempDir;
+
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+/// Packages installed for a single run, removed when dropped.
+pub struct TemporaryPrefix {
+    directory: TempDir,
+    /// The requested packages, without their dependencies
+    pub packages: Vec<VoltPackage>,
+}
+
+impl TemporaryPrefix {
+    /// Install `packages` and their dependencies into a new temporary prefix.
+    pub async fn install(config: &VoltConfig, packages: &[PackageSpec]) -> Result<Self> {
+        let directory = tempfile::Builder::new()
+            .prefix("volt-x-")
+            .tempdir()
+            .into_diagnostic()?;
+
+        let prefix = config.with_cwd(directory.path().to_path_buf());
+
+        let resolution = resolve(config, packages).await?;
+        let packages = resolution.direct_packages().cloned().collect();
+
+        install(&prefix, resolution).await?;
+
+        Ok(Self {
+            directory,
+            packages,
+        })
+    }
+
+    /// The `node_modules/.bin` directory of the prefix.
+    pub fn bin_dir(&self) -> PathBuf {
+        self.directory.path().join("node_modules").join(".bin")
+    }
+
+    /// The executable run when none is named: the only one of the first package, or the
+    /// one named after it.
+    pub fn default_command(&self) -> Result<String> {
+        let package = &self.packages[0];
+
+        let entries = package
+            .bin
+            .as_ref()
+            .map(|bin| bin_entries(&package.name, bin))
+            .unwrap_or_default();
+
+        let unscoped = package.name.rsplit('/').next().unwrap_or(&package.name);
+
+        match entries.as_slice() {
+            [] => Err(VoltError::NoExecutables {
+                package: package.name.clone(),
+            }
+            .into()),
+            [(name, _)] => Ok(name.clone()),
+            _ => entries
+                .iter()
+                .find(|(name, _)| name == unscoped)
+                .map(|(name, _)| name.clone())
+                .ok_or_else(|| {
+                    VoltError::AmbiguousExecutable {
+                        package: package.name.clone(),
+                        executables: entries
+                            .iter()
+                            .map(|(name, _)| name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    }
+                    .into()
+                }),
+        }
+    }
+}
+
+/// Run `command` from the current directory, the executables in `bin_dir` coming first
+/// on `PATH`.
+pub fn run_command(
+    config: &VoltConfig,
+    bin_dir: PathBuf,
+    command: &str,
+    args: &[String],
+) -> Result<()> {
+    // the shims are `.cmd` files on windows, which only the shell can run
+    let program = if cfg!(windows) {
+        bin_dir.join(format!("{}.cmd", command))
+    } else {
+        bin_dir.join(command)
+    };
+
+    let program = if program.exists() {
+        program
+    } else {
+        PathBuf::from(command)
+    };
+
+    let status = Command::new(&program)
+        .args(args)
+        .current_dir(config.cwd()?)
+        .env("PATH", path_with_bins(&[bin_dir])?)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .map_err(|e| ScriptError::CommandSpawn {
+            source: e,
+            command: command.to_string(),
+        })?;
+
+    if !status.success() {
+        return Err(ScriptError::CommandFailed {
+            command: command.to_string(),
+            code: status.code().unwrap_or(-1),
+        }
+        .into());
+    }
+
+    Ok(())
+}
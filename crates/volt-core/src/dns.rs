@@ -0,0 +1,259 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Resolve the hosts of a run once, and prefer the addresses that answer.
+//!
+//! Without it, every connection the http client opens asks the system resolver again,
+//! so a slow resolver stalls each request of an install. The [`DnsCache`] resolves a host
+//! the first time it's needed, making the requests to it wait for that one lookup, and
+//! then answers from memory for [`DNS_TTL`] (or longer when resolving again fails).
+//!
+//! On networks where IPv6 is broken, connecting to the IPv6 address of a registry hangs
+//! until the connector falls back to IPv4, on every new connection. Once a host has
+//! addresses of both families, their connections are raced happy-eyeballs style (IPv6
+//! first, IPv4 after [`FALLBACK_DELAY`], on the port of the urls requested from the host)
+//! and the family that connects first is put first, so that the following connections
+//! start with it; the other addresses are kept as the fallback.
+
+use futures::{future::select_ok, lock::Mutex as AsyncMutex, FutureExt};
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    Url,
+};
+use tokio::net::TcpStream;
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How long the addresses of a host are reused before it is resolved again.
+pub const DNS_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How long the IPv6 address gets to connect before the IPv4 one is tried too.
+pub const FALLBACK_DELAY: Duration = Duration::from_millis(250);
+
+/// How long the families of a host are raced before keeping the order of the resolver.
+const RACE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Port the families of a host are raced on before any url of it was seen, the one of
+/// https.
+const DEFAULT_PORT: u16 = 443;
+
+/// The addresses of the hosts resolved so far, shared by its clones.
+#[derive(Debug, Default, Clone)]
+pub struct DnsCache {
+    /// Each host has its own lock, so that only one lookup of it runs at once
+    hosts: Arc<Mutex<HashMap<String, Arc<AsyncMutex<Option<Resolved>>>>>>,
+    /// The client resolves the host of every connection it opens, so this counts them
+    opened: Arc<Mutex<HashMap<String, usize>>>,
+    /// The port of the urls requested from each host, which its families are raced on
+    ports: Arc<Mutex<HashMap<String, u16>>>,
+}
+
+#[derive(Debug, Clone)]
+struct Resolved {
+    /// In the order to connect to them
+    addresses: Vec<SocketAddr>,
+    at: Instant,
+}
+
+impl DnsCache {
+    /// Connections opened to `host` so far by the client resolving through the cache.
+    pub fn connections(&self, host: &str) -> usize {
+        self.opened
+            .lock()
+            .map_or(0, |opened| opened.get(host).copied().unwrap_or_default())
+    }
+
+    /// Note the port of the host of `url` (`4873` for `http://localhost:4873/`, or the
+    /// default of its scheme), to race the families of the host on it.
+    pub fn register(&self, url: &str) {
+        let url = match Url::parse(url) {
+            Ok(url) => url,
+            Err(_) => return,
+        };
+
+        if let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) {
+            if let Ok(mut ports) = self.ports.lock() {
+                ports.insert(host.to_string(), port);
+            }
+        }
+    }
+
+    /// The port the families of `host` are raced on.
+    fn port(&self, host: &str) -> u16 {
+        self.ports
+            .lock()
+            .ok()
+            .and_then(|ports| ports.get(host).copied())
+            .unwrap_or(DEFAULT_PORT)
+    }
+
+    /// The addresses of `host`, from memory when they were resolved less than
+    /// [`DNS_TTL`] ago.
+    pub async fn lookup(&self, host: &str) -> std::io::Result<Vec<SocketAddr>> {
+        let slot = {
+            let mut hosts = match self.hosts.lock() {
+                Ok(hosts) => hosts,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+
+            Arc::clone(hosts.entry(host.to_string()).or_default())
+        };
+
+        // the requests to a host being resolved wait for its addresses
+        let mut resolved = slot.lock().await;
+
+        if let Some(cached) = resolved
+            .as_ref()
+            .filter(|cached| cached.at.elapsed() < DNS_TTL)
+        {
+            return Ok(cached.addresses.clone());
+        }
+
+        let addresses = match tokio::net::lookup_host((host, 0)).await {
+            Ok(addresses) => addresses.collect::<Vec<_>>(),
+            // the last addresses are better than none
+            Err(error) => {
+                return match resolved.as_ref() {
+                    Some(stale) => {
+                        tracing::debug!("failed to resolve {} again: {}", host, error);
+
+                        Ok(stale.addresses.clone())
+                    }
+                    None => Err(error),
+                };
+            }
+        };
+
+        let addresses = match race(&addresses, self.port(host)).await {
+            Some(ipv6) => {
+                tracing::debug!(
+                    "{} answers first over {}",
+                    host,
+                    if ipv6 { "IPv6" } else { "IPv4" }
+                );
+
+                prefer(addresses, ipv6)
+            }
+            None => addresses,
+        };
+
+        tracing::debug!("resolved {} to {:?}", host, addresses);
+
+        *resolved = Some(Resolved {
+            addresses: addresses.clone(),
+            at: Instant::now(),
+        });
+
+        Ok(addresses)
+    }
+}
+
+impl Resolve for DnsCache {
+    fn resolve(&self, name: Name) -> Resolving {
+        if let Ok(mut opened) = self.opened.lock() {
+            *opened.entry(name.as_str().to_string()).or_default() += 1;
+        }
+
+        let cache = self.clone();
+
+        Box::pin(async move {
+            let addresses = cache.lookup(name.as_str()).await?;
+
+            let addresses: Addrs = Box::new(addresses.into_iter());
+
+            Ok::<_, Box<dyn Error + Send + Sync>>(addresses)
+        })
+    }
+}
+
+/// Connect to the first IPv6 and the first IPv4 address of `addresses` on `port`, the
+/// IPv4 one [`FALLBACK_DELAY`] later, returning whether IPv6 connected first. `None`
+/// without addresses of both families, or when neither connects.
+async fn race(addresses: &[SocketAddr], port: u16) -> Option<bool> {
+    let first = |ipv6: bool| {
+        addresses
+            .iter()
+            .find(|address| address.is_ipv6() == ipv6)
+            .map(|address| SocketAddr::new(address.ip(), port))
+    };
+
+    let (ipv6, ipv4) = (first(true)?, first(false)?);
+
+    let attempts = [
+        async move { TcpStream::connect(ipv6).await.map(|_| true) }.boxed(),
+        async move {
+            tokio::time::sleep(FALLBACK_DELAY).await;
+
+            TcpStream::connect(ipv4).await.map(|_| false)
+        }
+        .boxed(),
+    ];
+
+    tokio::time::timeout(RACE_TIMEOUT, select_ok(attempts))
+        .await
+        .ok()?
+        .ok()
+        .map(|(ipv6, _)| ipv6)
+}
+
+/// `addresses` with those of one family first, each family in the resolver's order.
+fn prefer(mut addresses: Vec<SocketAddr>, ipv6: bool) -> Vec<SocketAddr> {
+    addresses.sort_by_key(|address| address.is_ipv6() != ipv6);
+
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prefer, DnsCache, DEFAULT_PORT};
+
+    use std::net::SocketAddr;
+
+    #[test]
+    fn puts_the_family_that_answered_first() {
+        let addresses: Vec<SocketAddr> = ["[2606:4700::1]:0", "104.16.0.1:0", "104.16.0.2:0"]
+            .iter()
+            .map(|address| address.parse().unwrap())
+            .collect();
+
+        assert_eq!(
+            prefer(addresses.clone(), false),
+            [addresses[1], addresses[2], addresses[0]]
+        );
+        assert_eq!(prefer(addresses.clone(), true), addresses);
+    }
+
+    #[test]
+    fn races_on_the_port_of_the_urls() {
+        let dns = DnsCache::default();
+
+        dns.register("http://localhost:4873/react");
+        dns.register("http://mirror.internal/react/-/react-18.0.0.tgz");
+        dns.register("https://registry.npmjs.org/react");
+
+        assert_eq!(dns.port("localhost"), 4873);
+        assert_eq!(dns.port("mirror.internal"), 80);
+        assert_eq!(dns.port("registry.npmjs.org"), 443);
+        assert_eq!(dns.port("registry.yarnpkg.com"), DEFAULT_PORT);
+    }
+}
@@ -0,0 +1,455 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check the machine volt runs on for what commonly breaks installs.
+//!
+//! Every check ends in a [`Diagnosis`]: whether it passed, what was found, and what to do
+//! about it when it didn't.
+
+use crate::{
+    cache::entries,
+    config::VoltConfig,
+    engines::command_version,
+    registry::RegistryClient,
+    toolchain::{Toolchain, SHIMS},
+};
+
+use reqwest::{header::DATE, Method};
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// How far the clock may be from the registry's before signatures and caches misbehave.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// The outcome of one check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail,
+    /// The check couldn't run, because of an earlier failure
+    Skip,
+}
+
+/// What a check found.
+#[derive(Debug, Clone)]
+pub struct Diagnosis {
+    pub check: &'static str,
+    pub outcome: Outcome,
+    pub detail: String,
+    /// What to do about a failure
+    pub remedy: Option<String>,
+}
+
+impl Diagnosis {
+    fn pass(check: &'static str, detail: String) -> Self {
+        Self {
+            check,
+            outcome: Outcome::Pass,
+            detail,
+            remedy: None,
+        }
+    }
+
+    fn fail(check: &'static str, detail: String, remedy: String) -> Self {
+        Self {
+            check,
+            outcome: Outcome::Fail,
+            detail,
+            remedy: Some(remedy),
+        }
+    }
+
+    fn skip(check: &'static str, detail: String) -> Self {
+        Self {
+            check,
+            outcome: Outcome::Skip,
+            detail,
+            remedy: None,
+        }
+    }
+}
+
+/// Run every check, in the order they are reported.
+pub async fn diagnose(config: &VoltConfig) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![writable(config), shims(config), node()];
+
+    diagnoses.extend(registry(config).await);
+    diagnoses.push(node_modules(config));
+    diagnoses.push(store(config));
+
+    diagnoses
+}
+
+/// Whether volt can write to its home and to the store.
+fn writable(config: &VoltConfig) -> Diagnosis {
+    const CHECK: &str = "volt directory is writable";
+
+    let directories = match (config.volt_home(), config.store()) {
+        (Ok(home), Ok(store)) if home == store => vec![home],
+        (Ok(home), Ok(store)) => vec![home, store],
+        (Err(error), _) | (_, Err(error)) => {
+            return Diagnosis::fail(
+                CHECK,
+                error.to_string(),
+                "set HOME to your home directory".to_string(),
+            )
+        }
+    };
+
+    for directory in &directories {
+        let result = std::fs::create_dir_all(directory)
+            .and_then(|_| tempfile::NamedTempFile::new_in(directory).map(|_| ()));
+
+        if let Err(error) = result {
+            return Diagnosis::fail(
+                CHECK,
+                format!("can't write to {}: {}", directory.display(), error),
+                format!(
+                    "make {} writable by your user, or point `store-dir` elsewhere",
+                    directory.display()
+                ),
+            );
+        }
+    }
+
+    Diagnosis::pass(CHECK, display_paths(&directories))
+}
+
+/// Whether the `node`, `npm` and `npx` shims are linked and on `PATH`, when volt manages
+/// node versions at all.
+fn shims(config: &VoltConfig) -> Diagnosis {
+    const CHECK: &str = "node shims are on PATH";
+
+    let toolchain = match Toolchain::from_config(config) {
+        Ok(toolchain) => toolchain,
+        Err(error) => return Diagnosis::skip(CHECK, error.to_string()),
+    };
+
+    if toolchain.installed().is_empty() {
+        return Diagnosis::skip(CHECK, "volt doesn't manage any node version".to_string());
+    }
+
+    let bin_dir = toolchain.bin_dir();
+
+    let missing: Vec<&str> = SHIMS
+        .iter()
+        .filter(|tool| {
+            bin_dir
+                .join(format!("{}{}", tool, std::env::consts::EXE_SUFFIX))
+                .metadata()
+                .is_err()
+        })
+        .copied()
+        .collect();
+
+    if !missing.is_empty() {
+        return Diagnosis::fail(
+            CHECK,
+            format!("{} missing from {}", missing.join(", "), bin_dir.display()),
+            "link them again with `volt node use <version>`".to_string(),
+        );
+    }
+
+    let on_path = std::env::var_os("PATH").map_or(false, |path| {
+        std::env::split_paths(&path).any(|directory| directory == bin_dir)
+    });
+
+    if !on_path {
+        return Diagnosis::fail(
+            CHECK,
+            format!("{} isn't on PATH", bin_dir.display()),
+            format!("add {} to PATH in your shell profile", bin_dir.display()),
+        );
+    }
+
+    Diagnosis::pass(CHECK, bin_dir.display().to_string())
+}
+
+/// Whether `node` runs.
+fn node() -> Diagnosis {
+    const CHECK: &str = "node is available";
+
+    match command_version("node") {
+        Some(version) => Diagnosis::pass(CHECK, format!("v{}", version)),
+        None => Diagnosis::fail(
+            CHECK,
+            "`node --version` didn't run".to_string(),
+            "install node with `volt node install lts`".to_string(),
+        ),
+    }
+}
+
+/// Whether the registry answers, and whether the clock agrees with the time it sends.
+async fn registry(config: &VoltConfig) -> Vec<Diagnosis> {
+    const CHECK: &str = "registry is reachable";
+    const CLOCK: &str = "clock is in sync";
+
+    let client = match RegistryClient::new(config) {
+        Ok(client) => client,
+        Err(error) => {
+            return vec![
+                Diagnosis::fail(
+                    CHECK,
+                    error.to_string(),
+                    "check the `registry` and proxy settings".to_string(),
+                ),
+                Diagnosis::skip(CLOCK, "the registry didn't answer".to_string()),
+            ]
+        }
+    };
+
+    let response = match client.request(Method::GET, "-/ping").send().await {
+        Ok(response) => response,
+        Err(error) => {
+            return vec![
+                Diagnosis::fail(
+                    CHECK,
+                    format!("{}: {}", client.url, error),
+                    "check your connection, and the `registry` and proxy settings".to_string(),
+                ),
+                Diagnosis::skip(CLOCK, "the registry didn't answer".to_string()),
+            ]
+        }
+    };
+
+    // any answer means it is reachable, registries without `-/ping` answer 404
+    let reachable = if response.status().is_server_error() {
+        Diagnosis::fail(
+            CHECK,
+            format!("{} answered {}", client.url, response.status()),
+            "try again later, or use a mirror of the registry".to_string(),
+        )
+    } else {
+        Diagnosis::pass(CHECK, client.url.clone())
+    };
+
+    let date = response
+        .headers()
+        .get(DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| httpdate::parse_http_date(date).ok());
+
+    let clock = match date {
+        Some(date) => clock_skew(SystemTime::now(), date),
+        None => Diagnosis::skip(CLOCK, "the registry didn't send its time".to_string()),
+    };
+
+    vec![reachable, clock]
+}
+
+/// Compare the local time with a server's.
+fn clock_skew(now: SystemTime, server: SystemTime) -> Diagnosis {
+    const CHECK: &str = "clock is in sync";
+
+    let (skew, direction) = match now.duration_since(server) {
+        Ok(ahead) => (ahead, "ahead of"),
+        Err(behind) => (behind.duration(), "behind"),
+    };
+
+    let detail = format!("{}s {} the registry", skew.as_secs(), direction);
+
+    if skew > MAX_CLOCK_SKEW {
+        Diagnosis::fail(
+            CHECK,
+            detail,
+            "sync the system clock, certificates and signatures are checked against it".to_string(),
+        )
+    } else {
+        Diagnosis::pass(CHECK, detail)
+    }
+}
+
+/// Whether every symlink in the project's `node_modules` points at something.
+fn node_modules(config: &VoltConfig) -> Diagnosis {
+    const CHECK: &str = "node_modules has no broken links";
+
+    let node_modules = match config.node_modules() {
+        Ok(node_modules) if node_modules.is_dir() => node_modules,
+        _ => return Diagnosis::skip(CHECK, "no node_modules here".to_string()),
+    };
+
+    let mut broken = vec![];
+
+    broken_links(&node_modules, &mut broken);
+
+    if broken.is_empty() {
+        return Diagnosis::pass(CHECK, node_modules.display().to_string());
+    }
+
+    broken.sort();
+
+    Diagnosis::fail(
+        CHECK,
+        format!("{} broken: {}", broken.len(), display_paths(&broken)),
+        "link them again with `volt install`".to_string(),
+    )
+}
+
+/// Collect the symlinks under `directory` whose target is gone. Only the directories
+/// holding packages are looked into: `node_modules`, scopes, `.bin`, the `.volt` virtual
+/// store and the `node_modules` of the packages in them.
+fn broken_links(directory: &Path, broken: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let in_store = directory.file_name().map_or(false, |name| name == ".volt");
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+
+        let metadata = match path.symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+
+        if metadata.file_type().is_symlink() {
+            // metadata follows the link
+            if path.metadata().is_err() {
+                broken.push(path);
+            }
+
+            continue;
+        }
+
+        if !metadata.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if in_store || name.starts_with('@') || [".bin", ".volt", "node_modules"].contains(&&*name)
+        {
+            broken_links(&path, broken);
+        } else {
+            // a package, only its own node_modules holds packages
+            broken_links(&path.join("node_modules"), broken);
+        }
+    }
+}
+
+/// Whether every package in the store matches its hashes.
+fn store(config: &VoltConfig) -> Diagnosis {
+    const CHECK: &str = "store is intact";
+
+    let store = match config.store() {
+        Ok(store) => store,
+        Err(error) => return Diagnosis::skip(CHECK, error.to_string()),
+    };
+
+    let entries = match entries(&store) {
+        Ok(entries) => entries,
+        Err(error) => {
+            return Diagnosis::fail(
+                CHECK,
+                error.to_string(),
+                "remove the store with `volt cache clean`".to_string(),
+            )
+        }
+    };
+
+    let corrupt: Vec<String> = entries
+        .iter()
+        .filter(|entry| !entry.is_intact(&store))
+        .map(|entry| entry.spec())
+        .collect();
+
+    if corrupt.is_empty() {
+        return Diagnosis::pass(CHECK, format!("{} packages checked", entries.len()));
+    }
+
+    Diagnosis::fail(
+        CHECK,
+        format!("{} corrupt: {}", corrupt.len(), corrupt.join(", ")),
+        "remove them with `volt cache verify`".to_string(),
+    )
+}
+
+/// At most a few paths, for a one-line detail.
+fn display_paths(paths: &[PathBuf]) -> String {
+    let mut shown: Vec<String> = paths
+        .iter()
+        .take(3)
+        .map(|path| path.display().to_string())
+        .collect();
+
+    if paths.len() > shown.len() {
+        shown.push(format!("and {} more", paths.len() - shown.len()));
+    }
+
+    shown.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{broken_links, clock_skew, Outcome};
+
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn finds_broken_links_and_clock_skew() {
+        let now = SystemTime::now();
+
+        assert_eq!(clock_skew(now, now).outcome, Outcome::Pass);
+        assert_eq!(
+            clock_skew(now, now + Duration::from_secs(3600)).outcome,
+            Outcome::Fail
+        );
+
+        #[cfg(unix)]
+        {
+            let directory = tempfile::tempdir().unwrap();
+            let node_modules = directory.path().join("node_modules");
+
+            std::fs::create_dir_all(node_modules.join(".volt/a@1.0.0/node_modules/a")).unwrap();
+            std::fs::create_dir_all(node_modules.join("@scope")).unwrap();
+
+            std::os::unix::fs::symlink(
+                node_modules.join(".volt/a@1.0.0/node_modules/a"),
+                node_modules.join("a"),
+            )
+            .unwrap();
+            std::os::unix::fs::symlink(
+                node_modules.join(".volt/b@1.0.0/node_modules/b"),
+                node_modules.join("@scope/b"),
+            )
+            .unwrap();
+            std::os::unix::fs::symlink(
+                node_modules.join(".volt/c@1.0.0/node_modules/c"),
+                node_modules.join(".volt/a@1.0.0/node_modules/c"),
+            )
+            .unwrap();
+
+            let mut broken = vec![];
+
+            broken_links(&node_modules, &mut broken);
+            broken.sort();
+
+            assert_eq!(
+                broken,
+                vec![
+                    node_modules.join(".volt/a@1.0.0/node_modules/c"),
+                    node_modules.join("@scope/b"),
+                ]
+            );
+        }
+    }
+}
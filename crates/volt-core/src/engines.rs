@@ -0,0 +1,144 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Match the `engines` field of a package against the node, npm and volt running it.
+
+use crate::utils::voltapi::Engine;
+
+use node_semver::{Range, Version};
+use once_cell::unsync::OnceCell;
+
+use std::{collections::BTreeMap, process::Command};
+
+/// An engine whose version is outside the range a package asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unsatisfied {
+    pub engine: String,
+    pub range: String,
+    pub current: String,
+}
+
+/// The versions of the engines on this machine, found the first time a package asks.
+#[derive(Debug, Default)]
+pub struct Runtime {
+    node: OnceCell<Option<Version>>,
+    npm: OnceCell<Option<Version>>,
+}
+
+impl Runtime {
+    fn version(&self, engine: &str) -> Option<Version> {
+        match engine {
+            "node" => self.node.get_or_init(|| command_version("node")).clone(),
+            "npm" => self.npm.get_or_init(|| command_version("npm")).clone(),
+            "volt" => env!("CARGO_PKG_VERSION").parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The engines of `engines` whose version here is outside the range. Engines that
+    /// aren't installed and ranges that don't parse can't be checked, and are left out.
+    pub fn unsatisfied(&self, engines: &BTreeMap<String, String>) -> Vec<Unsatisfied> {
+        engines
+            .iter()
+            .filter_map(|(engine, range)| {
+                let parsed = range.parse::<Range>().ok()?;
+                let current = self.version(engine)?;
+
+                (!parsed.satisfies(&current)).then(|| Unsatisfied {
+                    engine: engine.clone(),
+                    range: range.clone(),
+                    current: current.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// `engine -> range` of an `engines` field, including the old `"node >= 0.6"` string
+/// and list forms.
+pub fn engine_ranges(engines: Option<&Engine>) -> BTreeMap<String, String> {
+    let split = |entry: &String| {
+        entry
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(engine, range)| (engine.to_string(), range.trim().to_string()))
+    };
+
+    match engines {
+        Some(Engine::Map(map)) => map.clone().into_iter().collect(),
+        Some(Engine::String(entry)) => split(entry).into_iter().collect(),
+        Some(Engine::List(entries)) => entries.iter().filter_map(split).collect(),
+        None => BTreeMap::new(),
+    }
+}
+
+/// The version `<command> --version` prints, if the command runs.
+pub fn command_version(command: &str) -> Option<Version> {
+    let output = Command::new(command).arg("--version").output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let version = String::from_utf8_lossy(&output.stdout);
+    let version = version.trim().trim_start_matches('v');
+
+    tracing::debug!("found {} {}", command, version);
+
+    version.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{engine_ranges, Runtime};
+    use crate::utils::voltapi::Engine;
+
+    use once_cell::unsync::OnceCell;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn reports_engines_outside_their_range() {
+        let runtime = Runtime {
+            node: OnceCell::from(Some("16.14.0".parse().unwrap())),
+            // npm isn't installed
+            npm: OnceCell::from(None),
+        };
+
+        let engines: BTreeMap<String, String> = [
+            ("node", ">=18"),
+            ("npm", ">=8"),
+            ("volt", "*"),
+            ("vscode", "^1.60.0"),
+        ]
+        .iter()
+        .map(|(engine, range)| (engine.to_string(), range.to_string()))
+        .collect();
+
+        let unsatisfied = runtime.unsatisfied(&engines);
+
+        assert_eq!(unsatisfied.len(), 1);
+        assert_eq!(unsatisfied[0].engine, "node");
+        assert_eq!(unsatisfied[0].current, "16.14.0");
+
+        assert_eq!(
+            engine_ranges(Some(&Engine::List(vec!["node >= 0.6".to_string()]))),
+            [("node".to_string(), ">= 0.6".to_string())]
+                .into_iter()
+                .collect()
+        );
+    }
+}
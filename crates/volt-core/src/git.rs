@@ -0,0 +1,298 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Thin wrapper around the `git` cli, and packages installed from git repositories.
+//!
+//! A git dependency is cloned at the requested ref, prepared when its scripts may run
+//! (its `prepare` script is run after installing its dependencies) and packed into a
+//! tarball like the registry would serve. The lock file records it as `git+<url>#<commit>` so installs are reproducible.
+
+use crate::{
+    config::VoltConfig,
+    install::{dependency_specs, install, project_dependencies, resolve, InstallScope, Resolution},
+    io::pack_directory,
+    lifecycle::scripts_allowed,
+    utils::{errors::VoltError, package::PackageJson, voltapi::VoltPackage},
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use miette::{IntoDiagnostic, Result};
+use package_spec::GitInfo;
+
+use std::{path::Path, process::Command};
+
+/// The url `git clone` should be given for a git specification.
+pub fn clone_url(info: &GitInfo) -> String {
+    match info {
+        GitInfo::Hosted { .. } => info.https().map(|url| url.to_string()).unwrap_or_default(),
+        GitInfo::Url { url, .. } => {
+            let url = url.to_string();
+
+            // `git+https://...` -> `https://...`
+            url.strip_prefix("git+").map(String::from).unwrap_or(url)
+        }
+        GitInfo::Ssh { ssh, .. } => ssh.clone(),
+    }
+}
+
+/// The commit, branch or tag requested by a git specification, if any.
+pub fn committish(info: &GitInfo) -> Option<&str> {
+    match info {
+        GitInfo::Hosted { committish, .. }
+        | GitInfo::Url { committish, .. }
+        | GitInfo::Ssh { committish, .. } => committish.as_deref(),
+    }
+}
+
+/// Run a git command, failing with its stderr if it exits unsuccessfully.
+pub fn git(cwd: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| VoltError::GitCommandError {
+            command: args.join(" "),
+            stderr: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(VoltError::GitCommandError {
+            command: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Clone `url` into `dest` and check out `committish` (the default branch if `None`).
+///
+/// Both come from package.json or volt.lock, so they're checked before reaching git,
+/// which would take them for options otherwise.
+pub fn clone(url: &str, committish: Option<&str>, dest: &Path) -> Result<()> {
+    validate_source(url, committish)?;
+
+    let dest_str = dest.to_string_lossy();
+
+    match committish {
+        // shallow clones can only check out branches and tags, so fetch everything for commits
+        Some(committish) => {
+            git(Path::new("."), &["clone", "--quiet", "--", url, &dest_str])?;
+            git(dest, &["checkout", "--quiet", committish, "--"])?;
+        }
+        None => {
+            git(
+                Path::new("."),
+                &["clone", "--quiet", "--depth", "1", "--", url, &dest_str],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fail unless `url` is an https, ssh, git or file url, or an scp-like `user@host:path`,
+/// and neither it nor `committish` starts with `-`.
+pub fn validate_source(url: &str, committish: Option<&str>) -> Result<()> {
+    let invalid = |reason: &str| -> miette::Report {
+        VoltError::InvalidGitSource {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        }
+        .into()
+    };
+
+    if url.starts_with('-') {
+        return Err(invalid("it starts with `-`"));
+    }
+
+    if committish.map_or(false, |committish| committish.starts_with('-')) {
+        return Err(invalid("the ref starts with `-`"));
+    }
+
+    match url.split_once("://") {
+        Some((scheme, _)) if ["https", "ssh", "git", "file"].contains(&scheme) => Ok(()),
+        Some((scheme, _)) => Err(invalid(&format!("`{}` urls aren't supported", scheme))),
+        // `git@github.com:user/repo.git`, but not git's `<transport>::<address>`
+        None => match url.split_once(':') {
+            Some((host, path))
+                if !host.is_empty()
+                    && host
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "@.-_".contains(c))
+                    && !path.is_empty()
+                    && !path.starts_with(':') =>
+            {
+                Ok(())
+            }
+            _ => Err(invalid("it isn't a url")),
+        },
+    }
+}
+
+/// Whether a locked `git+<url>#<commit>` source satisfies a git specification.
+///
+/// Branches and tags stay pinned to the locked commit, only a different commit or
+/// repository requires resolving the specification again.
+pub fn locks(info: &GitInfo, source: &str) -> bool {
+    let commit = match source.strip_prefix(&format!("git+{}#", clone_url(info))) {
+        Some(commit) => commit,
+        None => return false,
+    };
+
+    match committish(info) {
+        Some(committish) if is_commit(committish) => commit.starts_with(committish),
+        _ => true,
+    }
+}
+
+/// Whether a committish looks like a (possibly abbreviated) commit sha rather than a
+/// branch or tag.
+fn is_commit(committish: &str) -> bool {
+    committish.len() >= 7 && committish.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Resolve a git specification into the package its repository contains, along with
+/// the registry dependencies of that package.
+pub fn resolve_git<'a>(
+    config: &'a VoltConfig,
+    info: &'a GitInfo,
+) -> BoxFuture<'a, Result<Resolution>> {
+    async move {
+        let url = clone_url(info);
+
+        let directory = tempfile::tempdir().into_diagnostic()?;
+
+        clone(&url, committish(info), directory.path())?;
+
+        let commit = git(directory.path(), &["rev-parse", "HEAD"])?;
+        let source = format!("git+{}#{}", url, commit);
+
+        let (package_json, _) = PackageJson::get_from_dir(directory.path())?;
+
+        let dependencies = package_json.dependencies.clone().unwrap_or_default();
+
+        let mut resolution = resolve(config, &dependency_specs(&dependencies)).await?;
+
+        let tarball = prepare_and_pack(config, directory.path()).await?;
+
+        cacache::write_sync(config.store()?, tarball_key(&source), tarball).into_diagnostic()?;
+
+        let mut package = VoltPackage::from_manifest(&package_json, source);
+
+        package.dependencies = Some(resolution.edges());
+
+        let key = package.key();
+
+        resolution.direct = vec![key.clone()];
+        resolution.aliases.clear();
+        resolution.tree.insert(key, package);
+
+        Ok(resolution)
+    }
+    .boxed()
+}
+
+/// The packed tarball of a `git+<url>#<commit>` source, cloning and preparing the
+/// repository again if it isn't cached.
+pub async fn fetch_git_tarball(config: &VoltConfig, source: &str) -> Result<Vec<u8>> {
+    let store = config.store()?;
+
+    if let Ok(tarball) = cacache::read_sync(&store, tarball_key(source)) {
+        return Ok(tarball);
+    }
+
+    let (url, commit) = source
+        .strip_prefix("git+")
+        .and_then(|source| source.rsplit_once('#'))
+        .ok_or_else(|| miette::miette!("{} is not a git source", source))?;
+
+    let directory = tempfile::tempdir().into_diagnostic()?;
+
+    clone(url, Some(commit), directory.path())?;
+
+    let tarball = prepare_and_pack(config, directory.path()).await?;
+
+    cacache::write_sync(&store, tarball_key(source), &tarball).into_diagnostic()?;
+
+    Ok(tarball)
+}
+
+/// The store key of the packed tarball of a git source.
+pub fn tarball_key(source: &str) -> String {
+    format!("git::{}", source)
+}
+
+/// Run the `prepare` script of a cloned repository (installing its dependencies first, as
+/// it usually builds the package) and pack the result.
+///
+/// `prepare` is a script of a dependency like any other: unless `allow-scripts` has the
+/// package (or `ignore-scripts` is off), it only runs once approved, and the repository
+/// is packed as it is otherwise.
+async fn prepare_and_pack(config: &VoltConfig, directory: &Path) -> Result<Vec<u8>> {
+    let (package_json, _) = PackageJson::get_from_dir(directory)?;
+
+    let prepare = package_json
+        .scripts
+        .as_ref()
+        .and_then(|scripts| scripts.get("prepare"));
+
+    if let Some(prepare) = prepare {
+        let approved = scripts_allowed(config, &package_json.name)
+            || config.reporter().approve_scripts(&[(
+                format!("{}@{}", package_json.name, package_json.version),
+                vec![("prepare", prepare.clone())],
+            )])?;
+
+        if approved {
+            let repository = config.with_cwd(directory.to_path_buf());
+
+            let (dependencies, _) = project_dependencies(directory, &[], InstallScope::All)?;
+
+            let resolution = resolve(&repository, &dependency_specs(&dependencies)).await?;
+
+            // installing runs the root lifecycle scripts, `prepare` included
+            install(&repository, resolution).await?;
+        } else {
+            config.reporter().warning(&format!(
+                "skipped the prepare script of {}, which may leave it unbuilt; allow it with `allow-scripts = [\"{}\"]` in .voltrc",
+                package_json.name, package_json.name
+            ));
+        }
+    }
+
+    pack_directory(directory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_source;
+
+    #[test]
+    fn only_clones_repositories() {
+        assert!(validate_source("https://github.com/voltpkg/volt.git", Some("main")).is_ok());
+        assert!(validate_source("ssh://git@github.com/voltpkg/volt.git", None).is_ok());
+        assert!(validate_source("git@github.com:voltpkg/volt.git", None).is_ok());
+        assert!(validate_source("file:///srv/git/volt", None).is_ok());
+
+        assert!(validate_source("--upload-pack=touch /tmp/pwned", Some("x")).is_err());
+        assert!(validate_source("https://github.com/voltpkg/volt.git", Some("-p")).is_err());
+        assert!(validate_source("ext::sh -c touch% /tmp/pwned", None).is_err());
+        assert!(validate_source("http://github.com/voltpkg/volt.git", None).is_err());
+        assert!(validate_source("./volt", None).is_err());
+    }
+}
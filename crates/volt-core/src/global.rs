@@ -0,0 +1,226 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Packages installed globally into `~/.volt/global`.
+//!
+//! The prefix is laid out like a project: a package.json recording the installed
+//! packages, a `node_modules` directory and a `bin` directory with their executables.
+
+use crate::{
+    config::VoltConfig,
+    install::{dependency_specs, install, resolve, write_lock_file},
+    shim::{link_bins, unlink_bins},
+    utils::{errors::FilesystemError, package::PackageJson, voltapi::VoltPackage},
+};
+
+use miette::Result;
+use package_spec::PackageSpec;
+
+use std::{collections::BTreeMap, path::Path};
+
+/// The configuration used to install into the global prefix, creating it if needed.
+pub fn global_config(config: &VoltConfig) -> Result<VoltConfig> {
+    let prefix = config.global_prefix()?;
+
+    std::fs::create_dir_all(&prefix).map_err(|e| FilesystemError::Write {
+        source: e,
+        path: prefix.display().to_string(),
+    })?;
+
+    Ok(config.with_cwd(prefix))
+}
+
+/// Read the manifest of the global prefix (empty if nothing was installed yet).
+pub fn manifest(prefix: &Path) -> Result<PackageJson> {
+    if prefix.join("package.json").exists() {
+        Ok(PackageJson::get_from_dir(prefix)?.0)
+    } else {
+        Ok(PackageJson {
+            name: "volt-global".to_string(),
+            version: "0.0.0".to_string(),
+            private: Some(true),
+            ..Default::default()
+        })
+    }
+}
+
+/// Install `packages` globally and expose their executables in the global bin directory.
+pub async fn install_global(config: &VoltConfig, packages: &[PackageSpec]) -> Result<()> {
+    let global = global_config(config)?;
+    let prefix = global.cwd()?;
+    let node_modules = global.node_modules()?;
+    let bin_dir = config.global_bin()?;
+
+    let mut manifest = manifest(&prefix)?;
+
+    // the packages installed before stay, or the install would remove them
+    let resolution = resolve(config, &global_specs(&manifest, packages)).await?;
+
+    let requested: Vec<&str> = packages.iter().filter_map(spec_name).collect();
+
+    let direct: Vec<VoltPackage> = resolution
+        .direct_packages()
+        .filter(|package| requested.is_empty() || requested.contains(&package.name.as_str()))
+        .cloned()
+        .collect();
+
+    write_lock_file(&global, &resolution)?;
+
+    install(&global, resolution).await?;
+
+    for package in &direct {
+        if let Some(bin) = &package.bin {
+            // a link or a directory, depending on the layout
+            link_bins(
+                &bin_dir,
+                &node_modules.join(&package.name),
+                &package.name,
+                bin,
+            )?;
+        }
+
+        manifest
+            .dependencies
+            .get_or_insert_with(BTreeMap::new)
+            .insert(package.name.clone(), package.version.clone());
+    }
+
+    manifest.save_to(&prefix.join("package.json"))?;
+
+    let on_path = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|entry| entry == bin_dir))
+        .unwrap_or(false);
+
+    if !on_path {
+        config.reporter().info(&format!(
+            "note: add {} to your PATH to run globally installed executables",
+            bin_dir.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// The specs to resolve to install `packages` globally: theirs, and those of the
+/// packages installed before with other names.
+pub fn global_specs(manifest: &PackageJson, packages: &[PackageSpec]) -> Vec<PackageSpec> {
+    let requested: Vec<&str> = packages.iter().filter_map(spec_name).collect();
+
+    let installed: BTreeMap<String, String> = manifest
+        .dependencies
+        .iter()
+        .flatten()
+        .filter(|(name, _)| !requested.contains(&name.as_str()))
+        .map(|(name, version)| (name.clone(), version.clone()))
+        .collect();
+
+    let mut specs = dependency_specs(&installed);
+
+    specs.extend(packages.iter().cloned());
+
+    specs
+}
+
+/// The name a spec installs its package as, when the spec names it.
+fn spec_name(spec: &PackageSpec) -> Option<&str> {
+    match spec {
+        PackageSpec::Npm { name, .. } | PackageSpec::Alias { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+/// Uninstall globally installed packages and remove their executables.
+pub fn remove_global(config: &VoltConfig, names: &[String]) -> Result<()> {
+    let prefix = config.global_prefix()?;
+    let node_modules = prefix.join("node_modules");
+    let bin_dir = config.global_bin()?;
+
+    let mut manifest = manifest(&prefix)?;
+
+    let reporter = config.reporter();
+
+    for name in names {
+        let installed = manifest
+            .dependencies
+            .as_mut()
+            .and_then(|dependencies| dependencies.remove(name));
+
+        if installed.is_none() {
+            tracing::warn!("{} is not installed globally", name);
+
+            continue;
+        }
+
+        let package_dir = node_modules.join(name);
+
+        if let Ok((package_json, _)) = PackageJson::get_from_dir(&package_dir) {
+            if let Some(bin) = package_json.bin {
+                unlink_bins(&bin_dir, name, &bin.into())?;
+            }
+        }
+
+        if package_dir.symlink_metadata().is_ok() {
+            std::fs::remove_file(&package_dir)
+                .or_else(|_| std::fs::remove_dir(&package_dir))
+                .map_err(|e| FilesystemError::Write {
+                    source: e,
+                    path: package_dir.display().to_string(),
+                })?;
+        }
+
+        reporter.info(&format!("Removed {}", name));
+    }
+
+    manifest.save_to(&prefix.join("package.json"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{global_specs, manifest};
+
+    use package_spec::PackageSpec;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn keeps_the_globals_installed_before() {
+        let mut manifest = manifest(std::path::Path::new("/nonexistent")).unwrap();
+
+        let first: Vec<PackageSpec> = vec!["typescript@4.6.3".parse().unwrap()];
+
+        assert_eq!(global_specs(&manifest, &first), first);
+
+        // what installing typescript saved
+        manifest.dependencies = Some(BTreeMap::from([(
+            "typescript".to_string(),
+            "4.6.3".to_string(),
+        )]));
+
+        let second: Vec<PackageSpec> = vec!["prettier".parse().unwrap()];
+
+        assert_eq!(
+            global_specs(&manifest, &second),
+            [first[0].clone(), second[0].clone()]
+        );
+
+        // installing typescript again replaces the version installed
+        let upgrade: Vec<PackageSpec> = vec!["typescript@4.7.2".parse().unwrap()];
+
+        assert_eq!(global_specs(&manifest, &upgrade), upgrade);
+    }
+}
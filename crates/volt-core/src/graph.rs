@@ -0,0 +1,291 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The dependency graph of a project, for tools and diagrams.
+//!
+//! Nodes are `name@version` keys with the project as the root; edges go from a package
+//! to the packages it depends on. The graph prints as Graphviz DOT, mermaid or JSON.
+
+use crate::{install::Resolution, utils::voltapi::dependency_key};
+
+use serde_json::{json, Value};
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// The formats `volt graph` prints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ArgEnum))]
+pub enum GraphFormat {
+    Dot,
+    Json,
+    Mermaid,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DependencyGraph {
+    /// Name of the project
+    pub root: String,
+    /// Keys of the packages
+    pub nodes: BTreeSet<String>,
+    /// Dependent -> dependencies (the root included as a dependent)
+    pub edges: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+    /// The graph of every package of `resolution` under a project named `root`.
+    pub fn new(root: &str, resolution: &Resolution) -> Self {
+        let mut graph = Self {
+            root: root.to_string(),
+            ..Self::default()
+        };
+
+        let direct = resolution.direct.iter().chain(resolution.aliases.values());
+
+        graph
+            .edges
+            .insert(root.to_string(), direct.cloned().collect());
+
+        for (key, package) in &resolution.tree {
+            graph.nodes.insert(key.clone());
+
+            let dependencies = package
+                .dependencies
+                .iter()
+                .flatten()
+                .map(|(name, version)| dependency_key(name, version))
+                .filter(|dependency| resolution.tree.contains_key(dependency))
+                .collect();
+
+            graph.edges.insert(key.clone(), dependencies);
+        }
+
+        graph
+    }
+
+    /// Keep the packages at most `depth` levels of transitive dependencies below the
+    /// root (`0` for its direct dependencies only).
+    pub fn limit_depth(&mut self, depth: usize) {
+        let mut kept = BTreeSet::new();
+        let mut queue = VecDeque::from([(self.root.clone(), 0)]);
+
+        while let Some((key, level)) = queue.pop_front() {
+            for dependency in self.edges.get(&key).into_iter().flatten() {
+                if level <= depth && kept.insert(dependency.clone()) {
+                    queue.push_back((dependency.clone(), level + 1));
+                }
+            }
+        }
+
+        self.retain(&kept);
+    }
+
+    /// Keep the packages named `name`, what they depend on and what depends on them.
+    pub fn focus(&mut self, name: &str) {
+        let focused: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|key| key_name(key) == name)
+            .cloned()
+            .collect();
+
+        let mut dependents: BTreeMap<&String, Vec<&String>> = BTreeMap::new();
+
+        for (dependent, dependencies) in &self.edges {
+            for dependency in dependencies {
+                dependents.entry(dependency).or_default().push(dependent);
+            }
+        }
+
+        let walk = |next: &dyn Fn(&String) -> Vec<String>| {
+            let mut seen: BTreeSet<String> = focused.iter().cloned().collect();
+            let mut stack = focused.clone();
+
+            while let Some(key) = stack.pop() {
+                for other in next(&key) {
+                    if seen.insert(other.clone()) {
+                        stack.push(other);
+                    }
+                }
+            }
+
+            seen
+        };
+
+        let below = walk(&|key| self.edges.get(key).into_iter().flatten().cloned().collect());
+        let above = walk(&|key| {
+            dependents
+                .get(key)
+                .into_iter()
+                .flatten()
+                .map(|dependent| (*dependent).clone())
+                .collect()
+        });
+
+        let kept = below.union(&above).cloned().collect();
+
+        self.retain(&kept);
+    }
+
+    fn retain(&mut self, kept: &BTreeSet<String>) {
+        let root = self.root.clone();
+
+        self.nodes.retain(|key| kept.contains(key));
+        self.edges
+            .retain(|dependent, _| *dependent == root || kept.contains(dependent));
+
+        for dependencies in self.edges.values_mut() {
+            dependencies.retain(|dependency| kept.contains(dependency));
+        }
+    }
+
+    /// Every edge, dependents in order.
+    fn edge_list(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.edges.iter().flat_map(|(dependent, dependencies)| {
+            dependencies
+                .iter()
+                .map(move |dependency| (dependent, dependency))
+        })
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n    node [shape=box];\n");
+
+        dot.push_str(&format!("    {:?} [style=bold];\n", self.root));
+
+        for key in &self.nodes {
+            dot.push_str(&format!("    {:?};\n", key));
+        }
+
+        for (dependent, dependency) in self.edge_list() {
+            dot.push_str(&format!("    {:?} -> {:?};\n", dependent, dependency));
+        }
+
+        dot.push_str("}\n");
+
+        dot
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        // mermaid ids can't hold `@` or `/`, so nodes are numbered and labelled
+        let ids: BTreeMap<&String, String> = std::iter::once(&self.root)
+            .chain(&self.nodes)
+            .enumerate()
+            .map(|(index, key)| (key, format!("n{}", index)))
+            .collect();
+
+        let mut mermaid = String::from("graph TD\n");
+
+        for (key, id) in &ids {
+            mermaid.push_str(&format!("    {}[\"{}\"]\n", id, key.replace('"', "#quot;")));
+        }
+
+        for (dependent, dependency) in self.edge_list() {
+            mermaid.push_str(&format!("    {} --> {}\n", ids[dependent], ids[dependency]));
+        }
+
+        mermaid
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "root": self.root,
+            "nodes": self
+                .nodes
+                .iter()
+                .map(|key| {
+                    let name = key_name(key);
+
+                    json!({ "id": key, "name": name, "version": &key[name.len() + 1..] })
+                })
+                .collect::<Vec<_>>(),
+            "edges": self
+                .edge_list()
+                .map(|(from, to)| json!({ "from": from, "to": to }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// The name of a `name@version` key, scoped names included.
+fn key_name(key: &str) -> &str {
+    match key[1..].find('@') {
+        Some(index) => &key[..index + 1],
+        None => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DependencyGraph;
+
+    use std::collections::BTreeSet;
+
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph {
+            root: "app".to_string(),
+            ..DependencyGraph::default()
+        };
+
+        for (key, dependencies) in [
+            ("app", vec!["a@1.0.0", "@s/b@2.0.0"]),
+            ("a@1.0.0", vec!["c@1.0.0"]),
+            ("@s/b@2.0.0", vec![]),
+            ("c@1.0.0", vec!["d@1.0.0"]),
+            ("d@1.0.0", vec![]),
+        ] {
+            if key != "app" {
+                graph.nodes.insert(key.to_string());
+            }
+
+            graph.edges.insert(
+                key.to_string(),
+                dependencies.into_iter().map(String::from).collect(),
+            );
+        }
+
+        graph
+    }
+
+    #[test]
+    fn filters_and_prints_graphs() {
+        let mut shallow = graph();
+        shallow.limit_depth(0);
+        assert_eq!(
+            shallow.nodes,
+            BTreeSet::from(["a@1.0.0".to_string(), "@s/b@2.0.0".to_string()])
+        );
+
+        let mut focused = graph();
+        focused.focus("c");
+        assert_eq!(
+            focused.nodes,
+            BTreeSet::from([
+                "a@1.0.0".to_string(),
+                "c@1.0.0".to_string(),
+                "d@1.0.0".to_string()
+            ])
+        );
+        assert_eq!(focused.edges["app"].len(), 1);
+
+        shallow.limit_depth(0);
+        assert_eq!(
+            shallow.to_dot(),
+            "digraph dependencies {\n    node [shape=box];\n    \"app\" [style=bold];\n    \"@s/b@2.0.0\";\n    \"a@1.0.0\";\n    \"app\" -> \"@s/b@2.0.0\";\n    \"app\" -> \"a@1.0.0\";\n}\n"
+        );
+        assert!(shallow.to_mermaid().contains("n0 --> n1\n"));
+        assert_eq!(shallow.to_json()["nodes"][0]["name"], "@s/b");
+    }
+}
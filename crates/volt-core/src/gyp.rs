@@ -0,0 +1,297 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Build native addons with node-gyp.
+//!
+//! Like npm, a package with a `binding.gyp` and no `install` or `preinstall` script is
+//! built with `node-gyp rebuild`. The node-gyp that runs is the one of the `node-gyp`
+//! setting, of the project, of the npm bundled with node, or else the one on `PATH`, and
+//! the `python` and `msvs-version` settings are passed on to it.
+//!
+//! The headers of the node version the scripts run with are downloaded once into
+//! `~/.volt/node-headers/<version>` and given to node-gyp as its `nodedir`, so projects
+//! pinning different node versions each build against their own.
+
+use crate::{
+    config::VoltConfig,
+    toolchain::{install_headers, Pin, Toolchain},
+    utils::{errors::FilesystemError, find_executable},
+};
+
+use miette::Result;
+use node_semver::Version;
+
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The `install` script of packages with a `binding.gyp` and no install script of their
+/// own.
+pub const IMPLICIT_INSTALL: &str = "node-gyp rebuild";
+
+/// Where node-gyp is in a node-gyp package.
+const SCRIPT: &str = "bin/node-gyp.js";
+
+/// Whether `directory` holds a native addon node-gyp builds.
+pub fn has_binding(directory: &Path) -> bool {
+    directory.join("binding.gyp").is_file()
+}
+
+/// Whether a script runs node-gyp.
+pub fn uses_node_gyp(script: &str) -> bool {
+    script.contains("node-gyp")
+}
+
+/// The node-gyp scripts run with, and what they build with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeGyp {
+    /// `bin/node-gyp.js` of the node-gyp package
+    pub script: PathBuf,
+    /// Directory of a `node-gyp` executable running [`NodeGyp::script`], put on `PATH`
+    pub bin_dir: PathBuf,
+    pub python: Option<PathBuf>,
+    pub msvs_version: Option<String>,
+    /// The cached headers of the node version scripts run with
+    pub nodedir: Option<PathBuf>,
+}
+
+impl NodeGyp {
+    /// Find node-gyp and python, `None` if node-gyp isn't anywhere volt looks.
+    pub async fn discover(config: &VoltConfig) -> Result<Option<Self>> {
+        let settings = config.settings();
+
+        let script = match &settings.node_gyp {
+            Some(path) => Some(path.clone()),
+            None => bundled_scripts(config)?
+                .into_iter()
+                .find(|path| path.is_file()),
+        };
+
+        let script = match script {
+            Some(script) => script,
+            None => return Ok(None),
+        };
+
+        tracing::debug!("building native addons with {}", script.display());
+
+        let python = settings
+            .python
+            .clone()
+            // node-gyp looks for python itself when `PYTHON` is set
+            .or_else(|| match env::var_os("PYTHON") {
+                Some(_) => None,
+                None => find_executable("python3").or_else(|| find_executable("python")),
+            });
+
+        // a `nodedir` of the user's own comes first
+        let nodedir = match (env::var_os("npm_config_nodedir"), node_version(config)) {
+            (None, Some(version)) => {
+                let toolchain = Toolchain::from_config(config)?;
+
+                match install_headers(config, &toolchain, &version).await {
+                    Ok(nodedir) => Some(nodedir),
+                    Err(error) => {
+                        tracing::warn!(
+                            "failed to download the headers of node {}, node-gyp will try itself: {}",
+                            version,
+                            error
+                        );
+
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        Ok(Some(Self {
+            script,
+            bin_dir: write_shim(config)?,
+            python,
+            msvs_version: settings.msvs_version.clone(),
+            nodedir,
+        }))
+    }
+
+    /// The variables node-gyp reads its configuration from.
+    pub fn env(&self) -> Vec<(&'static str, OsString)> {
+        let mut env = vec![("npm_config_node_gyp", self.script.clone().into_os_string())];
+
+        if let Some(python) = &self.python {
+            env.push(("npm_config_python", python.clone().into_os_string()));
+        }
+
+        if let Some(version) = &self.msvs_version {
+            env.push(("npm_config_msvs_version", OsString::from(version)));
+        }
+
+        if let Some(nodedir) = &self.nodedir {
+            env.push(("npm_config_nodedir", nodedir.clone().into_os_string()));
+        }
+
+        env
+    }
+}
+
+/// Where node-gyp may be installed, in order: in the project, in the npm of the node
+/// version volt selects, in the npm of the `node` on `PATH`, and the `node-gyp` on `PATH`.
+fn bundled_scripts(config: &VoltConfig) -> Result<Vec<PathBuf>> {
+    let mut scripts = vec![config.node_modules()?.join("node-gyp").join(SCRIPT)];
+
+    let npm_gyp = |prefix: &Path| {
+        let modules = if cfg!(windows) {
+            prefix.join("node_modules")
+        } else {
+            prefix.join("lib").join("node_modules")
+        };
+
+        modules
+            .join("npm")
+            .join("node_modules")
+            .join("node-gyp")
+            .join(SCRIPT)
+    };
+
+    let toolchain = Toolchain::from_config(config)?;
+    let pin = Pin::find(&config.cwd()?).unwrap_or_default();
+
+    if let Ok(version) = toolchain.node_version(&pin, "node-gyp") {
+        scripts.push(npm_gyp(&toolchain.version_dir(&version)));
+    }
+
+    // `<prefix>/bin/node` on unix, `<prefix>\node.exe` on windows
+    let node_prefix = find_executable("node")
+        .and_then(|node| node.canonicalize().ok())
+        .and_then(|node| {
+            let directory = node.parent()?;
+
+            if cfg!(windows) {
+                Some(directory.to_path_buf())
+            } else {
+                directory.parent().map(Path::to_path_buf)
+            }
+        });
+
+    if let Some(prefix) = node_prefix {
+        scripts.push(npm_gyp(&prefix));
+    }
+
+    // a global install links `node-gyp` to its script
+    let global = find_executable("node-gyp")
+        .and_then(|path| path.canonicalize().ok())
+        .filter(|path| path.ends_with(SCRIPT));
+
+    scripts.extend(global);
+
+    Ok(scripts)
+}
+
+/// The version of the `node` scripts run with in the project, which the shims pick from
+/// its pin.
+fn node_version(config: &VoltConfig) -> Option<Version> {
+    let output = Command::new("node")
+        .args(["-p", "process.versions.node"])
+        .current_dir(config.cwd().ok()?)
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Write the `node-gyp` executable that runs `$npm_config_node_gyp`, like npm's
+/// `node-gyp-bin`, and return its directory.
+fn write_shim(config: &VoltConfig) -> Result<PathBuf> {
+    let bin_dir = config.volt_home()?.join("node-gyp-bin");
+
+    let (name, contents) = if cfg!(windows) {
+        ("node-gyp.cmd", "@node \"%npm_config_node_gyp%\" %*\r\n")
+    } else {
+        (
+            "node-gyp",
+            "#!/bin/sh\nexec node \"$npm_config_node_gyp\" \"$@\"\n",
+        )
+    };
+
+    let path = bin_dir.join(name);
+
+    if std::fs::read_to_string(&path).map_or(false, |existing| existing == contents) {
+        return Ok(bin_dir);
+    }
+
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    };
+
+    std::fs::create_dir_all(&bin_dir).map_err(write_error)?;
+    std::fs::write(&path, contents).map_err(write_error)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(write_error)?;
+    }
+
+    Ok(bin_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_binding, uses_node_gyp, NodeGyp};
+
+    use std::{ffi::OsString, path::PathBuf};
+
+    #[test]
+    fn configures_node_gyp() {
+        let directory = tempfile::tempdir().unwrap();
+
+        assert!(!has_binding(directory.path()));
+
+        std::fs::write(directory.path().join("binding.gyp"), "{}").unwrap();
+
+        assert!(has_binding(directory.path()));
+        assert!(uses_node_gyp("node-gyp rebuild --release"));
+        assert!(!uses_node_gyp("node install.js"));
+
+        let node_gyp = NodeGyp {
+            script: PathBuf::from("/gyp/bin/node-gyp.js"),
+            bin_dir: PathBuf::from("/home/.volt/node-gyp-bin"),
+            python: Some(PathBuf::from("/usr/bin/python3")),
+            msvs_version: None,
+            nodedir: Some(PathBuf::from("/home/.volt/node-headers/18.17.0")),
+        };
+
+        assert_eq!(
+            node_gyp.env(),
+            [
+                (
+                    "npm_config_node_gyp",
+                    OsString::from("/gyp/bin/node-gyp.js")
+                ),
+                ("npm_config_python", OsString::from("/usr/bin/python3")),
+                (
+                    "npm_config_nodedir",
+                    OsString::from("/home/.volt/node-headers/18.17.0")
+                ),
+            ]
+        );
+    }
+}
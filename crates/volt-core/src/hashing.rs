@@ -0,0 +1,243 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Integrity hashes of tarballs.
+//!
+//! Registries give the integrity of a tarball as a subresource integrity string
+//! (`sha512-<base64>`, several separated by spaces), and older packages only as the sha1
+//! `shasum`, which volt writes `sha1-<hex>`. Both are read into an [`Integrity`]; data is
+//! hashed once, with every algorithm the expected integrity has, and checked against the
+//! strongest of them.
+
+use crate::utils::errors::IntegrityError;
+
+use miette::Result;
+use ssri::{Hash, IntegrityOpts};
+
+use std::io::{self, Read, Write};
+
+pub use ssri::{Algorithm, Integrity};
+
+/// Hashes data as it is fed, with several algorithms in one pass.
+pub struct Hashing {
+    opts: IntegrityOpts,
+}
+
+impl Hashing {
+    /// A hashing of the `algorithms`; sha512 if there are none.
+    pub fn new(algorithms: &[Algorithm]) -> Self {
+        let mut opts = IntegrityOpts::new();
+
+        for algorithm in algorithms {
+            opts = opts.algorithm(*algorithm);
+        }
+
+        if algorithms.is_empty() {
+            opts = opts.algorithm(Algorithm::Sha512);
+        }
+
+        Self { opts }
+    }
+
+    /// A hashing of the algorithms of `expected`, to compare with it.
+    pub fn matching(expected: &Integrity) -> Self {
+        let algorithms: Vec<Algorithm> =
+            expected.hashes.iter().map(|hash| hash.algorithm).collect();
+
+        Self::new(&algorithms)
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.opts.input(data);
+    }
+
+    /// The digests of everything fed, the strongest first.
+    pub fn finish(self) -> Integrity {
+        self.opts.result()
+    }
+}
+
+impl Write for Hashing {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.update(data);
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The digests of `data` with each of `algorithms`.
+pub fn hash(data: &[u8], algorithms: &[Algorithm]) -> Integrity {
+    let mut hashing = Hashing::new(algorithms);
+
+    hashing.update(data);
+    hashing.finish()
+}
+
+/// The digests of everything `reader` reads, without holding it in memory.
+pub fn hash_reader(reader: &mut impl Read, algorithms: &[Algorithm]) -> io::Result<Integrity> {
+    let mut hashing = Hashing::new(algorithms);
+
+    io::copy(reader, &mut hashing)?;
+
+    Ok(hashing.finish())
+}
+
+/// Read an integrity from a registry or the lock file: subresource integrity, or
+/// `sha1-<hex>`.
+pub fn parse_integrity(integrity: &str) -> Result<Integrity> {
+    if let Some(hex) = integrity.strip_prefix("sha1-") {
+        if hex.len() == 40 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Ok(Integrity::from_hex(hex, Algorithm::Sha1).map_err(|_| malformed(integrity))?);
+        }
+    }
+
+    let parsed: Integrity = integrity.parse().map_err(|_| malformed(integrity))?;
+
+    if parsed.hashes.is_empty() {
+        return Err(malformed(integrity).into());
+    }
+
+    Ok(parsed)
+}
+
+/// The digest of `algorithm` in `integrity` alone, if it has one.
+pub fn select(integrity: &Integrity, algorithm: Algorithm) -> Option<Integrity> {
+    integrity
+        .hashes
+        .iter()
+        .find(|hash| hash.algorithm == algorithm)
+        .map(|hash| Integrity {
+            hashes: vec![Hash {
+                algorithm,
+                digest: hash.digest.clone(),
+            }],
+        })
+}
+
+/// Check `data` against the strongest digest of `expected`: `Ok(None)` when it matches,
+/// and what `data` hashes to otherwise.
+pub fn verify(data: &[u8], expected: &str) -> Result<Option<Integrity>> {
+    let expected = parse_integrity(expected)?;
+
+    let mut hashing = Hashing::matching(&expected);
+    hashing.update(data);
+
+    let actual = hashing.finish();
+
+    Ok(strongest_differs(&expected, actual))
+}
+
+/// Like [`verify`], for what `reader` reads.
+pub fn verify_reader(reader: &mut impl Read, expected: &str) -> Result<Option<Integrity>> {
+    let expected = parse_integrity(expected)?;
+
+    let mut hashing = Hashing::matching(&expected);
+
+    io::copy(reader, &mut hashing).map_err(|e| miette::miette!("failed to hash: {}", e))?;
+
+    Ok(strongest_differs(&expected, hashing.finish()))
+}
+
+fn strongest_differs(expected: &Integrity, actual: Integrity) -> Option<Integrity> {
+    let algorithm = expected.pick_algorithm();
+
+    if select(expected, algorithm) == select(&actual, algorithm) {
+        None
+    } else {
+        select(&actual, algorithm)
+    }
+}
+
+fn malformed(integrity: &str) -> IntegrityError {
+    IntegrityError::Malformed {
+        integrity: integrity.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash, hash_reader, parse_integrity, select, verify, Algorithm};
+
+    const DATA: &[u8] = b"volt";
+
+    #[test]
+    fn hashes_every_algorithm_in_one_pass() {
+        let integrity = hash(
+            DATA,
+            &[
+                Algorithm::Sha1,
+                Algorithm::Sha256,
+                Algorithm::Sha384,
+                Algorithm::Sha512,
+            ],
+        );
+
+        assert_eq!(integrity.hashes.len(), 4);
+
+        for algorithm in [
+            Algorithm::Sha1,
+            Algorithm::Sha256,
+            Algorithm::Sha384,
+            Algorithm::Sha512,
+        ] {
+            assert_eq!(
+                select(&integrity, algorithm),
+                Some(hash(DATA, &[algorithm]))
+            );
+        }
+
+        let streamed = hash_reader(&mut &DATA[..], &[Algorithm::Sha256]).unwrap();
+
+        assert_eq!(streamed, hash(DATA, &[Algorithm::Sha256]));
+    }
+
+    #[test]
+    fn reads_sri_and_hex_shasums() {
+        let sha1 = hash(DATA, &[Algorithm::Sha1]);
+        let (_, hex) = sha1.to_hex();
+
+        assert_eq!(parse_integrity(&format!("sha1-{}", hex)).unwrap(), sha1);
+        assert_eq!(parse_integrity(&sha1.to_string()).unwrap(), sha1);
+        assert!(parse_integrity("").is_err());
+        assert!(parse_integrity("md5-nope").is_err());
+    }
+
+    #[test]
+    fn verifies_against_the_strongest_digest() {
+        let sha256 = hash(DATA, &[Algorithm::Sha256]).to_string();
+        let sha384 = hash(DATA, &[Algorithm::Sha384]).to_string();
+
+        assert_eq!(verify(DATA, &sha256).unwrap(), None);
+        assert_eq!(verify(DATA, &sha384).unwrap(), None);
+        assert_eq!(
+            verify(b"other", &sha384).unwrap(),
+            Some(hash(b"other", &[Algorithm::Sha384]))
+        );
+
+        // a wrong weak digest next to a right strong one doesn't matter
+        let both = format!(
+            "{} sha1-{}",
+            sha384,
+            hash(b"other", &[Algorithm::Sha1]).to_hex().1
+        );
+
+        assert_eq!(verify(DATA, &both).unwrap(), None);
+    }
+}
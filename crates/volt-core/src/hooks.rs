@@ -0,0 +1,217 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Commands run at the steps of an install, to enforce policies such as packages that
+//! must never be installed.
+//!
+//! Each hook is a shell command set in `.voltrc` (or `~/.volt/config.toml`):
+//!
+//! ```toml
+//! # given the requested packages, before they are resolved
+//! pre-resolve-hook = "node scripts/check-requests.js"
+//! # given the resolved graph, every time packages are resolved (not when volt.lock has them)
+//! post-resolve-hook = "volt-policy resolved"
+//! # given the graph of every package an install is about to download
+//! pre-extract-hook = "node scripts/block-packages.js"
+//! # given the installed graph
+//! post-install-hook = "node scripts/report.js"
+//! ```
+//!
+//! The command runs in the project like a script, with `~/.volt/plugins` on `PATH` too,
+//! and reads a JSON document on stdin: the `hook`, the project's `cwd` and either the
+//! `requested` packages or the graph, the `packages` by `name@version` with the `direct`
+//! ones and the `aliases`. Exiting with an error stops the install; what the command
+//! prints is rendered like the output of plugins.
+
+use crate::{
+    config::VoltConfig,
+    install::Resolution,
+    lifecycle::{command_env, read_manifest},
+    plugin::{run_with_input, PROTOCOL_VERSION},
+    utils::errors::ScriptError,
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde_json::{json, Value};
+
+use std::{collections::BTreeMap, process::Command};
+
+/// The steps of an install hooks run at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreResolve,
+    PostResolve,
+    PreExtract,
+    PostInstall,
+}
+
+impl Hook {
+    /// The name of the hook, which its setting is named after (`<name>-hook`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PreResolve => "pre-resolve",
+            Self::PostResolve => "post-resolve",
+            Self::PreExtract => "pre-extract",
+            Self::PostInstall => "post-install",
+        }
+    }
+
+    fn command(self, config: &VoltConfig) -> Option<&str> {
+        let settings = config.settings();
+
+        match self {
+            Self::PreResolve => settings.pre_resolve_hook.as_deref(),
+            Self::PostResolve => settings.post_resolve_hook.as_deref(),
+            Self::PreExtract => settings.pre_extract_hook.as_deref(),
+            Self::PostInstall => settings.post_install_hook.as_deref(),
+        }
+    }
+}
+
+/// Run the command set for `hook`, if any, with `data` (an object) and the hook's name
+/// and project on stdin, failing if it does.
+pub fn run_hook(config: &VoltConfig, hook: Hook, data: Value) -> Result<()> {
+    let script = match hook.command(config) {
+        Some(script) => script,
+        None => return Ok(()),
+    };
+
+    let cwd = config.cwd()?;
+
+    let mut input = json!({
+        "protocol": PROTOCOL_VERSION,
+        "hook": hook.name(),
+        "cwd": cwd,
+    });
+
+    if let (Some(input), Value::Object(data)) = (input.as_object_mut(), data) {
+        input.extend(data);
+    }
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/d", "/s", "/c", script]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    };
+
+    let package_json = read_manifest(&cwd).unwrap_or(Value::Null);
+    let plugins = config.volt_home()?.join("plugins");
+
+    command
+        .current_dir(&cwd)
+        .envs(command_env(config, &cwd, &package_json, &[plugins])?)
+        .env("VOLT_HOOK", hook.name());
+
+    tracing::debug!("running the {} hook `{}`", hook.name(), script);
+
+    let input = serde_json::to_vec(&input).into_diagnostic()?;
+
+    let status =
+        run_with_input(config, &mut command, input).map_err(|e| ScriptError::CommandSpawn {
+            source: e,
+            command: script.to_string(),
+        })?;
+
+    if !status.success() {
+        return Err(ScriptError::Hook {
+            hook: hook.name().to_string(),
+            command: script.to_string(),
+            code: status.code().unwrap_or(-1),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// The graph of `resolution` given to hooks: its `packages` by key, the `direct` ones and
+/// the `aliases`.
+pub fn resolution_graph(resolution: &Resolution) -> Value {
+    let mut direct = resolution.direct.clone();
+    direct.sort();
+
+    json!({
+        "packages": resolution.tree.iter().collect::<BTreeMap<_, _>>(),
+        "direct": direct,
+        "aliases": resolution.aliases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolution_graph, Hook};
+    use crate::{install::Resolution, settings::KEYS};
+
+    use serde_json::json;
+
+    #[test]
+    fn describes_resolutions_to_hooks() {
+        for hook in [
+            Hook::PreResolve,
+            Hook::PostResolve,
+            Hook::PreExtract,
+            Hook::PostInstall,
+        ] {
+            assert!(KEYS.contains(&format!("{}-hook", hook.name()).as_str()));
+        }
+
+        let mut resolution = Resolution::default();
+
+        for (name, version) in [("send", "0.17.2"), ("ms", "2.1.3")] {
+            let package = serde_json::from_value(json!({
+                "name": name,
+                "version": version,
+                "optional": false,
+                "integrity": "",
+                "tarball": format!("https://registry.npmjs.org/{0}/-/{0}-{1}.tgz", name, version),
+                "bin": null,
+                "scripts": null,
+                "dependencies": null,
+                "peer_dependencies": null,
+                "peer_dependencies_meta": null,
+                "optional_dependencies": null,
+                "overrides": null,
+                "engines": null,
+                "os": null,
+                "cpu": null,
+            }))
+            .unwrap();
+
+            resolution
+                .tree
+                .insert(format!("{}@{}", name, version), package);
+        }
+
+        resolution.direct.push("send@0.17.2".to_string());
+
+        let graph = resolution_graph(&resolution);
+
+        assert_eq!(graph["direct"], json!(["send@0.17.2"]));
+        assert_eq!(graph["packages"]["ms@2.1.3"]["version"], "2.1.3");
+        assert_eq!(
+            graph["packages"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["ms@2.1.3", "send@0.17.2"]
+        );
+    }
+}
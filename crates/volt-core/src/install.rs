@@ -0,0 +1,1284 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Resolve, download and link packages into `node_modules`.
+//!
+//! Shared by every command that installs packages (`volt add`, `volt install`, ...).
+
+use crate::{
+    cache::record_project,
+    config::VoltConfig,
+    engines::{engine_ranges, Runtime},
+    git::{clone_url, resolve_git},
+    hooks::{resolution_graph, run_hook, Hook},
+    install_state::{InstallDiff, InstallState},
+    lifecycle::{allowed_scripts, run_dependency_scripts, run_root_scripts},
+    linker::{linker, remove_path, Linker, NodeLinker},
+    local::{resolve_file, resolve_link},
+    lock::{lock_project, PROJECT_LOCK},
+    model::lock_file::LockFile,
+    net::{fetch_dep_tree, resolve_remote},
+    patches::{apply_patches, read_patches},
+    paths::{long_path, symlink_dir},
+    peer::{check_peers, PeerWarning},
+    pipeline::{install_packages, Job},
+    platform::Platform,
+    release_age::check_release_age,
+    reporter::Reporter,
+    signatures::verify_signatures,
+    summary::Phase,
+    transaction::Transaction,
+    utils::{
+        damaged_files,
+        errors::{FilesystemError, ResolutionError},
+        link_package_bins,
+        package::PackageJson,
+        voltapi::{dependency_key, dependency_name, VoltPackage},
+        State,
+    },
+    workspace::{Filter, Workspace},
+};
+
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde_json::json;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+/// Which dependencies of package.json are installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallScope {
+    /// `dependencies` and `devDependencies`
+    All,
+    /// Only `dependencies` (`--production`)
+    Production,
+    /// Only `devDependencies` (`--dev-only`)
+    Development,
+}
+
+impl InstallScope {
+    /// The scope of the `--production` and `--dev-only` flags.
+    pub fn from_flags(production: bool, dev_only: bool) -> Self {
+        match (production, dev_only) {
+            (true, _) => Self::Production,
+            (_, true) => Self::Development,
+            _ => Self::All,
+        }
+    }
+}
+
+/// The flattened dependency tree of a set of requested packages.
+#[derive(Debug, Default)]
+pub struct Resolution {
+    /// Every package to install, keyed by `name@version`
+    pub tree: HashMap<String, VoltPackage>,
+    /// Keys of the packages that were requested directly
+    pub direct: Vec<String>,
+    /// Packages requested under another name (`"foo": "npm:bar@^2"`): name -> key
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Resolution {
+    /// Resolve `name -> range` dependencies from the lock file alone, or `None` if
+    /// anything they need isn't locked.
+    pub fn from_lock_file(
+        lock_file: &LockFile,
+        dependencies: &BTreeMap<String, String>,
+    ) -> Option<Self> {
+        let mut resolution = Self::default();
+        let mut stack = vec![];
+
+        for (name, range) in dependencies {
+            let package = lock_file.find(name, range)?;
+            let key = package.key();
+
+            if package.name == *name {
+                resolution.direct.push(key.clone());
+            } else {
+                resolution.aliases.insert(name.clone(), key.clone());
+            }
+
+            stack.push(key);
+        }
+
+        while let Some(key) = stack.pop() {
+            if resolution.tree.contains_key(&key) {
+                continue;
+            }
+
+            let package = lock_file.dependencies.get(&key)?;
+
+            for (name, version) in &package.dependencies {
+                stack.push(dependency_key(name, version));
+            }
+
+            // optional dependencies may have been skipped on the platform that locked them
+            for (name, version) in &package.optional_dependencies {
+                if lock_file.get(name, version).is_some() {
+                    stack.push(dependency_key(name, version));
+                }
+            }
+
+            resolution.tree.insert(key, package.to_package());
+        }
+
+        Some(resolution)
+    }
+
+    /// The part of the resolution that `dependencies` (`name -> range`, a subset of the
+    /// ones resolved) reach, such as the production dependencies of a project and what
+    /// they depend on. Packages shared with the rest stay in.
+    pub fn scoped(mut self, dependencies: &BTreeMap<String, String>) -> Self {
+        let mut lock_file = LockFile::default();
+        lock_file.extend(&self.tree);
+
+        let scoped = match Self::from_lock_file(&lock_file, dependencies) {
+            Some(scoped) => scoped,
+            None => return self,
+        };
+
+        self.tree.retain(|key, _| scoped.tree.contains_key(key));
+
+        Self {
+            tree: self.tree,
+            direct: scoped.direct,
+            aliases: scoped.aliases,
+        }
+    }
+
+    /// Add the packages of another resolution, keeping the requested ones requested.
+    pub fn merge(&mut self, other: Resolution) {
+        self.tree.extend(other.tree);
+        self.aliases.extend(other.aliases);
+
+        for key in other.direct {
+            if !self.direct.contains(&key) {
+                self.direct.push(key);
+            }
+        }
+    }
+
+    /// Add the packages of another resolution, requesting them as `name`.
+    fn merge_as(&mut self, name: &str, mut other: Resolution) {
+        for key in std::mem::take(&mut other.direct) {
+            match other.tree.get(&key) {
+                Some(package) if package.name != name => {
+                    other.aliases.insert(name.to_string(), key);
+                }
+                _ => other.direct.push(key),
+            }
+        }
+
+        self.merge(other);
+    }
+
+    /// The directly requested packages.
+    pub fn direct_packages(&self) -> impl Iterator<Item = &VoltPackage> {
+        self.direct.iter().filter_map(|key| self.tree.get(key))
+    }
+
+    /// The keys of the packages requested by the project, directly or under another name,
+    /// in a stable order.
+    pub fn requested(&self) -> BTreeSet<&String> {
+        self.direct.iter().chain(self.aliases.values()).collect()
+    }
+
+    /// The ranges package.json should save for the requested packages: the version after
+    /// `prefix` (`^`, `~` or nothing) for registry packages (`npm:name@^version` for
+    /// aliases), and the source of the others.
+    pub fn saved_ranges(&self, prefix: &str) -> BTreeMap<String, String> {
+        let range = |package: &VoltPackage| {
+            if package.is_registry() {
+                format!("{}{}", prefix, package.version)
+            } else {
+                package.tarball.clone()
+            }
+        };
+
+        let aliases = self.aliases.iter().filter_map(|(alias, key)| {
+            let package = self.tree.get(key)?;
+
+            Some((
+                alias.clone(),
+                format!("npm:{}@{}", package.name, range(package)),
+            ))
+        });
+
+        self.direct_packages()
+            .map(|package| (package.name.clone(), range(package)))
+            .chain(aliases)
+            .collect()
+    }
+
+    /// The exact versions of the requested registry packages, the way package.json saves
+    /// them (`npm:name@version` for aliases).
+    pub fn pinned_ranges(&self) -> BTreeMap<String, String> {
+        let registry: HashSet<&String> = self
+            .direct_packages()
+            .filter(|package| package.is_registry())
+            .map(|package| &package.name)
+            .chain(self.aliases.iter().filter_map(|(alias, key)| {
+                self.tree
+                    .get(key)
+                    .filter(|package| package.is_registry())
+                    .map(|_| alias)
+            }))
+            .collect();
+
+        self.saved_ranges("")
+            .into_iter()
+            .filter(|(name, _)| registry.contains(name))
+            .collect()
+    }
+
+    /// The requested packages as the dependencies of a package: `name -> version`, or
+    /// `alias -> npm:name@version` for aliases.
+    pub fn edges(&self) -> HashMap<String, String> {
+        let aliases = self.aliases.iter().filter_map(|(alias, key)| {
+            let package = self.tree.get(key)?;
+
+            Some((
+                alias.clone(),
+                format!("npm:{}@{}", package.name, package.resolved_version()),
+            ))
+        });
+
+        self.direct_packages()
+            .map(|package| (package.name.clone(), package.resolved_version()))
+            .chain(aliases)
+            .collect()
+    }
+}
+
+/// Resolve `packages` into a flattened dependency tree: registry packages come
+/// pre-flattened from the registry, the others are fetched and read one by one.
+#[tracing::instrument(level = "debug", skip_all, fields(packages = packages.len()))]
+pub async fn resolve(config: &VoltConfig, packages: &[PackageSpec]) -> Result<Resolution> {
+    let mut resolution = Resolution::default();
+
+    if packages.is_empty() {
+        return Ok(resolution);
+    }
+
+    run_hook(
+        config,
+        Hook::PreResolve,
+        json!({ "requested": packages.iter().map(ToString::to_string).collect::<Vec<_>>() }),
+    )?;
+
+    let mut registry = vec![];
+    // (name to request the package as, specification)
+    let mut others = vec![];
+
+    for spec in packages {
+        match spec {
+            PackageSpec::Npm { .. } => registry.push(spec.clone()),
+            PackageSpec::Alias { name, spec: target } => others.push((Some(name), &**target)),
+            _ => others.push((None, spec)),
+        }
+    }
+
+    let resolve_start = Instant::now();
+
+    let reporter = config.reporter();
+
+    if !registry.is_empty() {
+        let resolved = resolve_registry(config, &registry, &*reporter).await?;
+
+        check_release_age(config, &resolved).await?;
+
+        resolution.merge(resolved);
+    }
+
+    for (name, spec) in others {
+        reporter.resolving(&spec.to_string());
+
+        let other = match spec {
+            PackageSpec::Npm { .. } => {
+                let resolved =
+                    resolve_registry(config, std::slice::from_ref(spec), &*reporter).await?;
+
+                check_release_age(config, &resolved).await?;
+
+                resolved
+            }
+            PackageSpec::Git(info) => {
+                tracing::info!("Cloning {}", clone_url(info));
+
+                resolve_git(config, info).await?
+            }
+            PackageSpec::Dir { path } => resolve_file(config, path).await?,
+            PackageSpec::Link { path } => resolve_link(config, path)?,
+            PackageSpec::Remote { url } => resolve_remote(config, url.as_str()).await?,
+            // aliases of aliases can't be written
+            PackageSpec::Alias { .. } => continue,
+        };
+
+        reporter.resolved(other.tree.len());
+
+        match name {
+            Some(name) => resolution.merge_as(name, other),
+            None => resolution.merge(other),
+        }
+    }
+
+    reporter.finish();
+
+    reporter.done("Resolved", resolution.tree.len(), resolve_start.elapsed());
+
+    config.stats().time(Phase::Resolve, resolve_start.elapsed());
+
+    run_hook(config, Hook::PostResolve, resolution_graph(&resolution))?;
+
+    Ok(resolution)
+}
+
+/// The part of volt.lock installed for `dependencies`, failing if it's missing or doesn't
+/// match them.
+pub fn locked_resolution(
+    config: &VoltConfig,
+    dependencies: &BTreeMap<String, String>,
+) -> Result<Resolution> {
+    let lock_path = config.lockfile()?;
+
+    if !lock_path.exists() {
+        return Err(ResolutionError::LockFileMissing {
+            path: lock_path.display().to_string(),
+        }
+        .into());
+    }
+
+    let lock_file = LockFile::load(&lock_path, false).into_diagnostic()?;
+
+    Resolution::from_lock_file(&lock_file, dependencies).ok_or_else(|| {
+        ResolutionError::LockFileOutdated {
+            path: lock_path.display().to_string(),
+        }
+        .into()
+    })
+}
+
+/// Fetch the pre-flattened dependency trees of registry packages from the
+/// [`PackageSource`](crate::source::PackageSource) of `config`.
+async fn resolve_registry(
+    config: &VoltConfig,
+    packages: &[PackageSpec],
+    reporter: &dyn Reporter,
+) -> Result<Resolution> {
+    let mut resolution = Resolution::default();
+
+    for response in fetch_dep_tree(config, packages, reporter).await? {
+        resolution
+            .direct
+            .push(format!("{}@{}", response.name, response.version));
+
+        reporter.resolved(response.tree.len());
+
+        resolution.tree.extend(response.tree);
+    }
+
+    Ok(resolution)
+}
+
+/// Parse `name -> specifier` dependencies (as written in package.json) into package
+/// specifications, skipping the ones that can't be parsed.
+pub fn dependency_specs(dependencies: &BTreeMap<String, String>) -> Vec<PackageSpec> {
+    dependencies
+        .iter()
+        .filter_map(|(name, specifier)| {
+            match format!("{}@{}", name, specifier).parse::<PackageSpec>() {
+                Ok(spec) => Some(spec),
+                Err(_) => {
+                    tracing::warn!("skipping {}@{}, the specifier is invalid", name, specifier);
+
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Add the peer dependencies nothing in the tree provides as direct packages, taking
+/// them from the lock file when it has them (`--install-peers`).
+///
+/// With `frozen` set, peers missing from the lock file are an error instead of being
+/// fetched from the registry.
+pub async fn resolve_peers(
+    config: &VoltConfig,
+    resolution: &mut Resolution,
+    lock_file: &LockFile,
+    frozen: bool,
+) -> Result<()> {
+    // peers can have peers of their own
+    loop {
+        let missing: BTreeMap<String, String> = check_peers(resolution)
+            .into_iter()
+            .filter_map(|warning| match warning {
+                PeerWarning::Missing { name, range, .. } => Some((name, range)),
+                _ => None,
+            })
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let peers = match Resolution::from_lock_file(lock_file, &missing) {
+            Some(peers) => peers,
+            None if frozen => {
+                return Err(ResolutionError::LockFileOutdated {
+                    path: lock_file.path.display().to_string(),
+                }
+                .into())
+            }
+            None => {
+                let specs = missing
+                    .iter()
+                    .map(|(name, range)| format!("{}@{}", name, range).parse::<PackageSpec>())
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .into_diagnostic()?;
+
+                resolve(config, &specs).await?
+            }
+        };
+
+        // nothing new was found, avoid asking again forever
+        if peers
+            .direct
+            .iter()
+            .all(|key| resolution.direct.contains(key))
+        {
+            return Ok(());
+        }
+
+        resolution.merge(peers);
+    }
+}
+
+/// Install every package of a resolved tree into `node_modules`, laid out by the configured
+/// [`Linker`], and run lifecycle scripts.
+pub async fn install(config: &VoltConfig, resolution: Resolution) -> Result<()> {
+    install_into(config, resolution, false).await
+}
+
+/// Install like [`install`], into an emptied `node_modules`: whatever it held is removed
+/// once the project is locked, so no other install is running.
+pub async fn clean_install(config: &VoltConfig, resolution: Resolution) -> Result<()> {
+    install_into(config, resolution, true).await
+}
+
+#[tracing::instrument(level = "debug", skip_all, fields(packages = resolution.tree.len()))]
+async fn install_into(config: &VoltConfig, mut resolution: Resolution, clean: bool) -> Result<()> {
+    let install_start = Instant::now();
+
+    let node_modules = config.node_modules()?;
+
+    let platform = Platform::current();
+
+    let reporter = config.reporter();
+
+    let runtime = Runtime::default();
+
+    check_project_engines(config, &runtime, reporter.as_ref())?;
+
+    let mut skipped = check_engines(config, &runtime, &resolution, reporter.as_ref())?;
+
+    for (key, package) in resolution.tree.iter() {
+        if platform.supports(
+            package.os.as_deref(),
+            package.cpu.as_deref(),
+            package.libc.as_deref(),
+        ) {
+            continue;
+        }
+
+        // optional packages such as `fsevents` are simply left out elsewhere
+        if !package.optional {
+            let list = |values: &Option<Vec<String>>| {
+                values
+                    .as_ref()
+                    .map_or_else(|| "any".to_string(), |values| values.join(", "))
+            };
+
+            return Err(ResolutionError::UnsupportedPlatform {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                platform: format!(
+                    "{} {}{}",
+                    platform.os,
+                    platform.cpu,
+                    platform
+                        .libc
+                        .map(|libc| format!(" {}", libc))
+                        .unwrap_or_default()
+                ),
+                os: list(&package.os),
+                cpu: list(&package.cpu),
+                libc: list(&package.libc),
+            }
+            .into());
+        }
+
+        skipped.push(key.clone());
+    }
+
+    skipped.sort();
+    skipped.dedup();
+
+    remove_packages(&mut resolution, &skipped, &node_modules)?;
+
+    run_hook(config, Hook::PreExtract, resolution_graph(&resolution))?;
+
+    // before anything is downloaded, so a tampered package never reaches node_modules
+    let signatures = verify_signatures(config, &resolution).await?;
+
+    // held until the install is done
+    let _locks = lock_project(config).await?;
+
+    if clean {
+        empty_node_modules(&node_modules)?;
+    }
+
+    // what the last install left, read before node_modules changes
+    let previous = InstallState::read(config)
+        .map(|state| state.packages)
+        .unwrap_or_default();
+
+    // written again once node_modules is complete
+    InstallState::clear(config)?;
+
+    // rolls back what an interrupted install left before anything else changes
+    let transaction = Arc::new(Transaction::begin(&node_modules)?);
+
+    let linker = linker(config, &resolution)?;
+
+    let diff = InstallDiff::between(&previous, &store_keys(&resolution));
+
+    let patches = read_patches(config)?;
+
+    // the isolated layout puts a package at the same place whatever else is installed, so
+    // the ones the last install left are kept, unless their files were damaged since or
+    // they're patched
+    let incremental = config.settings().node_linker == NodeLinker::Isolated;
+
+    let unchanged: Vec<(&String, &VoltPackage, Vec<PathBuf>)> = diff
+        .unchanged
+        .iter()
+        .filter(|key| incremental && !patches.contains_key(*key))
+        .map(|key| (key, &resolution.tree[key], linker.directories(key)))
+        .collect();
+
+    let checked: Vec<(String, Option<String>)> = unchanged
+        .into_par_iter()
+        .map(|(key, package, directories)| {
+            let damage = directories.iter().find_map(|directory| {
+                damaged_files(config, package, directory, config.check_files)
+            });
+
+            (key.clone(), damage)
+        })
+        .collect();
+
+    let mut kept = HashSet::new();
+    let mut repaired = vec![];
+
+    for (key, damage) in checked {
+        match damage {
+            Some(damage) => {
+                tracing::debug!("extracting {} again: {}", key, damage);
+
+                repaired.push(key);
+            }
+            None => {
+                kept.insert(key);
+            }
+        }
+    }
+
+    if !repaired.is_empty() {
+        repaired.sort();
+
+        reporter.warning(&format!(
+            "extracting {} again, files in node_modules were missing or modified",
+            repaired.join(", ")
+        ));
+    }
+
+    tracing::debug_span!("prepare").in_scope(|| linker.prepare(&resolution))?;
+
+    let client = config.http_client()?;
+
+    reporter.installing(
+        resolution
+            .tree
+            .iter()
+            .filter(|(key, package)| !package.is_link() && !kept.contains(*key))
+            .count(),
+    );
+
+    // extracted aside and moved into place whole
+    let jobs = resolution
+        .tree
+        .iter()
+        .filter(|(key, package)| !package.is_link() && !kept.contains(*key))
+        .map(|(key, package)| Job {
+            key: key.clone(),
+            package: package.clone(),
+            targets: linker.directories(key),
+        })
+        .collect();
+
+    let state = State {
+        http_client: client,
+        reporter: reporter.clone(),
+    };
+
+    // downloaded, verified and extracted in overlapping stages
+    let results = install_packages(config, jobs, transaction.clone(), &state).await;
+
+    reporter.finish();
+
+    let mut failed = vec![];
+
+    for (key, result) in results {
+        if let Err(error) = result {
+            // a failed optional package doesn't fail the install
+            if !resolution.tree[&key].optional {
+                return Err(error);
+            }
+
+            reporter.warning(&format!("skipping optional dependency {}: {}", key, error));
+
+            failed.push(key);
+        }
+    }
+
+    for key in &failed {
+        for directory in linker.directories(key) {
+            transaction.remove(&directory)?;
+        }
+    }
+
+    if incremental {
+        // node_modules/.volt/@scope+name@1.0.0
+        for key in &diff.stale {
+            transaction.remove(&node_modules.join(".volt").join(key.replace('/', "+")))?;
+        }
+
+        remove_dangling_links(&node_modules)?;
+        remove_dangling_links(&node_modules.join(".volt").join("node_modules"))?;
+    }
+
+    remove_packages(&mut resolution, &failed, &node_modules)?;
+
+    // every package is in place, an interrupted link or script step is simply redone
+    Arc::try_unwrap(transaction)
+        .map_err(|_| miette::miette!("packages are still being installed"))?
+        .finish()?;
+
+    let unused = apply_patches(&resolution, linker.as_ref(), &patches)?;
+
+    if !unused.is_empty() {
+        reporter.warning(&format!(
+            "no package installed for the patches of {}",
+            unused.join(", ")
+        ));
+    }
+
+    let total = resolution.tree.len();
+
+    let link_start = Instant::now();
+
+    tracing::debug_span!("link").in_scope(|| -> Result<()> {
+        // make the packages resolvable from each other and the requested ones from the
+        // project
+        linker.link(&resolution)?;
+
+        link_executables(config, &resolution, linker.as_ref())
+    })?;
+
+    config.stats().time(Phase::Link, link_start.elapsed());
+
+    let scripts_start = Instant::now();
+
+    // run lifecycle scripts now that every package has been extracted and linked, the
+    // kept ones were built when they were
+    let mut allowed = allowed_scripts(config, &resolution.tree, linker.as_ref())?;
+
+    allowed.retain(|key| !kept.contains(key));
+
+    run_dependency_scripts(config, &resolution.tree, &allowed, linker.as_ref()).await?;
+    run_root_scripts(config)?;
+
+    config.stats().time(Phase::Scripts, scripts_start.elapsed());
+
+    // so `volt store gc` knows what the project still uses
+    record_project(config, &resolution).await?;
+
+    // so the next install knows what it changes
+    InstallState::record_packages(config, store_keys(&resolution))?;
+
+    run_hook(config, Hook::PostInstall, resolution_graph(&resolution))?;
+
+    reporter.done("Installed", total, install_start.elapsed());
+
+    if !diff.is_empty() {
+        reporter.changes(&diff);
+    }
+
+    reporter.summary(
+        &config
+            .stats()
+            .summary(&diff, kept.len(), install_start.elapsed()),
+    );
+
+    if let Some(signatures) = signatures {
+        reporter.signatures(signatures.verified, signatures.unsigned.len());
+
+        if !signatures.unsigned.is_empty() {
+            reporter.warning(&format!(
+                "no registry signature for {}",
+                signatures.unsigned.join(", ")
+            ));
+        }
+    }
+
+    for warning in check_peers(&resolution) {
+        reporter.diagnostic(&miette::Report::new(warning));
+    }
+
+    Ok(())
+}
+
+/// The store key of every package of `resolution` extracted into node_modules, by
+/// `name@version`.
+fn store_keys(resolution: &Resolution) -> BTreeMap<String, String> {
+    resolution
+        .tree
+        .iter()
+        .filter(|(_, package)| !package.is_link())
+        .map(|(key, package)| (key.clone(), package.cacache_key()))
+        .collect()
+}
+
+/// Create the `.bin` shims of `resolution`: those of the requested packages in
+/// `node_modules/.bin`, and those of the dependencies of every package in its own
+/// `node_modules/.bin`, where its scripts find them. Linked packages bring their own.
+pub fn link_executables(
+    config: &VoltConfig,
+    resolution: &Resolution,
+    linker: &dyn Linker,
+) -> Result<()> {
+    let bin_dir = config.node_modules()?.join(".bin");
+
+    // when two requested packages have the same command, the last key wins
+    for key in resolution.requested() {
+        if let (Some(package), Some(directory)) =
+            (resolution.tree.get(key), linker.directories(key).first())
+        {
+            link_package_bins(package, directory, &bin_dir)?;
+        }
+    }
+
+    let tree: BTreeMap<&String, &VoltPackage> = resolution.tree.iter().collect();
+
+    for (key, package) in tree {
+        if package.is_link() {
+            continue;
+        }
+
+        let dependencies: BTreeMap<_, _> = package.installed_dependencies().collect();
+
+        for dependent in linker.directories(key) {
+            let bin_dir = dependent.join("node_modules").join(".bin");
+
+            for (name, version) in &dependencies {
+                let key = dependency_key(name, version);
+
+                if let (Some(dependency), Some(directory)) =
+                    (resolution.tree.get(&key), linker.directories(&key).first())
+                {
+                    link_package_bins(dependency, directory, &bin_dir)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove everything in `node_modules` but the lock file of the project, held by the
+/// install.
+fn empty_node_modules(node_modules: &Path) -> Result<()> {
+    let entries = match std::fs::read_dir(node_modules) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    for entry in entries.flatten() {
+        if entry.file_name() != PROJECT_LOCK {
+            remove_path(&entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the links in `directory` (and its scopes) to packages that are gone.
+fn remove_dangling_links(directory: &Path) -> Result<()> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if entry.file_name().to_string_lossy().starts_with('@') && path.is_dir() {
+            remove_dangling_links(&path)?;
+
+            continue;
+        }
+
+        let is_link = path
+            .symlink_metadata()
+            .map_or(false, |metadata| metadata.file_type().is_symlink());
+
+        // following the link fails when its target is gone
+        if is_link && path.metadata().is_err() {
+            std::fs::remove_file(&path)
+                .or_else(|_| std::fs::remove_dir(&path))
+                .map_err(|e| FilesystemError::Remove {
+                    source: e,
+                    path: path.display().to_string(),
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop packages that won't be installed (`name@version` keys) from the tree, along with
+/// their directories in the virtual store and the links their dependents have to them.
+fn remove_packages(
+    resolution: &mut Resolution,
+    keys: &[String],
+    node_modules: &Path,
+) -> Result<()> {
+    for key in keys {
+        let package = match resolution.tree.remove(key) {
+            Some(package) => package,
+            None => continue,
+        };
+
+        resolution.direct.retain(|direct| direct != key);
+        resolution.aliases.retain(|_, alias| alias != key);
+
+        for dependent in resolution.tree.values_mut() {
+            let dependencies = match dependent.dependencies.as_mut() {
+                Some(dependencies) => dependencies,
+                None => continue,
+            };
+
+            let before = dependencies.len();
+
+            dependencies.retain(|name, version| {
+                !(version == &package.version && dependency_name(name, version) == package.name)
+            });
+
+            if dependencies.len() == before {
+                continue;
+            }
+
+            let link = dependent
+                .install_directory(node_modules)
+                .parent()
+                .map(|parent| parent.join(&package.name));
+
+            if let Some(link) = link.filter(|link| link.symlink_metadata().is_ok()) {
+                std::fs::remove_file(&link)
+                    .or_else(|_| std::fs::remove_dir(&link))
+                    .map_err(|e| FilesystemError::Write {
+                        source: e,
+                        path: link.display().to_string(),
+                    })?;
+            }
+        }
+
+        let store = node_modules.join(".volt").join(package.directory_name());
+
+        if store.exists() {
+            std::fs::remove_dir_all(&store).map_err(|e| FilesystemError::Write {
+                source: e,
+                path: store.display().to_string(),
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Collect the `name -> range` dependencies in `scope` to install for the project in `cwd`.
+///
+/// In a workspace these are the dependencies of the members selected by `filters` (plus
+/// the root's own when nothing is filtered), without the members themselves.
+pub fn project_dependencies(
+    cwd: &Path,
+    filters: &[Filter],
+    scope: InstallScope,
+) -> Result<(BTreeMap<String, String>, Option<Workspace>)> {
+    let (package_json, _) = PackageJson::get_from_dir(cwd)?;
+
+    let mut dependencies = BTreeMap::new();
+
+    let workspace = Workspace::discover(cwd)?;
+
+    match &workspace {
+        Some(workspace) => {
+            let members = workspace.filter(filters)?;
+
+            if members.is_empty() {
+                tracing::warn!("No workspace members matched the filters");
+            }
+
+            // the root manifest's dependencies are only installed with the whole workspace
+            if filters.is_empty() {
+                collect_dependencies(&package_json, scope, &mut dependencies);
+            }
+
+            for member in members {
+                collect_dependencies(&member.package_json, scope, &mut dependencies);
+            }
+
+            // members are linked from the workspace rather than downloaded
+            dependencies.retain(|name, _| workspace.member(name).is_none());
+        }
+        None => {
+            if !filters.is_empty() {
+                tracing::warn!("--filter is ignored, this project has no workspaces");
+            }
+
+            collect_dependencies(&package_json, scope, &mut dependencies);
+        }
+    }
+
+    Ok((dependencies, workspace))
+}
+
+/// Add the dependencies and dev dependencies in `scope` of a package.json.
+///
+/// Like npm, a name in both `dependencies` and `devDependencies` is installed in the range
+/// of `dependencies`. Workspace members requiring a name in different ranges share one
+/// install of it, in the range seen first (the root's, then the members' in order), with a
+/// warning for the others.
+fn collect_dependencies(
+    package_json: &PackageJson,
+    scope: InstallScope,
+    dependencies: &mut BTreeMap<String, String>,
+) {
+    let production = scope != InstallScope::Development;
+    let development = scope != InstallScope::Production;
+
+    let mut manifest: BTreeMap<&String, &String> = BTreeMap::new();
+
+    if development {
+        manifest.extend(package_json.dev_dependencies.iter().flatten());
+    }
+
+    // over the dev dependencies of the same names
+    if production {
+        manifest.extend(package_json.dependencies.iter().flatten());
+    }
+
+    for (name, range) in manifest {
+        match dependencies.get(name) {
+            Some(first) if first != range => tracing::warn!(
+                "{} depends on {}@{}, installing {}@{} required before it",
+                package_json.name,
+                name,
+                range,
+                name,
+                first
+            ),
+            Some(_) => {}
+            None => {
+                dependencies.insert(name.clone(), range.clone());
+            }
+        }
+    }
+}
+
+/// Check the `engines` of every package against the running node, npm and volt. Like
+/// npm, a mismatch only warns unless `engine-strict` is set, which fails the install or
+/// skips the package when it's optional. Returns the packages to skip.
+fn check_engines(
+    config: &VoltConfig,
+    runtime: &Runtime,
+    resolution: &Resolution,
+    reporter: &dyn Reporter,
+) -> Result<Vec<String>> {
+    let strict = config.settings().engine_strict;
+
+    let mut skipped = vec![];
+
+    for (key, package) in &resolution.tree {
+        let unsatisfied = runtime.unsatisfied(&engine_ranges(package.engines.as_ref()));
+
+        if let Some(unsatisfied) = unsatisfied.into_iter().next() {
+            let error = ResolutionError::UnsupportedEngine {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                engine: unsatisfied.engine,
+                range: unsatisfied.range,
+                current: unsatisfied.current,
+            };
+
+            if strict && !package.optional {
+                return Err(error.into());
+            }
+
+            reporter.warning(&error.to_string());
+
+            if strict {
+                skipped.push(key.clone());
+            }
+        }
+    }
+
+    Ok(skipped)
+}
+
+/// Check the `engines` of the project's own package.json, failing with `engine-strict`.
+fn check_project_engines(
+    config: &VoltConfig,
+    runtime: &Runtime,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    let package_json = match PackageJson::get_from_dir(&config.cwd()?) {
+        Ok((package_json, _)) => package_json,
+        Err(_) => return Ok(()),
+    };
+
+    let engines = package_json.engines.clone().unwrap_or_default();
+
+    for unsatisfied in runtime.unsatisfied(&engines) {
+        let error = ResolutionError::UnsupportedEngine {
+            name: package_json.name.clone(),
+            version: package_json.version.clone(),
+            engine: unsatisfied.engine,
+            range: unsatisfied.range,
+            current: unsatisfied.current,
+        };
+
+        if config.settings().engine_strict {
+            return Err(error.into());
+        }
+
+        reporter.warning(&error.to_string());
+    }
+
+    Ok(())
+}
+
+/// Make every workspace member resolvable from the root `node_modules`.
+pub fn link_workspace_members(config: &VoltConfig, workspace: &Workspace) -> Result<()> {
+    let node_modules = config.node_modules()?;
+
+    for member in &workspace.members {
+        link_directory(&member.path, &node_modules.join(member.name()))?;
+    }
+
+    Ok(())
+}
+
+/// Record the resolved packages in the project's `volt.lock`, in place of those locked
+/// before so that removed dependencies leave it.
+pub fn write_lock_file(config: &VoltConfig, resolution: &Resolution) -> Result<()> {
+    resolved_lock_file(config, resolution)?.save()
+}
+
+/// Fail when the `volt.lock` the resolved packages would be recorded in isn't the one on
+/// disk, for installs that mustn't change it (`--frozen-lockfile`, `volt ci`).
+pub fn check_lock_file(config: &VoltConfig, resolution: &Resolution) -> Result<()> {
+    let lock_file = resolved_lock_file(config, resolution)?;
+
+    if lock_file.is_dirty() {
+        return Err(ResolutionError::LockFileOutdated {
+            path: lock_file.path.display().to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+fn resolved_lock_file(config: &VoltConfig, resolution: &Resolution) -> Result<LockFile> {
+    let mut lock_file = LockFile::new(config.lockfile()?, false);
+
+    lock_file.extend(&resolution.tree);
+
+    Ok(lock_file)
+}
+
+/// Link the directory `target` to `link` (a symlink, or a junction on windows without
+/// developer mode), replacing whatever was at `link` before.
+pub fn link_directory(target: &Path, link: &Path) -> Result<()> {
+    let (target, link) = (&long_path(target), &long_path(link));
+
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: link.display().to_string(),
+    };
+
+    // already in place, as most links are when an install changes a few packages
+    if std::fs::read_link(link).map_or(false, |existing| existing == target) {
+        return Ok(());
+    }
+
+    if let Ok(metadata) = link.symlink_metadata() {
+        if metadata.is_dir() {
+            std::fs::remove_dir_all(link).map_err(write_error)?;
+        } else {
+            // symlinks (and junctions) are removed without touching their target
+            std::fs::remove_file(link)
+                .or_else(|_| std::fs::remove_dir(link))
+                .map_err(write_error)?;
+        }
+    }
+
+    // node_modules/@scope for scoped packages
+    if let Some(parent) = link.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: parent.display().to_string(),
+        })?;
+    }
+
+    symlink_dir(target, link).map_err(write_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_lock_file, collect_dependencies, write_lock_file, InstallScope, Resolution};
+
+    use crate::{
+        config::VoltConfig,
+        model::lock_file::{LockFile, LockedPackage},
+        utils::package::PackageJson,
+    };
+
+    use std::collections::BTreeMap;
+
+    fn resolution(names: &[&str]) -> Resolution {
+        let mut resolution = Resolution::default();
+
+        for name in names {
+            let key = format!("{}@1.0.0", name);
+            let package = LockedPackage {
+                name: name.to_string(),
+                version: "1.0.0".to_string(),
+                ..Default::default()
+            };
+
+            resolution.tree.insert(key.clone(), package.to_package());
+            resolution.direct.push(key);
+        }
+
+        resolution
+    }
+
+    #[test]
+    fn removed_dependencies_leave_the_lock_file() {
+        let project = tempfile::tempdir().unwrap();
+        let config = VoltConfig::new(project.path());
+
+        write_lock_file(&config, &resolution(&["a", "b"])).unwrap();
+        write_lock_file(&config, &resolution(&["a"])).unwrap();
+
+        let lock_file = LockFile::load(config.lockfile().unwrap(), false).unwrap();
+
+        assert_eq!(
+            lock_file.dependencies.keys().collect::<Vec<_>>(),
+            ["a@1.0.0"]
+        );
+    }
+
+    #[test]
+    fn frozen_lock_file_matches_the_resolution() {
+        let project = tempfile::tempdir().unwrap();
+        let config = VoltConfig::new(project.path());
+
+        write_lock_file(&config, &resolution(&["a", "b"])).unwrap();
+
+        assert!(check_lock_file(&config, &resolution(&["a", "b"])).is_ok());
+        assert!(check_lock_file(&config, &resolution(&["a"])).is_err());
+        assert!(check_lock_file(&config, &resolution(&["a", "b", "c"])).is_err());
+    }
+
+    #[test]
+    fn dependencies_take_precedence_over_dev_dependencies() {
+        let ranges = |pairs: &[(&str, &str)]| {
+            Some(
+                pairs
+                    .iter()
+                    .map(|(name, range)| (name.to_string(), range.to_string()))
+                    .collect::<BTreeMap<_, _>>(),
+            )
+        };
+
+        let root = PackageJson {
+            name: "app".to_string(),
+            dependencies: ranges(&[("react", "^17.0.0")]),
+            dev_dependencies: ranges(&[("react", "^18.0.0"), ("jest", "^27.0.0")]),
+            ..Default::default()
+        };
+
+        let member = PackageJson {
+            name: "web".to_string(),
+            dependencies: ranges(&[("react", "^18.0.0"), ("lodash", "^4.0.0")]),
+            ..Default::default()
+        };
+
+        let mut dependencies = BTreeMap::new();
+
+        collect_dependencies(&root, InstallScope::All, &mut dependencies);
+        collect_dependencies(&member, InstallScope::All, &mut dependencies);
+
+        assert_eq!(
+            dependencies,
+            ranges(&[
+                ("jest", "^27.0.0"),
+                ("lodash", "^4.0.0"),
+                ("react", "^17.0.0")
+            ])
+            .unwrap()
+        );
+
+        let mut dev = BTreeMap::new();
+
+        collect_dependencies(&root, InstallScope::Development, &mut dev);
+
+        assert_eq!(dev["react"], "^18.0.0");
+    }
+}
@@ -0,0 +1,365 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! What node_modules was installed from, so that `volt install` can tell it has nothing
+//! to do.
+//!
+//! `volt install` writes `node_modules/.volt-state.json` when it's done: the hash of
+//! volt.lock, the dependencies it installed, the workspace members it linked, the
+//! patches it applied and the settings that shape node_modules. When the next install
+//! would write the same state, and the dependencies are still in node_modules, it stops
+//! there, without resolving, reading the store or linking. Anything else changing
+//! node_modules (an install that fails halfway, `volt link`) removes the file first.
+//!
+//! Every install also records the packages it left in node_modules, with their store
+//! keys, so the next one only extracts what was added or changed and removes what is
+//! gone ([`InstallDiff`]).
+
+use crate::{
+    config::VoltConfig,
+    install::InstallScope,
+    metadata_cache::write_json,
+    patches::read_patches,
+    utils::errors::FilesystemError,
+    workspace::{Workspace, WorkspaceMember},
+};
+
+use miette::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    io::ErrorKind,
+    path::PathBuf,
+};
+
+/// Name of the file in node_modules.
+pub const INSTALL_STATE: &str = ".volt-state.json";
+
+/// What node_modules was installed from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InstallState {
+    /// Version of volt that installed it
+    pub volt: String,
+    /// sha256 of volt.lock, empty without one
+    pub lock_file: String,
+    /// The dependencies installed, `name -> range`
+    pub dependencies: BTreeMap<String, String>,
+    /// Names of the workspace members linked
+    pub workspace_members: Vec<String>,
+    pub scope: String,
+    pub node_linker: String,
+    pub hoist_pattern: Vec<String>,
+    pub public_hoist_pattern: Vec<String>,
+    /// Whether missing peer dependencies were installed
+    pub install_peers: bool,
+    /// The patches applied, `name@version -> sha256`
+    pub patches: BTreeMap<String, String>,
+    /// The packages in node_modules, `name@version -> store key`
+    pub packages: BTreeMap<String, String>,
+}
+
+impl InstallState {
+    /// The state an install of `dependencies` in the project of `config` leaves.
+    pub fn new(
+        config: &VoltConfig,
+        dependencies: &BTreeMap<String, String>,
+        workspace: Option<&Workspace>,
+        scope: InstallScope,
+        install_peers: bool,
+    ) -> Result<Self> {
+        let lock_file = match std::fs::read(config.lockfile()?) {
+            Ok(contents) => hex::encode(Sha256::digest(&contents)),
+            Err(_) => String::new(),
+        };
+
+        let settings = config.settings();
+
+        Ok(Self {
+            volt: env!("CARGO_PKG_VERSION").to_string(),
+            lock_file,
+            dependencies: dependencies.clone(),
+            workspace_members: workspace
+                .map(|workspace| {
+                    workspace
+                        .members
+                        .iter()
+                        .map(WorkspaceMember::name)
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            scope: format!("{:?}", scope).to_lowercase(),
+            node_linker: settings.node_linker.to_string(),
+            hoist_pattern: settings.hoist_pattern.clone(),
+            public_hoist_pattern: settings.public_hoist_pattern.clone(),
+            install_peers,
+            patches: read_patches(config)?
+                .into_iter()
+                .map(|(key, patch)| (key, hex::encode(Sha256::digest(patch.as_bytes()))))
+                .collect(),
+            packages: BTreeMap::new(),
+        })
+    }
+
+    /// The state node_modules of the project of `config` was left in, if any.
+    pub fn read(config: &VoltConfig) -> Option<Self> {
+        let contents = std::fs::read(path(config).ok()?).ok()?;
+
+        serde_json::from_slice(&contents).ok()
+    }
+
+    /// Whether node_modules was installed in this state and its dependencies are still
+    /// there.
+    pub fn is_installed(&self, config: &VoltConfig) -> Result<bool> {
+        let installed = match Self::read(config) {
+            // the packages aren't known before resolving
+            Some(installed) => Self {
+                packages: self.packages.clone(),
+                ..installed
+            },
+            None => return Ok(false),
+        };
+
+        if installed != *self || self.lock_file.is_empty() {
+            return Ok(false);
+        }
+
+        let node_modules = config.node_modules()?;
+
+        Ok(self
+            .dependencies
+            .keys()
+            .all(|name| node_modules.join(name).symlink_metadata().is_ok()))
+    }
+
+    /// Record that node_modules of the project of `config` is in this state, with the
+    /// packages the install left in it.
+    pub fn record(mut self, config: &VoltConfig) -> Result<()> {
+        self.packages = Self::read(config)
+            .map(|installed| installed.packages)
+            .unwrap_or_default();
+
+        self.write(config)
+    }
+
+    /// Record the packages an install left in node_modules of the project of `config`.
+    pub fn record_packages(config: &VoltConfig, packages: BTreeMap<String, String>) -> Result<()> {
+        Self {
+            packages,
+            ..Self::default()
+        }
+        .write(config)
+    }
+
+    fn write(&self, config: &VoltConfig) -> Result<()> {
+        write_json(&path(config)?, self)
+    }
+
+    /// Forget the state of node_modules of the project of `config`, before changing it.
+    pub fn clear(config: &VoltConfig) -> Result<()> {
+        let path = path(config)?;
+
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(FilesystemError::Remove {
+                source: e,
+                path: path.display().to_string(),
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What an install changes in node_modules: the names of the packages added, removed and
+/// installed in other versions, and the `name@version` of those it can leave in place or
+/// has to remove.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstallDiff {
+    pub added: BTreeSet<String>,
+    pub removed: BTreeSet<String>,
+    pub changed: BTreeSet<String>,
+    /// Installed before with the same contents
+    pub unchanged: BTreeSet<String>,
+    /// Installed before and no longer
+    pub stale: BTreeSet<String>,
+}
+
+impl InstallDiff {
+    /// The changes from the `previous` packages to `packages` (`name@version -> store key`).
+    pub fn between(
+        previous: &BTreeMap<String, String>,
+        packages: &BTreeMap<String, String>,
+    ) -> Self {
+        let by_name = |packages: &BTreeMap<String, String>| {
+            let mut names: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+
+            for (key, store_key) in packages {
+                let name = key.rsplit_once('@').map_or(key.as_str(), |(name, _)| name);
+
+                names
+                    .entry(name.to_string())
+                    .or_default()
+                    .insert(key.clone(), store_key.clone());
+            }
+
+            names
+        };
+
+        let (before, after) = (by_name(previous), by_name(packages));
+
+        let mut diff = Self::default();
+
+        for (name, installed) in &after {
+            match before.get(name) {
+                None => {
+                    diff.added.insert(name.clone());
+                }
+                Some(was) if was != installed => {
+                    diff.changed.insert(name.clone());
+                }
+                Some(_) => {}
+            }
+        }
+
+        diff.removed = before
+            .keys()
+            .filter(|name| !after.contains_key(*name))
+            .cloned()
+            .collect();
+
+        diff.unchanged = packages
+            .iter()
+            .filter(|(key, store_key)| previous.get(*key) == Some(store_key))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        diff.stale = previous
+            .keys()
+            .filter(|key| !packages.contains_key(*key))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Whether nothing was added, removed or changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for InstallDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "+{} \u{2212}{} ~{} packages",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+fn path(config: &VoltConfig) -> Result<PathBuf> {
+    Ok(config.node_modules()?.join(INSTALL_STATE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstallDiff, InstallState};
+
+    use crate::{config::VoltConfig, install::InstallScope};
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn tells_whether_node_modules_is_installed() {
+        let project = tempfile::tempdir().unwrap();
+        let config = VoltConfig::new(project.path());
+
+        std::fs::write(config.lockfile().unwrap(), "lock").unwrap();
+
+        let dependencies = BTreeMap::from([("a".to_string(), "^1.0.0".to_string())]);
+
+        let state =
+            InstallState::new(&config, &dependencies, None, InstallScope::All, false).unwrap();
+
+        assert!(!state.is_installed(&config).unwrap());
+
+        std::fs::create_dir_all(config.node_modules().unwrap().join("a")).unwrap();
+        state.clone().record(&config).unwrap();
+
+        assert!(state.is_installed(&config).unwrap());
+
+        // a changed lock file
+        std::fs::write(config.lockfile().unwrap(), "changed").unwrap();
+
+        let changed =
+            InstallState::new(&config, &dependencies, None, InstallScope::All, false).unwrap();
+
+        assert!(!changed.is_installed(&config).unwrap());
+
+        // a removed dependency
+        std::fs::write(config.lockfile().unwrap(), "lock").unwrap();
+        std::fs::remove_dir(config.node_modules().unwrap().join("a")).unwrap();
+
+        assert!(!state.is_installed(&config).unwrap());
+
+        InstallState::clear(&config).unwrap();
+
+        assert_eq!(InstallState::read(&config), None);
+    }
+
+    #[test]
+    fn diffs_the_installed_packages() {
+        let packages = |keys: &[(&str, &str)]| {
+            keys.iter()
+                .map(|(key, store_key)| (key.to_string(), store_key.to_string()))
+                .collect()
+        };
+
+        let previous = packages(&[
+            ("@scope/a@1.0.0", "a1"),
+            ("b@1.0.0", "b1"),
+            ("c@2.0.0", "c2"),
+            ("d@1.0.0", "d1"),
+        ]);
+
+        let diff = InstallDiff::between(
+            &previous,
+            &packages(&[
+                ("@scope/a@1.0.0", "a1"),
+                ("b@1.1.0", "b11"),
+                ("c@2.0.0", "c2-rebuilt"),
+                ("e@1.0.0", "e1"),
+            ]),
+        );
+
+        assert_eq!(diff.added, ["e".to_string()].into());
+        assert_eq!(diff.removed, ["d".to_string()].into());
+        assert_eq!(diff.changed, ["b".to_string(), "c".to_string()].into());
+        assert_eq!(diff.unchanged, ["@scope/a@1.0.0".to_string()].into());
+        assert_eq!(
+            diff.stale,
+            ["b@1.0.0".to_string(), "d@1.0.0".to_string()].into()
+        );
+        assert_eq!(diff.to_string(), "+1 \u{2212}1 ~2 packages");
+    }
+}
@@ -0,0 +1,417 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+use crate::{
+    config::VoltConfig,
+    pack::package_files,
+    paths::{is_case_sensitive, long_path},
+    utils::{
+        decompress_gzip,
+        errors::{FilesystemError, IntegrityError},
+        package::PackageJson,
+        voltapi::VoltPackage,
+    },
+};
+
+use miette::IntoDiagnostic;
+use ssri::Integrity;
+use tar::Archive;
+
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+/// Extract an uncompressed tarball into each of `directories`, and its files into the
+/// store.
+///
+/// Entries that would be written outside of the package (`../`, absolute paths), and
+/// symlinks pointing outside of it, fail the extraction; so do paths that only differ
+/// in case on a filesystem that doesn't tell them apart. Like npm, the links inside the
+/// package are left out.
+pub fn extract_tarball(
+    data: impl Read,
+    package: &VoltPackage,
+    directories: &[PathBuf],
+    config: &VoltConfig,
+) -> miette::Result<()> {
+    // Generate the tarball archive given the decompressed bytes
+    let mut node_archive = Archive::new(data);
+
+    // extract to both the global store + node_modules (in the case of them using the pnpm linking algorithm)
+    let mut cas_file_map: HashMap<String, Integrity> = HashMap::new();
+
+    // Add package's directory to list of created directories
+    let mut created_directories: Vec<PathBuf> = vec![];
+
+    // the files extracted and their directories, by their lowercase path
+    let mut folded: HashMap<String, PathBuf> = HashMap::new();
+
+    let unsafe_entry = |entry: &Path| IntegrityError::UnsafeEntry {
+        package: format!("{}@{}", package.name, package.version),
+        entry: entry.display().to_string(),
+    };
+
+    for entry in node_archive.entries().into_diagnostic()? {
+        let mut entry = entry.into_diagnostic()?;
+
+        let entry_type = entry.header().entry_type();
+        let link = entry_type.is_symlink() || entry_type.is_hard_link();
+
+        // directories are created for the files in them
+        if !entry_type.is_file() && !link {
+            continue;
+        }
+
+        let entry_path = entry.path().into_diagnostic()?.into_owned();
+
+        // Remove `package/` from `package/lib/index.js`
+        let cleaned_entry_path_string =
+            package_path(&entry_path).ok_or_else(|| unsafe_entry(&entry_path))?;
+
+        if link {
+            let target = entry.link_name().into_diagnostic()?.unwrap_or_default();
+
+            if !link_stays_inside(&cleaned_entry_path_string, &target, entry_type.is_symlink()) {
+                return Err(unsafe_entry(&entry_path).into());
+            }
+
+            tracing::debug!("leaving out the link {}", entry_path.display());
+
+            continue;
+        }
+
+        if let Some((first, second)) = case_collision(&mut folded, &cleaned_entry_path_string) {
+            if directories
+                .first()
+                .map_or(false, |directory| !is_case_sensitive(directory))
+            {
+                return Err(IntegrityError::CaseCollision {
+                    package: format!("{}@{}", package.name, package.version),
+                    first: first.display().to_string(),
+                    second: second.display().to_string(),
+                }
+                .into());
+            }
+        }
+
+        // Read the contents of the entry
+        let mut buffer = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut buffer).into_diagnostic()?;
+
+        // the linker decides where the package goes (node_modules/.volt/send@0.17.2/node_modules/send)
+        for directory in directories {
+            let file_path = long_path(&directory.join(&cleaned_entry_path_string));
+
+            let write_error = |e| FilesystemError::Write {
+                source: e,
+                path: file_path.display().to_string(),
+            };
+
+            // Get the entry's parent
+            let entry_path_parent = file_path.parent().unwrap_or(directory);
+
+            // If we haven't created this directory yet, create it; a file of the package
+            // may already be in the way (`lib` and then `lib/index.js`)
+            if !created_directories.iter().any(|p| p == entry_path_parent) {
+                created_directories.push(entry_path_parent.to_path_buf());
+                std::fs::create_dir_all(entry_path_parent).map_err(|e| {
+                    FilesystemError::CreateDir {
+                        source: e,
+                        path: entry_path_parent.display().to_string(),
+                    }
+                })?;
+            }
+
+            // Write the contents to node_modules, failing where a directory of the
+            // package is (`lib/index.js` and then `lib`)
+            let mut file = std::fs::File::create(&file_path).map_err(write_error)?;
+
+            file.write_all(&buffer).map_err(write_error)?;
+        }
+
+        // Write the contents of the entry into the content-addressable store located at `app.volt_dir`
+        // We get a hash of the file
+        let sri = cacache::write_hash_sync(&config.store()?, &buffer).into_diagnostic()?;
+
+        // Insert the name of the file and map it to the hash of the file
+        cas_file_map.insert(
+            cleaned_entry_path_string.to_string_lossy().into_owned(),
+            sri,
+        );
+    }
+
+    // Write the file, shasum map to the content-addressable store
+    cacache::write_sync(
+        &config.store()?,
+        &package.cacache_key(),
+        serde_json::to_string(&cas_file_map).into_diagnostic()?,
+    )
+    .into_diagnostic()?;
+
+    Ok(())
+}
+
+/// Record the file at `path` and its directories in `folded`, by their lowercase path,
+/// returning the first path recorded and `path` (or its directory) when they only differ
+/// in case: `Lib/index.js` and `lib/util.js` collide as much as `README` and `readme`.
+fn case_collision(
+    folded: &mut HashMap<String, PathBuf>,
+    path: &Path,
+) -> Option<(PathBuf, PathBuf)> {
+    for ancestor in path.ancestors() {
+        if ancestor.as_os_str().is_empty() {
+            break;
+        }
+
+        let lowercase = ancestor.to_string_lossy().to_lowercase();
+
+        match folded.get(&lowercase) {
+            Some(first) if first != ancestor => {
+                return Some((first.clone(), ancestor.to_path_buf()))
+            }
+            // its directories were recorded with it
+            Some(_) => break,
+            None => {
+                folded.insert(lowercase, ancestor.to_path_buf());
+            }
+        }
+    }
+
+    None
+}
+
+/// The path of a tarball entry inside the package, without its first directory
+/// (`package/`), or `None` if it points outside of the package.
+fn package_path(entry: &Path) -> Option<PathBuf> {
+    let mut components = entry.components();
+
+    // the directory every file is in, `package/` most of the time
+    match components.next()? {
+        Component::Normal(_) => {}
+        _ => return None,
+    }
+
+    let mut path = PathBuf::new();
+
+    for component in components {
+        match component {
+            Component::Normal(name) => path.push(name),
+            Component::CurDir => {}
+            // `..`, `/` and `C:`
+            _ => return None,
+        }
+    }
+
+    Some(path).filter(|path| !path.as_os_str().is_empty())
+}
+
+/// Whether a link at `path` in the package points to `target` inside of it: symlinks are
+/// relative to their directory, hard links to the root of the tarball.
+fn link_stays_inside(path: &Path, target: &Path, symlink: bool) -> bool {
+    if !symlink {
+        return package_path(target).is_some();
+    }
+
+    // how many directories deep the link resolves to
+    let mut depth = path.components().count() as isize - 1;
+
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => depth -= 1,
+            _ => return false,
+        }
+
+        if depth < 0 {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Read `package/package.json` out of a gzipped tarball.
+pub fn read_manifest(tarball: &[u8]) -> miette::Result<PackageJson> {
+    let mut archive = Archive::new(Cursor::new(decompress_gzip(tarball)?));
+
+    for entry in archive.entries().into_diagnostic()? {
+        let mut entry = entry.into_diagnostic()?;
+
+        if entry.path().into_diagnostic()? == Path::new("package/package.json") {
+            let mut contents = String::new();
+
+            entry.read_to_string(&mut contents).into_diagnostic()?;
+
+            return serde_json::from_str(&contents).into_diagnostic();
+        }
+    }
+
+    miette::bail!("the tarball has no package/package.json")
+}
+
+/// Pack a directory into a gzipped tarball the way the registry serves them (under
+/// `package/`), with the files `volt pack` would publish.
+pub fn pack_directory(directory: &Path) -> miette::Result<Vec<u8>> {
+    let (package_json, _) = PackageJson::get_from_dir(directory)?;
+
+    let files = package_files(directory, &package_json)?;
+
+    pack_files(directory, &files)
+}
+
+/// Pack the given files (relative to `directory`) into a gzipped tarball under `package/`.
+///
+/// Entries are sorted and stripped of timestamps and owners, so packing the same files
+/// twice gives the same bytes.
+pub fn pack_files(directory: &Path, files: &[PathBuf]) -> miette::Result<Vec<u8>> {
+    let mut files = files.to_vec();
+    files.sort();
+
+    let mut builder = tar::Builder::new(Vec::new());
+
+    for relative in files {
+        let file = directory.join(&relative);
+        let contents = std::fs::read(&file).into_diagnostic()?;
+
+        let mut header = tar::Header::new_gnu();
+
+        header.set_size(contents.len() as u64);
+        header.set_mtime(0);
+        header.set_mode(if is_executable(&file) { 0o755 } else { 0o644 });
+
+        builder
+            .append_data(&mut header, Path::new("package").join(relative), &*contents)
+            .into_diagnostic()?;
+    }
+
+    let archive = builder.into_inner().into_diagnostic()?;
+
+    let mut compressor = libdeflater::Compressor::new(libdeflater::CompressionLvl::default());
+    let mut compressed = vec![0; compressor.gzip_compress_bound(archive.len())];
+
+    let size = compressor
+        .gzip_compress(&archive, &mut compressed)
+        .map_err(|e| miette::miette!("failed to compress {}: {:?}", directory.display(), e))?;
+
+    compressed.truncate(size);
+
+    Ok(compressed)
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::metadata(path).map_or(false, |metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+/// Total size of the files under `path`, without following symlinks (0 if it doesn't exist).
+pub fn directory_size(path: &Path) -> u64 {
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(_) => return 0,
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| directory_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{case_collision, link_stays_inside, package_path};
+
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+    };
+
+    #[test]
+    fn keeps_entries_inside_the_package() {
+        assert_eq!(
+            package_path(Path::new("package/lib/index.js")),
+            Some(PathBuf::from("lib/index.js"))
+        );
+        assert_eq!(
+            package_path(Path::new("node/./index.js")),
+            Some(PathBuf::from("index.js"))
+        );
+
+        assert_eq!(package_path(Path::new("package/../../.bashrc")), None);
+        assert_eq!(package_path(Path::new("/etc/passwd")), None);
+        assert_eq!(package_path(Path::new("package/")), None);
+    }
+
+    #[test]
+    fn keeps_links_inside_the_package() {
+        let link = Path::new("lib/current");
+
+        assert!(link_stays_inside(link, Path::new("../dist/index.js"), true));
+        assert!(link_stays_inside(link, Path::new("v2"), true));
+        assert!(!link_stays_inside(link, Path::new("../../index.js"), true));
+        assert!(!link_stays_inside(link, Path::new("/etc/passwd"), true));
+
+        assert!(link_stays_inside(
+            link,
+            Path::new("package/index.js"),
+            false
+        ));
+        assert!(!link_stays_inside(
+            link,
+            Path::new("package/../../index.js"),
+            false
+        ));
+    }
+
+    #[test]
+    fn finds_paths_differing_in_case() {
+        let mut folded = HashMap::new();
+
+        for path in ["lib/index.js", "lib/util.js", "README.md", "lib/index.js"] {
+            assert_eq!(case_collision(&mut folded, Path::new(path)), None);
+        }
+
+        assert_eq!(
+            case_collision(&mut folded, Path::new("readme.md")),
+            Some((PathBuf::from("README.md"), PathBuf::from("readme.md")))
+        );
+        assert_eq!(
+            case_collision(&mut folded, Path::new("Lib/other.js")),
+            Some((PathBuf::from("lib"), PathBuf::from("Lib")))
+        );
+        assert_eq!(
+            case_collision(&mut folded, Path::new("LIB")),
+            Some((PathBuf::from("lib"), PathBuf::from("LIB")))
+        );
+    }
+}
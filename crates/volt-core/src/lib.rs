@@ -0,0 +1,124 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The resolver, lock file, package store and installer of volt, without its command
+//! line.
+//!
+//! Everything starts from a [`VoltConfig`](config::VoltConfig), the project directory
+//! and options of a run, with the settings of the user and the project loaded into it:
+//!
+//! ```no_run
+//! use volt_core::{
+//!     config::VoltConfig,
+//!     install::{dependency_specs, install, project_dependencies, resolve, InstallScope},
+//! };
+//!
+//! # async fn example() -> miette::Result<()> {
+//! let mut config = VoltConfig::new("path/to/project");
+//! config.load_settings()?;
+//!
+//! let (dependencies, _) =
+//!     project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+//!
+//! let resolution = resolve(&config, &dependency_specs(&dependencies)).await?;
+//! install(&config, resolution).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! - [`install`] resolves packages from the registry and other sources and installs them
+//!   into `node_modules`, laid out by a [`linker`]
+//! - [`model::lock_file`] reads and writes `volt.lock`
+//! - [`cache`] inspects the package store, [`task_cache`] the cached results of scripts
+//! - [`settings`] reads `.voltrc`, `~/.volt/config.toml`, `.npmrc` and the environment
+//! - [`source`] is where registry packages come from: the registry, a directory of
+//!   tarballs, or the store
+//! - [`reporter`] is how progress and prompts reach the embedding tool
+//!
+//! Errors are [`miette`] reports, most of them from [`utils::errors`], with a code and a
+//! hint for the user.
+
+pub mod audit;
+pub mod bundle;
+pub mod cache;
+pub mod classes;
+pub mod config;
+pub mod connections;
+pub mod dedupe;
+pub mod deprecate;
+pub mod diff;
+pub mod dist_tags;
+pub mod dlx;
+pub mod dns;
+pub mod doctor;
+pub mod engines;
+pub mod git;
+pub mod global;
+pub mod graph;
+pub mod gyp;
+pub mod hashing;
+pub mod hooks;
+pub mod install;
+pub mod install_state;
+pub mod io;
+pub mod licenses;
+pub mod lifecycle;
+pub mod linker;
+pub mod links;
+pub mod local;
+pub mod lock;
+pub mod login;
+pub mod metadata_cache;
+pub mod mirror;
+pub mod model;
+pub mod net;
+pub mod owners;
+pub mod pack;
+pub mod patches;
+pub mod paths;
+pub mod peer;
+pub mod pipeline;
+pub mod platform;
+pub mod plugin;
+pub mod prune;
+pub mod publish;
+pub mod registry;
+pub mod release_age;
+pub mod remote_cache;
+pub mod reporter;
+pub mod resolution_cache;
+pub mod search;
+pub mod settings;
+pub mod shim;
+pub mod signatures;
+pub mod size;
+pub mod source;
+pub mod summary;
+pub mod tarball;
+pub mod task_cache;
+pub mod tasks;
+pub mod throttle;
+pub mod toolchain;
+pub mod transaction;
+pub mod unpublish;
+pub mod update;
+pub mod upgrade;
+pub mod utils;
+pub mod vendor;
+pub mod verify;
+pub mod view;
+pub mod watch;
+pub mod workspace;
@@ -0,0 +1,138 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The licenses of installed packages, and the policy they are held to.
+//!
+//! A policy file holds the same `key = value` lines as `.voltrc`:
+//!
+//! ```toml
+//! # licenses that fail `volt licenses`
+//! deny = ["GPL-3.0", "AGPL-*"]
+//! # if set, the only licenses that pass
+//! allow = ["MIT", "ISC", "Apache-2.0", "BSD-*"]
+//! ```
+//!
+//! Entries are SPDX identifiers or patterns; `GPL-3.0` also covers `GPL-3.0-only` and
+//! `GPL-3.0-or-later`. A package under `(MIT OR GPL-3.0)` passes if one of the
+//! alternatives does, one under `(MIT AND GPL-3.0)` only if both do.
+
+use crate::{
+    linker::matches_patterns,
+    settings::{parse_list, read_settings},
+    utils::package::PackageJson,
+};
+
+use miette::Result;
+
+use std::path::Path;
+
+/// What is recorded for packages that don't say.
+pub const UNKNOWN: &str = "UNKNOWN";
+
+/// The license of a package: its `license`, or the types of the older `licenses` list.
+pub fn license_of(package_json: &PackageJson) -> String {
+    if let Some(license) = package_json.license.as_ref().filter(|l| !l.is_empty()) {
+        return license.clone();
+    }
+
+    let types: Vec<&str> = package_json
+        .licenses
+        .iter()
+        .flatten()
+        .filter_map(|license| license.get("type"))
+        .map(String::as_str)
+        .collect();
+
+    match types.len() {
+        0 => UNKNOWN.to_string(),
+        1 => types[0].to_string(),
+        _ => format!("({})", types.join(" OR ")),
+    }
+}
+
+/// The licenses a project accepts.
+#[derive(Debug, Default, PartialEq)]
+pub struct Policy {
+    pub deny: Vec<String>,
+    pub allow: Vec<String>,
+}
+
+impl Policy {
+    /// Read a policy file (an empty policy if there is none).
+    pub fn load(path: &Path) -> Result<Self> {
+        let values = read_settings(path)?;
+
+        let list = |key: &str| values.get(key).map(|value| parse_list(value));
+
+        Ok(Self {
+            deny: list("deny").unwrap_or_default(),
+            allow: list("allow").unwrap_or_default(),
+        })
+    }
+
+    /// Whether a package under the SPDX expression `license` may be installed.
+    pub fn permits(&self, license: &str) -> bool {
+        let expression = license.trim_start_matches('(').trim_end_matches(')');
+
+        expression.split(" OR ").any(|alternative| {
+            alternative
+                .trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace())
+                .split(" AND ")
+                .all(|license| self.permits_one(license.trim()))
+        })
+    }
+
+    fn permits_one(&self, license: &str) -> bool {
+        let listed = |patterns: &[String]| {
+            patterns.iter().any(|pattern| {
+                matches_patterns(std::slice::from_ref(pattern), license)
+                    || license.starts_with(&format!("{}-", pattern))
+            })
+        };
+
+        !listed(&self.deny) && (self.allow.is_empty() || listed(&self.allow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Policy;
+
+    #[test]
+    fn holds_licenses_to_the_policy() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("license-policy.toml");
+
+        std::fs::write(&path, "deny = [\"GPL-3.0\", \"AGPL-*\"]\n").unwrap();
+
+        let policy = Policy::load(&path).unwrap();
+
+        assert_eq!(policy.deny, ["GPL-3.0", "AGPL-*"]);
+        assert!(policy.permits("MIT"));
+        assert!(!policy.permits("GPL-3.0-or-later"));
+        assert!(!policy.permits("AGPL-1.0"));
+        assert!(policy.permits("(MIT OR GPL-3.0)"));
+        assert!(!policy.permits("(MIT AND GPL-3.0-only)"));
+
+        let policy = Policy {
+            allow: vec!["MIT".to_string(), "BSD-*".to_string()],
+            ..Policy::default()
+        };
+
+        assert!(policy.permits("BSD-3-Clause"));
+        assert!(!policy.permits("UNKNOWN"));
+    }
+}
@@ -0,0 +1,857 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Run package lifecycle scripts (`preinstall`, `install`, `postinstall` and `prepare`, and
+//! the publishing scripts of the root project).
+
+use crate::{
+    config::VoltConfig,
+    gyp::{has_binding, uses_node_gyp, NodeGyp, IMPLICIT_INSTALL},
+    linker::{matches_patterns, Linker},
+    utils::{errors::ScriptError, package::PackageJson, voltapi::VoltPackage},
+};
+
+use miette::{IntoDiagnostic, Result};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    env,
+    ffi::OsString,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    PreInstall,
+    Install,
+    PostInstall,
+    Prepare,
+}
+
+impl LifecycleEvent {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::PreInstall => "preinstall",
+            Self::Install => "install",
+            Self::PostInstall => "postinstall",
+            Self::Prepare => "prepare",
+        }
+    }
+
+    /// Events that are run for installed dependencies, in the order they are run.
+    pub const DEPENDENCY: [Self; 3] = [Self::PreInstall, Self::Install, Self::PostInstall];
+
+    /// Events that are run for the root project, in the order they are run.
+    pub const ROOT: [Self; 4] = [
+        Self::PreInstall,
+        Self::Install,
+        Self::PostInstall,
+        Self::Prepare,
+    ];
+}
+
+/// A single script invocation for a package.
+#[derive(Debug)]
+pub struct ScriptRun<'a> {
+    /// Name of the package the script belongs to
+    pub name: &'a str,
+    /// Version of the package the script belongs to
+    pub version: &'a str,
+    /// Directory the script is run from
+    pub cwd: &'a Path,
+    /// Name of the script (e.g. `postinstall`)
+    pub event: &'a str,
+    /// The shell command itself
+    pub script: &'a str,
+    /// The node-gyp native addons are built with, for scripts of dependencies
+    pub node_gyp: Option<&'a NodeGyp>,
+}
+
+/// Build a `PATH` value with every `node_modules/.bin` directory prepended to the existing one.
+pub fn path_with_bins(bin_dirs: &[PathBuf]) -> Result<OsString> {
+    let mut paths: Vec<PathBuf> = bin_dirs.to_vec();
+
+    if let Some(existing) = env::var_os("PATH") {
+        paths.extend(env::split_paths(&existing));
+    }
+
+    env::join_paths(paths).into_diagnostic()
+}
+
+/// Quote an argument so that it is passed through the platform shell untouched.
+pub fn quote_arg(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c));
+
+    if is_plain {
+        arg.to_string()
+    } else if cfg!(windows) {
+        format!("\"{}\"", arg.replace('"', "\"\""))
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// The `npm_package_*` variables npm sets from a package.json: its `name`, `version`,
+/// `config`, `engines` and `bin`, objects flattened with `_` (`npm_package_config_port`),
+/// lists joined by blank lines and `null` or `false` empty.
+pub fn package_env(package_json: &Value) -> BTreeMap<String, String> {
+    fn flatten(value: &Value) -> String {
+        match value {
+            Value::String(string) => string.clone(),
+            Value::Null | Value::Bool(false) => String::new(),
+            Value::Array(values) => values.iter().map(flatten).collect::<Vec<_>>().join("\n\n"),
+            value => value.to_string(),
+        }
+    }
+
+    fn add(env: &mut BTreeMap<String, String>, prefix: &str, value: &Value) {
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    add(env, &format!("{}_{}", prefix, key), value);
+                }
+            }
+            value => {
+                env.insert(prefix.to_string(), flatten(value));
+            }
+        }
+    }
+
+    let mut env = BTreeMap::new();
+
+    for field in ["name", "version", "config", "engines", "bin"] {
+        if let Some(value) = package_json.get(field) {
+            add(&mut env, &format!("npm_package_{}", field), value);
+        }
+    }
+
+    env
+}
+
+/// Run a script through the platform shell, inheriting stdio.
+///
+/// The script sees the variables npm sets: `npm_package_*` (see [`package_env`]),
+/// `npm_package_json`, `npm_lifecycle_event` and `npm_lifecycle_script`, `INIT_CWD` (the
+/// directory volt was started in), and `npm_execpath` and `npm_config_user_agent` naming
+/// volt (see [`command_env`]).
+///
+/// With `--json`, stdout only carries JSON so the script writes to stderr instead.
+pub fn run_script(config: &VoltConfig, run: &ScriptRun) -> Result<()> {
+    config.reporter().script(&header(run));
+
+    let stdout = if config.json() {
+        Stdio::from(std::io::stderr())
+    } else {
+        Stdio::inherit()
+    };
+
+    let status = script_command(config, run)?
+        .stdout(stdout)
+        .stderr(Stdio::inherit())
+        .status()
+        .map_err(|e| ScriptError::Spawn {
+            source: e,
+            name: run.name.to_string(),
+            script: run.event.to_string(),
+        })?;
+
+    if !status.success() {
+        return Err(failure(run, status.code().unwrap_or(-1)));
+    }
+
+    Ok(())
+}
+
+/// Run a script like [`run_script`], but hold its output back and only print it, under
+/// the header of the script, if it fails; scripts run at once don't mix their output.
+fn run_script_quietly(config: &VoltConfig, run: &ScriptRun) -> Result<()> {
+    let output = script_command(config, run)?
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| ScriptError::Spawn {
+            source: e,
+            name: run.name.to_string(),
+            script: run.event.to_string(),
+        })?;
+
+    if output.status.success() {
+        config.reporter().script(&header(run));
+
+        return Ok(());
+    }
+
+    // in one write, so that failures printed at once stay apart
+    eprint!(
+        "{}\n{}{}",
+        header(run),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    Err(failure(run, output.status.code().unwrap_or(-1)))
+}
+
+/// Run a script like [`run_script`], with each line of its output after `prefix`; scripts
+/// of workspace members run at once stream their output side by side.
+///
+/// Returns the output of the script under its header, without the prefix.
+pub fn run_script_prefixed(config: &VoltConfig, run: &ScriptRun, prefix: &str) -> Result<String> {
+    let header = header(run);
+
+    config.reporter().script(&format!("{} {}", prefix, header));
+
+    let (status, output) =
+        status_prefixed(&mut script_command(config, run)?, prefix, config.json()).map_err(|e| {
+            ScriptError::Spawn {
+                source: e,
+                name: run.name.to_string(),
+                script: run.event.to_string(),
+            }
+        })?;
+
+    if !status.success() {
+        return Err(failure(run, status.code().unwrap_or(-1)));
+    }
+
+    Ok(format!("{}\n{}", header, output))
+}
+
+fn header(run: &ScriptRun) -> String {
+    format!(
+        "$ {}@{} {}: {}",
+        run.name, run.version, run.event, run.script
+    )
+}
+
+/// The error of a script that exited with `code`, native addon builds getting their own.
+fn failure(run: &ScriptRun, code: i32) -> miette::Report {
+    if run.node_gyp.is_some() && uses_node_gyp(run.script) {
+        ScriptError::NativeBuild {
+            name: run.name.to_string(),
+            code,
+        }
+        .into()
+    } else {
+        ScriptError::Failed {
+            name: run.name.to_string(),
+            script: run.event.to_string(),
+            code,
+        }
+        .into()
+    }
+}
+
+/// The shell command of a script, with the environment npm gives scripts.
+fn script_command(config: &VoltConfig, run: &ScriptRun) -> Result<Command> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/d", "/s", "/c", run.script]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", run.script]);
+        command
+    };
+
+    tracing::debug!(
+        "running {} of {} in {}",
+        run.event,
+        run.name,
+        run.cwd.display()
+    );
+
+    // the scripts of packages that aren't on disk (yet) still get their name and version
+    let package_json = read_manifest(run.cwd)
+        .unwrap_or_else(|| json!({ "name": run.name, "version": run.version }));
+
+    let extra_bins: Vec<PathBuf> = run
+        .node_gyp
+        .iter()
+        .map(|node_gyp| node_gyp.bin_dir.clone())
+        .collect();
+
+    command
+        .current_dir(run.cwd)
+        .envs(command_env(config, run.cwd, &package_json, &extra_bins)?)
+        .env("npm_lifecycle_event", run.event)
+        .env("npm_lifecycle_script", run.script);
+
+    if let Some(node_gyp) = run.node_gyp {
+        command.envs(node_gyp.env());
+    }
+
+    Ok(command)
+}
+
+/// The package.json in `directory`, if there is a readable one.
+pub fn read_manifest(directory: &Path) -> Option<Value> {
+    std::fs::read(directory.join("package.json"))
+        .ok()
+        .and_then(|package_json| serde_json::from_slice(&package_json).ok())
+}
+
+/// The environment npm runs scripts and commands in `cwd` with: `PATH` starting with the
+/// `node_modules/.bin` of `cwd` and of the project (then `extra_bins`), the variables of
+/// `package_json` and `npm_package_json`, `INIT_CWD`, `npm_execpath` and
+/// `npm_config_user_agent`.
+pub fn command_env(
+    config: &VoltConfig,
+    cwd: &Path,
+    package_json: &Value,
+    extra_bins: &[PathBuf],
+) -> Result<Vec<(String, OsString)>> {
+    let mut bin_dirs = bin_dirs(config, cwd)?;
+    bin_dirs.extend_from_slice(extra_bins);
+
+    let mut env: Vec<(String, OsString)> = vec![("PATH".to_string(), path_with_bins(&bin_dirs)?)];
+
+    env.extend(
+        package_env(package_json)
+            .into_iter()
+            .map(|(key, value)| (key, OsString::from(value))),
+    );
+
+    env.push((
+        "npm_package_json".to_string(),
+        cwd.join("package.json").into_os_string(),
+    ));
+
+    if let Ok(init_cwd) = env::current_dir() {
+        env.push(("INIT_CWD".to_string(), init_cwd.into_os_string()));
+    }
+
+    if let Ok(volt) = env::current_exe() {
+        env.push(("npm_execpath".to_string(), volt.into_os_string()));
+    }
+
+    env.push((
+        "npm_config_user_agent".to_string(),
+        OsString::from(format!(
+            "volt/{} {} {}",
+            env!("CARGO_PKG_VERSION"),
+            env::consts::OS,
+            env::consts::ARCH
+        )),
+    ));
+
+    Ok(env)
+}
+
+/// The `node_modules/.bin` directories of `cwd` and of the project, the ones commands run
+/// in `cwd` find executables in first.
+pub fn bin_dirs(config: &VoltConfig, cwd: &Path) -> Result<Vec<PathBuf>> {
+    let mut bin_dirs = vec![cwd.join("node_modules").join(".bin")];
+    let project = config.node_modules()?.join(".bin");
+
+    if !bin_dirs.contains(&project) {
+        bin_dirs.push(project);
+    }
+
+    Ok(bin_dirs)
+}
+
+/// Run `command`, printing each line it writes to stdout or stderr after `prefix`, so that
+/// the output of commands run at once can be told apart. With `stderr_only`, lines of
+/// stdout go to stderr too.
+///
+/// Returns the exit status and every line of output, without the prefix, in the order
+/// they were printed.
+pub fn status_prefixed(
+    command: &mut Command,
+    prefix: &str,
+    stderr_only: bool,
+) -> std::io::Result<(ExitStatus, String)> {
+    fn forward(
+        reader: impl Read + Send + 'static,
+        prefix: String,
+        stderr: bool,
+        output: Arc<Mutex<String>>,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+
+                // under the lock, so that the captured lines keep the printed order
+                let mut output = output.lock().unwrap();
+
+                if stderr {
+                    eprintln!("{} {}", prefix, line);
+                } else {
+                    println!("{} {}", prefix, line);
+                }
+
+                output.push_str(&line);
+                output.push('\n');
+            }
+        })
+    }
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let output = Arc::new(Mutex::new(String::new()));
+    let mut readers = vec![];
+
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(forward(
+            stdout,
+            prefix.to_string(),
+            stderr_only,
+            output.clone(),
+        ));
+    }
+
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(forward(stderr, prefix.to_string(), true, output.clone()));
+    }
+
+    let status = child.wait()?;
+
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let output = output.lock().unwrap().clone();
+
+    Ok((status, output))
+}
+
+/// Order the packages of a tree so that every package comes after its dependencies.
+///
+/// Keys are in the `name@version` form used by the registry response.
+pub fn topological_order(tree: &HashMap<String, VoltPackage>) -> Vec<String> {
+    fn visit(
+        key: &str,
+        tree: &HashMap<String, VoltPackage>,
+        visited: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) {
+        if !visited.insert(key.to_string()) {
+            return;
+        }
+
+        if let Some(package) = tree.get(key) {
+            if let Some(dependencies) = &package.dependencies {
+                // sort so that the order is stable across runs
+                let dependencies: BTreeMap<_, _> = dependencies.iter().collect();
+
+                for (name, version) in dependencies {
+                    visit(&format!("{}@{}", name, version), tree, visited, order);
+                }
+            }
+
+            order.push(key.to_string());
+        }
+    }
+
+    let mut keys: Vec<&String> = tree.keys().collect();
+    keys.sort();
+
+    let mut visited = HashSet::with_capacity(tree.len());
+    let mut order = Vec::with_capacity(tree.len());
+
+    for key in keys {
+        visit(key, tree, &mut visited, &mut order);
+    }
+
+    order
+}
+
+/// The install scripts of a package in `directory`, in the order they run: its own, and
+/// the implicit `node-gyp rebuild` of a native addon without an install script.
+pub fn dependency_scripts(package: &VoltPackage, directory: &Path) -> Vec<(&'static str, String)> {
+    let scripts = package.scripts.clone().unwrap_or_default();
+
+    let mut events: Vec<(&'static str, String)> = LifecycleEvent::DEPENDENCY
+        .iter()
+        .filter_map(|event| {
+            let script = scripts.get(event.as_str())?;
+
+            Some((event.as_str(), script.clone()))
+        })
+        .collect();
+
+    let builds_itself = scripts.contains_key(LifecycleEvent::Install.as_str())
+        || scripts.contains_key(LifecycleEvent::PreInstall.as_str());
+
+    // without `preinstall` or `install`, `install` runs first
+    if !builds_itself && has_binding(directory) {
+        events.insert(
+            0,
+            (
+                LifecycleEvent::Install.as_str(),
+                IMPLICIT_INSTALL.to_string(),
+            ),
+        );
+    }
+
+    events
+}
+
+/// Whether the scripts of the dependency `name` run without asking: `ignore-scripts` is
+/// off, or `allow-scripts` has it.
+pub fn scripts_allowed(config: &VoltConfig, name: &str) -> bool {
+    let settings = config.settings();
+
+    !settings.ignore_scripts || matches_patterns(&settings.allow_scripts, name)
+}
+
+/// The packages of the tree whose install scripts may run.
+///
+/// With `ignore-scripts` (the default) only local packages and those matching
+/// `allow-scripts` run theirs; the others are listed and, when the reporter can ask (on a
+/// terminal), the user is asked whether to run them anyway.
+pub fn allowed_scripts(
+    config: &VoltConfig,
+    tree: &HashMap<String, VoltPackage>,
+    linker: &dyn Linker,
+) -> Result<HashSet<String>> {
+    let mut with_scripts: Vec<(&String, Vec<(&str, String)>)> = tree
+        .iter()
+        .filter_map(|(key, package)| {
+            let directory = linker.directories(key).into_iter().next()?;
+            let scripts = dependency_scripts(package, &directory);
+
+            (!scripts.is_empty()).then(|| (key, scripts))
+        })
+        .collect();
+
+    with_scripts.sort();
+
+    let settings = config.settings();
+
+    if !settings.ignore_scripts {
+        return Ok(with_scripts
+            .into_iter()
+            .map(|(key, _)| key.clone())
+            .collect());
+    }
+
+    let (allowed, blocked): (Vec<_>, Vec<_>) = with_scripts.into_iter().partition(|(key, _)| {
+        let package = &tree[*key];
+
+        // the project's own `file:` and `link:` packages are trusted like the project
+        package.tarball.starts_with("file:")
+            || package.tarball.starts_with("link:")
+            || scripts_allowed(config, &package.name)
+    });
+
+    let mut allowed: HashSet<String> = allowed.into_iter().map(|(key, _)| key.clone()).collect();
+
+    if blocked.is_empty() {
+        return Ok(allowed);
+    }
+
+    let packages: Vec<(String, Vec<(&str, String)>)> = blocked
+        .iter()
+        .map(|(key, scripts)| {
+            let package = &tree[*key];
+
+            (
+                format!("{}@{}", package.name, package.version),
+                scripts.clone(),
+            )
+        })
+        .collect();
+
+    if config.reporter().approve_scripts(&packages)? {
+        allowed.extend(blocked.into_iter().map(|(key, _)| key.clone()));
+
+        return Ok(allowed);
+    }
+
+    let mut names: Vec<&str> = blocked
+        .iter()
+        .map(|(key, _)| tree[*key].name.as_str())
+        .collect();
+
+    names.dedup();
+
+    tracing::warn!(
+        "skipped the install scripts of {}; allow them with `allow-scripts = [{}]` in .voltrc",
+        names.join(", "),
+        names
+            .iter()
+            .map(|name| format!("\"{}\"", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(allowed)
+}
+
+/// The scripts of one copy of a package to run.
+struct Build<'a> {
+    package: &'a VoltPackage,
+    cwd: PathBuf,
+    scripts: Vec<(&'static str, String)>,
+}
+
+impl Build<'_> {
+    #[tracing::instrument(
+        level = "debug",
+        name = "build",
+        skip_all,
+        fields(package = %format!("{}@{}", self.package.name, self.package.version))
+    )]
+    fn run(&self, config: &VoltConfig, node_gyp: Option<&NodeGyp>, quietly: bool) -> Result<()> {
+        for (event, script) in &self.scripts {
+            let run = ScriptRun {
+                name: &self.package.name,
+                version: &self.package.version,
+                cwd: &self.cwd,
+                event,
+                script,
+                node_gyp,
+            };
+
+            if quietly {
+                run_script_quietly(config, &run)?;
+            } else {
+                run_script(config, &run)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Run the install scripts of the `allowed` packages in the tree, dependencies first.
+///
+/// Packages that don't depend on each other build at once, up to `child-concurrency` of
+/// them, their output only shown if they fail.
+#[tracing::instrument(level = "debug", name = "scripts", skip_all)]
+pub async fn run_dependency_scripts(
+    config: &VoltConfig,
+    tree: &HashMap<String, VoltPackage>,
+    allowed: &HashSet<String>,
+    linker: &dyn Linker,
+) -> Result<()> {
+    let mut levels: Vec<Vec<Build>> = vec![];
+
+    // a package builds a level after the last of its dependencies that builds
+    let mut depths: HashMap<String, (usize, bool)> = HashMap::new();
+
+    for key in topological_order(tree) {
+        let package = &tree[&key];
+
+        let depth = package
+            .dependencies
+            .iter()
+            .flatten()
+            .filter_map(|(name, version)| depths.get(&format!("{}@{}", name, version)))
+            .map(|(depth, builds)| depth + usize::from(*builds))
+            .max()
+            .unwrap_or_default();
+
+        let builds: Vec<Build> = if allowed.contains(&key) {
+            // hoisted packages can have several copies, each built on its own
+            linker
+                .directories(&key)
+                .into_iter()
+                .map(|cwd| Build {
+                    package,
+                    scripts: dependency_scripts(package, &cwd),
+                    cwd,
+                })
+                .filter(|build| !build.scripts.is_empty())
+                .collect()
+        } else {
+            vec![]
+        };
+
+        depths.insert(key, (depth, !builds.is_empty()));
+
+        if !builds.is_empty() {
+            levels.resize_with(levels.len().max(depth + 1), Vec::new);
+            levels[depth].extend(builds);
+        }
+    }
+
+    let needs_node_gyp = levels.iter().flatten().any(|build| {
+        build
+            .scripts
+            .iter()
+            .any(|(_, script)| uses_node_gyp(script))
+    });
+
+    let node_gyp = if needs_node_gyp {
+        let node_gyp = NodeGyp::discover(config).await?;
+
+        if node_gyp.is_none() {
+            tracing::warn!(
+                "node-gyp wasn't found in the project or next to npm; if native addons fail to build, set `node-gyp` in .voltrc to its bin/node-gyp.js"
+            );
+        }
+
+        node_gyp
+    } else {
+        None
+    };
+
+    let concurrency = config.settings().child_concurrency;
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(concurrency)
+        .build()
+        .into_diagnostic()?;
+
+    for level in levels {
+        if level.len() == 1 || concurrency == 1 {
+            for build in &level {
+                build.run(config, node_gyp.as_ref(), false)?;
+            }
+
+            continue;
+        }
+
+        let mut failures: Vec<(String, miette::Report)> = pool.install(|| {
+            level
+                .par_iter()
+                .filter_map(|build| {
+                    let error = build.run(config, node_gyp.as_ref(), true).err()?;
+
+                    Some((build.package.name.clone(), error))
+                })
+                .collect()
+        });
+
+        match failures.len() {
+            0 => {}
+            1 => return Err(failures.remove(0).1),
+            count => {
+                return Err(ScriptError::Builds {
+                    count,
+                    packages: failures
+                        .iter()
+                        .map(|(name, _)| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                }
+                .into())
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the install scripts of the root project (including `prepare`).
+#[tracing::instrument(level = "debug", skip_all)]
+pub fn run_root_scripts(config: &VoltConfig) -> Result<()> {
+    let events = LifecycleEvent::ROOT.map(|event| event.as_str());
+
+    run_project_scripts(config, &events)
+}
+
+/// Run the scripts of the root project defined for `events`, in order.
+pub fn run_project_scripts(config: &VoltConfig, events: &[&str]) -> Result<()> {
+    let cwd = config.cwd()?;
+
+    let package_json = match PackageJson::get_from_dir(&cwd) {
+        Ok((package_json, _)) => package_json,
+        // nothing to run if the project has no package.json
+        Err(_) => return Ok(()),
+    };
+
+    if let Some(scripts) = &package_json.scripts {
+        for event in events {
+            if let Some(script) = scripts.get(*event) {
+                run_script(
+                    config,
+                    &ScriptRun {
+                        name: &package_json.name,
+                        version: &package_json.version,
+                        cwd: &cwd,
+                        event,
+                        script,
+                        node_gyp: None,
+                    },
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{package_env, topological_order};
+    use crate::utils::voltapi::VoltPackage;
+
+    use serde_json::json;
+
+    use std::collections::{BTreeMap, HashMap};
+
+    fn package(name: &str, dependencies: &[(&str, &str)]) -> VoltPackage {
+        VoltPackage::test(name, "1.0.0", dependencies)
+    }
+
+    #[test]
+    fn flattens_the_package_env_like_npm() {
+        let env = package_env(&json!({
+            "name": "app",
+            "version": "1.0.0",
+            "description": "not passed",
+            "config": { "port": 8080, "db": { "host": "localhost" }, "tags": ["a", "b"] },
+            "engines": { "node": ">=16" },
+            "bin": "cli.js",
+        }));
+
+        let expected: BTreeMap<String, String> = [
+            ("npm_package_name", "app"),
+            ("npm_package_version", "1.0.0"),
+            ("npm_package_config_port", "8080"),
+            ("npm_package_config_db_host", "localhost"),
+            ("npm_package_config_tags", "a\n\nb"),
+            ("npm_package_engines_node", ">=16"),
+            ("npm_package_bin", "cli.js"),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+        assert_eq!(env, expected);
+    }
+
+    #[test]
+    fn dependencies_come_before_dependents() {
+        let mut tree = HashMap::new();
+        tree.insert("a@1.0.0".to_string(), package("a", &[("b", "1.0.0")]));
+        tree.insert("b@1.0.0".to_string(), package("b", &[("c", "1.0.0")]));
+        tree.insert("c@1.0.0".to_string(), package("c", &[("a", "1.0.0")]));
+
+        let order = topological_order(&tree);
+
+        assert_eq!(order, vec!["c@1.0.0", "b@1.0.0", "a@1.0.0"]);
+    }
+}
@@ -0,0 +1,620 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! How installed packages are laid out in `node_modules`.
+//!
+//! - `isolated` (the default): every package is extracted once into the `.volt` virtual
+//!   store (`node_modules/.volt/send@0.17.2/node_modules/send`) next to symlinks to its own
+//!   dependencies, and only the requested packages are linked into `node_modules`, so the
+//!   project can't use what it doesn't depend on.
+//! - `hoisted`: npm's flat layout, one version of every package in `node_modules/<name>`
+//!   and the versions that conflict with it nested in the `node_modules` of their
+//!   dependents. For tools that don't follow symlinks.
+//!
+//! Which packages are hoisted is controlled by name patterns (`*` matches anything,
+//! `!` excludes):
+//! - `hoist-pattern` (`*` by default): the packages hoisted to the top of `node_modules`
+//!   with the hoisted linker, and to `node_modules/.volt/node_modules` with the isolated
+//!   one, where packages of the store that use what they don't declare still find it.
+//! - `public-hoist-pattern` (none by default): the packages the isolated linker also links
+//!   into `node_modules`, for tools that expect their plugins there (`*eslint*`).
+
+use crate::{
+    config::VoltConfig,
+    install::{link_directory, Resolution},
+    paths::{case_collision, is_case_sensitive, long_path},
+    utils::{
+        errors::{FilesystemError, VoltError},
+        glob, link_dependencies,
+        voltapi::dependency_key,
+    },
+};
+
+use miette::Result;
+use node_semver::Version;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The layouts of `node_modules`, chosen with `--node-linker` or the `node-linker` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ArgEnum))]
+pub enum NodeLinker {
+    Isolated,
+    Hoisted,
+}
+
+impl Default for NodeLinker {
+    fn default() -> Self {
+        Self::Isolated
+    }
+}
+
+impl fmt::Display for NodeLinker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let linker = match self {
+            Self::Isolated => "isolated",
+            Self::Hoisted => "hoisted",
+        };
+
+        write!(f, "{}", linker)
+    }
+}
+
+impl FromStr for NodeLinker {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "isolated" => Ok(Self::Isolated),
+            "hoisted" => Ok(Self::Hoisted),
+            _ => Err("`isolated` or `hoisted`"),
+        }
+    }
+}
+
+/// Places the packages of a resolution in `node_modules`.
+pub trait Linker: Send + Sync {
+    /// The directories the contents of the package `key` are written to: the first is the
+    /// one its executables point to, and its scripts run in each. Packages linked from a
+    /// local directory (`link:../foo`) are only read from theirs.
+    fn directories(&self, key: &str) -> Vec<PathBuf>;
+
+    /// Create the directories of every package, removing whatever was in the way.
+    fn prepare(&self, resolution: &Resolution) -> Result<()>;
+
+    /// Make every extracted package resolvable from its dependents, and the requested ones
+    /// from the project.
+    fn link(&self, resolution: &Resolution) -> Result<()>;
+
+    /// Every directory and link the layout has in `node_modules`, to tell what's left over
+    /// from earlier installs.
+    fn paths(&self, resolution: &Resolution) -> BTreeSet<PathBuf>;
+}
+
+/// The linker chosen for `config`, with the place of every package of `resolution`; fails
+/// when two packages would land in the same place on a filesystem that doesn't tell case
+/// apart.
+pub fn linker(config: &VoltConfig, resolution: &Resolution) -> Result<Box<dyn Linker>> {
+    let node_modules = config.node_modules()?;
+    let settings = config.settings();
+
+    let linker: Box<dyn Linker> = match settings.node_linker {
+        NodeLinker::Isolated => Box::new(IsolatedLinker::new(
+            node_modules.clone(),
+            resolution,
+            &settings.hoist_pattern,
+            &settings.public_hoist_pattern,
+        )),
+        NodeLinker::Hoisted => Box::new(HoistedLinker::new(
+            node_modules.clone(),
+            resolution,
+            &settings.hoist_pattern,
+        )),
+    };
+
+    if !is_case_sensitive(&config.cwd()?) {
+        if let Some((first, second)) = case_collision(&linker.paths(resolution)) {
+            let relative = |path: &Path| {
+                path.strip_prefix(&node_modules)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            };
+
+            return Err(VoltError::CaseCollision {
+                first: relative(&first),
+                second: relative(&second),
+            }
+            .into());
+        }
+    }
+
+    Ok(linker)
+}
+
+/// Whether a package name matches hoisting patterns: any of them, and none of those
+/// starting with `!`.
+pub fn matches_patterns(patterns: &[String], name: &str) -> bool {
+    // `*` stands for anything here, scopes included (`*eslint*`, `@types/*`)
+    let matches = |pattern: &str| glob::matches(&pattern.replace('*', "**"), name);
+
+    let (excluded, included): (Vec<&String>, Vec<&String>) = patterns
+        .iter()
+        .partition(|pattern| pattern.starts_with('!'));
+
+    included.iter().any(|pattern| matches(pattern))
+        && !excluded.iter().any(|pattern| matches(&pattern[1..]))
+}
+
+/// The version of every package that goes to the top of a hoisted tree, by the name it's
+/// depended on as: the requested one, or else the one most packages depend on (the
+/// highest of those that tie).
+pub fn top_versions(resolution: &Resolution) -> BTreeMap<String, String> {
+    let mut dependents: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+
+    for package in resolution.tree.values() {
+        for (name, version) in package.installed_dependencies() {
+            let key = dependency_key(name, version);
+
+            if resolution.tree.contains_key(&key) {
+                *dependents
+                    .entry(name.to_string())
+                    .or_default()
+                    .entry(key)
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let version = |key: &str| {
+        resolution
+            .tree
+            .get(key)
+            .and_then(|package| Version::parse(&package.version).ok())
+    };
+
+    let mut top: BTreeMap<String, String> = dependents
+        .into_iter()
+        .filter_map(|(name, keys)| {
+            let key = keys
+                .into_iter()
+                .max_by(|(a, a_count), (b, b_count)| {
+                    a_count
+                        .cmp(b_count)
+                        .then_with(|| version(a).cmp(&version(b)))
+                })?
+                .0;
+
+            Some((name, key))
+        })
+        .collect();
+
+    for package in resolution.direct_packages() {
+        top.insert(package.name.clone(), package.key());
+    }
+
+    top.extend(resolution.aliases.clone());
+
+    top
+}
+
+/// Packages in the `.volt` virtual store, linked to each other.
+pub struct IsolatedLinker {
+    node_modules: PathBuf,
+    directories: HashMap<String, PathBuf>,
+    /// Links in `node_modules/.volt/node_modules`: name -> key
+    hoisted: BTreeMap<String, String>,
+    /// Links in `node_modules` on top of the requested packages: name -> key
+    public: BTreeMap<String, String>,
+}
+
+impl IsolatedLinker {
+    pub fn new(
+        node_modules: PathBuf,
+        resolution: &Resolution,
+        hoist_pattern: &[String],
+        public_hoist_pattern: &[String],
+    ) -> Self {
+        let directories = resolution
+            .tree
+            .iter()
+            .map(|(key, package)| (key.clone(), package.install_directory(&node_modules)))
+            .collect();
+
+        let top = top_versions(resolution);
+
+        let hoisted = top
+            .iter()
+            .filter(|(name, _)| matches_patterns(hoist_pattern, name))
+            .map(|(name, key)| (name.clone(), key.clone()))
+            .collect();
+
+        let public = top
+            .into_iter()
+            .filter(|(name, key)| {
+                !resolution.direct.contains(key)
+                    && !resolution.aliases.contains_key(name)
+                    && matches_patterns(public_hoist_pattern, name)
+            })
+            .collect();
+
+        Self {
+            node_modules,
+            directories,
+            hoisted,
+            public,
+        }
+    }
+}
+
+impl Linker for IsolatedLinker {
+    fn directories(&self, key: &str) -> Vec<PathBuf> {
+        self.directories.get(key).cloned().into_iter().collect()
+    }
+
+    fn prepare(&self, resolution: &Resolution) -> Result<()> {
+        for (key, package) in &resolution.tree {
+            if package.is_link() {
+                continue;
+            }
+
+            // node_modules/.volt/@scope+name@1.0.0/node_modules/@scope/name
+            for directory in self.directories(key) {
+                create_directory(&directory)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn link(&self, resolution: &Resolution) -> Result<()> {
+        for package in resolution
+            .tree
+            .values()
+            .filter(|package| !package.is_link())
+        {
+            link_dependencies(package, &self.node_modules)?;
+        }
+
+        for (key, package) in &resolution.tree {
+            if resolution.direct.contains(key) {
+                link_directory(
+                    &self.directories[key],
+                    &self.node_modules.join(&package.name),
+                )?;
+            }
+        }
+
+        for (alias, key) in &resolution.aliases {
+            if let Some(directory) = self.directories.get(key) {
+                link_directory(directory, &self.node_modules.join(alias))?;
+            }
+        }
+
+        let hidden = self.node_modules.join(".volt").join("node_modules");
+
+        for (name, key) in &self.hoisted {
+            if let Some(directory) = self.directories.get(key) {
+                link_directory(directory, &hidden.join(name))?;
+            }
+        }
+
+        for (name, key) in &self.public {
+            if let Some(directory) = self.directories.get(key) {
+                link_directory(directory, &self.node_modules.join(name))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn paths(&self, resolution: &Resolution) -> BTreeSet<PathBuf> {
+        let mut paths = BTreeSet::new();
+        let hidden = self.node_modules.join(".volt").join("node_modules");
+
+        for (key, package) in &resolution.tree {
+            if resolution.direct.contains(key) {
+                paths.insert(self.node_modules.join(&package.name));
+            }
+
+            if package.is_link() {
+                continue;
+            }
+
+            paths.extend(self.directories(key));
+
+            // node_modules/.volt/send@0.17.2/node_modules/ms
+            for (name, _) in package.installed_dependencies() {
+                paths.insert(
+                    self.node_modules
+                        .join(".volt")
+                        .join(package.directory_name())
+                        .join("node_modules")
+                        .join(name),
+                );
+            }
+        }
+
+        paths.extend(
+            resolution
+                .aliases
+                .keys()
+                .chain(self.public.keys())
+                .map(|name| self.node_modules.join(name)),
+        );
+
+        paths.extend(self.hoisted.keys().map(|name| hidden.join(name)));
+
+        paths
+    }
+}
+
+/// Packages in a flat `node_modules`, nesting the versions that conflict.
+pub struct HoistedLinker {
+    node_modules: PathBuf,
+    /// The package at every place, a place being the names of the directories leading to
+    /// it: `["send", "ms"]` is `node_modules/send/node_modules/ms`
+    places: BTreeMap<Vec<String>, String>,
+}
+
+impl HoistedLinker {
+    pub fn new(node_modules: PathBuf, resolution: &Resolution, hoist_pattern: &[String]) -> Self {
+        Self {
+            node_modules,
+            places: hoist(resolution, hoist_pattern),
+        }
+    }
+
+    fn path(&self, place: &[String]) -> PathBuf {
+        let mut path = self.node_modules.join(&place[0]);
+
+        for name in &place[1..] {
+            path = path.join("node_modules").join(name);
+        }
+
+        path
+    }
+}
+
+impl Linker for HoistedLinker {
+    fn directories(&self, key: &str) -> Vec<PathBuf> {
+        self.places
+            .iter()
+            .filter(|(_, placed)| *placed == key)
+            .map(|(place, _)| self.path(place))
+            .collect()
+    }
+
+    fn prepare(&self, resolution: &Resolution) -> Result<()> {
+        let places: Vec<PathBuf> = self
+            .places
+            .iter()
+            .filter(|(_, key)| resolution.tree.get(*key).map_or(false, |p| !p.is_link()))
+            .map(|(place, _)| self.path(place))
+            .collect();
+
+        // clear every place before creating any, as places are nested in each other
+        for path in &places {
+            remove_path(path)?;
+        }
+
+        for path in &places {
+            create_directory(path)?;
+        }
+
+        Ok(())
+    }
+
+    fn link(&self, resolution: &Resolution) -> Result<()> {
+        // the other packages are extracted in place
+        for (place, key) in &self.places {
+            if let Some(package) = resolution.tree.get(key).filter(|p| p.is_link()) {
+                link_directory(
+                    &package.install_directory(&self.node_modules),
+                    &self.path(place),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn paths(&self, _resolution: &Resolution) -> BTreeSet<PathBuf> {
+        self.places.keys().map(|place| self.path(place)).collect()
+    }
+}
+
+/// Place every package of `resolution`: the top version of every package matching
+/// `hoist_pattern` (and every requested one) at the top, then the dependencies of each
+/// placed package breadth first, nested in its `node_modules` unless node already finds
+/// the version needed in a `node_modules` above it.
+fn hoist(resolution: &Resolution, hoist_pattern: &[String]) -> BTreeMap<Vec<String>, String> {
+    let mut places = BTreeMap::new();
+    let mut queue = VecDeque::new();
+
+    for (name, key) in top_versions(resolution) {
+        let requested = resolution.direct.contains(&key) || resolution.aliases.contains_key(&name);
+
+        if requested || matches_patterns(hoist_pattern, &name) {
+            places.insert(vec![name.clone()], key);
+            queue.push_back(vec![name]);
+        }
+    }
+
+    while let Some(place) = queue.pop_front() {
+        let package = &resolution.tree[&places[&place]];
+
+        // linked packages bring their own node_modules
+        if package.is_link() {
+            continue;
+        }
+
+        // sort so that the layout is stable across runs
+        let dependencies: BTreeMap<_, _> = package.installed_dependencies().collect();
+
+        for (name, version) in dependencies {
+            let key = dependency_key(name, version);
+
+            if !resolution.tree.contains_key(&key) {
+                continue;
+            }
+
+            // the closest `name` node would find from this place
+            let found = (0..=place.len()).rev().find_map(|depth| {
+                let mut candidate = place[..depth].to_vec();
+                candidate.push(name.to_string());
+
+                places.get(&candidate)
+            });
+
+            if found == Some(&key) {
+                continue;
+            }
+
+            let mut nested = place.clone();
+            nested.push(name.to_string());
+
+            places.insert(nested.clone(), key);
+            queue.push_back(nested);
+        }
+    }
+
+    places
+}
+
+fn create_directory(path: &Path) -> Result<()> {
+    std::fs::create_dir_all(long_path(path)).map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    Ok(())
+}
+
+/// Remove a directory, or a symlink without touching its target.
+pub(crate) fn remove_path(path: &Path) -> Result<()> {
+    let long = long_path(path);
+
+    let metadata = match long.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(()),
+    };
+
+    if metadata.is_dir() {
+        std::fs::remove_dir_all(&long)
+    } else {
+        std::fs::remove_file(&long).or_else(|_| std::fs::remove_dir(&long))
+    }
+    .map_err(|e| FilesystemError::Remove {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hoist, matches_patterns};
+    use crate::{install::Resolution, utils::voltapi::VoltPackage};
+
+    use std::collections::HashMap;
+
+    fn package(name: &str, version: &str, dependencies: &[(&str, &str)]) -> VoltPackage {
+        VoltPackage::test(name, version, dependencies)
+    }
+
+    fn resolution() -> Resolution {
+        let tree: HashMap<String, VoltPackage> = [
+            package("app-a", "1.0.0", &[("ms", "2.0.0"), ("debug", "4.0.0")]),
+            package("app-b", "1.0.0", &[("ms", "1.0.0")]),
+            package("app-c", "1.0.0", &[("ms", "1.0.0")]),
+            package("debug", "4.0.0", &[("ms", "2.0.0")]),
+            package("ms", "1.0.0", &[]),
+            package("ms", "2.0.0", &[]),
+        ]
+        .into_iter()
+        .map(|package| (format!("{}@{}", package.name, package.version), package))
+        .collect();
+
+        Resolution {
+            tree,
+            direct: vec![
+                "app-a@1.0.0".to_string(),
+                "app-b@1.0.0".to_string(),
+                "app-c@1.0.0".to_string(),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn places(patterns: &[&str]) -> Vec<(String, String)> {
+        let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+
+        hoist(&resolution(), &patterns)
+            .into_iter()
+            .map(|(place, key)| (place.join("/"), key))
+            .collect()
+    }
+
+    #[test]
+    fn hoists_and_nests_conflicts() {
+        // both versions of ms have two dependents, the highest goes to the top
+        assert_eq!(
+            places(&["*"]),
+            [
+                ("app-a", "app-a@1.0.0"),
+                ("app-b", "app-b@1.0.0"),
+                ("app-b/ms", "ms@1.0.0"),
+                ("app-c", "app-c@1.0.0"),
+                ("app-c/ms", "ms@1.0.0"),
+                ("debug", "debug@4.0.0"),
+                ("ms", "ms@2.0.0"),
+            ]
+            .map(|(place, key)| (place.to_string(), key.to_string()))
+        );
+
+        assert_eq!(
+            places(&["*", "!debug"]),
+            [
+                ("app-a", "app-a@1.0.0"),
+                ("app-a/debug", "debug@4.0.0"),
+                ("app-b", "app-b@1.0.0"),
+                ("app-b/ms", "ms@1.0.0"),
+                ("app-c", "app-c@1.0.0"),
+                ("app-c/ms", "ms@1.0.0"),
+                ("ms", "ms@2.0.0"),
+            ]
+            .map(|(place, key)| (place.to_string(), key.to_string()))
+        );
+    }
+
+    #[test]
+    fn matches_hoist_patterns() {
+        let patterns = ["*eslint*".to_string(), "!@types/*".to_string()];
+
+        assert!(matches_patterns(&patterns, "eslint-plugin-react"));
+        assert!(matches_patterns(&patterns, "@typescript-eslint/parser"));
+        assert!(!matches_patterns(&patterns, "@types/eslint"));
+        assert!(!matches_patterns(&patterns, "prettier"));
+        assert!(!matches_patterns(&[], "eslint"));
+    }
+}
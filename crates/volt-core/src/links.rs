@@ -0,0 +1,294 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Packages linked for local development, tracked in `~/.volt/links.json`.
+//!
+//! `volt link` in a package registers its directory under its name, and links it into
+//! the global prefix so its executables are on the global bin directory. `volt link
+//! <name>` in a project then symlinks that directory into the project's node_modules,
+//! so changes to the package show up without publishing it.
+
+use crate::{
+    config::VoltConfig,
+    install::link_directory,
+    install_state::InstallState,
+    shim::{link_bins, unlink_bins},
+    utils::{errors::FilesystemError, package::PackageJson},
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde::{Deserialize, Serialize};
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// What `links.json` records.
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Links {
+    /// Name -> directory of the packages registered with `volt link`
+    #[serde(default)]
+    pub packages: BTreeMap<String, PathBuf>,
+    /// Project directory -> names of the registered packages linked into it
+    #[serde(default)]
+    pub projects: BTreeMap<PathBuf, BTreeSet<String>>,
+}
+
+impl Links {
+    fn path(config: &VoltConfig) -> Result<PathBuf> {
+        Ok(config.volt_home()?.join("links.json"))
+    }
+
+    /// Read `links.json` (empty if nothing was linked yet).
+    pub fn load(config: &VoltConfig) -> Result<Self> {
+        let path = Self::path(config)?;
+
+        match std::fs::read(&path) {
+            Ok(links) => serde_json::from_slice(&links).into_diagnostic(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(FilesystemError::Read {
+                source: e,
+                path: path.display().to_string(),
+            }
+            .into()),
+        }
+    }
+
+    pub fn save(&self, config: &VoltConfig) -> Result<()> {
+        let path = Self::path(config)?;
+
+        let write_error = |e| FilesystemError::Write {
+            source: e,
+            path: path.display().to_string(),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(write_error)?;
+        }
+
+        let links = serde_json::to_vec_pretty(self).into_diagnostic()?;
+
+        std::fs::write(&path, links).map_err(write_error)?;
+
+        Ok(())
+    }
+
+    /// The registered package `node_modules/<name>` of `project` links to, if it does.
+    pub fn linked(&self, project: &Path, name: &str) -> Option<&PathBuf> {
+        let registered = self.packages.get(name)?;
+        let installed = project
+            .join("node_modules")
+            .join(name)
+            .canonicalize()
+            .ok()?;
+
+        (installed == *registered).then(|| registered)
+    }
+}
+
+/// The canonical form of `directory`, so the same project is recorded once.
+fn canonical(directory: &Path) -> Result<PathBuf> {
+    directory.canonicalize().map_err(|e| {
+        FilesystemError::Read {
+            source: e,
+            path: directory.display().to_string(),
+        }
+        .into()
+    })
+}
+
+/// Register the package in `directory` and link it into the global prefix. Returns its
+/// name.
+pub fn register(config: &VoltConfig, directory: &Path) -> Result<String> {
+    let directory = canonical(directory)?;
+    let (package_json, _) = PackageJson::get_from_dir(&directory)?;
+
+    let global = config.global_prefix()?.join("node_modules");
+
+    link_directory(&directory, &global.join(&package_json.name))?;
+
+    if let Some(bin) = package_json.bin {
+        link_bins(
+            &config.global_bin()?,
+            &directory,
+            &package_json.name,
+            &bin.into(),
+        )?;
+    }
+
+    let mut links = Links::load(config)?;
+
+    links.packages.insert(package_json.name.clone(), directory);
+
+    links.save(config)?;
+
+    Ok(package_json.name)
+}
+
+/// Forget the package registered as `name`, removing it from the global prefix. Returns
+/// the projects it is still linked into.
+pub fn unregister(config: &VoltConfig, name: &str) -> Result<Vec<PathBuf>> {
+    let mut links = Links::load(config)?;
+
+    let directory = match links.packages.remove(name) {
+        Some(directory) => directory,
+        None => {
+            tracing::warn!("{} is not linked", name);
+
+            return Ok(vec![]);
+        }
+    };
+
+    if let Ok((package_json, _)) = PackageJson::get_from_dir(&directory) {
+        if let Some(bin) = package_json.bin {
+            unlink_bins(&config.global_bin()?, name, &bin.into())?;
+        }
+    }
+
+    remove_link(&config.global_prefix()?.join("node_modules").join(name))?;
+
+    let projects = links
+        .projects
+        .iter()
+        .filter(|(_, names)| names.contains(name))
+        .map(|(project, _)| project.clone())
+        .collect();
+
+    links.save(config)?;
+
+    Ok(projects)
+}
+
+/// Symlink the registered package `name` into the project's node_modules, with its
+/// executables in `node_modules/.bin`. Returns the directory linked to.
+pub fn link_into(config: &VoltConfig, name: &str) -> Result<Option<PathBuf>> {
+    let mut links = Links::load(config)?;
+
+    let directory = match links.packages.get(name) {
+        Some(directory) => directory.clone(),
+        None => return Ok(None),
+    };
+
+    let node_modules = config.node_modules()?;
+
+    InstallState::clear(config)?;
+
+    link_directory(&directory, &node_modules.join(name))?;
+
+    if let Ok((package_json, _)) = PackageJson::get_from_dir(&directory) {
+        if let Some(bin) = package_json.bin {
+            link_bins(&node_modules.join(".bin"), &directory, name, &bin.into())?;
+        }
+    }
+
+    links
+        .projects
+        .entry(canonical(&config.cwd()?)?)
+        .or_default()
+        .insert(name.to_string());
+
+    links.save(config)?;
+
+    Ok(Some(directory))
+}
+
+/// Remove the link to `name` from the project's node_modules. Returns whether it was
+/// linked.
+pub fn unlink_from(config: &VoltConfig, name: &str) -> Result<bool> {
+    let mut links = Links::load(config)?;
+    let project = canonical(&config.cwd()?)?;
+    let node_modules = config.node_modules()?;
+
+    let linked = links.linked(&project, name).is_some();
+
+    if linked {
+        InstallState::clear(config)?;
+
+        let package_dir = node_modules.join(name);
+
+        if let Ok((package_json, _)) = PackageJson::get_from_dir(&package_dir) {
+            if let Some(bin) = package_json.bin {
+                unlink_bins(&node_modules.join(".bin"), name, &bin.into())?;
+            }
+        }
+
+        remove_link(&package_dir)?;
+    }
+
+    if let Some(names) = links.projects.get_mut(&project) {
+        names.remove(name);
+
+        if names.is_empty() {
+            links.projects.remove(&project);
+        }
+    }
+
+    links.save(config)?;
+
+    Ok(linked)
+}
+
+/// Remove a symlink (or junction) without touching its target.
+fn remove_link(link: &Path) -> Result<()> {
+    if link.symlink_metadata().is_err() {
+        return Ok(());
+    }
+
+    std::fs::remove_file(link)
+        .or_else(|_| std::fs::remove_dir(link))
+        .map_err(|e| {
+            FilesystemError::Remove {
+                source: e,
+                path: link.display().to_string(),
+            }
+            .into()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Links;
+
+    #[test]
+    fn flags_packages_linked_into_a_project() {
+        let directory = tempfile::tempdir().unwrap();
+        let package = directory.path().join("a");
+        let project = directory.path().join("app");
+
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::create_dir_all(project.join("node_modules/b")).unwrap();
+
+        crate::install::link_directory(&package, &project.join("node_modules/a")).unwrap();
+
+        let mut links = Links::default();
+
+        links
+            .packages
+            .insert("a".to_string(), package.canonicalize().unwrap());
+        links
+            .packages
+            .insert("b".to_string(), directory.path().join("b"));
+
+        assert_eq!(links.linked(&project, "a"), links.packages.get("a"));
+        // installed from the registry rather than linked
+        assert_eq!(links.linked(&project, "b"), None);
+
+        let saved = serde_json::to_string(&links).unwrap();
+
+        assert_eq!(serde_json::from_str::<Links>(&saved).unwrap(), links);
+    }
+}
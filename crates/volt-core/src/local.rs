@@ -0,0 +1,140 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Packages installed from the local filesystem.
+//!
+//! `file:` packages (a directory or a tarball) are packed and installed into the virtual
+//! store like any other package, with the integrity of the packed tarball recorded in
+//! the lock file to notice when they change. `link:` packages are symlinked as they are
+//! and their dependencies are left to the linked directory.
+//!
+//! Paths are relative to the project root, as written in package.json.
+
+use crate::{
+    config::VoltConfig,
+    hashing::{hash, Algorithm},
+    install::{dependency_specs, resolve, Resolution},
+    io::{pack_directory, read_manifest},
+    utils::{package::PackageJson, voltapi::VoltPackage},
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+
+use std::{collections::HashMap, path::Path};
+
+/// Resolve a `file:` directory or tarball into its package, along with the dependencies
+/// of that package.
+pub fn resolve_file<'a>(
+    config: &'a VoltConfig,
+    path: &'a Path,
+) -> BoxFuture<'a, Result<Resolution>> {
+    async move {
+        let tarball = local_tarball(config, path)?;
+        let package_json = read_manifest(&tarball)?;
+
+        // `file:` dependencies of the package are relative to the package itself
+        let specs = dependency_specs(&package_json.dependencies.clone().unwrap_or_default())
+            .into_iter()
+            .map(|spec| relative_to(spec, path))
+            .collect::<Vec<_>>();
+
+        let mut resolution = resolve(config, &specs).await?;
+
+        let mut package =
+            VoltPackage::from_manifest(&package_json, format!("file:{}", path.display()));
+
+        package.integrity = hash(&tarball, &[Algorithm::Sha512]).to_string();
+        package.dependencies = Some(resolution.edges());
+
+        let key = package.key();
+
+        resolution.direct = vec![key.clone()];
+        resolution.aliases.clear();
+        resolution.tree.insert(key, package);
+
+        Ok(resolution)
+    }
+    .boxed()
+}
+
+/// Resolve a `link:` directory into the package it contains, without its dependencies.
+pub fn resolve_link(config: &VoltConfig, path: &Path) -> Result<Resolution> {
+    let (package_json, _) = PackageJson::get_from_dir(&config.cwd()?.join(path))?;
+
+    let mut package = VoltPackage::from_manifest(&package_json, format!("link:{}", path.display()));
+
+    // the linked directory is managed by its owner, its scripts aren't ours to run
+    package.scripts = None;
+
+    let key = package.key();
+
+    Ok(Resolution {
+        direct: vec![key.clone()],
+        tree: HashMap::from([(key, package)]),
+        ..Default::default()
+    })
+}
+
+/// The tarball of a `file:` package: the file itself, or the packed directory.
+pub fn local_tarball(config: &VoltConfig, path: &Path) -> Result<Vec<u8>> {
+    let path = config.cwd()?.join(path);
+
+    if path.is_file() {
+        std::fs::read(&path).into_diagnostic()
+    } else {
+        pack_directory(&path)
+    }
+}
+
+/// Whether a `file:` package of the tree no longer matches what was resolved.
+pub fn local_packages_changed(config: &VoltConfig, resolution: &Resolution) -> Result<bool> {
+    for package in resolution.tree.values() {
+        if let Some(path) = package.tarball.strip_prefix("file:") {
+            let tarball = match local_tarball(config, Path::new(path)) {
+                Ok(tarball) => tarball,
+                // moved or deleted
+                Err(_) => return Ok(true),
+            };
+
+            let integrity = hash(&tarball, &[Algorithm::Sha512]).to_string();
+
+            if integrity != package.integrity {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Make a path specification relative to the project root instead of `base`.
+fn relative_to(spec: PackageSpec, base: &Path) -> PackageSpec {
+    match spec {
+        PackageSpec::Dir { path } if path.is_relative() => PackageSpec::Dir {
+            path: base.join(path),
+        },
+        PackageSpec::Link { path } if path.is_relative() => PackageSpec::Link {
+            path: base.join(path),
+        },
+        PackageSpec::Alias { name, spec } => PackageSpec::Alias {
+            name,
+            spec: Box::new(relative_to(*spec, base)),
+        },
+        spec => spec,
+    }
+}
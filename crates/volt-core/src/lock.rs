@@ -0,0 +1,240 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Keep volt processes from changing the same files at once.
+//!
+//! Installs hold `node_modules/.volt.lock` exclusively and the store's `store.lock`
+//! shared: projects install one at a time, and while any install runs nothing cleans the
+//! store from under it. Commands removing from the store hold `store.lock` exclusively.
+//! The locks are advisory (`flock`, `LockFileEx`) and released with the process, so a
+//! killed volt never leaves one behind.
+//!
+//! Waiting for a lock sleeps on the runtime rather than the thread, since the commands
+//! taking one run on it.
+
+use crate::{
+    config::VoltConfig,
+    utils::errors::{FilesystemError, VoltError},
+};
+
+use miette::Result;
+
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// Name of the lock file of a project, in its `node_modules`.
+pub const PROJECT_LOCK: &str = ".volt.lock";
+
+/// Whether other processes can hold the lock too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+/// A lock on a file, released when dropped.
+#[derive(Debug)]
+pub struct ProcessLock {
+    _file: File,
+}
+
+impl ProcessLock {
+    /// Lock `path` (creating it), waiting up to `timeout` for other processes to release it.
+    pub async fn acquire(path: &Path, mode: LockMode, timeout: Duration) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| FilesystemError::CreateDir {
+                source: e,
+                path: parent.display().to_string(),
+            })?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+
+        let start = Instant::now();
+        let mut waiting = false;
+
+        loop {
+            let locked = try_lock(&file, mode).map_err(|e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+
+            if locked {
+                tracing::debug!("locked {}", path.display());
+
+                return Ok(Self { _file: file });
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(VoltError::LockTimeout {
+                    path: path.display().to_string(),
+                    seconds: timeout.as_secs(),
+                }
+                .into());
+            }
+
+            if !waiting {
+                tracing::warn!(
+                    "waiting for another volt process to release {}",
+                    path.display()
+                );
+
+                waiting = true;
+            }
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// Lock the project's `node_modules` and the store for an install, unless `--no-lock`.
+pub async fn lock_project(config: &VoltConfig) -> Result<Vec<ProcessLock>> {
+    if !config.locking() {
+        return Ok(vec![]);
+    }
+
+    let timeout = Duration::from_secs(config.settings().lock_timeout);
+
+    Ok(vec![
+        ProcessLock::acquire(
+            &config.node_modules()?.join(PROJECT_LOCK),
+            LockMode::Exclusive,
+            timeout,
+        )
+        .await?,
+        ProcessLock::acquire(
+            &config.store()?.join("store.lock"),
+            LockMode::Shared,
+            timeout,
+        )
+        .await?,
+    ])
+}
+
+/// Lock the store in `mode`, unless `--no-lock`: exclusively to remove from it, shared to
+/// only add to it.
+pub async fn lock_store(config: &VoltConfig, mode: LockMode) -> Result<Option<ProcessLock>> {
+    if !config.locking() {
+        return Ok(None);
+    }
+
+    ProcessLock::acquire(
+        &config.store()?.join("store.lock"),
+        mode,
+        Duration::from_secs(config.settings().lock_timeout),
+    )
+    .await
+    .map(Some)
+}
+
+#[cfg(unix)]
+fn try_lock(file: &File, mode: LockMode) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    let operation = match mode {
+        LockMode::Shared => libc::LOCK_SH,
+        LockMode::Exclusive => libc::LOCK_EX,
+    };
+
+    if unsafe { libc::flock(file.as_raw_fd(), operation | libc::LOCK_NB) } == 0 {
+        return Ok(true);
+    }
+
+    let error = io::Error::last_os_error();
+
+    if error.raw_os_error() == Some(libc::EWOULDBLOCK) {
+        Ok(false)
+    } else {
+        Err(error)
+    }
+}
+
+#[cfg(windows)]
+fn try_lock(file: &File, mode: LockMode) -> io::Result<bool> {
+    use std::os::windows::io::AsRawHandle;
+    use winapi::{
+        shared::winerror::ERROR_LOCK_VIOLATION,
+        um::{
+            fileapi::LockFileEx,
+            minwinbase::{LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY, OVERLAPPED},
+        },
+    };
+
+    let flags = match mode {
+        LockMode::Shared => LOCKFILE_FAIL_IMMEDIATELY,
+        LockMode::Exclusive => LOCKFILE_FAIL_IMMEDIATELY | LOCKFILE_EXCLUSIVE_LOCK,
+    };
+
+    let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+
+    let locked =
+        unsafe { LockFileEx(file.as_raw_handle() as _, flags, 0, !0, !0, &mut overlapped) };
+
+    if locked != 0 {
+        return Ok(true);
+    }
+
+    let error = io::Error::last_os_error();
+
+    if error.raw_os_error() == Some(ERROR_LOCK_VIOLATION as i32) {
+        Ok(false)
+    } else {
+        Err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LockMode, ProcessLock};
+
+    use futures::executor::block_on;
+
+    use std::{path::Path, time::Duration};
+
+    fn acquire(path: &Path, mode: LockMode) -> miette::Result<ProcessLock> {
+        // without a timeout nothing waits, so no runtime is needed
+        block_on(ProcessLock::acquire(path, mode, Duration::ZERO))
+    }
+
+    #[test]
+    fn waits_for_exclusive_locks() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(".volt.lock");
+
+        let shared = acquire(&path, LockMode::Shared).unwrap();
+
+        // each acquire opens the file again, so locks conflict like between processes
+        assert!(acquire(&path, LockMode::Shared).is_ok());
+        assert!(acquire(&path, LockMode::Exclusive).is_err());
+
+        drop(shared);
+
+        assert!(acquire(&path, LockMode::Exclusive).is_ok());
+    }
+}
@@ -0,0 +1,239 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Log in to a registry and keep its token.
+//!
+//! The web flow asks the registry for a login url (`POST /-/v1/login`), which the user
+//! opens in a browser, and polls the `doneUrl` it was given until the registry hands out
+//! a token. Registries without it (Verdaccio, older Artifactory) take the legacy CouchDB
+//! flow: the username and password are sent in `PUT /-/user/org.couchdb.user:<name>`.
+//!
+//! Either way the token is saved in the user's `.npmrc` under the key of its registry
+//! (`//registry.npmjs.org/:_authToken`), where [`crate::registry`] reads it, and the
+//! file is only readable by the user.
+
+use crate::{
+    registry::{token_key, RegistryClient},
+    utils::errors::{FilesystemError, NetworkError},
+};
+
+use miette::{IntoDiagnostic, Result};
+use reqwest::{header::RETRY_AFTER, Method, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+
+use std::{path::Path, time::Duration};
+
+/// Seconds between polls of a web login when the registry doesn't say.
+const DEFAULT_POLL_INTERVAL: u64 = 1;
+
+/// How to log in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ArgEnum))]
+pub enum AuthType {
+    /// In a browser, falling back to `legacy` on registries without it
+    Web,
+    /// With a username and password
+    Legacy,
+}
+
+/// A web login in progress.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebLogin {
+    /// Where the user logs in
+    pub login_url: String,
+    /// Polled until the login is done
+    pub done_url: String,
+}
+
+#[derive(Deserialize)]
+struct TokenBody {
+    token: Option<String>,
+}
+
+/// Start a web login; `None` if the registry only has the legacy flow.
+pub async fn start_web_login(client: &RegistryClient, hostname: &str) -> Result<Option<WebLogin>> {
+    let request = client
+        .request(Method::POST, "-/v1/login")
+        .json(&json!({ "hostname": hostname }));
+
+    let response = match client.send(request).await {
+        Ok(response) => response,
+        Err(error) => {
+            return match error.downcast_ref::<NetworkError>() {
+                Some(NetworkError::Registry {
+                    status: 404 | 405 | 501,
+                    ..
+                }) => Ok(None),
+                _ => Err(error),
+            }
+        }
+    };
+
+    Ok(Some(response.json().await.into_diagnostic()?))
+}
+
+/// Poll a web login until the user is done with it; the token.
+pub async fn finish_web_login(client: &RegistryClient, login: &WebLogin) -> Result<String> {
+    loop {
+        let response = client
+            .send(client.request_url(Method::GET, &login.done_url))
+            .await?;
+
+        // `202 Accepted` while the user hasn't logged in yet
+        if response.status() == StatusCode::ACCEPTED {
+            let seconds = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_POLL_INTERVAL);
+
+            tokio::time::sleep(Duration::from_secs(seconds)).await;
+
+            continue;
+        }
+
+        let body: TokenBody = response.json().await.into_diagnostic()?;
+
+        return token(client, body);
+    }
+}
+
+/// Log in with a username and password (which creates the user on registries that allow
+/// it); the token. `username` must be url-safe.
+pub async fn legacy_login(
+    client: &RegistryClient,
+    username: &str,
+    password: &str,
+    email: Option<&str>,
+) -> Result<String> {
+    let id = format!("org.couchdb.user:{}", username);
+
+    let request = client
+        .request(Method::PUT, &format!("-/user/{}", id))
+        .json(&json!({
+            "_id": id,
+            "name": username,
+            "password": password,
+            "email": email,
+            "type": "user",
+            "roles": [],
+        }));
+
+    let body: TokenBody = client.send(request).await?.json().await.into_diagnostic()?;
+
+    token(client, body)
+}
+
+fn token(client: &RegistryClient, body: TokenBody) -> Result<String> {
+    body.token
+        .ok_or_else(|| miette::miette!("{} didn't send a token back", client.url))
+}
+
+/// Save the token of `registry` in the `.npmrc` at `path`, replacing the one it had,
+/// with the file only readable by the user.
+pub fn save_token(path: &Path, registry: &str, token: &str) -> Result<()> {
+    let key = token_key(registry);
+
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    };
+
+    let contents = if path.is_file() {
+        std::fs::read_to_string(path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?
+    } else {
+        String::new()
+    };
+
+    let mut lines: Vec<String> = contents
+        .lines()
+        .filter(|line| {
+            line.split_once('=')
+                .map_or(true, |(name, _)| name.trim() != key)
+        })
+        .map(ToString::to_string)
+        .collect();
+
+    lines.push(format!("{}={}", key, token));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path).map_err(write_error)?;
+
+    // the mode only applies to new files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(write_error)?;
+    }
+
+    std::io::Write::write_all(&mut file, format!("{}\n", lines.join("\n")).as_bytes())
+        .map_err(write_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::save_token;
+
+    #[test]
+    fn replaces_the_token_of_the_registry() {
+        let home = tempfile::tempdir().unwrap();
+        let npmrc = home.path().join(".npmrc");
+
+        std::fs::write(
+            &npmrc,
+            "registry=https://npm.example.com/\n//npm.example.com/:_authToken=old\n",
+        )
+        .unwrap();
+
+        save_token(&npmrc, "https://npm.example.com/", "new").unwrap();
+        save_token(&npmrc, "https://registry.npmjs.org/", "npm").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&npmrc).unwrap(),
+            "registry=https://npm.example.com/\n\
+             //npm.example.com/:_authToken=new\n\
+             //registry.npmjs.org/:_authToken=npm\n"
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = std::fs::metadata(&npmrc).unwrap().permissions().mode();
+
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Package documents of the registry kept on disk, revalidated with conditional requests.
+//!
+//! Each document is saved in `<store>/metadata/<registry>/` with the `ETag` and
+//! `Last-Modified` the registry sent along. The next request for it carries them as
+//! `If-None-Match` and `If-Modified-Since`; when the package hasn't changed the registry
+//! answers `304 Not Modified` without a body, and the saved document is used. The full
+//! document and the abbreviated one (`application/vnd.npm.install-v1+json`) are kept
+//! apart. `volt cache metadata clear` empties the cache, along with the cached
+//! resolutions of [`crate::resolution_cache`].
+
+use crate::{io::directory_size, utils::errors::FilesystemError};
+
+use miette::Result;
+use reqwest::{
+    header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    RequestBuilder,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use std::path::{Path, PathBuf};
+
+/// A package document with the validators it was sent with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedDocument {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    pub document: Value,
+}
+
+impl CachedDocument {
+    /// The document of a response, with its validators, if it has any worth keeping.
+    pub fn new(headers: &HeaderMap, document: Value) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            etag,
+            last_modified,
+            document,
+        })
+    }
+
+    /// `request`, sent only for a document that changed since this one.
+    pub fn conditional(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+
+        request
+    }
+}
+
+/// The cached documents of one registry.
+#[derive(Debug, Clone)]
+pub struct MetadataCache {
+    dir: PathBuf,
+}
+
+impl MetadataCache {
+    /// The cache of `registry` in the store at `store`.
+    pub fn new(store: &Path, registry: &str) -> Self {
+        // registries are told apart by a hash of their url, which can't be a file name
+        let registry = hex::encode(&Sha256::digest(registry.as_bytes())[..8]);
+
+        Self {
+            dir: metadata_dir(store).join(registry),
+        }
+    }
+
+    /// The saved document of `name`, abbreviated or full, if there is a readable one.
+    pub fn get(&self, name: &str, abbreviated: bool) -> Option<CachedDocument> {
+        let contents = std::fs::read(self.path(name, abbreviated)).ok()?;
+
+        serde_json::from_slice(&contents).ok()
+    }
+
+    /// Save the document of `name`. The cache only spares requests, so failing to write
+    /// it is logged rather than reported.
+    pub fn put(&self, name: &str, abbreviated: bool, document: &CachedDocument) {
+        let path = self.path(name, abbreviated);
+
+        if let Err(error) = write_json(&path, document) {
+            tracing::debug!("failed to cache {}: {:?}", path.display(), error);
+        }
+    }
+
+    fn path(&self, name: &str, abbreviated: bool) -> PathBuf {
+        let kind = if abbreviated { "abbreviated" } else { "full" };
+
+        self.dir
+            .join(format!("{}.{}.json", name.replace('/', "%2f"), kind))
+    }
+}
+
+/// Directory of the cached documents of every registry.
+pub fn metadata_dir(store: &Path) -> PathBuf {
+    store.join("metadata")
+}
+
+/// Remove every cached document; the bytes reclaimed.
+pub fn clear(store: &Path) -> Result<u64> {
+    let dir = metadata_dir(store);
+
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let bytes = directory_size(&dir);
+
+    std::fs::remove_dir_all(&dir).map_err(|e| FilesystemError::Remove {
+        source: e,
+        path: dir.display().to_string(),
+    })?;
+
+    Ok(bytes)
+}
+
+/// Write `value` to `path` as JSON, whole: aside first, then renamed over it.
+pub(crate) fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(write_error)?;
+    }
+
+    let partial = path.with_extension(format!("{}.partial", std::process::id()));
+
+    std::fs::write(
+        &partial,
+        serde_json::to_vec(value).map_err(|e| miette::miette!("{}", e))?,
+    )
+    .map_err(write_error)?;
+
+    std::fs::rename(&partial, path).map_err(write_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear, CachedDocument, MetadataCache};
+
+    use reqwest::header::{HeaderMap, HeaderValue, ETAG};
+    use serde_json::json;
+
+    #[test]
+    fn keeps_documents_with_their_validators() {
+        let store = tempfile::tempdir().unwrap();
+
+        let npm = MetadataCache::new(store.path(), "https://registry.npmjs.org/");
+        let mirror = MetadataCache::new(store.path(), "https://npm.example.com/");
+
+        let mut headers = HeaderMap::new();
+
+        assert!(CachedDocument::new(&headers, json!({})).is_none());
+
+        headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+
+        let document = CachedDocument::new(&headers, json!({ "name": "@scope/a" })).unwrap();
+
+        npm.put("@scope/a", true, &document);
+
+        let cached = npm.get("@scope/a", true).unwrap();
+
+        assert_eq!(cached.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(cached.document["name"], "@scope/a");
+        assert!(npm.get("@scope/a", false).is_none());
+        assert!(mirror.get("@scope/a", true).is_none());
+
+        assert!(clear(store.path()).unwrap() > 0);
+        assert!(npm.get("@scope/a", true).is_none());
+        assert_eq!(clear(store.path()).unwrap(), 0);
+    }
+}
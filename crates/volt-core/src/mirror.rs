@@ -0,0 +1,299 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Download mirrors for registry tarballs and node builds.
+//!
+//! A mirror serves the same paths as the server it stands in for, under its own url:
+//! with `tarball-mirror = https://artifactory.example.com/api/npm/npm/`, the tarball
+//! `https://registry.npmjs.org/react/-/react-18.2.0.tgz` is first requested from
+//! `https://artifactory.example.com/api/npm/npm/react/-/react-18.2.0.tgz`. Package
+//! metadata still comes from the registry, and integrity is checked the same way
+//! whichever server answers.
+//!
+//! Only the package part of a tarball path (`@corp/ui/-/ui-1.0.0.tgz`) is carried over,
+//! so tarballs of registries served under a path (Artifactory's `/api/npm/<repo>/`) or
+//! with an encoded scope (Verdaccio's `@corp%2fui`) land at the same place on the mirror.
+//! When package documents point at the public registry (`registry.npmjs.org`,
+//! `registry.yarnpkg.com`) but another registry is configured, as proxies do, the
+//! tarballs are downloaded from the configured registry instead.
+//!
+//! Mirrors are tried in the configured order and the original server last. A mirror that
+//! fails is moved behind the others for the rest of the run, so one that is down only
+//! slows the first download.
+
+use crate::settings::Settings;
+
+use lazy_static::lazy_static;
+use reqwest::Url;
+
+use std::{collections::HashSet, sync::Mutex};
+
+lazy_static! {
+    /// Origins of the mirrors that failed during this run.
+    static ref UNAVAILABLE: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Hosts of the public registry, which documents of proxying registries often still
+/// point tarballs at.
+const PUBLIC_REGISTRIES: &[&str] = &["registry.npmjs.org", "registry.yarnpkg.com"];
+
+/// The urls to download the registry tarball `url` of the package `name` from, in order:
+/// its scope's mirrors (or the `tarball-mirror` ones), then `url` itself, on `registry`
+/// (the configured registry) when `url` is on the public one.
+pub fn tarball_urls(settings: &Settings, registry: &str, name: &str, url: &str) -> Vec<String> {
+    let url = on_registry(registry, name, url);
+
+    let scope = name
+        .strip_prefix('@')
+        .and_then(|scoped| scoped.split_once('/'))
+        .map(|(scope, _)| format!("@{}", scope));
+
+    let mirrors = scope
+        .and_then(|scope| settings.scoped_tarball_mirrors.get(&scope))
+        .unwrap_or(&settings.tarball_mirror);
+
+    let mut urls: Vec<String> = mirrors
+        .iter()
+        .filter_map(|mirror| rewrite(mirror, name, &url))
+        .collect();
+
+    urls.push(url);
+
+    order(urls)
+}
+
+/// The base urls to download node builds from, in order: the `node-mirror` ones, then
+/// `default`.
+pub fn node_mirrors(settings: &Settings, default: &str) -> Vec<String> {
+    let mut mirrors: Vec<String> = settings
+        .node_mirror
+        .iter()
+        .map(|mirror| mirror.trim_end_matches('/').to_string())
+        .collect();
+
+    mirrors.push(default.to_string());
+
+    order(mirrors)
+}
+
+/// The canonical tarball url of a version on `registry`: npm drops the scope from the
+/// file name (`@corp/ui/-/ui-1.0.0.tgz`).
+pub fn tarball_url(registry: &str, name: &str, version: &str) -> String {
+    let unscoped = name.rsplit('/').next().unwrap_or(name);
+
+    format!(
+        "{}/{}/-/{}-{}.tgz",
+        registry.trim_end_matches('/'),
+        name,
+        unscoped,
+        version
+    )
+}
+
+/// `url`, moved to `registry` if it is on the public registry and `registry` isn't.
+fn on_registry(registry: &str, name: &str, url: &str) -> String {
+    if is_public(url) && !is_public(registry) {
+        if let Some(moved) = rewrite(registry, name, url) {
+            return moved;
+        }
+    }
+
+    url.to_string()
+}
+
+/// Whether `url` is on the public registry.
+pub(crate) fn is_public(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(|host| PUBLIC_REGISTRIES.contains(&host)))
+        .unwrap_or(false)
+}
+
+/// `url` served from `mirror`: the package part of the path of `url` (or all of it, when
+/// the package can't be found in it) under the url of the mirror.
+pub fn rewrite(mirror: &str, name: &str, url: &str) -> Option<String> {
+    let original = Url::parse(url).ok()?;
+
+    // joined as a directory, so `.../api/npm/npm` keeps its last segment
+    let base = if mirror.ends_with('/') {
+        Url::parse(mirror).ok()?
+    } else {
+        Url::parse(&format!("{}/", mirror)).ok()?
+    };
+
+    let path = package_path(original.path(), name)
+        .unwrap_or_else(|| original.path().trim_start_matches('/').to_string());
+
+    let mut mirrored = base.join(&path).ok()?;
+    mirrored.set_query(original.query());
+
+    Some(mirrored.to_string())
+}
+
+/// The path of a tarball from its package on (`@corp/ui/-/ui-1.0.0.tgz`), without the
+/// path of the registry before it and with the scope decoded.
+fn package_path(path: &str, name: &str) -> Option<String> {
+    let (package, file) = path.split_once("/-/")?;
+    let package = package.replace("%2f", "/").replace("%2F", "/");
+
+    package
+        .strip_suffix(name)
+        .filter(|base| base.ends_with('/'))
+        .map(|_| format!("{}/-/{}", name, file))
+}
+
+/// Remember that the server of `url` failed, to try it last from now on.
+pub fn mark_unavailable(url: &str) {
+    if let Some(origin) = origin(url) {
+        tracing::debug!("{} failed, trying it last from now on", origin);
+
+        UNAVAILABLE.lock().unwrap().insert(origin);
+    }
+}
+
+/// Move the urls of servers that failed behind the others, keeping the order otherwise.
+fn order(mut urls: Vec<String>) -> Vec<String> {
+    let unavailable = UNAVAILABLE.lock().unwrap();
+
+    urls.dedup();
+    urls.sort_by_key(|url| origin(url).map_or(false, |origin| unavailable.contains(&origin)));
+
+    urls
+}
+
+fn origin(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rewrite, tarball_url, tarball_urls};
+    use crate::{registry::DEFAULT_REGISTRY, settings::Settings};
+
+    #[test]
+    fn tries_mirrors_before_the_registry() {
+        let tarball = "https://registry.npmjs.org/@corp/ui/-/ui-1.0.0.tgz";
+
+        assert_eq!(tarball_url(DEFAULT_REGISTRY, "@corp/ui", "1.0.0"), tarball);
+
+        assert_eq!(
+            rewrite(
+                "https://artifactory.example.com/api/npm/npm",
+                "@corp/ui",
+                tarball
+            )
+            .as_deref(),
+            Some("https://artifactory.example.com/api/npm/npm/@corp/ui/-/ui-1.0.0.tgz")
+        );
+
+        let mut settings = Settings {
+            tarball_mirror: vec!["https://mirror.example.com/".to_string()],
+            ..Settings::default()
+        };
+
+        assert_eq!(
+            tarball_urls(&settings, DEFAULT_REGISTRY, "@corp/ui", tarball),
+            vec![
+                "https://mirror.example.com/@corp/ui/-/ui-1.0.0.tgz".to_string(),
+                tarball.to_string()
+            ]
+        );
+
+        // a scope's mirrors replace the others
+        settings.scoped_tarball_mirrors.insert(
+            "@corp".to_string(),
+            vec!["https://corp.example.com/npm/".to_string()],
+        );
+
+        assert_eq!(
+            tarball_urls(&settings, DEFAULT_REGISTRY, "@corp/ui", tarball)[0],
+            "https://corp.example.com/npm/@corp/ui/-/ui-1.0.0.tgz"
+        );
+    }
+
+    #[test]
+    fn moves_public_tarballs_to_the_configured_registry() {
+        let settings = Settings::default();
+        let tarball = "https://registry.npmjs.org/@corp/ui/-/ui-1.0.0.tgz";
+
+        // Verdaccio
+        assert_eq!(
+            tarball_urls(&settings, "http://localhost:4873/", "@corp/ui", tarball),
+            vec!["http://localhost:4873/@corp/ui/-/ui-1.0.0.tgz".to_string()]
+        );
+
+        // Artifactory, under the path of its repository
+        assert_eq!(
+            tarball_urls(
+                &settings,
+                "https://example.jfrog.io/artifactory/api/npm/npm-remote",
+                "@corp/ui",
+                tarball
+            ),
+            vec![
+                "https://example.jfrog.io/artifactory/api/npm/npm-remote/@corp/ui/-/ui-1.0.0.tgz"
+                    .to_string()
+            ]
+        );
+
+        // tarballs of other registries stay where they are
+        assert_eq!(
+            tarball_urls(
+                &settings,
+                "http://localhost:4873/",
+                "ms",
+                "https://npm.example.com/ms/-/ms-2.1.3.tgz"
+            ),
+            vec!["https://npm.example.com/ms/-/ms-2.1.3.tgz".to_string()]
+        );
+    }
+
+    #[test]
+    fn mirrors_only_the_package_part_of_the_path() {
+        let mirror = "https://mirror.example.com/npm/";
+
+        // Verdaccio encodes the slash of the scope
+        assert_eq!(
+            rewrite(
+                mirror,
+                "@corp/ui",
+                "http://localhost:4873/@corp%2fui/-/ui-1.0.0.tgz"
+            )
+            .as_deref(),
+            Some("https://mirror.example.com/npm/@corp/ui/-/ui-1.0.0.tgz")
+        );
+
+        // Artifactory serves under the path of its repository, and keeps the scope in
+        // the file name
+        assert_eq!(
+            rewrite(
+                mirror,
+                "@corp/ui",
+                "https://example.jfrog.io/artifactory/api/npm/npm-remote/@corp/ui/-/@corp/ui-1.0.0.tgz"
+            )
+            .as_deref(),
+            Some("https://mirror.example.com/npm/@corp/ui/-/@corp/ui-1.0.0.tgz")
+        );
+
+        // a path without the package is carried over whole
+        assert_eq!(
+            rewrite(mirror, "ms", "https://files.example.com/tarballs/ms.tgz").as_deref(),
+            Some("https://mirror.example.com/npm/tarballs/ms.tgz")
+        );
+    }
+}
@@ -14,7 +14,7 @@
     limitations under the License.
 */
 
-use crate::core::utils::package::NpmPackage;
+use crate::utils::package::NpmPackage;
 
 use isahc::{http::StatusCode, AsyncReadResponseExt};
 use thiserror::Error;
@@ -38,7 +38,7 @@ pub enum GetPackageError {
 /// ## Arguments
 /// * `name` - Name of the package to request from `registry.yarnpkg.com`
 /// ## Examples
-/// ```
+/// ```ignore
 /// // Await an async response
 /// get_package("react").await;
 /// ```
@@ -0,0 +1,672 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+use miette::{IntoDiagnostic, Result};
+use node_semver::{Range, Version};
+use package_spec::PackageSpec;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    engines::engine_ranges,
+    git,
+    lifecycle::LifecycleEvent,
+    shim::bin_entries,
+    utils::voltapi::{
+        dependency_key, is_registry, resolved_version, Bin, Engine, VoltPackage, OPTIONAL_PEER,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum LockFileError {
+    #[error("unable to read lock file")]
+    IO(io::Error),
+    #[error("unable to deserialize lock file")]
+    Decode(serde_json::Error),
+    #[error("unable to serialize lock file")]
+    Encode(serde_json::Error),
+}
+
+/// A package pinned by the lock file, with everything needed to install it again
+/// without fetching its metadata.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub integrity: String,
+    pub tarball: String,
+    /// Only installed because something depends on it optionally
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    /// `name -> exact version` of the package's dependencies
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+    /// `name -> exact version` of the dependencies that may fail to install
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub optional_dependencies: BTreeMap<String, String>,
+    /// `name -> range` of the peer dependencies the package expects its dependents to provide
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub peer_dependencies: BTreeMap<String, String>,
+    /// Names of the peer dependencies the package works without
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub optional_peers: BTreeSet<String>,
+    /// `command -> script` of the package's executables
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub bin: BTreeMap<String, String>,
+    /// Install lifecycle scripts (`preinstall`, `install` and `postinstall`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub scripts: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub libc: Option<Vec<String>>,
+    /// `engine -> range` of the node, npm and volt versions the package runs on
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub engines: BTreeMap<String, String>,
+    /// Installed from a tarball url rather than the registry
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub remote: bool,
+}
+
+impl LockedPackage {
+    /// Every `(name, version)` edge of the package, optional ones included.
+    pub fn edges(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.dependencies
+            .iter()
+            .chain(self.optional_dependencies.iter())
+    }
+
+    /// Whether the package comes from the registry rather than git, the filesystem or a
+    /// tarball url.
+    pub fn is_registry(&self) -> bool {
+        is_registry(&self.tarball, self.remote)
+    }
+
+    /// The key of the package in the lock file, as [`VoltPackage::key`].
+    pub fn key(&self) -> String {
+        format!(
+            "{}@{}",
+            self.name,
+            resolved_version(&self.version, &self.tarball, self.remote)
+        )
+    }
+
+    /// Rebuild the package the registry resolved, to install it straight from the lock file.
+    pub fn to_package(&self) -> VoltPackage {
+        let to_map = |map: &BTreeMap<String, String>| {
+            (!map.is_empty()).then(|| map.clone().into_iter().collect::<HashMap<_, _>>())
+        };
+
+        VoltPackage {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            optional: self.optional,
+            integrity: self.integrity.clone(),
+            tarball: self.tarball.clone(),
+            bin: (!self.bin.is_empty()).then(|| Bin::Map(self.bin.clone().into_iter().collect())),
+            scripts: to_map(&self.scripts),
+            dependencies: self
+                .edges()
+                .map(|(name, version)| (name.clone(), version.clone()))
+                .collect::<HashMap<_, _>>()
+                .into(),
+            peer_dependencies: to_map(&self.peer_dependencies),
+            peer_dependencies_meta: (!self.optional_peers.is_empty()).then(|| {
+                self.optional_peers
+                    .iter()
+                    .map(|name| (name.clone(), OPTIONAL_PEER.to_string()))
+                    .collect()
+            }),
+            optional_dependencies: to_map(&self.optional_dependencies),
+            overrides: None,
+            engines: (!self.engines.is_empty())
+                .then(|| Engine::Map(self.engines.clone().into_iter().collect())),
+            os: self.os.clone(),
+            cpu: self.cpu.clone(),
+            libc: self.libc.clone(),
+            remote: self.remote,
+        }
+    }
+}
+
+impl From<&VoltPackage> for LockedPackage {
+    fn from(package: &VoltPackage) -> Self {
+        let optional_names: Vec<&str> = package
+            .optional_dependencies
+            .iter()
+            .flat_map(|dependencies| dependencies.keys())
+            .map(String::as_str)
+            .collect();
+
+        let mut dependencies = BTreeMap::new();
+        let mut optional_dependencies = BTreeMap::new();
+
+        for (name, version) in package.installed_dependencies() {
+            if optional_names.contains(&name) {
+                optional_dependencies.insert(name.to_string(), version.clone());
+            } else {
+                dependencies.insert(name.to_string(), version.clone());
+            }
+        }
+
+        let scripts = package
+            .scripts
+            .iter()
+            .flatten()
+            .filter(|(event, _)| {
+                LifecycleEvent::DEPENDENCY
+                    .iter()
+                    .any(|lifecycle| lifecycle.as_str() == event.as_str())
+            })
+            .map(|(event, script)| (event.clone(), script.clone()))
+            .collect();
+
+        Self {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            integrity: package.integrity.clone(),
+            tarball: package.tarball.clone(),
+            optional: package.optional,
+            dependencies,
+            optional_dependencies,
+            peer_dependencies: package
+                .peer_dependencies
+                .iter()
+                .flatten()
+                .map(|(name, range)| (name.clone(), range.clone()))
+                .collect(),
+            optional_peers: package
+                .peer_dependencies
+                .iter()
+                .flatten()
+                .map(|(name, _)| name)
+                .filter(|name| package.is_optional_peer(name))
+                .cloned()
+                .collect(),
+            bin: package
+                .bin
+                .as_ref()
+                .map(|bin| bin_entries(&package.name, bin).into_iter().collect())
+                .unwrap_or_default(),
+            scripts,
+            os: package.os.clone(),
+            cpu: package.cpu.clone(),
+            libc: package.libc.clone(),
+            engines: engine_ranges(package.engines.as_ref()),
+            remote: package.remote,
+        }
+    }
+}
+
+/// The lock file is responsible for locking/pinning dependency versions in a given project.
+/// It stores every resolved package keyed by `name@version` (with a hash of the source
+/// after the version of packages from git, urls or the filesystem), along with its
+/// registry url, checksum and the exact versions of its own dependencies, so installs can
+/// be reproduced (and `volt why` can answer) without the registry.
+///
+/// ## Examples
+///
+/// ```ignore
+/// // Load the lock file for the current project (empty if it doesn't exist yet)
+/// let mut lock_file = LockFile::load(config.lockfile()?, false)?;
+///
+/// // Add the resolved packages
+/// lock_file.extend(&resolution.tree);
+///
+/// // Save changes to disk
+/// lock_file.save()?;
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LockFile {
+    #[serde(skip)]
+    pub path: PathBuf,
+    #[serde(skip)]
+    pub global: bool,
+    pub dependencies: BTreeMap<String, LockedPackage>,
+}
+
+impl LockFile {
+    /// Creates a new instance of a lock file with a path it should be saved at.
+    /// It can be saved to the file by calling [`Self::save()`].
+    pub fn new<P: AsRef<Path>>(path: P, global: bool) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            global,
+            dependencies: BTreeMap::new(),
+        }
+    }
+
+    /// Loads a lock file from the given path, or an empty one if it doesn't exist.
+    pub fn load<P: AsRef<Path>>(path: P, global: bool) -> Result<Self, LockFileError> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(Self::new(path, global));
+        }
+
+        let f = File::open(path).map_err(LockFileError::IO)?;
+        let reader = BufReader::new(f);
+
+        let mut lock_file: Self = serde_json::from_reader(reader).map_err(LockFileError::Decode)?;
+
+        lock_file.path = path.to_path_buf();
+        lock_file.global = global;
+
+        Ok(lock_file)
+    }
+
+    /// Add resolved packages (keyed by `name@version`) to the lock file.
+    pub fn extend(&mut self, packages: &HashMap<String, VoltPackage>) {
+        self.dependencies.extend(
+            packages
+                .iter()
+                .map(|(key, package)| (key.clone(), LockedPackage::from(package))),
+        );
+    }
+
+    /// Get the locked package for an exact version, or the one an alias
+    /// (`npm:bar@1.0.0`) points at.
+    pub fn get(&self, name: &str, version: &str) -> Option<&LockedPackage> {
+        self.dependencies.get(&dependency_key(name, version))
+    }
+
+    /// Index the dependents of every locked package: `name@version` -> keys of the
+    /// packages depending on it, sorted.
+    pub fn dependents(&self) -> HashMap<String, Vec<String>> {
+        let mut dependents: HashMap<String, Vec<String>> = HashMap::new();
+
+        // iterating a BTreeMap keeps every list sorted
+        for (key, package) in &self.dependencies {
+            for (name, version) in package.edges() {
+                dependents
+                    .entry(dependency_key(name, version))
+                    .or_default()
+                    .push(key.clone());
+            }
+        }
+
+        dependents
+    }
+
+    /// Every chain of dependents from the package `key` up to a dependency of the project,
+    /// given as `name -> range` (the project's dependencies and dev dependencies), stopping
+    /// at `limit` of them: a package deep in a tree sharing many dependencies is pulled
+    /// through more chains than can be walked.
+    ///
+    /// Chains are leaf first: `[ms@2.0.0, debug@2.6.9, express@4.17.1]`.
+    pub fn chains(
+        &self,
+        key: &str,
+        direct: &BTreeMap<&String, &String>,
+        limit: usize,
+    ) -> Vec<Vec<String>> {
+        let mut chains = vec![];
+
+        self.find_chains(
+            &self.dependents(),
+            &self.required(direct),
+            direct,
+            key,
+            &mut vec![key.to_string()],
+            &mut chains,
+            limit,
+        );
+
+        chains
+    }
+
+    /// The keys of the packages the project depends on, directly or not.
+    fn required(&self, direct: &BTreeMap<&String, &String>) -> HashSet<String> {
+        let mut required = HashSet::new();
+
+        let mut queue: Vec<String> = self
+            .dependencies
+            .iter()
+            .filter(|(_, package)| is_direct(direct, package))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        while let Some(key) = queue.pop() {
+            if let Some(package) = self.dependencies.get(&key) {
+                if required.insert(key) {
+                    queue.extend(
+                        package
+                            .edges()
+                            .map(|(name, version)| dependency_key(name, version)),
+                    );
+                }
+            }
+        }
+
+        required
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn find_chains(
+        &self,
+        dependents: &HashMap<String, Vec<String>>,
+        required: &HashSet<String>,
+        direct: &BTreeMap<&String, &String>,
+        key: &str,
+        path: &mut Vec<String>,
+        chains: &mut Vec<Vec<String>>,
+        limit: usize,
+    ) {
+        if let Some(package) = self.dependencies.get(key) {
+            if is_direct(direct, package) {
+                chains.push(path.clone());
+            }
+        }
+
+        for dependent in dependents.get(key).into_iter().flatten() {
+            if chains.len() >= limit {
+                return;
+            }
+
+            // cycles never lead anywhere new, and neither do the packages the project
+            // doesn't depend on
+            if path.contains(dependent) || !required.contains(dependent) {
+                continue;
+            }
+
+            path.push(dependent.clone());
+            self.find_chains(dependents, required, direct, dependent, path, chains, limit);
+            path.pop();
+        }
+    }
+
+    /// Find the highest locked version of `name` satisfying `range`, which can also be a
+    /// git specification (`github:user/repo#main`), a path, a tarball url or an alias
+    /// (`npm:bar@^2.0.0`).
+    ///
+    /// Packages that don't come from the registry are found by their source alone, since
+    /// the dependency name can be an alias of the name in their package.json.
+    pub fn find(&self, name: &str, range: &str) -> Option<&LockedPackage> {
+        // parsed the way package.json dependencies are, so `file:` and `npm:` read as aliases
+        match format!("{}@{}", name, range).parse::<PackageSpec>() {
+            Ok(PackageSpec::Alias { spec, .. }) => self.find_spec(*spec),
+            Ok(PackageSpec::Npm { .. }) | Err(_) => self.find_version(name, range),
+            Ok(spec) => self.find_spec(spec),
+        }
+    }
+
+    fn find_spec(&self, spec: PackageSpec) -> Option<&LockedPackage> {
+        match spec {
+            PackageSpec::Git(info) => self
+                .dependencies
+                .values()
+                .find(|package| git::locks(&info, &package.tarball)),
+            PackageSpec::Dir { path } => self.find_source(&format!("file:{}", path.display())),
+            PackageSpec::Link { path } => self.find_source(&format!("link:{}", path.display())),
+            PackageSpec::Remote { url } => self.find_source(url.as_str()),
+            // `npm:bar@^2.0.0`
+            PackageSpec::Npm {
+                name, requested, ..
+            } => self.find_version(
+                &name,
+                &requested.map_or_else(|| "*".to_string(), |requested| requested.to_string()),
+            ),
+            PackageSpec::Alias { spec, .. } => self.find_spec(*spec),
+        }
+    }
+
+    fn find_version(&self, name: &str, range: &str) -> Option<&LockedPackage> {
+        let range: Option<Range> = range.parse().ok();
+
+        self.dependencies
+            .values()
+            .filter(|package| package.name == name && package.is_registry())
+            .filter_map(|package| {
+                let version: Version = package.version.parse().ok()?;
+
+                match &range {
+                    Some(range) if !version.satisfies(range) => None,
+                    _ => Some((version, package)),
+                }
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, package)| package)
+    }
+
+    fn find_source(&self, source: &str) -> Option<&LockedPackage> {
+        self.dependencies
+            .values()
+            .find(|package| package.tarball == source)
+    }
+
+    /// Serialize the lock file.
+    ///
+    /// The output is byte-for-byte deterministic: packages and their fields are always
+    /// written in the same order, indented with two spaces and followed by a newline.
+    pub fn serialize(&self) -> Result<String, LockFileError> {
+        let mut buffer = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b"  ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+
+        Serialize::serialize(self, &mut serializer).map_err(LockFileError::Encode)?;
+
+        buffer.push(b'\n');
+
+        // serde_json only ever writes valid UTF-8
+        Ok(String::from_utf8(buffer).unwrap_or_default())
+    }
+
+    /// Whether saving would change the lock file on disk.
+    pub fn is_dirty(&self) -> bool {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => self
+                .serialize()
+                .map_or(true, |lock_file| lock_file != contents),
+            // there's nothing to write for a project without dependencies
+            Err(_) => !self.dependencies.is_empty(),
+        }
+    }
+
+    /// Saves a lock file to the same path it was opened from, leaving it untouched if
+    /// nothing changed.
+    pub fn save(&self) -> Result<()> {
+        if !self.is_dirty() {
+            return Ok(());
+        }
+
+        let contents = self.serialize().into_diagnostic()?;
+
+        let lock_file = File::create(&self.path).into_diagnostic()?;
+        let mut writer = BufWriter::new(lock_file);
+
+        writer.write_all(contents.as_bytes()).into_diagnostic()?;
+
+        Ok(())
+    }
+}
+
+/// Whether the project's package.json asks for this exact package.
+fn is_direct(direct: &BTreeMap<&String, &String>, package: &LockedPackage) -> bool {
+    match direct.get(&package.name) {
+        Some(range) => match (package.version.parse::<Version>(), range.parse::<Range>()) {
+            (Ok(version), Ok(range)) => version.satisfies(&range),
+            _ => true,
+        },
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LockFile, LockedPackage};
+
+    use crate::utils::voltapi::{dependency_key, dependency_target};
+
+    use std::collections::BTreeMap;
+
+    fn package(name: &str, version: &str) -> LockedPackage {
+        LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            dependencies: [("b".to_string(), "1.0.0".to_string())].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn serialization_is_deterministic() {
+        let mut first = LockFile::new("volt.lock", false);
+        let mut second = LockFile::new("volt.lock", false);
+
+        for (name, version) in [("a", "1.0.0"), ("c", "2.0.0"), ("@scope/b", "0.1.0")] {
+            first
+                .dependencies
+                .insert(format!("{}@{}", name, version), package(name, version));
+        }
+
+        for (name, version) in [("@scope/b", "0.1.0"), ("c", "2.0.0"), ("a", "1.0.0")] {
+            second
+                .dependencies
+                .insert(format!("{}@{}", name, version), package(name, version));
+        }
+
+        let serialized = first.serialize().unwrap();
+
+        assert_eq!(serialized, second.serialize().unwrap());
+        assert!(serialized.ends_with("}\n"));
+        assert!(serialized.starts_with("{\n  \"dependencies\": {\n    \"@scope/b@0.1.0\""));
+    }
+
+    #[test]
+    fn saved_lock_file_is_clean() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("volt.lock");
+
+        let mut lock_file = LockFile::new(&path, false);
+        assert!(!lock_file.is_dirty());
+
+        lock_file
+            .dependencies
+            .insert("a@1.0.0".to_string(), package("a", "1.0.0"));
+        assert!(lock_file.is_dirty());
+
+        lock_file.save().unwrap();
+        assert!(!lock_file.is_dirty());
+        assert!(!LockFile::load(&path, false).unwrap().is_dirty());
+    }
+
+    #[test]
+    fn finds_aliases_and_tarball_urls() {
+        let mut lock_file = LockFile::new("volt.lock", false);
+
+        let remote = LockedPackage {
+            tarball: "https://example.com/c-1.0.0.tgz".to_string(),
+            remote: true,
+            ..package("c", "1.0.0")
+        };
+
+        lock_file
+            .dependencies
+            .insert("b@2.1.0".to_string(), package("b", "2.1.0"));
+        lock_file.dependencies.insert("c@1.0.0".to_string(), remote);
+
+        assert_eq!(
+            lock_file.find("a", "npm:b@^2.0.0").unwrap().version,
+            "2.1.0"
+        );
+        assert!(lock_file.get("a", "npm:b@2.1.0").is_some());
+
+        assert_eq!(
+            lock_file
+                .find("d", "https://example.com/c-1.0.0.tgz")
+                .unwrap()
+                .name,
+            "c"
+        );
+        // packages from a tarball url don't satisfy registry ranges
+        assert!(lock_file.find("c", "^1.0.0").is_none());
+    }
+
+    #[test]
+    fn keys_packages_from_elsewhere_by_their_source() {
+        let registry = package("a", "1.0.0");
+        let first = LockedPackage {
+            tarball: "git+https://github.com/user/a.git#1111111".to_string(),
+            ..package("a", "1.0.0")
+        };
+        let second = LockedPackage {
+            tarball: "git+https://github.com/user/a.git#2222222".to_string(),
+            ..package("a", "1.0.0")
+        };
+
+        assert_eq!(registry.key(), "a@1.0.0");
+        assert!(first.key().starts_with("a@1.0.0+"));
+        assert_ne!(first.key(), second.key());
+
+        // dependents record the version that leads to the key
+        let version = first.to_package().resolved_version();
+        assert_eq!(dependency_key("a", &version), first.key());
+
+        assert!(dependency_target("a", "npm:").is_err());
+        assert_eq!(
+            dependency_target("a", "npm:@scope/b@1.0.0").unwrap(),
+            ("@scope/b", "1.0.0")
+        );
+    }
+
+    #[test]
+    fn stops_at_the_chain_limit() {
+        let mut lock_file = LockFile::new("volt.lock", false);
+
+        // every package of a level depends on both of the next one: 2^24 chains to `leaf`
+        for level in 0..24 {
+            for side in ["a", "b"] {
+                let mut package = package(&format!("{}{}", side, level), "1.0.0");
+
+                package.dependencies = if level == 23 {
+                    [("leaf".to_string(), "1.0.0".to_string())].into()
+                } else {
+                    ["a", "b"]
+                        .iter()
+                        .map(|next| (format!("{}{}", next, level + 1), "1.0.0".to_string()))
+                        .collect()
+                };
+
+                lock_file.dependencies.insert(package.key(), package);
+            }
+        }
+
+        let mut leaf = package("leaf", "1.0.0");
+        leaf.dependencies.clear();
+
+        lock_file.dependencies.insert(leaf.key(), leaf);
+
+        let (name, range) = ("a0".to_string(), "^1.0.0".to_string());
+        let direct = BTreeMap::from([(&name, &range)]);
+
+        let chains = lock_file.chains("leaf@1.0.0", &direct, 10);
+
+        assert_eq!(chains.len(), 10);
+        assert!(chains
+            .iter()
+            .all(|chain| chain.len() == 25 && chain[24] == "a0@1.0.0"));
+    }
+}
@@ -0,0 +1,592 @@
+use std::{collections::HashMap, future::Future, path::Path, time::Duration};
+
+use crate::{
+    config::VoltConfig,
+    hashing::{hash, Algorithm},
+    install::{dependency_specs, resolve, Resolution},
+    io::read_manifest,
+    lock::{LockMode, ProcessLock},
+    mirror::{mark_unavailable, tarball_urls},
+    registry::{RegistryClient, DEFAULT_REGISTRY},
+    reporter::Reporter,
+    tarball::Tarball,
+    utils::{
+        constants::MAX_RETRIES,
+        errors::{FilesystemError, IntegrityError, NetworkError, ResolutionError},
+        voltapi::{VoltPackage, VoltResponse},
+        State,
+    },
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use isahc::AsyncReadResponseExt;
+use miette::{IntoDiagnostic, Result};
+use node_semver::Version;
+use package_spec::PackageSpec;
+use reqwest::{
+    header::{CONTENT_RANGE, RANGE},
+    StatusCode,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use speedy::Readable;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+};
+use tracing::Instrument;
+
+pub async fn get_volt_response_multi(
+    config: &VoltConfig,
+    packages: &[PackageSpec],
+    reporter: &dyn Reporter,
+) -> Vec<Result<VoltResponse>> {
+    let source = config.source();
+
+    packages
+        .iter()
+        .map(|spec| {
+            if let PackageSpec::Npm {
+                name, requested, ..
+            } = spec
+            {
+                let mut version: String = "latest".to_string();
+
+                if requested.is_some() {
+                    version = requested.as_ref().unwrap().to_string();
+                };
+
+                reporter.resolving(&format!("{}@{}", name, version));
+            }
+
+            source
+                .resolve(config, spec)
+                .instrument(tracing::debug_span!("metadata", package = %spec))
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<Result<VoltResponse>>>()
+        .await
+}
+
+// Get response from volt CDN
+pub async fn get_volt_response(package_spec: &PackageSpec) -> Result<VoltResponse> {
+    // number of retries
+    let mut retries = 0;
+
+    if let PackageSpec::Npm { name, .. } = package_spec {
+        // loop until MAX_RETRIES reached.
+        loop {
+            // get a response
+            let url = format!("http://registry.voltpkg.com/{}.sp", &package_spec);
+
+            let mut response =
+                isahc::get_async(&url)
+                    .await
+                    .map_err(|source| NetworkError::VoltRegistry {
+                        url: url.clone(),
+                        source,
+                    })?;
+
+            tracing::debug!("GET {}.sp - {}", package_spec, response.status());
+
+            // check the status of the response
+            match response.status() {
+                // 200 (OK)
+                StatusCode::OK => {
+                    let bytes = response
+                        .bytes()
+                        .await
+                        .map_err(|e| NetworkError::VoltRegistry {
+                            url: url.clone(),
+                            source: e.into(),
+                        })?;
+
+                    let mut response = VoltResponse::read_from_buffer(&bytes).map_err(|e| {
+                        NetworkError::InvalidResponse {
+                            url: url.clone(),
+                            reason: e.to_string(),
+                        }
+                    })?;
+
+                    response.name = name.to_string();
+
+                    return Ok(response);
+                }
+                // 429 (TOO_MANY_REQUESTS)
+                StatusCode::TOO_MANY_REQUESTS => {
+                    return Err(NetworkError::TooManyRequests {
+                        url: format!("http://registry.voltpkg.com/{}.sp", &package_spec),
+                    }
+                    .into());
+                }
+                // 400 (BAD_REQUEST)
+                StatusCode::BAD_REQUEST => {
+                    return Err(NetworkError::BadRequest {
+                        url: format!("http://registry.voltpkg.com/{}.sp", &package_spec),
+                    }
+                    .into());
+                }
+                // 404 (NOT_FOUND)
+                StatusCode::NOT_FOUND if retries == MAX_RETRIES => {
+                    return Err(ResolutionError::NotFound {
+                        url: format!("http://registry.voltpkg.com/{}.sp", &package_spec),
+                        package: package_spec.to_string(),
+                    }
+                    .into());
+                }
+                // Other Errors
+                _ => {
+                    if retries == MAX_RETRIES {
+                        return Err(NetworkError::Status {
+                            url: format!("http://registry.voltpkg.com/{}.sp", name),
+                            status: response.status().as_str().to_string(),
+                        }
+                        .into());
+                    }
+                }
+            }
+
+            retries += 1;
+        }
+    } else {
+        Err(NetworkError::NotInVoltRegistry {
+            spec: package_spec.to_string(),
+        }
+        .into())
+    }
+}
+
+/// Download the tarball of a package, returning it once its integrity is verified.
+///
+/// The download is written to `<store>/partial` as it arrives, in a file named after its
+/// integrity and locked while it's written; when the connection drops, the next attempt
+/// (or the next install) only requests the rest with a `Range` header.
+/// There are `fetch-retries` more attempts, and each stops once the server sends nothing
+/// for `fetch-timeout`.
+pub async fn fetch_tarball(
+    config: &VoltConfig,
+    package: &VoltPackage,
+    state: &State,
+) -> Result<Tarball> {
+    let directory = config.store()?.join("partial");
+
+    tokio::fs::create_dir_all(&directory)
+        .await
+        .map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: directory.display().to_string(),
+        })?;
+
+    let name = partial_name(package);
+    let path = directory.join(format!("{}.tgz", name));
+
+    // two processes downloading the same tarball would append to each other's file
+    let _lock = if config.locking() {
+        Some(
+            ProcessLock::acquire(
+                &directory.join(format!("{}.lock", name)),
+                LockMode::Exclusive,
+                Duration::from_secs(config.settings().lock_timeout),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    // registry tarballs may come from a mirror, the others only from where they are
+    let urls = if package.remote || !package.tarball.starts_with("http") {
+        vec![package.tarball.clone()]
+    } else {
+        let registry = RegistryClient::new(config)
+            .map_or_else(|_| DEFAULT_REGISTRY.to_string(), |registry| registry.url);
+
+        tarball_urls(
+            config.settings(),
+            &registry,
+            &package.name,
+            &package.tarball,
+        )
+    };
+
+    let mut tarball = None;
+
+    for (index, url) in urls.iter().enumerate() {
+        match download_verified(config, &path, url, package, state).await {
+            Ok(downloaded) => {
+                tarball = Some(downloaded);
+
+                break;
+            }
+            // the last server's error is the one reported
+            Err(error) if index + 1 == urls.len() => return Err(error),
+            Err(error) => {
+                tracing::warn!(
+                    "failed to download {}, trying the next server: {}",
+                    url,
+                    error
+                );
+
+                mark_unavailable(url);
+
+                // the next server starts over
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+        }
+    }
+
+    let tarball =
+        tarball.ok_or_else(|| miette::miette!("no url to download {} from", package.name))?;
+
+    state
+        .reporter
+        .downloaded(&format!("{}@{}", package.name, package.version));
+
+    Ok(tarball)
+}
+
+/// Download the tarball of `package` from `url`, resuming dropped connections, and verify
+/// its integrity; kept in a file when it's larger than the `tarball-memory-limit` setting.
+async fn download_verified(
+    config: &VoltConfig,
+    path: &Path,
+    url: &str,
+    package: &VoltPackage,
+    state: &State,
+) -> Result<Tarball> {
+    let mut retries = 0;
+
+    while let Err(error) = download(config, path, url, package, state).await {
+        // only dropped connections are worth resuming, not error responses
+        let interrupted = matches!(
+            error.downcast_ref::<NetworkError>(),
+            Some(NetworkError::Request { .. } | NetworkError::Timeout { .. })
+        );
+
+        if !interrupted || retries == config.settings().fetch_retries {
+            return Err(error);
+        }
+
+        tracing::debug!("download of {} interrupted, resuming", url);
+
+        retries += 1;
+    }
+
+    // a corrupted download can't be resumed, the next attempt starts over
+    let tarball = tokio::task::spawn_blocking({
+        let path = path.to_path_buf();
+        let memory_limit = config.settings().tarball_memory_limit;
+
+        move || take_partial(&path, memory_limit)
+    })
+    .await
+    .into_diagnostic()??;
+
+    tracing::debug!(
+        "GET {} - {}",
+        url,
+        if tarball.in_memory() {
+            "in memory"
+        } else {
+            "in a file"
+        }
+    );
+
+    if let Some(actual) = tarball.verify(&package.integrity)? {
+        return Err(IntegrityError::Checksum {
+            package: format!("{}@{}", package.name, package.version),
+            tarball: url.to_string(),
+            expected: package.integrity.clone(),
+            actual: actual.to_string(),
+        }
+        .into());
+    }
+
+    Ok(tarball)
+}
+
+/// The downloaded tarball at `path`, moved out of the way of the next download of it.
+fn take_partial(path: &Path, memory_limit: u64) -> Result<Tarball> {
+    let tarball = match Tarball::read(path, memory_limit)? {
+        Tarball::File { .. } => {
+            let write_error = |e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            };
+
+            let directory = path.parent().unwrap_or_else(|| Path::new("."));
+
+            let temporary = tempfile::Builder::new()
+                .suffix(".tgz")
+                .tempfile_in(directory)
+                .map_err(write_error)?
+                .into_temp_path();
+
+            std::fs::rename(path, &temporary).map_err(write_error)?;
+
+            Tarball::temporary(temporary)
+        }
+        tarball => {
+            let _ = std::fs::remove_file(path);
+
+            tarball
+        }
+    };
+
+    Ok(tarball)
+}
+
+/// Download the rest of a tarball into `path`.
+#[tracing::instrument(level = "debug", skip_all, fields(url = %url))]
+async fn download(
+    config: &VoltConfig,
+    path: &Path,
+    url: &str,
+    package: &VoltPackage,
+    state: &State,
+) -> Result<()> {
+    let request_error = |source| NetworkError::Request {
+        url: url.to_string(),
+        source,
+    };
+
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    };
+
+    config.connections().dns().register(url);
+
+    let downloaded = tokio::fs::metadata(path)
+        .await
+        .map_or(0, |metadata| metadata.len());
+
+    let mut request = state.http_client.get(url);
+
+    if downloaded > 0 {
+        request = request.header(RANGE, format!("bytes={}-", downloaded));
+    }
+
+    let timeout = config.settings().fetch_timeout;
+
+    let mut response = within(timeout, url, request.send())
+        .await?
+        .map_err(request_error)?;
+
+    let resumed = response.status() == StatusCode::PARTIAL_CONTENT
+        && response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(range_start)
+            == Some(downloaded);
+
+    // the partial download is already complete or longer than the tarball now is, or
+    // the server sent another part of it than the rest
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE
+        || (response.status() == StatusCode::PARTIAL_CONTENT && !resumed)
+    {
+        tokio::fs::remove_file(path).await.map_err(write_error)?;
+
+        response = within(timeout, url, state.http_client.get(url).send())
+            .await?
+            .map_err(request_error)?;
+    }
+
+    let mut file = match response.status() {
+        StatusCode::PARTIAL_CONTENT if resumed => {
+            tracing::debug!("resuming {} at {} bytes", url, downloaded);
+
+            state.reporter.download_progress(downloaded);
+
+            OpenOptions::new().append(true).open(path).await
+        }
+        // servers without ranges send the whole tarball again
+        status if status.is_success() && status != StatusCode::PARTIAL_CONTENT => {
+            File::create(path).await
+        }
+        status => {
+            return Err(NetworkError::Download {
+                package: format!("{}@{}", package.name, package.version),
+                url: url.to_string(),
+                status: status.as_u16(),
+            }
+            .into())
+        }
+    }
+    .map_err(write_error)?;
+
+    // read it in chunks to report the progress of large tarballs
+    while let Some(chunk) = within(timeout, url, response.chunk())
+        .await?
+        .map_err(request_error)?
+    {
+        if let Some(throttle) = config.throttle() {
+            throttle.take(chunk.len() as u64).await;
+        }
+
+        state.reporter.download_progress(chunk.len() as u64);
+        config.stats().add_bytes(chunk.len() as u64);
+        file.write_all(&chunk).await.map_err(write_error)?;
+    }
+
+    file.flush().await.map_err(write_error)?;
+
+    Ok(())
+}
+
+/// Name of the partial download of `package` in `<store>/partial`, from its integrity
+/// (its tarball url without one) so that packages sharing a name and version don't
+/// share it.
+fn partial_name(package: &VoltPackage) -> String {
+    let source = if package.integrity.is_empty() {
+        &package.tarball
+    } else {
+        &package.integrity
+    };
+
+    hex::encode(Sha256::digest(source.as_bytes()))
+}
+
+/// Where the part sent by a server starts, from its `Content-Range` header
+/// (`bytes 1024-4095/4096`).
+fn range_start(content_range: &str) -> Option<u64> {
+    content_range
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// The output of `future`, failing with [`NetworkError::Timeout`] when it takes longer
+/// than `timeout`.
+async fn within<T>(
+    timeout: Option<Duration>,
+    url: &str,
+    future: impl Future<Output = T>,
+) -> Result<T> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return Ok(future.await),
+    };
+
+    tokio::time::timeout(timeout, future).await.map_err(|_| {
+        NetworkError::Timeout {
+            url: url.to_string(),
+            seconds: timeout.as_secs(),
+        }
+        .into()
+    })
+}
+
+/// Resolve a tarball url into the package it contains, along with the dependencies of
+/// that package.
+pub fn resolve_remote<'a>(
+    config: &'a VoltConfig,
+    url: &'a str,
+) -> BoxFuture<'a, Result<Resolution>> {
+    async move {
+        let client = config.http_client()?;
+
+        let request_error = |source| NetworkError::Request {
+            url: url.to_string(),
+            source,
+        };
+
+        let response = client.get(url).send().await.map_err(request_error)?;
+
+        if !response.status().is_success() {
+            return Err(NetworkError::Download {
+                package: url.to_string(),
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            }
+            .into());
+        }
+
+        let tarball = response.bytes().await.map_err(request_error)?;
+
+        let package_json = read_manifest(&tarball)?;
+
+        let dependencies = package_json.dependencies.clone().unwrap_or_default();
+
+        let mut resolution = resolve(config, &dependency_specs(&dependencies)).await?;
+
+        let mut package = VoltPackage::from_manifest(&package_json, url.to_string());
+
+        package.integrity = hash(&tarball, &[Algorithm::Sha512]).to_string();
+        package.remote = true;
+        package.dependencies = Some(resolution.edges());
+
+        let key = package.key();
+
+        resolution.direct = vec![key.clone()];
+        resolution.aliases.clear();
+        resolution.tree.insert(key, package);
+
+        Ok(resolution)
+    }
+    .boxed()
+}
+
+/// The versions of a package published to the configured registry.
+pub async fn fetch_versions(config: &VoltConfig, name: &str) -> Result<Vec<Version>> {
+    #[derive(Deserialize)]
+    struct Packument {
+        versions: HashMap<String, serde_json::Value>,
+    }
+
+    let packument = RegistryClient::new(config)?.packument(name).await?;
+    let packument: Packument = serde_json::from_value(packument).into_diagnostic()?;
+
+    Ok(packument
+        .versions
+        .keys()
+        .filter_map(|version| version.parse().ok())
+        .collect())
+}
+
+/// The flattened dependency trees of registry packages, from the source of `config`.
+pub async fn fetch_dep_tree(
+    config: &VoltConfig,
+    data: &[PackageSpec],
+    reporter: &dyn Reporter,
+) -> Result<Vec<VoltResponse>> {
+    if data.len() > 1 {
+        Ok(get_volt_response_multi(config, data, reporter)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?)
+    } else {
+        if let PackageSpec::Npm {
+            name, requested, ..
+        } = &data[0]
+        {
+            let mut version: String = "latest".to_string();
+
+            if requested.is_some() {
+                version = requested.as_ref().unwrap().to_string();
+            };
+
+            reporter.resolving(&format!("{}@{}", name, version));
+        }
+
+        Ok(vec![config.source().resolve(config, &data[0]).await?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::range_start;
+
+    #[test]
+    fn reads_where_a_part_starts() {
+        assert_eq!(range_start("bytes 1024-4095/4096"), Some(1024));
+        assert_eq!(range_start("bytes 0-4095/*"), Some(0));
+        assert_eq!(range_start("bytes */4096"), None);
+        assert_eq!(range_start("items 0-10/20"), None);
+    }
+}
@@ -0,0 +1,190 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The owners of a package: the users who can publish it.
+//!
+//! They are the `maintainers` of its document. Changing them sends the new list with the
+//! revision of the document it was read from (`PUT /<name>/-rev/<rev>`), so the registry
+//! rejects a change made over someone else's. Added users are looked up first
+//! (`GET /-/user/org.couchdb.user:<name>`) for the email the list needs.
+
+use crate::{
+    registry::RegistryClient,
+    reporter::Reporter,
+    utils::{
+        errors::{NetworkError, VoltError},
+        package::Maintainer,
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// The owners of `name`.
+pub async fn list(client: &RegistryClient, name: &str) -> Result<Vec<Maintainer>> {
+    Ok(maintainers(&client.packument(name).await?))
+}
+
+/// Make `user` an owner of `name`; `false` if they already are one.
+pub async fn add(
+    client: &RegistryClient,
+    name: &str,
+    user: &str,
+    reporter: &dyn Reporter,
+) -> Result<bool> {
+    let packument = client.packument(name).await?;
+    let mut owners = maintainers(&packument);
+
+    if owners.iter().any(|owner| owner.name == user) {
+        return Ok(false);
+    }
+
+    owners.push(find_user(client, user).await?);
+
+    save(client, name, &packument, &owners, reporter).await?;
+
+    Ok(true)
+}
+
+/// Take `user` off the owners of `name`; `false` if they weren't one. The last owner
+/// can't be removed.
+pub async fn remove(
+    client: &RegistryClient,
+    name: &str,
+    user: &str,
+    reporter: &dyn Reporter,
+) -> Result<bool> {
+    let packument = client.packument(name).await?;
+    let mut owners = maintainers(&packument);
+
+    if !owners.iter().any(|owner| owner.name == user) {
+        return Ok(false);
+    }
+
+    owners.retain(|owner| owner.name != user);
+
+    if owners.is_empty() {
+        return Err(VoltError::LastOwner {
+            name: name.to_string(),
+            user: user.to_string(),
+        }
+        .into());
+    }
+
+    save(client, name, &packument, &owners, reporter).await?;
+
+    Ok(true)
+}
+
+/// The user `name` of the registry, with their email.
+async fn find_user(client: &RegistryClient, name: &str) -> Result<Maintainer> {
+    #[derive(Deserialize)]
+    struct User {
+        name: String,
+        #[serde(default)]
+        email: String,
+    }
+
+    let request = client.request(
+        Method::GET,
+        &format!(
+            "-/user/org.couchdb.user:{}",
+            RegistryClient::package_path(name)
+        ),
+    );
+
+    let response = match client.send(request).await {
+        Ok(response) => response,
+        Err(error) => {
+            return match error.downcast_ref::<NetworkError>() {
+                Some(NetworkError::Registry { status: 404, .. }) => Err(VoltError::UnknownUser {
+                    user: name.to_string(),
+                    registry: client.url.clone(),
+                }
+                .into()),
+                _ => Err(error),
+            }
+        }
+    };
+
+    let user: User = response.json().await.into_diagnostic()?;
+
+    Ok(Maintainer {
+        name: user.name,
+        email: user.email,
+    })
+}
+
+async fn save(
+    client: &RegistryClient,
+    name: &str,
+    packument: &Value,
+    owners: &[Maintainer],
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    let revision = packument["_rev"].as_str().unwrap_or_default();
+
+    let body = json!({
+        "_id": packument["_id"],
+        "_rev": revision,
+        "maintainers": owners,
+    });
+
+    client
+        .send_mutation(reporter, |client| {
+            Ok(client
+                .authenticated(
+                    Method::PUT,
+                    &format!("{}/-rev/{}", RegistryClient::package_path(name), revision),
+                )?
+                .json(&body))
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn maintainers(packument: &Value) -> Vec<Maintainer> {
+    serde_json::from_value(packument["maintainers"].clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::maintainers;
+
+    use serde_json::json;
+
+    #[test]
+    fn reads_the_maintainers_of_a_document() {
+        let packument = json!({
+            "_id": "a",
+            "_rev": "3-abc",
+            "maintainers": [
+                { "name": "alice", "email": "alice@example.com" },
+                { "name": "bob" },
+            ],
+        });
+
+        let owners = maintainers(&packument);
+
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners[0].email, "alice@example.com");
+        assert_eq!(owners[1].name, "bob");
+        assert!(maintainers(&json!({})).is_empty());
+    }
+}
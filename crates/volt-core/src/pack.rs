@@ -0,0 +1,392 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Select the files of a package and pack them the way npm publishes them.
+//!
+//! With a `files` field only the listed files are packed, otherwise everything is packed
+//! except what `.npmignore` (or `.gitignore` without one) leaves out. Either way
+//! package.json, the readme, the license and the `main` file are always packed, and
+//! version control, `node_modules` and lock files never are.
+
+use crate::{
+    hashing::{hash, select, Algorithm},
+    io::pack_files,
+    utils::{errors::FilesystemError, glob, package::PackageJson},
+};
+
+use miette::{IntoDiagnostic, Result};
+
+use std::path::{Path, PathBuf};
+
+/// Never packed, matched against every file and directory name.
+const ALWAYS_IGNORED: [&str; 19] = [
+    ".npmignore",
+    ".gitignore",
+    ".git",
+    ".svn",
+    ".hg",
+    "CVS",
+    ".DS_Store",
+    "._*",
+    ".*.swp",
+    ".npmrc",
+    ".lock-wscript",
+    "npm-debug.log",
+    "config.gypi",
+    "*.orig",
+    "node_modules",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "volt.lock",
+];
+
+/// Always packed when found at the root, whatever the package says (case insensitive).
+const ALWAYS_INCLUDED: [&str; 4] = ["readme", "license", "licence", "copying"];
+
+/// A packed package, ready to be written to disk or published.
+#[derive(Debug)]
+pub struct Packed {
+    pub name: String,
+    pub version: String,
+    pub tarball: Vec<u8>,
+    /// Packed files relative to the package root, with their size in bytes
+    pub files: Vec<(PathBuf, u64)>,
+    /// sha512 integrity of the tarball
+    pub integrity: String,
+    /// Hex sha1 of the tarball, still expected by the registry
+    pub shasum: String,
+}
+
+impl Packed {
+    /// Name of the tarball, the way npm writes it: `@scope/a@1.0.0` gives `scope-a-1.0.0.tgz`.
+    pub fn filename(&self) -> String {
+        format!(
+            "{}-{}.tgz",
+            self.name.trim_start_matches('@').replace('/', "-"),
+            self.version
+        )
+    }
+}
+
+/// Pack the package in `directory`.
+pub fn pack(directory: &Path) -> Result<Packed> {
+    let (package_json, _) = PackageJson::get_from_dir(directory)?;
+
+    let files = package_files(directory, &package_json)?;
+    let tarball = pack_files(directory, &files)?;
+
+    let sizes = files
+        .into_iter()
+        .map(|file| {
+            let size = std::fs::metadata(directory.join(&file))
+                .map_err(|e| FilesystemError::Read {
+                    source: e,
+                    path: file.display().to_string(),
+                })?
+                .len();
+
+            Ok((file, size))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // the integrity and npm's older `shasum`, in one pass
+    let digests = hash(&tarball, &[Algorithm::Sha512, Algorithm::Sha1]);
+
+    let integrity = select(&digests, Algorithm::Sha512)
+        .expect("hashed with sha512")
+        .to_string();
+    let shasum = select(&digests, Algorithm::Sha1)
+        .expect("hashed with sha1")
+        .to_hex()
+        .1;
+
+    Ok(Packed {
+        name: package_json.name,
+        version: package_json.version,
+        tarball,
+        files: sizes,
+        integrity,
+        shasum,
+    })
+}
+
+/// The files of the package in `directory` to pack, relative to it and sorted.
+pub fn package_files(directory: &Path, package_json: &PackageJson) -> Result<Vec<PathBuf>> {
+    let always_ignored = Rules::parse(&ALWAYS_IGNORED.join("\n"));
+
+    let ignored = match &package_json.files {
+        // `files` replaces the ignore files
+        Some(_) => Rules::default(),
+        None => [".npmignore", ".gitignore"]
+            .iter()
+            .map(|name| directory.join(name))
+            .find(|path| path.is_file())
+            .map(|path| std::fs::read_to_string(path).into_diagnostic())
+            .transpose()?
+            .map(|contents| Rules::parse(&contents))
+            .unwrap_or_default(),
+    };
+
+    let mut walked = vec![];
+
+    walk(
+        directory,
+        Path::new(""),
+        &|relative, is_dir| {
+            always_ignored.ignores(relative, is_dir) || ignored.ignores(relative, is_dir)
+        },
+        &mut walked,
+    )?;
+
+    let main = package_json
+        .main
+        .as_deref()
+        .map(|main| main.trim_start_matches("./").to_string());
+
+    let mut files: Vec<PathBuf> = walked
+        .into_iter()
+        .filter(|file| {
+            let relative = to_slashes(file);
+
+            let always_included = relative == "package.json"
+                || main.as_deref() == Some(relative.as_str())
+                || (!relative.contains('/') && {
+                    let lowercase = relative.to_lowercase();
+
+                    ALWAYS_INCLUDED
+                        .iter()
+                        .any(|name| lowercase.split('.').next() == Some(*name))
+                });
+
+            always_included
+                || package_json
+                    .files
+                    .as_ref()
+                    .map_or(true, |patterns| listed(patterns, &relative))
+        })
+        .collect();
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Collect the files under `directory/relative` that `ignored` keeps.
+fn walk(
+    directory: &Path,
+    relative: &Path,
+    ignored: &dyn Fn(&str, bool) -> bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let path = directory.join(relative);
+
+    let entries = std::fs::read_dir(&path)
+        .map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?
+        .collect::<Result<Vec<_>, _>>()
+        .into_diagnostic()?;
+
+    for entry in entries {
+        let file = relative.join(entry.file_name());
+        let file_type = entry.file_type().into_diagnostic()?;
+
+        // an ignored directory can't have its files included back, like with git
+        if ignored(&to_slashes(&file), file_type.is_dir()) {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            walk(directory, &file, ignored, files)?;
+        } else if file_type.is_file() {
+            files.push(file);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the `files` field lists a file, directly or through one of its directories.
+fn listed(patterns: &[String], relative: &str) -> bool {
+    let mut included = false;
+
+    for pattern in patterns {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern.as_str()),
+        };
+
+        let pattern = pattern.trim_start_matches("./").trim_matches('/');
+
+        let matched = glob::matches(pattern, relative)
+            || ancestors(relative).any(|directory| glob::matches(pattern, directory));
+
+        if matched {
+            included = !negated;
+        }
+    }
+
+    included
+}
+
+/// The directories containing a relative path, innermost last: `a/b/c` gives `a`, `a/b`.
+fn ancestors(relative: &str) -> impl Iterator<Item = &str> {
+    relative
+        .match_indices('/')
+        .map(move |(index, _)| &relative[..index])
+}
+
+fn to_slashes(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// The rules of a gitignore-style file, the last matching rule winning.
+#[derive(Debug, Default)]
+struct Rules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    pattern: String,
+    negated: bool,
+    /// Written with a trailing `/`: only matches directories
+    directory_only: bool,
+}
+
+impl Rules {
+    fn parse(contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(line) => (true, line),
+                    None => (false, line),
+                };
+
+                let directory_only = line.ends_with('/');
+                let line = line.trim_end_matches('/');
+
+                // a pattern with a slash is relative to the root, any other matches at
+                // every depth
+                let pattern = if line.contains('/') {
+                    line.trim_start_matches('/').to_string()
+                } else {
+                    format!("**/{}", line)
+                };
+
+                Rule {
+                    pattern,
+                    negated,
+                    directory_only,
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    fn ignores(&self, relative: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+
+        for rule in &self.rules {
+            if rule.directory_only && !is_dir {
+                continue;
+            }
+
+            if glob::matches(&rule.pattern, relative) {
+                ignored = !rule.negated;
+            }
+        }
+
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::package_files;
+    use crate::utils::package::PackageJson;
+
+    use std::{fs, path::PathBuf};
+
+    #[test]
+    fn selects_published_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+
+        for file in [
+            "package.json",
+            "README.md",
+            "LICENSE",
+            "index.js",
+            "lib/a.js",
+            "lib/a.test.js",
+            "test/a.js",
+            "node_modules/dep/index.js",
+            "volt.lock",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        fs::write(root.join(".npmignore"), "test/\n*.test.js\n").unwrap();
+
+        let mut package_json = PackageJson {
+            name: "@scope/a".to_string(),
+            version: "1.0.0".to_string(),
+            ..Default::default()
+        };
+
+        let files = |package_json: &PackageJson| -> Vec<String> {
+            package_files(root, package_json)
+                .unwrap()
+                .iter()
+                .map(|file: &PathBuf| file.to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+
+        assert_eq!(
+            files(&package_json),
+            [
+                "LICENSE",
+                "README.md",
+                "index.js",
+                "lib/a.js",
+                "package.json"
+            ]
+        );
+
+        package_json.files = Some(vec!["lib".to_string(), "!lib/*.test.js".to_string()]);
+        package_json.main = Some("./index.js".to_string());
+
+        assert_eq!(
+            files(&package_json),
+            [
+                "LICENSE",
+                "README.md",
+                "index.js",
+                "lib/a.js",
+                "package.json"
+            ]
+        );
+    }
+}
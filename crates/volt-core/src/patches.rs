@@ -0,0 +1,626 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Changes to installed packages, kept in the project and applied on every install.
+//!
+//! `volt patch <pkg>` writes the published files of a package to a directory to edit, and
+//! `volt patch-commit <dir>` saves what was changed in them as a unified diff,
+//! `patches/<name>@<version>.patch` (`@scope+name@1.0.0.patch` for scoped packages).
+//! Every install applies the patches of the project to the packages they name once they
+//! are extracted, so patched packages are extracted again rather than kept.
+//!
+//! Patches only change text files: added, removed and modified ones.
+
+use crate::{
+    config::VoltConfig,
+    diff::{diff_files, fetch_files},
+    install::Resolution,
+    linker::Linker,
+    registry::RegistryClient,
+    utils::{
+        errors::{FilesystemError, ResolutionError, VoltError},
+        package::NpmPackage,
+    },
+    view::resolve_version,
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde_json::Value;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Directory of the patches, in the project.
+pub const PATCHES_DIRECTORY: &str = "patches";
+
+/// A patch saved by [`commit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Committed {
+    pub name: String,
+    pub version: String,
+    /// The patch file, `None` when nothing was changed and the patch was removed
+    pub path: Option<PathBuf>,
+    /// The files changed
+    pub files: usize,
+}
+
+/// The name of the patch file of `name@version`.
+pub fn patch_file_name(name: &str, version: &str) -> String {
+    format!("{}@{}.patch", name.replace('/', "+"), version)
+}
+
+/// The patches of the project of `config`, by the `name@version` they apply to.
+pub fn read_patches(config: &VoltConfig) -> Result<BTreeMap<String, String>> {
+    let directory = config.cwd()?.join(PATCHES_DIRECTORY);
+
+    let entries = match std::fs::read_dir(&directory) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(BTreeMap::new()),
+    };
+
+    let mut patches = BTreeMap::new();
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+
+        let key = match path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".patch"))
+        {
+            Some(key) => key.replacen('+', "/", 1),
+            None => continue,
+        };
+
+        let contents = std::fs::read_to_string(&path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        patches.insert(key, contents);
+    }
+
+    Ok(patches)
+}
+
+/// Write the published files of `name` to a directory to edit, with its patch applied if
+/// the project has one, and return the version and the directory. The version is the
+/// `requested` one, or else the one installed, or else the latest.
+pub async fn prepare(
+    config: &VoltConfig,
+    name: &str,
+    requested: Option<&str>,
+) -> Result<(String, PathBuf)> {
+    let packument = RegistryClient::new(config)?.packument(name).await?;
+    let package: NpmPackage = serde_json::from_value(packument).into_diagnostic()?;
+
+    let installed = installed_version(config, name);
+
+    let version = match requested.or(installed.as_deref()) {
+        Some(requested) => resolve_version(&package, Some(requested)),
+        None => resolve_version(&package, None),
+    }
+    .ok_or_else(|| ResolutionError::NoMatchingVersion {
+        name: name.to_string(),
+        requested: requested.unwrap_or("latest").to_string(),
+    })?;
+
+    let files = fetch_files(config, &package, &version).await?;
+
+    let directory = std::env::temp_dir().join("volt-patch").join(format!(
+        "{}@{}",
+        name.replace('/', "+"),
+        version
+    ));
+
+    if directory.exists() {
+        std::fs::remove_dir_all(&directory).map_err(|e| FilesystemError::Remove {
+            source: e,
+            path: directory.display().to_string(),
+        })?;
+    }
+
+    for (path, contents) in &files {
+        write_file(&directory.join(path), contents)?;
+    }
+
+    let key = format!("{}@{}", name, version);
+
+    if let Some(patch) = read_patches(config)?.get(&key) {
+        apply_patch(&directory, patch).map_err(|reason| VoltError::PatchFailed {
+            package: key.clone(),
+            patch: patch_file_name(name, &version),
+            reason,
+        })?;
+    }
+
+    Ok((version, directory))
+}
+
+/// Save the changes made in `directory` (from [`prepare`]) to the published files of its
+/// package as the patch of the project of `config`.
+pub async fn commit(config: &VoltConfig, directory: &Path) -> Result<Committed> {
+    let edited = read_directory(directory)?;
+
+    let manifest: Value = edited
+        .get("package.json")
+        .and_then(|contents| serde_json::from_slice(contents).ok())
+        .ok_or_else(|| miette::miette!("{} has no package.json", directory.display()))?;
+
+    let (name, version) = match (manifest["name"].as_str(), manifest["version"].as_str()) {
+        (Some(name), Some(version)) => (name.to_string(), version.to_string()),
+        _ => miette::bail!(
+            "the package.json of {} has no name or version",
+            directory.display()
+        ),
+    };
+
+    let packument = RegistryClient::new(config)?.packument(&name).await?;
+    let package: NpmPackage = serde_json::from_value(packument).into_diagnostic()?;
+
+    let published = fetch_files(config, &package, &version).await?;
+
+    let mut patch = String::new();
+    let changes = diff_files(&published, &edited);
+
+    for change in &changes {
+        match &change.patch {
+            Some(diff) => {
+                patch.push_str(&format!("diff --git a/{} b/{}\n", change.path, change.path));
+                patch.push_str(diff);
+            }
+            None => miette::bail!(
+                "{} is a binary file, patches can only change text files",
+                change.path
+            ),
+        }
+    }
+
+    let path = config
+        .cwd()?
+        .join(PATCHES_DIRECTORY)
+        .join(patch_file_name(&name, &version));
+
+    if changes.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| FilesystemError::Remove {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+        }
+
+        return Ok(Committed {
+            name,
+            version,
+            path: None,
+            files: 0,
+        });
+    }
+
+    write_file(&path, patch.as_bytes())?;
+
+    Ok(Committed {
+        name,
+        version,
+        path: Some(path),
+        files: changes.len(),
+    })
+}
+
+/// Apply `patches` (from [`read_patches`]) to the packages of `resolution` in the
+/// directories of `linker`, and return the `name@version` of those that match no package.
+pub fn apply_patches(
+    resolution: &Resolution,
+    linker: &dyn Linker,
+    patches: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    let mut unused = vec![];
+
+    for (key, patch) in patches {
+        let package = match resolution
+            .tree
+            .get(key)
+            .filter(|package| !package.is_link())
+        {
+            Some(package) => package,
+            None => {
+                unused.push(key.clone());
+
+                continue;
+            }
+        };
+
+        for directory in linker.directories(key) {
+            apply_patch(&directory, patch).map_err(|reason| VoltError::PatchFailed {
+                package: key.clone(),
+                patch: patch_file_name(&package.name, &package.version),
+                reason,
+            })?;
+        }
+    }
+
+    Ok(unused)
+}
+
+/// Apply a unified diff to the files in `directory`, or say why it doesn't apply.
+pub fn apply_patch(directory: &Path, patch: &str) -> Result<(), String> {
+    for file in parse_patch(patch)? {
+        let target = match file.new.as_ref().or(file.old.as_ref()) {
+            Some(target) => target,
+            None => continue,
+        };
+
+        let path = directory.join(target);
+
+        let contents = match &file.old {
+            Some(_) => {
+                std::fs::read_to_string(&path).map_err(|_| format!("{} is missing", target))?
+            }
+            None => String::new(),
+        };
+
+        let patched =
+            apply_hunks(&contents, &file.hunks).ok_or_else(|| format!("{} has changed", target))?;
+
+        let written = match file.new {
+            Some(_) => write_file(&path, patched.as_bytes()).is_ok(),
+            None => std::fs::remove_file(&path).is_ok(),
+        };
+
+        if !written {
+            return Err(format!("{} can't be written", target));
+        }
+    }
+
+    Ok(())
+}
+
+/// The changes a patch makes to one file.
+#[derive(Debug, PartialEq)]
+struct FilePatch {
+    /// Path of the file before, `None` for added files
+    old: Option<String>,
+    /// Path of the file after, `None` for removed files
+    new: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Hunk {
+    /// Line of the old file the hunk starts at, from 1 (the line before it when it only
+    /// adds lines)
+    old_start: usize,
+    /// The lines it replaces, with their line endings
+    old: Vec<String>,
+    /// The lines it replaces them with
+    new: Vec<String>,
+}
+
+/// The files of a unified diff, or what is wrong with it.
+fn parse_patch(patch: &str) -> Result<Vec<FilePatch>, String> {
+    let mut lines = patch.split_inclusive('\n').peekable();
+    let mut files = vec![];
+
+    while let Some(line) = lines.next() {
+        let old = match line.strip_prefix("--- ") {
+            Some(old) => old,
+            None => continue,
+        };
+
+        let new = lines
+            .next()
+            .and_then(|line| line.strip_prefix("+++ "))
+            .ok_or_else(|| format!("no `+++` line after `--- {}`", old.trim_end()))?;
+
+        let mut file = FilePatch {
+            old: file_path(old),
+            new: file_path(new),
+            hunks: vec![],
+        };
+
+        while let Some(header) = lines.peek().and_then(|line| line.strip_prefix("@@ ")) {
+            let (old_start, mut old_left, mut new_left) = parse_hunk_header(header)
+                .ok_or_else(|| format!("malformed hunk `@@ {}`", header.trim_end()))?;
+
+            lines.next();
+
+            let mut hunk = Hunk {
+                old_start,
+                old: vec![],
+                new: vec![],
+            };
+
+            while old_left > 0 || new_left > 0 {
+                let line = lines.next().ok_or("a hunk is cut short")?;
+
+                // editors strip the space of empty context lines
+                let (sign, text) = match line {
+                    "\n" | "\r\n" => (' ', line),
+                    _ => (
+                        line.chars().next().unwrap_or(' '),
+                        line.get(1..).unwrap_or_default(),
+                    ),
+                };
+
+                let (old, new) = match sign {
+                    ' ' if old_left > 0 && new_left > 0 => (true, true),
+                    '-' if old_left > 0 => (true, false),
+                    '+' if new_left > 0 => (false, true),
+                    _ => return Err(format!("unexpected line in a hunk: `{}`", line.trim_end())),
+                };
+
+                if old {
+                    hunk.old.push(text.to_string());
+                    old_left -= 1;
+                }
+
+                if new {
+                    hunk.new.push(text.to_string());
+                    new_left -= 1;
+                }
+
+                // `\ No newline at end of file` applies to the line before it
+                if lines.peek().map_or(false, |line| line.starts_with('\\')) {
+                    lines.next();
+
+                    for (side, changed) in [(&mut hunk.old, old), (&mut hunk.new, new)] {
+                        if let Some(last) = side.last_mut().filter(|_| changed) {
+                            let trimmed = last.trim_end_matches('\n').len();
+
+                            last.truncate(trimmed);
+                        }
+                    }
+                }
+            }
+
+            file.hunks.push(hunk);
+        }
+
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// The path in the package of a `---` or `+++` line, `None` for `/dev/null`.
+fn file_path(line: &str) -> Option<String> {
+    // `a/lib/index.js\t2021-05-01 12:34:56`
+    let path = line.trim_end().split('\t').next().unwrap_or_default();
+
+    if path == "/dev/null" {
+        return None;
+    }
+
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+
+    Some(path.to_string())
+}
+
+/// The start of the old side and the lengths of both sides of `-1,3 +1,4 @@`.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, length)) => Some((start.parse().ok()?, length.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+
+    let mut parts = header.split_whitespace();
+
+    let (old_start, old_length) = range(parts.next()?.strip_prefix('-')?)?;
+    let (_, new_length) = range(parts.next()?.strip_prefix('+')?)?;
+
+    Some((old_start, old_length, new_length))
+}
+
+/// `contents` with `hunks` applied, searching around where each hunk says it starts when
+/// the lines before it changed, or `None` if one doesn't match.
+fn apply_hunks(contents: &str, hunks: &[Hunk]) -> Option<String> {
+    let mut lines: Vec<String> = contents.split_inclusive('\n').map(String::from).collect();
+
+    // how far the lines moved from where the patch expects them
+    let mut offset = 0isize;
+
+    for hunk in hunks {
+        let start = if hunk.old.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+
+        let expected = (start as isize + offset).max(0) as usize;
+
+        let matches = |position: usize| {
+            position + hunk.old.len() <= lines.len()
+                && lines[position..position + hunk.old.len()] == hunk.old[..]
+        };
+
+        let position = (0..=lines.len()).find_map(|distance| {
+            [expected.checked_sub(distance), Some(expected + distance)]
+                .into_iter()
+                .flatten()
+                .find(|position| matches(*position))
+        })?;
+
+        lines.splice(
+            position..position + hunk.old.len(),
+            hunk.new.iter().cloned(),
+        );
+
+        offset =
+            position as isize - start as isize + hunk.new.len() as isize - hunk.old.len() as isize;
+    }
+
+    Some(lines.concat())
+}
+
+/// The version of `name` in node_modules of the project of `config`, if it's installed.
+fn installed_version(config: &VoltConfig, name: &str) -> Option<String> {
+    let manifest = config.node_modules().ok()?.join(name).join("package.json");
+
+    let manifest: Value = serde_json::from_slice(&std::fs::read(manifest).ok()?).ok()?;
+
+    manifest["version"].as_str().map(ToString::to_string)
+}
+
+/// The files under `directory`, without `node_modules`, by their path in it.
+fn read_directory(directory: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+
+    for entry in jwalk::WalkDir::new(directory)
+        .process_read_dir(|_, _, _, children| {
+            children.retain(|child| {
+                child
+                    .as_ref()
+                    .map_or(true, |child| child.file_name() != "node_modules")
+            });
+        })
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+    {
+        let path = entry.path();
+
+        let relative = path
+            .strip_prefix(directory)
+            .into_diagnostic()?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let contents = std::fs::read(&path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        files.insert(relative, contents);
+    }
+
+    Ok(files)
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: parent.display().to_string(),
+        })?;
+    }
+
+    std::fs::write(path, contents).map_err(|e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_patch, parse_patch};
+    use crate::diff::diff_files;
+
+    use std::collections::BTreeMap;
+
+    fn files(files: &[(&str, &str)]) -> BTreeMap<String, Vec<u8>> {
+        files
+            .iter()
+            .map(|(path, contents)| (path.to_string(), contents.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn applies_the_patches_it_makes() {
+        let published = files(&[
+            (
+                "index.js",
+                "const a = 1;\nconst b = 2;\nmodule.exports = a + b;\n",
+            ),
+            ("lib/old.js", "gone\n"),
+            ("README.md", "# a\n"),
+        ]);
+
+        let edited = files(&[
+            (
+                "index.js",
+                "const a = 1;\nconst b = 3;\nmodule.exports = a + b;",
+            ),
+            ("lib/new.js", "module.exports = 'new';\n"),
+            ("README.md", "# a\n"),
+        ]);
+
+        let patch: String = diff_files(&published, &edited)
+            .into_iter()
+            .filter_map(|file| file.patch)
+            .collect();
+
+        let directory = tempfile::tempdir().unwrap();
+
+        for (path, contents) in &published {
+            let path = directory.path().join(path);
+
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        apply_patch(directory.path(), &patch).unwrap();
+
+        for (path, contents) in &edited {
+            assert_eq!(
+                &std::fs::read(directory.path().join(path)).unwrap(),
+                contents
+            );
+        }
+
+        assert!(!directory.path().join("lib/old.js").exists());
+    }
+
+    #[test]
+    fn finds_hunks_that_moved() {
+        let patch = "--- a/index.js\n+++ b/index.js\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n";
+
+        let directory = tempfile::tempdir().unwrap();
+        let index = directory.path().join("index.js");
+
+        std::fs::write(&index, "// added since\na\nb\n").unwrap();
+        apply_patch(directory.path(), patch).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&index).unwrap(),
+            "// added since\na\nc\n"
+        );
+
+        std::fs::write(&index, "a\nd\n").unwrap();
+
+        assert_eq!(
+            apply_patch(directory.path(), patch),
+            Err("index.js has changed".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_patches() {
+        assert!(parse_patch("--- a/index.js\n@@ -1 +1 @@\n").is_err());
+        assert!(parse_patch("--- a/index.js\n+++ b/index.js\n@@ -1,2 +1,2 @@\n a\n").is_err());
+        assert_eq!(parse_patch("not a patch\n"), Ok(vec![]));
+    }
+}
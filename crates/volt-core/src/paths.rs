@@ -0,0 +1,206 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Paths in `node_modules` that work on every filesystem.
+//!
+//! - Windows limits paths to 260 characters unless they start with `\\?\`, which deep
+//!   trees (`node_modules/.volt/<package>/node_modules/<package>/lib/...`) go past:
+//!   [`long_path`] adds the prefix to the paths that need it.
+//! - Symlinks on windows need developer mode (or an elevated prompt): [`symlink_dir`]
+//!   falls back to a junction when windows refuses to create one.
+//! - Windows and macOS don't tell `JSONStream` from `jsonstream` by default:
+//!   [`case_collision`] finds the paths a layout would have twice there.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The longest path windows takes without the `\\?\` prefix.
+pub const MAX_PATH: usize = 260;
+
+/// `path` as windows takes it when it's longer than [`MAX_PATH`]: absolute, with `\\?\`
+/// (`\\?\UNC\` for network shares) and only backslashes. Other paths, and every path on
+/// other platforms, are left as they are.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> PathBuf {
+    use std::path::{Component, Prefix};
+
+    if path.as_os_str().len() < MAX_PATH || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    // `\\?\` turns off the normalization of `/`, `.` and `..`, so the path is rebuilt from
+    // its components
+    let mut long = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => match prefix.kind() {
+                Prefix::Disk(disk) => long.push(format!(r"\\?\{}:", disk as char)),
+                Prefix::UNC(server, share) => {
+                    let mut unc = std::ffi::OsString::from(r"\\?\UNC\");
+                    unc.push(server);
+                    unc.push(r"\");
+                    unc.push(share);
+
+                    long.push(unc);
+                }
+                // already verbatim, or a device
+                _ => return path.to_path_buf(),
+            },
+            Component::RootDir => long.push(r"\"),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                long.pop();
+            }
+            Component::Normal(name) => long.push(name),
+        }
+    }
+
+    long
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// Link the directory `link` to `target`: a symlink where the system lets volt create
+/// them, and a junction on windows without developer mode.
+#[cfg(windows)]
+pub fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // ERROR_PRIVILEGE_NOT_HELD
+    const NO_SYMLINK_PRIVILEGE: i32 = 1314;
+
+    // remembered after the first refusal, so that it isn't asked for every package
+    static SYMLINKS: AtomicBool = AtomicBool::new(true);
+
+    if SYMLINKS.load(Ordering::Relaxed) {
+        match std::os::windows::fs::symlink_dir(target, link) {
+            Err(e) if e.raw_os_error() == Some(NO_SYMLINK_PRIVILEGE) => {
+                tracing::debug!("symlinks need developer mode, linking with junctions");
+
+                SYMLINKS.store(false, Ordering::Relaxed);
+            }
+            result => return result,
+        }
+    }
+
+    junction::create(target, link)
+}
+
+#[cfg(unix)]
+pub fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Whether the filesystem holding the existing `directory` tells apart names that only
+/// differ in case.
+pub fn is_case_sensitive(directory: &Path) -> bool {
+    let probe = match tempfile::Builder::new()
+        .prefix(".volt-case-probe")
+        .tempfile_in(directory)
+    {
+        Ok(probe) => probe,
+        // guess from the platform when the directory can't be written to
+        Err(_) => return !cfg!(any(windows, target_os = "macos")),
+    };
+
+    let name = probe
+        .path()
+        .file_name()
+        .map(|name| name.to_string_lossy().to_uppercase())
+        .unwrap_or_default();
+
+    directory.join(name).symlink_metadata().is_err()
+}
+
+/// Two of `paths` that are the same path on a filesystem that doesn't tell case apart.
+pub fn case_collision(paths: &BTreeSet<PathBuf>) -> Option<(PathBuf, PathBuf)> {
+    let mut seen: HashMap<String, &PathBuf> = HashMap::new();
+
+    for path in paths {
+        let folded = path.to_string_lossy().to_lowercase();
+
+        if let Some(first) = seen.insert(folded, path) {
+            return Some((first.clone(), path.clone()));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{case_collision, long_path};
+
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn finds_paths_that_only_differ_in_case() {
+        let paths = [
+            "node_modules/JSONStream",
+            "node_modules/.volt/jsonstream@1.3.5",
+            "node_modules/jsonstream",
+            "node_modules/send",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+
+        assert_eq!(
+            case_collision(&paths),
+            Some((
+                PathBuf::from("node_modules/JSONStream"),
+                PathBuf::from("node_modules/jsonstream")
+            ))
+        );
+
+        let paths = ["node_modules/a", "node_modules/b"]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+
+        assert_eq!(case_collision(&paths), None);
+    }
+
+    #[test]
+    fn leaves_short_paths_alone() {
+        let path = Path::new("node_modules/.volt/send@0.17.2/node_modules/send");
+
+        assert_eq!(long_path(path), path);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn prefixes_long_paths() {
+        let deep = "a/".repeat(200);
+
+        assert_eq!(
+            long_path(&Path::new(r"C:\project").join(&deep).join("../index.js")),
+            PathBuf::from(format!(r"\\?\C:\project\{}index.js", r"a\".repeat(199)))
+        );
+
+        assert_eq!(
+            long_path(&Path::new(r"\\server\share").join(&deep)),
+            PathBuf::from(format!(r"\\?\UNC\server\share\{}", r"a\".repeat(199) + "a"))
+        );
+    }
+}
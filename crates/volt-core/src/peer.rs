@@ -0,0 +1,291 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check the peer dependencies of a resolved tree.
+//!
+//! A package finds its peers among its own dependencies or, like everything else in the
+//! virtual store, among the packages linked into the project's `node_modules`. Peers marked
+//! optional in `peerDependenciesMeta` may be missing, but not of another version.
+
+use crate::install::Resolution;
+
+use miette::Diagnostic;
+use node_semver::{Range, Version};
+use thiserror::Error;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A peer dependency that isn't satisfied, with every package that asked for it.
+#[derive(Debug, Error, Diagnostic)]
+pub enum PeerWarning {
+    #[error("missing peer dependency {name}")]
+    #[diagnostic(
+        code(ERESOLVE),
+        severity(Warning),
+        help("required by {}\nadd {name}@{range} to your dependencies or run `volt install --install-peers`", requirements(.required_by))
+    )]
+    Missing {
+        name: String,
+        /// The range satisfying every dependent
+        range: String,
+        required_by: Vec<(String, String)>,
+    },
+
+    #[error("incompatible peer dependency {name}@{installed}")]
+    #[diagnostic(
+        code(ERESOLVE),
+        severity(Warning),
+        help("required by {}", requirements(.required_by))
+    )]
+    Incompatible {
+        name: String,
+        installed: String,
+        /// Only the requirements that `installed` doesn't satisfy
+        required_by: Vec<(String, String)>,
+    },
+
+    #[error("conflicting peer dependency {name}")]
+    #[diagnostic(
+        code(ERESOLVE),
+        severity(Warning),
+        help("no version satisfies every dependent: required by {}", requirements(.required_by))
+    )]
+    Conflicting {
+        name: String,
+        required_by: Vec<(String, String)>,
+    },
+}
+
+fn requirements(required_by: &[(String, String)]) -> String {
+    required_by
+        .iter()
+        .map(|(dependent, range)| format!("{} ({})", dependent, range))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Check every peer dependency of the tree, grouping the problems by peer.
+pub fn check_peers(resolution: &Resolution) -> Vec<PeerWarning> {
+    // peer name -> (dependent key, range)
+    let mut requirements: BTreeMap<&str, Vec<(String, String)>> = BTreeMap::new();
+    // (peer name, dependent key) of the peers dependents work without
+    let mut optional = BTreeSet::new();
+
+    for (key, package) in &resolution.tree {
+        for (name, range) in package.peer_dependencies.iter().flatten() {
+            // a package that depends on its peer directly provides it to itself
+            let provided = package
+                .installed_dependencies()
+                .any(|(dependency, _)| dependency == name);
+
+            if !provided {
+                requirements
+                    .entry(name)
+                    .or_default()
+                    .push((key.clone(), range.clone()));
+
+                if package.is_optional_peer(name) {
+                    optional.insert((name.as_str(), key.as_str()));
+                }
+            }
+        }
+    }
+
+    let mut warnings = vec![];
+
+    for (name, mut required_by) in requirements {
+        required_by.sort();
+
+        let installed = resolution
+            .direct_packages()
+            .find(|package| package.name == name);
+
+        match installed {
+            Some(package) => {
+                let version = match package.version.parse::<Version>() {
+                    Ok(version) => version,
+                    Err(_) => continue,
+                };
+
+                // tags and urls can't be checked
+                required_by.retain(|(_, range)| {
+                    range
+                        .parse::<Range>()
+                        .map_or(false, |range| !version.satisfies(&range))
+                });
+
+                if !required_by.is_empty() {
+                    warnings.push(PeerWarning::Incompatible {
+                        name: name.to_string(),
+                        installed: package.version.clone(),
+                        required_by,
+                    });
+                }
+            }
+            None => {
+                // the dependents working without the peer don't need it installed
+                required_by
+                    .retain(|(dependent, _)| !optional.contains(&(name, dependent.as_str())));
+
+                if required_by.is_empty() {
+                    continue;
+                }
+
+                match intersection(&required_by) {
+                    Some(range) => warnings.push(PeerWarning::Missing {
+                        name: name.to_string(),
+                        range,
+                        required_by,
+                    }),
+                    None => warnings.push(PeerWarning::Conflicting {
+                        name: name.to_string(),
+                        required_by,
+                    }),
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// The range every requirement agrees on, if there is one.
+fn intersection(required_by: &[(String, String)]) -> Option<String> {
+    let mut ranges = required_by.iter().map(|(_, range)| range);
+
+    let first = ranges.next()?;
+
+    // keep the range as written when everyone asks for the same thing
+    if required_by.iter().all(|(_, range)| range == first) {
+        return Some(first.clone());
+    }
+
+    let mut combined = match first.parse::<Range>() {
+        Ok(range) => range,
+        // tags and urls are installed as requested
+        Err(_) => return Some(first.clone()),
+    };
+
+    for range in ranges {
+        if let Ok(range) = range.parse::<Range>() {
+            combined = combined.intersect(&range)?;
+        }
+    }
+
+    Some(combined.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_peers, PeerWarning};
+    use crate::{
+        install::Resolution,
+        utils::voltapi::{VoltPackage, OPTIONAL_PEER},
+    };
+
+    fn package(name: &str, version: &str, peers: &[(&str, &str)]) -> VoltPackage {
+        VoltPackage {
+            peer_dependencies: Some(
+                peers
+                    .iter()
+                    .map(|(n, r)| (n.to_string(), r.to_string()))
+                    .collect(),
+            ),
+            ..VoltPackage::test(name, version, &[])
+        }
+    }
+
+    fn resolution(packages: Vec<VoltPackage>, direct: &[&str]) -> Resolution {
+        Resolution {
+            tree: packages
+                .into_iter()
+                .map(|package| (format!("{}@{}", package.name, package.version), package))
+                .collect(),
+            direct: direct.iter().map(|key| key.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_peer_problems() {
+        let missing = resolution(
+            vec![
+                package("react-dom", "17.0.2", &[("react", "^17.0.0")]),
+                package("react-router", "6.0.0", &[("react", ">=16.8")]),
+            ],
+            &["react-dom@17.0.2", "react-router@6.0.0"],
+        );
+
+        match check_peers(&missing).as_slice() {
+            [PeerWarning::Missing {
+                name, required_by, ..
+            }] => {
+                assert_eq!(name, "react");
+                assert_eq!(required_by.len(), 2);
+            }
+            warnings => panic!("unexpected warnings: {:?}", warnings),
+        }
+
+        let conflicting = resolution(
+            vec![
+                package("a", "1.0.0", &[("react", "^16.0.0")]),
+                package("b", "1.0.0", &[("react", "^17.0.0")]),
+            ],
+            &["a@1.0.0", "b@1.0.0"],
+        );
+
+        assert!(matches!(
+            check_peers(&conflicting).as_slice(),
+            [PeerWarning::Conflicting { .. }]
+        ));
+
+        let incompatible = resolution(
+            vec![
+                package("react", "16.14.0", &[]),
+                package("react-dom", "17.0.2", &[("react", "^17.0.0")]),
+            ],
+            &["react@16.14.0", "react-dom@17.0.2"],
+        );
+
+        assert!(matches!(
+            check_peers(&incompatible).as_slice(),
+            [PeerWarning::Incompatible { installed, .. }] if installed == "16.14.0"
+        ));
+    }
+
+    #[test]
+    fn optional_peers_may_be_missing() {
+        let mut plugin = package("plugin", "1.0.0", &[("typescript", "^4.0.0")]);
+
+        plugin.peer_dependencies_meta =
+            Some([("typescript".to_string(), OPTIONAL_PEER.to_string())].into());
+
+        let missing = resolution(vec![plugin.clone()], &["plugin@1.0.0"]);
+
+        assert!(check_peers(&missing).is_empty());
+
+        // still not of another version
+        let incompatible = resolution(
+            vec![plugin, package("typescript", "3.9.0", &[])],
+            &["plugin@1.0.0", "typescript@3.9.0"],
+        );
+
+        assert!(matches!(
+            check_peers(&incompatible).as_slice(),
+            [PeerWarning::Incompatible { .. }]
+        ));
+    }
+}
@@ -0,0 +1,265 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The downloads, integrity checks and extractions of an install, as stages running at
+//! once.
+//!
+//! Each package goes through the stages in order, but while some are being extracted the
+//! next ones are already being hashed and downloaded: `concurrency` downloads, a hashing
+//! per cpu and `concurrency` extractions at a time. The stages are joined by bounded
+//! channels, so a slow disk holds the downloads back instead of piling tarballs up in
+//! memory, and a slow network leaves the disk free for the packages already there.
+//! Packages in the store skip the stages and are copied out of it.
+//!
+//! The time of the stages, and of every package in them, goes to the
+//! [`InstallStats`](crate::summary::InstallStats) of the run.
+
+use crate::{
+    config::VoltConfig,
+    summary::Phase,
+    tarball::Tarball,
+    transaction::Transaction,
+    utils::{
+        copy_from_store, extract_package_tarball, fetch_package_tarball,
+        verify_existing_installation, verify_package_tarball, voltapi::VoltPackage, State,
+    },
+};
+
+use futures::{channel::mpsc, stream, SinkExt, StreamExt};
+use miette::{IntoDiagnostic, Result};
+
+use std::{path::PathBuf, sync::Arc, time::Instant};
+
+/// A package to install, into the directories the linker gives it.
+pub struct Job {
+    pub key: String,
+    pub package: VoltPackage,
+    pub targets: Vec<PathBuf>,
+}
+
+/// A downloaded package, with the directories its files are staged into.
+struct Downloaded {
+    job: Job,
+    staged: Vec<PathBuf>,
+    tarball: Tarball,
+}
+
+/// Install every job through the stages, committing each package into place with
+/// `transaction` once it is extracted; the result of each job, by key.
+pub async fn install_packages(
+    config: &VoltConfig,
+    jobs: Vec<Job>,
+    transaction: Arc<Transaction>,
+    state: &State,
+) -> Vec<(String, Result<()>)> {
+    let concurrency = config.settings().concurrency;
+
+    let start = Instant::now();
+
+    let (mut downloaded_tx, downloaded_rx) = mpsc::channel::<Downloaded>(concurrency);
+    let (mut verified_tx, verified_rx) = mpsc::channel::<Downloaded>(concurrency);
+
+    let reporter = &state.reporter;
+    let transaction = &transaction;
+
+    let download = async move {
+        let mut finished = vec![];
+
+        let mut downloads = stream::iter(jobs)
+            .map(|job| {
+                let transaction = transaction.clone();
+
+                async move {
+                    let key = job.key.clone();
+
+                    (key, fetch(config, &transaction, job, state).await)
+                }
+            })
+            .buffer_unordered(concurrency);
+
+        while let Some((key, fetched)) = downloads.next().await {
+            match fetched {
+                Ok(Some(downloaded)) => {
+                    if downloaded_tx.send(downloaded).await.is_err() {
+                        finished.push((key, Err(stopped())));
+                    }
+                }
+                // copied from the store
+                Ok(None) => {
+                    reporter.extracted(&key);
+
+                    finished.push((key, Ok(())));
+                }
+                Err(error) => finished.push((key, Err(error))),
+            }
+        }
+
+        // the next stages end once they've taken everything
+        drop(downloaded_tx);
+
+        let fetching = start.elapsed();
+
+        config.stats().time(Phase::Fetch, fetching);
+
+        (finished, fetching)
+    };
+
+    let verify = async move {
+        let mut finished = vec![];
+
+        let mut checks = downloaded_rx
+            .map(|downloaded| {
+                let key = downloaded.job.key.clone();
+
+                async move {
+                    let span = tracing::debug_span!("verify", package = %key);
+
+                    let verified = tokio::task::spawn_blocking(move || {
+                        let _span = span.entered();
+
+                        verify_package_tarball(&downloaded.job.package, &downloaded.tarball)
+                            .map(|()| downloaded)
+                    })
+                    .await
+                    .into_diagnostic()
+                    .and_then(|verified| verified);
+
+                    (key, verified)
+                }
+            })
+            .buffer_unordered(rayon::current_num_threads());
+
+        while let Some((key, verified)) = checks.next().await {
+            match verified {
+                Ok(downloaded) => {
+                    if verified_tx.send(downloaded).await.is_err() {
+                        finished.push((key, Err(stopped())));
+                    }
+                }
+                Err(error) => finished.push((key, Err(error))),
+            }
+        }
+
+        drop(verified_tx);
+
+        finished
+    };
+
+    let extract = verified_rx
+        .map(|downloaded| {
+            let key = downloaded.job.key.clone();
+            let config = config.clone();
+            let transaction = transaction.clone();
+
+            let span = tracing::debug_span!("extract", package = %key);
+
+            async move {
+                let extracted = tokio::task::spawn_blocking(move || {
+                    let _span = span.entered();
+                    let start = Instant::now();
+
+                    extract_package_tarball(
+                        &config,
+                        &downloaded.job.package,
+                        &downloaded.tarball,
+                        &downloaded.staged,
+                    )?;
+
+                    let committed =
+                        commit(&transaction, &downloaded.staged, &downloaded.job.targets);
+
+                    config
+                        .stats()
+                        .sample(&["extract", &downloaded.job.key], start.elapsed());
+
+                    committed
+                })
+                .await
+                .into_diagnostic()
+                .and_then(|extracted| extracted);
+
+                (key, extracted)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .inspect(|(key, _)| reporter.extracted(key))
+        .collect::<Vec<_>>();
+
+    let ((downloaded, fetching), verified, extracted) = futures::join!(download, verify, extract);
+
+    // what's left once the last download is done
+    config
+        .stats()
+        .time(Phase::Extract, start.elapsed().saturating_sub(fetching));
+
+    downloaded
+        .into_iter()
+        .chain(verified)
+        .chain(extracted)
+        .collect()
+}
+
+/// Stage the directories of a job and download its tarball, or install it straight from
+/// the store (`None`).
+#[tracing::instrument(level = "debug", skip_all, fields(package = %job.key))]
+async fn fetch(
+    config: &VoltConfig,
+    transaction: &Transaction,
+    job: Job,
+    state: &State,
+) -> Result<Option<Downloaded>> {
+    let staged = job
+        .targets
+        .iter()
+        .map(|_| transaction.stage())
+        .collect::<Result<Vec<_>>>()?;
+
+    let start = Instant::now();
+
+    if let Ok(index) = verify_existing_installation(&job.package, config) {
+        copy_from_store(config, &job.package, index, &staged).await?;
+        commit(transaction, &staged, &job.targets)?;
+
+        config.stats().copied_from_store();
+        config.stats().sample(&["store", &job.key], start.elapsed());
+
+        return Ok(None);
+    }
+
+    let tarball = fetch_package_tarball(config, &job.package, state).await?;
+
+    config.stats().downloaded();
+    config.stats().sample(&["fetch", &job.key], start.elapsed());
+
+    Ok(Some(Downloaded {
+        job,
+        staged,
+        tarball,
+    }))
+}
+
+/// Move the staged directories of a package into place.
+fn commit(transaction: &Transaction, staged: &[PathBuf], targets: &[PathBuf]) -> Result<()> {
+    for (staged, target) in staged.iter().zip(targets) {
+        transaction.commit(staged, target)?;
+    }
+
+    Ok(())
+}
+
+fn stopped() -> miette::Report {
+    miette::miette!("the install stopped before the package was extracted")
+}
@@ -0,0 +1,123 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Match the `os`, `cpu` and `libc` fields of a package against the current machine.
+
+/// The current machine, named the way node does (`process.platform`, `process.arch`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Platform {
+    pub os: &'static str,
+    pub cpu: &'static str,
+    /// `glibc` or `musl` on linux, `None` everywhere else
+    pub libc: Option<&'static str>,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        let os = match std::env::consts::OS {
+            "windows" => "win32",
+            "macos" => "darwin",
+            os => os,
+        };
+
+        let cpu = match std::env::consts::ARCH {
+            "x86_64" => "x64",
+            "x86" => "ia32",
+            "aarch64" => "arm64",
+            "powerpc64" => "ppc64",
+            cpu => cpu,
+        };
+
+        let libc = if os != "linux" {
+            None
+        } else if cfg!(target_env = "musl") {
+            Some("musl")
+        } else {
+            Some("glibc")
+        };
+
+        Self { os, cpu, libc }
+    }
+
+    /// Whether a package restricted to the given `os`, `cpu` and `libc` lists can run here.
+    pub fn supports(
+        &self,
+        os: Option<&[String]>,
+        cpu: Option<&[String]>,
+        libc: Option<&[String]>,
+    ) -> bool {
+        allowed(os, self.os)
+            && allowed(cpu, self.cpu)
+            && self.libc.map_or(true, |value| allowed(libc, value))
+    }
+}
+
+/// npm's matching rules: `["!win32"]` excludes a value, `["linux", "darwin"]` only allows
+/// those values and a missing or empty list allows everything.
+fn allowed(list: Option<&[String]>, value: &str) -> bool {
+    let list = match list {
+        Some(list) if !list.is_empty() => list,
+        _ => return true,
+    };
+
+    if list
+        .iter()
+        .any(|entry| entry.strip_prefix('!') == Some(value))
+    {
+        return false;
+    }
+
+    let mut allowed_values = list
+        .iter()
+        .filter(|entry| !entry.starts_with('!'))
+        .peekable();
+
+    allowed_values.peek().is_none() || allowed_values.any(|entry| entry == value || entry == "any")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Platform;
+
+    fn list(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_npm_platform_rules() {
+        let platform = Platform {
+            os: "linux",
+            cpu: "x64",
+            libc: Some("glibc"),
+        };
+
+        assert!(platform.supports(None, None, None));
+        assert!(platform.supports(Some(&list(&["linux", "darwin"])), None, None));
+        assert!(platform.supports(Some(&list(&["!win32"])), Some(&list(&["x64"])), None));
+        assert!(!platform.supports(Some(&list(&["darwin"])), None, None));
+        assert!(!platform.supports(None, Some(&list(&["!x64"])), None));
+        assert!(!platform.supports(None, None, Some(&list(&["musl"]))));
+
+        let windows = Platform {
+            os: "win32",
+            cpu: "x64",
+            libc: None,
+        };
+
+        // libc only applies to linux
+        assert!(windows.supports(None, None, Some(&list(&["musl"]))));
+    }
+}
@@ -0,0 +1,334 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Run plugins for the commands volt doesn't have.
+//!
+//! `volt <name> [args]` runs the first of `~/.volt/plugins/volt-<name>`,
+//! `~/.volt/plugins/volt-<name>.wasm` (with `wasmtime` or `wasmer`) and `volt-<name>` on
+//! `PATH`, with its arguments and the directory volt was started in.
+//!
+//! The plugin reads its context from stdin, a JSON document holding the command line,
+//! the project (its package.json and volt.lock) and volt's configuration, and may write
+//! lines of JSON to stdout in the format of volt's `--json` output, which volt renders:
+//!
+//! ```json
+//! {"type": "log", "data": {"level": "warn", "message": "3 packages are outdated"}}
+//! {"type": "table", "data": {"headers": ["Package", "Version"], "rows": [["react", "18.2.0"]]}}
+//! {"type": "result", "data": {"outdated": 3}}
+//! ```
+//!
+//! Any other line is printed as it is.
+
+use crate::{
+    config::VoltConfig,
+    model::lock_file::LockFile,
+    registry::RegistryClient,
+    utils::{
+        errors::{ScriptError, VoltError},
+        find_executable,
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{Command, ExitStatus, Stdio},
+    thread,
+};
+
+/// Version of the context plugins are given, changed whenever a field is removed.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A plugin found for a command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Plugin {
+    /// An executable, run directly
+    Executable(PathBuf),
+    /// A WASI module, run with a WebAssembly runtime
+    Wasm(PathBuf),
+}
+
+impl Plugin {
+    /// The plugin running `volt <name>`, if there is one.
+    pub fn find(config: &VoltConfig, name: &str) -> Result<Option<Self>> {
+        // names are file names, never paths
+        if name.is_empty() || name.contains(['/', '\\'].as_ref()) || name.starts_with('.') {
+            return Ok(None);
+        }
+
+        let file_name = format!("volt-{}", name);
+        let plugins = config.volt_home()?.join("plugins");
+
+        let installed = [
+            file_name.clone(),
+            format!("{}.exe", file_name),
+            format!("{}.cmd", file_name),
+        ]
+        .iter()
+        .map(|file_name| plugins.join(file_name))
+        .find(|path| path.is_file());
+
+        if let Some(path) = installed {
+            return Ok(Some(Self::Executable(path)));
+        }
+
+        let wasm = plugins.join(format!("{}.wasm", file_name));
+
+        if wasm.is_file() {
+            return Ok(Some(Self::Wasm(wasm)));
+        }
+
+        Ok(find_executable(&file_name).map(Self::Executable))
+    }
+
+    /// The command running the plugin with `args`.
+    fn command(&self, config: &VoltConfig, args: &[String]) -> Result<Command> {
+        let mut command = match self {
+            Self::Executable(path) => Command::new(path),
+            Self::Wasm(path) => {
+                let cwd = config.cwd()?;
+
+                // WASI modules only see the directories they are given
+                if let Some(wasmtime) = find_executable("wasmtime") {
+                    let mut command = Command::new(wasmtime);
+                    command
+                        .arg("run")
+                        .arg(format!("--dir={}", cwd.display()))
+                        .arg(path)
+                        .arg("--");
+                    command
+                } else if let Some(wasmer) = find_executable("wasmer") {
+                    let mut command = Command::new(wasmer);
+                    command
+                        .arg("run")
+                        .arg(format!("--dir={}", cwd.display()))
+                        .arg(path)
+                        .arg("--");
+                    command
+                } else {
+                    return Err(VoltError::NoWasmRuntime {
+                        plugin: path.display().to_string(),
+                    }
+                    .into());
+                }
+            }
+        };
+
+        command.args(args).current_dir(config.cwd()?);
+
+        if let Ok(volt) = env::current_exe() {
+            // so that plugins can run volt itself
+            command.env("VOLT", volt);
+        }
+
+        Ok(command)
+    }
+}
+
+/// The context given to plugins on stdin.
+pub fn context(config: &VoltConfig, name: &str, args: &[String]) -> Result<Value> {
+    let cwd = config.cwd()?;
+
+    let package_json = std::fs::read(cwd.join("package.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_slice::<Value>(&contents).ok());
+
+    let lock_path = config.lockfile()?;
+
+    let lock_file = if lock_path.exists() {
+        serde_json::to_value(LockFile::load(&lock_path, false).into_diagnostic()?)
+            .into_diagnostic()?
+    } else {
+        Value::Null
+    };
+
+    Ok(json!({
+        "protocol": PROTOCOL_VERSION,
+        "volt": env!("CARGO_PKG_VERSION"),
+        "command": name,
+        "args": args,
+        "cwd": cwd,
+        "project": {
+            "packageJson": package_json,
+            "lockFile": lock_file,
+        },
+        "config": {
+            "json": config.json(),
+            "registry": RegistryClient::new(config)?.url,
+            "home": config.volt_home()?,
+            "store": config.store()?,
+            "nodeModules": config.node_modules()?,
+        },
+    }))
+}
+
+/// Run the plugin of `volt <args[0]>`, failing if there is none.
+pub fn run_plugin(config: &VoltConfig, args: &[String]) -> Result<()> {
+    // clap only gives external subcommands with their name
+    let (name, args) = args.split_first().unwrap();
+
+    let plugin = match Plugin::find(config, name)? {
+        Some(plugin) => plugin,
+        None => return Err(VoltError::UnknownCommand { name: name.clone() }.into()),
+    };
+
+    tracing::debug!("running {:?} for `volt {}`", plugin, name);
+
+    let context = serde_json::to_vec(&context(config, name, args)?).into_diagnostic()?;
+
+    let mut command = plugin.command(config, args)?;
+
+    let status =
+        run_with_input(config, &mut command, context).map_err(|e| ScriptError::CommandSpawn {
+            source: e,
+            command: format!("volt-{}", name),
+        })?;
+
+    if !status.success() {
+        return Err(ScriptError::CommandFailed {
+            command: format!("volt-{}", name),
+            code: status.code().unwrap_or(-1),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Run `command` with `input` on its stdin, rendering the messages it prints to stdout,
+/// and wait for it to exit.
+pub fn run_with_input(
+    config: &VoltConfig,
+    command: &mut Command,
+    input: Vec<u8>,
+) -> io::Result<ExitStatus> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    // written from a thread, so that a command writing before it reads can't block volt
+    let writer = child.stdin.take().map(|mut stdin| {
+        thread::spawn(move || {
+            // a command may exit without reading its input
+            let _ = stdin.write_all(&input);
+        })
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        let reporter = config.reporter();
+
+        for line in BufReader::new(stdout).lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+
+            // messages as they are with `--json`, any other line as it is
+            match serde_json::from_str::<Message>(&line) {
+                Ok(message) if !config.json() => reporter.message(&message),
+                _ => println!("{}", line),
+            }
+        }
+    }
+
+    let status = child.wait()?;
+
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+
+    Ok(status)
+}
+
+/// A line of output of a plugin or hook that volt renders, through the
+/// [`Reporter`](crate::reporter::Reporter).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum Message {
+    Log {
+        level: Level,
+        message: String,
+    },
+    Table {
+        #[serde(default)]
+        headers: Vec<String>,
+        rows: Vec<Vec<Value>>,
+    },
+    Result(Value),
+}
+
+impl Message {
+    /// The text of a cell of a table: strings without their quotes, `null` empty.
+    pub fn cell(value: &Value) -> String {
+        match value {
+            Value::String(string) => string.clone(),
+            Value::Null => String::new(),
+            value => value.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Info,
+    Success,
+    Warn,
+    Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Level, Message};
+
+    #[test]
+    fn parses_plugin_messages() {
+        let message: Message = serde_json::from_str(
+            r#"{"type": "log", "data": {"level": "warn", "message": "outdated"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            message,
+            Message::Log {
+                level: Level::Warn,
+                ..
+            }
+        ));
+
+        let message: Message = serde_json::from_str(
+            r#"{"type": "table", "data": {"rows": [["react", "18.2.0", 3]]}}"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(message, Message::Table { headers, rows } if headers.is_empty() && rows[0].len() == 3)
+        );
+
+        let message: Message =
+            serde_json::from_str(r#"{"type": "result", "data": {"outdated": 3}}"#).unwrap();
+        assert!(matches!(message, Message::Result(value) if value["outdated"] == 3));
+
+        // anything else is printed as it is
+        assert!(serde_json::from_str::<Message>(r#"{"type": "progress"}"#).is_err());
+        assert!(serde_json::from_str::<Message>("done").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Remove what earlier installs left in `node_modules`.
+//!
+//! Only the directories holding packages are looked into (`node_modules`, scopes, the
+//! `.volt` virtual store and the `node_modules` of packages with nested ones), so files of
+//! other tools (`node_modules/.cache`) and what packages ship in their own `node_modules`
+//! are left alone.
+
+use crate::{
+    config::VoltConfig, install::Resolution, io::directory_size, linker::linker, shim::bin_entries,
+    utils::errors::FilesystemError, workspace::Workspace,
+};
+
+use miette::Result;
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+/// What was removed from `node_modules`.
+#[derive(Debug, Default)]
+pub struct Pruned {
+    /// Directories and links removed
+    pub paths: Vec<PathBuf>,
+    /// Size of the files removed with them
+    pub bytes: u64,
+}
+
+/// Remove what the layout of `resolution` (and the links of the workspace members)
+/// doesn't have from the project's `node_modules`.
+pub fn prune_project(
+    config: &VoltConfig,
+    resolution: &Resolution,
+    workspace: Option<&Workspace>,
+) -> Result<Pruned> {
+    let mut pruned = Pruned::default();
+
+    for path in leftovers(config, resolution, workspace)? {
+        remove(&path, &mut pruned)?;
+    }
+
+    Ok(pruned)
+}
+
+/// What [`prune_project`] would remove: the directories, links and `.bin` shims of the
+/// project's `node_modules` that the layout of `resolution` doesn't have.
+pub fn leftovers(
+    config: &VoltConfig,
+    resolution: &Resolution,
+    workspace: Option<&Workspace>,
+) -> Result<Vec<PathBuf>> {
+    let node_modules = config.node_modules()?;
+
+    let mut keep = linker(config, resolution)?.paths(resolution);
+
+    if let Some(workspace) = workspace {
+        keep.extend(
+            workspace
+                .members
+                .iter()
+                .map(|member| node_modules.join(member.name())),
+        );
+    }
+
+    // the shims of the other packages are in the `node_modules` of their dependents
+    let bins = resolution
+        .requested()
+        .into_iter()
+        .filter_map(|key| resolution.tree.get(key))
+        .flat_map(|package| {
+            package
+                .bin
+                .iter()
+                .flat_map(|bin| bin_entries(&package.name, bin))
+                .map(|(name, _)| name)
+        })
+        .collect();
+
+    find_leftovers(&node_modules, &keep, &bins)
+}
+
+/// Everything in `node_modules` that isn't one of the `keep` paths (or on the way to
+/// one), and the `.bin` shims not named in `bins`.
+pub fn find_leftovers(
+    node_modules: &Path,
+    keep: &BTreeSet<PathBuf>,
+    bins: &BTreeSet<String>,
+) -> Result<Vec<PathBuf>> {
+    let mut leftovers = vec![];
+
+    if !node_modules.is_dir() {
+        return Ok(leftovers);
+    }
+
+    walk(node_modules, node_modules, keep, &mut leftovers)?;
+
+    let bin_dir = node_modules.join(".bin");
+
+    for entry in read_dir(&bin_dir)? {
+        let name = entry.to_string_lossy().to_string();
+
+        if !bins.contains(shim_command(&name)) {
+            leftovers.push(bin_dir.join(entry));
+        }
+    }
+
+    Ok(leftovers)
+}
+
+/// The command a file of `.bin` is the shim of: windows shims are `<name>.cmd` and
+/// `<name>.ps1` next to `<name>`.
+pub fn shim_command(file_name: &str) -> &str {
+    file_name
+        .strip_suffix(".cmd")
+        .or_else(|| file_name.strip_suffix(".ps1"))
+        .unwrap_or(file_name)
+}
+
+fn walk(
+    directory: &Path,
+    node_modules: &Path,
+    keep: &BTreeSet<PathBuf>,
+    leftovers: &mut Vec<PathBuf>,
+) -> Result<()> {
+    for name in read_dir(directory)? {
+        let path = directory.join(&name);
+
+        // `.bin` is pruned by the shims it should hold, other dot files belong to other tools
+        if name.to_string_lossy().starts_with('.') {
+            if directory == node_modules && name == ".volt" {
+                walk(&path, node_modules, keep, leftovers)?;
+            }
+
+            continue;
+        }
+
+        let leads_to_kept = keep
+            .range(path.clone()..)
+            .next()
+            .map_or(false, |kept| kept != &path && kept.starts_with(&path));
+
+        if keep.contains(&path) {
+            let nested = path.join("node_modules");
+
+            // hoisted packages hold the versions that conflict with the top ones
+            let holds_kept = keep
+                .range(nested.clone()..)
+                .next()
+                .map_or(false, |kept| kept.starts_with(&nested));
+
+            if holds_kept && !is_symlink(&path) {
+                walk(&nested, node_modules, keep, leftovers)?;
+            }
+        } else if leads_to_kept {
+            walk(&path, node_modules, keep, leftovers)?;
+        } else {
+            leftovers.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_dir(directory: &Path) -> Result<Vec<std::ffi::OsString>> {
+    if !directory.is_dir() || is_symlink(directory) {
+        return Ok(vec![]);
+    }
+
+    let entries = std::fs::read_dir(directory).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: directory.display().to_string(),
+    })?;
+
+    let mut names: Vec<_> = entries
+        .filter_map(|entry| Some(entry.ok()?.file_name()))
+        .collect();
+
+    names.sort();
+
+    Ok(names)
+}
+
+fn is_symlink(path: &Path) -> bool {
+    path.symlink_metadata()
+        .map_or(false, |metadata| metadata.file_type().is_symlink())
+}
+
+/// Remove a directory, or a link without touching its target.
+fn remove(path: &Path, pruned: &mut Pruned) -> Result<()> {
+    let bytes = directory_size(path);
+
+    let result = if path.is_dir() && !is_symlink(path) {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path).or_else(|_| std::fs::remove_dir(path))
+    };
+
+    result.map_err(|e| FilesystemError::Remove {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    tracing::debug!("removed {}", path.display());
+
+    pruned.paths.push(path.to_path_buf());
+    pruned.bytes += bytes;
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Publish a packed package to the registry.
+//!
+//! A new version is published by sending the package document with only that version,
+//! the dist-tag pointing to it and the tarball attached in base64, the registry merging it
+//! into the existing document.
+
+use crate::{mirror::tarball_url, pack::Packed, registry::RegistryClient, reporter::Reporter};
+
+use miette::Result;
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use std::fmt;
+
+/// Who can install a published scoped package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ArgEnum))]
+pub enum Access {
+    Public,
+    Restricted,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Public => write!(f, "public"),
+            Self::Restricted => write!(f, "restricted"),
+        }
+    }
+}
+
+/// The document sent to publish `packed`, whose package.json is `manifest`.
+pub fn publish_document(
+    manifest: &Value,
+    packed: &Packed,
+    registry: &str,
+    tag: &str,
+    access: Option<Access>,
+) -> Value {
+    let mut version = manifest.clone();
+
+    version["_id"] = json!(format!("{}@{}", packed.name, packed.version));
+    version["dist"] = json!({
+        "integrity": packed.integrity,
+        "shasum": packed.shasum,
+        "tarball": tarball_url(registry, &packed.name, &packed.version),
+    });
+
+    json!({
+        "_id": packed.name,
+        "name": packed.name,
+        "description": manifest.get("description"),
+        "dist-tags": { tag: packed.version },
+        "versions": { packed.version.as_str(): version },
+        "access": access.map(|access| access.to_string()),
+        "_attachments": {
+            format!("{}-{}.tgz", packed.name, packed.version): {
+                "content_type": "application/octet-stream",
+                "data": base64::encode(&packed.tarball),
+                "length": packed.tarball.len(),
+            }
+        },
+    })
+}
+
+/// Publish `packed` to the registry of `client` under `tag`, asking `reporter` for a
+/// one-time password if the account needs one.
+pub async fn publish(
+    client: &RegistryClient,
+    manifest: &Value,
+    packed: &Packed,
+    tag: &str,
+    access: Option<Access>,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    let document = publish_document(manifest, packed, &client.url, tag, access);
+
+    client
+        .send_mutation(reporter, |client| {
+            Ok(client
+                .authenticated(Method::PUT, &RegistryClient::package_path(&packed.name))?
+                .json(&document))
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{publish_document, Access};
+    use crate::pack::Packed;
+
+    use serde_json::json;
+
+    #[test]
+    fn builds_the_publish_document() {
+        let packed = Packed {
+            name: "@scope/a".to_string(),
+            version: "1.2.0".to_string(),
+            tarball: b"tarball".to_vec(),
+            files: vec![],
+            integrity: "sha512-abc".to_string(),
+            shasum: "0123".to_string(),
+        };
+
+        assert_eq!(packed.filename(), "scope-a-1.2.0.tgz");
+
+        let manifest = json!({ "name": "@scope/a", "version": "1.2.0", "main": "index.js" });
+
+        let document = publish_document(
+            &manifest,
+            &packed,
+            "https://registry.npmjs.org/",
+            "next",
+            Some(Access::Public),
+        );
+
+        assert_eq!(document["dist-tags"]["next"], "1.2.0");
+        assert_eq!(document["access"], "public");
+
+        let version = &document["versions"]["1.2.0"];
+
+        assert_eq!(version["main"], "index.js");
+        assert_eq!(version["_id"], "@scope/a@1.2.0");
+        assert_eq!(
+            version["dist"]["tarball"],
+            "https://registry.npmjs.org/@scope/a/-/a-1.2.0.tgz"
+        );
+
+        let attachment = &document["_attachments"]["@scope/a-1.2.0.tgz"];
+
+        assert_eq!(attachment["data"], base64::encode("tarball"));
+        assert_eq!(attachment["length"], 7);
+    }
+}
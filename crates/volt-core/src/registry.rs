@@ -0,0 +1,529 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Authenticated requests to the npm registry API.
+//!
+//! The registry and its credentials come from `.npmrc`, the project's overriding the
+//! user's: `registry=https://...` and `//registry.npmjs.org/:_authToken=...`, where
+//! `${VARIABLE}` is replaced by the environment variable.
+//!
+//! Accounts with two-factor authentication must send a one-time password along with
+//! changes (publishing, dist-tags, owners): the registry rejects them with `EOTP`, and
+//! [`RegistryClient::send_mutation`] asks the reporter for the password and sends them
+//! again.
+//!
+//! Package documents are kept in the [metadata cache](crate::metadata_cache) and only
+//! downloaded again when the registry says they changed.
+
+use crate::{
+    config::VoltConfig,
+    connections::Connections,
+    metadata_cache::{CachedDocument, MetadataCache},
+    release_age::parse_timestamp,
+    reporter::Reporter,
+    utils::{
+        errors::{FilesystemError, NetworkError, VoltError},
+        package::{Dist, DistTags},
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use reqwest::{
+    header::{HeaderMap, WWW_AUTHENTICATE},
+    Client, Method, RequestBuilder, Response, StatusCode,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+pub const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org/";
+
+/// Media type of the abbreviated package documents, with only what installs need.
+const ABBREVIATED: &str = "application/vnd.npm.install-v1+json";
+
+/// A client for one registry, with the token configured for it (if any).
+#[derive(Debug, Clone)]
+pub struct RegistryClient {
+    /// Registry url, always ending with `/`
+    pub url: String,
+    token: Option<String>,
+    /// One-time password sent with every request, for accounts with two-factor
+    /// authentication
+    otp: Option<String>,
+    client: Client,
+    cache: Option<MetadataCache>,
+    /// Times a package document is requested again, and how long each request may take
+    retries: u32,
+    timeout: Option<Duration>,
+    /// Shared by the clients of the run, to multiplex their requests
+    connections: Arc<Connections>,
+}
+
+impl RegistryClient {
+    /// The client for the `registry` setting, or the registry configured in `.npmrc`
+    /// (npm's by default).
+    pub fn new(config: &VoltConfig) -> Result<Self> {
+        let npmrc = npmrc(config)?;
+
+        let url = config
+            .settings()
+            .registry
+            .as_ref()
+            .or_else(|| npmrc.get("registry"))
+            .map_or(DEFAULT_REGISTRY, String::as_str)
+            .to_string();
+
+        Self::with_npmrc(config, &npmrc, &url)
+    }
+
+    /// The client for another registry (`publishConfig.registry`), still authenticated
+    /// from `.npmrc`.
+    pub fn for_registry(config: &VoltConfig, url: &str) -> Result<Self> {
+        Self::with_npmrc(config, &npmrc(config)?, url)
+    }
+
+    fn with_npmrc(
+        config: &VoltConfig,
+        npmrc: &BTreeMap<String, String>,
+        url: &str,
+    ) -> Result<Self> {
+        let url = if url.ends_with('/') {
+            url.to_string()
+        } else {
+            format!("{}/", url)
+        };
+
+        let token = npmrc
+            .get(&token_key(&url))
+            // an unset `${NPM_TOKEN}` leaves an empty token
+            .filter(|token| !token.is_empty())
+            .cloned();
+
+        let client = config.http_client()?;
+
+        let cache = config
+            .store()
+            .ok()
+            .map(|store| MetadataCache::new(&store, &url));
+
+        Ok(Self {
+            url,
+            token,
+            otp: None,
+            client,
+            cache,
+            retries: config.settings().fetch_retries,
+            timeout: config.settings().fetch_timeout,
+            connections: config.connections(),
+        })
+    }
+
+    /// The same client, sending `otp` as the one-time password of the account.
+    pub fn with_otp(mut self, otp: Option<String>) -> Self {
+        self.otp = otp;
+        self
+    }
+
+    /// The path of a package document, `@scope/name` being escaped as `@scope%2fname`.
+    pub fn package_path(name: &str) -> String {
+        name.replace('/', "%2f")
+    }
+
+    /// Start a request to a path of the registry, with the token when there is one.
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self.client.request(method, format!("{}{}", self.url, path));
+
+        if let Some(otp) = &self.otp {
+            request = request.header("npm-otp", otp);
+        }
+
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Start a request to a url the registry handed out, such as the one a web login is
+    /// polled at, without the token.
+    pub fn request_url(&self, method: Method, url: &str) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    /// Start an authenticated request to a path of the registry, failing without a token.
+    pub fn authenticated(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        if self.token.is_none() {
+            return Err(VoltError::NotLoggedIn {
+                registry: self.url.clone(),
+            }
+            .into());
+        }
+
+        Ok(self.request(method, path))
+    }
+
+    /// Fetch the full document of a package, with every version and its metadata.
+    pub async fn packument(&self, name: &str) -> Result<Value> {
+        self.document(name, false).await
+    }
+
+    /// The `dist` of a published version (integrity, signatures, size), from the
+    /// abbreviated document of its package. `None` if it can't be fetched.
+    pub async fn dist(&self, name: &str, version: &str) -> Option<Dist> {
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(default)]
+            dist: Dist,
+        }
+
+        #[derive(Deserialize)]
+        struct Packument {
+            versions: HashMap<String, Version>,
+        }
+
+        let packument: Packument =
+            serde_json::from_value(self.document(name, true).await.ok()?).ok()?;
+
+        packument
+            .versions
+            .into_iter()
+            .find(|(published, _)| published == version)
+            .map(|(_, version)| version.dist)
+    }
+
+    /// When a version was published, from the `time` map of the full document of its
+    /// package. `None` if it can't be fetched.
+    pub async fn published(&self, name: &str, version: &str) -> Option<SystemTime> {
+        #[derive(Deserialize)]
+        struct Packument {
+            #[serde(default)]
+            time: HashMap<String, Value>,
+        }
+
+        let packument = self
+            .packument(name)
+            .await
+            .map_err(|error| tracing::debug!("no publish date for {}@{}: {}", name, version, error))
+            .ok()?;
+
+        serde_json::from_value::<Packument>(packument)
+            .ok()?
+            .time
+            .get(version)?
+            .as_str()
+            .and_then(parse_timestamp)
+    }
+
+    /// The versions the dist-tags of a package point to, from its abbreviated document.
+    /// `None` if it can't be fetched.
+    pub async fn dist_tags(&self, name: &str) -> Option<DistTags> {
+        #[derive(Deserialize)]
+        struct Packument {
+            #[serde(default, rename = "dist-tags")]
+            dist_tags: DistTags,
+        }
+
+        let packument: Packument =
+            serde_json::from_value(self.document(name, true).await.ok()?).ok()?;
+
+        Some(packument.dist_tags)
+    }
+
+    /// The full or abbreviated document of a package, from the cache when the registry
+    /// answers that it didn't change.
+    async fn document(&self, name: &str, abbreviated: bool) -> Result<Value> {
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(name, abbreviated));
+
+        let request = || {
+            let mut request = self.request(Method::GET, &Self::package_path(name));
+
+            if abbreviated {
+                request = request.header("Accept", ABBREVIATED);
+            }
+
+            if let Some(timeout) = self.timeout {
+                request = request.timeout(timeout);
+            }
+
+            match &cached {
+                Some(cached) => cached.conditional(request),
+                None => request,
+            }
+        };
+
+        let response = self.send_retrying(request).await?;
+
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            tracing::debug!("{} is unchanged, using the cached document", name);
+
+            return Ok(cached.document);
+        }
+
+        let headers = response.headers().clone();
+        let document: Value = response.json().await.into_diagnostic()?;
+
+        if let Some(cache) = &self.cache {
+            if let Some(cached) = CachedDocument::new(&headers, document.clone()) {
+                cache.put(name, abbreviated, &cached);
+            }
+        }
+
+        Ok(document)
+    }
+
+    /// Send the change `build` makes; when the registry asks for a one-time password,
+    /// ask `reporter` for it and send the change again with it.
+    pub async fn send_mutation(
+        &self,
+        reporter: &dyn Reporter,
+        build: impl Fn(&Self) -> Result<RequestBuilder>,
+    ) -> Result<Response> {
+        let error = match self.send(build(self)?).await {
+            Err(error) if matches!(error.downcast_ref(), Some(VoltError::OtpRequired { .. })) => {
+                error
+            }
+            result => return result,
+        };
+
+        let otp = match reporter.one_time_password(&self.url)? {
+            Some(otp) => otp,
+            None => return Err(error),
+        };
+
+        let client = self.clone().with_otp(Some(otp));
+
+        client.send(build(&client)?).await
+    }
+
+    /// Send the request `build` makes when it's its turn to use the connections to the
+    /// registry, and again after failures of the network and of the registry itself, up
+    /// to the `fetch-retries` setting.
+    async fn send_retrying(&self, build: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let mut retries = 0;
+
+        loop {
+            let turn = self.connections.turn(&self.url).await;
+
+            let result = self.send(build()).await;
+
+            // the connection is left to the next requests while this one waits to retry
+            match &result {
+                Ok(response) => turn.answered(response),
+                Err(_) => drop(turn),
+            }
+
+            let error = match result {
+                Err(error) if retries < self.retries && transient(&error) => error,
+                result => return result,
+            };
+
+            retries += 1;
+
+            // 1s, 2s, 4s...
+            let backoff = Duration::from_secs(1 << (retries - 1).min(5));
+
+            tracing::debug!("{}, retrying in {}s", error, backoff.as_secs());
+
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Send a request, turning error responses into a [`NetworkError::Registry`] with
+    /// the message of the registry, or [`VoltError::OtpRequired`] when it asks for a
+    /// one-time password.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        #[derive(Deserialize)]
+        struct ErrorBody {
+            error: Option<String>,
+            reason: Option<String>,
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|source| NetworkError::Request {
+                url: source
+                    .url()
+                    .map_or_else(|| self.url.clone(), ToString::to_string),
+                source,
+            })?;
+
+        tracing::debug!("{} - {}", response.url(), response.status());
+
+        // only conditional requests are answered `304 Not Modified`
+        if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(response);
+        }
+
+        let status = response.status().as_u16();
+        let url = response.url().to_string();
+        let headers = response.headers().clone();
+
+        let message = response
+            .json::<ErrorBody>()
+            .await
+            .ok()
+            .and_then(|body| body.error.or(body.reason))
+            .unwrap_or_else(|| "no details given".to_string());
+
+        if otp_required(status, &headers, &message) {
+            return Err(VoltError::OtpRequired {
+                registry: self.url.clone(),
+            }
+            .into());
+        }
+
+        Err(NetworkError::Registry {
+            url,
+            status,
+            message,
+        }
+        .into())
+    }
+}
+
+/// Whether a request failing with `error` may succeed when sent again: it didn't reach
+/// the registry, or the registry failed or was too busy to answer.
+fn transient(error: &miette::Report) -> bool {
+    match error.downcast_ref::<NetworkError>() {
+        Some(NetworkError::Request { .. }) => true,
+        Some(NetworkError::Registry { status, .. }) => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+/// Whether an error response asks for a one-time password: npm's registry says so in
+/// `WWW-Authenticate: OTP`, others only in the message.
+fn otp_required(status: u16, headers: &HeaderMap, message: &str) -> bool {
+    let challenge = headers
+        .get(WWW_AUTHENTICATE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.to_ascii_lowercase().contains("otp"));
+
+    status == 401 && (challenge || message.to_ascii_lowercase().contains("one-time pass"))
+}
+
+/// The `.npmrc` key of the token of `registry`: its url without the protocol,
+/// `//registry.npmjs.org/:_authToken`.
+pub fn token_key(registry: &str) -> String {
+    let url = registry.split_once("//").map_or(registry, |(_, rest)| rest);
+
+    format!("//{}:_authToken", url)
+}
+
+/// Read the user's and the project's `.npmrc`, the project's settings taking precedence.
+pub fn npmrc(config: &VoltConfig) -> Result<BTreeMap<String, String>> {
+    let mut settings = BTreeMap::new();
+
+    for path in [config.home()?.join(".npmrc"), config.cwd()?.join(".npmrc")] {
+        settings.extend(read_npmrc(&path)?);
+    }
+
+    Ok(settings)
+}
+
+/// The settings of one `.npmrc`, empty if it doesn't exist.
+pub fn read_npmrc(path: &Path) -> Result<BTreeMap<String, String>> {
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = std::fs::read_to_string(path).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    Ok(parse_npmrc(&contents))
+}
+
+fn parse_npmrc(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"');
+
+            (key.trim().to_string(), expand_env(value))
+        })
+        .collect()
+}
+
+/// Replace every `${VARIABLE}` by the value of the environment variable.
+fn expand_env(value: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&std::env::var(&rest[start + 2..end]).unwrap_or_default());
+
+        rest = &rest[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{otp_required, parse_npmrc};
+
+    use reqwest::header::{HeaderMap, HeaderValue, WWW_AUTHENTICATE};
+
+    #[test]
+    fn parses_npmrc() {
+        std::env::set_var("VOLT_TEST_NPM_TOKEN", "secret");
+
+        let settings = parse_npmrc(
+            "# comment\nregistry = https://registry.example.com/\n//registry.example.com/:_authToken=${VOLT_TEST_NPM_TOKEN}\n",
+        );
+
+        assert_eq!(settings["registry"], "https://registry.example.com/");
+        assert_eq!(settings["//registry.example.com/:_authToken"], "secret");
+    }
+
+    #[test]
+    fn recognizes_one_time_password_challenges() {
+        let mut headers = HeaderMap::new();
+
+        assert!(!otp_required(401, &headers, "unauthorized"));
+        assert!(otp_required(
+            401,
+            &headers,
+            "This operation requires a one-time password."
+        ));
+        assert!(!otp_required(403, &headers, "one-time password"));
+
+        headers.insert(WWW_AUTHENTICATE, HeaderValue::from_static("OTP"));
+
+        assert!(otp_required(401, &headers, "no details given"));
+    }
+}
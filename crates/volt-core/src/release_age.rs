@@ -0,0 +1,157 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Keep versions out of the tree until they have been published for a while.
+//!
+//! Compromised versions are usually found and unpublished within days, so with
+//! `minimum-release-age = "72h"` a resolution that picks a version younger than that
+//! fails, going by the `time` map of the package document. `--allow-fresh` lifts it.
+
+use crate::{
+    config::VoltConfig, install::Resolution, registry::RegistryClient, utils::errors::VoltError,
+};
+
+use futures::{stream, StreamExt};
+use miette::Result;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Fail if a registry package of `resolution` was published less than the
+/// `minimum-release-age` setting ago.
+pub async fn check_release_age(config: &VoltConfig, resolution: &Resolution) -> Result<()> {
+    let age = match config.settings().minimum_release_age {
+        Some(age) => age,
+        None => return Ok(()),
+    };
+
+    let client = RegistryClient::new(config)?;
+
+    // git, local and tarball url packages have no publish date
+    let mut packages: Vec<(String, String)> = resolution
+        .tree
+        .values()
+        .filter(|package| !package.remote && package.tarball.starts_with("http"))
+        .map(|package| (package.name.clone(), package.version.clone()))
+        .collect();
+
+    packages.sort_unstable();
+
+    let checks = packages.into_iter().map(|(name, version)| {
+        let client = client.clone();
+
+        async move {
+            let published = client.published(&name, &version).await;
+
+            (name, version, published)
+        }
+    });
+
+    let now = SystemTime::now();
+
+    let published: Vec<_> = stream::iter(checks)
+        .buffer_unordered(config.settings().concurrency)
+        .collect()
+        .await;
+
+    let mut fresh: Vec<String> = published
+        .into_iter()
+        .filter(|(_, _, published)| {
+            // a date in the future is as fresh as it gets
+            published.map_or(false, |published| {
+                now.duration_since(published).unwrap_or_default() < age
+            })
+        })
+        .map(|(name, version, _)| format!("{}@{}", name, version))
+        .collect();
+
+    if fresh.is_empty() {
+        return Ok(());
+    }
+
+    fresh.sort();
+
+    Err(VoltError::FreshRelease {
+        packages: fresh.join(", "),
+        age: describe(age),
+    }
+    .into())
+}
+
+/// `72h`, `3d`: the largest unit the duration is a whole number of.
+fn describe(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+
+    match minutes {
+        minutes if minutes % (60 * 24 * 7) == 0 => format!("{}w", minutes / (60 * 24 * 7)),
+        minutes if minutes % (60 * 24) == 0 => format!("{}d", minutes / (60 * 24)),
+        minutes if minutes % 60 == 0 => format!("{}h", minutes / 60),
+        minutes => format!("{}m", minutes),
+    }
+}
+
+/// The time of an RFC 3339 timestamp in UTC, as written by the registry
+/// (`2021-05-01T12:34:56.789Z`).
+pub(crate) fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let time = time.split('.').next()?;
+    let mut time = time.splitn(3, ':').map(str::parse::<u64>);
+    let (hours, minutes, seconds) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    // days since 1970-01-01 of a date in the proleptic gregorian calendar
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146_097 + day_of_era - 719_468).ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hours * 3_600 + minutes * 60 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe, parse_timestamp};
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn reads_registry_timestamps() {
+        assert_eq!(
+            parse_timestamp("1970-01-01T00:00:00.000Z"),
+            Some(UNIX_EPOCH)
+        );
+        assert_eq!(
+            parse_timestamp("2021-05-01T12:34:56.789Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_619_872_496))
+        );
+        assert_eq!(
+            parse_timestamp("2024-02-29T00:00:00Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_timestamp("yesterday"), None);
+
+        assert_eq!(describe(Duration::from_secs(72 * 3_600)), "3d");
+        assert_eq!(describe(Duration::from_secs(90 * 60)), "90m");
+    }
+}
@@ -0,0 +1,410 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Report the progress of resolving, downloading and extracting packages.
+//!
+//! Progress is written as plain lines unless the tool running volt sets a reporter of
+//! its own with [`VoltConfig::set_reporter`](crate::config::VoltConfig::set_reporter),
+//! like the bars of the cli. With `--json` every event is a line of JSON on stdout,
+//! `{"type": "...", "data": ...}`.
+
+use crate::{
+    install_state::InstallDiff,
+    plugin::{Level, Message},
+    summary::InstallSummary,
+};
+
+use miette::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Receives the progress of an install; implementations must be cheap, as the events
+/// come from every download.
+pub trait Reporter: Send + Sync {
+    /// Started resolving `package` (`name@range`).
+    fn resolving(&self, package: &str);
+
+    /// `count` more packages were resolved.
+    fn resolved(&self, count: usize);
+
+    /// Started installing `total` packages.
+    fn installing(&self, total: usize);
+
+    /// Received `bytes` more bytes of a tarball.
+    fn download_progress(&self, bytes: u64);
+
+    /// Finished downloading the tarball of `package` (`name@version`).
+    fn downloaded(&self, package: &str);
+
+    /// Finished extracting (or copying from the store) `package`.
+    fn extracted(&self, package: &str);
+
+    /// Stop reporting, clearing what was drawn.
+    fn finish(&self);
+
+    /// Finished a phase: `action` (`Resolved`, `Installed`) `count` packages in `elapsed`.
+    fn done(&self, action: &str, count: usize, elapsed: Duration) {
+        println!(
+            "[{:.2}s] {} {} dependencies",
+            elapsed.as_secs_f32(),
+            action,
+            count
+        );
+    }
+
+    /// What an install changed in node_modules (`+12 −3 ~1 packages`).
+    fn changes(&self, diff: &InstallDiff) {
+        println!("{}", diff);
+    }
+
+    /// What an install downloaded, took from the store and spent its time on.
+    fn summary(&self, summary: &InstallSummary) {
+        println!("{}", fetched(summary));
+
+        println!(
+            "{}",
+            summary
+                .phases
+                .iter()
+                .map(|(phase, elapsed)| format!("{} {:.2}s", phase, elapsed))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    /// A problem that doesn't stop the command.
+    fn warning(&self, message: &str) {
+        tracing::warn!("{}", message);
+    }
+
+    /// A problem that doesn't stop the command, explained like errors are: with its code
+    /// and help.
+    fn diagnostic(&self, report: &miette::Report) {
+        eprintln!("{:?}", report);
+    }
+
+    /// What the command did besides installing (`Removed left-pad`).
+    fn info(&self, message: &str) {
+        println!("{}", message);
+    }
+
+    /// Started running a script, shown by `header`
+    /// (`$ esbuild@0.14.2 postinstall: node install.js`).
+    fn script(&self, header: &str) {
+        println!("{}", header);
+    }
+
+    /// Checked the registry signatures: `verified` packages are signed, `unsigned` aren't.
+    fn signatures(&self, verified: usize, unsigned: usize) {
+        let unsigned = if unsigned > 0 {
+            format!(", {} unsigned", unsigned)
+        } else {
+            String::new()
+        };
+
+        println!("✓ {} verified registry signatures{}", verified, unsigned);
+    }
+
+    /// Ask whether the install scripts that aren't allowed to run may run anyway: the
+    /// `name@version` of each package with the `(event, script)` it runs. Without anyone
+    /// to ask they don't.
+    fn approve_scripts(&self, _packages: &[(String, Vec<(&str, String)>)]) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Ask for the one-time password of an account with two-factor authentication on
+    /// `registry`. Without anyone to ask there is none.
+    fn one_time_password(&self, _registry: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// A message a plugin or hook printed.
+    fn message(&self, message: &Message) {
+        match message {
+            Message::Log { level, message } => match level {
+                Level::Info => println!("{}", message),
+                Level::Success => println!("success {}", message),
+                Level::Warn => eprintln!("warning: {}", message),
+                Level::Error => eprintln!("error: {}", message),
+            },
+            Message::Table { headers, rows } => {
+                let mut lines: Vec<Vec<String>> = rows
+                    .iter()
+                    .map(|row| row.iter().map(Message::cell).collect())
+                    .collect();
+
+                if !headers.is_empty() {
+                    lines.insert(0, headers.clone());
+                }
+
+                let columns = lines.iter().map(Vec::len).max().unwrap_or(0);
+
+                let widths: Vec<usize> = (0..columns)
+                    .map(|column| {
+                        lines
+                            .iter()
+                            .filter_map(|line| line.get(column))
+                            .map(|cell| cell.chars().count())
+                            .max()
+                            .unwrap_or(0)
+                    })
+                    .collect();
+
+                for line in lines {
+                    let cells: Vec<String> = line
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                        .collect();
+
+                    println!("{}", cells.join("  ").trim_end());
+                }
+            }
+            Message::Result(Value::String(string)) => println!("{}", string),
+            Message::Result(value) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(value).unwrap_or_default()
+                );
+            }
+        }
+    }
+}
+
+/// One line per downloaded package and a summary of the downloads; the resolved and
+/// installed counts are already printed once each phase is done.
+#[derive(Default)]
+pub struct PlainReporter {
+    downloaded: AtomicUsize,
+    bytes: AtomicU64,
+}
+
+impl Reporter for PlainReporter {
+    fn resolving(&self, _package: &str) {}
+
+    fn resolved(&self, _count: usize) {}
+
+    fn installing(&self, _total: usize) {}
+
+    fn download_progress(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn downloaded(&self, package: &str) {
+        self.downloaded.fetch_add(1, Ordering::Relaxed);
+
+        eprintln!("Downloaded {}", package);
+    }
+
+    fn extracted(&self, _package: &str) {}
+
+    fn finish(&self) {
+        let downloaded = self.downloaded.load(Ordering::Relaxed);
+
+        if downloaded > 0 {
+            eprintln!(
+                "Downloaded {} packages ({})",
+                downloaded,
+                human_bytes(self.bytes.load(Ordering::Relaxed))
+            );
+        }
+    }
+}
+
+/// Where the packages of an install came from: `3 packages downloaded (1.50 MiB), 40
+/// from the store (93% cache hits), 12 kept`.
+pub fn fetched(summary: &InstallSummary) -> String {
+    let mut parts = vec![format!(
+        "{} packages downloaded ({})",
+        summary.downloaded,
+        human_bytes(summary.bytes)
+    )];
+
+    if let Some(rate) = summary.cache_hit_rate {
+        parts.push(format!(
+            "{} from the store ({:.0}% cache hits)",
+            summary.from_store,
+            rate * 100.0
+        ));
+    }
+
+    if summary.kept > 0 {
+        parts.push(format!("{} kept", summary.kept));
+    }
+
+    parts.join(", ")
+}
+
+/// `bytes` in the largest binary unit they make at least one of (`1.50 MiB`).
+pub fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.2} {}", size, UNITS[unit])
+}
+
+/// An event of the JSON output.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum Event<'a> {
+    Resolving {
+        package: &'a str,
+    },
+    Resolved {
+        count: usize,
+    },
+    Installing {
+        total: usize,
+    },
+    Downloaded {
+        package: &'a str,
+    },
+    Extracted {
+        package: &'a str,
+    },
+    Done {
+        action: String,
+        count: usize,
+        /// Seconds
+        elapsed: f32,
+    },
+    /// Packages added, removed and installed in other versions by an install
+    Changes {
+        added: usize,
+        removed: usize,
+        changed: usize,
+    },
+    /// What an install downloaded, took from the store and spent its time on
+    Summary(&'a InstallSummary),
+    Warning {
+        message: &'a str,
+    },
+    /// What the command did besides installing
+    Info {
+        message: &'a str,
+    },
+    Signatures {
+        verified: usize,
+        unsigned: usize,
+    },
+    /// What a command produced: the audit report, the package info, the search results
+    Result(Value),
+    Error {
+        code: Option<String>,
+        message: String,
+    },
+    Finished {
+        /// Seconds
+        elapsed: f32,
+    },
+}
+
+/// Print an event as a line of JSON on stdout.
+pub fn emit(event: &Event) {
+    // the events only hold strings, numbers and values which always serialize
+    if let Ok(line) = serde_json::to_string(event) {
+        println!("{}", line);
+    }
+}
+
+/// Every event as a line of JSON on stdout.
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn resolving(&self, package: &str) {
+        emit(&Event::Resolving { package });
+    }
+
+    fn resolved(&self, count: usize) {
+        emit(&Event::Resolved { count });
+    }
+
+    fn installing(&self, total: usize) {
+        emit(&Event::Installing { total });
+    }
+
+    // the size of each download isn't worth a line
+    fn download_progress(&self, _bytes: u64) {}
+
+    fn downloaded(&self, package: &str) {
+        emit(&Event::Downloaded { package });
+    }
+
+    fn extracted(&self, package: &str) {
+        emit(&Event::Extracted { package });
+    }
+
+    fn finish(&self) {}
+
+    fn done(&self, action: &str, count: usize, elapsed: Duration) {
+        emit(&Event::Done {
+            action: action.to_lowercase(),
+            count,
+            elapsed: elapsed.as_secs_f32(),
+        });
+    }
+
+    fn changes(&self, diff: &InstallDiff) {
+        emit(&Event::Changes {
+            added: diff.added.len(),
+            removed: diff.removed.len(),
+            changed: diff.changed.len(),
+        });
+    }
+
+    fn summary(&self, summary: &InstallSummary) {
+        emit(&Event::Summary(summary));
+    }
+
+    fn warning(&self, message: &str) {
+        emit(&Event::Warning { message });
+    }
+
+    fn diagnostic(&self, report: &miette::Report) {
+        emit(&Event::Warning {
+            message: &report.to_string(),
+        });
+    }
+
+    fn info(&self, message: &str) {
+        emit(&Event::Info { message });
+    }
+
+    // the output of scripts goes to stderr, under their header
+    fn script(&self, header: &str) {
+        eprintln!("{}", header);
+    }
+
+    fn signatures(&self, verified: usize, unsigned: usize) {
+        emit(&Event::Signatures { verified, unsigned });
+    }
+}
@@ -0,0 +1,261 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Resolutions of registry specs kept on disk, so that `volt add` and `volt install` of
+//! the same specs don't resolve them again.
+//!
+//! A [`CachedSource`] saves the tree another [`PackageSource`] resolves for a spec in
+//! `<store>/metadata/resolutions/`, keyed by the spec and the registry, and reuses it
+//! for the `resolution-cache-ttl` setting (10 minutes by default, `0` to always
+//! resolve). A range only expires; a dist-tag (`react@next`, or no range for `latest`)
+//! is also checked against the dist-tags of the registry, through the cached documents
+//! of [`crate::metadata_cache`], and resolved again as soon as it moves.
+
+use crate::{
+    config::VoltConfig,
+    metadata_cache::{metadata_dir, write_json},
+    registry::{RegistryClient, DEFAULT_REGISTRY},
+    source::PackageSource,
+    tarball::Tarball,
+    utils::{
+        voltapi::{VoltPackage, VoltResponse},
+        State,
+    },
+};
+
+use async_trait::async_trait;
+use miette::Result;
+use package_spec::{PackageSpec, VersionSpec};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A resolution as it is saved.
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    /// Seconds since the epoch when it was resolved
+    resolved_at: u64,
+    version: String,
+    versions: Vec<String>,
+    tree: HashMap<String, VoltPackage>,
+}
+
+/// The cached resolutions of every registry.
+#[derive(Debug, Clone)]
+pub struct ResolutionCache {
+    dir: PathBuf,
+}
+
+impl ResolutionCache {
+    /// The cache of the store at `store`.
+    pub fn new(store: &Path) -> Self {
+        Self {
+            dir: metadata_dir(store).join("resolutions"),
+        }
+    }
+
+    /// The resolution of `spec` on `registry`, if one was saved less than `ttl` ago.
+    pub fn get(&self, registry: &str, spec: &PackageSpec, ttl: Duration) -> Option<VoltResponse> {
+        let contents = std::fs::read(self.path(registry, spec)).ok()?;
+        let entry: Entry = serde_json::from_slice(&contents).ok()?;
+
+        let age = now().checked_sub(Duration::from_secs(entry.resolved_at))?;
+
+        if age >= ttl {
+            return None;
+        }
+
+        Some(VoltResponse {
+            name: name(spec)?.to_string(),
+            version: entry.version,
+            versions: entry.versions,
+            tree: entry.tree,
+        })
+    }
+
+    /// Save the resolution of `spec` on `registry`. The cache only spares resolutions, so
+    /// failing to write it is logged rather than reported.
+    pub fn put(&self, registry: &str, spec: &PackageSpec, response: &VoltResponse) {
+        let path = self.path(registry, spec);
+
+        let entry = Entry {
+            resolved_at: now().as_secs(),
+            version: response.version.clone(),
+            versions: response.versions.clone(),
+            tree: response.tree.clone(),
+        };
+
+        if let Err(error) = write_json(&path, &entry) {
+            tracing::debug!("failed to cache {}: {:?}", path.display(), error);
+        }
+    }
+
+    fn path(&self, registry: &str, spec: &PackageSpec) -> PathBuf {
+        // specs hold characters (`>`, `|`, `*`) that can't be in a file name
+        let key = Sha256::digest(format!("{}\n{}", registry, spec).as_bytes());
+
+        self.dir.join(format!("{}.json", hex::encode(&key[..16])))
+    }
+}
+
+/// `S`, with its resolutions reused from the [`ResolutionCache`] of the store; its
+/// tarballs aren't cached here, the store already keeps them.
+#[derive(Debug, Clone)]
+pub struct CachedSource<S> {
+    inner: S,
+}
+
+impl<S: PackageSource> CachedSource<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    /// Whether the dist-tag `spec` asks for still points to the version it resolved to.
+    /// Ranges always do; so does a tag whose dist-tags can't be fetched.
+    async fn current(
+        &self,
+        registry: Option<&RegistryClient>,
+        spec: &PackageSpec,
+        cached: &VoltResponse,
+    ) -> bool {
+        let tag = match spec {
+            PackageSpec::Npm {
+                requested: Some(VersionSpec::Tag(tag)),
+                ..
+            } => tag.as_str(),
+            PackageSpec::Npm {
+                requested: None, ..
+            } => "latest",
+            _ => return true,
+        };
+
+        let dist_tags = match registry {
+            Some(registry) => registry.dist_tags(&cached.name).await,
+            None => None,
+        };
+
+        match dist_tags {
+            Some(dist_tags) => dist_tags.get(tag) == Some(&cached.version),
+            None => true,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: PackageSource> PackageSource for CachedSource<S> {
+    async fn resolve(&self, config: &VoltConfig, spec: &PackageSpec) -> Result<VoltResponse> {
+        let ttl = match config.settings().resolution_cache_ttl {
+            Some(ttl) => ttl,
+            None => return self.inner.resolve(config, spec).await,
+        };
+
+        let cache = ResolutionCache::new(&config.store()?);
+
+        let registry = RegistryClient::new(config).ok();
+        let url = registry
+            .as_ref()
+            .map_or(DEFAULT_REGISTRY, |registry| registry.url.as_str());
+
+        if let Some(cached) = cache.get(url, spec, ttl) {
+            if self.current(registry.as_ref(), spec, &cached).await {
+                tracing::debug!("reusing the cached resolution of {}", spec);
+
+                return Ok(cached);
+            }
+
+            tracing::debug!("the dist-tag of {} moved, resolving it again", spec);
+        }
+
+        let response = self.inner.resolve(config, spec).await?;
+
+        cache.put(url, spec, &response);
+
+        Ok(response)
+    }
+
+    async fn tarball(
+        &self,
+        config: &VoltConfig,
+        package: &VoltPackage,
+        state: &State,
+    ) -> Result<Tarball> {
+        self.inner.tarball(config, package, state).await
+    }
+
+    fn describe(&self) -> String {
+        self.inner.describe()
+    }
+}
+
+/// The name of a registry spec.
+fn name(spec: &PackageSpec) -> Option<&str> {
+    match spec {
+        PackageSpec::Npm { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResolutionCache;
+    use crate::utils::voltapi::VoltResponse;
+
+    use std::{collections::HashMap, time::Duration};
+
+    #[test]
+    fn keeps_resolutions_by_spec_and_registry() {
+        let store = tempfile::tempdir().unwrap();
+        let cache = ResolutionCache::new(store.path());
+
+        let npm = "https://registry.npmjs.org/";
+        let spec = "ms@^2.0.0".parse().unwrap();
+        let hour = Duration::from_secs(60 * 60);
+
+        cache.put(
+            npm,
+            &spec,
+            &VoltResponse {
+                name: "ms".to_string(),
+                version: "2.1.3".to_string(),
+                versions: vec!["2.0.0".to_string(), "2.1.3".to_string()],
+                tree: HashMap::new(),
+            },
+        );
+
+        let cached = cache.get(npm, &spec, hour).unwrap();
+
+        assert_eq!(cached.name, "ms");
+        assert_eq!(cached.version, "2.1.3");
+        assert_eq!(cached.versions.len(), 2);
+
+        assert!(cache.get(npm, &spec, Duration::ZERO).is_none());
+        assert!(cache.get("https://npm.example.com/", &spec, hour).is_none());
+        assert!(cache
+            .get(npm, &"ms@^3.0.0".parse().unwrap(), hour)
+            .is_none());
+    }
+}
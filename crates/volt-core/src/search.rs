@@ -0,0 +1,134 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Search the registry through its `/-/v1/search` endpoint.
+
+use crate::registry::RegistryClient;
+
+use miette::{IntoDiagnostic, Result};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// Most results the registry returns for one request.
+pub const MAX_PAGE_SIZE: usize = 250;
+
+/// One page of results, ranked by the registry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchResults {
+    pub objects: Vec<SearchResult>,
+    /// Number of results across every page
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SearchResult {
+    pub package: SearchPackage,
+    pub score: Score,
+    pub search_score: f64,
+    /// Only given by some registries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downloads: Option<Downloads>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchPackage {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    pub date: Option<String>,
+    pub publisher: Option<Publisher>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Publisher {
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Score {
+    #[serde(rename = "final")]
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Downloads {
+    pub weekly: u64,
+    pub monthly: u64,
+}
+
+/// Search the registry of `client`, skipping the first `from` results.
+pub async fn search(
+    client: &RegistryClient,
+    query: &str,
+    from: usize,
+    size: usize,
+) -> Result<SearchResults> {
+    let request = client.request(Method::GET, "-/v1/search").query(&[
+        ("text", query.to_string()),
+        ("from", from.to_string()),
+        ("size", size.min(MAX_PAGE_SIZE).to_string()),
+    ]);
+
+    client.send(request).await?.json().await.into_diagnostic()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchResults;
+
+    #[test]
+    fn parses_search_results() {
+        let results: SearchResults = serde_json::from_str(
+            r#"{
+                "objects": [
+                    {
+                        "package": {
+                            "name": "react",
+                            "version": "17.0.2",
+                            "description": "React is a JavaScript library for building user interfaces.",
+                            "keywords": ["react"],
+                            "links": { "npm": "https://www.npmjs.com/package/react" },
+                            "publisher": { "username": "gaearon", "email": "dan.abramov@gmail.com" }
+                        },
+                        "score": { "final": 0.92, "detail": { "quality": 0.8, "popularity": 0.9, "maintenance": 1 } },
+                        "searchScore": 100000.1,
+                        "downloads": { "weekly": 14000000, "monthly": 60000000 }
+                    },
+                    { "package": { "name": "preact", "version": "10.5.15" }, "searchScore": 0.5 }
+                ],
+                "total": 2840,
+                "time": "Sat Oct 16 2021 10:00:00 GMT+0000 (Coordinated Universal Time)"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(results.total, 2840);
+        assert_eq!(results.objects[0].package.name, "react");
+        assert_eq!(
+            results.objects[0].downloads.as_ref().unwrap().weekly,
+            14000000
+        );
+        assert!(results.objects[1].package.description.is_none());
+        assert!(results.objects[1].downloads.is_none());
+    }
+}
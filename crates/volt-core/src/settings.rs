@@ -0,0 +1,932 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Settings read from config files and the environment.
+//!
+//! From the highest precedence to the lowest:
+//! 1. environment variables, `VOLT_` followed by the key (`VOLT_STRICT_SSL=false`)
+//! 2. the project's `.voltrc`, then its `volt.toml`
+//! 3. the user's `~/.volt/config.toml`
+//! 4. the project's and then the user's `.npmrc`, so that the `registry`, proxy and
+//!    certificate settings of npm apply too
+//! 5. the defaults
+//!
+//! The project's files are committed, so that everyone working on it installs the same
+//! way (`node-linker`, `hoist-pattern`, `ignore-scripts`, `save-exact`, `registry`). Where
+//! volt upgrades itself from (`update-url`, `update-public-key`) is only read from the
+//! user's `config.toml` and the environment, and a `remote-cache-url` set by the project
+//! is only sent a `remote-cache-token` set beside it.
+//! They hold `key = value` lines, the values optionally quoted, and `#` comments:
+//!
+//! ```toml
+//! registry = "https://registry.example.com/"
+//! concurrency = 8
+//! strict-ssl = false
+//! ```
+//!
+//! Tarballs of a scope can come from their own mirrors: `@corp:tarball-mirror = https://...`.
+//!
+//! Relative directories (`store-dir = .volt-store`) are relative to the file setting them,
+//! so a `.voltrc` can keep the store in the project, and `~/` is the home directory.
+
+use crate::{
+    linker::NodeLinker,
+    registry::read_npmrc,
+    utils::errors::{FilesystemError, VoltError},
+};
+
+use miette::Result;
+use reqwest::Url;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Keys only the user's `~/.volt/config.toml` and the environment set: a cloned project
+/// setting them could have `volt upgrade-self` install a build it signed itself.
+const USER_KEYS: &[&str] = &["update-url", "update-public-key"];
+
+/// Tarballs downloaded and extracted at once by default.
+pub const DEFAULT_CONCURRENCY: usize = 16;
+
+/// Largest tarball held in memory by default, in bytes.
+pub const DEFAULT_TARBALL_MEMORY_LIMIT: u64 = 64 * 1024 * 1024;
+
+/// How long a resolved range is reused by default before it is resolved again.
+pub const DEFAULT_RESOLUTION_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Packages whose install scripts run at once by default.
+pub const DEFAULT_CHILD_CONCURRENCY: usize = 5;
+
+/// Workspace members whose scripts run at once by default with `--recursive`.
+pub const DEFAULT_WORKSPACE_CONCURRENCY: usize = 4;
+
+/// Times a failed request is retried by default.
+pub const DEFAULT_FETCH_RETRIES: u32 = 2;
+
+/// How long a request may wait on the registry by default.
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Seconds to wait for another volt process to release node_modules or the store.
+pub const DEFAULT_LOCK_TIMEOUT: u64 = 600;
+
+/// The keys of every setting.
+pub const KEYS: &[&str] = &[
+    "registry",
+    "concurrency",
+    "store-dir",
+    "cache-dir",
+    "strict-ssl",
+    "proxy",
+    "https-proxy",
+    "noproxy",
+    "cafile",
+    "node-linker",
+    "hoist-pattern",
+    "public-hoist-pattern",
+    "lock-timeout",
+    "engine-strict",
+    "save-exact",
+    "save-prefix",
+    "pin",
+    "tarball-mirror",
+    "node-mirror",
+    "update-check",
+    "update-url",
+    "update-public-key",
+    "ignore-scripts",
+    "allow-scripts",
+    "minimum-release-age",
+    "verify-signatures",
+    "require-signatures",
+    "child-concurrency",
+    "workspace-concurrency",
+    "remote-cache-url",
+    "remote-cache-token",
+    "remote-cache-region",
+    "remote-cache-read-only",
+    "node-gyp",
+    "python",
+    "msvs-version",
+    "pre-resolve-hook",
+    "post-resolve-hook",
+    "pre-extract-hook",
+    "post-install-hook",
+    "vendored",
+    "tarball-memory-limit",
+    "resolution-cache-ttl",
+    "network-limit",
+    "fetch-retries",
+    "fetch-timeout",
+];
+
+/// The keys of the settings naming a directory, relative to where they're set.
+const DIRECTORY_KEYS: &[&str] = &["store-dir", "cache-dir"];
+
+/// Suffix of the per-scope tarball mirror keys (`@corp:tarball-mirror`).
+const SCOPED_TARBALL_MIRROR: &str = ":tarball-mirror";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Registry to resolve packages from, over the `registry` of `.npmrc`
+    pub registry: Option<String>,
+    /// Most packages downloaded and extracted at once
+    pub concurrency: usize,
+    /// Directory of the package store (defaults to `~/.volt`), `cache-dir` being its
+    /// older name
+    pub store_dir: Option<PathBuf>,
+    /// Whether the certificates of registries are verified
+    pub strict_ssl: bool,
+    /// Proxy for every request
+    pub proxy: Option<String>,
+    /// Proxy for https requests, over `proxy`
+    pub https_proxy: Option<String>,
+    /// Hosts reached without the proxy: domains (and their subdomains), ip addresses or `*`
+    pub noproxy: Vec<String>,
+    /// File of PEM certificates trusted on top of the usual ones, for registries with
+    /// internal certificates
+    pub cafile: Option<PathBuf>,
+    /// Layout of `node_modules`
+    pub node_linker: NodeLinker,
+    /// Packages hoisted to the top of `node_modules` (or of the virtual store)
+    pub hoist_pattern: Vec<String>,
+    /// Packages the isolated linker also links into `node_modules`
+    pub public_hoist_pattern: Vec<String>,
+    /// Seconds to wait for another volt process to release node_modules or the store
+    pub lock_timeout: u64,
+    /// Whether packages whose `engines` don't match the running node, npm or volt fail
+    /// the install rather than only warn
+    pub engine_strict: bool,
+    /// Whether `volt add` saves the exact versions it added, over `save_prefix`
+    pub save_exact: bool,
+    /// What `volt add` saves before the versions it added: `^` (the default), `~` or
+    /// nothing; also set by `pin`, to `major`, `minor` or `patch`
+    pub save_prefix: String,
+    /// Servers to download registry tarballs from before the registry itself, in order
+    pub tarball_mirror: Vec<String>,
+    /// `@scope -> servers` replacing `tarball_mirror` for the packages of a scope
+    pub scoped_tarball_mirrors: BTreeMap<String, Vec<String>>,
+    /// Servers to download node builds from before nodejs.org, in order
+    pub node_mirror: Vec<String>,
+    /// Whether volt looks for a newer version of itself once a day
+    pub update_check: bool,
+    /// Where `volt upgrade-self` finds the latest release, over GitHub's releases
+    pub update_url: Option<String>,
+    /// Base64 Ed25519 key the `SHA256SUMS` of releases are signed with, over the one
+    /// volt is built with
+    pub update_public_key: Option<String>,
+    /// Whether the install scripts of dependencies are skipped unless allowed; the
+    /// project's own scripts always run
+    pub ignore_scripts: bool,
+    /// Dependencies whose install scripts run even with `ignore_scripts`
+    pub allow_scripts: Vec<String>,
+    /// How long a version must have been published before it is resolved
+    pub minimum_release_age: Option<Duration>,
+    /// Whether the registry signatures of packages are checked before they are installed
+    pub verify_signatures: bool,
+    /// Whether packages without a registry signature fail the install rather than only
+    /// warn
+    pub require_signatures: bool,
+    /// Most packages whose install scripts run at once
+    pub child_concurrency: usize,
+    /// Most workspace members whose scripts run at once with `volt run --recursive`
+    pub workspace_concurrency: usize,
+    /// Where the task cache is shared: an http endpoint or an S3 bucket
+    /// (`https://s3.eu-west-1.amazonaws.com/bucket/prefix`), holding `<hash>.tar.gz`
+    pub remote_cache_url: Option<String>,
+    /// Bearer token of the remote task cache (best set through `VOLT_REMOTE_CACHE_TOKEN`),
+    /// dropped when a project sets the url but not the token
+    pub remote_cache_token: Option<String>,
+    /// Region of an S3 remote cache; requests are then signed with the `AWS_ACCESS_KEY_ID`
+    /// and `AWS_SECRET_ACCESS_KEY` of the environment
+    pub remote_cache_region: Option<String>,
+    /// Whether the remote task cache is only read from, never written to
+    pub remote_cache_read_only: bool,
+    /// `bin/node-gyp.js` of the node-gyp that builds native addons, over the one found
+    /// in the project or bundled with npm
+    pub node_gyp: Option<PathBuf>,
+    /// Python node-gyp runs, over the `python3` on `PATH`
+    pub python: Option<PathBuf>,
+    /// Visual Studio version node-gyp builds with on windows (`2022`)
+    pub msvs_version: Option<String>,
+    /// Command run before packages are resolved, given the requested ones
+    pub pre_resolve_hook: Option<String>,
+    /// Command run once packages are resolved, given the resolved graph
+    pub post_resolve_hook: Option<String>,
+    /// Command run before the packages of an install are downloaded, given their graph
+    pub pre_extract_hook: Option<String>,
+    /// Command run once an install is done, given the installed graph
+    pub post_install_hook: Option<String>,
+    /// Whether registry packages are resolved and downloaded only from the `vendor`
+    /// directory of the project, without reaching the registry
+    pub vendored: bool,
+    /// Largest tarball held in memory, in bytes; larger ones stay in a file while they
+    /// are verified and extracted
+    pub tarball_memory_limit: u64,
+    /// How long the resolution of a spec is reused before it is resolved again; `None`
+    /// resolves every time
+    pub resolution_cache_ttl: Option<Duration>,
+    /// Most bytes of tarballs downloaded per second, by every download of the run
+    /// together; `None` doesn't limit them
+    pub network_limit: Option<u64>,
+    /// Times a request failing on the network, or with a server error, is sent again
+    pub fetch_retries: u32,
+    /// How long a request waits to connect, for metadata, or for the next bytes of a
+    /// tarball; `None` waits forever
+    pub fetch_timeout: Option<Duration>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            registry: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            store_dir: None,
+            strict_ssl: true,
+            proxy: None,
+            https_proxy: None,
+            noproxy: vec![],
+            cafile: None,
+            node_linker: NodeLinker::default(),
+            hoist_pattern: vec!["*".to_string()],
+            public_hoist_pattern: vec![],
+            lock_timeout: DEFAULT_LOCK_TIMEOUT,
+            engine_strict: false,
+            save_exact: false,
+            save_prefix: "^".to_string(),
+            tarball_mirror: vec![],
+            scoped_tarball_mirrors: BTreeMap::new(),
+            node_mirror: vec![],
+            update_check: true,
+            update_url: None,
+            update_public_key: None,
+            ignore_scripts: true,
+            allow_scripts: vec![],
+            minimum_release_age: None,
+            verify_signatures: true,
+            require_signatures: false,
+            child_concurrency: DEFAULT_CHILD_CONCURRENCY,
+            workspace_concurrency: DEFAULT_WORKSPACE_CONCURRENCY,
+            remote_cache_url: None,
+            remote_cache_token: None,
+            remote_cache_region: None,
+            remote_cache_read_only: false,
+            node_gyp: None,
+            python: None,
+            msvs_version: None,
+            pre_resolve_hook: None,
+            post_resolve_hook: None,
+            pre_extract_hook: None,
+            post_install_hook: None,
+            vendored: false,
+            tarball_memory_limit: DEFAULT_TARBALL_MEMORY_LIMIT,
+            resolution_cache_ttl: Some(DEFAULT_RESOLUTION_CACHE_TTL),
+            network_limit: None,
+            fetch_retries: DEFAULT_FETCH_RETRIES,
+            fetch_timeout: Some(DEFAULT_FETCH_TIMEOUT),
+        }
+    }
+}
+
+impl Settings {
+    /// Read the settings of the user (in `home` and `volt_home`), of the project in `cwd`
+    /// and of the environment.
+    pub fn load(home: &Path, volt_home: &Path, cwd: &Path) -> Result<Self> {
+        let mut values = BTreeMap::new();
+
+        let project = [
+            cwd.join(".npmrc"),
+            cwd.join("volt.toml"),
+            cwd.join(".voltrc"),
+        ];
+
+        // the keys of the user alone are skipped from any other file
+        let user_only = |key: &str, path: &Path| {
+            let skipped = USER_KEYS.contains(&key) && path != volt_home.join("config.toml");
+
+            if skipped && project.iter().any(|project| project == path) {
+                tracing::warn!(
+                    "ignoring `{}` set by {}, only ~/.volt/config.toml and VOLT_{} can set it",
+                    key,
+                    path.display(),
+                    key.replace('-', "_").to_uppercase()
+                );
+            }
+
+            skipped
+        };
+
+        for path in [home.join(".npmrc"), cwd.join(".npmrc")] {
+            for (key, value) in read_npmrc(&path)? {
+                if user_only(&key, &path) {
+                    continue;
+                }
+
+                if KEYS.contains(&key.as_str()) || key.ends_with(SCOPED_TARBALL_MIRROR) {
+                    let value = directory_value(&key, value, home, path.parent().unwrap_or(cwd));
+
+                    values.insert(key, (value, path.display().to_string()));
+                }
+            }
+        }
+
+        for path in [
+            volt_home.join("config.toml"),
+            cwd.join("volt.toml"),
+            cwd.join(".voltrc"),
+        ] {
+            for (key, value) in read_settings(&path)? {
+                if user_only(&key, &path) {
+                    continue;
+                }
+
+                let value = directory_value(&key, value, home, path.parent().unwrap_or(cwd));
+
+                values.insert(key, (value, path.display().to_string()));
+            }
+        }
+
+        for key in KEYS {
+            let variable = format!("VOLT_{}", key.replace('-', "_").to_uppercase());
+
+            if let Ok(value) = std::env::var(&variable) {
+                values.insert(
+                    key.to_string(),
+                    (directory_value(key, value, home, cwd), variable),
+                );
+            }
+        }
+
+        // a token is only sent to a url of the same file, so that a project can't have the
+        // token of the user sent to a server of its choice
+        if let (Some((_, url_origin)), Some((_, token_origin))) = (
+            values.get("remote-cache-url"),
+            values.get("remote-cache-token"),
+        ) {
+            let from_project = project
+                .iter()
+                .any(|path| path.display().to_string() == *url_origin);
+
+            if from_project && url_origin != token_origin {
+                tracing::warn!(
+                    "ignoring `remote-cache-token`, `remote-cache-url` is set by {} and the token by {}",
+                    url_origin,
+                    token_origin
+                );
+
+                values.remove("remote-cache-token");
+            }
+        }
+
+        let mut settings = Self::default();
+
+        for (key, (value, origin)) in values {
+            settings
+                .set(&key, &value)
+                .map_err(|expected| VoltError::InvalidSetting {
+                    key,
+                    value,
+                    origin,
+                    expected,
+                })?;
+        }
+
+        Ok(settings)
+    }
+
+    /// Set `key` to `value`, or describe the values it expects. Unknown keys are ignored,
+    /// so that files can be shared with newer versions.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "registry" => self.registry = Some(value.to_string()),
+            "concurrency" => {
+                self.concurrency = value
+                    .parse()
+                    .ok()
+                    .filter(|concurrency| *concurrency > 0)
+                    .ok_or("a number above 0")?;
+            }
+            "store-dir" | "cache-dir" => self.store_dir = Some(PathBuf::from(value)),
+            "strict-ssl" => {
+                self.strict_ssl = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "proxy" => self.proxy = Some(parse_url(value)?),
+            "https-proxy" => self.https_proxy = Some(parse_url(value)?),
+            "noproxy" => self.noproxy = parse_list(value),
+            "cafile" => self.cafile = Some(PathBuf::from(value)),
+            "node-linker" => {
+                self.node_linker = value.parse()?;
+            }
+            "hoist-pattern" => self.hoist_pattern = parse_list(value),
+            "public-hoist-pattern" => self.public_hoist_pattern = parse_list(value),
+            "lock-timeout" => {
+                self.lock_timeout = value.parse().map_err(|_| "a number of seconds")?;
+            }
+            "engine-strict" => {
+                self.engine_strict = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "save-exact" => {
+                self.save_exact = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "save-prefix" => {
+                self.save_prefix = match value {
+                    "^" | "~" | "" => value.to_string(),
+                    _ => return Err("`^`, `~` or `\"\"`".to_string()),
+                };
+            }
+            // the part of the version updates may move
+            "pin" => {
+                self.save_prefix = match value {
+                    "major" => "^",
+                    "minor" => "~",
+                    "patch" => "",
+                    _ => return Err("`major`, `minor` or `patch`".to_string()),
+                }
+                .to_string();
+            }
+            "tarball-mirror" => self.tarball_mirror = parse_urls(value)?,
+            "node-mirror" => self.node_mirror = parse_urls(value)?,
+            "update-check" => {
+                self.update_check = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "update-url" => self.update_url = Some(parse_url(value)?),
+            "update-public-key" => self.update_public_key = Some(value.trim().to_string()),
+            "ignore-scripts" => {
+                self.ignore_scripts = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "allow-scripts" => self.allow_scripts = parse_list(value),
+            "verify-signatures" => {
+                self.verify_signatures = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "require-signatures" => {
+                self.require_signatures = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "child-concurrency" => {
+                self.child_concurrency = value
+                    .parse()
+                    .ok()
+                    .filter(|concurrency| *concurrency > 0)
+                    .ok_or("a number above 0")?;
+            }
+            "workspace-concurrency" => {
+                self.workspace_concurrency = value
+                    .parse()
+                    .ok()
+                    .filter(|concurrency| *concurrency > 0)
+                    .ok_or("a number above 0")?;
+            }
+            "remote-cache-url" => self.remote_cache_url = Some(parse_url(value)?),
+            "remote-cache-token" => self.remote_cache_token = Some(value.to_string()),
+            "remote-cache-region" => self.remote_cache_region = Some(value.to_string()),
+            "remote-cache-read-only" => {
+                self.remote_cache_read_only = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "node-gyp" => self.node_gyp = Some(PathBuf::from(value)),
+            "python" => self.python = Some(PathBuf::from(value)),
+            "msvs-version" => self.msvs_version = Some(value.to_string()),
+            "pre-resolve-hook" => self.pre_resolve_hook = Some(value.to_string()),
+            "post-resolve-hook" => self.post_resolve_hook = Some(value.to_string()),
+            "pre-extract-hook" => self.pre_extract_hook = Some(value.to_string()),
+            "post-install-hook" => self.post_install_hook = Some(value.to_string()),
+            "vendored" => {
+                self.vendored = value.parse().map_err(|_| "`true` or `false`")?;
+            }
+            "tarball-memory-limit" => {
+                self.tarball_memory_limit = value.parse().map_err(|_| "a number of bytes")?;
+            }
+            "resolution-cache-ttl" => {
+                self.resolution_cache_ttl =
+                    Some(parse_duration(value)?).filter(|ttl| !ttl.is_zero());
+            }
+            "network-limit" => {
+                self.network_limit = Some(parse_rate(value)?).filter(|rate| *rate > 0);
+            }
+            "fetch-retries" => {
+                self.fetch_retries = value.parse().map_err(|_| "a number of retries")?;
+            }
+            "fetch-timeout" => {
+                self.fetch_timeout =
+                    Some(parse_timeout(value)?).filter(|timeout| !timeout.is_zero());
+            }
+            "minimum-release-age" => {
+                self.minimum_release_age =
+                    Some(parse_duration(value)?).filter(|age| !age.is_zero());
+            }
+            key if key.starts_with('@') && key.ends_with(SCOPED_TARBALL_MIRROR) => {
+                let scope = key.trim_end_matches(SCOPED_TARBALL_MIRROR).to_string();
+
+                self.scoped_tarball_mirrors
+                    .insert(scope, parse_urls(value)?);
+            }
+            _ => tracing::debug!("ignoring unknown setting `{}`", key),
+        }
+
+        Ok(())
+    }
+
+    /// Whether requests to `host` skip the proxy.
+    pub fn bypasses_proxy(&self, host: &str) -> bool {
+        self.noproxy.iter().any(|pattern| {
+            let pattern = pattern.trim_start_matches('.');
+
+            pattern == "*"
+                || host == pattern
+                || host
+                    .strip_suffix(pattern)
+                    .map_or(false, |rest| rest.ends_with('.'))
+        })
+    }
+}
+
+/// `value` with the directory it names made absolute when `key` is a directory: `~/`
+/// starts from `home`, and relative directories from `base`.
+fn directory_value(key: &str, value: String, home: &Path, base: &Path) -> String {
+    if !DIRECTORY_KEYS.contains(&key) {
+        return value;
+    }
+
+    let directory = match value.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => base.join(&value),
+    };
+
+    directory.display().to_string()
+}
+
+/// A comma separated list (`localhost, .internal.example.com`), or a TOML array of
+/// strings (`["esbuild", "sharp"]`).
+pub(crate) fn parse_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .unwrap_or(value);
+
+    value
+        .split(',')
+        .map(|item| item.trim().trim_matches(|c| c == '"' || c == '\''))
+        .filter(|item| !item.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// A duration in minutes (`90`), hours (`72h`), days (`3d`) or weeks (`1w`).
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "m"),
+    };
+
+    let minutes = match unit.trim() {
+        "m" | "min" => 1,
+        "h" => 60,
+        "d" => 60 * 24,
+        "w" => 60 * 24 * 7,
+        _ => 0,
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .filter(|_| minutes > 0)
+        .map(|number| Duration::from_secs(number * minutes * 60))
+        .ok_or_else(|| "a duration, like `90` (minutes), `72h`, `3d` or `1w`".to_string())
+}
+
+/// A timeout in milliseconds (`30000`, like npm's), seconds (`30s`) or minutes (`5m`).
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "ms"),
+    };
+
+    let millis = match unit.trim() {
+        "ms" => 1,
+        "s" => 1000,
+        "m" | "min" => 60 * 1000,
+        _ => 0,
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .filter(|_| millis > 0)
+        .map(|number| Duration::from_millis(number * millis))
+        .ok_or_else(|| "a timeout, like `30000` (milliseconds), `30s` or `5m`".to_string())
+}
+
+/// A rate in bytes per second: `5MB/s`, `500KB/s`, `1MiB/s` or a number of bytes; the
+/// `/s` may be left out, and `0` means no limit.
+pub fn parse_rate(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let value = value.strip_suffix("/s").unwrap_or(value);
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit() && c != '.') {
+        Some(index) => value.split_at(index),
+        None => (value, "B"),
+    };
+
+    let bytes: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "b" => 1,
+        "k" | "kb" => 1000,
+        "m" | "mb" => 1000 * 1000,
+        "g" | "gb" => 1000 * 1000 * 1000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        _ => 0,
+    };
+
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|number| bytes > 0 && number.is_finite())
+        .map(|number| (number * bytes as f64) as u64)
+        .ok_or_else(|| "a rate, like `5MB/s`, `500KB/s` or `1MiB/s`".to_string())
+}
+
+fn parse_url(value: &str) -> Result<String, String> {
+    Url::parse(value)
+        .map(|_| value.to_string())
+        .map_err(|_| "a url, like `http://proxy.example.com:8080`".to_string())
+}
+
+/// A comma separated list of urls, in order of preference.
+fn parse_urls(value: &str) -> Result<Vec<String>, String> {
+    parse_list(value)
+        .iter()
+        .map(|url| parse_url(url))
+        .collect::<Result<_, _>>()
+        .map_err(|_| "a comma separated list of urls".to_string())
+}
+
+pub(crate) fn read_settings(path: &Path) -> Result<BTreeMap<String, String>> {
+    if !path.is_file() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = std::fs::read_to_string(path).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    Ok(parse_settings(&contents))
+}
+
+/// The `key = value` lines of a settings file; `strict_ssl` is read as `strict-ssl`.
+fn parse_settings(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+
+            (key.trim().replace('_', "-"), value.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{directory_value, parse_settings, Settings};
+    use crate::linker::NodeLinker;
+
+    use std::path::Path;
+
+    #[test]
+    fn projects_dont_choose_where_volt_upgrades_from() {
+        let (home, project) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let volt_home = home.path().join(".volt");
+
+        std::fs::create_dir_all(&volt_home).unwrap();
+        std::fs::write(
+            volt_home.join("config.toml"),
+            "update-public-key = \"dXNlcg==\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.path().join(".voltrc"),
+            "update-url = \"https://evil.example.com/release\"\nupdate-public-key = \"ZXZpbA==\"\nsave-exact = true\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(home.path(), &volt_home, project.path()).unwrap();
+
+        assert_eq!(settings.update_url, None);
+        assert_eq!(settings.update_public_key.as_deref(), Some("dXNlcg=="));
+        assert!(settings.save_exact);
+    }
+
+    #[test]
+    fn projects_dont_get_the_remote_cache_token() {
+        let (home, project) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let volt_home = home.path().join(".volt");
+
+        std::fs::create_dir_all(&volt_home).unwrap();
+        std::fs::write(
+            volt_home.join("config.toml"),
+            "remote-cache-url = \"https://cache.example.com/volt\"\nremote-cache-token = \"secret\"\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(home.path(), &volt_home, project.path()).unwrap();
+        assert_eq!(settings.remote_cache_token.as_deref(), Some("secret"));
+
+        std::fs::write(
+            project.path().join("volt.toml"),
+            "remote-cache-url = \"https://evil.example.com/volt\"\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(home.path(), &volt_home, project.path()).unwrap();
+        assert_eq!(
+            settings.remote_cache_url.as_deref(),
+            Some("https://evil.example.com/volt")
+        );
+        assert_eq!(settings.remote_cache_token, None);
+
+        // unless the project gives its own
+        std::fs::write(
+            project.path().join("volt.toml"),
+            "remote-cache-url = \"https://cache.project.com\"\nremote-cache-token = \"project\"\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(home.path(), &volt_home, project.path()).unwrap();
+        assert_eq!(settings.remote_cache_token.as_deref(), Some("project"));
+    }
+
+    #[test]
+    fn parses_settings() {
+        let values = parse_settings(
+            "# comment\nregistry = \"https://registry.example.com/\"\nstrict_ssl = false\nconcurrency=4\n",
+        );
+
+        let mut settings = Settings::default();
+
+        for (key, value) in &values {
+            settings.set(key, value).unwrap();
+        }
+
+        assert_eq!(
+            settings.registry.as_deref(),
+            Some("https://registry.example.com/")
+        );
+        assert!(!settings.strict_ssl);
+        assert_eq!(settings.concurrency, 4);
+
+        settings.set("node-linker", "hoisted").unwrap();
+        assert_eq!(settings.node_linker, NodeLinker::Hoisted);
+
+        assert!(settings.set("concurrency", "0").is_err());
+        assert!(settings.set("strict-ssl", "nope").is_err());
+        assert!(settings.set("proxy", "not a url").is_err());
+        assert!(settings.set("node-linker", "flat").is_err());
+        assert!(settings.set("lock-timeout", "soon").is_err());
+        assert!(settings.set("engine-strict", "yes").is_err());
+        assert!(settings.set("save-exact", "maybe").is_err());
+        assert!(settings.set("save-prefix", ">=").is_err());
+        assert!(settings.set("pin", "everything").is_err());
+
+        settings.set("pin", "minor").unwrap();
+        assert_eq!(settings.save_prefix, "~");
+        assert!(settings.set("update-check", "daily").is_err());
+        assert!(settings.set("require-signatures", "always").is_err());
+        assert!(settings
+            .set("tarball-mirror", "https://mirror.example.com/, nope")
+            .is_err());
+
+        settings
+            .set("noproxy", "localhost, .internal.example.com")
+            .unwrap();
+
+        assert!(settings.bypasses_proxy("localhost"));
+        assert!(settings.bypasses_proxy("registry.internal.example.com"));
+        assert!(settings.bypasses_proxy("internal.example.com"));
+        assert!(!settings.bypasses_proxy("notinternal.example.com"));
+        assert!(!settings.bypasses_proxy("registry.npmjs.org"));
+
+        assert!(settings.ignore_scripts);
+        assert!(settings.set("ignore-scripts", "sometimes").is_err());
+
+        let values = parse_settings("allow-scripts = [\"esbuild\", 'sharp']\n");
+
+        settings
+            .set("allow-scripts", &values["allow-scripts"])
+            .unwrap();
+        assert_eq!(settings.allow_scripts, ["esbuild", "sharp"]);
+
+        settings.set("minimum-release-age", "72h").unwrap();
+        assert_eq!(
+            settings.minimum_release_age,
+            Some(std::time::Duration::from_secs(72 * 60 * 60))
+        );
+        settings.set("minimum-release-age", "0").unwrap();
+        assert_eq!(settings.minimum_release_age, None);
+        assert!(settings.set("minimum-release-age", "soon").is_err());
+        assert!(settings.set("minimum-release-age", "3y").is_err());
+
+        assert_eq!(
+            settings.resolution_cache_ttl,
+            Some(std::time::Duration::from_secs(10 * 60))
+        );
+        settings.set("resolution-cache-ttl", "0").unwrap();
+        assert_eq!(settings.resolution_cache_ttl, None);
+
+        assert_eq!(settings.network_limit, None);
+        settings.set("network-limit", "5MB/s").unwrap();
+        assert_eq!(settings.network_limit, Some(5_000_000));
+        settings.set("network-limit", "1.5MiB").unwrap();
+        assert_eq!(settings.network_limit, Some(1_572_864));
+        settings.set("network-limit", "0").unwrap();
+        assert_eq!(settings.network_limit, None);
+        assert!(settings.set("network-limit", "fast").is_err());
+        assert!(settings.set("network-limit", "5MB/h").is_err());
+
+        assert_eq!(settings.fetch_retries, 2);
+        settings.set("fetch-retries", "5").unwrap();
+        assert_eq!(settings.fetch_retries, 5);
+        assert!(settings.set("fetch-retries", "-1").is_err());
+
+        settings.set("fetch-timeout", "30000").unwrap();
+        assert_eq!(
+            settings.fetch_timeout,
+            Some(std::time::Duration::from_secs(30))
+        );
+        settings.set("fetch-timeout", "2m").unwrap();
+        assert_eq!(
+            settings.fetch_timeout,
+            Some(std::time::Duration::from_secs(120))
+        );
+        settings.set("fetch-timeout", "0").unwrap();
+        assert_eq!(settings.fetch_timeout, None);
+        assert!(settings.set("fetch-timeout", "1h").is_err());
+
+        assert_eq!(settings.child_concurrency, 5);
+        settings.set("child-concurrency", "2").unwrap();
+        assert_eq!(settings.child_concurrency, 2);
+        assert!(settings.set("child-concurrency", "0").is_err());
+
+        assert_eq!(settings.workspace_concurrency, 4);
+        settings.set("workspace-concurrency", "1").unwrap();
+        assert_eq!(settings.workspace_concurrency, 1);
+
+        settings
+            .set("remote-cache-url", "https://cache.example.com/volt")
+            .unwrap();
+        assert_eq!(
+            settings.remote_cache_url.as_deref(),
+            Some("https://cache.example.com/volt")
+        );
+        assert!(settings.set("remote-cache-url", "cache").is_err());
+        assert!(settings.set("remote-cache-read-only", "yes").is_err());
+
+        settings
+            .set("pre-extract-hook", "node scripts/policy.js")
+            .unwrap();
+        assert_eq!(
+            settings.pre_extract_hook.as_deref(),
+            Some("node scripts/policy.js")
+        );
+    }
+
+    #[test]
+    fn resolves_directories_from_where_they_are_set() {
+        let (home, project) = (Path::new("/home/me"), Path::new("/work/app"));
+
+        assert_eq!(
+            directory_value("store-dir", ".volt-store".to_string(), home, project),
+            Path::new("/work/app/.volt-store").display().to_string()
+        );
+        assert_eq!(
+            directory_value("cache-dir", "~/stores/volt".to_string(), home, project),
+            Path::new("/home/me/stores/volt").display().to_string()
+        );
+        assert_eq!(
+            directory_value("store-dir", "/var/volt".to_string(), home, project),
+            "/var/volt"
+        );
+        assert_eq!(
+            directory_value("registry", "./nope".to_string(), home, project),
+            "./nope"
+        );
+    }
+}
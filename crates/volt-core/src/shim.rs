@@ -0,0 +1,252 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Generate executables in `node_modules/.bin` for the `bin` field of a package.
+
+use crate::utils::{errors::FilesystemError, voltapi::Bin};
+
+use miette::Result;
+
+use std::path::{Path, PathBuf};
+
+/// List the `(command name, relative script path)` pairs declared by a `bin` field.
+///
+/// A string `bin` is exposed under the unscoped package name. Names that would
+/// escape `node_modules/.bin` are skipped.
+pub fn bin_entries(package_name: &str, bin: &Bin) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = match bin {
+        Bin::String(path) if path.is_empty() => vec![],
+        Bin::String(path) => vec![(unscoped(package_name).to_string(), path.clone())],
+        Bin::Map(map) => map
+            .iter()
+            .map(|(name, path)| (unscoped(name).to_string(), path.clone()))
+            .collect(),
+    };
+
+    entries.retain(|(name, _)| {
+        !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+    });
+    entries.sort();
+
+    entries
+}
+
+/// `@scope/cli` is exposed as `cli`
+fn unscoped(name: &str) -> &str {
+    match name.strip_prefix('@') {
+        Some(scoped) => scoped.split_once('/').map_or(name, |(_, name)| name),
+        None => name,
+    }
+}
+
+/// Create a shim in `bin_dir` for every command in `bin`, pointing into `package_dir`.
+pub fn link_bins(bin_dir: &Path, package_dir: &Path, package_name: &str, bin: &Bin) -> Result<()> {
+    let entries = bin_entries(package_name, bin);
+
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(bin_dir).map_err(|e| FilesystemError::Write {
+        source: e,
+        path: bin_dir.display().to_string(),
+    })?;
+
+    for (name, path) in entries {
+        let target = package_dir.join(&path);
+
+        write_shim(bin_dir, &name, &target)?;
+    }
+
+    Ok(())
+}
+
+/// Remove the shims created by [`link_bins`] for a package.
+pub fn unlink_bins(bin_dir: &Path, package_name: &str, bin: &Bin) -> Result<()> {
+    for (name, _) in bin_entries(package_name, bin) {
+        #[cfg(windows)]
+        let files = vec![format!("{}.cmd", name), format!("{}.ps1", name), name];
+
+        #[cfg(unix)]
+        let files = vec![name];
+
+        for file in files {
+            let path = bin_dir.join(file);
+
+            if path.symlink_metadata().is_ok() {
+                std::fs::remove_file(&path).map_err(|e| FilesystemError::Write {
+                    source: e,
+                    path: path.display().to_string(),
+                })?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Relative path from `bin_dir` to `target`, falling back to the absolute path when
+/// the two don't share a `node_modules` root.
+fn relative_target(bin_dir: &Path, target: &Path) -> PathBuf {
+    bin_dir
+        .parent()
+        .and_then(|root| target.strip_prefix(root).ok())
+        .map(|relative| Path::new("..").join(relative))
+        .unwrap_or_else(|| target.to_path_buf())
+}
+
+#[cfg(unix)]
+fn write_shim(bin_dir: &Path, name: &str, target: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let link = bin_dir.join(name);
+
+    // replace shims left behind by a previous install
+    if link.symlink_metadata().is_ok() {
+        std::fs::remove_file(&link).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: link.display().to_string(),
+        })?;
+    }
+
+    std::os::unix::fs::symlink(relative_target(bin_dir, target), &link).map_err(|e| {
+        FilesystemError::Write {
+            source: e,
+            path: link.display().to_string(),
+        }
+    })?;
+
+    // tarballs don't always preserve the executable bit
+    if let Ok(metadata) = std::fs::metadata(target) {
+        let mut permissions = metadata.permissions();
+        permissions.set_mode(permissions.mode() | 0o111);
+
+        std::fs::set_permissions(target, permissions).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: target.display().to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn write_shim(bin_dir: &Path, name: &str, target: &Path) -> Result<()> {
+    let relative = relative_target(bin_dir, target);
+    let windows_path = relative.display().to_string().replace('/', "\\");
+    let unix_path = relative.display().to_string().replace('\\', "/");
+
+    let cmd_file = format!(
+        r#"@SETLOCAL
+@IF EXIST "%~dp0\node.exe" (
+  "%~dp0\node.exe"  "%~dp0\{path}" %*
+) ELSE (
+  @SET PATHEXT=%PATHEXT:;.JS;=;%
+  node  "%~dp0\{path}" %*
+)
+"#,
+        path = windows_path
+    );
+
+    let ps1_file = format!(
+        r#"#!/usr/bin/env pwsh
+$basedir=Split-Path $MyInvocation.MyCommand.Definition -Parent
+
+$exe=""
+if ($PSVersionTable.PSVersion -lt "6.0" -or $IsWindows) {{
+  $exe=".exe"
+}}
+$ret=0
+if (Test-Path "$basedir/node$exe") {{
+  if ($MyInvocation.ExpectingInput) {{
+    $input | & "$basedir/node$exe"  "$basedir/{path}" $args
+  }} else {{
+    & "$basedir/node$exe"  "$basedir/{path}" $args
+  }}
+  $ret=$LASTEXITCODE
+}} else {{
+  if ($MyInvocation.ExpectingInput) {{
+    $input | & "node$exe"  "$basedir/{path}" $args
+  }} else {{
+    & "node$exe"  "$basedir/{path}" $args
+  }}
+  $ret=$LASTEXITCODE
+}}
+exit $ret
+"#,
+        path = unix_path
+    );
+
+    let sh_file = format!(
+        r#"#!/bin/sh
+basedir=$(dirname "$(echo "$0" | sed -e 's,\\,/,g')")
+
+case `uname` in
+    *CYGWIN*) basedir=`cygpath -w "$basedir"`;;
+esac
+
+if [ -x "$basedir/node" ]; then
+  exec "$basedir/node"  "$basedir/{path}" "$@"
+else
+  exec node  "$basedir/{path}" "$@"
+fi
+"#,
+        path = unix_path
+    );
+
+    for (file_name, contents) in [
+        (format!("{}.cmd", name), cmd_file),
+        (format!("{}.ps1", name), ps1_file),
+        (name.to_string(), sh_file),
+    ] {
+        let path = bin_dir.join(&file_name);
+
+        std::fs::write(&path, contents).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bin_entries;
+    use crate::utils::voltapi::Bin;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn string_bin_uses_unscoped_package_name() {
+        let entries = bin_entries("@scope/tool", &Bin::String("./cli.js".to_string()));
+
+        assert_eq!(entries, vec![("tool".to_string(), "./cli.js".to_string())]);
+    }
+
+    #[test]
+    fn map_bin_skips_unsafe_names() {
+        let map = HashMap::from([
+            ("ok".to_string(), "bin/ok.js".to_string()),
+            ("../evil".to_string(), "bin/evil.js".to_string()),
+            (".hidden".to_string(), "bin/hidden.js".to_string()),
+        ]);
+
+        let entries = bin_entries("pkg", &Bin::Map(map));
+
+        assert_eq!(entries, vec![("ok".to_string(), "bin/ok.js".to_string())]);
+    }
+}
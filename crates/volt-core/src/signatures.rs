@@ -0,0 +1,319 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check the signatures the registry puts on the packages it serves.
+//!
+//! The npm registry signs `name@version:integrity` of every version with ECDSA P-256
+//! keys it publishes at `/-/npm/v1/keys`, and lists the signatures in `dist.signatures`
+//! of the version. A signature that doesn't verify fails the install; a package without
+//! one only does with `require-signatures`. Registries without keys are skipped.
+//!
+//! Keys the registry rotated out carry an `expires` date: a signature by one of them only
+//! counts for versions published before it.
+
+use crate::{
+    config::VoltConfig,
+    install::Resolution,
+    registry::RegistryClient,
+    release_age::parse_timestamp,
+    utils::{
+        errors::{IntegrityError, VoltError},
+        package::Signature,
+    },
+};
+
+use futures::{stream, StreamExt};
+use miette::Result;
+use reqwest::Method;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1};
+use serde::Deserialize;
+
+use std::{collections::HashMap, time::SystemTime};
+
+/// The DER prefix of a P-256 public key in a SubjectPublicKeyInfo, before its 65 bytes.
+const P256_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// A signing key of the registry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegistryKey {
+    pub keyid: String,
+    /// `ecdsa-sha2-nistp256`
+    pub scheme: String,
+    /// Base64 SubjectPublicKeyInfo
+    pub key: String,
+    /// When the key stopped signing, as an RFC 3339 timestamp; `None` while it still does
+    #[serde(default)]
+    pub expires: Option<String>,
+}
+
+impl RegistryKey {
+    /// Whether `signature` (base64 DER) is this key's signature of `message`.
+    pub fn verifies(&self, message: &str, signature: &str) -> bool {
+        if self.scheme != "ecdsa-sha2-nistp256" {
+            return false;
+        }
+
+        let (key, signature) = match (base64::decode(&self.key), base64::decode(signature)) {
+            (Ok(key), Ok(signature)) => (key, signature),
+            _ => return false,
+        };
+
+        match key.strip_prefix(P256_SPKI_PREFIX) {
+            Some(point) => UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, point)
+                .verify(message.as_bytes(), &signature)
+                .is_ok(),
+            None => false,
+        }
+    }
+
+    /// Whether the key had expired before `published`, so it can't have signed what was
+    /// published then.
+    pub fn expired_before(&self, published: SystemTime) -> bool {
+        self.expires
+            .as_deref()
+            .and_then(parse_timestamp)
+            .map_or(false, |expires| expires < published)
+    }
+}
+
+/// What the check found, for the install summary.
+#[derive(Debug, Default)]
+pub struct SignatureReport {
+    pub verified: usize,
+    /// `name@version` of the packages without a signature
+    pub unsigned: Vec<String>,
+}
+
+/// The signing keys of the registry, by id; `None` if it publishes none.
+async fn registry_keys(client: &RegistryClient) -> Option<HashMap<String, RegistryKey>> {
+    #[derive(Deserialize)]
+    struct Keys {
+        keys: Vec<RegistryKey>,
+    }
+
+    let response = client
+        .send(client.request(Method::GET, "-/npm/v1/keys"))
+        .await
+        .map_err(|error| tracing::debug!("no registry keys: {}", error))
+        .ok()?;
+
+    let keys: Keys = response.json().await.ok()?;
+
+    Some(
+        keys.keys
+            .into_iter()
+            .map(|key| (key.keyid.clone(), key))
+            .collect(),
+    )
+}
+
+/// Check the signatures of the registry packages of `resolution`. `None` when the check
+/// is turned off or the registry doesn't sign packages.
+pub async fn verify_signatures(
+    config: &VoltConfig,
+    resolution: &Resolution,
+) -> Result<Option<SignatureReport>> {
+    let settings = config.settings();
+
+    if !settings.verify_signatures {
+        return Ok(None);
+    }
+
+    let client = RegistryClient::new(config)?;
+
+    let keys = match registry_keys(&client).await {
+        Some(keys) => keys,
+        None if settings.require_signatures => HashMap::new(),
+        None => return Ok(None),
+    };
+
+    // git, local and tarball url packages aren't signed by anyone
+    let mut packages: Vec<(String, String, String)> = resolution
+        .tree
+        .values()
+        .filter(|package| !package.remote && package.tarball.starts_with("http"))
+        .map(|package| {
+            (
+                package.name.clone(),
+                package.version.clone(),
+                package.integrity.clone(),
+            )
+        })
+        .collect();
+
+    packages.sort_unstable();
+
+    let keys = &keys;
+
+    let checks = packages.into_iter().map(|(name, version, integrity)| {
+        let client = client.clone();
+
+        async move {
+            let dist = client.dist(&name, &version).await;
+
+            // only the full document has the publish date, fetched for keys that expire
+            let expiring = dist
+                .iter()
+                .flat_map(|dist| &dist.signatures)
+                .any(|signature| {
+                    keys.get(&signature.keyid)
+                        .map_or(false, |key| key.expires.is_some())
+                });
+
+            let published = if expiring {
+                client.published(&name, &version).await
+            } else {
+                None
+            };
+
+            (name, version, integrity, dist, published)
+        }
+    });
+
+    let dists: Vec<_> = stream::iter(checks)
+        .buffer_unordered(settings.concurrency)
+        .collect()
+        .await;
+
+    let mut report = SignatureReport::default();
+
+    for (name, version, integrity, dist, published) in dists {
+        let package = format!("{}@{}", name, version);
+
+        let dist = match dist {
+            Some(dist) => dist,
+            None => {
+                report.unsigned.push(package);
+
+                continue;
+            }
+        };
+
+        // signatures by keys the registry no longer lists can't be checked
+        let signatures: Vec<&Signature> = dist
+            .signatures
+            .iter()
+            .filter(|signature| keys.contains_key(&signature.keyid))
+            .collect();
+
+        if signatures.is_empty() {
+            report.unsigned.push(package);
+
+            continue;
+        }
+
+        let message = format!("{}:{}", package, dist.integrity);
+
+        // the tarball about to be installed must be the one that was signed
+        let same_tarball = !integrity.starts_with("sha512-")
+            || !dist.integrity.starts_with("sha512-")
+            || integrity == dist.integrity;
+
+        for signature in signatures {
+            let key = &keys[&signature.keyid];
+
+            if !same_tarball || !key.verifies(&message, &signature.sig) {
+                return Err(IntegrityError::Signature {
+                    package,
+                    keyid: signature.keyid.clone(),
+                }
+                .into());
+            }
+
+            // like npm, a version without a publish date is taken as signed in time
+            if published.map_or(false, |published| key.expired_before(published)) {
+                return Err(IntegrityError::ExpiredKey {
+                    package,
+                    keyid: signature.keyid.clone(),
+                    expires: key.expires.clone().unwrap_or_default(),
+                }
+                .into());
+            }
+        }
+
+        report.verified += 1;
+    }
+
+    report.unsigned.sort();
+
+    if settings.require_signatures && !report.unsigned.is_empty() {
+        return Err(VoltError::UnsignedPackages {
+            packages: report.unsigned.join(", "),
+        }
+        .into());
+    }
+
+    Ok(Some(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RegistryKey, P256_SPKI_PREFIX};
+
+    use crate::release_age::parse_timestamp;
+
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+    };
+
+    #[test]
+    fn verifies_registry_signatures() {
+        let random = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &random).unwrap();
+        let pair =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref()).unwrap();
+
+        let key = RegistryKey {
+            keyid: "SHA256:test".to_string(),
+            scheme: "ecdsa-sha2-nistp256".to_string(),
+            key: base64::encode([P256_SPKI_PREFIX, pair.public_key().as_ref()].concat()),
+            expires: None,
+        };
+
+        let message = "ms@2.1.3:sha512-tgp+dl5cGk28utYktBsrFqA7HKgrhgPsg6Z/EfhWI4gl1Hwq8B/GmY/0oXZ6nF8hDVesS/FpnYaD/kOWhYQvyg==";
+        let signature = base64::encode(pair.sign(&random, message.as_bytes()).unwrap());
+
+        assert!(key.verifies(message, &signature));
+        assert!(!key.verifies("ms@2.1.2:sha512-", &signature));
+        assert!(!key.verifies(message, "bm90IGEgc2lnbmF0dXJl"));
+    }
+
+    #[test]
+    fn expired_keys_only_sign_older_versions() {
+        let key = RegistryKey {
+            keyid: "SHA256:old".to_string(),
+            scheme: "ecdsa-sha2-nistp256".to_string(),
+            key: String::new(),
+            expires: Some("2025-01-29T00:00:00.000Z".to_string()),
+        };
+
+        let published = |time| parse_timestamp(time).unwrap();
+
+        assert!(!key.expired_before(published("2024-12-01T10:00:00.000Z")));
+        assert!(key.expired_before(published("2025-02-01T10:00:00.000Z")));
+
+        let current = RegistryKey {
+            expires: None,
+            ..key
+        };
+
+        assert!(!current.expired_before(published("2025-02-01T10:00:00.000Z")));
+    }
+}
@@ -0,0 +1,218 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! How much the installed packages weigh.
+//!
+//! Registry packages are measured by the `unpackedSize` and `fileCount` the registry
+//! records for them; packages it doesn't know (git, local, tarball urls) by their
+//! directory in node_modules.
+
+use crate::{
+    config::VoltConfig, install::Resolution, linker::Linker, registry::RegistryClient,
+    utils::voltapi::dependency_key,
+};
+
+use futures::{stream, StreamExt};
+use miette::Result;
+use serde::Serialize;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::AddAssign,
+    path::Path,
+};
+
+/// The unpacked size and file count of a package, or of several.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Footprint {
+    /// Bytes
+    pub size: u64,
+    pub files: u64,
+}
+
+impl AddAssign for Footprint {
+    fn add_assign(&mut self, other: Self) {
+        self.size += other.size;
+        self.files += other.files;
+    }
+}
+
+impl Footprint {
+    /// Measure the files under `path`, links left out.
+    pub fn of_directory(path: &Path) -> Self {
+        let mut footprint = Self::default();
+
+        for entry in std::fs::read_dir(path).into_iter().flatten().flatten() {
+            let metadata = match entry.path().symlink_metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+
+            if metadata.is_dir() {
+                // the dependencies of a hoisted package aren't part of it
+                if entry.file_name() != "node_modules" {
+                    footprint += Self::of_directory(&entry.path());
+                }
+            } else if metadata.is_file() {
+                footprint += Self {
+                    size: metadata.len(),
+                    files: 1,
+                };
+            }
+        }
+
+        footprint
+    }
+}
+
+/// The footprint of every package of `resolution`, by key.
+pub async fn footprints(
+    config: &VoltConfig,
+    resolution: &Resolution,
+    linker: &dyn Linker,
+) -> Result<HashMap<String, Footprint>> {
+    let client = RegistryClient::new(config)?;
+
+    let packages: Vec<(String, String, String, bool)> = resolution
+        .tree
+        .iter()
+        .map(|(key, package)| {
+            (
+                key.clone(),
+                package.name.clone(),
+                package.version.clone(),
+                !package.remote && package.tarball.starts_with("http"),
+            )
+        })
+        .collect();
+
+    let lookups = packages.into_iter().map(|(key, name, version, registry)| {
+        let client = client.clone();
+
+        async move {
+            let dist = if registry {
+                client.dist(&name, &version).await
+            } else {
+                None
+            };
+
+            (key, dist)
+        }
+    });
+
+    let dists: Vec<_> = stream::iter(lookups)
+        .buffer_unordered(config.settings().concurrency)
+        .collect()
+        .await;
+
+    Ok(dists
+        .into_iter()
+        .map(|(key, dist)| {
+            let footprint = match dist.filter(|dist| dist.unpacked_size > 0) {
+                Some(dist) => Footprint {
+                    size: dist.unpacked_size as u64,
+                    files: dist.file_count.max(0) as u64,
+                },
+                None => linker
+                    .directories(&key)
+                    .first()
+                    .map(|directory| Footprint::of_directory(directory))
+                    .unwrap_or_default(),
+            };
+
+            (key, footprint)
+        })
+        .collect())
+}
+
+/// The package `key` and every package it depends on, directly or not.
+pub fn subtree(resolution: &Resolution, key: &str) -> BTreeSet<String> {
+    let mut keys = BTreeSet::new();
+    let mut stack = vec![key.to_string()];
+
+    while let Some(key) = stack.pop() {
+        let package = match resolution.tree.get(&key) {
+            Some(package) => package,
+            None => continue,
+        };
+
+        if !keys.insert(key) {
+            continue;
+        }
+
+        for (name, version) in package.dependencies.iter().flatten() {
+            stack.push(dependency_key(name, version));
+        }
+    }
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{subtree, Footprint};
+    use crate::{install::Resolution, utils::voltapi::VoltPackage};
+
+    use std::collections::{BTreeSet, HashMap};
+
+    #[test]
+    fn measures_subtrees() {
+        let mut resolution = Resolution::default();
+
+        for (name, dependencies) in [("a", vec!["b", "c"]), ("b", vec!["c"]), ("c", vec![])] {
+            let mut package: VoltPackage = serde_json::from_value(serde_json::json!({
+                "name": name,
+                "version": "1.0.0",
+                "optional": false,
+                "integrity": "",
+                "tarball": "",
+            }))
+            .unwrap();
+
+            package.dependencies = Some(
+                dependencies
+                    .into_iter()
+                    .map(|name| (name.to_string(), "1.0.0".to_string()))
+                    .collect::<HashMap<_, _>>(),
+            );
+
+            resolution.tree.insert(format!("{}@1.0.0", name), package);
+        }
+
+        assert_eq!(
+            subtree(&resolution, "a@1.0.0"),
+            ["a@1.0.0", "b@1.0.0", "c@1.0.0"]
+                .iter()
+                .map(ToString::to_string)
+                .collect::<BTreeSet<_>>()
+        );
+        assert_eq!(subtree(&resolution, "c@1.0.0").len(), 1);
+
+        let directory = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir_all(directory.path().join("lib")).unwrap();
+        std::fs::create_dir_all(directory.path().join("node_modules/dep")).unwrap();
+        std::fs::write(directory.path().join("index.js"), "12345").unwrap();
+        std::fs::write(directory.path().join("lib/util.js"), "123").unwrap();
+        std::fs::write(directory.path().join("node_modules/dep/index.js"), "1").unwrap();
+
+        assert_eq!(
+            Footprint::of_directory(directory.path()),
+            Footprint { size: 8, files: 2 }
+        );
+    }
+}
@@ -0,0 +1,625 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Where registry packages are resolved and downloaded from.
+//!
+//! The resolver asks the [`PackageSource`] of the [`VoltConfig`] for the flattened tree of
+//! every registry package, and the installer asks it for the tarballs missing from the
+//! store. It is the registry unless the embedding tool sets another one with
+//! [`VoltConfig::set_source`]: the packages of a lock file and the store
+//! ([`OfflineSource`]), a directory of tarballs ([`TarballDirectory`]), or packages held
+//! in memory for tests ([`FixtureSource`]). The resolutions of the registry are reused
+//! for a while from [`crate::resolution_cache`].
+//!
+//! Git, `file:`, `link:` and tarball url packages don't come from a source.
+
+use crate::{
+    config::VoltConfig,
+    dist_tags::tag_version,
+    hashing::{hash, Algorithm},
+    install::Resolution,
+    io::read_manifest,
+    mirror::tarball_url,
+    model::lock_file::LockFile,
+    net::{fetch_tarball, get_volt_response},
+    registry::DEFAULT_REGISTRY,
+    tarball::Tarball,
+    utils::{
+        errors::{FilesystemError, ResolutionError},
+        package::{DistTags, PackageJson},
+        voltapi::{VoltPackage, VoltResponse},
+        State,
+    },
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use miette::{IntoDiagnostic, Result};
+use node_semver::{Range, Version};
+use package_spec::{PackageSpec, VersionSpec};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+/// Resolves registry packages and provides their tarballs.
+#[async_trait]
+pub trait PackageSource: Send + Sync {
+    /// The flattened dependency tree of a registry package (`name@range`).
+    async fn resolve(&self, config: &VoltConfig, spec: &PackageSpec) -> Result<VoltResponse>;
+
+    /// The gzipped tarball of a package resolved by [`PackageSource::resolve`], in a file
+    /// when it's larger than the `tarball-memory-limit` setting; the installer verifies
+    /// its integrity.
+    async fn tarball(
+        &self,
+        config: &VoltConfig,
+        package: &VoltPackage,
+        state: &State,
+    ) -> Result<Tarball>;
+
+    /// What the source is, for messages (`the registry`, `./vendor`).
+    fn describe(&self) -> String;
+}
+
+/// The volt registry for trees, and the npm registry (or its mirrors) for tarballs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RegistrySource;
+
+#[async_trait]
+impl PackageSource for RegistrySource {
+    async fn resolve(&self, config: &VoltConfig, spec: &PackageSpec) -> Result<VoltResponse> {
+        // the volt registry flattens versions and ranges; tags are looked up first
+        if let PackageSpec::Npm {
+            name,
+            requested: Some(VersionSpec::Tag(tag)),
+            ..
+        } = spec
+        {
+            let version = tag_version(config, name, tag).await?;
+            let spec = format!("{}@{}", name, version)
+                .parse::<PackageSpec>()
+                .into_diagnostic()?;
+
+            return get_volt_response(&spec).await;
+        }
+
+        get_volt_response(spec).await
+    }
+
+    async fn tarball(
+        &self,
+        config: &VoltConfig,
+        package: &VoltPackage,
+        state: &State,
+    ) -> Result<Tarball> {
+        fetch_tarball(config, package, state).await
+    }
+
+    fn describe(&self) -> String {
+        "the registry".to_string()
+    }
+}
+
+/// The packages of a lock file, installed from the store without any network: packages
+/// that aren't locked, or aren't in the store, can't be installed.
+#[derive(Debug, Clone)]
+pub struct OfflineSource {
+    lock_file: LockFile,
+}
+
+impl OfflineSource {
+    pub fn new(lock_file: LockFile) -> Self {
+        Self { lock_file }
+    }
+}
+
+#[async_trait]
+impl PackageSource for OfflineSource {
+    async fn resolve(&self, _config: &VoltConfig, spec: &PackageSpec) -> Result<VoltResponse> {
+        let (name, range) = requested(spec)?;
+
+        let unavailable = || ResolutionError::Unavailable {
+            package: format!("{}@{}", name, range),
+            origin: self.describe(),
+        };
+
+        let resolution = Resolution::from_lock_file(
+            &self.lock_file,
+            &BTreeMap::from([(name.clone(), range.clone())]),
+        )
+        .ok_or_else(unavailable)?;
+
+        let key = resolution.direct.first().ok_or_else(unavailable)?;
+
+        let versions = self
+            .lock_file
+            .dependencies
+            .values()
+            .filter(|package| package.name == name)
+            .map(|package| package.version.clone())
+            .collect();
+
+        Ok(VoltResponse {
+            version: resolution.tree[key].version.clone(),
+            name,
+            versions,
+            tree: resolution.tree,
+        })
+    }
+
+    // the installer only asks for the tarballs of packages missing from the store
+    async fn tarball(
+        &self,
+        _config: &VoltConfig,
+        package: &VoltPackage,
+        _state: &State,
+    ) -> Result<Tarball> {
+        Err(ResolutionError::Unavailable {
+            package: format!("{}@{}", package.name, package.version),
+            origin: "the store".to_string(),
+        }
+        .into())
+    }
+
+    fn describe(&self) -> String {
+        self.lock_file.path.display().to_string()
+    }
+}
+
+/// The gzipped tarballs of a directory (`<name>-<version>.tgz`, `+` for the `/` of
+/// scopes), resolved the way the registry flattens trees: the highest version that
+/// satisfies each range.
+#[derive(Debug, Clone)]
+pub struct TarballDirectory {
+    directory: PathBuf,
+    index: Index,
+}
+
+impl TarballDirectory {
+    /// Read the manifest of every tarball in `directory`.
+    pub fn open(directory: &Path) -> Result<Self> {
+        let read_error = |e| FilesystemError::Read {
+            source: e,
+            path: directory.display().to_string(),
+        };
+
+        let mut index = Index::default();
+
+        for entry in std::fs::read_dir(directory).map_err(read_error)? {
+            let path = entry.map_err(read_error)?.path();
+
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "tgz")
+            {
+                continue;
+            }
+
+            let tarball = std::fs::read(&path).map_err(|e| FilesystemError::Read {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+
+            index.add(&read_manifest(&tarball)?, &tarball)?;
+        }
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            index,
+        })
+    }
+
+    /// The file name of the tarball of `package` in the directory.
+    pub fn file_name(package: &VoltPackage) -> String {
+        format!("{}-{}.tgz", package.name.replace('/', "+"), package.version)
+    }
+}
+
+#[async_trait]
+impl PackageSource for TarballDirectory {
+    async fn resolve(&self, _config: &VoltConfig, spec: &PackageSpec) -> Result<VoltResponse> {
+        self.index.resolve(spec, &self.describe())
+    }
+
+    async fn tarball(
+        &self,
+        config: &VoltConfig,
+        package: &VoltPackage,
+        _state: &State,
+    ) -> Result<Tarball> {
+        let path = self.directory.join(Self::file_name(package));
+
+        Tarball::read(&path, config.settings().tarball_memory_limit)
+    }
+
+    fn describe(&self) -> String {
+        self.directory.display().to_string()
+    }
+}
+
+/// Packages added by tests, with their tarballs held in memory.
+#[derive(Debug, Default, Clone)]
+pub struct FixtureSource {
+    index: Index,
+    tarballs: HashMap<String, Bytes>,
+}
+
+impl FixtureSource {
+    /// Add the package of a gzipped tarball.
+    pub fn add(&mut self, tarball: Vec<u8>) -> Result<&mut Self> {
+        let package = self.index.add(&read_manifest(&tarball)?, &tarball)?;
+
+        self.tarballs.insert(
+            format!("{}@{}", package.name, package.version),
+            Bytes::from(tarball),
+        );
+
+        Ok(self)
+    }
+
+    /// Point the dist-tag `tag` of `name` to `version`.
+    pub fn tag(&mut self, name: &str, tag: &str, version: &str) -> &mut Self {
+        self.index
+            .dist_tags
+            .entry(name.to_string())
+            .or_default()
+            .insert(tag, version);
+
+        self
+    }
+}
+
+#[async_trait]
+impl PackageSource for FixtureSource {
+    async fn resolve(&self, _config: &VoltConfig, spec: &PackageSpec) -> Result<VoltResponse> {
+        self.index.resolve(spec, &self.describe())
+    }
+
+    async fn tarball(
+        &self,
+        _config: &VoltConfig,
+        package: &VoltPackage,
+        _state: &State,
+    ) -> Result<Tarball> {
+        let key = format!("{}@{}", package.name, package.version);
+
+        self.tarballs
+            .get(&key)
+            .cloned()
+            .map(Tarball::from)
+            .ok_or_else(|| {
+                ResolutionError::Unavailable {
+                    package: key,
+                    origin: self.describe(),
+                }
+                .into()
+            })
+    }
+
+    fn describe(&self) -> String {
+        "the fixtures".to_string()
+    }
+}
+
+/// The name and range a registry package is requested with (`*` when it isn't given).
+fn requested(spec: &PackageSpec) -> Result<(String, String)> {
+    match spec {
+        PackageSpec::Npm {
+            name, requested, ..
+        } => {
+            let range = requested
+                .as_ref()
+                .map_or_else(|| "*".to_string(), ToString::to_string);
+
+            Ok((name.clone(), range))
+        }
+        spec => miette::bail!("{} is not a registry package", spec),
+    }
+}
+
+/// The candidate with the highest version.
+fn highest<'a>(candidates: impl Iterator<Item = &'a Candidate>) -> Option<&'a Candidate> {
+    candidates.max_by(|a, b| a.version.cmp(&b.version))
+}
+
+/// Packages by name, with the ranges of their dependencies and their dist-tags.
+#[derive(Debug, Default, Clone)]
+struct Index {
+    packages: HashMap<String, Vec<Candidate>>,
+    dist_tags: HashMap<String, DistTags>,
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    version: Version,
+    package: VoltPackage,
+    dependencies: BTreeMap<String, String>,
+    optional_dependencies: BTreeMap<String, String>,
+}
+
+impl Index {
+    /// Add the package of a tarball, as if it came from the npm registry.
+    fn add(&mut self, package_json: &PackageJson, tarball: &[u8]) -> Result<VoltPackage> {
+        let version: Version = package_json.version.parse().map_err(|_| {
+            miette::miette!(
+                "{}@{} doesn't have a valid version",
+                package_json.name,
+                package_json.version
+            )
+        })?;
+
+        let mut package = VoltPackage::from_manifest(
+            package_json,
+            tarball_url(DEFAULT_REGISTRY, &package_json.name, &package_json.version),
+        );
+
+        package.integrity = hash(tarball, &[Algorithm::Sha512]).to_string();
+
+        self.packages
+            .entry(package.name.clone())
+            .or_default()
+            .push(Candidate {
+                version,
+                package: package.clone(),
+                dependencies: package_json.dependencies.clone().unwrap_or_default(),
+                optional_dependencies: package_json.opt_dependencies.clone().unwrap_or_default(),
+            });
+
+        Ok(package)
+    }
+
+    /// The highest version of `name` satisfying `range`, or the version the dist-tag
+    /// `range` points to; `latest` is the highest stable version unless it was tagged.
+    fn find(&self, name: &str, range: &str) -> Option<&Candidate> {
+        let candidates = self.packages.get(name)?.iter();
+
+        if let Ok(range) = range.parse::<Range>() {
+            return highest(candidates.filter(|candidate| candidate.version.satisfies(&range)));
+        }
+
+        let tag = range;
+
+        match self.dist_tags.get(name).and_then(|tags| tags.get(tag)) {
+            Some(version) => candidates
+                .into_iter()
+                .find(|candidate| candidate.package.version == *version),
+            None if tag == "latest" => {
+                highest(candidates.filter(|candidate| candidate.version.pre_release.is_empty()))
+            }
+            None => None,
+        }
+    }
+
+    /// The flattened tree of a registry package.
+    fn resolve(&self, spec: &PackageSpec, origin: &str) -> Result<VoltResponse> {
+        let (name, range) = requested(spec)?;
+
+        let root = match self.find(&name, &range) {
+            Some(root) => root,
+            None if range.parse::<Range>().is_err() => {
+                return Err(ResolutionError::UnknownDistTag { name, tag: range }.into());
+            }
+            None => {
+                return Err(ResolutionError::Unavailable {
+                    package: format!("{}@{}", name, range),
+                    origin: origin.to_string(),
+                }
+                .into());
+            }
+        };
+
+        let mut tree = HashMap::new();
+        let mut stack = vec![root];
+
+        while let Some(candidate) = stack.pop() {
+            let key = format!("{}@{}", candidate.package.name, candidate.package.version);
+
+            if tree.contains_key(&key) {
+                continue;
+            }
+
+            let mut package = candidate.package.clone();
+            let mut dependencies = HashMap::new();
+
+            for (dependency, range) in &candidate.dependencies {
+                let found =
+                    self.find(dependency, range)
+                        .ok_or_else(|| ResolutionError::Unavailable {
+                            package: format!("{}@{} (a dependency of {})", dependency, range, key),
+                            origin: origin.to_string(),
+                        })?;
+
+                dependencies.insert(dependency.clone(), found.package.version.clone());
+                stack.push(found);
+            }
+
+            // optional dependencies that aren't there are left out
+            for (dependency, range) in &candidate.optional_dependencies {
+                if let Some(found) = self.find(dependency, range) {
+                    dependencies.insert(dependency.clone(), found.package.version.clone());
+                    stack.push(found);
+                }
+            }
+
+            package.dependencies = Some(dependencies);
+            tree.insert(key, package);
+        }
+
+        Ok(VoltResponse {
+            name: name.clone(),
+            version: root.package.version.clone(),
+            versions: self.packages[&name]
+                .iter()
+                .map(|candidate| candidate.package.version.clone())
+                .collect(),
+            tree,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FixtureSource, OfflineSource, PackageSource, TarballDirectory};
+    use crate::{
+        config::VoltConfig,
+        io::pack_files,
+        model::lock_file::LockFile,
+        reporter::PlainReporter,
+        utils::{voltapi::VoltPackage, State},
+    };
+
+    use futures::executor::block_on;
+    use std::{path::PathBuf, sync::Arc};
+
+    fn tarball(manifest: serde_json::Value) -> Vec<u8> {
+        let directory = tempfile::tempdir().unwrap();
+
+        std::fs::write(directory.path().join("package.json"), manifest.to_string()).unwrap();
+
+        pack_files(directory.path(), &[PathBuf::from("package.json")]).unwrap()
+    }
+
+    fn fixtures() -> FixtureSource {
+        let mut source = FixtureSource::default();
+
+        source
+            .add(tarball(serde_json::json!({
+                "name": "a",
+                "version": "1.0.0",
+                "dependencies": { "ms": "^2.0.0" },
+            })))
+            .unwrap()
+            .add(tarball(
+                serde_json::json!({ "name": "ms", "version": "2.0.0" }),
+            ))
+            .unwrap()
+            .add(tarball(
+                serde_json::json!({ "name": "ms", "version": "2.1.3" }),
+            ))
+            .unwrap()
+            .add(tarball(
+                serde_json::json!({ "name": "ms", "version": "3.0.0" }),
+            ))
+            .unwrap();
+
+        source
+    }
+
+    #[test]
+    fn resolves_the_highest_satisfying_versions() {
+        let config = VoltConfig::default();
+
+        let response = block_on(fixtures().resolve(&config, &"a@^1".parse().unwrap())).unwrap();
+
+        assert_eq!(response.version, "1.0.0");
+        assert_eq!(response.tree.len(), 2);
+        assert_eq!(
+            response.tree["a@1.0.0"].dependencies.as_ref().unwrap()["ms"],
+            "2.1.3"
+        );
+        assert!(response.tree["a@1.0.0"].integrity.starts_with("sha512-"));
+        assert_eq!(
+            response.tree["ms@2.1.3"].tarball,
+            "https://registry.npmjs.org/ms/-/ms-2.1.3.tgz"
+        );
+
+        let latest = block_on(fixtures().resolve(&config, &"ms".parse().unwrap())).unwrap();
+
+        assert_eq!(latest.version, "3.0.0");
+        assert!(block_on(fixtures().resolve(&config, &"ms@^4".parse().unwrap())).is_err());
+    }
+
+    #[test]
+    fn resolves_dist_tags() {
+        let config = VoltConfig::default();
+        let mut source = fixtures();
+
+        source
+            .add(tarball(
+                serde_json::json!({ "name": "ms", "version": "4.0.0-beta.1" }),
+            ))
+            .unwrap()
+            .tag("ms", "beta", "4.0.0-beta.1");
+
+        let resolve = |spec: &str| block_on(source.resolve(&config, &spec.parse().unwrap()));
+
+        assert_eq!(resolve("ms@latest").unwrap().version, "3.0.0");
+        assert_eq!(resolve("ms@beta").unwrap().version, "4.0.0-beta.1");
+        assert!(resolve("ms@canary").is_err());
+
+        source.tag("ms", "latest", "2.1.3");
+
+        let resolve = |spec: &str| block_on(source.resolve(&config, &spec.parse().unwrap()));
+
+        assert_eq!(resolve("ms@latest").unwrap().version, "2.1.3");
+    }
+
+    #[test]
+    fn serves_tarballs_of_a_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let contents = tarball(serde_json::json!({ "name": "@scope/b", "version": "1.2.0" }));
+
+        std::fs::write(directory.path().join("@scope+b-1.2.0.tgz"), &contents).unwrap();
+        std::fs::write(directory.path().join("notes.txt"), "").unwrap();
+
+        let source = TarballDirectory::open(directory.path()).unwrap();
+        let config = VoltConfig::default();
+
+        let response = block_on(source.resolve(&config, &"@scope/b@1".parse().unwrap())).unwrap();
+        let package = &response.tree["@scope/b@1.2.0"];
+
+        assert_eq!(TarballDirectory::file_name(package), "@scope+b-1.2.0.tgz");
+
+        let state = State {
+            http_client: reqwest::Client::new(),
+            reporter: Arc::new(PlainReporter::default()),
+        };
+
+        let served = block_on(source.tarball(&config, package, &state)).unwrap();
+
+        assert_eq!(&served.to_bytes().unwrap()[..], &contents[..]);
+    }
+
+    #[test]
+    fn resolves_offline_from_the_lock_file() {
+        let fixtures = fixtures();
+        let config = VoltConfig::default();
+
+        let response = block_on(fixtures.resolve(&config, &"a@1".parse().unwrap())).unwrap();
+
+        let mut lock_file = LockFile::default();
+        lock_file.extend(&response.tree);
+
+        let offline = OfflineSource::new(lock_file);
+
+        let locked = block_on(offline.resolve(&config, &"a@^1.0.0".parse().unwrap())).unwrap();
+
+        assert_eq!(locked.version, "1.0.0");
+        assert_eq!(locked.tree.len(), 2);
+        assert!(block_on(offline.resolve(&config, &"ms@^3".parse().unwrap())).is_err());
+
+        let state = State {
+            http_client: reqwest::Client::new(),
+            reporter: Arc::new(PlainReporter::default()),
+        };
+
+        let package: &VoltPackage = &locked.tree["ms@2.1.3"];
+
+        assert!(block_on(offline.tarball(&config, package, &state)).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! What an install did and where its time went.
+//!
+//! The stages of an install record what they do in the [`InstallStats`] of the run
+//! ([`VoltConfig::stats`](crate::config::VoltConfig::stats)), and the install ends with
+//! an [`InstallSummary`] of them. Downloads and extractions overlap, so `fetch` lasts
+//! until the last download is done and `extract` is the rest of the pipeline after it.
+//!
+//! With `--profile <file>` the time of every package in every phase is also written to
+//! a file of folded stacks (`install;fetch;send@0.17.2 1532`, in microseconds), which
+//! `flamegraph.pl` and `inferno-flamegraph` draw.
+
+use crate::{install_state::InstallDiff, utils::errors::FilesystemError};
+
+use miette::Result;
+use serde::Serialize;
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// A phase of an install, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Resolve,
+    Fetch,
+    Extract,
+    Link,
+    Scripts,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phase = match self {
+            Self::Resolve => "resolve",
+            Self::Fetch => "fetch",
+            Self::Extract => "extract",
+            Self::Link => "link",
+            Self::Scripts => "scripts",
+        };
+
+        write!(f, "{}", phase)
+    }
+}
+
+/// What the installs of a run did so far, shared by the configurations cloned from its
+/// own.
+#[derive(Debug, Default)]
+pub struct InstallStats {
+    bytes: AtomicU64,
+    downloaded: AtomicUsize,
+    from_store: AtomicUsize,
+    phases: Mutex<BTreeMap<Phase, Duration>>,
+    /// Only sampled with `--profile`
+    profiling: AtomicBool,
+    samples: Mutex<BTreeMap<String, Duration>>,
+}
+
+impl InstallStats {
+    /// Received `bytes` more bytes of tarballs.
+    pub fn add_bytes(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Downloaded the tarball of a package.
+    pub fn downloaded(&self) {
+        self.downloaded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Copied a package out of the store instead of downloading it.
+    pub fn copied_from_store(&self) {
+        self.from_store.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Spent `elapsed` more in `phase`.
+    pub fn time(&self, phase: Phase, elapsed: Duration) {
+        if let Ok(mut phases) = self.phases.lock() {
+            *phases.entry(phase).or_default() += elapsed;
+        }
+
+        // the packages are sampled one by one in the others
+        if matches!(phase, Phase::Resolve | Phase::Link | Phase::Scripts) {
+            self.sample(&[&phase.to_string()], elapsed);
+        }
+    }
+
+    /// Record the time of every package from now on, for [`InstallStats::write_profile`].
+    pub fn start_profiling(&self) {
+        self.profiling.store(true, Ordering::Relaxed);
+    }
+
+    /// Spent `elapsed` on the frames of `stack` (`["fetch", "send@0.17.2"]`) when
+    /// profiling.
+    pub fn sample(&self, stack: &[&str], elapsed: Duration) {
+        if !self.profiling.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // frames are separated by `;` and the sample by a space
+        let stack = std::iter::once("install")
+            .chain(stack.iter().copied())
+            .map(|frame| frame.replace([';', ' '], "_"))
+            .collect::<Vec<_>>()
+            .join(";");
+
+        if let Ok(mut samples) = self.samples.lock() {
+            *samples.entry(stack).or_default() += elapsed;
+        }
+    }
+
+    /// Write the samples to `path` as folded stacks, in microseconds.
+    pub fn write_profile(&self, path: &Path) -> Result<()> {
+        let samples = self.samples.lock().map(|samples| samples.clone());
+
+        let folded: String = samples
+            .unwrap_or_default()
+            .iter()
+            .map(|(stack, elapsed)| format!("{} {}\n", stack, elapsed.as_micros()))
+            .collect();
+
+        std::fs::write(path, folded).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        Ok(())
+    }
+
+    /// What the install that made `diff` did, keeping `kept` packages where they were and
+    /// taking `elapsed` once resolved.
+    pub fn summary(&self, diff: &InstallDiff, kept: usize, elapsed: Duration) -> InstallSummary {
+        let downloaded = self.downloaded.load(Ordering::Relaxed);
+        let from_store = self.from_store.load(Ordering::Relaxed);
+
+        let fetched = downloaded + from_store;
+
+        let phases = self
+            .phases
+            .lock()
+            .map(|phases| phases.clone())
+            .unwrap_or_default();
+
+        let resolving = phases.get(&Phase::Resolve).copied().unwrap_or_default();
+
+        InstallSummary {
+            added: diff.added.len(),
+            removed: diff.removed.len(),
+            changed: diff.changed.len(),
+            downloaded,
+            from_store,
+            kept,
+            bytes: self.bytes.load(Ordering::Relaxed),
+            cache_hit_rate: (fetched > 0).then(|| from_store as f32 / fetched as f32),
+            phases: phases
+                .into_iter()
+                .map(|(phase, elapsed)| (phase, elapsed.as_secs_f32()))
+                .collect(),
+            elapsed: (resolving + elapsed).as_secs_f32(),
+        }
+    }
+}
+
+/// What an install did, in the `summary` event of the JSON output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallSummary {
+    /// Packages added, removed and installed in other versions in node_modules
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    /// Packages whose tarball was downloaded
+    pub downloaded: usize,
+    /// Packages copied out of the store
+    pub from_store: usize,
+    /// Packages left in place from the last install
+    pub kept: usize,
+    /// Bytes of tarballs received
+    pub bytes: u64,
+    /// Share of the packages written to node_modules that came from the store, none when
+    /// nothing was written
+    pub cache_hit_rate: Option<f32>,
+    /// Seconds spent in each phase
+    pub phases: BTreeMap<Phase, f32>,
+    /// Seconds, resolving included
+    pub elapsed: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstallStats, Phase};
+
+    use crate::install_state::InstallDiff;
+
+    use std::time::Duration;
+
+    #[test]
+    fn sums_up_an_install() {
+        let stats = InstallStats::default();
+
+        stats.start_profiling();
+
+        stats.downloaded();
+        stats.add_bytes(1024);
+        stats.copied_from_store();
+        stats.copied_from_store();
+        stats.copied_from_store();
+
+        stats.time(Phase::Link, Duration::from_millis(250));
+        stats.time(Phase::Fetch, Duration::from_millis(500));
+        stats.time(Phase::Fetch, Duration::from_millis(500));
+        stats.sample(&["fetch", "send@0.17.2"], Duration::from_micros(1532));
+
+        let diff = InstallDiff {
+            added: ["send".to_string()].into(),
+            ..InstallDiff::default()
+        };
+
+        let summary = stats.summary(&diff, 4, Duration::from_secs(2));
+
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.bytes, 1024);
+        assert_eq!(summary.kept, 4);
+        assert_eq!(summary.cache_hit_rate, Some(0.75));
+        assert_eq!(
+            summary.phases.into_iter().collect::<Vec<_>>(),
+            vec![(Phase::Fetch, 1.0), (Phase::Link, 0.25)]
+        );
+
+        let profile = tempfile::NamedTempFile::new().unwrap();
+
+        stats.write_profile(profile.path()).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(profile.path()).unwrap(),
+            "install;fetch;send@0.17.2 1532\ninstall;link 250000\n"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Tarballs on their way from a source to the store.
+//!
+//! Most tarballs are small and held in memory, where they are decompressed in one go.
+//! Those larger than the `tarball-memory-limit` setting (electron, playwright and other
+//! packages of hundreds of megabytes) stay in a file instead, and are hashed and
+//! decompressed in chunks as they are extracted, so an install never holds them whole.
+
+use crate::{
+    hashing::{verify_reader, Integrity},
+    utils::{decompress_gzip, errors::FilesystemError},
+};
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use miette::Result;
+use tempfile::TempPath;
+
+use std::{
+    fs::File,
+    io::{BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+};
+
+/// A gzipped tarball, in memory or in a file.
+#[derive(Debug)]
+pub enum Tarball {
+    Memory(Bytes),
+    File {
+        path: PathBuf,
+        /// Removes the file once the tarball is dropped, when it was only downloaded
+        temporary: Option<TempPath>,
+    },
+}
+
+impl Tarball {
+    /// The tarball at `path`, read from there when it's larger than `memory_limit` bytes.
+    pub fn read(path: &Path, memory_limit: u64) -> Result<Self> {
+        let read_error = |e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        };
+
+        let len = std::fs::metadata(path).map_err(read_error)?.len();
+
+        if len > memory_limit {
+            return Ok(Self::File {
+                path: path.to_path_buf(),
+                temporary: None,
+            });
+        }
+
+        Ok(Self::Memory(Bytes::from(
+            std::fs::read(path).map_err(read_error)?,
+        )))
+    }
+
+    /// The downloaded tarball at `path`, which is removed once the tarball is dropped.
+    pub fn temporary(path: TempPath) -> Self {
+        Self::File {
+            path: path.to_path_buf(),
+            temporary: Some(path),
+        }
+    }
+
+    /// Whether the tarball is held in memory.
+    pub fn in_memory(&self) -> bool {
+        matches!(self, Self::Memory(_))
+    }
+
+    /// The gzipped bytes, a chunk at a time.
+    pub fn reader(&self) -> Result<Box<dyn Read + Send + '_>> {
+        match self {
+            Self::Memory(bytes) => Ok(Box::new(&bytes[..])),
+            Self::File { path, .. } => Ok(Box::new(BufReader::new(open(path)?))),
+        }
+    }
+
+    /// The uncompressed tar archive: decompressed at once in memory, or streamed out of
+    /// the file.
+    pub fn unpacked(&self) -> Result<Box<dyn Read + Send + '_>> {
+        match self {
+            Self::Memory(bytes) => Ok(Box::new(Cursor::new(decompress_gzip(bytes)?))),
+            Self::File { .. } => Ok(Box::new(GzDecoder::new(self.reader()?))),
+        }
+    }
+
+    /// Check the tarball against `integrity`: `None` when it matches, and what it hashes
+    /// to otherwise.
+    pub fn verify(&self, integrity: &str) -> Result<Option<Integrity>> {
+        verify_reader(&mut self.reader()?, integrity)
+    }
+
+    /// The whole tarball in memory, read from its file if it has one.
+    pub fn to_bytes(&self) -> Result<Bytes> {
+        match self {
+            Self::Memory(bytes) => Ok(bytes.clone()),
+            Self::File { path, .. } => Ok(Bytes::from(std::fs::read(path).map_err(|e| {
+                FilesystemError::Read {
+                    source: e,
+                    path: path.display().to_string(),
+                }
+            })?)),
+        }
+    }
+
+    /// Write the tarball to `path`.
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let write_error = |e| FilesystemError::Write {
+            source: e,
+            path: path.display().to_string(),
+        };
+
+        match self {
+            Self::Memory(bytes) => std::fs::write(path, bytes).map_err(write_error)?,
+            Self::File { path: source, .. } => {
+                std::fs::copy(source, path).map_err(write_error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Bytes> for Tarball {
+    fn from(bytes: Bytes) -> Self {
+        Self::Memory(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Tarball {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Memory(Bytes::from(bytes))
+    }
+}
+
+fn open(path: &Path) -> Result<File> {
+    Ok(File::open(path).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: path.display().to_string(),
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tarball;
+    use crate::{
+        diff::read_tarball,
+        hashing::{hash, Algorithm},
+        io::pack_files,
+    };
+
+    use std::{io::Read, path::PathBuf};
+
+    #[test]
+    fn streams_tarballs_past_the_memory_limit() {
+        let directory = tempfile::tempdir().unwrap();
+
+        std::fs::write(directory.path().join("package.json"), r#"{"name":"a"}"#).unwrap();
+
+        let contents = pack_files(directory.path(), &[PathBuf::from("package.json")]).unwrap();
+        let path = directory.path().join("a-1.0.0.tgz");
+
+        std::fs::write(&path, &contents).unwrap();
+
+        let integrity = hash(&contents, &[Algorithm::Sha512]).to_string();
+
+        let small = Tarball::read(&path, contents.len() as u64).unwrap();
+        let large = Tarball::read(&path, contents.len() as u64 - 1).unwrap();
+
+        assert!(small.in_memory());
+        assert!(!large.in_memory());
+
+        for tarball in [small, large] {
+            assert_eq!(tarball.verify(&integrity).unwrap(), None);
+
+            let mut tar = vec![];
+            tarball.unpacked().unwrap().read_to_end(&mut tar).unwrap();
+
+            assert_eq!(
+                read_tarball(&tar).unwrap()["package.json"],
+                br#"{"name":"a"}"#
+            );
+        }
+    }
+}
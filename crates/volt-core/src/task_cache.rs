@@ -0,0 +1,639 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Cache the results of workspace scripts by the hash of what they read.
+//!
+//! Scripts are cached once they are described in the `volt` field of the package.json of
+//! their member, or of the workspace root for every member:
+//!
+//! ```json
+//! "volt": { "tasks": { "build": { "inputs": ["src/**", "tsconfig.json"], "outputs": ["dist/**"] } } }
+//! ```
+//!
+//! The hash of a script covers its text, its input files (every file of the member but
+//! `node_modules` and the outputs when there are no `inputs`), the files of the workspace
+//! members it depends on and volt.lock. A script whose hash is in `~/.volt/task-cache`
+//! isn't run: its outputs are restored and its output printed again instead.
+
+use crate::{
+    diff::read_tarball,
+    io::pack_files,
+    utils::{decompress_gzip, errors::FilesystemError, glob},
+    workspace::{Workspace, WorkspaceMember},
+};
+
+use miette::{IntoDiagnostic, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Version of the hash, changed whenever what goes into it does.
+const HASH_VERSION: &str = "1";
+
+/// The inputs and outputs of a cached script.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TaskConfig {
+    /// Files the script reads, relative to its member (every file if there are none)
+    pub inputs: Vec<String>,
+    /// Files the script writes, relative to its member, restored from the cache
+    pub outputs: Vec<String>,
+}
+
+impl TaskConfig {
+    /// The configuration of `script` in `member`, over the one of the workspace root, or
+    /// `None` if the script isn't cached.
+    pub fn find(workspace: &Workspace, member: &WorkspaceMember, script: &str) -> Option<Self> {
+        let task = |directory: &Path| {
+            let contents = fs::read_to_string(directory.join("package.json")).ok()?;
+            let package_json: Value = serde_json::from_str(&contents).ok()?;
+
+            serde_json::from_value(
+                package_json
+                    .pointer(&format!("/volt/tasks/{}", script))?
+                    .clone(),
+            )
+            .ok()
+        };
+
+        task(&member.path).or_else(|| task(&workspace.root))
+    }
+}
+
+/// Hashes the scripts of a workspace, remembering the hashes of the files of members.
+#[derive(Debug)]
+pub struct TaskHasher<'a> {
+    workspace: &'a Workspace,
+    script: &'a str,
+    lock_file: String,
+    files: Mutex<HashMap<String, String>>,
+}
+
+impl<'a> TaskHasher<'a> {
+    pub fn new(workspace: &'a Workspace, script: &'a str) -> Self {
+        let lock_file = fs::read(workspace.root.join("volt.lock"))
+            .map(|contents| hex::encode(Sha256::digest(&contents)))
+            .unwrap_or_default();
+
+        Self {
+            workspace,
+            script,
+            lock_file,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The script hashed.
+    pub fn script(&self) -> &'a str {
+        self.script
+    }
+
+    /// The hash of running `scripts` (the script with its `pre` and `post` scripts, as
+    /// they are run) in `member`.
+    pub fn hash(
+        &self,
+        member: &WorkspaceMember,
+        task: &TaskConfig,
+        scripts: &str,
+    ) -> Result<String> {
+        let mut hasher = Sha256::new();
+
+        for part in [
+            HASH_VERSION,
+            member.name(),
+            self.script,
+            scripts,
+            &self.lock_file,
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+
+        hasher.update(hash_files(&member.path, task)?.as_bytes());
+
+        for dependency in self.dependencies(member.name()) {
+            hasher.update(dependency.as_bytes());
+            hasher.update(self.member_files(dependency)?.as_bytes());
+        }
+
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// Every member `name` depends on, directly or not.
+    fn dependencies(&self, name: &str) -> BTreeSet<&'a str> {
+        let mut dependencies = BTreeSet::new();
+        let mut stack = self.workspace.local_dependencies(name);
+
+        while let Some(dependency) = stack.pop() {
+            if dependency != name && dependencies.insert(dependency) {
+                stack.extend(self.workspace.local_dependencies(dependency));
+            }
+        }
+
+        dependencies
+    }
+
+    /// The hash of the files of a member, without the outputs of the script.
+    fn member_files(&self, name: &str) -> Result<String> {
+        if let Some(hash) = self.files.lock().unwrap().get(name) {
+            return Ok(hash.clone());
+        }
+
+        let hash = match self.workspace.member(name) {
+            Some(member) => {
+                let task =
+                    TaskConfig::find(self.workspace, member, self.script).unwrap_or_default();
+
+                hash_files(
+                    &member.path,
+                    &TaskConfig {
+                        inputs: vec![],
+                        ..task
+                    },
+                )?
+            }
+            None => String::new(),
+        };
+
+        self.files
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), hash.clone());
+
+        Ok(hash)
+    }
+}
+
+/// The hash of the input files of a task in `directory`, by path and contents.
+fn hash_files(directory: &Path, task: &TaskConfig) -> Result<String> {
+    let mut hasher = Sha256::new();
+
+    for relative in walk(directory)? {
+        let selected = if task.inputs.is_empty() {
+            !selects(&task.outputs, &relative)
+        } else {
+            selects(&task.inputs, &relative)
+        };
+
+        if !selected {
+            continue;
+        }
+
+        let path = directory.join(&relative);
+        let contents = fs::read(&path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        hasher.update(relative.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&contents));
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Whether `hash` is a hash of [`TaskHasher::hash`], which are hex encoded sha256.
+pub fn is_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Whether any of `patterns` matches the file at `relative`, or a directory above it.
+fn selects(patterns: &[String], relative: &str) -> bool {
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+
+        glob::matches(pattern, relative) || glob::matches(&format!("{}/**", pattern), relative)
+    })
+}
+
+/// The files in `directory`, relative with `/` and sorted, outside of `node_modules` and
+/// `.git`.
+fn walk(directory: &Path) -> Result<Vec<String>> {
+    let mut files = vec![];
+    let mut stack = vec![directory.to_path_buf()];
+
+    while let Some(current) = stack.pop() {
+        let entries = fs::read_dir(&current).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: current.display().to_string(),
+        })?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+
+            if file_type.is_dir() {
+                if entry.file_name() != "node_modules" && entry.file_name() != ".git" {
+                    stack.push(path);
+                }
+            } else if file_type.is_file() {
+                if let Ok(relative) = path.strip_prefix(directory) {
+                    files.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// `output` as a path relative to the member, or `None` if it is empty or has a `..`,
+/// `.`, root or prefix component, like the paths of package tarballs.
+fn output_path(output: &str) -> Option<&Path> {
+    let path = Path::new(output);
+
+    let normal = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+
+    Some(path).filter(|path| normal && !path.as_os_str().is_empty())
+}
+
+/// What is kept of a script run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    name: String,
+    script: String,
+    /// The outputs kept, relative to the member
+    outputs: Vec<String>,
+}
+
+/// The results of scripts, by hash (`~/.volt/task-cache`).
+#[derive(Debug, Clone)]
+pub struct TaskCache {
+    dir: PathBuf,
+}
+
+impl TaskCache {
+    pub fn new(volt_home: &Path) -> Self {
+        Self {
+            dir: volt_home.join("task-cache"),
+        }
+    }
+
+    fn entry_dir(&self, hash: &str) -> PathBuf {
+        self.dir.join(hash)
+    }
+
+    /// Whether the run with `hash` is cached.
+    pub fn contains(&self, hash: &str) -> bool {
+        self.entry_dir(hash).join("entry.json").is_file()
+    }
+
+    /// The hashes of every cached run.
+    pub fn hashes(&self) -> Result<Vec<String>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(vec![]),
+        };
+
+        let mut hashes: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|hash| is_hash(hash) && self.contains(hash))
+            .collect();
+
+        hashes.sort();
+
+        Ok(hashes)
+    }
+
+    /// The cached run with `hash` as a gzipped tarball, to share it.
+    pub fn pack(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        if !self.contains(hash) {
+            return Ok(None);
+        }
+
+        let entry_dir = self.entry_dir(hash);
+
+        let files: Vec<PathBuf> = walk(&entry_dir)?.into_iter().map(PathBuf::from).collect();
+
+        pack_files(&entry_dir, &files).map(Some)
+    }
+
+    /// Add a run shared as a gzipped tarball by [`TaskCache::pack`].
+    pub fn unpack(&self, hash: &str, tarball: &[u8]) -> Result<()> {
+        if !is_hash(hash) {
+            miette::bail!("{} is not the hash of a task", hash);
+        }
+
+        if self.contains(hash) {
+            return Ok(());
+        }
+
+        let files = read_tarball(&decompress_gzip(tarball)?)?;
+
+        if !files.contains_key("entry.json") || !files.contains_key("output.log") {
+            miette::bail!("the task cache entry {} is incomplete", hash);
+        }
+
+        // checked before anything is written, `restore` would copy the outputs wherever
+        // they point
+        let entry: Entry = serde_json::from_slice(&files["entry.json"]).into_diagnostic()?;
+
+        if let Some(output) = entry
+            .outputs
+            .iter()
+            .find(|output| output_path(output).is_none())
+        {
+            miette::bail!(
+                "the task cache entry {} has an output outside of its member: {}",
+                hash,
+                output
+            );
+        }
+
+        let staging = self.dir.join(format!(".{}-{}", hash, std::process::id()));
+
+        for (relative, contents) in files {
+            let path = staging.join(relative);
+
+            let write_error = |e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            };
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(write_error)?;
+            }
+
+            fs::write(&path, contents).map_err(write_error)?;
+        }
+
+        if fs::rename(&staging, self.entry_dir(hash)).is_err() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+
+        Ok(())
+    }
+
+    /// Put the outputs of the run with `hash` back into `directory` and return its output,
+    /// or `None` if it isn't cached.
+    pub fn restore(&self, hash: &str, directory: &Path) -> Result<Option<String>> {
+        let entry_dir = self.entry_dir(hash);
+
+        let entry: Entry = match fs::read(entry_dir.join("entry.json")) {
+            Ok(contents) => match serde_json::from_slice(&contents) {
+                Ok(entry) => entry,
+                Err(_) => return Ok(None),
+            },
+            Err(_) => return Ok(None),
+        };
+
+        let output = match fs::read_to_string(entry_dir.join("output.log")) {
+            Ok(output) => output,
+            Err(_) => return Ok(None),
+        };
+
+        for output in &entry.outputs {
+            let relative = match output_path(output) {
+                Some(relative) => relative,
+                None => miette::bail!(
+                    "the task cache entry {} has an output outside of its member: {}",
+                    hash,
+                    output
+                ),
+            };
+
+            let (from, to) = (
+                entry_dir.join("outputs").join(relative),
+                directory.join(relative),
+            );
+
+            let write_error = |e| FilesystemError::Write {
+                source: e,
+                path: to.display().to_string(),
+            };
+
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent).map_err(write_error)?;
+            }
+
+            fs::copy(&from, &to).map_err(write_error)?;
+        }
+
+        Ok(Some(output))
+    }
+
+    /// Keep the output of a successful run with `hash` and the files of `directory`
+    /// matching the outputs of `task`.
+    pub fn save(
+        &self,
+        hash: &str,
+        member: &WorkspaceMember,
+        script: &str,
+        task: &TaskConfig,
+        output: &str,
+    ) -> Result<()> {
+        let entry_dir = self.entry_dir(hash);
+
+        if entry_dir.exists() {
+            return Ok(());
+        }
+
+        // written beside the entry, then moved in place, so that a partial entry is never read
+        let staging = self.dir.join(format!(".{}-{}", hash, std::process::id()));
+
+        let write_error = |path: &Path| {
+            let path = path.display().to_string();
+
+            move |e| FilesystemError::Write { source: e, path }
+        };
+
+        let outputs: Vec<String> = walk(&member.path)?
+            .into_iter()
+            .filter(|relative| selects(&task.outputs, relative))
+            .collect();
+
+        fs::create_dir_all(&staging).map_err(write_error(&staging))?;
+
+        for relative in &outputs {
+            let to = staging.join("outputs").join(relative);
+
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent).map_err(write_error(parent))?;
+            }
+
+            fs::copy(member.path.join(relative), &to).map_err(write_error(&to))?;
+        }
+
+        let entry = Entry {
+            name: member.name().to_string(),
+            script: script.to_string(),
+            outputs,
+        };
+
+        let path = staging.join("entry.json");
+        fs::write(&path, serde_json::to_vec(&entry).into_diagnostic()?)
+            .map_err(write_error(&path))?;
+
+        let path = staging.join("output.log");
+        fs::write(&path, output).map_err(write_error(&path))?;
+
+        if fs::rename(&staging, &entry_dir).is_err() {
+            // another run saved the same entry first
+            let _ = fs::remove_dir_all(&staging);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{output_path, TaskCache, TaskConfig, TaskHasher};
+    use crate::{io::pack_files, workspace::Workspace};
+
+    use std::fs;
+
+    #[test]
+    fn caches_task_outputs() {
+        let root = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+
+        fs::write(
+            root.path().join("package.json"),
+            r#"{ "name": "root", "workspaces": ["packages/*"], "volt": { "tasks": { "build": { "outputs": ["dist"] } } } }"#,
+        )
+        .unwrap();
+
+        for (name, dependencies) in [("core", "{}"), ("app", r#"{ "core": "*" }"#)] {
+            let directory = root.path().join("packages").join(name);
+            fs::create_dir_all(directory.join("src")).unwrap();
+            fs::write(
+                directory.join("package.json"),
+                format!(
+                    r#"{{ "name": "{}", "version": "1.0.0", "dependencies": {} }}"#,
+                    name, dependencies
+                ),
+            )
+            .unwrap();
+            fs::write(directory.join("src/index.js"), name).unwrap();
+        }
+
+        let workspace = Workspace::discover(root.path()).unwrap().unwrap();
+        let (core, app) = (
+            workspace.member("core").unwrap(),
+            workspace.member("app").unwrap(),
+        );
+
+        let task = TaskConfig::find(&workspace, app, "build").unwrap();
+        assert_eq!(task.outputs, ["dist"]);
+        assert!(TaskConfig::find(&workspace, app, "test").is_none());
+
+        let hash = || {
+            TaskHasher::new(&workspace, "build")
+                .hash(app, &task, "tsc")
+                .unwrap()
+        };
+        let before = hash();
+
+        // outputs don't change the hash, inputs of the member and of its dependencies do
+        fs::create_dir_all(app.path.join("dist")).unwrap();
+        fs::write(app.path.join("dist/index.js"), "built").unwrap();
+        assert_eq!(hash(), before);
+
+        fs::write(core.path.join("src/index.js"), "changed").unwrap();
+        assert_ne!(hash(), before);
+
+        let cache = TaskCache::new(home.path());
+        let hash = hash();
+
+        assert_eq!(cache.restore(&hash, &app.path).unwrap(), None);
+        cache.save(&hash, app, "build", &task, "built\n").unwrap();
+
+        fs::remove_dir_all(app.path.join("dist")).unwrap();
+
+        assert_eq!(
+            cache.restore(&hash, &app.path).unwrap().as_deref(),
+            Some("built\n")
+        );
+        assert_eq!(
+            fs::read_to_string(app.path.join("dist/index.js")).unwrap(),
+            "built"
+        );
+
+        // entries are shared as tarballs
+        let other = tempfile::tempdir().unwrap();
+        let shared = TaskCache::new(other.path());
+
+        shared
+            .unpack(&hash, &cache.pack(&hash).unwrap().unwrap())
+            .unwrap();
+
+        assert_eq!(shared.hashes().unwrap(), [hash.as_str()]);
+        assert_eq!(
+            shared.restore(&hash, &app.path).unwrap().as_deref(),
+            Some("built\n")
+        );
+    }
+
+    #[test]
+    fn rejects_outputs_outside_the_member() {
+        assert!(output_path("dist/index.js").is_some());
+
+        for output in [
+            "",
+            "../escape",
+            "dist/../../escape",
+            "./dist",
+            "/etc/passwd",
+        ] {
+            assert!(output_path(output).is_none(), "{}", output);
+        }
+
+        let crafted = tempfile::tempdir().unwrap();
+        fs::write(
+            crafted.path().join("entry.json"),
+            r#"{ "name": "app", "script": "tsc", "outputs": ["../escape"] }"#,
+        )
+        .unwrap();
+        fs::write(crafted.path().join("output.log"), "").unwrap();
+        fs::create_dir_all(crafted.path().join("outputs")).unwrap();
+        fs::write(crafted.path().join("outputs/escape"), "escaped").unwrap();
+
+        let tarball = pack_files(
+            crafted.path(),
+            &[
+                "entry.json".into(),
+                "output.log".into(),
+                "outputs/escape".into(),
+            ],
+        )
+        .unwrap();
+
+        let home = tempfile::tempdir().unwrap();
+        let cache = TaskCache::new(home.path());
+        let hash = "0".repeat(64);
+
+        assert!(cache.unpack(&hash, &tarball).is_err());
+        assert!(!cache.contains(&hash));
+        assert!(cache.hashes().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Run tasks across the members of a workspace, each after the members it depends on.
+//!
+//! The members form a graph in which a member waits for every selected member it depends
+//! on, directly or through members that aren't selected. Members whose dependencies are
+//! done run at once, up to a limit, and members waiting for one that failed are skipped
+//! while the rest of the graph carries on.
+
+use crate::workspace::{Workspace, WorkspaceMember};
+
+use miette::{IntoDiagnostic, Result};
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::mpsc,
+};
+
+/// Members by name, with the names of the members each waits for.
+pub type TaskGraph = BTreeMap<String, BTreeSet<String>>;
+
+/// How the task of a member ended.
+#[derive(Debug)]
+pub enum TaskOutcome {
+    Succeeded,
+    Failed(miette::Report),
+    /// Not run because a member it waits for failed
+    Skipped,
+}
+
+/// The graph of the selected `members` of `workspace`.
+pub fn task_graph(workspace: &Workspace, members: &[&WorkspaceMember]) -> TaskGraph {
+    let selected: HashSet<&str> = members.iter().map(|member| member.name()).collect();
+
+    members
+        .iter()
+        .map(|member| {
+            let mut waits_for = BTreeSet::new();
+            let mut visited = HashSet::new();
+            let mut stack = workspace.local_dependencies(member.name());
+
+            while let Some(name) = stack.pop() {
+                if !visited.insert(name) {
+                    continue;
+                }
+
+                if selected.contains(name) {
+                    waits_for.insert(name.to_string());
+                } else {
+                    stack.extend(workspace.local_dependencies(name));
+                }
+            }
+
+            waits_for.remove(member.name());
+
+            (member.name().to_string(), waits_for)
+        })
+        .collect()
+}
+
+/// Run `task` for every member of `graph` once the members it waits for have succeeded,
+/// at most `concurrency` at a time, and return how each ended.
+///
+/// Members are started in name order among those that are ready. When members wait for
+/// each other in a cycle, the first of them is started anyway.
+pub fn run_graph<F>(
+    graph: &TaskGraph,
+    concurrency: usize,
+    task: F,
+) -> Result<BTreeMap<String, TaskOutcome>>
+where
+    F: Fn(&str) -> Result<()> + Sync,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(concurrency.max(1))
+        .build()
+        .into_diagnostic()?;
+
+    let mut pending: BTreeMap<&str, BTreeSet<&str>> = graph
+        .iter()
+        .map(|(name, waits_for)| {
+            (
+                name.as_str(),
+                waits_for
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|dependency| graph.contains_key(*dependency))
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let mut outcomes = BTreeMap::new();
+
+    pool.in_place_scope(|scope| {
+        let (sender, receiver) = mpsc::channel::<(&str, Result<()>)>();
+        let task = &task;
+        let mut running = 0;
+
+        loop {
+            let mut ready: Vec<&str> = pending
+                .iter()
+                .filter(|(_, waits_for)| waits_for.is_empty())
+                .map(|(name, _)| *name)
+                .collect();
+
+            if ready.is_empty() && running == 0 {
+                match pending.keys().next() {
+                    Some(name) => {
+                        tracing::warn!(
+                            "workspace members depend on each other in a cycle, running {} first",
+                            name
+                        );
+
+                        ready.push(*name);
+                    }
+                    None => break,
+                }
+            }
+
+            for name in ready.into_iter().take(concurrency.max(1) - running) {
+                pending.remove(name);
+                running += 1;
+
+                let sender = sender.clone();
+
+                scope.spawn(move |_| {
+                    // the receiver outlives every task
+                    let _ = sender.send((name, task(name)));
+                });
+            }
+
+            let (name, result) = match receiver.recv() {
+                Ok(finished) => finished,
+                Err(_) => break,
+            };
+
+            running -= 1;
+
+            match result {
+                Ok(()) => {
+                    for waits_for in pending.values_mut() {
+                        waits_for.remove(name);
+                    }
+
+                    outcomes.insert(name.to_string(), TaskOutcome::Succeeded);
+                }
+                Err(error) => {
+                    outcomes.insert(name.to_string(), TaskOutcome::Failed(error));
+
+                    skip_dependents(name, &mut pending, &mut outcomes);
+                }
+            }
+        }
+    });
+
+    Ok(outcomes)
+}
+
+/// Take every member waiting for `failed`, directly or not, out of `pending` as skipped.
+fn skip_dependents<'a>(
+    failed: &str,
+    pending: &mut BTreeMap<&'a str, BTreeSet<&'a str>>,
+    outcomes: &mut BTreeMap<String, TaskOutcome>,
+) {
+    let mut stack = vec![failed.to_string()];
+
+    while let Some(name) = stack.pop() {
+        let dependents: Vec<&str> = pending
+            .iter()
+            .filter(|(_, waits_for)| waits_for.contains(name.as_str()))
+            .map(|(dependent, _)| *dependent)
+            .collect();
+
+        for dependent in dependents {
+            pending.remove(dependent);
+            outcomes.insert(dependent.to_string(), TaskOutcome::Skipped);
+            stack.push(dependent.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_graph, TaskGraph, TaskOutcome};
+
+    use std::sync::Mutex;
+
+    #[test]
+    fn runs_graph_in_order() {
+        let graph: TaskGraph = [
+            ("app", vec!["ui", "api"]),
+            ("api", vec!["core"]),
+            ("core", vec![]),
+            ("docs", vec![]),
+            ("ui", vec!["core"]),
+        ]
+        .iter()
+        .map(|(name, waits_for)| {
+            (
+                name.to_string(),
+                waits_for.iter().map(|name| name.to_string()).collect(),
+            )
+        })
+        .collect();
+
+        let order = Mutex::new(vec![]);
+
+        let outcomes = run_graph(&graph, 2, |name| {
+            order.lock().unwrap().push(name.to_string());
+
+            if name == "api" {
+                miette::bail!("api failed");
+            }
+
+            Ok(())
+        })
+        .unwrap();
+
+        let order = order.into_inner().unwrap();
+        let position = |name: &str| order.iter().position(|ran| ran == name).unwrap();
+
+        assert!(position("core") < position("ui"));
+        assert!(position("core") < position("api"));
+        assert!(!order.contains(&"app".to_string()));
+
+        assert!(matches!(outcomes["core"], TaskOutcome::Succeeded));
+        assert!(matches!(outcomes["docs"], TaskOutcome::Succeeded));
+        assert!(matches!(outcomes["api"], TaskOutcome::Failed(_)));
+        assert!(matches!(outcomes["app"], TaskOutcome::Skipped));
+    }
+}
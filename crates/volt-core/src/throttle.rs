@@ -0,0 +1,118 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Limit the bandwidth of the downloads of a run (the `network-limit` setting).
+//!
+//! Every download takes the bytes it received out of the same bucket, which fills at the
+//! limit and holds up to a second of it; a download taking more than there is waits
+//! until the bucket has made up for it, so the downloads together stay at the limit
+//! whatever their number.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The bucket shared by the downloads of a run.
+#[derive(Debug)]
+pub struct Throttle {
+    /// Bytes per second
+    rate: u64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Bytes that can be received without waiting, below zero once downloads are ahead
+    available: f64,
+    filled: Instant,
+}
+
+impl Throttle {
+    /// A throttle letting `rate` bytes through per second.
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate,
+            bucket: Mutex::new(Bucket {
+                available: rate as f64,
+                filled: Instant::now(),
+            }),
+        }
+    }
+
+    /// Bytes per second let through.
+    pub fn rate(&self) -> u64 {
+        self.rate
+    }
+
+    /// Wait until `bytes` more bytes can be received.
+    pub async fn take(&self, bytes: u64) {
+        let wait = self.reserve(bytes, Instant::now());
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take `bytes` out of the bucket at `now`, returning how long to wait before
+    /// receiving them.
+    fn reserve(&self, bytes: u64, now: Instant) -> Duration {
+        let rate = self.rate as f64;
+
+        let mut bucket = match self.bucket.lock() {
+            Ok(bucket) => bucket,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let elapsed = now.saturating_duration_since(bucket.filled).as_secs_f64();
+
+        bucket.available = (bucket.available + elapsed * rate).min(rate) - bytes as f64;
+        bucket.filled = now;
+
+        if bucket.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.available / rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Throttle;
+
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn waits_once_the_limit_is_reached() {
+        let throttle = Throttle::new(1000);
+        let now = Instant::now();
+
+        // a second of bytes goes through at once
+        assert_eq!(throttle.reserve(600, now), Duration::ZERO);
+        assert_eq!(throttle.reserve(400, now), Duration::ZERO);
+
+        // then every download waits for its share
+        assert_eq!(throttle.reserve(500, now), Duration::from_millis(500));
+        assert_eq!(throttle.reserve(500, now), Duration::from_secs(1));
+
+        // the bucket fills again, but not above a second
+        let later = now + Duration::from_secs(10);
+
+        assert_eq!(throttle.reserve(1000, later), Duration::ZERO);
+        assert_eq!(throttle.reserve(100, later), Duration::from_millis(100));
+    }
+}
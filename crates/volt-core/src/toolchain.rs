@@ -0,0 +1,861 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Node versions installed from the official builds into `~/.volt/node/<version>`, and
+//! the `node`, `npm` and `npx` shims in `~/.volt/bin` that run the selected one.
+//!
+//! The shims are links to the volt executable itself: started under one of their names,
+//! volt finds the version to run and runs it in its place (see [`run_shim`]), so
+//! switching versions never rewrites anything on `PATH`.
+//!
+//! Projects pin the versions they run with in the `volt` field of their package.json
+//! (or the `volta` field, for projects set up with volta):
+//!
+//! ```json
+//! "volt": { "node": "18.17.0", "npm": "9.8.1" }
+//! ```
+//!
+//! Inside the project the shims run the pinned versions, and the default one elsewhere.
+
+use crate::{
+    config::VoltConfig,
+    install::{install, resolve},
+    mirror::{mark_unavailable, node_mirrors},
+    platform::Platform,
+    utils::{
+        decompress_gzip,
+        errors::{FilesystemError, IntegrityError, NetworkError, VoltError},
+    },
+};
+
+use miette::{IntoDiagnostic, Result};
+use node_semver::{Range, Version};
+use package_spec::PackageSpec;
+use reqwest::Client;
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+
+use std::{
+    ffi::OsString,
+    future::Future,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Where the official node builds are downloaded from, after any `node-mirror`.
+pub const NODE_MIRROR: &str = "https://nodejs.org/dist";
+
+/// The commands shimmed in `~/.volt/bin`.
+pub const SHIMS: [&str; 3] = ["node", "npm", "npx"];
+
+/// A node version published on the mirror, from its `index.json`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct NodeRelease {
+    pub version: Version,
+    /// The codename of LTS releases (`Hydrogen`)
+    #[serde(deserialize_with = "deserialize_lts")]
+    pub lts: Option<String>,
+}
+
+/// `lts` is `false` for releases outside of an LTS line, and the codename otherwise.
+fn deserialize_lts<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(serde_json::Value::deserialize(deserializer)?
+        .as_str()
+        .map(String::from))
+}
+
+/// The versions a project pins, from the `volt` (or `volta`) field of its package.json.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Pin {
+    pub node: Option<String>,
+    pub npm: Option<String>,
+}
+
+impl Pin {
+    /// The pins of the nearest package.json at or above `directory` that has any, the
+    /// `volt` field taking precedence over the `volta` one.
+    pub fn find(directory: &Path) -> Option<Self> {
+        directory.ancestors().find_map(|directory| {
+            let contents = std::fs::read_to_string(directory.join("package.json")).ok()?;
+            let package_json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+
+            let field = |name| {
+                package_json
+                    .get(name)
+                    .and_then(|pin| serde_json::from_value::<Self>(pin.clone()).ok())
+                    .unwrap_or_default()
+            };
+
+            let (volt, volta) = (field("volt"), field("volta"));
+
+            Some(Self {
+                node: volt.node.or(volta.node),
+                npm: volt.npm.or(volta.npm),
+            })
+            .filter(|pin| pin.node.is_some() || pin.npm.is_some())
+        })
+    }
+}
+
+/// The node versions installed in a volt home, and the shims running them.
+#[derive(Debug, Clone)]
+pub struct Toolchain {
+    home: PathBuf,
+}
+
+impl Toolchain {
+    pub fn new(volt_home: &Path) -> Self {
+        Self {
+            home: volt_home.to_path_buf(),
+        }
+    }
+
+    pub fn from_config(config: &VoltConfig) -> Result<Self> {
+        Ok(Self::new(&config.volt_home()?))
+    }
+
+    /// The toolchain of the user running volt, for shims started without a `VoltConfig`.
+    pub fn current() -> Result<Self> {
+        Ok(Self::new(&VoltConfig::default().volt_home()?))
+    }
+
+    /// Directory holding every installed version (`~/.volt/node`)
+    pub fn versions_dir(&self) -> PathBuf {
+        self.home.join("node")
+    }
+
+    /// Directory of the shims, to put on `PATH` (`~/.volt/bin`)
+    pub fn bin_dir(&self) -> PathBuf {
+        self.home.join("bin")
+    }
+
+    pub fn version_dir(&self, version: &Version) -> PathBuf {
+        self.versions_dir().join(version.to_string())
+    }
+
+    /// The installed versions, newest first.
+    pub fn installed(&self) -> Vec<Version> {
+        let mut versions: Vec<Version> = std::fs::read_dir(self.versions_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect();
+
+        versions.sort_by(|a, b| b.cmp(a));
+
+        versions
+    }
+
+    /// Directory of the node headers native addons are built against
+    /// (`~/.volt/node-headers`)
+    pub fn headers_dir(&self) -> PathBuf {
+        self.home.join("node-headers")
+    }
+
+    /// The headers of a version, laid out like the node source tree node-gyp's `nodedir`
+    /// expects: `include/node`, and `Release/node.lib` on windows.
+    pub fn version_headers_dir(&self, version: &Version) -> PathBuf {
+        self.headers_dir().join(version.to_string())
+    }
+
+    pub fn is_installed(&self, version: &Version) -> bool {
+        self.version_dir(version).is_dir()
+    }
+
+    /// Directory of the npm versions projects pin (`~/.volt/npm`)
+    pub fn npm_dir(&self) -> PathBuf {
+        self.home.join("npm")
+    }
+
+    pub fn npm_version_dir(&self, version: &Version) -> PathBuf {
+        self.npm_dir().join(version.to_string())
+    }
+
+    /// The script of `tool` (`npm`, `npx`) in an installed npm version.
+    pub fn npm_script(&self, version: &Version, tool: &str) -> PathBuf {
+        self.npm_version_dir(version)
+            .join("node_modules")
+            .join("npm")
+            .join("bin")
+            .join(format!("{}-cli.js", tool))
+    }
+
+    /// The newest installed node version matching `request`, a version or a range.
+    pub fn select_installed(&self, request: &str) -> Option<Version> {
+        newest_matching(self.installed(), request)
+    }
+
+    /// The newest installed npm version matching `request`, a version or a range.
+    pub fn select_installed_npm(&self, request: &str) -> Option<Version> {
+        let versions = std::fs::read_dir(self.npm_dir())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+            .collect();
+
+        newest_matching(versions, request)
+    }
+
+    /// The node version the shims run under `pin`: the newest installed one matching it,
+    /// or else the default.
+    pub fn node_version(&self, pin: &Pin, tool: &str) -> Result<Version> {
+        let version = match &pin.node {
+            Some(request) => self.select_installed(request).ok_or_else(|| {
+                VoltError::NodeVersionNotInstalled {
+                    version: request.clone(),
+                }
+            })?,
+            None => self
+                .default_version()
+                .ok_or_else(|| VoltError::NoNodeVersion {
+                    tool: tool.to_string(),
+                })?,
+        };
+
+        if !self.is_installed(&version) {
+            return Err(VoltError::NodeVersionNotInstalled {
+                version: version.to_string(),
+            }
+            .into());
+        }
+
+        Ok(version)
+    }
+
+    /// The version the shims run outside of pinned projects, set by `volt node use`.
+    pub fn default_version(&self) -> Option<Version> {
+        std::fs::read_to_string(self.versions_dir().join("default"))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    pub fn set_default(&self, version: Option<&Version>) -> Result<()> {
+        let path = self.versions_dir().join("default");
+
+        let result = match version {
+            Some(version) => std::fs::write(&path, version.to_string()),
+            None if path.exists() => std::fs::remove_file(&path),
+            None => Ok(()),
+        };
+
+        result.map_err(|e| {
+            FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// Path to `tool` (`node`, `npm`, `npx`) in an installed version.
+    pub fn executable(&self, version: &Version, tool: &str) -> PathBuf {
+        let directory = self.version_dir(version);
+
+        if cfg!(windows) {
+            match tool {
+                "node" => directory.join("node.exe"),
+                _ => directory.join(format!("{}.cmd", tool)),
+            }
+        } else {
+            directory.join("bin").join(tool)
+        }
+    }
+
+    /// Link the `node`, `npm` and `npx` shims in [`Toolchain::bin_dir`] to the running volt.
+    pub fn link_shims(&self) -> Result<()> {
+        let bin_dir = self.bin_dir();
+
+        std::fs::create_dir_all(&bin_dir).map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: bin_dir.display().to_string(),
+        })?;
+
+        let volt = std::env::current_exe().map_err(|e| VoltError::EnvironmentError {
+            env: "CURRENT_EXE".to_string(),
+            source: e,
+        })?;
+
+        for tool in SHIMS {
+            let shim = bin_dir.join(format!("{}{}", tool, std::env::consts::EXE_SUFFIX));
+
+            if shim.symlink_metadata().is_ok() {
+                std::fs::remove_file(&shim).map_err(|e| FilesystemError::Remove {
+                    source: e,
+                    path: shim.display().to_string(),
+                })?;
+            }
+
+            // windows runs a copy, as links to executables need extra privileges there
+            #[cfg(unix)]
+            let result = std::os::unix::fs::symlink(&volt, &shim);
+
+            #[cfg(windows)]
+            let result = std::fs::hard_link(&volt, &shim)
+                .or_else(|_| std::fs::copy(&volt, &shim).map(|_| ()));
+
+            result.map_err(|e| FilesystemError::Write {
+                source: e,
+                path: shim.display().to_string(),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove an installed version, and forget it as the default.
+    pub fn remove(&self, version: &Version) -> Result<()> {
+        let directory = self.version_dir(version);
+
+        std::fs::remove_dir_all(&directory).map_err(|e| FilesystemError::Remove {
+            source: e,
+            path: directory.display().to_string(),
+        })?;
+
+        if self.default_version().as_ref() == Some(version) {
+            self.set_default(None)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn newest_matching(versions: Vec<Version>, request: &str) -> Option<Version> {
+    let request = request.trim().trim_start_matches('v');
+
+    if let Ok(version) = request.parse::<Version>() {
+        return versions.into_iter().find(|installed| *installed == version);
+    }
+
+    let range = request.parse::<Range>().ok()?;
+
+    versions
+        .into_iter()
+        .filter(|version| version.satisfies(&range))
+        .max()
+}
+
+/// Install the newest npm matching `request` for projects pinning it, into its own prefix
+/// of [`Toolchain::npm_dir`]. Returns its version, and `false` when it was already there.
+pub async fn install_npm(
+    config: &VoltConfig,
+    toolchain: &Toolchain,
+    request: &str,
+) -> Result<(Version, bool)> {
+    let spec: PackageSpec = format!("npm@{}", request).parse().into_diagnostic()?;
+
+    let resolution = resolve(config, &[spec]).await?;
+
+    let version: Version = resolution
+        .direct_packages()
+        .next()
+        .and_then(|package| package.version.parse().ok())
+        .ok_or_else(|| VoltError::NodeVersionNotFound {
+            version: format!("npm@{}", request),
+        })?;
+
+    let prefix = toolchain.npm_version_dir(&version);
+
+    if toolchain.npm_script(&version, "npm").exists() {
+        return Ok((version, false));
+    }
+
+    std::fs::create_dir_all(&prefix).map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: prefix.display().to_string(),
+    })?;
+
+    install(&config.with_cwd(prefix), resolution).await?;
+
+    Ok((version, true))
+}
+
+/// Run `fetch` with the base url of each node mirror in turn (the `node-mirror` ones,
+/// then nodejs.org), until one succeeds.
+async fn from_mirrors<T, F, Fut>(config: &VoltConfig, fetch: F) -> Result<T>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mirrors = node_mirrors(config.settings(), NODE_MIRROR);
+
+    for (index, mirror) in mirrors.iter().enumerate() {
+        match fetch(mirror.clone()).await {
+            Ok(value) => return Ok(value),
+            // the last mirror's error is the one reported
+            Err(error) if index + 1 == mirrors.len() => return Err(error),
+            Err(error) => {
+                tracing::warn!(
+                    "failed to download from {}, trying the next mirror: {}",
+                    mirror,
+                    error
+                );
+
+                mark_unavailable(mirror);
+            }
+        }
+    }
+
+    Err(miette::miette!("no mirror to download node from"))
+}
+
+/// Every node version published on the mirror, newest first.
+pub async fn releases(config: &VoltConfig) -> Result<Vec<NodeRelease>> {
+    let client = config.http_client()?;
+
+    let mut releases = from_mirrors(config, |mirror| fetch_releases(&client, mirror)).await?;
+
+    releases.sort_by(|a, b| b.version.cmp(&a.version));
+
+    Ok(releases)
+}
+
+async fn fetch_releases(client: &Client, mirror: String) -> Result<Vec<NodeRelease>> {
+    let url = format!("{}/index.json", mirror);
+
+    let request_error = |source| NetworkError::Request {
+        url: url.clone(),
+        source,
+    };
+
+    let response = client.get(&url).send().await.map_err(request_error)?;
+
+    if !response.status().is_success() {
+        return Err(NetworkError::Status {
+            url,
+            status: response.status().to_string(),
+        }
+        .into());
+    }
+
+    Ok(response.json().await.map_err(request_error)?)
+}
+
+/// The newest release matching `request`: `lts`, `latest`, an LTS codename (`hydrogen`,
+/// `lts/hydrogen`), a version (`18.17.0`, `v18.17.0`) or a range (`18`, `^16.14`).
+pub fn select_release<'a>(releases: &'a [NodeRelease], request: &str) -> Option<&'a NodeRelease> {
+    let request = request.trim();
+    let lowercase = request.to_lowercase();
+    let codename = lowercase.strip_prefix("lts/").unwrap_or(&lowercase);
+
+    let newest = |matches: &dyn Fn(&NodeRelease) -> bool| {
+        releases
+            .iter()
+            .filter(|release| matches(release))
+            .max_by(|a, b| a.version.cmp(&b.version))
+    };
+
+    match codename {
+        "lts" | "*" => return newest(&|release| release.lts.is_some()),
+        "latest" | "current" | "node" => return newest(&|_| true),
+        _ => {}
+    }
+
+    if let Some(release) = newest(&|release| {
+        release
+            .lts
+            .as_ref()
+            .map_or(false, |lts| lts.to_lowercase() == codename)
+    }) {
+        return Some(release);
+    }
+
+    let request = request.trim_start_matches('v');
+
+    if let Ok(version) = request.parse::<Version>() {
+        return newest(&|release| release.version == version);
+    }
+
+    let range = request.parse::<Range>().ok()?;
+
+    newest(&|release| release.version.satisfies(&range))
+}
+
+/// The name of the official build for this machine (`node-v18.17.0-linux-x64`).
+fn distribution(version: &Version) -> String {
+    let platform = Platform::current();
+
+    let os = match platform.os {
+        "win32" => "win",
+        os => os,
+    };
+
+    let cpu = match platform.cpu {
+        "ia32" => "x86",
+        "arm" => "armv7l",
+        "ppc64" => "ppc64le",
+        cpu => cpu,
+    };
+
+    format!("node-v{}-{}-{}", version, os, cpu)
+}
+
+/// Download `release` into the toolchain, checking it against the `SHASUMS256.txt` the
+/// mirror publishes. Returns `false` when the version was already installed.
+pub async fn install_node(
+    config: &VoltConfig,
+    toolchain: &Toolchain,
+    release: &NodeRelease,
+) -> Result<bool> {
+    let version = &release.version;
+
+    if toolchain.is_installed(version) {
+        return Ok(false);
+    }
+
+    let distribution = distribution(version);
+
+    // windows builds are zip files, the executable alone is published next to them
+    let file = if cfg!(windows) {
+        format!(
+            "{}/node.exe",
+            distribution.trim_start_matches(&format!("node-v{}-", version))
+        )
+    } else {
+        format!("{}.tar.xz", distribution)
+    };
+
+    let client = config.http_client()?;
+
+    let archive = from_mirrors(config, |mirror| {
+        download_node(&client, format!("{}/v{}", mirror, version), &file)
+    })
+    .await?;
+
+    let versions_dir = toolchain.versions_dir();
+
+    std::fs::create_dir_all(&versions_dir).map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: versions_dir.display().to_string(),
+    })?;
+
+    // extracted aside, so an interrupted install never leaves a partial version behind
+    let staging = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(&versions_dir)
+        .map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: versions_dir.display().to_string(),
+        })?;
+
+    let extracted = extract(&archive, staging.path(), &distribution)?;
+
+    let target = toolchain.version_dir(version);
+
+    std::fs::rename(&extracted, &target).map_err(|e| FilesystemError::Write {
+        source: e,
+        path: target.display().to_string(),
+    })?;
+
+    Ok(true)
+}
+
+/// Download the headers of a node version into the toolchain, unless they already are
+/// there, and return their directory. Every project building native addons for that
+/// version shares them.
+pub async fn install_headers(
+    config: &VoltConfig,
+    toolchain: &Toolchain,
+    version: &Version,
+) -> Result<PathBuf> {
+    let target = toolchain.version_headers_dir(version);
+
+    if target.join("include").join("node").is_dir() {
+        return Ok(target);
+    }
+
+    let client = config.http_client()?;
+
+    let file = format!("node-v{}-headers.tar.gz", version);
+
+    let archive = from_mirrors(config, |mirror| {
+        download_node(&client, format!("{}/v{}", mirror, version), &file)
+    })
+    .await?;
+
+    let headers_dir = toolchain.headers_dir();
+
+    std::fs::create_dir_all(&headers_dir).map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: headers_dir.display().to_string(),
+    })?;
+
+    // extracted aside, so that builds never find half of the headers
+    let staging = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(&headers_dir)
+        .map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: headers_dir.display().to_string(),
+        })?;
+
+    tar::Archive::new(std::io::Cursor::new(decompress_gzip(&archive)?))
+        .unpack(staging.path())
+        .map_err(|e| FilesystemError::Write {
+            source: e,
+            path: staging.path().display().to_string(),
+        })?;
+
+    let extracted = staging.path().join(format!("node-v{}", version));
+
+    // addons link against node.lib on windows
+    if cfg!(windows) {
+        let cpu = match Platform::current().cpu {
+            "ia32" => "x86",
+            cpu => cpu,
+        };
+
+        let file = format!("win-{}/node.lib", cpu);
+
+        let library = from_mirrors(config, |mirror| {
+            download_node(&client, format!("{}/v{}", mirror, version), &file)
+        })
+        .await?;
+
+        let release = extracted.join("Release");
+
+        std::fs::create_dir_all(&release)
+            .and_then(|_| std::fs::write(release.join("node.lib"), library))
+            .map_err(|e| FilesystemError::Write {
+                source: e,
+                path: release.display().to_string(),
+            })?;
+    }
+
+    std::fs::rename(&extracted, &target).map_err(|e| FilesystemError::Write {
+        source: e,
+        path: target.display().to_string(),
+    })?;
+
+    Ok(target)
+}
+
+/// Download `file` of a release from `base`, verified against the release's checksums.
+async fn download_node(client: &Client, base: String, file: &str) -> Result<bytes::Bytes> {
+    let checksums = get(client, &format!("{}/SHASUMS256.txt", base)).await?;
+
+    let expected = String::from_utf8_lossy(&checksums)
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, name)| name.trim() == file)
+        .map(|(checksum, _)| checksum.to_string())
+        .ok_or_else(|| VoltError::NodeVersionNotFound {
+            version: format!("{} ({})", base, file),
+        })?;
+
+    let url = format!("{}/{}", base, file);
+
+    let archive = get(client, &url).await?;
+
+    let actual = hex::encode(Sha256::digest(&archive));
+
+    if actual != expected {
+        return Err(IntegrityError::Checksum {
+            package: format!("node {}", file),
+            tarball: url,
+            expected,
+            actual,
+        }
+        .into());
+    }
+
+    Ok(archive)
+}
+
+#[cfg(unix)]
+fn extract(archive: &[u8], staging: &Path, distribution: &str) -> Result<PathBuf> {
+    let tarball = lzma::decompress(archive).map_err(|e| FilesystemError::Read {
+        source: std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()),
+        path: format!("{}.tar.xz", distribution),
+    })?;
+
+    tar::Archive::new(std::io::Cursor::new(tarball))
+        .unpack(staging)
+        .map_err(|e| FilesystemError::Write {
+            source: e,
+            path: staging.display().to_string(),
+        })?;
+
+    Ok(staging.join(distribution))
+}
+
+#[cfg(windows)]
+fn extract(archive: &[u8], staging: &Path, _distribution: &str) -> Result<PathBuf> {
+    let directory = staging.join("node");
+
+    std::fs::create_dir_all(&directory)
+        .and_then(|_| std::fs::write(directory.join("node.exe"), archive))
+        .map_err(|e| FilesystemError::Write {
+            source: e,
+            path: directory.display().to_string(),
+        })?;
+
+    Ok(directory)
+}
+
+async fn get(client: &Client, url: &str) -> Result<bytes::Bytes> {
+    let request_error = |source| NetworkError::Request {
+        url: url.to_string(),
+        source,
+    };
+
+    let response = client.get(url).send().await.map_err(request_error)?;
+
+    if !response.status().is_success() {
+        return Err(NetworkError::Status {
+            url: url.to_string(),
+            status: response.status().to_string(),
+        }
+        .into());
+    }
+
+    Ok(response.bytes().await.map_err(request_error)?)
+}
+
+/// The shim volt was started as (`node`, `npm`, `npx`), if any.
+pub fn shim_name() -> Option<String> {
+    let program = PathBuf::from(std::env::args_os().next()?);
+    let name = program.file_stem()?.to_str()?;
+
+    SHIMS.contains(&name).then(|| name.to_string())
+}
+
+/// Run `tool` of the node version pinned by the project volt runs in, or else the
+/// default one, with the arguments volt was started with. Returns its exit code.
+pub fn run_shim(tool: &str) -> Result<i32> {
+    let toolchain = Toolchain::current()?;
+
+    let cwd = std::env::current_dir().map_err(|e| VoltError::EnvironmentError {
+        env: "CURRENT_DIRECTORY".to_string(),
+        source: e,
+    })?;
+
+    let pin = Pin::find(&cwd).unwrap_or_default();
+
+    let version = toolchain.node_version(&pin, tool)?;
+
+    let node = toolchain.executable(&version, "node");
+
+    // a pinned npm runs its scripts with the selected node
+    let (executable, script) = match (tool, &pin.npm) {
+        ("npm" | "npx", Some(request)) => {
+            let npm = toolchain.select_installed_npm(request).ok_or_else(|| {
+                VoltError::NpmVersionNotInstalled {
+                    version: request.clone(),
+                }
+            })?;
+
+            (node.clone(), Some(toolchain.npm_script(&npm, tool)))
+        }
+        _ => (toolchain.executable(&version, tool), None),
+    };
+
+    // scripts run by `npm` find the same `node` first
+    let mut path = OsString::from(node.parent().unwrap_or_else(|| Path::new(".")).as_os_str());
+
+    if let Some(current) = std::env::var_os("PATH") {
+        path.push(if cfg!(windows) { ";" } else { ":" });
+        path.push(current);
+    }
+
+    let mut command = Command::new(&executable);
+    command
+        .args(script)
+        .args(std::env::args_os().skip(1))
+        .env("PATH", path);
+
+    let spawn_error = |e| FilesystemError::Read {
+        source: e,
+        path: executable.display().to_string(),
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        // only returns when the executable couldn't be started
+        Err(spawn_error(command.exec()).into())
+    }
+
+    #[cfg(windows)]
+    {
+        let status = command.status().map_err(spawn_error)?;
+
+        Ok(status.code().unwrap_or(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_release, NodeRelease, Pin};
+
+    fn release(version: &str, lts: Option<&str>) -> NodeRelease {
+        NodeRelease {
+            version: version.parse().unwrap(),
+            lts: lts.map(String::from),
+        }
+    }
+
+    #[test]
+    fn selects_the_newest_matching_release() {
+        let releases = vec![
+            release("20.5.0", None),
+            release("18.17.0", Some("Hydrogen")),
+            release("18.16.1", Some("Hydrogen")),
+            release("16.20.1", Some("Gallium")),
+        ];
+
+        let select = |request| select_release(&releases, request).map(|r| r.version.to_string());
+
+        assert_eq!(select("lts").as_deref(), Some("18.17.0"));
+        assert_eq!(select("latest").as_deref(), Some("20.5.0"));
+        assert_eq!(select("lts/gallium").as_deref(), Some("16.20.1"));
+        assert_eq!(select("v18.16.1").as_deref(), Some("18.16.1"));
+        assert_eq!(select("18").as_deref(), Some("18.17.0"));
+        assert_eq!(select("19"), None);
+    }
+
+    #[test]
+    fn finds_the_nearest_project_pins() {
+        let project = tempfile::tempdir().unwrap();
+        let nested = project.path().join("packages").join("app");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        std::fs::write(
+            project.path().join("package.json"),
+            r#"{ "volt": { "node": "18.17.0" }, "volta": { "node": "16", "npm": "9.8.1" } }"#,
+        )
+        .unwrap();
+
+        // a package.json without pins doesn't hide the ones above it
+        std::fs::write(nested.join("package.json"), r#"{ "name": "app" }"#).unwrap();
+
+        assert_eq!(
+            Pin::find(&nested),
+            Some(Pin {
+                node: Some("18.17.0".to_string()),
+                npm: Some("9.8.1".to_string()),
+            })
+        );
+    }
+}
@@ -0,0 +1,416 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Crash-safe changes to `node_modules`.
+//!
+//! Packages are extracted into `node_modules/.volt-staging`, synced to disk, and only then
+//! renamed into place, so a package directory either holds the whole package or what the
+//! previous install left. A journal in the staging directory records each package as it
+//! begins moving into place and commits: when an install is killed, the next one finds the
+//! staging directory still there and removes the packages that began without committing,
+//! as they may be half moved. Directories are removed by renaming them into the staging directory
+//! first.
+//!
+//! A package replaces what was at its place whole: the old directory is renamed aside,
+//! the staged one renamed into place, and only then is the old one deleted, so no file of
+//! the previous version is left next to the new ones. The packages nested in the
+//! `node_modules` of a hoisted package are carried over to the new directory. Commits only
+//! rename, and run one at a time so that a nested package is never committed into a
+//! directory being swapped.
+
+use crate::utils::errors::FilesystemError;
+
+use miette::Result;
+
+use std::{
+    collections::BTreeSet,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+const STAGING: &str = ".volt-staging";
+const JOURNAL: &str = "journal";
+
+/// The changes of one install to a `node_modules` directory.
+#[derive(Debug)]
+pub struct Transaction {
+    staging: PathBuf,
+    journal: Mutex<File>,
+    next: AtomicUsize,
+    /// Held while a package is swapped into place
+    swapping: Mutex<()>,
+}
+
+impl Transaction {
+    /// Start changing `node_modules`, first rolling back what an interrupted install left.
+    pub fn begin(node_modules: &Path) -> Result<Self> {
+        let rolled_back = recover(node_modules)?;
+
+        if !rolled_back.is_empty() {
+            tracing::warn!(
+                "removed {} packages an interrupted install left incomplete",
+                rolled_back.len()
+            );
+        }
+
+        let staging = node_modules.join(STAGING);
+
+        std::fs::create_dir_all(&staging).map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: staging.display().to_string(),
+        })?;
+
+        let path = staging.join(JOURNAL);
+
+        let journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+
+        Ok(Self {
+            staging,
+            journal: Mutex::new(journal),
+            next: AtomicUsize::new(0),
+            swapping: Mutex::new(()),
+        })
+    }
+
+    /// A new empty directory to extract a package into. Nothing is recorded until it is
+    /// committed, so its target is left alone if the install stops first.
+    pub fn stage(&self) -> Result<PathBuf> {
+        let staged = self.temporary();
+
+        std::fs::create_dir_all(&staged).map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: staged.display().to_string(),
+        })?;
+
+        Ok(staged)
+    }
+
+    /// Sync a staged package to disk and swap it with what was at `target`.
+    pub fn commit(&self, staged: &Path, target: &Path) -> Result<()> {
+        sync_tree(staged)?;
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| FilesystemError::CreateDir {
+                source: e,
+                path: parent.display().to_string(),
+            })?;
+        }
+
+        let old = {
+            let _swapping = self.swapping.lock().unwrap();
+
+            // only now is `target` about to change
+            self.record("begin", target)?;
+
+            self.swap(staged, target)?
+        };
+
+        if let Some(parent) = target.parent() {
+            sync_directory(parent);
+        }
+
+        if let Some(old) = old {
+            std::fs::remove_dir_all(&old).map_err(|e| FilesystemError::Remove {
+                source: e,
+                path: old.display().to_string(),
+            })?;
+        }
+
+        self.record("commit", target)
+    }
+
+    /// Rename what is at `target` aside, keeping the packages nested in it, and `staged`
+    /// into place. Returns where the old directory went.
+    fn swap(&self, staged: &Path, target: &Path) -> Result<Option<PathBuf>> {
+        let old = match target.symlink_metadata() {
+            Ok(metadata) if metadata.is_dir() => {
+                let old = self.temporary();
+
+                rename(target, &old)?;
+                keep_nested(&old.join("node_modules"), &staged.join("node_modules"))?;
+
+                Some(old)
+            }
+            // a link or file in the way
+            Ok(_) => {
+                std::fs::remove_file(target)
+                    .or_else(|_| std::fs::remove_dir(target))
+                    .map_err(|e| FilesystemError::Remove {
+                        source: e,
+                        path: target.display().to_string(),
+                    })?;
+
+                None
+            }
+            Err(_) => None,
+        };
+
+        rename(staged, target)?;
+
+        Ok(old)
+    }
+
+    /// Remove a directory or link, atomically for directories.
+    pub fn remove(&self, path: &Path) -> Result<()> {
+        let metadata = match path.symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(()),
+        };
+
+        let result = if metadata.is_dir() {
+            let trash = self.temporary();
+
+            std::fs::rename(path, &trash).and_then(|_| std::fs::remove_dir_all(&trash))
+        } else {
+            std::fs::remove_file(path).or_else(|_| std::fs::remove_dir(path))
+        };
+
+        result.map_err(|e| {
+            FilesystemError::Remove {
+                source: e,
+                path: path.display().to_string(),
+            }
+            .into()
+        })
+    }
+
+    /// Every change is in place: drop the staging directory and its journal.
+    pub fn finish(self) -> Result<()> {
+        drop(self.journal);
+
+        std::fs::remove_dir_all(&self.staging).map_err(|e| {
+            FilesystemError::Remove {
+                source: e,
+                path: self.staging.display().to_string(),
+            }
+            .into()
+        })
+    }
+
+    fn temporary(&self) -> PathBuf {
+        self.staging
+            .join(self.next.fetch_add(1, Ordering::SeqCst).to_string())
+    }
+
+    fn record(&self, action: &str, target: &Path) -> Result<()> {
+        let mut journal = self.journal.lock().unwrap();
+
+        // synced before anything is moved, so a rollback knows every package it has to
+        let line = format!("{}\t{}\n", action, target.display());
+
+        journal
+            .write_all(line.as_bytes())
+            .and_then(|_| journal.sync_data())
+            .map_err(|e| {
+                FilesystemError::Write {
+                    source: e,
+                    path: self.staging.join(JOURNAL).display().to_string(),
+                }
+                .into()
+            })
+    }
+}
+
+/// Roll back what an interrupted install left in `node_modules`: remove the packages that
+/// began moving into place without committing, and the staging directory. Returns the
+/// removed packages.
+pub fn recover(node_modules: &Path) -> Result<Vec<PathBuf>> {
+    let staging = node_modules.join(STAGING);
+
+    if !staging.exists() {
+        return Ok(vec![]);
+    }
+
+    let journal = std::fs::read_to_string(staging.join(JOURNAL)).unwrap_or_default();
+
+    let mut incomplete = BTreeSet::new();
+
+    for line in journal.lines() {
+        match line.split_once('\t') {
+            Some(("begin", target)) => {
+                incomplete.insert(PathBuf::from(target));
+            }
+            Some(("commit", target)) => {
+                incomplete.remove(Path::new(target));
+            }
+            _ => {}
+        }
+    }
+
+    let mut removed = vec![];
+
+    for target in incomplete {
+        if target.exists() {
+            std::fs::remove_dir_all(&target).map_err(|e| FilesystemError::Remove {
+                source: e,
+                path: target.display().to_string(),
+            })?;
+
+            removed.push(target);
+        }
+    }
+
+    std::fs::remove_dir_all(&staging).map_err(|e| FilesystemError::Remove {
+        source: e,
+        path: staging.display().to_string(),
+    })?;
+
+    Ok(removed)
+}
+
+/// Move the packages of the `node_modules` of an old package directory into the one of
+/// the package replacing it, leaving those the new package bundles.
+fn keep_nested(old: &Path, new: &Path) -> Result<()> {
+    if !old.symlink_metadata().map_or(false, |m| m.is_dir()) {
+        return Ok(());
+    }
+
+    if !new.exists() {
+        return rename(old, new);
+    }
+
+    let entries = std::fs::read_dir(old).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: old.display().to_string(),
+    })?;
+
+    for entry in entries.flatten() {
+        let to = new.join(entry.file_name());
+
+        if to.symlink_metadata().is_err() {
+            rename(&entry.path(), &to)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn rename(from: &Path, to: &Path) -> Result<()> {
+    std::fs::rename(from, to).map_err(|e| {
+        FilesystemError::Write {
+            source: e,
+            path: to.display().to_string(),
+        }
+        .into()
+    })
+}
+
+/// Flush every file under `directory` to disk.
+fn sync_tree(directory: &Path) -> Result<()> {
+    let entries = std::fs::read_dir(directory).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: directory.display().to_string(),
+    })?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            sync_tree(&path)?;
+        } else {
+            File::open(&path)
+                .and_then(|file| file.sync_all())
+                .map_err(|e| FilesystemError::Write {
+                    source: e,
+                    path: path.display().to_string(),
+                })?;
+        }
+    }
+
+    sync_directory(directory);
+
+    Ok(())
+}
+
+/// Flush the entries of a directory; not every platform can open directories, so this is
+/// best effort.
+fn sync_directory(directory: &Path) {
+    if let Ok(directory) = File::open(directory) {
+        let _ = directory.sync_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{recover, Transaction};
+
+    #[test]
+    fn rolls_back_uncommitted_packages() {
+        let node_modules = tempfile::tempdir().unwrap();
+        let committed = node_modules.path().join("a");
+        let interrupted = node_modules.path().join("b");
+
+        let transaction = Transaction::begin(node_modules.path()).unwrap();
+
+        let staged = transaction.stage().unwrap();
+        std::fs::write(staged.join("index.js"), "").unwrap();
+        transaction.commit(&staged, &committed).unwrap();
+
+        // killed while moving `b` into place
+        transaction.record("begin", &interrupted).unwrap();
+        std::fs::create_dir_all(&interrupted).unwrap();
+
+        // and while extracting `c`, whose previous version is still whole
+        let extracting = node_modules.path().join("c");
+        std::fs::create_dir_all(&extracting).unwrap();
+        std::fs::write(extracting.join("index.js"), "").unwrap();
+        transaction.stage().unwrap();
+
+        std::mem::forget(transaction);
+
+        assert_eq!(
+            recover(node_modules.path()).unwrap(),
+            vec![interrupted.clone()]
+        );
+        assert!(committed.join("index.js").exists());
+        assert!(!interrupted.exists());
+        assert!(extracting.join("index.js").exists());
+        assert!(!node_modules.path().join(".volt-staging").exists());
+    }
+
+    #[test]
+    fn replaces_the_previous_version() {
+        let node_modules = tempfile::tempdir().unwrap();
+        let target = node_modules.path().join("a");
+
+        // the previous version, with a package nested in it by the hoisted layout
+        std::fs::create_dir_all(target.join("node_modules").join("b")).unwrap();
+        std::fs::write(target.join("removed.js"), "").unwrap();
+
+        let transaction = Transaction::begin(node_modules.path()).unwrap();
+
+        let staged = transaction.stage().unwrap();
+        std::fs::write(staged.join("index.js"), "").unwrap();
+        transaction.commit(&staged, &target).unwrap();
+        transaction.finish().unwrap();
+
+        assert!(target.join("index.js").exists());
+        assert!(!target.join("removed.js").exists());
+        assert!(target.join("node_modules").join("b").exists());
+    }
+}
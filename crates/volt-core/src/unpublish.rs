@@ -0,0 +1,269 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Unpublish a package, or one of its versions.
+//!
+//! A version is removed by sending the package document without it, and without the
+//! dist-tags pointing to it (`latest` moves to the highest version left), to
+//! `PUT /<name>/-rev/<rev>`; its tarball is then deleted with
+//! `DELETE /<name>/-/<file>.tgz/-rev/<rev>` at the revision the change made. A whole
+//! package, or its last version, is deleted with `DELETE /<name>/-rev/<rev>`, which
+//! has to be forced.
+//!
+//! The public registry only lets versions be unpublished for 72 hours after they were
+//! published, and older ones when nothing depends on them, so unpublishing older versions
+//! there warns that the registry may refuse.
+
+use crate::{
+    mirror::is_public,
+    registry::RegistryClient,
+    release_age::parse_timestamp,
+    reporter::Reporter,
+    utils::errors::{ResolutionError, VoltError},
+};
+
+use miette::Result;
+use node_semver::Version;
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use std::time::{Duration, SystemTime};
+
+/// How long after it was published the public registry lets a version be unpublished
+/// freely.
+pub const UNPUBLISH_WINDOW: Duration = Duration::from_secs(72 * 60 * 60);
+
+/// What was unpublished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unpublished {
+    /// The whole package, with every version
+    Package,
+    /// One version
+    Version(String),
+}
+
+/// Unpublish `version` of `name`, or the whole package without one; unpublishing the
+/// whole package (or its last version) needs `force`.
+pub async fn unpublish(
+    client: &RegistryClient,
+    name: &str,
+    version: Option<&str>,
+    force: bool,
+    reporter: &dyn Reporter,
+) -> Result<Unpublished> {
+    let mut packument = client.packument(name).await?;
+
+    let published: Vec<String> = packument["versions"]
+        .as_object()
+        .map(|versions| versions.keys().cloned().collect())
+        .unwrap_or_default();
+
+    if let Some(version) = version {
+        if !published.iter().any(|published| published == version) {
+            return Err(ResolutionError::NoMatchingVersion {
+                name: name.to_string(),
+                requested: version.to_string(),
+            }
+            .into());
+        }
+    }
+
+    let version = version.filter(|_| published.len() > 1);
+
+    if version.is_none() && !force {
+        return Err(VoltError::WholePackageUnpublish {
+            name: name.to_string(),
+        }
+        .into());
+    }
+
+    if is_public(&client.url) {
+        let removed = match version {
+            Some(version) => vec![version.to_string()],
+            None => published.clone(),
+        };
+
+        let old = published_before(&packument, &removed, SystemTime::now() - UNPUBLISH_WINDOW);
+
+        if !old.is_empty() {
+            let (verb, pronoun) = if old.len() == 1 {
+                ("was", "it")
+            } else {
+                ("were", "them")
+            };
+
+            reporter.warning(&format!(
+                "{} {} published more than 72 hours ago: the registry refuses to unpublish {} if other packages depend on {}",
+                old.join(", "),
+                verb,
+                pronoun,
+                pronoun
+            ));
+        }
+    }
+
+    let revision = packument["_rev"].as_str().unwrap_or_default().to_string();
+
+    let version = match version {
+        Some(version) => version,
+        None => {
+            client
+                .send_mutation(reporter, |client| {
+                    client.authenticated(
+                        Method::DELETE,
+                        &format!("{}/-rev/{}", RegistryClient::package_path(name), revision),
+                    )
+                })
+                .await?;
+
+            return Ok(Unpublished::Package);
+        }
+    };
+
+    remove_version(&mut packument, version);
+
+    client
+        .send_mutation(reporter, |client| {
+            Ok(client
+                .authenticated(
+                    Method::PUT,
+                    &format!("{}/-rev/{}", RegistryClient::package_path(name), revision),
+                )?
+                .json(&packument))
+        })
+        .await?;
+
+    // the tarball goes at the revision the change made
+    let revision = client.packument(name).await?["_rev"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let unscoped = name.rsplit('/').next().unwrap_or(name);
+
+    client
+        .send_mutation(reporter, |client| {
+            client.authenticated(
+                Method::DELETE,
+                &format!("{}/-/{}-{}.tgz/-rev/{}", name, unscoped, version, revision),
+            )
+        })
+        .await?;
+
+    Ok(Unpublished::Version(version.to_string()))
+}
+
+/// Take `version` out of `packument`, with the dist-tags pointing to it; `latest` moves
+/// to the highest version left.
+fn remove_version(packument: &mut Value, version: &str) {
+    if let Some(versions) = packument["versions"].as_object_mut() {
+        versions.remove(version);
+    }
+
+    let highest = packument["versions"].as_object().and_then(|versions| {
+        versions
+            .keys()
+            .filter_map(|version| version.parse::<Version>().ok())
+            .max()
+    });
+
+    if let Some(dist_tags) = packument["dist-tags"].as_object_mut() {
+        let latest = dist_tags.get("latest").and_then(Value::as_str) == Some(version);
+
+        dist_tags.retain(|_, tagged| tagged.as_str() != Some(version));
+
+        if let (true, Some(highest)) = (latest, highest) {
+            dist_tags.insert("latest".to_string(), json!(highest.to_string()));
+        }
+    }
+
+    if let Some(document) = packument.as_object_mut() {
+        document.remove("_attachments");
+    }
+}
+
+/// The `versions` of `packument` published before `cutoff`.
+fn published_before(packument: &Value, versions: &[String], cutoff: SystemTime) -> Vec<String> {
+    let mut old: Vec<String> = versions
+        .iter()
+        .filter(|version| {
+            packument["time"][version.as_str()]
+                .as_str()
+                .and_then(parse_timestamp)
+                .map_or(false, |published| published < cutoff)
+        })
+        .cloned()
+        .collect();
+
+    old.sort_by_key(|version| version.parse::<Version>().ok());
+    old
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{published_before, remove_version};
+
+    use serde_json::json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn removes_the_version_and_its_dist_tags() {
+        let mut packument = json!({
+            "_id": "a",
+            "_rev": "5-abc",
+            "dist-tags": { "latest": "2.0.0", "next": "2.0.0", "legacy": "1.0.0" },
+            "versions": {
+                "1.0.0": {},
+                "1.5.0": {},
+                "2.0.0": {},
+            },
+        });
+
+        remove_version(&mut packument, "2.0.0");
+
+        assert_eq!(
+            packument["dist-tags"],
+            json!({ "latest": "1.5.0", "legacy": "1.0.0" })
+        );
+        assert!(packument["versions"].get("2.0.0").is_none());
+        assert_eq!(packument["_rev"], "5-abc");
+    }
+
+    #[test]
+    fn finds_versions_published_before_the_window() {
+        let packument = json!({
+            "time": {
+                "1.0.0": "1970-01-01T00:00:00.000Z",
+                "2.0.0": "2021-05-01T12:34:56.789Z",
+            },
+        });
+
+        let versions = [
+            "1.0.0".to_string(),
+            "2.0.0".to_string(),
+            "3.0.0".to_string(),
+        ];
+
+        assert_eq!(
+            published_before(
+                &packument,
+                &versions,
+                UNIX_EPOCH + Duration::from_secs(86_400)
+            ),
+            ["1.0.0"]
+        );
+    }
+}
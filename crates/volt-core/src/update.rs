@@ -0,0 +1,282 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Find the dependencies of a project that have newer versions, and move package.json to
+//! them.
+//!
+//! Like npm, `wanted` is the highest version the range in package.json allows and
+//! `latest` the version the `latest` tag points to.
+
+use crate::{
+    config::VoltConfig,
+    model::lock_file::LockFile,
+    registry::RegistryClient,
+    utils::{
+        errors::FilesystemError,
+        package::{NewRepository, NpmPackage, PackageJson},
+    },
+    view::resolve_version,
+};
+
+use futures::{stream, StreamExt};
+use miette::{IntoDiagnostic, Result};
+use node_semver::Version;
+use package_spec::PackageSpec;
+use serde::Serialize;
+
+use std::{collections::BTreeMap, path::Path};
+
+/// A dependency with a newer version than the locked one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outdated {
+    pub name: String,
+    /// The range in package.json
+    pub range: String,
+    /// The locked version, if it is locked
+    pub current: Option<String>,
+    pub wanted: String,
+    pub latest: String,
+    /// Where the changes between versions are described
+    pub changelog: Option<String>,
+}
+
+impl Outdated {
+    /// The version to move to: `latest` or `wanted`.
+    pub fn target(&self, latest: bool) -> &str {
+        if latest {
+            &self.latest
+        } else {
+            &self.wanted
+        }
+    }
+}
+
+/// The registry dependencies of the project in package.json (`names` of them, if any are
+/// given) whose wanted or latest version is newer than the locked one.
+pub async fn outdated_dependencies(config: &VoltConfig, names: &[String]) -> Result<Vec<Outdated>> {
+    let (package_json, _) = PackageJson::get_from_dir(&config.cwd()?)?;
+    let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+    let mut dependencies: Vec<(String, String, Option<String>)> = package_json
+        .dependencies
+        .iter()
+        .chain(package_json.dev_dependencies.iter())
+        .flatten()
+        .filter(|(name, _)| names.is_empty() || names.contains(name))
+        // aliases, git, local and url dependencies aren't versioned by the registry
+        .filter(|(name, range)| {
+            matches!(
+                format!("{}@{}", name, range).parse(),
+                Ok(PackageSpec::Npm { .. })
+            )
+        })
+        .map(|(name, range)| {
+            let current = lock_file
+                .find(name, range)
+                .map(|package| package.version.clone());
+
+            (name.clone(), range.clone(), current)
+        })
+        .collect();
+
+    dependencies.sort();
+
+    let client = RegistryClient::new(config)?;
+
+    let lookups = dependencies.into_iter().map(|(name, range, current)| {
+        let client = client.clone();
+
+        async move {
+            let packument = client.packument(&name).await?;
+            let package: NpmPackage = serde_json::from_value(packument).into_diagnostic()?;
+
+            Ok::<_, miette::Report>(outdated(name, range, current, &package))
+        }
+    });
+
+    let results: Vec<_> = stream::iter(lookups)
+        .buffered(config.settings().concurrency)
+        .collect()
+        .await;
+
+    let mut outdated = vec![];
+
+    for result in results {
+        outdated.extend(result?);
+    }
+
+    Ok(outdated)
+}
+
+fn outdated(
+    name: String,
+    range: String,
+    current: Option<String>,
+    package: &NpmPackage,
+) -> Option<Outdated> {
+    let latest = package.dist_tags.latest()?.clone();
+    let wanted = resolve_version(package, Some(&range)).unwrap_or_else(|| latest.clone());
+
+    let newer = |version: &str| match (&current, version.parse::<Version>()) {
+        (Some(current), Ok(version)) => current
+            .parse::<Version>()
+            .map_or(true, |current| version > current),
+        _ => current.is_none(),
+    };
+
+    if !newer(&wanted) && !newer(&latest) {
+        return None;
+    }
+
+    Some(Outdated {
+        changelog: changelog_url(package),
+        name,
+        range,
+        current,
+        wanted,
+        latest,
+    })
+}
+
+/// The releases page of the package's GitHub repository, or its homepage.
+pub fn changelog_url(package: &NpmPackage) -> Option<String> {
+    let repository = match &package.repository {
+        Some(NewRepository::Str(url)) => Some(url.as_str()),
+        Some(NewRepository::BTreeMap(repository)) => repository.get("url").map(String::as_str),
+        None => None,
+    };
+
+    let github = repository.and_then(|url| {
+        let path = url
+            .strip_prefix("github:")
+            .or_else(|| url.split_once("github.com").map(|(_, path)| path))
+            // `user/repo` is short for a GitHub repository
+            .or_else(|| (!url.contains(':') && url.matches('/').count() == 1).then(|| url))?;
+
+        let path = path.trim_start_matches(|c| c == '/' || c == ':');
+        let path = path.trim_end_matches('/').trim_end_matches(".git");
+
+        Some(format!("https://github.com/{}/releases", path))
+    });
+
+    github.or_else(|| package.homepage.clone())
+}
+
+/// The range saving `version` the way `range` was written: `~` and exact versions stay
+/// so, anything else becomes `^`.
+pub fn updated_range(range: &str, version: &str) -> String {
+    if range.starts_with('~') {
+        format!("~{}", version)
+    } else if range.parse::<Version>().is_ok() {
+        version.to_string()
+    } else {
+        format!("^{}", version)
+    }
+}
+
+/// Set the range of dependencies in package.json, in whichever dependency section lists
+/// them, leaving the rest of the file as it was.
+pub fn update_ranges(path: &Path, ranges: &BTreeMap<String, String>) -> Result<()> {
+    if ranges.is_empty() {
+        return Ok(());
+    }
+
+    let contents = std::fs::read_to_string(path).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    let mut manifest: serde_json::Value = serde_json::from_str(&contents).into_diagnostic()?;
+
+    for section in ["dependencies", "devDependencies", "optionalDependencies"] {
+        if let Some(dependencies) = manifest
+            .get_mut(section)
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            for (name, range) in ranges {
+                if let Some(current) = dependencies.get_mut(name) {
+                    *current = range.clone().into();
+                }
+            }
+        }
+    }
+
+    let mut contents = serde_json::to_string_pretty(&manifest).into_diagnostic()?;
+    contents.push('\n');
+
+    std::fs::write(path, contents).map_err(|e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changelog_url, outdated, updated_range};
+    use crate::utils::package::{NewRepository, NpmPackage};
+
+    #[test]
+    fn finds_newer_versions() {
+        let mut package: NpmPackage = serde_json::from_value(serde_json::json!({
+            "name": "ms",
+            "dist-tags": { "latest": "3.0.0" },
+            "versions": { "2.0.0": {}, "2.1.3": {}, "3.0.0": {} },
+        }))
+        .unwrap();
+
+        let found = outdated(
+            "ms".to_string(),
+            "^2.0.0".to_string(),
+            Some("2.0.0".to_string()),
+            &package,
+        )
+        .unwrap();
+
+        assert_eq!(
+            (found.wanted.as_str(), found.latest.as_str()),
+            ("2.1.3", "3.0.0")
+        );
+        assert_eq!(
+            outdated(
+                "ms".to_string(),
+                "^3.0.0".to_string(),
+                Some("3.0.0".to_string()),
+                &package
+            ),
+            None
+        );
+
+        assert_eq!(updated_range("~2.0.0", "2.1.3"), "~2.1.3");
+        assert_eq!(updated_range("2.0.0", "2.1.3"), "2.1.3");
+        assert_eq!(updated_range(">=2", "3.0.0"), "^3.0.0");
+
+        package.repository = Some(NewRepository::Str(
+            "git+https://github.com/vercel/ms.git".to_string(),
+        ));
+        assert_eq!(
+            changelog_url(&package).as_deref(),
+            Some("https://github.com/vercel/ms/releases")
+        );
+
+        package.repository = Some(NewRepository::Str("vercel/ms".to_string()));
+        assert_eq!(
+            changelog_url(&package).as_deref(),
+            Some("https://github.com/vercel/ms/releases")
+        );
+    }
+}
@@ -0,0 +1,392 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Find, download and install newer releases of volt itself.
+//!
+//! Releases are read from GitHub (or the `update-url` setting, answering the same JSON):
+//! each has a `volt-<target>` build per platform and a `SHA256SUMS` file the builds are
+//! checked against, itself signed in `SHA256SUMS.sig` by the Ed25519 release key (the one
+//! volt was built with, or the `update-public-key` setting). Both settings are only read
+//! from the user's config and the environment, never from a project.
+//!
+//! Once a day, commands also look for a newer release in the background and mention it
+//! when they finish, unless `update-check` is off.
+
+use crate::{
+    config::VoltConfig,
+    utils::errors::{FilesystemError, IntegrityError, NetworkError, VoltError},
+};
+
+use miette::Result;
+use node_semver::Version;
+use reqwest::{header::USER_AGENT, Client};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::task::JoinHandle;
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The latest release of volt on GitHub.
+pub const RELEASES_URL: &str = "https://api.github.com/repos/voltpkg/volt/releases/latest";
+
+/// The base64 Ed25519 key releases are signed with, given when volt is built for release.
+const RELEASE_KEY: Option<&str> = option_env!("VOLT_RELEASE_KEY");
+
+/// How long the result of the background check is trusted.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A release, as GitHub describes it.
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl Release {
+    /// The version of the release, from its `v1.2.3` tag.
+    pub fn version(&self) -> Option<Version> {
+        self.tag_name.trim_start_matches('v').parse().ok()
+    }
+
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// The result of the last background check, in `~/.volt/update-check.json`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct CheckState {
+    /// Seconds since the epoch
+    checked: u64,
+    latest: Option<String>,
+}
+
+/// The running version of volt.
+pub fn current_version() -> Version {
+    env!("CARGO_PKG_VERSION")
+        .parse()
+        .expect("the crate version is semver")
+}
+
+/// The target triple builds are published for, like `x86_64-unknown-linux-gnu`.
+pub fn target() -> String {
+    let platform = match std::env::consts::OS {
+        "linux" if cfg!(target_env = "musl") => "unknown-linux-musl",
+        "linux" => "unknown-linux-gnu",
+        "macos" => "apple-darwin",
+        "windows" => "pc-windows-msvc",
+        os => os,
+    };
+
+    format!("{}-{}", std::env::consts::ARCH, platform)
+}
+
+/// The name of the build of this platform in a release.
+fn build_name() -> String {
+    format!("volt-{}{}", target(), std::env::consts::EXE_SUFFIX)
+}
+
+/// The checksum `SHA256SUMS` lists for `file`.
+fn expected_checksum(checksums: &str, file: &str) -> Option<String> {
+    checksums
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        // `sha256sum` marks binary files with a `*`
+        .find(|(_, name)| name.trim().trim_start_matches('*') == file)
+        .map(|(checksum, _)| checksum.to_lowercase())
+}
+
+/// Whether `signature` (base64, as in `SHA256SUMS.sig`) is the signature of `checksums`
+/// by the base64 Ed25519 `key`.
+fn signed_by(key: &str, checksums: &[u8], signature: &[u8]) -> bool {
+    let signature = String::from_utf8_lossy(signature);
+
+    match (base64::decode(key.trim()), base64::decode(signature.trim())) {
+        (Ok(key), Ok(signature)) => UnparsedPublicKey::new(&ED25519, key)
+            .verify(checksums, &signature)
+            .is_ok(),
+        _ => false,
+    }
+}
+
+/// Fetch `url`, with the user agent GitHub requires.
+async fn get(client: &Client, url: &str) -> Result<bytes::Bytes> {
+    let request_error = |source| NetworkError::Request {
+        url: url.to_string(),
+        source,
+    };
+
+    let response = client
+        .get(url)
+        .header(USER_AGENT, concat!("volt/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    if !response.status().is_success() {
+        return Err(NetworkError::Status {
+            url: url.to_string(),
+            status: response.status().to_string(),
+        }
+        .into());
+    }
+
+    Ok(response.bytes().await.map_err(request_error)?)
+}
+
+/// The latest release.
+pub async fn latest_release(config: &VoltConfig) -> Result<Release> {
+    let url = config
+        .settings()
+        .update_url
+        .clone()
+        .unwrap_or_else(|| RELEASES_URL.to_string());
+
+    let body = get(&config.http_client()?, &url).await?;
+
+    serde_json::from_slice(&body).map_err(|e| {
+        VoltError::InvalidRelease {
+            url,
+            reason: e.to_string(),
+        }
+        .into()
+    })
+}
+
+/// Download the build of this platform in `release`, checked against its `SHA256SUMS`
+/// once their signature is.
+pub async fn download(config: &VoltConfig, release: &Release) -> Result<bytes::Bytes> {
+    let no_build = || VoltError::NoUpgradeBuild {
+        version: release.tag_name.clone(),
+        target: target(),
+    };
+
+    let unsigned = || VoltError::UnsignedRelease {
+        version: release.tag_name.clone(),
+    };
+
+    let name = build_name();
+
+    let build = release.asset(&name).ok_or_else(no_build)?;
+    // builds aren't installed unchecked
+    let checksums = release.asset("SHA256SUMS").ok_or_else(no_build)?;
+    let signature = release.asset("SHA256SUMS.sig").ok_or_else(unsigned)?;
+
+    let key = config
+        .settings()
+        .update_public_key
+        .as_deref()
+        .or(RELEASE_KEY)
+        .ok_or_else(unsigned)?;
+
+    let client = config.http_client()?;
+
+    let checksums = get(&client, &checksums.browser_download_url).await?;
+    let signature = get(&client, &signature.browser_download_url).await?;
+
+    if !signed_by(key, &checksums, &signature) {
+        return Err(unsigned().into());
+    }
+
+    let expected =
+        expected_checksum(&String::from_utf8_lossy(&checksums), &name).ok_or_else(no_build)?;
+
+    let binary = get(&client, &build.browser_download_url).await?;
+
+    let actual = hex::encode(Sha256::digest(&binary));
+
+    if actual != expected {
+        return Err(IntegrityError::Checksum {
+            package: format!("volt {}", release.tag_name),
+            tarball: build.browser_download_url.clone(),
+            expected,
+            actual,
+        }
+        .into());
+    }
+
+    Ok(binary)
+}
+
+/// Replace the running executable with `binary`, returning its path. The new executable
+/// is written next to it and renamed over it, so an interrupted upgrade leaves the old
+/// one in place.
+pub fn replace_executable(binary: &[u8]) -> Result<PathBuf> {
+    let executable = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .map_err(|e| VoltError::EnvironmentError {
+            env: "CURRENT_EXE".to_string(),
+            source: e,
+        })?;
+
+    let directory = executable.parent().unwrap_or(executable.as_path());
+
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: executable.display().to_string(),
+    };
+
+    let mut staged = tempfile::NamedTempFile::new_in(directory).map_err(write_error)?;
+
+    staged.write_all(binary).map_err(write_error)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(staged.path(), std::fs::Permissions::from_mode(0o755))
+            .map_err(write_error)?;
+    }
+
+    // windows can't replace a running executable, but can rename it out of the way
+    #[cfg(windows)]
+    let old = {
+        let old = executable.with_extension("old.exe");
+
+        let _ = std::fs::remove_file(&old);
+
+        std::fs::rename(&executable, &old).map_err(write_error)?;
+
+        old
+    };
+
+    let persisted = staged.persist(&executable);
+
+    // without the new executable, the old one goes back where it was
+    #[cfg(windows)]
+    if persisted.is_err() {
+        let _ = std::fs::rename(&old, &executable);
+    }
+
+    persisted.map_err(|e| write_error(e.error))?;
+
+    Ok(executable)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Look for a newer volt in the background, at most once a day; the task resolves to
+/// the newer version, if there is one. `None` when `update-check` is off, or in CI.
+pub fn check_for_update(config: &VoltConfig) -> Option<JoinHandle<Option<Version>>> {
+    // CI runs are short-lived and nobody reads the notice
+    if !config.settings().update_check || std::env::var_os("CI").is_some() {
+        return None;
+    }
+
+    let path = config.volt_home().ok()?.join("update-check.json");
+    let config = config.clone();
+
+    Some(tokio::spawn(async move {
+        let state: CheckState = std::fs::read(&path)
+            .ok()
+            .and_then(|state| serde_json::from_slice(&state).ok())
+            .unwrap_or_default();
+
+        let latest = if now().saturating_sub(state.checked) < CHECK_INTERVAL.as_secs() {
+            state.latest.and_then(|latest| latest.parse().ok())
+        } else {
+            let latest = latest_release(&config)
+                .await
+                .map_err(|e| tracing::debug!("couldn't check for a newer volt: {}", e))
+                .ok()
+                .and_then(|release| release.version());
+
+            // failures are retried no sooner than successes, to not slow every command
+            let state = CheckState {
+                checked: now(),
+                latest: latest.as_ref().map(Version::to_string),
+            };
+
+            if let Ok(state) = serde_json::to_vec(&state) {
+                let _ = std::fs::write(&path, state);
+            }
+
+            latest
+        };
+
+        latest.filter(|latest| *latest > current_version())
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_name, expected_checksum, signed_by, Release};
+
+    use ring::{
+        rand::SystemRandom,
+        signature::{Ed25519KeyPair, KeyPair},
+    };
+
+    #[test]
+    fn finds_the_build_and_its_checksum() {
+        let release: Release = serde_json::from_str(&format!(
+            r#"{{
+                "tag_name": "v0.1.0",
+                "assets": [
+                    {{ "name": "SHA256SUMS", "browser_download_url": "https://example.com/SHA256SUMS" }},
+                    {{ "name": "{}", "browser_download_url": "https://example.com/volt" }}
+                ]
+            }}"#,
+            build_name()
+        ))
+        .unwrap();
+
+        assert_eq!(release.version().unwrap().to_string(), "0.1.0");
+        assert!(release.asset(&build_name()).is_some());
+
+        let checksums = format!("ABC123 *{}\ndef456  volt-other\n", build_name());
+
+        assert_eq!(
+            expected_checksum(&checksums, &build_name()).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(expected_checksum(&checksums, "volt-missing"), None);
+    }
+
+    #[test]
+    fn checks_the_signature_of_the_checksums() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let key = base64::encode(pair.public_key().as_ref());
+        let checksums = format!("abc123 *{}\n", build_name());
+        let signature = format!("{}\n", base64::encode(pair.sign(checksums.as_bytes())));
+
+        assert!(signed_by(&key, checksums.as_bytes(), signature.as_bytes()));
+        assert!(!signed_by(&key, b"def456 *volt\n", signature.as_bytes()));
+        assert!(!signed_by(
+            &key,
+            checksums.as_bytes(),
+            b"bm90IGEgc2lnbmF0dXJl"
+        ));
+    }
+}
@@ -0,0 +1,702 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! The errors of volt, grouped by what went wrong: the network, resolving packages, their
+//! integrity, the filesystem and scripts.
+//!
+//! Every error has an npm-style code (`EINTEGRITY`, `ETARGET`), shown with it and in the
+//! `--json` output, names the package, url or path involved, and says what to do about it.
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+use std::{fmt::Display, io};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum VoltError {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Network(#[from] NetworkError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Resolution(#[from] ResolutionError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Integrity(#[from] IntegrityError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Filesystem(#[from] FilesystemError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Script(#[from] ScriptError),
+
+    #[error("failed to detect `{env}`")]
+    #[diagnostic(
+        code(EENV),
+        help("check that the directory exists and that you have permission to read it")
+    )]
+    EnvironmentError { source: io::Error, env: String },
+
+    #[error("invalid value `{value}` for `{key}` in {origin}")]
+    #[diagnostic(code(ECONFIG), help("`{key}` expects {expected}"))]
+    InvalidSetting {
+        key: String,
+        value: String,
+        origin: String,
+        expected: String,
+    },
+
+    #[error("failed to read the certificates in {path}")]
+    #[diagnostic(
+        code(ECERT),
+        help("the `cafile` setting must point to a file of PEM certificates (`-----BEGIN CERTIFICATE-----`)")
+    )]
+    InvalidCertificate { path: String },
+
+    #[error("failed to parse {path}: {message}")]
+    #[diagnostic(code(EJSONPARSE), help("fix the syntax of {path}"))]
+    InvalidJson { path: String, message: String },
+
+    #[error("timed out after {seconds}s waiting for another volt process to release {path}")]
+    #[diagnostic(
+        code(ELOCKED),
+        help("wait for the other process to finish, raise the `lock-timeout` setting, or pass `--no-lock` if no other volt process is running")
+    )]
+    LockTimeout { path: String, seconds: u64 },
+
+    #[error("no node version matches `{version}`")]
+    #[diagnostic(
+        code(ENODEVERSION),
+        help("check the versions published on https://nodejs.org/dist, or use `lts` or `latest`")
+    )]
+    NodeVersionNotFound { version: String },
+
+    #[error("{name} is not linked")]
+    #[diagnostic(
+        code(ENOTLINKED),
+        help("run `volt link` in the directory of {name} first")
+    )]
+    NotLinked { name: String },
+
+    #[error("volt {version} has no build for {target}")]
+    #[diagnostic(
+        code(EUPGRADE),
+        help("download a build for your platform from https://github.com/voltpkg/volt/releases, or build it with `cargo install volt`")
+    )]
+    NoUpgradeBuild { version: String, target: String },
+
+    #[error("the checksums of volt {version} aren't signed by the release key")]
+    #[diagnostic(
+        code(EUPGRADE),
+        help("download volt from https://github.com/voltpkg/volt/releases, or set `update-public-key` to the key the releases of `update-url` are signed with")
+    )]
+    UnsignedRelease { version: String },
+
+    #[error("{url} doesn't describe a release: {reason}")]
+    #[diagnostic(
+        code(EUPGRADE),
+        help("point `update-url` at a document shaped like GitHub's latest release")
+    )]
+    InvalidRelease { url: String, reason: String },
+
+    #[error("{packages} published less than {age} ago")]
+    #[diagnostic(
+        code(EFRESH),
+        help("wait for the `minimum-release-age` to pass, pin an older version, or pass `--allow-fresh`")
+    )]
+    FreshRelease { packages: String, age: String },
+
+    #[error("packages without a registry signature: {packages}")]
+    #[diagnostic(
+        code(EUNSIGNED),
+        help("if you trust where they come from, install without `--require-signatures` and with `require-signatures = false`")
+    )]
+    UnsignedPackages { packages: String },
+
+    #[error("node {version} is not installed")]
+    #[diagnostic(code(ENODEVERSION), help("run `volt node install {version}`"))]
+    NodeVersionNotInstalled { version: String },
+
+    #[error("npm {version} is not installed")]
+    #[diagnostic(
+        code(ENODEVERSION),
+        help("run `volt node pin npm@{version}` in the project to install it")
+    )]
+    NpmVersionNotInstalled { version: String },
+
+    #[error("no node version is selected to run `{tool}`")]
+    #[diagnostic(
+        code(ENONODE),
+        help("run `volt node use lts` to install and select one")
+    )]
+    NoNodeVersion { tool: String },
+
+    #[error("failed to detect your home directory")]
+    #[diagnostic(code(ENOHOME), help("set the HOME environment variable"))]
+    GetHomeDirError,
+
+    #[error("failed to get the name of the current directory")]
+    #[diagnostic(
+        code(ECWD),
+        help("run the command from a named directory rather than the filesystem root")
+    )]
+    GetCurrentDirNameError,
+
+    // Convert error to `String` instead of having a `source` because `git_config::parser::Error`
+    // has a lifetime parameter
+    #[error("failed to parse git configuration file: `{error_text}`")]
+    #[diagnostic(code(EGITCONFIG), help("fix the syntax of your ~/.gitconfig"))]
+    GitConfigParseError { error_text: String },
+
+    #[error("`git {command}` failed: {stderr}")]
+    #[diagnostic(code(EGIT), help("make sure git is installed and on your PATH"))]
+    GitCommandError { command: String, stderr: String },
+
+    #[error("`{url}` can't be cloned: {reason}")]
+    #[diagnostic(
+        code(EGITURL),
+        help("git dependencies are cloned from https, ssh, git or file urls, or `user@host:path`")
+    )]
+    InvalidGitSource { url: String, reason: String },
+
+    #[error("unknown template: `{name}`")]
+    #[diagnostic(
+        code(ETEMPLATE),
+        help("use one of the built-in templates ({templates}) or a git repository url")
+    )]
+    UnknownTemplate { name: String, templates: String },
+
+    #[error("invalid filter `{filter}`")]
+    #[diagnostic(
+        code(EFILTER),
+        help("filter by name (`@scope/*`), path (`./packages/app`) or git reference (`[origin/main]`), optionally with `...` before or after")
+    )]
+    InvalidFilter { filter: String },
+
+    #[error("found {count} vulnerabilities at or above {level} severity")]
+    #[diagnostic(
+        code(EAUDIT),
+        help("update the affected packages, or raise the threshold with `--audit-level`")
+    )]
+    Vulnerable { count: usize, level: String },
+
+    #[error("{count} of volt's checks failed")]
+    #[diagnostic(
+        code(EDOCTOR),
+        help("follow the suggestions next to the failed checks")
+    )]
+    DoctorFailed { count: usize },
+
+    #[error("{count} packages have licenses the policy doesn't allow")]
+    #[diagnostic(
+        code(ELICENSE),
+        help("replace them, or change the `deny` and `allow` lists of the license policy")
+    )]
+    LicensePolicy { count: usize },
+
+    #[error("package.json, volt.lock and node_modules disagree in {count} places")]
+    #[diagnostic(
+        code(EVERIFY),
+        help("run `volt install` to bring volt.lock and node_modules up to date")
+    )]
+    VerifyFailed { count: usize },
+
+    #[error("not logged in to {registry}")]
+    #[diagnostic(
+        code(ENEEDAUTH),
+        help(
+            "run `volt login --registry {registry}`, or add a token for it to your .npmrc, keyed by the registry url without its protocol: `//registry.example.com/:_authToken=${{NPM_TOKEN}}`"
+        )
+    )]
+    NotLoggedIn { registry: String },
+
+    #[error("{registry} asks for a one-time password")]
+    #[diagnostic(
+        code(EOTP),
+        help(
+            "your account has two-factor authentication: pass the code of your authenticator app with `--otp`"
+        )
+    )]
+    OtpRequired { registry: String },
+
+    #[error("`{tag}` can't be used as a dist-tag")]
+    #[diagnostic(
+        code(EINVALIDTAGNAME),
+        help("dist-tags can't be empty or look like a version or a range, which specs would read them as")
+    )]
+    InvalidDistTag { tag: String },
+
+    #[error("{user} is the last owner of {name}")]
+    #[diagnostic(
+        code(EOWNER),
+        help("add another owner with `volt owner add <user> {name}` before removing {user}")
+    )]
+    LastOwner { name: String, user: String },
+
+    #[error("{registry} has no user named {user}")]
+    #[diagnostic(code(E404), help("check the spelling of the username"))]
+    UnknownUser { user: String, registry: String },
+
+    #[error("refusing to unpublish every version of {name}")]
+    #[diagnostic(
+        code(EUNPUBLISH),
+        help("pass `--force` to unpublish the whole package; its name can't be published again for 24 hours")
+    )]
+    WholePackageUnpublish { name: String },
+
+    #[error("node_modules/{first} and node_modules/{second} only differ in case")]
+    #[diagnostic(
+        code(ECASE),
+        help("this filesystem doesn't tell them apart, so one would overwrite the other; depend on one of the packages, or alias one with `npm:`")
+    )]
+    CaseCollision { first: String, second: String },
+
+    #[error("patches/{patch} doesn't apply to {package}: {reason}")]
+    #[diagnostic(
+        code(EPATCH),
+        help("the package changed since the patch was made; edit it again with `volt patch {package}` and save it with `volt patch-commit`")
+    )]
+    PatchFailed {
+        package: String,
+        patch: String,
+        reason: String,
+    },
+
+    #[error("{name} is private and can't be published")]
+    #[diagnostic(
+        code(EPRIVATE),
+        help("remove `\"private\": true` from package.json to publish it")
+    )]
+    PrivatePackage { name: String },
+
+    #[error("{package} has no executables")]
+    #[diagnostic(
+        code(ENOBIN),
+        help("only packages with a `bin` field in their package.json can be run")
+    )]
+    NoExecutables { package: String },
+
+    #[error("could not determine which executable of {package} to run")]
+    #[diagnostic(
+        code(EAMBIGUOUS),
+        help("name one of {executables} with `volt x --package <package> <command>`")
+    )]
+    AmbiguousExecutable {
+        package: String,
+        executables: String,
+    },
+
+    #[error("unknown command: `{name}`")]
+    #[diagnostic(
+        code(ECOMMAND),
+        help("run `volt --help` for the commands; plugins are `volt-{name}` executables on PATH or in ~/.volt/plugins")
+    )]
+    UnknownCommand { name: String },
+
+    #[error("no WebAssembly runtime to run {plugin}")]
+    #[diagnostic(
+        code(EWASM),
+        help("WebAssembly plugins run with `wasmtime` or `wasmer`, install one of them on PATH")
+    )]
+    NoWasmRuntime { plugin: String },
+}
+
+/// Requests that failed or that the registry turned down.
+#[derive(Debug, Error, Diagnostic)]
+pub enum NetworkError {
+    #[error("request to {url} failed")]
+    #[diagnostic(
+        code(ENETWORK),
+        help(
+            "check your internet connection, the `registry` in your .npmrc and your proxy settings"
+        )
+    )]
+    Request { url: String, source: reqwest::Error },
+
+    #[error("request to {url} timed out after {seconds}s without an answer")]
+    #[diagnostic(
+        code(ETIMEDOUT),
+        help("check your internet connection, or raise the `fetch-timeout` setting")
+    )]
+    Timeout { url: String, seconds: u64 },
+
+    #[error("request to {url} failed")]
+    #[diagnostic(
+        code(ENETWORK),
+        help("check your internet connection, and try again later")
+    )]
+    VoltRegistry { url: String, source: isahc::Error },
+
+    #[error("GET {url} - the volt registry sent a response that can't be read: {reason}")]
+    #[diagnostic(code(ENETWORK), help("try again later"))]
+    InvalidResponse { url: String, reason: String },
+
+    #[error("the volt registry only serves registry packages, not `{spec}`")]
+    #[diagnostic(
+        code(ENETWORK),
+        help("git, local and tarball packages are installed from the npm registry source")
+    )]
+    NotInVoltRegistry { spec: String },
+
+    #[error("GET {url} - 429 - too many requests have been sent to the volt registry")]
+    #[diagnostic(code(E429), help("wait a moment before trying again"))]
+    TooManyRequests { url: String },
+
+    #[error("GET {url} - 400 - bad request")]
+    #[diagnostic(
+        code(E400),
+        help("check the package specification, and try again later")
+    )]
+    BadRequest { url: String },
+
+    #[error("GET {url} - {status} - the registry failed to respond")]
+    #[diagnostic(code(ENETWORK), help("try again later"))]
+    Status { url: String, status: String },
+
+    #[error("GET {url} - {status} - failed to download {package}")]
+    #[diagnostic(
+        code(EDOWNLOAD),
+        help("the tarball may have been removed from the registry; run `volt install` to resolve the package again")
+    )]
+    Download {
+        package: String,
+        url: String,
+        status: u16,
+    },
+
+    #[error("{url} - {status} - {message}")]
+    #[diagnostic(
+        code(EREGISTRY),
+        help(
+            "check the package name, and that the token for the registry in .npmrc gives access to it"
+        )
+    )]
+    Registry {
+        url: String,
+        status: u16,
+        message: String,
+    },
+}
+
+/// Packages, versions and lock files that can't be resolved.
+#[derive(Debug, Error, Diagnostic)]
+pub enum ResolutionError {
+    #[error("GET {url} - 404 - {package} was not found in the volt registry")]
+    #[diagnostic(
+        code(E404),
+        help("check the spelling of the package name, and for private packages that you can access them")
+    )]
+    NotFound { url: String, package: String },
+
+    #[error("no version of {name} matches `{requested}`")]
+    #[diagnostic(
+        code(ETARGET),
+        help("run `volt info {name} versions` to list the published versions")
+    )]
+    NoMatchingVersion { name: String, requested: String },
+
+    #[error("{name} has no dist-tag `{tag}`")]
+    #[diagnostic(
+        code(ETARGET),
+        help("run `volt dist-tag ls {name}` to list its dist-tags")
+    )]
+    UnknownDistTag { name: String, tag: String },
+
+    #[error("{name}@{version} does not support this platform ({platform})")]
+    #[diagnostic(
+        code(EBADPLATFORM),
+        help("the package is limited to os {os}, cpu {cpu} and libc {libc}")
+    )]
+    UnsupportedPlatform {
+        name: String,
+        version: String,
+        platform: String,
+        os: String,
+        cpu: String,
+        libc: String,
+    },
+
+    #[error("{path} is out of date with package.json")]
+    #[diagnostic(code(ELOCKOUTDATED), help("run `volt install` to update it"))]
+    LockFileOutdated { path: String },
+
+    #[error("{name}@{version} needs {engine} {range}, but {current} is running")]
+    #[diagnostic(
+        code(EBADENGINE),
+        help(
+            "switch to a supported {engine} version, or set `engine-strict = false` to only warn"
+        )
+    )]
+    UnsupportedEngine {
+        name: String,
+        version: String,
+        engine: String,
+        range: String,
+        current: String,
+    },
+
+    #[error("{path} does not exist")]
+    #[diagnostic(code(ENOLOCK), help("run `volt install` to create it"))]
+    LockFileMissing { path: String },
+
+    #[error("{package} is not available from {origin}")]
+    #[diagnostic(
+        code(ENOTCACHED),
+        help("install it with the registry available first, or add its tarball to {origin}")
+    )]
+    Unavailable { package: String, origin: String },
+
+    #[error("the vendor directory {path} doesn't exist")]
+    #[diagnostic(
+        code(ENOVENDOR),
+        help("run `volt vendor` with the registry available to create it, with `VOLT_VENDORED=false` if `vendored` is set")
+    )]
+    NoVendorDirectory { path: String },
+
+    #[error("the alias `{name}: {spec}` doesn't name a package")]
+    #[diagnostic(
+        code(EINVALIDALIAS),
+        help("aliases name the package they stand for and its version: `npm:bar@^2.0.0`")
+    )]
+    InvalidAlias { name: String, spec: String },
+}
+
+/// Downloads that aren't what was published.
+#[derive(Debug, Error, Diagnostic)]
+pub enum IntegrityError {
+    #[error(
+        "failed to verify the tarball checksum of {package} (expected {expected}, got {actual})"
+    )]
+    #[diagnostic(
+        code(EINTEGRITY),
+        help("{tarball} was corrupted on the way or changed since it was published; try again, and if it keeps failing check any proxy or mirror in between")
+    )]
+    Checksum {
+        package: String,
+        tarball: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("the registry signature of {package} doesn't match (key {keyid})")]
+    #[diagnostic(
+        code(EINTEGRITY),
+        help("the package isn't the one the registry signed; don't install it, and report it to the registry if it keeps failing")
+    )]
+    Signature { package: String, keyid: String },
+
+    #[error("{package} is signed by a registry key that expired before it was published (key {keyid}, expired {expires})")]
+    #[diagnostic(
+        code(EINTEGRITY),
+        help("a key can't sign what is published after it expired; don't install the package, and report it to the registry")
+    )]
+    ExpiredKey {
+        package: String,
+        keyid: String,
+        expires: String,
+    },
+
+    #[error("`{integrity}` is not an integrity volt can check")]
+    #[diagnostic(
+        code(EINTEGRITY),
+        help(
+            "integrities are `sha512-<base64>` (or sha256, sha384, sha1), or a `sha1-<hex>` shasum"
+        )
+    )]
+    Malformed { integrity: String },
+
+    #[error("the tarball of {package} has an entry outside of the package: {entry}")]
+    #[diagnostic(
+        code(ETAR),
+        help(
+            "nothing of the package was installed; don't install it, and report it to the registry"
+        )
+    )]
+    UnsafeEntry { package: String, entry: String },
+
+    #[error("the tarball of {package} has both {first} and {second}")]
+    #[diagnostic(
+        code(ECASE),
+        help("this filesystem doesn't tell them apart, so one would overwrite the other; ask the authors of the package to rename one")
+    )]
+    CaseCollision {
+        package: String,
+        first: String,
+        second: String,
+    },
+}
+
+/// Files and directories that can't be read or written; the code is the one of the
+/// operating system error (`ENOENT`, `EACCES`).
+#[derive(Debug, Error)]
+pub enum FilesystemError {
+    #[error("failed to read `{path}`")]
+    Read { source: io::Error, path: String },
+
+    #[error("failed to write to `{path}`")]
+    Write { source: io::Error, path: String },
+
+    #[error("failed to create directory `{path}`")]
+    CreateDir { source: io::Error, path: String },
+
+    #[error("failed to remove `{path}`")]
+    Remove { source: io::Error, path: String },
+}
+
+impl FilesystemError {
+    fn source_error(&self) -> &io::Error {
+        match self {
+            Self::Read { source, .. }
+            | Self::Write { source, .. }
+            | Self::CreateDir { source, .. }
+            | Self::Remove { source, .. } => source,
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self {
+            Self::Read { path, .. }
+            | Self::Write { path, .. }
+            | Self::CreateDir { path, .. }
+            | Self::Remove { path, .. } => path,
+        }
+    }
+}
+
+impl Diagnostic for FilesystemError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self.source_error().kind() {
+            io::ErrorKind::NotFound => "ENOENT",
+            io::ErrorKind::PermissionDenied => "EACCES",
+            io::ErrorKind::AlreadyExists => "EEXIST",
+            _ => "EIO",
+        };
+
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self.source_error().kind() {
+            io::ErrorKind::NotFound => format!("{} or its directory doesn't exist", self.path()),
+            io::ErrorKind::PermissionDenied => format!(
+                "check that you have permission to access {}, and that no other program holds it",
+                self.path()
+            ),
+            io::ErrorKind::AlreadyExists => {
+                format!("{} already exists; remove it and try again", self.path())
+            }
+            _ => format!(
+                "check that the disk holding {} isn't full or read-only",
+                self.path()
+            ),
+        };
+
+        Some(Box::new(help))
+    }
+}
+
+/// Lifecycle scripts and commands that couldn't run or failed.
+#[derive(Debug, Error, Diagnostic)]
+pub enum ScriptError {
+    #[error("failed to run the `{script}` script of `{name}`")]
+    #[diagnostic(
+        code(ESPAWN),
+        help("check that a shell is available to run scripts (`sh`, or `cmd` on windows)")
+    )]
+    Spawn {
+        source: io::Error,
+        name: String,
+        script: String,
+    },
+
+    #[error("missing script: `{name}`")]
+    #[diagnostic(
+        code(EMISSINGSCRIPT),
+        help("run `volt run` to list the scripts defined in package.json")
+    )]
+    NotFound { name: String },
+
+    #[error("the `{script}` script of `{name}` exited with code {code}")]
+    #[diagnostic(
+        code(ELIFECYCLE),
+        help("this is a problem with the script of {name}, see its output above")
+    )]
+    Failed {
+        name: String,
+        script: String,
+        code: i32,
+    },
+
+    #[error("the native addon of `{name}` failed to build (exit code {code})")]
+    #[diagnostic(
+        code(ELIFECYCLE),
+        help("node-gyp needs python 3 and a C++ compiler: build-essential on linux, the Xcode command line tools on macOS, or Visual Studio on windows (see the `python` and `msvs-version` settings); the output of {name} is above")
+    )]
+    NativeBuild { name: String, code: i32 },
+
+    #[error("the install scripts of {count} packages failed: {packages}")]
+    #[diagnostic(code(ELIFECYCLE), help("the output of each package is above"))]
+    Builds { count: usize, packages: String },
+
+    #[error("failed to run `{command}`")]
+    #[diagnostic(
+        code(ESPAWN),
+        help("check that `{command}` is an executable of the package or on your PATH")
+    )]
+    CommandSpawn { source: io::Error, command: String },
+
+    #[error("`{command}` exited with code {code}")]
+    #[diagnostic(
+        code(ELIFECYCLE),
+        help("this is a problem with `{command}`, see its output above")
+    )]
+    CommandFailed { command: String, code: i32 },
+
+    #[error("the {hook} hook `{command}` exited with code {code}")]
+    #[diagnostic(
+        code(EHOOK),
+        help("the `{hook}-hook` setting stopped the install, its output is above")
+    )]
+    Hook {
+        hook: String,
+        command: String,
+        code: i32,
+    },
+
+    #[error("`{command}` failed in {count} workspace members: {members}")]
+    #[diagnostic(code(ELIFECYCLE), help("the output of each member is above"))]
+    CommandsFailed {
+        command: String,
+        count: usize,
+        members: String,
+    },
+
+    #[error("the `{script}` script failed in {count} workspace members: {members}")]
+    #[diagnostic(
+        code(ELIFECYCLE),
+        help("the output of each member is above; {skipped} members depending on them didn't run")
+    )]
+    WorkspaceScripts {
+        script: String,
+        count: usize,
+        members: String,
+        skipped: usize,
+    },
+}
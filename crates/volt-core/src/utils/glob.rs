@@ -0,0 +1,81 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Minimal glob matching for package names and workspace paths.
+//!
+//! `*` matches anything but `/`, `**` matches anything (including `/`) and `?` matches a
+//! single character that isn't `/`.
+
+/// Check whether `text` matches `pattern`.
+pub fn matches(pattern: &str, text: &str) -> bool {
+    matches_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn matches_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // `**/` also matches zero directories
+            let rest_without_slash = rest.strip_prefix(b"/").unwrap_or(rest);
+
+            matches_bytes(rest_without_slash, text)
+                || (0..=text.len()).any(|i| matches_bytes(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => {
+            for i in 0..=text.len() {
+                if matches_bytes(rest, &text[i..]) {
+                    return true;
+                }
+
+                if i < text.len() && text[i] == b'/' {
+                    return false;
+                }
+            }
+
+            false
+        }
+        [b'?', rest @ ..] => match text {
+            [c, text_rest @ ..] if *c != b'/' => matches_bytes(rest, text_rest),
+            _ => false,
+        },
+        [p, rest @ ..] => match text {
+            [c, text_rest @ ..] if c == p => matches_bytes(rest, text_rest),
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn single_star_stays_within_a_segment() {
+        assert!(matches("packages/*", "packages/core"));
+        assert!(!matches("packages/*", "packages/core/nested"));
+        assert!(matches("@scope/*", "@scope/utils"));
+        assert!(!matches("@scope/*", "@other/utils"));
+    }
+
+    #[test]
+    fn double_star_crosses_segments() {
+        assert!(matches("apps/**", "apps/web/client"));
+        assert!(matches("**/test", "test"));
+        assert!(matches("**/test", "a/b/test"));
+        assert!(matches("eslint-*", "eslint-plugin-react"));
+        assert!(matches("?ebpack", "webpack"));
+    }
+}
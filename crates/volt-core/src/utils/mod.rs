@@ -0,0 +1,416 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+pub mod constants;
+pub mod errors;
+pub mod extensions;
+pub mod glob;
+pub mod package;
+pub mod package_editor;
+pub mod voltapi;
+
+use crate::{
+    config::VoltConfig,
+    git::fetch_git_tarball,
+    install::link_directory,
+    io::extract_tarball,
+    local::local_tarball,
+    paths::long_path,
+    reporter::Reporter,
+    shim::link_bins,
+    tarball::Tarball,
+    utils::voltapi::{dependency_target, VoltPackage},
+};
+
+use errors::{FilesystemError, IntegrityError, VoltError};
+use git_config::file::GitConfig;
+use git_config::parser::parse_from_str;
+use miette::{IntoDiagnostic, Result};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use reqwest::Client;
+use ssri::Integrity;
+
+use std::{
+    collections::HashMap,
+    fs::read_to_string,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub struct State {
+    pub http_client: Client,
+    pub reporter: Arc<dyn Reporter>,
+}
+
+pub fn decompress_gzip(gz_data: &[u8]) -> Result<Vec<u8>> {
+    // gzip RFC1952: a valid gzip file has an ISIZE field in the
+    // footer, which is a little-endian u32 number representing the
+    // decompressed size. This is ideal for libdeflate, which needs
+    // preallocating the decompressed buffer.
+    let isize = {
+        let isize_start = gz_data.len() - 4;
+        let isize_bytes: [u8; 4] = gz_data[isize_start..].try_into().into_diagnostic()?;
+        u32::from_le_bytes(isize_bytes) as usize
+    };
+
+    let mut decompressor = libdeflater::Decompressor::new();
+
+    let mut outbuf = vec![0; isize];
+    decompressor
+        .gzip_decompress(gz_data, &mut outbuf)
+        .into_diagnostic()?;
+
+    Ok(outbuf)
+}
+
+fn get_git_config_value_if_exists(
+    config: &VoltConfig,
+    section: &str,
+    subsection: Option<&str>,
+    key: &str,
+) -> Result<Option<String>> {
+    let config_path = config.home()?.join(".gitconfig");
+
+    if config_path.exists() {
+        let data = read_to_string(&config_path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: config_path.display().to_string(),
+        })?;
+
+        let parser = parse_from_str(&data).map_err(|err| VoltError::GitConfigParseError {
+            error_text: err.to_string(),
+        })?;
+
+        let config = GitConfig::from(parser);
+        let value = config.get_raw_value(section, subsection, key).ok();
+
+        Ok(value.map(|v| String::from_utf8_lossy(&v).into_owned()))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The first `name` executable on `PATH`.
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    let names = if cfg!(windows) {
+        vec![format!("{}.exe", name), format!("{}.cmd", name)]
+    } else {
+        vec![name.to_string()]
+    };
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|directory| names.iter().map(move |name| directory.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// Gets a config key from git using the git cli.
+/// Uses `gitoxide` to read from your git configuration.
+pub fn get_git_config(config: &VoltConfig, key: &str) -> Result<Option<String>> {
+    match key {
+        "user.name" => get_git_config_value_if_exists(config, "user", None, "name"),
+        "user.email" => get_git_config_value_if_exists(config, "user", None, "email"),
+        "repository.url" => get_git_config_value_if_exists(config, "remote", Some("origin"), "url"),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(unix)]
+pub fn enable_ansi_support() -> Result<(), u32> {
+    Ok(())
+}
+
+// Windows Function
+/// Enable ansi support and colors
+#[cfg(windows)]
+pub fn enable_ansi_support() -> Result<(), u32> {
+    // ref: https://docs.microsoft.com/en-us/windows/console/console-virtual-terminal-sequences#EXAMPLE_OF_ENABLING_VIRTUAL_TERMINAL_PROCESSING @@ https://archive.is/L7wRJ#76%
+
+    use std::ffi::OsStr;
+    use std::iter::once;
+    use std::os::windows::prelude::OsStrExt;
+    use std::ptr::null_mut;
+    use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+    use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::fileapi::{CreateFileW, OPEN_EXISTING};
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+    use winapi::um::winnt::{FILE_SHARE_WRITE, GENERIC_READ, GENERIC_WRITE};
+
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    unsafe {
+        // ref: https://docs.microsoft.com/en-us/windows/win32/api/fileapi/nf-fileapi-createfilew
+        // Using `CreateFileW("CONOUT$", ...)` to retrieve the console handle works correctly even if STDOUT and/or STDERR are redirected
+        let console_out_name: Vec<u16> =
+            OsStr::new("CONOUT$").encode_wide().chain(once(0)).collect();
+
+        let console_handle = CreateFileW(
+            console_out_name.as_ptr(),
+            GENERIC_READ | GENERIC_WRITE,
+            FILE_SHARE_WRITE,
+            null_mut(),
+            OPEN_EXISTING,
+            0,
+            null_mut(),
+        );
+        if console_handle == INVALID_HANDLE_VALUE {
+            return Err(GetLastError());
+        }
+
+        // ref: https://docs.microsoft.com/en-us/windows/console/getconsolemode
+        let mut console_mode: u32 = 0;
+        if 0 == GetConsoleMode(console_handle, &mut console_mode) {
+            return Err(GetLastError());
+        }
+
+        // VT processing not already enabled?
+        if console_mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING == 0 {
+            // https://docs.microsoft.com/en-us/windows/console/setconsolemode
+            if 0 == SetConsoleMode(
+                console_handle,
+                console_mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+            ) {
+                return Err(GetLastError());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn _check_peer_dependency(_package_name: &str) -> bool {
+    false
+}
+
+pub fn verify_existing_installation(
+    package: &VoltPackage,
+    config: &VoltConfig,
+) -> miette::Result<Vec<u8>> {
+    let store = config.store()?;
+
+    let result = cacache::read_sync(store, package.cacache_key()).into_diagnostic()?;
+
+    Ok(result)
+}
+
+/// What is wrong with the files of `package` in `directory`, checked against its file
+/// index in the store: a missing file, or with `check_files`, one whose contents differ.
+/// Without the package in the store only its package.json is looked for.
+pub fn damaged_files(
+    config: &VoltConfig,
+    package: &VoltPackage,
+    directory: &Path,
+    check_files: bool,
+) -> Option<String> {
+    let files = verify_existing_installation(package, config)
+        .ok()
+        .and_then(|index| serde_json::from_slice::<HashMap<PathBuf, Integrity>>(&index).ok());
+
+    let files = match files {
+        Some(files) => files,
+        None if directory.join("package.json").is_file() => return None,
+        None => return Some("package.json is missing".to_string()),
+    };
+
+    for (name, integrity) in &files {
+        let path = directory.join(name);
+
+        if !check_files {
+            if !path.is_file() {
+                return Some(format!("{} is missing", name.display()));
+            }
+
+            continue;
+        }
+
+        match std::fs::read(&path) {
+            Ok(contents) if integrity.check(&contents).is_ok() => {}
+            Ok(_) => return Some(format!("{} was modified", name.display())),
+            Err(_) => return Some(format!("{} is missing", name.display())),
+        }
+    }
+
+    None
+}
+
+/// Link the dependencies of a package in the `.volt` virtual store next to it.
+pub fn link_dependencies(package: &VoltPackage, node_modules: &Path) -> miette::Result<()> {
+    // link the subdependencies for a package
+    for (name, version) in package.installed_dependencies() {
+        // aliases link `bar` from the store as `foo`
+        let (target, target_version) = dependency_target(name, version)?;
+
+        // node_modules/.volt/accepts@1.2.3/node_modules/accepts
+        let dependency_link_path = node_modules
+            .join(".volt")
+            .join(format!("{}@{}", target.replace('/', "+"), target_version))
+            .join("node_modules")
+            .join(target);
+
+        // node_modules/.volt/send@0.17.2/node_modules/ms
+        let target_link_path = node_modules
+            .join(".volt")
+            .join(package.directory_name())
+            .join("node_modules")
+            .join(name);
+
+        link_directory(&dependency_link_path, &target_link_path)?;
+    }
+
+    Ok(())
+}
+
+/// Create the shims for a package's `bin` field in `bin_dir`, pointing into the package's
+/// `directory`.
+pub fn link_package_bins(
+    package: &VoltPackage,
+    directory: &Path,
+    bin_dir: &Path,
+) -> miette::Result<()> {
+    if let Some(bin) = &package.bin {
+        link_bins(bin_dir, directory, &package.name, bin)?;
+    }
+
+    Ok(())
+}
+
+/// Write the files of a package in the store (its file index, from
+/// [`verify_existing_installation`]) into each of `directories`.
+pub async fn copy_from_store(
+    config: &VoltConfig,
+    package: &VoltPackage,
+    index: Vec<u8>,
+    directories: &[PathBuf],
+) -> Result<()> {
+    tracing::debug!("{}@{} is in the store", package.name, package.version);
+
+    let cas_file_map: Vec<(PathBuf, Integrity)> =
+        serde_json::from_slice::<HashMap<PathBuf, Integrity>>(&index)
+            .into_diagnostic()?
+            .into_par_iter()
+            .map(|(k, v)| (k, v))
+            .collect();
+
+    // Add package's directory to list of created directories
+    let created_directories: Vec<PathBuf> = vec![];
+
+    let mut handles = vec![];
+
+    for package_path in directories {
+        for chunk in cas_file_map.chunks(6) {
+            let config_instance = config.clone();
+            let package_path_instance = package_path.clone();
+            let mut created_directories_instance = created_directories.clone();
+
+            let chunk_instance = chunk.to_vec();
+
+            handles.push(tokio::task::spawn_blocking(move || {
+                for (name, hash) in chunk_instance.clone() {
+                    let contents = cacache::read_hash_sync(config_instance.clone().store()?, &hash)
+                        .into_diagnostic()?;
+
+                    let file_path = long_path(&package_path_instance.join(&name));
+
+                    // If we haven't created this directory yet, create it
+                    if !created_directories_instance
+                        .clone()
+                        .iter()
+                        .any(|p| p == &file_path)
+                    {
+                        if let Some(value) = name.parent() {
+                            created_directories_instance.push(file_path.to_path_buf());
+                            let directory = long_path(&package_path_instance.join(value));
+
+                            std::fs::create_dir_all(&directory).map_err(|e| {
+                                FilesystemError::CreateDir {
+                                    source: e,
+                                    path: directory.display().to_string(),
+                                }
+                            })?;
+                        }
+                    }
+
+                    // Write the contents to node_modules
+                    let write_error = |e| FilesystemError::Write {
+                        source: e,
+                        path: file_path.display().to_string(),
+                    };
+
+                    let mut file = std::fs::File::create(&file_path).map_err(write_error)?;
+
+                    file.write_all(&contents).map_err(write_error)?;
+                }
+
+                Ok(()) as Result<()>
+            }));
+        }
+    }
+
+    for handle in handles {
+        handle.await.into_diagnostic()??;
+    }
+
+    Ok(())
+}
+
+/// The tarball of a package that isn't in the store: from the registry (or the
+/// configured source), or built from git or a local directory.
+pub async fn fetch_package_tarball(
+    config: &VoltConfig,
+    package: &VoltPackage,
+    state: &State,
+) -> Result<Tarball> {
+    if package.is_git() {
+        Ok(Tarball::from(
+            fetch_git_tarball(config, &package.tarball).await?,
+        ))
+    } else if let Some(path) = package.tarball.strip_prefix("file:") {
+        Ok(Tarball::from(local_tarball(config, Path::new(path))?))
+    } else {
+        config.source().tarball(config, package, state).await
+    }
+}
+
+/// Check a tarball against the integrity of its package; git packages are pinned by
+/// their commit instead.
+pub fn verify_package_tarball(package: &VoltPackage, tarball: &Tarball) -> Result<()> {
+    if package.is_git() {
+        return Ok(());
+    }
+
+    if let Some(actual) = tarball.verify(&package.integrity)? {
+        return Err(IntegrityError::Checksum {
+            package: format!("{}@{}", package.name, package.version),
+            tarball: package.tarball.clone(),
+            expected: package.integrity.clone(),
+            actual: actual.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Decompress a verified tarball and extract it into the store and each of
+/// `directories`.
+pub fn extract_package_tarball(
+    config: &VoltConfig,
+    package: &VoltPackage,
+    tarball: &Tarball,
+    directories: &[PathBuf],
+) -> Result<()> {
+    extract_tarball(tarball.unpacked()?, package, directories, config)
+}
@@ -14,7 +14,7 @@
     limitations under the License.
 */
 
-use super::errors::VoltError;
+use super::errors::{FilesystemError, VoltError};
 
 use miette::{IntoDiagnostic, Result};
 use package_spec::PackageSpec;
@@ -32,7 +32,7 @@ use std::{
 
 // TODO: consolidate this code. will require extensive testing of other parts of the codebase
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct NpmPackage {
     #[serde(rename = "_id")]
     pub id: String,
@@ -40,31 +40,47 @@ pub struct NpmPackage {
     pub rev: Option<String>,
     pub name: String,
     #[serde(rename = "dist-tags")]
-    pub dist_tags: HashMap<String, String>,
+    pub dist_tags: DistTags,
     pub versions: HashMap<String, Version>,
     pub time: HashMap<String, String>,
     pub maintainers: Vec<Maintainer>,
     pub description: Option<String>,
     pub homepage: Option<String>,
-    pub repository: Option<Repository>,
-    pub author: Option<Author>,
+    pub repository: Option<NewRepository>,
+    pub author: Option<NewAuthor>,
     pub keywords: Option<Vec<String>>,
-    pub bugs: Option<Bugs>,
+    pub bugs: Option<NewBugs>,
     pub license: Option<String>,
     pub readme: Option<String>,
 }
 
-// #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-// #[serde(default, rename_all = "camelCase")]
-// pub struct DistTags {
-//     pub latest: String,
-//     pub stable: Option<String>,
-//     pub canary: Option<String>,
-//     pub dev: Option<String>,
-//     pub beta: Option<String>,
-//     pub alpha: Option<String>,
-//     pub experimental: Option<String>,
-// }
+/// The dist-tags of a package: `latest`, and whichever others (`next`, `beta`, `canary`)
+/// its publishers added, each pointing to a published version.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DistTags(BTreeMap<String, String>);
+
+impl DistTags {
+    /// The version `latest` points to, the one installed without a range.
+    pub fn latest(&self) -> Option<&String> {
+        self.get("latest")
+    }
+
+    /// The version `tag` points to.
+    pub fn get(&self, tag: &str) -> Option<&String> {
+        self.0.get(tag)
+    }
+
+    /// Point `tag` to `version`.
+    pub fn insert(&mut self, tag: impl Into<String>, version: impl Into<String>) {
+        self.0.insert(tag.into(), version.into());
+    }
+
+    /// Every tag and its version, by tag.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+}
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename_all = "camelCase")]
@@ -77,11 +93,19 @@ pub struct Version {
     #[serde(rename = "jsnext:main")]
     pub jsnext_main: String,
     pub scripts: Scripts,
+    pub bin: Option<NewBin>,
     pub dependencies: HashMap<String, String>,
     pub peer_dependencies: HashMap<String, String>,
     pub dev_dependencies: HashMap<String, String>,
+    pub optional_dependencies: HashMap<String, String>,
+    /// Operating systems the package runs on (`["darwin"]`, `["!win32"]`)
+    pub os: Vec<String>,
+    /// Cpu architectures the package runs on (`["x64", "arm64"]`)
+    pub cpu: Vec<String>,
+    /// C libraries the package links against on linux (`["glibc"]`, `["musl"]`)
+    pub libc: Vec<String>,
     pub git_head: String,
-    pub bugs: Bugs,
+    pub bugs: Option<NewBugs>,
     pub homepage: String,
     #[serde(rename = "_id")]
     pub id: String,
@@ -103,23 +127,17 @@ pub struct Version {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename_all = "camelCase")]
-pub struct Author {
-    pub name: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(default, rename_all = "camelCase")]
-pub struct Repository {
-    #[serde(rename = "type")]
-    pub type_field: String,
-    pub url: String,
+pub struct Engines {
+    pub node: String,
+    pub npm: String,
 }
 
+/// What `peerDependenciesMeta` says of a peer dependency.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename_all = "camelCase")]
-pub struct Engines {
-    pub node: String,
-    pub npm: String,
+pub struct PeerDependencyMeta {
+    /// The package works without the peer, so a missing one isn't a problem
+    pub optional: bool,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -135,12 +153,6 @@ pub struct Scripts {
     pub deploy: String,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(default, rename_all = "camelCase")]
-pub struct Bugs {
-    pub url: String,
-}
-
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct Dist {
@@ -151,6 +163,15 @@ pub struct Dist {
     pub unpacked_size: i64,
     #[serde(rename = "npm-signature")]
     pub npm_signature: String,
+    /// ECDSA signatures of `name@version:integrity` by the registry's keys
+    pub signatures: Vec<Signature>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Signature {
+    pub keyid: String,
+    pub sig: String,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -285,7 +306,9 @@ pub struct PackageJson {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub peer_dependencies: Option<BTreeMap<String, String>>,
-    // TODO: peerDependenciesMeta
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub peer_dependencies_meta: Option<BTreeMap<String, PeerDependencyMeta>>,
     #[serde(alias = "bundleDependencies")]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
@@ -318,9 +341,9 @@ impl PackageJson {
             let pkg_path = parent.join("package.json");
 
             if pkg_path.exists() {
-                let data = read_to_string(&pkg_path).map_err(|e| VoltError::ReadFileError {
+                let data = read_to_string(&pkg_path).map_err(|e| FilesystemError::Read {
                     source: e,
-                    name: pkg_path.to_str().unwrap().to_string(),
+                    path: pkg_path.to_str().unwrap().to_string(),
                 })?;
 
                 return Ok((
@@ -338,9 +361,9 @@ impl PackageJson {
             let pkg_path = from.join("package.json");
 
             if pkg_path.exists() {
-                let data = read_to_string(&pkg_path).map_err(|e| VoltError::ReadFileError {
+                let data = read_to_string(&pkg_path).map_err(|e| FilesystemError::Read {
                     source: e,
-                    name: pkg_path.to_str().unwrap().to_string(),
+                    path: pkg_path.to_str().unwrap().to_string(),
                 })?;
                 return Ok((
                     serde_json::from_str(data.as_str()).into_diagnostic()?,
@@ -351,6 +374,19 @@ impl PackageJson {
         miette::bail!("No package.json found!");
     }
 
+    /// Write the package.json to `path`.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let mut contents = serde_json::to_string_pretty(self).into_diagnostic()?;
+        contents.push('\n');
+
+        fs::write(path, contents).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        Ok(())
+    }
+
     pub fn _save(&self) -> Result<()> {
         let mut file = fs::File::create("package.json").into_diagnostic()?;
 
@@ -359,9 +395,9 @@ impl PackageJson {
                 .into_diagnostic()?
                 .as_bytes(),
         )
-        .map_err(|e| VoltError::WriteFileError {
+        .map_err(|e| FilesystemError::Write {
             source: e,
-            name: String::from("package.json"),
+            path: String::from("package.json"),
         })?;
 
         Ok(())
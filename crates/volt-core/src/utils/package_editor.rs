@@ -0,0 +1,637 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Edit package.json in place.
+//!
+//! Going through [`PackageJson`](super::package::PackageJson) rewrites the whole file:
+//! fields it doesn't know are lost and the user's formatting is replaced. The editor
+//! instead finds where each key is in the text and only rewrites the members it changes,
+//! matching the indentation and line endings around them. Comments and trailing commas
+//! (jsonc) are kept.
+
+use super::errors::{FilesystemError, VoltError};
+
+use miette::Result;
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Value};
+
+use std::path::{Path, PathBuf};
+
+/// The fields of package.json holding dependencies.
+pub const DEPENDENCY_FIELDS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "optionalDependencies",
+    "peerDependencies",
+];
+
+/// A package.json being edited.
+#[derive(Debug, Clone)]
+pub struct PackageJsonEditor {
+    path: PathBuf,
+    text: String,
+}
+
+impl PackageJsonEditor {
+    /// Read the package.json at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: path.display().to_string(),
+        })?;
+
+        Self::parse(path, text)
+    }
+
+    /// Edit `text`, to be saved to `path`.
+    pub fn parse(path: &Path, text: String) -> Result<Self> {
+        let editor = Self {
+            path: path.to_path_buf(),
+            text,
+        };
+
+        match editor.root() {
+            Ok(root) if root.members.is_some() => Ok(editor),
+            Ok(_) => Err(editor.invalid("the top level isn't an object".to_string())),
+            Err(message) => Err(editor.invalid(message)),
+        }
+    }
+
+    /// The value at `path` (`["dependencies", "react"]`), if there is one.
+    pub fn get(&self, path: &[&str]) -> Option<Value> {
+        let root = self.root().ok()?;
+        let node = root.find(path)?;
+
+        serde_json::from_str(&self.text[node.start..node.end]).ok()
+    }
+
+    /// Set the value at `path`, creating the objects on the way. Existing members are
+    /// rewritten where they are; new ones go in key order when the object's keys are
+    /// sorted (like dependencies), and last otherwise.
+    pub fn set(&mut self, path: &[&str], value: &Value) -> Result<()> {
+        let root = self.root().map_err(|message| self.invalid(message))?;
+
+        let mut node = &root;
+        let mut depth = 0;
+
+        for key in path {
+            match node.member(key) {
+                Some(member) => {
+                    node = &member.value;
+                    depth += 1;
+                }
+                None => break,
+            }
+        }
+
+        if depth == path.len() {
+            let indent = self.line_indent(node.start);
+            let rendered = self.render(value, &indent);
+
+            self.text.replace_range(node.start..node.end, &rendered);
+
+            return Ok(());
+        }
+
+        if node.members.is_none() {
+            return Err(self.invalid(format!("`{}` isn't an object", path[..depth].join("."))));
+        }
+
+        // the missing objects are created along with the member
+        let value = path[depth + 1..]
+            .iter()
+            .rev()
+            .fold(value.clone(), |value, key| {
+                let mut object = serde_json::Map::new();
+                object.insert(key.to_string(), value);
+                Value::Object(object)
+            });
+
+        let (position, text) = self.insertion(node, path[depth], &value);
+
+        self.text.insert_str(position, &text);
+
+        Ok(())
+    }
+
+    /// Remove the member at `path`; returns whether there was one.
+    pub fn remove(&mut self, path: &[&str]) -> Result<bool> {
+        let root = self.root().map_err(|message| self.invalid(message))?;
+
+        let (key, parent) = match path.split_last() {
+            Some((key, parent)) => (key, parent),
+            None => return Ok(false),
+        };
+
+        let object = match root.find(parent) {
+            Some(object) => object,
+            None => return Ok(false),
+        };
+
+        let members = match &object.members {
+            Some(members) => members,
+            None => return Ok(false),
+        };
+
+        let index = match members.iter().position(|member| member.key == *key) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+
+        let member = &members[index];
+
+        let range = if members.len() == 1 {
+            // `{ "only": 1 }` -> `{}`
+            object.start + 1..object.end - 1
+        } else if let Some(comma) = member.comma {
+            let start = self.line_start_if_blank(member.key_start);
+            let mut end = comma + 1;
+
+            // the whole line goes when the member had it to itself
+            if start < member.key_start {
+                let rest = &self.text[end..];
+                let spaces = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+
+                end += spaces;
+
+                if self.text[end..].starts_with("\r\n") {
+                    end += 2;
+                } else if self.text[end..].starts_with('\n') {
+                    end += 1;
+                }
+            }
+
+            start..end
+        } else {
+            // the last member takes the comma before it along
+            let comma = members[index - 1]
+                .comma
+                .unwrap_or(members[index - 1].value.end);
+
+            comma..member.value.end
+        };
+
+        self.text.replace_range(range, "");
+
+        Ok(true)
+    }
+
+    /// The edited text.
+    #[cfg(test)]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Write the edited text back.
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, &self.text).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: self.path.display().to_string(),
+        })?;
+
+        Ok(())
+    }
+
+    fn root(&self) -> Result<Node, String> {
+        let mut parser = Parser {
+            text: self.text.as_bytes(),
+            position: 0,
+        };
+
+        parser.skip_trivia()?;
+
+        let root = parser.value()?;
+
+        parser.skip_trivia()?;
+
+        if parser.position < self.text.len() {
+            return Err(parser.error("unexpected content after the top level object"));
+        }
+
+        Ok(root)
+    }
+
+    fn invalid(&self, message: String) -> miette::Report {
+        VoltError::InvalidJson {
+            path: self.path.display().to_string(),
+            message,
+        }
+        .into()
+    }
+
+    /// Where to insert `key` into `object`, and the text of the new member.
+    fn insertion(&self, object: &Node, key: &str, value: &Value) -> (usize, String) {
+        let members = object.members.as_deref().unwrap_or_default();
+        let newline = self.newline();
+        let key_json = serde_json::to_string(key).unwrap_or_default();
+
+        let first = match members.first() {
+            Some(first) => first,
+            None => {
+                let outer = self.line_indent(object.start);
+                let inner = format!("{}{}", outer, self.indent_unit());
+
+                let member = format!(
+                    "{}{}{}: {}{}{}",
+                    newline,
+                    inner,
+                    key_json,
+                    self.render(value, &inner),
+                    newline,
+                    outer
+                );
+
+                return (object.start + 1, member);
+            }
+        };
+
+        // `{ "a": 1, "b": 2 }` stays on one line
+        let inline = !self.text[object.start..first.key_start].contains('\n');
+
+        let (indent, separator) = if inline {
+            (String::new(), " ".to_string())
+        } else {
+            let indent = self.line_indent(first.key_start);
+            let separator = format!("{}{}", newline, indent);
+
+            (indent, separator)
+        };
+
+        let member = format!("{}: {}", key_json, self.render(value, &indent));
+
+        let sorted = members.windows(2).all(|pair| pair[0].key <= pair[1].key);
+
+        if let Some(next) = members
+            .iter()
+            .find(|member| sorted && member.key.as_str() > key)
+        {
+            return (next.key_start, format!("{},{}", member, separator));
+        }
+
+        let last = &members[members.len() - 1];
+
+        match last.comma {
+            // keep the trailing comma last
+            Some(comma) => (comma + 1, format!("{}{},", separator, member)),
+            None => (last.value.end, format!(",{}{}", separator, member)),
+        }
+    }
+
+    /// `value` as JSON in the file's indentation, continued lines starting at `indent`.
+    fn render(&self, value: &Value, indent: &str) -> String {
+        let unit = self.indent_unit();
+
+        let mut buffer = vec![];
+        let mut serializer = serde_json::Serializer::with_formatter(
+            &mut buffer,
+            PrettyFormatter::with_indent(unit.as_bytes()),
+        );
+
+        value
+            .serialize(&mut serializer)
+            .expect("Valid serialization state");
+
+        let rendered = String::from_utf8(buffer).unwrap_or_default();
+
+        rendered.replace('\n', &format!("{}{}", self.newline(), indent))
+    }
+
+    /// The indentation of the file: that of its first indented line, or two spaces.
+    fn indent_unit(&self) -> String {
+        self.text
+            .lines()
+            .map(|line| {
+                let content = line.trim_start_matches([' ', '\t']);
+
+                (&line[..line.len() - content.len()], content)
+            })
+            .find(|(indent, content)| !indent.is_empty() && !content.is_empty())
+            .map_or_else(|| "  ".to_string(), |(indent, _)| indent.to_string())
+    }
+
+    fn newline(&self) -> &'static str {
+        if self.text.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    /// The whitespace the line holding `position` starts with.
+    fn line_indent(&self, position: usize) -> String {
+        let start = self.text[..position]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let line = &self.text[start..];
+
+        line[..line.len() - line.trim_start_matches([' ', '\t']).len()].to_string()
+    }
+
+    /// The start of the line holding `position` if only whitespace precedes it there.
+    fn line_start_if_blank(&self, position: usize) -> usize {
+        let start = self.text[..position]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+
+        if self.text[start..position].trim().is_empty() {
+            start
+        } else {
+            position
+        }
+    }
+}
+
+/// A value in the text, with its members if it's an object.
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    members: Option<Vec<Member>>,
+}
+
+#[derive(Debug)]
+struct Member {
+    key: String,
+    key_start: usize,
+    value: Node,
+    /// Position of the comma after the member, if there's one
+    comma: Option<usize>,
+}
+
+impl Node {
+    fn member(&self, key: &str) -> Option<&Member> {
+        self.members
+            .as_ref()?
+            .iter()
+            .find(|member| member.key == key)
+    }
+
+    fn find(&self, path: &[&str]) -> Option<&Node> {
+        path.iter()
+            .try_fold(self, |node, key| Some(&node.member(key)?.value))
+    }
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self) -> Result<Node, String> {
+        let start = self.position;
+
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => {
+                self.string()?;
+
+                Ok(Node {
+                    start,
+                    end: self.position,
+                    members: None,
+                })
+            }
+            Some(_) => {
+                while let Some(byte) = self.peek() {
+                    if matches!(byte, b',' | b']' | b'}' | b'/') || byte.is_ascii_whitespace() {
+                        break;
+                    }
+
+                    self.position += 1;
+                }
+
+                let literal = std::str::from_utf8(&self.text[start..self.position]).unwrap_or("");
+
+                match serde_json::from_str::<Value>(literal) {
+                    Ok(Value::Bool(_) | Value::Null | Value::Number(_)) => Ok(Node {
+                        start,
+                        end: self.position,
+                        members: None,
+                    }),
+                    _ => {
+                        self.position = start;
+
+                        Err(self.error("expected a value"))
+                    }
+                }
+            }
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Node, String> {
+        let start = self.position;
+        let mut members = vec![];
+
+        self.position += 1;
+        self.skip_trivia()?;
+
+        while self.peek() != Some(b'}') {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a key"));
+            }
+
+            let key_start = self.position;
+            let key = self.string()?;
+
+            self.skip_trivia()?;
+            self.expect(b':')?;
+            self.skip_trivia()?;
+
+            let value = self.value()?;
+
+            self.skip_trivia()?;
+
+            let comma = (self.peek() == Some(b',')).then(|| self.position);
+
+            members.push(Member {
+                key,
+                key_start,
+                value,
+                comma,
+            });
+
+            match self.peek() {
+                Some(b',') => {
+                    self.position += 1;
+                    self.skip_trivia()?;
+                }
+                Some(b'}') => {}
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+
+        self.position += 1;
+
+        Ok(Node {
+            start,
+            end: self.position,
+            members: Some(members),
+        })
+    }
+
+    fn array(&mut self) -> Result<Node, String> {
+        let start = self.position;
+
+        self.position += 1;
+        self.skip_trivia()?;
+
+        while self.peek() != Some(b']') {
+            self.value()?;
+            self.skip_trivia()?;
+
+            match self.peek() {
+                Some(b',') => {
+                    self.position += 1;
+                    self.skip_trivia()?;
+                }
+                Some(b']') => {}
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+
+        self.position += 1;
+
+        Ok(Node {
+            start,
+            end: self.position,
+            members: None,
+        })
+    }
+
+    /// Read a string, returning its value.
+    fn string(&mut self) -> Result<String, String> {
+        let start = self.position;
+
+        self.position += 1;
+
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.position += 2,
+                Some(b'"') => break,
+                Some(_) => self.position += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+
+        self.position += 1;
+
+        let literal = std::str::from_utf8(&self.text[start..self.position]).unwrap_or("");
+
+        serde_json::from_str(literal).map_err(|_| {
+            self.position = start;
+            self.error("invalid string")
+        })
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            match (self.peek(), self.text.get(self.position + 1)) {
+                (Some(byte), _) if byte.is_ascii_whitespace() => self.position += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while !matches!(self.peek(), Some(b'\n') | None) {
+                        self.position += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    let end = self.text[self.position + 2..]
+                        .windows(2)
+                        .position(|pair| pair == b"*/")
+                        .ok_or_else(|| self.error("unterminated comment"))?;
+
+                    self.position += end + 4;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.position).copied()
+    }
+
+    fn error(&self, message: &str) -> String {
+        let before = &self.text[..self.position.min(self.text.len())];
+        let line = before.iter().filter(|&&byte| byte == b'\n').count() + 1;
+        let column = before
+            .iter()
+            .rev()
+            .take_while(|&&byte| byte != b'\n')
+            .count()
+            + 1;
+
+        format!("{} at line {} column {}", message, line, column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PackageJsonEditor;
+
+    use serde_json::json;
+    use std::path::Path;
+
+    fn editor(text: &str) -> PackageJsonEditor {
+        PackageJsonEditor::parse(Path::new("package.json"), text.to_string()).unwrap()
+    }
+
+    #[test]
+    fn edits_only_the_members_it_touches() {
+        let mut editor = editor(
+            "{\n    \"name\": \"app\", // the app\n    \"custom\": { \"kept\": true },\n    \"dependencies\": {\n        \"a\": \"^1.0.0\",\n        \"c\": \"^3.0.0\"\n    }\n}\n",
+        );
+
+        editor
+            .set(&["dependencies", "b"], &json!("^2.0.0"))
+            .unwrap();
+        editor
+            .set(&["dependencies", "c"], &json!("^3.1.0"))
+            .unwrap();
+        editor.remove(&["dependencies", "a"]).unwrap();
+        editor
+            .set(&["devDependencies", "d"], &json!("^4.0.0"))
+            .unwrap();
+
+        assert_eq!(
+            editor.text(),
+            "{\n    \"name\": \"app\", // the app\n    \"custom\": { \"kept\": true },\n    \"dependencies\": {\n        \"b\": \"^2.0.0\",\n        \"c\": \"^3.1.0\"\n    },\n    \"devDependencies\": {\n        \"d\": \"^4.0.0\"\n    }\n}\n"
+        );
+
+        editor.remove(&["devDependencies", "d"]).unwrap();
+        editor.remove(&["devDependencies"]).unwrap();
+        editor.set(&["custom", "other"], &json!(1)).unwrap();
+
+        assert_eq!(
+            editor.text(),
+            "{\n    \"name\": \"app\", // the app\n    \"custom\": { \"kept\": true, \"other\": 1 },\n    \"dependencies\": {\n        \"b\": \"^2.0.0\",\n        \"c\": \"^3.1.0\"\n    }\n}\n"
+        );
+
+        assert_eq!(editor.get(&["dependencies", "c"]), Some(json!("^3.1.0")));
+    }
+}
@@ -0,0 +1,311 @@
+/*
+    Copyright 2021 Volt Contributors
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+        http://www.apache.org/licenses/LICENSE-2.0
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+use super::{
+    errors::ResolutionError,
+    package::{NewBin, PackageJson, PeerDependencyMeta},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use speedy::{Readable, Writable};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// The `peer_dependencies_meta` entry of an optional peer, the entry of package.json as
+/// json.
+pub const OPTIONAL_PEER: &str = r#"{"optional":true}"#;
+
+#[derive(Debug, Clone, Writable, Readable)]
+pub struct VoltResponse {
+    #[speedy(skip)]
+    pub name: String,
+    pub version: String,                    // the latest version of the package
+    pub versions: Vec<String>,              // list of versions of the package
+    pub tree: HashMap<String, VoltPackage>, // the flattened dependency tree for the latest version of the package <name@version, data>
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Writable, Readable)]
+pub struct VoltPackage {
+    pub name: String,                                       // the name of the package
+    pub version: String,                                    // the version of the package
+    pub optional: bool,    // whether the package is optional or not
+    pub integrity: String, // sha-1 base64 encoded hash or the "integrity" field if it exists
+    pub tarball: String,   // url to the tarball to fetch
+    pub bin: Option<Bin>,  // binary scripts required by / for the package
+    pub scripts: Option<HashMap<String, String>>, // scripts required by / for the package
+    pub dependencies: Option<HashMap<String, String>>, // dependencies of the package
+    pub peer_dependencies: Option<HashMap<String, String>>, // peer dependencies of the package
+    pub peer_dependencies_meta: Option<HashMap<String, String>>, // peer dependencies metadata of the package
+    pub optional_dependencies: Option<HashMap<String, String>>, // optional dependencies of the package
+    pub overrides: Option<HashMap<String, String>>,             // overrides specific to the package
+    pub engines: Option<Engine>,  // engines compatible with the package
+    pub os: Option<Vec<String>>,  // operating systems compatible with the package
+    pub cpu: Option<Vec<String>>, // cpu architectures compatible with the package
+    #[speedy(skip)]
+    #[serde(default)]
+    pub libc: Option<Vec<String>>, // c libraries (glibc, musl) compatible with the package
+    #[speedy(skip)]
+    #[serde(default)]
+    pub remote: bool, // installed straight from a tarball url rather than from the registry
+}
+
+impl VoltPackage {
+    /// The package described by a package.json that isn't published to the registry, with
+    /// `tarball` recording where it comes from (`git+...`, `file:...`, `link:...`, a url).
+    pub fn from_manifest(package_json: &PackageJson, tarball: String) -> Self {
+        Self {
+            name: package_json.name.clone(),
+            version: package_json.version.clone(),
+            optional: false,
+            integrity: String::new(),
+            tarball,
+            bin: package_json.bin.clone().map(Into::into),
+            scripts: package_json
+                .scripts
+                .clone()
+                .map(|scripts| scripts.into_iter().collect()),
+            dependencies: None,
+            peer_dependencies: package_json
+                .peer_dependencies
+                .clone()
+                .map(|peers| peers.into_iter().collect()),
+            peer_dependencies_meta: package_json.peer_dependencies_meta.as_ref().map(|meta| {
+                meta.iter()
+                    .filter(|(_, meta)| meta.optional)
+                    .map(|(name, _)| (name.clone(), OPTIONAL_PEER.to_string()))
+                    .collect()
+            }),
+            optional_dependencies: None,
+            overrides: None,
+            engines: None,
+            os: package_json.os.clone(),
+            cpu: package_json.cpu.clone(),
+            libc: None,
+            remote: false,
+        }
+    }
+
+    /// The key of the package in the tree, the lock file and the store: `name@version`,
+    /// with the version it's depended on by (see [`resolved_version`]).
+    pub fn key(&self) -> String {
+        format!("{}@{}", self.name, self.resolved_version())
+    }
+
+    /// The version dependents record for the package.
+    pub fn resolved_version(&self) -> String {
+        resolved_version(&self.version, &self.tarball, self.remote)
+    }
+
+    pub fn directory_name(&self) -> String {
+        self.key().replace('/', "+")
+    }
+
+    /// The dependencies of the package by the name they are installed as (see
+    /// [`dependency_name`]), with their versions.
+    pub fn installed_dependencies(&self) -> impl Iterator<Item = (&str, &String)> {
+        self.dependencies
+            .iter()
+            .flatten()
+            .map(|(name, version)| (dependency_name(name, version), version))
+    }
+
+    /// Path to the package's extracted contents inside of the `.volt` virtual store
+    /// (`node_modules/.volt/send@0.17.2/node_modules/send`)
+    pub fn install_directory(&self, node_modules: &Path) -> PathBuf {
+        // linked packages stay where they are (`link:../foo` is relative to the project)
+        if let Some(path) = self.tarball.strip_prefix("link:") {
+            return node_modules.parent().unwrap_or(node_modules).join(path);
+        }
+
+        node_modules
+            .join(".volt")
+            .join(self.directory_name())
+            .join("node_modules")
+            .join(&self.name)
+    }
+
+    /// Whether the package is built from a git repository (`git+https://...#<sha>`) rather
+    /// than downloaded from the registry.
+    pub fn is_git(&self) -> bool {
+        self.tarball.starts_with("git+")
+    }
+
+    /// Whether the package is a symlink to a local directory (`link:../foo`).
+    pub fn is_link(&self) -> bool {
+        self.tarball.starts_with("link:")
+    }
+
+    /// Whether the package comes from the registry rather than git, the filesystem or a
+    /// tarball url.
+    pub fn is_registry(&self) -> bool {
+        is_registry(&self.tarball, self.remote)
+    }
+
+    /// Whether the package works without its peer dependency `name`
+    /// (`"peerDependenciesMeta": { "name": { "optional": true } }`).
+    pub fn is_optional_peer(&self, name: &str) -> bool {
+        self.peer_dependencies_meta
+            .as_ref()
+            .and_then(|meta| meta.get(name))
+            .and_then(|meta| serde_json::from_str::<PeerDependencyMeta>(meta).ok())
+            .map_or(false, |meta| meta.optional)
+    }
+
+    pub fn cacache_key(&self) -> String {
+        // packages built from git have no integrity, the commit in the url pins them instead
+        let integrity = if self.is_git() {
+            &self.tarball
+        } else {
+            &self.integrity
+        };
+
+        format!("pkg::{}::{}::{}", self.name, self.version, integrity)
+    }
+}
+
+/// Whether a package with this `tarball` comes from the registry rather than git, the
+/// filesystem (`file:`, `link:`) or a tarball url (`remote`).
+pub fn is_registry(tarball: &str, remote: bool) -> bool {
+    !remote
+        && !["git+", "file:", "link:"]
+            .iter()
+            .any(|prefix| tarball.starts_with(prefix))
+}
+
+/// The version a package is keyed and depended on by: registry packages go by their
+/// version, the others by their version followed by a hash of where they come from
+/// (`1.0.0+2f0c9a1e5d3b7c64`), as two commits or directories can hold the same version.
+pub fn resolved_version(version: &str, tarball: &str, remote: bool) -> String {
+    if is_registry(tarball, remote) {
+        return version.to_string();
+    }
+
+    let source = hex::encode(Sha256::digest(tarball.as_bytes()));
+
+    // the hash goes in the build metadata, which a version may already have
+    let separator = if version.contains('+') { '.' } else { '+' };
+
+    format!("{}{}{}", version, separator, &source[..16])
+}
+
+/// The package a dependency edge points at, following aliases: `bar -> 1.0.0` is
+/// `bar@1.0.0` and `foo -> npm:bar@1.0.0` is `bar@1.0.0` linked as `foo`. Fails on
+/// aliases without a package (`foo -> npm:`).
+pub fn dependency_target<'a>(
+    name: &'a str,
+    version: &'a str,
+) -> Result<(&'a str, &'a str), ResolutionError> {
+    let target = match version.strip_prefix("npm:") {
+        Some(target) => target,
+        None => return Ok((name, version)),
+    };
+
+    // skip the `@` of scoped packages
+    let index = match target.get(1..).and_then(|rest| rest.find('@')) {
+        Some(index) => index + 1,
+        // `npm:bar` stands for any version of bar
+        None if !target.is_empty() && target != "@" => return Ok((name, version)),
+        None => {
+            return Err(ResolutionError::InvalidAlias {
+                name: name.to_string(),
+                spec: version.to_string(),
+            })
+        }
+    };
+
+    Ok((&target[..index], &target[index + 1..]))
+}
+
+#[cfg(test)]
+impl VoltPackage {
+    /// A registry package depending on `dependencies` (`name -> version`), for tests.
+    pub(crate) fn test(name: &str, version: &str, dependencies: &[(&str, &str)]) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            optional: false,
+            integrity: String::new(),
+            tarball: String::new(),
+            bin: None,
+            scripts: None,
+            dependencies: Some(
+                dependencies
+                    .iter()
+                    .map(|(name, version)| (name.to_string(), version.to_string()))
+                    .collect(),
+            ),
+            peer_dependencies: None,
+            peer_dependencies_meta: None,
+            optional_dependencies: None,
+            overrides: None,
+            engines: None,
+            os: None,
+            cpu: None,
+            libc: None,
+            remote: false,
+        }
+    }
+}
+
+/// The name a dependency of a [`VoltPackage`] is installed as: the names of its
+/// `dependencies` are sometimes stored as `name@version`.
+pub fn dependency_name<'a>(name: &'a str, version: &str) -> &'a str {
+    name.strip_suffix(&format!("@{}", version)).unwrap_or(name)
+}
+
+/// The `name@version` key of the package a dependency edge points at; edges that point at
+/// nothing have keys no package has.
+pub fn dependency_key(name: &str, version: &str) -> String {
+    let (name, version) = dependency_target(name, version).unwrap_or((name, version));
+
+    format!("{}@{}", name, version)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Readable, Writable)]
+#[serde(untagged)]
+pub enum Engine {
+    String(String),
+    List(Vec<String>),
+    Map(HashMap<String, String>),
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::String(String::new())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Readable, Writable)]
+#[serde(untagged)]
+pub enum Bin {
+    String(String),
+    Map(HashMap<String, String>),
+}
+
+impl From<NewBin> for Bin {
+    fn from(bin: NewBin) -> Self {
+        match bin {
+            NewBin::Str(path) => Bin::String(path),
+            NewBin::BTreeMap(map) => Bin::Map(map.into_iter().collect()),
+        }
+    }
+}
+
+impl Default for Bin {
+    fn default() -> Self {
+        Self::String(String::new())
+    }
+}
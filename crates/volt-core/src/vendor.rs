@@ -0,0 +1,259 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Copies of the registry tarballs of a project, to install it without the registry.
+//!
+//! `volt vendor` writes the tarball of every registry package of the lock file into
+//! `./vendor` (`<name>-<version>.tgz`, `+` for the `/` of scopes) and removes the ones
+//! nothing uses anymore, so the directory can be checked in or archived next to the
+//! project. With `vendored = true` in `.voltrc`, or `--vendored`, registry packages are
+//! then resolved and downloaded only from that directory: nothing reaches the registry,
+//! and a package that isn't vendored fails the install. Git and local packages are still
+//! fetched from where they are.
+
+use crate::{
+    config::VoltConfig,
+    hashing::verify_reader,
+    install::Resolution,
+    source::TarballDirectory,
+    utils::{
+        errors::{FilesystemError, IntegrityError},
+        voltapi::VoltPackage,
+        State,
+    },
+};
+
+use futures::{stream, StreamExt};
+use miette::Result;
+
+use std::{collections::HashSet, path::Path};
+
+/// Directory of the vendored tarballs, in the project.
+pub const VENDOR_DIRECTORY: &str = "vendor";
+
+/// What [`vendor`] changed in the directory.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VendorReport {
+    /// Tarballs downloaded into the directory
+    pub added: usize,
+    /// Tarballs that were already there
+    pub kept: usize,
+    /// Tarballs of packages no longer in the resolution, removed
+    pub removed: usize,
+}
+
+/// Write the tarball of every registry package of `resolution` into `directory`, from
+/// the source of `config`, and remove the tarballs of other packages.
+pub async fn vendor(
+    config: &VoltConfig,
+    resolution: &Resolution,
+    directory: &Path,
+) -> Result<VendorReport> {
+    std::fs::create_dir_all(directory).map_err(|e| FilesystemError::CreateDir {
+        source: e,
+        path: directory.display().to_string(),
+    })?;
+
+    // git, local and tarball url packages aren't served by a registry
+    let packages: Vec<&VoltPackage> = resolution
+        .tree
+        .values()
+        .filter(|package| !package.remote && package.tarball.starts_with("http"))
+        .collect();
+
+    let reporter = config.reporter();
+    let client = config.http_client()?;
+
+    let copies = packages.iter().map(|package| {
+        let state = State {
+            http_client: client.clone(),
+            reporter: reporter.clone(),
+        };
+
+        async move { vendor_package(config, package, directory, &state).await }
+    });
+
+    let results = stream::iter(copies)
+        .buffer_unordered(config.settings().concurrency)
+        .collect::<Vec<_>>()
+        .await;
+
+    reporter.finish();
+
+    let mut report = VendorReport::default();
+
+    for added in results {
+        if added? {
+            report.added += 1;
+        } else {
+            report.kept += 1;
+        }
+    }
+
+    let vendored: HashSet<String> = packages
+        .iter()
+        .map(|package| TarballDirectory::file_name(package))
+        .collect();
+
+    let read_error = |e| FilesystemError::Read {
+        source: e,
+        path: directory.display().to_string(),
+    };
+
+    for entry in std::fs::read_dir(directory).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+
+        let stale = path
+            .extension()
+            .map_or(false, |extension| extension == "tgz")
+            && path
+                .file_name()
+                .map_or(false, |name| !vendored.contains(&*name.to_string_lossy()));
+
+        if stale {
+            std::fs::remove_file(&path).map_err(|e| FilesystemError::Remove {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+
+            report.removed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Write the tarball of `package` into `directory` unless an intact one is already there;
+/// whether it was written.
+async fn vendor_package(
+    config: &VoltConfig,
+    package: &VoltPackage,
+    directory: &Path,
+    state: &State,
+) -> Result<bool> {
+    let path = directory.join(TarballDirectory::file_name(package));
+
+    if let Ok(mut existing) = std::fs::File::open(&path) {
+        if verify_reader(&mut existing, &package.integrity)?.is_none() {
+            return Ok(false);
+        }
+    }
+
+    let tarball = config.source().tarball(config, package, state).await?;
+
+    if let Some(actual) = tarball.verify(&package.integrity)? {
+        return Err(IntegrityError::Checksum {
+            package: format!("{}@{}", package.name, package.version),
+            tarball: package.tarball.clone(),
+            expected: package.integrity.clone(),
+            actual: actual.to_string(),
+        }
+        .into());
+    }
+
+    // written aside and renamed, so an interrupted run never leaves half a tarball
+    let partial = path.with_extension("tgz.partial");
+
+    let write_error = |e| FilesystemError::Write {
+        source: e,
+        path: path.display().to_string(),
+    };
+
+    tarball.write_to(&partial)?;
+    std::fs::rename(&partial, &path).map_err(write_error)?;
+
+    state
+        .reporter
+        .downloaded(&format!("{}@{}", package.name, package.version));
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vendor;
+    use crate::{
+        config::VoltConfig,
+        install::Resolution,
+        io::pack_files,
+        source::{FixtureSource, PackageSource, TarballDirectory},
+    };
+
+    use futures::executor::block_on;
+    use std::path::PathBuf;
+
+    fn tarball(manifest: serde_json::Value) -> Vec<u8> {
+        let directory = tempfile::tempdir().unwrap();
+
+        std::fs::write(directory.path().join("package.json"), manifest.to_string()).unwrap();
+
+        pack_files(directory.path(), &[PathBuf::from("package.json")]).unwrap()
+    }
+
+    #[test]
+    fn vendors_the_registry_tarballs_of_a_resolution() {
+        let mut fixtures = FixtureSource::default();
+
+        fixtures
+            .add(tarball(serde_json::json!({
+                "name": "@scope/a",
+                "version": "1.0.0",
+                "dependencies": { "ms": "^2.0.0" },
+            })))
+            .unwrap()
+            .add(tarball(
+                serde_json::json!({ "name": "ms", "version": "2.1.3" }),
+            ))
+            .unwrap();
+
+        let mut config = VoltConfig::default();
+        config.set_source(fixtures.clone());
+
+        let response = block_on(fixtures.resolve(&config, &"@scope/a".parse().unwrap())).unwrap();
+
+        let resolution = Resolution {
+            direct: vec![format!("{}@{}", response.name, response.version)],
+            tree: response.tree,
+            ..Resolution::default()
+        };
+
+        let directory = tempfile::tempdir().unwrap();
+
+        std::fs::write(directory.path().join("left-pad-1.3.0.tgz"), "").unwrap();
+        std::fs::write(directory.path().join("README.md"), "").unwrap();
+
+        let report = block_on(vendor(&config, &resolution, directory.path())).unwrap();
+
+        assert_eq!((report.added, report.kept, report.removed), (2, 0, 1));
+        assert!(directory.path().join("@scope+a-1.0.0.tgz").exists());
+        assert!(directory.path().join("ms-2.1.3.tgz").exists());
+        assert!(directory.path().join("README.md").exists());
+
+        let again = block_on(vendor(&config, &resolution, directory.path())).unwrap();
+
+        assert_eq!((again.added, again.kept, again.removed), (0, 2, 0));
+
+        // installs resolve the same tree from the directory alone
+        let vendored = TarballDirectory::open(directory.path()).unwrap();
+        let offline = block_on(vendored.resolve(&config, &"@scope/a".parse().unwrap())).unwrap();
+
+        assert_eq!(offline.tree.len(), 2);
+        assert_eq!(
+            offline.tree["ms@2.1.3"].integrity,
+            resolution.tree["ms@2.1.3"].integrity
+        );
+    }
+}
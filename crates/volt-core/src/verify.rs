@@ -0,0 +1,385 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check that package.json, volt.lock and `node_modules` agree, without changing any of
+//! them.
+//!
+//! The dependencies of package.json have to be in volt.lock with everything they depend
+//! on, and `node_modules` has to hold what volt.lock does: every package where the layout
+//! puts it, with the files of the store, nothing else, and a `.bin` shim only for the
+//! commands of the packages installed.
+
+use crate::{
+    config::VoltConfig,
+    install::{project_dependencies, InstallScope, Resolution},
+    linker::linker,
+    model::lock_file::LockFile,
+    patches::read_patches,
+    prune::{leftovers, shim_command},
+    shim::bin_entries,
+    utils::{damaged_files, errors::ResolutionError, voltapi::dependency_key},
+};
+
+use miette::{IntoDiagnostic, Result};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// What kind of disagreement a [`Problem`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProblemKind {
+    /// A dependency, or a dependency of a locked package, isn't in volt.lock
+    Unlocked,
+    /// A locked package isn't in `node_modules`
+    Missing,
+    /// Files of a package in `node_modules` are missing or differ from the store
+    Integrity,
+    /// `node_modules` has a package volt.lock doesn't
+    Extraneous,
+    /// A `.bin` shim points to a file that isn't there
+    DanglingBin,
+}
+
+impl fmt::Display for ProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Self::Unlocked => "unlocked",
+            Self::Missing => "missing",
+            Self::Integrity => "integrity",
+            Self::Extraneous => "extraneous",
+            Self::DanglingBin => "dangling-bin",
+        };
+
+        write!(f, "{}", kind)
+    }
+}
+
+/// One disagreement between package.json, volt.lock and `node_modules`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Problem {
+    pub kind: ProblemKind,
+    /// The package (`name@version`, or `name@range` for dependencies that aren't locked),
+    /// or the path relative to `node_modules`
+    pub subject: String,
+    pub detail: String,
+}
+
+impl Problem {
+    fn new(kind: ProblemKind, subject: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            kind,
+            subject: subject.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Every disagreement in the project of `config`, sorted by kind. `node_modules` is only
+/// looked at once volt.lock has every dependency.
+pub fn verify_project(config: &VoltConfig) -> Result<Vec<Problem>> {
+    let (dependencies, workspace) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+    let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+    let mut problems = unlocked(&lock_file, &dependencies);
+
+    // what node_modules should hold isn't known
+    if !problems.is_empty() {
+        return Ok(problems);
+    }
+
+    let resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+        ResolutionError::LockFileOutdated {
+            path: config.lockfile()?.display().to_string(),
+        },
+    )?;
+
+    let node_modules = config.node_modules()?;
+
+    if !node_modules.is_dir() {
+        if !resolution.tree.is_empty() {
+            problems.push(Problem::new(
+                ProblemKind::Missing,
+                "node_modules",
+                "the project isn't installed",
+            ));
+        }
+
+        return Ok(problems);
+    }
+
+    problems.extend(damaged(config, &resolution)?);
+
+    let bin_dir = node_modules.join(".bin");
+
+    problems.extend(
+        leftovers(config, &resolution, workspace.as_ref())?
+            .into_iter()
+            // shims are checked against the commands of the packages
+            .filter(|path| !path.starts_with(&bin_dir))
+            .map(|path| {
+                Problem::new(
+                    ProblemKind::Extraneous,
+                    relative(&node_modules, &path),
+                    "volt.lock doesn't have it",
+                )
+            }),
+    );
+
+    problems.extend(dangling_bins(config, &resolution)?);
+
+    problems.sort();
+
+    Ok(problems)
+}
+
+/// The `dependencies` (`name -> range`) volt.lock doesn't have, and the dependencies of
+/// locked packages it doesn't have either.
+pub fn unlocked(lock_file: &LockFile, dependencies: &BTreeMap<String, String>) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    // (key, the package depending on it)
+    let mut stack: Vec<(String, String)> = vec![];
+
+    for (name, range) in dependencies {
+        match lock_file.find(name, range) {
+            Some(package) => {
+                stack.push((
+                    format!("{}@{}", package.name, package.version),
+                    "package.json".to_string(),
+                ));
+            }
+            None => problems.push(Problem::new(
+                ProblemKind::Unlocked,
+                format!("{}@{}", name, range),
+                "package.json depends on it",
+            )),
+        }
+    }
+
+    let mut seen = HashSet::new();
+
+    while let Some((key, dependent)) = stack.pop() {
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+
+        let package = match lock_file.dependencies.get(&key) {
+            Some(package) => package,
+            None => {
+                problems.push(Problem::new(
+                    ProblemKind::Unlocked,
+                    key,
+                    format!("{} depends on it", dependent),
+                ));
+
+                continue;
+            }
+        };
+
+        // optional dependencies may have been skipped on the platform that locked them
+        for (name, version) in &package.dependencies {
+            stack.push((dependency_key(name, version), key.clone()));
+        }
+    }
+
+    problems.sort();
+    problems.dedup();
+
+    problems
+}
+
+/// The packages of `resolution` missing from `node_modules`, or whose files differ from
+/// the ones in the store. Patched packages differ on purpose, so only their presence is
+/// checked.
+fn damaged(config: &VoltConfig, resolution: &Resolution) -> Result<Vec<Problem>> {
+    let node_modules = config.node_modules()?;
+    let linker = linker(config, resolution)?;
+    let patches = read_patches(config)?;
+
+    let packages: Vec<_> = resolution
+        .tree
+        .iter()
+        .filter(|(_, package)| !package.is_link())
+        .flat_map(|(key, package)| {
+            linker
+                .directories(key)
+                .into_iter()
+                .map(move |directory| (key, package, directory))
+        })
+        .collect();
+
+    Ok(packages
+        .into_par_iter()
+        .filter_map(|(key, package, directory)| {
+            if !directory.is_dir() {
+                return Some(Problem::new(
+                    ProblemKind::Missing,
+                    key.clone(),
+                    format!("{} doesn't exist", relative(&node_modules, &directory)),
+                ));
+            }
+
+            if patches.contains_key(key) {
+                return None;
+            }
+
+            damaged_files(config, package, &directory, true).map(|damage| {
+                Problem::new(
+                    ProblemKind::Integrity,
+                    key.clone(),
+                    format!("{} in {}", damage, relative(&node_modules, &directory)),
+                )
+            })
+        })
+        .collect())
+}
+
+/// The shims of `node_modules/.bin` for commands no requested package of `resolution` has, or
+/// pointing to files that aren't there.
+fn dangling_bins(config: &VoltConfig, resolution: &Resolution) -> Result<Vec<Problem>> {
+    let node_modules = config.node_modules()?;
+    let bin_dir = node_modules.join(".bin");
+    let linker = linker(config, resolution)?;
+
+    // several packages may have the same command, the shim points to one of them
+    let mut commands: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+
+    for key in resolution.requested() {
+        let package = match resolution.tree.get(key) {
+            Some(package) => package,
+            None => continue,
+        };
+
+        let (bin, directory) = match (&package.bin, linker.directories(key).first()) {
+            (Some(bin), Some(directory)) => (bin, directory.clone()),
+            _ => continue,
+        };
+
+        for (name, path) in bin_entries(&package.name, bin) {
+            commands.entry(name).or_default().push(directory.join(path));
+        }
+    }
+
+    let shims: BTreeSet<PathBuf> = match std::fs::read_dir(&bin_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .collect(),
+        Err(_) => return Ok(vec![]),
+    };
+
+    let mut problems = vec![];
+
+    for shim in shims {
+        let file_name = shim.file_name().unwrap_or_default().to_string_lossy();
+        let command = shim_command(&file_name);
+
+        let detail = match commands.get(command) {
+            None => format!("no installed package has a `{}` command", command),
+            // a symlink to nothing
+            Some(_) if !shim.exists() => "its target doesn't exist".to_string(),
+            Some(targets) if !targets.iter().any(|target| target.is_file()) => {
+                format!("{} doesn't exist", relative(&node_modules, &targets[0]))
+            }
+            Some(_) => continue,
+        };
+
+        problems.push(Problem::new(
+            ProblemKind::DanglingBin,
+            relative(&node_modules, &shim),
+            detail,
+        ));
+    }
+
+    Ok(problems)
+}
+
+/// `path` relative to `node_modules`, with `/` on every platform.
+fn relative(node_modules: &Path, path: &Path) -> String {
+    path.strip_prefix(node_modules)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unlocked, ProblemKind};
+
+    use crate::model::lock_file::{LockFile, LockedPackage};
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn finds_what_volt_lock_is_missing() {
+        let mut lock_file = LockFile::default();
+
+        lock_file.dependencies.insert(
+            "a@1.0.0".to_string(),
+            LockedPackage {
+                name: "a".to_string(),
+                version: "1.0.0".to_string(),
+                dependencies: BTreeMap::from([("b".to_string(), "2.0.0".to_string())]),
+                ..LockedPackage::default()
+            },
+        );
+
+        let dependencies = BTreeMap::from([
+            ("a".to_string(), "^1.0.0".to_string()),
+            ("c".to_string(), "^3.0.0".to_string()),
+        ]);
+
+        let problems = unlocked(&lock_file, &dependencies);
+
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.kind,
+                    problem.subject.as_str(),
+                    problem.detail.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (ProblemKind::Unlocked, "b@2.0.0", "a@1.0.0 depends on it"),
+                (
+                    ProblemKind::Unlocked,
+                    "c@^3.0.0",
+                    "package.json depends on it"
+                ),
+            ]
+        );
+
+        lock_file.dependencies.insert(
+            "b@2.0.0".to_string(),
+            LockedPackage {
+                name: "b".to_string(),
+                version: "2.0.0".to_string(),
+                ..LockedPackage::default()
+            },
+        );
+
+        assert!(unlocked(
+            &lock_file,
+            &BTreeMap::from([("a".to_string(), "^1.0.0".to_string())])
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Pick the version and the fields `volt info` shows from a package document.
+
+use crate::utils::package::NpmPackage;
+
+use node_semver::{Range, Version};
+use serde_json::Value;
+
+/// The version of `package` that a tag, version or range points to, `latest` without one.
+///
+/// Like npm, the `latest` version is preferred when it satisfies the range.
+pub fn resolve_version(package: &NpmPackage, requested: Option<&str>) -> Option<String> {
+    let requested = requested.unwrap_or("latest");
+
+    if let Some(version) = package.dist_tags.get(requested) {
+        return Some(version.clone());
+    }
+
+    let range: Range = requested.parse().ok()?;
+
+    let latest = package
+        .dist_tags
+        .latest()
+        .and_then(|latest| latest.parse::<Version>().ok());
+
+    if let Some(latest) = latest.filter(|latest| latest.satisfies(&range)) {
+        return Some(latest.to_string());
+    }
+
+    package
+        .versions
+        .keys()
+        .filter_map(|version| version.parse::<Version>().ok())
+        .filter(|version| version.satisfies(&range))
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// The value at a dot-path (`dist.tarball`, `maintainers.0.name`), looked up in the version
+/// document first and then in the package document (`dist-tags`, `time`, `versions`).
+pub fn field<'a>(version: &'a Value, packument: &'a Value, path: &str) -> Option<&'a Value> {
+    let segments: Vec<&str> = path.split('.').collect();
+
+    lookup(version, &segments).or_else(|| lookup(packument, &segments))
+}
+
+fn lookup<'a>(value: &'a Value, segments: &[&str]) -> Option<&'a Value> {
+    if segments.is_empty() {
+        return Some(value);
+    }
+
+    match value {
+        // keys can contain dots themselves (`time.1.0.0`), so the longest key goes first
+        Value::Object(map) => (1..=segments.len()).rev().find_map(|end| {
+            map.get(&segments[..end].join("."))
+                .and_then(|value| lookup(value, &segments[end..]))
+        }),
+        Value::Array(items) => items
+            .get(segments[0].parse::<usize>().ok()?)
+            .and_then(|value| lookup(value, &segments[1..])),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field, resolve_version};
+    use crate::utils::package::NpmPackage;
+
+    use serde_json::json;
+
+    #[test]
+    fn resolves_versions_and_fields() {
+        let packument = json!({
+            "name": "a",
+            "dist-tags": { "latest": "1.2.0", "next": "2.0.0-beta.1" },
+            "versions": {
+                "1.0.0": { "name": "a", "version": "1.0.0" },
+                "1.2.0": { "name": "a", "version": "1.2.0" },
+                "1.3.0": { "name": "a", "version": "1.3.0" },
+                "2.0.0-beta.1": { "name": "a", "version": "2.0.0-beta.1" },
+            },
+            "time": { "1.2.0": "2021-10-01T00:00:00.000Z" },
+        });
+
+        let package: NpmPackage = serde_json::from_value(packument.clone()).unwrap();
+
+        assert_eq!(resolve_version(&package, None).unwrap(), "1.2.0");
+        assert_eq!(
+            resolve_version(&package, Some("next")).unwrap(),
+            "2.0.0-beta.1"
+        );
+        assert_eq!(resolve_version(&package, Some("^1.0.0")).unwrap(), "1.2.0");
+        assert_eq!(resolve_version(&package, Some("~1.0.0")).unwrap(), "1.0.0");
+        assert_eq!(resolve_version(&package, Some("^3.0.0")), None);
+
+        let version = json!({
+            "version": "1.2.0",
+            "dist": { "tarball": "https://registry.npmjs.org/a/-/a-1.2.0.tgz" },
+            "maintainers": [{ "name": "someone" }],
+        });
+
+        assert_eq!(
+            field(&version, &packument, "dist.tarball").unwrap(),
+            "https://registry.npmjs.org/a/-/a-1.2.0.tgz"
+        );
+        assert_eq!(
+            field(&version, &packument, "maintainers.0.name").unwrap(),
+            "someone"
+        );
+        assert_eq!(
+            field(&version, &packument, "time.1.2.0").unwrap(),
+            "2021-10-01T00:00:00.000Z"
+        );
+        assert_eq!(field(&version, &packument, "dist.size"), None);
+    }
+}
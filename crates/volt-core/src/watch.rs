@@ -0,0 +1,245 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Follow the local packages a project depends on while they are worked on.
+//!
+//! `file:` and `link:` dependencies and workspace members are watched by comparing
+//! snapshots of their files (path, size and modification time, without `.git` and
+//! `node_modules`). `link:` packages and workspace members are symlinked, so their
+//! changes show up on their own; `file:` packages are copies, and are packed and
+//! installed again.
+
+use crate::{
+    config::VoltConfig,
+    install::{
+        dependency_specs, install, link_workspace_members, project_dependencies, resolve,
+        write_lock_file, InstallScope, Resolution,
+    },
+    local::resolve_file,
+    model::lock_file::LockFile,
+    utils::package::PackageJson,
+};
+
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// How a watched package gets into node_modules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    /// `file:`, copied into the store
+    File,
+    /// `link:`, symlinked
+    Link,
+    /// A member of the workspace, symlinked
+    Workspace,
+}
+
+/// A local package being watched.
+#[derive(Debug, Clone)]
+pub struct Watched {
+    pub name: String,
+    pub source: Source,
+    /// The path as written in package.json, relative to the project
+    pub path: PathBuf,
+    /// Where the package is
+    pub directory: PathBuf,
+}
+
+/// The local packages of the project in `config`.
+pub fn watched_packages(config: &VoltConfig) -> Result<Vec<Watched>> {
+    let cwd = config.cwd()?;
+
+    let (dependencies, workspace) = project_dependencies(&cwd, &[], InstallScope::All)?;
+
+    let mut watched = vec![];
+
+    for spec in dependency_specs(&dependencies) {
+        // `name@file:../dir` parses as an alias of the directory
+        let spec = match spec {
+            PackageSpec::Alias { spec, .. } => *spec,
+            spec => spec,
+        };
+
+        let (source, path) = match spec {
+            PackageSpec::Dir { path } => (Source::File, path),
+            PackageSpec::Link { path } => (Source::Link, path),
+            _ => continue,
+        };
+
+        let directory = cwd.join(&path);
+
+        // a `file:` tarball has no package.json to read, the file name will do
+        let name = PackageJson::get_from_dir(&directory)
+            .map(|(package_json, _)| package_json.name)
+            .unwrap_or_else(|_| path.display().to_string());
+
+        watched.push(Watched {
+            name,
+            source,
+            path,
+            directory,
+        });
+    }
+
+    for member in workspace.iter().flat_map(|workspace| &workspace.members) {
+        watched.push(Watched {
+            name: member.name().to_string(),
+            source: Source::Workspace,
+            path: member
+                .path
+                .strip_prefix(&cwd)
+                .unwrap_or(&member.path)
+                .to_path_buf(),
+            directory: member.path.clone(),
+        });
+    }
+
+    Ok(watched)
+}
+
+/// The files of a package at one point in time: path -> (size, modification time).
+#[derive(Debug, Default, PartialEq)]
+pub struct Snapshot(BTreeMap<PathBuf, (u64, Option<SystemTime>)>);
+
+impl Snapshot {
+    /// Record the files under `path` (or `path` itself, for a tarball).
+    pub fn take(path: &Path) -> Self {
+        fn collect(path: &Path, files: &mut BTreeMap<PathBuf, (u64, Option<SystemTime>)>) {
+            let metadata = match path.symlink_metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => return,
+            };
+
+            if !metadata.is_dir() {
+                files.insert(
+                    path.to_path_buf(),
+                    (metadata.len(), metadata.modified().ok()),
+                );
+
+                return;
+            }
+
+            for entry in std::fs::read_dir(path).into_iter().flatten().flatten() {
+                let name = entry.file_name();
+
+                if name != ".git" && name != "node_modules" {
+                    collect(&entry.path(), files);
+                }
+            }
+        }
+
+        let mut files = BTreeMap::new();
+
+        collect(path, &mut files);
+
+        Self(files)
+    }
+
+    /// The files added, removed or modified since `earlier`.
+    pub fn changes(&self, earlier: &Snapshot) -> Vec<PathBuf> {
+        let modified = self
+            .0
+            .iter()
+            .filter(|(path, file)| earlier.0.get(*path) != Some(file))
+            .map(|(path, _)| path.clone());
+
+        let removed = earlier
+            .0
+            .keys()
+            .filter(|path| !self.0.contains_key(*path))
+            .cloned();
+
+        modified.chain(removed).collect()
+    }
+}
+
+/// Pack the changed `file:` packages again and install them, leaving the rest of the
+/// locked tree as it is.
+pub async fn refresh_file_packages(config: &VoltConfig, changed: &[&Watched]) -> Result<()> {
+    let (dependencies, workspace) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+    let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+    let mut resolution = match Resolution::from_lock_file(&lock_file, &dependencies) {
+        Some(resolution) => resolution,
+        None => resolve(config, &dependency_specs(&dependencies)).await?,
+    };
+
+    for watched in changed {
+        let tarball = format!("file:{}", watched.path.display());
+
+        // the package may have a new version, so its old entry goes by where it's from
+        let stale: Vec<String> = resolution
+            .tree
+            .iter()
+            .filter(|(_, package)| package.tarball == tarball)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in stale {
+            resolution.tree.remove(&key);
+            resolution.direct.retain(|direct| *direct != key);
+        }
+
+        resolution.merge(resolve_file(config, &watched.path).await?);
+    }
+
+    write_lock_file(config, &resolution)?;
+
+    install(config, resolution).await?;
+
+    if let Some(workspace) = &workspace {
+        link_workspace_members(config, workspace)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snapshot;
+
+    #[test]
+    fn notices_changed_files() {
+        let directory = tempfile::tempdir().unwrap();
+        let package = directory.path();
+
+        std::fs::create_dir_all(package.join("src")).unwrap();
+        std::fs::create_dir_all(package.join("node_modules/dep")).unwrap();
+        std::fs::write(package.join("src/index.js"), "1").unwrap();
+        std::fs::write(package.join("README.md"), "readme").unwrap();
+
+        let before = Snapshot::take(package);
+
+        assert!(Snapshot::take(package).changes(&before).is_empty());
+
+        std::fs::write(package.join("src/index.js"), "12").unwrap();
+        std::fs::remove_file(package.join("README.md")).unwrap();
+        // dependencies of the package aren't its sources
+        std::fs::write(package.join("node_modules/dep/index.js"), "").unwrap();
+
+        assert_eq!(
+            Snapshot::take(package).changes(&before),
+            vec![package.join("src/index.js"), package.join("README.md")]
+        );
+    }
+}
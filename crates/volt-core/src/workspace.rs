@@ -0,0 +1,477 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Discover the members of a workspace and select subsets of them with `--filter`.
+
+use crate::{
+    git,
+    utils::{errors::VoltError, glob, package::PackageJson},
+};
+
+use miette::Result;
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A package inside of a workspace.
+#[derive(Debug, Clone)]
+pub struct WorkspaceMember {
+    /// Directory containing the member's package.json
+    pub path: PathBuf,
+    pub package_json: PackageJson,
+}
+
+impl WorkspaceMember {
+    pub fn name(&self) -> &str {
+        &self.package_json.name
+    }
+
+    /// Names of every dependency (including dev dependencies) of the member.
+    pub fn dependency_names(&self) -> impl Iterator<Item = &String> {
+        self.package_json
+            .dependencies
+            .iter()
+            .chain(self.package_json.dev_dependencies.iter())
+            .flat_map(|dependencies| dependencies.keys())
+    }
+}
+
+/// A project with a `workspaces` field in its package.json.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub members: Vec<WorkspaceMember>,
+}
+
+impl Workspace {
+    /// Load the workspace rooted at `root`, or `None` if the project doesn't declare any workspaces.
+    pub fn discover(root: &Path) -> Result<Option<Self>> {
+        let (package_json, _) = PackageJson::get_from_dir(root)?;
+
+        let patterns = match package_json.workspaces {
+            Some(patterns) if !patterns.is_empty() => patterns,
+            _ => return Ok(None),
+        };
+
+        let mut members = vec![];
+
+        for path in package_directories(root) {
+            let relative = match path.strip_prefix(root) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                _ => continue,
+            };
+
+            let relative = relative.to_string_lossy().replace('\\', "/");
+
+            if patterns.iter().any(|pattern| {
+                glob::matches(
+                    pattern.trim_start_matches("./").trim_end_matches('/'),
+                    &relative,
+                )
+            }) {
+                let (package_json, _) = PackageJson::get_from_dir(&path)?;
+
+                members.push(WorkspaceMember { path, package_json });
+            }
+        }
+
+        members.sort_by(|a, b| a.name().cmp(b.name()));
+
+        Ok(Some(Self {
+            root: root.to_path_buf(),
+            members,
+        }))
+    }
+
+    pub fn member(&self, name: &str) -> Option<&WorkspaceMember> {
+        self.members.iter().find(|member| member.name() == name)
+    }
+
+    /// Names of the members that `name` depends on directly.
+    pub fn local_dependencies(&self, name: &str) -> Vec<&str> {
+        self.member(name)
+            .map(|member| {
+                member
+                    .dependency_names()
+                    .filter_map(|dependency| self.member(dependency).map(WorkspaceMember::name))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Names of the members that depend on `name` directly.
+    pub fn local_dependents(&self, name: &str) -> Vec<&str> {
+        self.members
+            .iter()
+            .filter(|member| {
+                member
+                    .dependency_names()
+                    .any(|dependency| dependency == name)
+            })
+            .map(WorkspaceMember::name)
+            .collect()
+    }
+
+    /// Select the members matching any of the filters (every member if there are none).
+    pub fn filter(&self, filters: &[Filter]) -> Result<Vec<&WorkspaceMember>> {
+        if filters.is_empty() {
+            return Ok(self.members.iter().collect());
+        }
+
+        let mut selected = BTreeSet::new();
+
+        for filter in filters {
+            let matched: Vec<&str> = match &filter.selector {
+                Selector::Name(pattern) => self
+                    .members
+                    .iter()
+                    .filter(|member| glob::matches(pattern, member.name()))
+                    .map(WorkspaceMember::name)
+                    .collect(),
+                Selector::Path(path) => {
+                    let path = self.root.join(path);
+
+                    self.members
+                        .iter()
+                        .filter(|member| member.path.starts_with(&path))
+                        .map(WorkspaceMember::name)
+                        .collect()
+                }
+                Selector::ChangedSince(reference) => self.changed_since(reference)?,
+            };
+
+            for name in matched {
+                selected.insert(name.to_string());
+
+                if filter.include_dependencies {
+                    self.walk(name, &mut selected, |name| self.local_dependencies(name));
+                }
+
+                if filter.include_dependents {
+                    self.walk(name, &mut selected, |name| self.local_dependents(name));
+                }
+            }
+        }
+
+        Ok(self
+            .members
+            .iter()
+            .filter(|member| selected.contains(member.name()))
+            .collect())
+    }
+
+    /// Order `members` so that every member comes after the members it depends on, even
+    /// through members that aren't selected. Cycles are broken where they are found.
+    pub fn topological_order<'a>(
+        &self,
+        members: &[&'a WorkspaceMember],
+    ) -> Vec<&'a WorkspaceMember> {
+        fn visit(
+            workspace: &Workspace,
+            name: &str,
+            visited: &mut HashSet<String>,
+            order: &mut Vec<String>,
+        ) {
+            if !visited.insert(name.to_string()) {
+                return;
+            }
+
+            let mut dependencies = workspace.local_dependencies(name);
+            dependencies.sort_unstable();
+
+            for dependency in dependencies {
+                visit(workspace, dependency, visited, order);
+            }
+
+            order.push(name.to_string());
+        }
+
+        let mut visited = HashSet::new();
+        let mut order = vec![];
+
+        // members are sorted by name, which keeps the order stable
+        for member in members {
+            visit(self, member.name(), &mut visited, &mut order);
+        }
+
+        order
+            .iter()
+            .filter_map(|name| members.iter().find(|member| member.name() == name).copied())
+            .collect()
+    }
+
+    /// Add everything reachable from `name` through `edges` to `selected`.
+    fn walk<'a>(
+        &'a self,
+        name: &str,
+        selected: &mut BTreeSet<String>,
+        edges: impl Fn(&str) -> Vec<&'a str>,
+    ) {
+        let mut visited = HashSet::new();
+        let mut stack = vec![name.to_string()];
+
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+
+            for next in edges(&current) {
+                selected.insert(next.to_string());
+                stack.push(next.to_string());
+            }
+        }
+    }
+
+    /// Members with files that changed since the given git reference.
+    fn changed_since(&self, reference: &str) -> Result<Vec<&str>> {
+        let changed = git::git(&self.root, &["diff", "--name-only", reference])?;
+        let top_level = PathBuf::from(git::git(&self.root, &["rev-parse", "--show-toplevel"])?);
+
+        let changed: Vec<PathBuf> = changed.lines().map(|file| top_level.join(file)).collect();
+
+        let root = self
+            .root
+            .canonicalize()
+            .unwrap_or_else(|_| self.root.clone());
+
+        Ok(self
+            .members
+            .iter()
+            .filter(|member| {
+                let relative = member.path.strip_prefix(&self.root).unwrap_or(&member.path);
+                let path = root.join(relative);
+
+                changed.iter().any(|file| file.starts_with(&path))
+            })
+            .map(WorkspaceMember::name)
+            .collect())
+    }
+}
+
+/// The projects below `root` (itself included) for `volt install --recursive`: the
+/// directories with a package.json, outside of node_modules and without the members of
+/// the workspaces among them, which their root installs.
+pub fn find_projects(root: &Path) -> Result<Vec<PathBuf>> {
+    let directories = package_directories(root);
+
+    let mut members = HashSet::new();
+
+    for directory in &directories {
+        if let Some(workspace) = Workspace::discover(directory)? {
+            members.extend(workspace.members.into_iter().map(|member| member.path));
+        }
+    }
+
+    Ok(directories
+        .into_iter()
+        .filter(|directory| !members.contains(directory))
+        .collect())
+}
+
+/// The directories below `root` (itself included) with a package.json, sorted, never
+/// looking into node_modules or .git.
+fn package_directories(root: &Path) -> Vec<PathBuf> {
+    let walker = jwalk::WalkDir::new(root)
+        .follow_links(false)
+        .sort(true)
+        .process_read_dir(|_, _, _, children| {
+            // never descend into installed packages
+            children.retain(|entry| {
+                entry.as_ref().map_or(true, |entry| {
+                    entry.file_name() != "node_modules" && entry.file_name() != ".git"
+                })
+            });
+        });
+
+    walker
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_dir())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("package.json").is_file())
+        .collect()
+}
+
+/// What a `--filter` selects before dependencies/dependents are added.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    /// Members whose name matches a glob (`@scope/*`)
+    Name(String),
+    /// Members inside of a directory (`./packages/app`)
+    Path(PathBuf),
+    /// Members with changes since a git reference (`[origin/main]`)
+    ChangedSince(String),
+}
+
+/// A workspace selector in pnpm's `--filter` syntax.
+///
+/// * `name` / `@scope/*` - members by name
+/// * `name...` - the member and everything it depends on
+/// * `...name` - the member and everything that depends on it
+/// * `[ref]` - members changed since a git reference
+/// * `./path` - members inside of a directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub selector: Selector,
+    pub include_dependencies: bool,
+    pub include_dependents: bool,
+}
+
+impl FromStr for Filter {
+    type Err = VoltError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let include_dependents = s.starts_with("...");
+        let include_dependencies = s.ends_with("...") && s.len() > 3;
+
+        let selector = s.trim_start_matches("...").trim_end_matches("...");
+
+        let selector = if let Some(reference) = selector
+            .strip_prefix('[')
+            .and_then(|selector| selector.strip_suffix(']'))
+        {
+            Selector::ChangedSince(reference.to_string())
+        } else if selector.starts_with("./") || selector.starts_with("../") || selector == "." {
+            Selector::Path(PathBuf::from(selector))
+        } else if !selector.is_empty() {
+            Selector::Name(selector.to_string())
+        } else {
+            return Err(VoltError::InvalidFilter {
+                filter: s.to_string(),
+            });
+        };
+
+        Ok(Self {
+            selector,
+            include_dependencies,
+            include_dependents,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_projects, Filter, Selector, Workspace, WorkspaceMember};
+
+    use crate::utils::package::PackageJson;
+
+    use std::path::PathBuf;
+
+    #[test]
+    fn parses_filter_syntax() {
+        let filter: Filter = "...@scope/app".parse().unwrap();
+        assert_eq!(filter.selector, Selector::Name("@scope/app".to_string()));
+        assert!(filter.include_dependents && !filter.include_dependencies);
+
+        let filter: Filter = "core...".parse().unwrap();
+        assert_eq!(filter.selector, Selector::Name("core".to_string()));
+        assert!(filter.include_dependencies && !filter.include_dependents);
+
+        let filter: Filter = "...[origin/main]".parse().unwrap();
+        assert_eq!(
+            filter.selector,
+            Selector::ChangedSince("origin/main".to_string())
+        );
+
+        assert!("...".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn orders_members_topologically() {
+        let member = |name: &str, dependencies: &[&str]| {
+            let package_json: PackageJson = serde_json::from_value(serde_json::json!({
+                "name": name,
+                "version": "1.0.0",
+                "dependencies": dependencies
+                    .iter()
+                    .map(|dependency| (dependency.to_string(), "*"))
+                    .collect::<std::collections::BTreeMap<_, _>>(),
+            }))
+            .unwrap();
+
+            WorkspaceMember {
+                path: PathBuf::from(name),
+                package_json,
+            }
+        };
+
+        let workspace = Workspace {
+            root: PathBuf::new(),
+            members: vec![
+                member("app", &["ui", "lodash"]),
+                member("core", &[]),
+                member("ui", &["utils"]),
+                member("utils", &["core"]),
+            ],
+        };
+
+        let selected: Vec<&WorkspaceMember> = workspace
+            .members
+            .iter()
+            .filter(|member| member.name() != "utils")
+            .collect();
+
+        let order: Vec<&str> = workspace
+            .topological_order(&selected)
+            .into_iter()
+            .map(WorkspaceMember::name)
+            .collect();
+
+        assert_eq!(order, ["core", "ui", "app"]);
+    }
+
+    #[test]
+    fn finds_nested_projects_without_workspace_members() {
+        let root = tempfile::tempdir().unwrap();
+
+        let write = |path: &str, contents: &str| {
+            let path = root.path().join(path);
+
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        };
+
+        write("package.json", r#"{ "name": "root" }"#);
+        write("examples/basic/package.json", r#"{ "name": "basic" }"#);
+        write(
+            "tools/package.json",
+            r#"{ "name": "tools", "workspaces": ["packages/*"] }"#,
+        );
+        write("tools/packages/cli/package.json", r#"{ "name": "cli" }"#);
+        write(
+            "examples/basic/node_modules/a/package.json",
+            r#"{ "name": "a" }"#,
+        );
+
+        let projects: Vec<PathBuf> = find_projects(root.path())
+            .unwrap()
+            .into_iter()
+            .map(|project| project.strip_prefix(root.path()).unwrap().to_path_buf())
+            .collect();
+
+        assert_eq!(
+            projects,
+            [
+                PathBuf::new(),
+                PathBuf::from("examples/basic"),
+                PathBuf::from("tools")
+            ]
+        );
+    }
+}
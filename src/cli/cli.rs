@@ -1,10 +1,14 @@
 use crate::commands::{
-    add, clean, clone, discord, info, init, list, login, node, outdated, run, search,
+    add, audit, bundle, cache, ci, clean, clone, dedupe, deprecate, diff, discord, dist_tag,
+    doctor, exec, graph, info, init, install, licenses, link, list, login, node, outdated, owner,
+    pack, patch, pin, prune, publish, rebuild, remove, run, search, size, store, unlink, unpublish,
+    update, upgrade_self, vendor, verify, watch, why, x,
 }; // remove outdated later
 use async_trait::async_trait;
 use clap::{crate_authors, crate_description, crate_name, crate_version, Parser, Subcommand};
+use volt_core::plugin::run_plugin;
 
-use super::VoltConfig;
+use super::{VoltConfig, VoltOptions};
 
 /// A trait to be implemented by subcommands
 #[async_trait]
@@ -16,17 +20,55 @@ pub trait VoltCommand {
 #[derive(Debug, Subcommand)]
 pub enum VoltSubCmd {
     Add(add::Add),
+    Audit(audit::Audit),
+    Bundle(bundle::Bundle),
+    Cache(cache::Cache),
+    Ci(ci::Ci),
     Clone(clone::Clone),
     Init(init::Init),
+    Install(install::Install),
+    Licenses(licenses::Licenses),
+    Link(link::Link),
     Clean(clean::Clean),
+    Dedupe(dedupe::Dedupe),
+    Deprecate(deprecate::Deprecate),
+    Diff(diff::Diff),
     Discord(discord::Discord),
+    DistTag(dist_tag::DistTag),
+    Doctor(doctor::Doctor),
+    Exec(exec::Exec),
+    Graph(graph::Graph),
     Search(search::Search),
+    Size(size::Size),
     Login(login::Login),
+    Rebuild(rebuild::Rebuild),
+    Remove(remove::Remove),
     Run(run::Run),
     Info(info::Info),
     Node(node::Node),
     Outdated(outdated::Outdated), // remove later???
-    List(list::List),             // remove later???
+    Owner(owner::Owner),
+    Pack(pack::Pack),
+    Patch(patch::Patch),
+    PatchCommit(patch::PatchCommit),
+    Pin(pin::Pin),
+    Prune(prune::Prune),
+    Publish(publish::Publish),
+    Store(store::Store),
+    Unlink(unlink::Unlink),
+    Unpublish(unpublish::Unpublish),
+    Update(update::Update),
+    UpgradeSelf(upgrade_self::UpgradeSelf),
+    Vendor(vendor::Vendor),
+    Verify(verify::Verify),
+    List(list::List), // remove later???
+    Watch(watch::Watch),
+    Why(why::Why),
+    #[clap(visible_alias = "dlx", trailing_var_arg = true)]
+    X(x::X),
+    /// Any other command, run by the `volt-<name>` plugin
+    #[clap(external_subcommand)]
+    Plugin(Vec<String>),
 }
 
 #[async_trait]
@@ -34,17 +76,52 @@ impl VoltCommand for VoltSubCmd {
     async fn exec(self, config: VoltConfig) -> miette::Result<()> {
         match self {
             Self::Add(x) => x.exec(config).await,
+            Self::Audit(x) => x.exec(config).await,
+            Self::Bundle(x) => x.exec(config).await,
+            Self::Cache(x) => x.exec(config).await,
+            Self::Ci(x) => x.exec(config).await,
             Self::Clone(x) => x.exec(config).await,
             Self::Init(x) => x.exec(config).await,
+            Self::Install(x) => x.exec(config).await,
+            Self::Licenses(x) => x.exec(config).await,
+            Self::Link(x) => x.exec(config).await,
             Self::Clean(x) => x.exec(config).await,
+            Self::Dedupe(x) => x.exec(config).await,
+            Self::Deprecate(x) => x.exec(config).await,
+            Self::Diff(x) => x.exec(config).await,
             Self::Discord(x) => x.exec(config).await,
+            Self::DistTag(x) => x.exec(config).await,
+            Self::Doctor(x) => x.exec(config).await,
+            Self::Exec(x) => x.exec(config).await,
+            Self::Graph(x) => x.exec(config).await,
             Self::Search(x) => x.exec(config).await,
+            Self::Size(x) => x.exec(config).await,
             Self::Login(x) => x.exec(config).await,
+            Self::Rebuild(x) => x.exec(config).await,
+            Self::Remove(x) => x.exec(config).await,
             Self::Run(x) => x.exec(config).await,
             Self::Info(x) => x.exec(config).await,
             Self::Node(x) => x.exec(config).await,
             Self::Outdated(x) => x.exec(config).await, // remove later
-            Self::List(x) => x.exec(config).await,     // remove later
+            Self::Owner(x) => x.exec(config).await,
+            Self::Pack(x) => x.exec(config).await,
+            Self::Patch(x) => x.exec(config).await,
+            Self::PatchCommit(x) => x.exec(config).await,
+            Self::Pin(x) => x.exec(config).await,
+            Self::Prune(x) => x.exec(config).await,
+            Self::Publish(x) => x.exec(config).await,
+            Self::Store(x) => x.exec(config).await,
+            Self::Unlink(x) => x.exec(config).await,
+            Self::Unpublish(x) => x.exec(config).await,
+            Self::Update(x) => x.exec(config).await,
+            Self::UpgradeSelf(x) => x.exec(config).await,
+            Self::Vendor(x) => x.exec(config).await,
+            Self::Verify(x) => x.exec(config).await,
+            Self::List(x) => x.exec(config).await, // remove later
+            Self::Watch(x) => x.exec(config).await,
+            Self::Why(x) => x.exec(config).await,
+            Self::X(x) => x.exec(config).await,
+            Self::Plugin(args) => run_plugin(&config, &args),
         }
     }
 }
@@ -60,7 +137,7 @@ impl VoltCommand for VoltSubCmd {
 #[allow(clippy::module_name_repetitions)]
 pub struct VoltCli {
     #[clap(flatten)]
-    pub config: VoltConfig,
+    pub options: VoltOptions,
 
     #[clap(subcommand)]
     pub cmd: VoltSubCmd,
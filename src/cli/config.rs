@@ -14,76 +14,116 @@ See the License for the specific language governing permissions and
 limitations under the License.
 */
 
-use crate::core::utils::errors::VoltError;
+use super::reporter::{Bars, TerminalReporter};
+use volt_core::{
+    config::VoltConfig,
+    linker::NodeLinker,
+    reporter::{PlainReporter, Reporter},
+    settings::parse_rate,
+};
 
 use clap::Parser;
-use ssri::Algorithm;
-use std::{env, path::PathBuf};
+use std::{io::IsTerminal, path::PathBuf, sync::Arc};
 
+/// The flags every command takes, which make its [`VoltConfig`]
 #[derive(Debug, Clone, Parser)]
-pub struct VoltConfig {
+pub struct VoltOptions {
     /// Path to current working directory
     #[clap(short, long)]
     cwd: Option<PathBuf>,
-}
 
-impl VoltConfig {
-    pub const _OS: &'static str = env::consts::OS;
-    pub const VOLT_HOME: &'static str = ".volt";
-    pub const _VOLT_LOCK: &'static str = "volt.lock";
+    /// Print progress as plain lines instead of progress bars
+    #[clap(long, global = true)]
+    no_progress: bool,
 
-    pub fn home(&self) -> miette::Result<PathBuf> {
-        Ok(dirs::home_dir().ok_or(VoltError::GetHomeDirError)?)
-    }
+    /// Print progress and results as JSON lines, for tools (also enabled by `VOLT_JSON=1`)
+    #[clap(long, global = true)]
+    json: bool,
 
-    /// Return the current directory (defaults to `.` if not provided)
-    pub fn cwd(&self) -> miette::Result<PathBuf> {
-        Ok(self.cwd.to_owned().unwrap_or({
-            env::current_dir().map_err(|e| VoltError::EnvironmentError {
-                env: "CURRENT_DIRECTORY".to_string(),
-                source: e,
-            })?
-        }))
-    }
+    /// Log more: `-v` for debug messages, `-vv` for everything
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    verbose: u8,
 
-    /// Path to the volt lockfile (defaults to `./volt.lock`)
-    pub fn _lockfile(&self) -> miette::Result<PathBuf> {
-        Ok(self.cwd()?.join(Self::_VOLT_LOCK))
-    }
+    /// Only log errors
+    #[clap(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
 
-    /// Path to the `node_modules` directory (defaults to `./node_modules`)
-    pub fn node_modules(&self) -> miette::Result<PathBuf> {
-        Ok(self.cwd()?.join("node_modules"))
-    }
+    /// Layout of node_modules, over the `node-linker` setting
+    #[clap(long, global = true, arg_enum)]
+    node_linker: Option<NodeLinker>,
 
-    /// Path to the config directory (defaults to `~/.volt`)
-    pub fn volt_home(&self) -> miette::Result<PathBuf> {
-        Ok(self.home()?.join(Self::VOLT_HOME))
-    }
+    /// Packages to hoist, over the `hoist-pattern` setting (`*`, `*eslint*`, `!@types/*`)
+    #[clap(long, global = true)]
+    hoist_pattern: Vec<String>,
+
+    /// Don't wait for other volt processes using node_modules or the store
+    #[clap(long, global = true)]
+    no_lock: bool,
+
+    /// Resolve versions published more recently than the `minimum-release-age` setting
+    #[clap(long, global = true)]
+    allow_fresh: bool,
+
+    /// Fail installs of packages without a registry signature, over the
+    /// `require-signatures` setting
+    #[clap(long, global = true)]
+    require_signatures: bool,
 
-    /// Calculate the hash of a tarball
-    ///
-    /// ## Examples
-    /// ```rs
-    /// calc_hash(bytes::Bytes::new(), ssri::Algorithm::Sha1)?;
-    /// ```
-    /// ## Returns
-    /// * Result<String>
-    pub fn calc_hash(data: &bytes::Bytes, algorithm: Algorithm) -> miette::Result<String> {
-        let integrity = if algorithm == Algorithm::Sha1 {
-            let hash = ssri::IntegrityOpts::new()
-                .algorithm(Algorithm::Sha1)
-                .chain(&data)
-                .result();
-            format!("sha1-{}", hash.to_hex().1)
-        } else {
-            ssri::IntegrityOpts::new()
-                .algorithm(Algorithm::Sha512)
-                .chain(&data)
-                .result()
-                .to_string()
+    /// Install registry packages only from the `vendor` directory, over the `vendored`
+    /// setting
+    #[clap(long, global = true)]
+    vendored: bool,
+
+    /// Directory of the package store, over the `store-dir` setting
+    #[clap(long, global = true)]
+    store_dir: Option<PathBuf>,
+
+    /// Most bytes of tarballs downloaded per second (`5MB/s`, `500KB/s`), over the
+    /// `network-limit` setting; `0` doesn't limit them
+    #[clap(long, global = true, value_name = "RATE", parse(try_from_str = parse_rate))]
+    network_limit: Option<u64>,
+
+    /// Write the spans of the run (resolving, downloads, extraction, linking, scripts) to
+    /// this file in the Chrome trace format, for chrome://tracing or Perfetto
+    #[clap(long, global = true, value_name = "FILE")]
+    trace_file: Option<PathBuf>,
+}
+
+impl VoltOptions {
+    /// The configuration of the command, reporting progress with bars on a terminal and
+    /// as plain lines otherwise
+    pub fn config(&self) -> VoltConfig {
+        let mut config = VoltConfig {
+            cwd: self.cwd.clone(),
+            json: self.json,
+            verbose: self.verbose,
+            quiet: self.quiet,
+            node_linker: self.node_linker,
+            hoist_pattern: self.hoist_pattern.clone(),
+            no_lock: self.no_lock,
+            allow_fresh: self.allow_fresh,
+            require_signatures: self.require_signatures,
+            vendored: self.vendored,
+            store_dir: self.store_dir.clone(),
+            network_limit: self.network_limit,
+            ..VoltConfig::default()
         };
 
-        Ok(integrity)
+        let no_progress = self.no_progress || !std::io::stderr().is_terminal();
+
+        config.set_reporter(move || -> Arc<dyn Reporter> {
+            if no_progress {
+                Arc::new(TerminalReporter::new(PlainReporter::default()))
+            } else {
+                Arc::new(TerminalReporter::new(Bars::new()))
+            }
+        });
+
+        config
+    }
+
+    /// The file to write the spans of the run to, if any
+    pub fn trace_file(&self) -> Option<&PathBuf> {
+        self.trace_file.as_ref()
     }
 }
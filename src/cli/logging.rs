@@ -0,0 +1,171 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Log to the terminal at the verbosity asked for, and always at debug level to a file.
+//!
+//! The file of every run goes to `~/.volt/logs/`, where the last [`MAX_LOGS`] are kept, so
+//! a failed install can be looked into after the fact. `RUST_LOG` (`volt=trace,reqwest=debug`)
+//! replaces the terminal verbosity. With `--trace-file` the spans of the run are also
+//! recorded, see [`trace`](crate::cli::trace).
+
+use crate::cli::{trace::ChromeLayer, VoltConfig};
+
+use colored::Colorize;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::{
+    filter::Targets,
+    fmt::{
+        format::{self, FormatEvent, FormatFields},
+        FmtContext,
+    },
+    layer::{Layer, SubscriberExt},
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+};
+
+use std::{
+    fmt,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Log files kept in `~/.volt/logs`, including the one of the current run.
+pub const MAX_LOGS: usize = 10;
+
+/// Start logging, and recording the spans of the run with `trace`; returns the log file of
+/// this run, if it could be created.
+pub fn init(config: &VoltConfig, trace: Option<ChromeLayer>) -> Option<PathBuf> {
+    let terminal = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|directives| directives.parse::<Targets>().ok())
+        .unwrap_or_else(|| Targets::new().with_target("volt", config.log_level()));
+
+    let terminal = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .event_format(TerminalFormat {
+            start: Instant::now(),
+        })
+        .with_filter(terminal);
+
+    let log_file = config
+        .volt_home()
+        .ok()
+        .and_then(|volt_home| create_log_file(&volt_home.join("logs")));
+
+    let file = log_file.as_ref().and_then(|(_, file)| {
+        Some(
+            tracing_subscriber::fmt::layer()
+                .with_writer(Mutex::new(file.try_clone().ok()?))
+                .with_ansi(false)
+                .with_filter(Targets::new().with_target("volt", Level::DEBUG)),
+        )
+    });
+
+    let trace =
+        trace.map(|trace| trace.with_filter(Targets::new().with_target("volt", Level::DEBUG)));
+
+    tracing_subscriber::registry()
+        .with(terminal)
+        .with(file)
+        .with(trace)
+        .init();
+
+    tracing::debug!(
+        "volt {} running `{}` on {} {}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::args().collect::<Vec<_>>().join(" "),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+
+    log_file.map(|(path, _)| path)
+}
+
+/// Create the log file of this run, removing the oldest ones beyond [`MAX_LOGS`].
+fn create_log_file(directory: &Path) -> Option<(PathBuf, File)> {
+    fs::create_dir_all(directory).ok()?;
+
+    // named after the time of the run so that the names sort oldest first
+    let mut logs: Vec<PathBuf> = fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.ends_with("-debug.log"))
+        })
+        .collect();
+
+    logs.sort();
+
+    for old in logs.iter().rev().skip(MAX_LOGS - 1) {
+        let _ = fs::remove_file(old);
+    }
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis());
+
+    let path = directory.join(format!("{}-debug.log", millis));
+
+    File::create(&path).ok().map(|file| (path, file))
+}
+
+/// `error:` and `warning:` like the rest of the output, and the time since the start for
+/// the verbose levels.
+struct TerminalFormat {
+    start: Instant,
+}
+
+impl<S, N> FormatEvent<S, N> for TerminalFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: format::Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let level = *event.metadata().level();
+
+        match level {
+            Level::ERROR => write!(writer, "{} ", "error:".red().bold())?,
+            Level::WARN => write!(writer, "{} ", "warning:".yellow().bold())?,
+            Level::INFO => {}
+            _ => {
+                write!(
+                    writer,
+                    "{} ",
+                    format!(
+                        "[{:.3}s] {} {}:",
+                        self.start.elapsed().as_secs_f32(),
+                        level.as_str().to_lowercase(),
+                        event.metadata().target()
+                    )
+                    .truecolor(156, 156, 156)
+                )?;
+            }
+        }
+
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+
+        writeln!(writer)
+    }
+}
@@ -1,6 +1,11 @@
 #[allow(clippy::module_inception)]
 mod cli;
 mod config;
+pub mod logging;
+pub mod prompt;
+pub mod reporter;
+pub mod trace;
 
 pub use cli::*;
 pub use config::*;
+pub use volt_core::config::VoltConfig;
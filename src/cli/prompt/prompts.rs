@@ -14,7 +14,7 @@
     limitations under the License.
 */
 
-use crate::core::prompt::input;
+use crate::cli::prompt::input;
 
 use dialoguer::{console, theme::ColorfulTheme};
 use std::{borrow::Cow, io::Result};
@@ -213,3 +213,53 @@ impl<'i> Select<'i> {
         input.interact()
     }
 }
+
+/// Prompt that lets the user check any number of items with space and confirm with enter
+#[derive(Debug)]
+pub struct MultiSelect<'i> {
+    /// Message for the prompt
+    pub message: Cow<'i, str>,
+
+    /// Items that can be checked
+    pub items: Vec<Cow<'i, str>>,
+}
+
+impl<'i> MultiSelect<'i> {
+    /// The indices of the checked items.
+    pub fn run(&self) -> Result<Vec<usize>> {
+        if self.items.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let theme = ColorfulTheme {
+            defaults_style: console::Style::new(),
+            prompt_style: console::Style::new().bold(),
+            prompt_prefix: console::style(String::from("?")).yellow().bright(),
+            prompt_suffix: console::style(String::from(">")).blue().dim(),
+            success_prefix: console::style(String::from("✔")).green().bright(),
+            success_suffix: console::style(String::from("·")).blue().dim(),
+            error_prefix: console::style(String::from("❌")).bright().red(),
+            error_style: console::Style::new(),
+            hint_style: console::Style::new().dim(),
+            values_style: console::Style::new(),
+            active_item_style: console::Style::new().bold(),
+            inactive_item_style: console::Style::new(),
+            active_item_prefix: console::style(String::from("❯")).cyan(),
+            inactive_item_prefix: console::style(String::from(" ")),
+            checked_item_prefix: console::style(String::from("◉")).green(),
+            unchecked_item_prefix: console::style(String::from("◯")),
+            picked_item_prefix: console::style(String::from("")),
+            unpicked_item_prefix: console::style(String::from("")),
+            inline_selections: false,
+        };
+
+        dialoguer::MultiSelect::with_theme(&theme)
+            .with_prompt(format!(
+                "{} {}",
+                self.message,
+                console::style("(space to check, enter to confirm)").dim()
+            ))
+            .items(&self.items)
+            .interact()
+    }
+}
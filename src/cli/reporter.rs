@@ -0,0 +1,335 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Report the progress of volt on a terminal.
+//!
+//! A terminal gets animated bars; with `--no-progress` or when stderr isn't a terminal
+//! (CI logs, redirected output) progress is written as plain lines instead, still in
+//! color, and the user is asked about install scripts only when there is a terminal to
+//! answer on.
+
+use crate::cli::prompt::prompts::{Confirm, Input};
+use volt_core::{
+    install_state::InstallDiff,
+    plugin::{Level, Message},
+    reporter::{fetched, Reporter},
+    summary::InstallSummary,
+};
+
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, ContentArrangement, Table};
+use dialoguer::console::Term;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use miette::{IntoDiagnostic, Result};
+use serde_json::Value;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+    time::Duration,
+};
+
+/// The progress of `P` (bars, or plain lines), with the summaries, prompts and plugin
+/// messages in color.
+pub struct TerminalReporter<P> {
+    progress: P,
+}
+
+impl<P: Reporter> TerminalReporter<P> {
+    pub fn new(progress: P) -> Self {
+        Self { progress }
+    }
+}
+
+impl<P: Reporter> Reporter for TerminalReporter<P> {
+    fn resolving(&self, package: &str) {
+        self.progress.resolving(package);
+    }
+
+    fn resolved(&self, count: usize) {
+        self.progress.resolved(count);
+    }
+
+    fn installing(&self, total: usize) {
+        self.progress.installing(total);
+    }
+
+    fn download_progress(&self, bytes: u64) {
+        self.progress.download_progress(bytes);
+    }
+
+    fn downloaded(&self, package: &str) {
+        self.progress.downloaded(package);
+    }
+
+    fn extracted(&self, package: &str) {
+        self.progress.extracted(package);
+    }
+
+    fn finish(&self) {
+        self.progress.finish();
+    }
+
+    fn done(&self, action: &str, count: usize, elapsed: Duration) {
+        println!(
+            "{} {} {} dependencies",
+            format!("[{:.2}{}]", elapsed.as_secs_f32(), "s")
+                .truecolor(156, 156, 156)
+                .bold(),
+            action,
+            count.to_string().truecolor(196, 206, 255).bold()
+        );
+    }
+
+    fn changes(&self, diff: &InstallDiff) {
+        println!(
+            "{} {} {} packages",
+            format!("+{}", diff.added.len()).green().bold(),
+            format!("\u{2212}{}", diff.removed.len()).red().bold(),
+            format!("~{}", diff.changed.len()).yellow().bold()
+        );
+    }
+
+    fn summary(&self, summary: &InstallSummary) {
+        println!("{}", fetched(summary).truecolor(156, 156, 156));
+
+        println!(
+            "{}",
+            summary
+                .phases
+                .iter()
+                .map(|(phase, elapsed)| format!(
+                    "{} {}",
+                    phase,
+                    format!("{:.2}s", elapsed).truecolor(196, 206, 255).bold()
+                ))
+                .collect::<Vec<_>>()
+                .join(&", ".truecolor(156, 156, 156).to_string())
+        );
+    }
+
+    fn signatures(&self, verified: usize, unsigned: usize) {
+        let unsigned = if unsigned > 0 {
+            format!(", {} unsigned", unsigned.to_string().yellow().bold())
+        } else {
+            String::new()
+        };
+
+        println!(
+            "{} {} verified registry signatures{}",
+            "✓".green().bold(),
+            verified.to_string().truecolor(196, 206, 255).bold(),
+            unsigned
+        );
+    }
+
+    fn approve_scripts(&self, packages: &[(String, Vec<(&str, String)>)]) -> Result<bool> {
+        if !(Term::stdout().is_term() && Term::stderr().is_term()) {
+            return Ok(false);
+        }
+
+        eprintln!(
+            "{} {} to run install scripts:",
+            "note:".cyan().bold(),
+            if packages.len() == 1 {
+                "1 dependency wants".to_string()
+            } else {
+                format!("{} dependencies want", packages.len())
+            }
+        );
+
+        for (package, scripts) in packages {
+            eprintln!("  {}", package);
+
+            for (event, script) in scripts {
+                eprintln!(
+                    "    {}",
+                    format!("{}: {}", event, script).truecolor(156, 156, 156)
+                );
+            }
+        }
+
+        let confirm = Confirm {
+            message: "Run their install scripts?".into(),
+            default: false,
+        };
+
+        confirm.run().into_diagnostic()
+    }
+
+    fn one_time_password(&self, registry: &str) -> Result<Option<String>> {
+        if !(Term::stdout().is_term() && Term::stderr().is_term()) {
+            return Ok(None);
+        }
+
+        let input = Input {
+            message: format!("One-time password for {}", registry).into(),
+            default: None,
+            allow_empty: false,
+        };
+
+        input.run().map(Some).into_diagnostic()
+    }
+
+    fn message(&self, message: &Message) {
+        match message {
+            Message::Log { level, message } => match level {
+                Level::Info => println!("{}", message),
+                Level::Success => println!("{} {}", "success".green().bold(), message),
+                Level::Warn => eprintln!("{} {}", "warning".yellow().bold(), message),
+                Level::Error => eprintln!("{} {}", "error".red().bold(), message),
+            },
+            Message::Table { headers, rows } => {
+                let mut table = Table::new();
+
+                table
+                    .load_preset(UTF8_FULL)
+                    .set_content_arrangement(ContentArrangement::Dynamic);
+
+                if !headers.is_empty() {
+                    table.set_header(
+                        headers
+                            .iter()
+                            .map(|header| Cell::new(header).add_attribute(Attribute::Bold)),
+                    );
+                }
+
+                for row in rows {
+                    table.add_row(row.iter().map(Message::cell));
+                }
+
+                println!("{}", table);
+            }
+            Message::Result(Value::String(string)) => println!("{}", string),
+            Message::Result(value) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(value).unwrap_or_default()
+                );
+            }
+        }
+    }
+}
+
+/// Multiple bars: the resolved packages, the downloads and their speed, the extracted
+/// packages.
+pub struct Bars {
+    bars: MultiProgress,
+    resolve: Bar,
+    download: Bar,
+    extract: Bar,
+    downloaded: AtomicUsize,
+}
+
+/// A bar only drawn once its phase starts, so nothing shows for phases that don't happen.
+struct Bar {
+    bar: ProgressBar,
+    started: Once,
+}
+
+impl Bar {
+    fn new(style: ProgressStyle) -> Self {
+        Self {
+            bar: ProgressBar::with_draw_target(0, ProgressDrawTarget::hidden()).with_style(style),
+            started: Once::new(),
+        }
+    }
+
+    fn start(&self, bars: &MultiProgress) -> &ProgressBar {
+        self.started.call_once(|| {
+            bars.add(self.bar.clone()).enable_steady_tick(80);
+        });
+
+        &self.bar
+    }
+}
+
+impl Bars {
+    pub fn new() -> Self {
+        let spinner = |template: &str| ProgressStyle::default_spinner().template(template);
+
+        let download = Bar::new(spinner(
+            "{spinner:.cyan} downloaded {prefix} ({bytes}, {binary_bytes_per_sec}) {msg}",
+        ));
+
+        download.bar.set_prefix("0");
+
+        Self {
+            bars: MultiProgress::with_draw_target(ProgressDrawTarget::stderr()),
+            resolve: Bar::new(spinner("{spinner:.cyan} resolved {pos} {msg}")),
+            download,
+            extract: Bar::new(
+                ProgressStyle::default_bar()
+                    .template("[{bar:40.cyan/blue}] {pos:>7}/{len:7} {msg}")
+                    .progress_chars("=>-"),
+            ),
+            downloaded: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for Bars {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporter for Bars {
+    fn resolving(&self, package: &str) {
+        self.resolve
+            .start(&self.bars)
+            .set_message(package.truecolor(125, 125, 125).to_string());
+    }
+
+    fn resolved(&self, count: usize) {
+        self.resolve.start(&self.bars).inc(count as u64);
+    }
+
+    fn installing(&self, total: usize) {
+        self.resolve.bar.finish_and_clear();
+
+        self.extract.bar.set_length(total as u64);
+        self.extract.start(&self.bars);
+    }
+
+    fn download_progress(&self, bytes: u64) {
+        self.download.start(&self.bars).inc(bytes);
+    }
+
+    fn downloaded(&self, package: &str) {
+        let count = self.downloaded.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let bar = self.download.start(&self.bars);
+
+        bar.set_prefix(count.to_string());
+        bar.set_message(package.truecolor(125, 125, 125).to_string());
+    }
+
+    fn extracted(&self, package: &str) {
+        let bar = self.extract.start(&self.bars);
+
+        bar.inc(1);
+        bar.set_message(package.to_string());
+    }
+
+    fn finish(&self) {
+        for bar in [&self.resolve, &self.download, &self.extract] {
+            bar.bar.finish_and_clear();
+        }
+    }
+}
@@ -0,0 +1,210 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Write the spans of a run to a file in the Chrome trace format (`--trace-file`).
+//!
+//! The resolver, the downloads, the extractions, the linker and the install scripts run in
+//! spans; each becomes a complete event (`"ph": "X"`) with the fields of the span as its
+//! arguments, which `chrome://tracing`, Perfetto and speedscope show on a timeline.
+//! Spans of async code last from their first poll to their end, waits included, on the
+//! thread that first polled them.
+
+use serde_json::{json, Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    span, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+use volt_core::utils::errors::FilesystemError;
+
+use std::{
+    fmt,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+/// Records the spans that close into events.
+pub struct ChromeLayer {
+    start: Instant,
+    events: Arc<Mutex<Vec<Value>>>,
+}
+
+/// Writes the events recorded by its [`ChromeLayer`] once the run is over.
+pub struct TraceFile {
+    path: PathBuf,
+    events: Arc<Mutex<Vec<Value>>>,
+}
+
+/// The layer recording the spans of the run, and the file they go to.
+pub fn chrome_trace(path: PathBuf) -> (ChromeLayer, TraceFile) {
+    let events = Arc::default();
+
+    (
+        ChromeLayer {
+            start: Instant::now(),
+            events: Arc::clone(&events),
+        },
+        TraceFile { path, events },
+    )
+}
+
+impl TraceFile {
+    /// Write the events of the spans closed so far.
+    pub fn write(&self) -> miette::Result<()> {
+        let events = self
+            .events
+            .lock()
+            .map(|events| events.clone())
+            .unwrap_or_default();
+
+        let trace = json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        });
+
+        std::fs::write(&self.path, trace.to_string()).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: self.path.display().to_string(),
+        })?;
+
+        Ok(())
+    }
+}
+
+/// What is known of a span until it closes.
+struct Timing {
+    args: Map<String, Value>,
+    /// When it was first entered, and on which thread
+    entered: Option<(Instant, u64)>,
+}
+
+impl<S> Layer<S> for ChromeLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut args = Args(Map::new());
+
+        attrs.record(&mut args);
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Timing {
+                args: args.0,
+                entered: None,
+            });
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<Timing>() {
+                let mut args = Args(std::mem::take(&mut timing.args));
+
+                values.record(&mut args);
+
+                timing.args = args.0;
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<Timing>() {
+                timing
+                    .entered
+                    .get_or_insert_with(|| (Instant::now(), thread_id()));
+            }
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+
+        let timing = match span.extensions_mut().remove::<Timing>() {
+            Some(timing) => timing,
+            None => return,
+        };
+
+        // never entered, nothing ran in it
+        let (entered, thread) = match timing.entered {
+            Some(entered) => entered,
+            None => return,
+        };
+
+        let event = json!({
+            "name": span.name(),
+            "cat": span.metadata().target(),
+            "ph": "X",
+            "ts": micros(entered.duration_since(self.start)),
+            "dur": micros(entered.elapsed()),
+            "pid": std::process::id(),
+            "tid": thread,
+            "args": timing.args,
+        });
+
+        if let Ok(mut events) = self.events.lock() {
+            events.push(event);
+        }
+    }
+}
+
+fn micros(duration: std::time::Duration) -> f64 {
+    duration.as_nanos() as f64 / 1000.0
+}
+
+/// A small number for the current thread, the same for all its spans.
+fn thread_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+
+    thread_local! {
+        static ID: u64 = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    ID.with(|id| *id)
+}
+
+/// The fields of a span as the arguments of its event.
+struct Args(Map<String, Value>);
+
+impl Visit for Args {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+}
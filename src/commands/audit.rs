@@ -14,168 +14,374 @@
     limitations under the License.
 */
 
-//! Handle an unknown command (can be listed in scripts).
-
-use async_trait::async_trait;
-use miette::Result;
-use serde::{Deserialize, Serialize};
-
-use std::collections::HashMap;
+//! Report and fix the known vulnerabilities of the installed packages.
 
 use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    audit::{
+        fetch_advisories, find_fix, find_vulnerabilities, Advisory, Fix, Severity, Vulnerability,
+    },
+    install::{
+        dependency_specs, install, link_workspace_members, locked_resolution, project_dependencies,
+        resolve, write_lock_file, InstallScope,
+    },
+    model::lock_file::LockFile,
+    net::fetch_versions,
+    reporter::{emit, Event},
+    update::update_ranges,
+    utils::{errors::VoltError, package::PackageJson},
+};
 
-pub struct Audit {}
-
-#[derive(Debug)]
-pub struct AuditObject {
-    _name: String,
-    _version: String,
-    _install: Vec<String>,
-    _remove: Vec<String>,
-    _metadata: HashMap<String, String>,
-    _requires: HashMap<String, String>,
-    _dependencies: HashMap<String, AuditDependency>,
-}
-
-#[derive(Debug)]
-pub struct AuditDependency {
-    _version: String,
-    _integrity: String,
-    _requires: HashMap<String, String>,
-    _dependencies: HashMap<String, AuditDependency>,
-    _dev: bool,
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use colored::{ColoredString, Colorize};
+use miette::{IntoDiagnostic, Result};
+use node_semver::Version;
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+
+/// Chains printed per vulnerability before the rest are summarized.
+const MAX_CHAINS: usize = 5;
+
+/// Check the packages in volt.lock against the npm security advisories
+#[derive(Debug, Parser)]
+pub struct Audit {
+    #[clap(subcommand)]
+    cmd: Option<AuditCommand>,
+
+    /// Lowest severity that makes the command fail
+    #[clap(long, arg_enum, default_value = "info")]
+    audit_level: Severity,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AuditResponse {
-    actions: Vec<String>,
-    advisories: HashMap<String, String>,
-    muted: Vec<String>,
-    metadata: AuditMetadata,
+#[derive(Debug, Subcommand)]
+pub enum AuditCommand {
+    Fix(AuditFix),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Vulnerabilities {
-    info: u128,
-    low: u128,
-    moderate: u128,
-    high: u128,
-    critical: u128,
+/// Upgrade vulnerable dependencies to the closest fixed versions
+#[derive(Debug, Parser)]
+pub struct AuditFix {
+    /// Also apply fixes that need a major version bump
+    #[clap(long)]
+    force: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AuditMetadata {
-    vulnerabilities: Vulnerabilities,
-    dependencies: u128,
-    dev_dependencies: u128,
-    optional_dependencies: u128,
-    total_dependencies: u128,
+/// The report printed by `volt --json audit`.
+#[derive(Serialize)]
+struct Report<'a> {
+    /// Number of packages audited
+    packages: usize,
+    /// Number of vulnerabilities per severity
+    summary: BTreeMap<Severity, usize>,
+    vulnerabilities: &'a [Vulnerability],
 }
 
-// pub fn flatten_dependency_tree(
-//     package: &VoltPackage,
-//     volt_packages: &HashMap<String, VoltPackage>,
-// ) -> Vec<AuditDependency> {
-//     let mut packages: Vec<AuditDependency> = vec![];
-
-//     if package.dependencies.is_some() {
-//         for dep in package.dependencies.as_ref().unwrap().iter() {
-//             let dependency = volt_packages.get(dep).unwrap();
-
-//             let mut requires: HashMap<String, String> = HashMap::new();
-
-//             if dependency.dependencies.is_some() {
-//                 for de in dependency.dependencies.as_ref().unwrap() {
-//                     let pkg = volt_packages.get(de).unwrap();
-//                     requires.insert(de.to_owned(), pkg.version.clone());
-//                 }
-//             }
-
-//             let fdt = &mut flatten_dependency_tree(dependency, volt_packages);
-
-//             packages.push(AuditDependency {
-//                 version: dependency.version,
-//                 integrity: dependency.integrity,
-//                 requires: requires,
-//                 dependencies: HashMap::new()
-//                     .insert(
-//                         dep.to_owned(),
-//                         ,
-//                     )
-//                     .unwrap(),
-//                 dev: false,
-//             });
-//         }
-//     }
-
-//     packages
-// }
-
 #[async_trait]
 impl VoltCommand for Audit {
     /// Execute the `volt audit` command
     ///
-    /// Execute a audit command
+    /// Send every registry package of `volt.lock` to the npm advisory endpoint and print
+    /// the vulnerable ones grouped by severity, with the dependencies that pull them in.
     /// ## Arguments
-    /// * `error` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// //
+    /// // Fail on high and critical vulnerabilities only
     /// // .exec() is an async call so you need to await it
-    /// Audit.exec(app).await;
+    /// Audit { cmd: None, audit_level: Severity::High }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        // let package_json = PackageJson::from("package.json");
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        if let Some(AuditCommand::Fix(fix)) = self.cmd {
+            return fix.exec(config).await;
+        }
+
+        let (package_json, _) = PackageJson::get_from_dir(&config.cwd()?)?;
+        let (dependencies, _) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        let lock_file = locked_tree(&config, &dependencies)?;
+        let vulnerabilities = audit(&config, &lock_file, &dependencies).await?;
+
+        let packages = registry_packages(&lock_file);
+
+        if config.json() {
+            let report = Report {
+                packages,
+                summary: summary(&vulnerabilities),
+                vulnerabilities: &vulnerabilities,
+            };
+
+            emit(&Event::Result(
+                serde_json::to_value(&report).into_diagnostic()?,
+            ));
+        } else {
+            print_report(&package_json.name, &vulnerabilities);
+            print_summary(&vulnerabilities, packages);
+        }
+
+        let count = vulnerabilities
+            .iter()
+            .filter(|vulnerability| vulnerability.advisory.severity >= self.audit_level)
+            .count();
+
+        if count > 0 {
+            return Err(VoltError::Vulnerable {
+                count,
+                level: self.audit_level.to_string(),
+            }
+            .into());
+        }
 
-        // let mut requires = package_json.dependencies;
-        // requires.extend(package_json.dev_dependencies);
+        Ok(())
+    }
+}
 
-        // let responses =
-        //     utils::get_volt_response_multi(requires.keys().cloned().collect::<Vec<String>>()).await;
+#[async_trait]
+impl VoltCommand for AuditFix {
+    /// Execute the `volt audit fix` command
+    ///
+    /// Raise the ranges of the vulnerable direct dependencies in package.json to the lowest
+    /// fixed versions (within their major version unless `--force` is given), resolve the
+    /// whole tree again to pick up fixed transitive dependencies and install it.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Apply every fix, major version bumps included
+    /// // .exec() is an async call so you need to await it
+    /// AuditFix { force: true }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (package_json, manifest_path) = PackageJson::get_from_dir(&config.cwd()?)?;
+        let (dependencies, _) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        let lock_file = locked_tree(&config, &dependencies)?;
+        let vulnerabilities = audit(&config, &lock_file, &dependencies).await?;
+
+        if vulnerabilities.is_empty() {
+            print_summary(&vulnerabilities, registry_packages(&lock_file));
+
+            return Ok(());
+        }
+
+        // the advisories of each vulnerable package the project depends on directly
+        let mut direct: BTreeMap<(&str, &str), Vec<&Advisory>> = BTreeMap::new();
+
+        for vulnerability in &vulnerabilities {
+            let key = format!("{}@{}", vulnerability.name, vulnerability.version);
+
+            if vulnerability
+                .chains
+                .iter()
+                .any(|chain| chain.len() == 1 && chain[0] == key)
+            {
+                direct
+                    .entry((&vulnerability.name, &vulnerability.version))
+                    .or_default()
+                    .push(&vulnerability.advisory);
+            }
+        }
+
+        let mut ranges = BTreeMap::new();
+        // (name, fixed version) of the fixes left for `--force`
+        let mut breaking = vec![];
+
+        for ((name, version), advisories) in direct {
+            let current: Version = match version.parse() {
+                Ok(version) => version,
+                Err(_) => continue,
+            };
+
+            match find_fix(&current, &fetch_versions(&config, name).await?, &advisories) {
+                Fix::Compatible(fixed) => {
+                    ranges.insert(name.to_string(), format!("^{}", fixed));
+                }
+                Fix::Major(fixed) if self.force => {
+                    ranges.insert(name.to_string(), format!("^{}", fixed));
+                }
+                Fix::Major(fixed) => breaking.push((name, fixed)),
+                Fix::Unavailable => {}
+            }
+        }
+
+        update_ranges(&manifest_path, &ranges)?;
+
+        // resolve from scratch, letting transitive dependencies move within their ranges
+        let (dependencies, workspace) =
+            project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        let resolution = resolve(&config, &dependency_specs(&dependencies)).await?;
+
+        write_lock_file(&config, &resolution)?;
+
+        let mut fixed_tree = LockFile::new(config.lockfile()?, false);
+        fixed_tree.extend(&resolution.tree);
+
+        install(&config, resolution).await?;
+
+        if let Some(workspace) = &workspace {
+            link_workspace_members(&config, workspace)?;
+        }
+
+        let remaining = audit(&config, &fixed_tree, &dependencies).await?;
+
+        for (name, range) in &ranges {
+            println!("{} {} to {}", "Updated".green().bold(), name, range);
+        }
+
+        println!(
+            "{} fixed {} of {} vulnerabilities",
+            "audit:".green().bold(),
+            vulnerabilities.len().saturating_sub(remaining.len()),
+            vulnerabilities.len()
+        );
+
+        if !remaining.is_empty() {
+            println!();
+            print_report(&package_json.name, &remaining);
+
+            for (name, fixed) in &breaking {
+                println!(
+                    "{} {}@{} fixes {}, a breaking change: run `volt audit fix --force` to install it",
+                    "note:".cyan().bold(),
+                    name,
+                    fixed,
+                    name
+                );
+            }
+        }
 
-        // let mut dependencies: HashMap<String, AuditDependency> = HashMap::new();
+        Ok(())
+    }
+}
 
-        // let start = Instant::now();
+/// The part of volt.lock installed for the project's current dependencies.
+fn locked_tree(config: &VoltConfig, dependencies: &BTreeMap<String, String>) -> Result<LockFile> {
+    let resolution = locked_resolution(config, dependencies)?;
 
-        // for res in responses {
-        //     let version = res.version;
+    let mut tree = LockFile::new(&config.lockfile()?, false);
+    tree.extend(&resolution.tree);
 
-        //     let packages = &res.versions.get(&version).unwrap().packages;
+    Ok(tree)
+}
 
-        //     for package in packages {
-        //         let flattened_dependency_tree: Vec<AuditDependency> =
-        //             flatten_dependency_tree(package.1, packages);
+/// Fetch the advisories of a locked tree and find its vulnerable packages.
+async fn audit(
+    config: &VoltConfig,
+    lock_file: &LockFile,
+    dependencies: &BTreeMap<String, String>,
+) -> Result<Vec<Vulnerability>> {
+    let advisories = fetch_advisories(config, lock_file).await?;
+    let direct: BTreeMap<&String, &String> = dependencies.iter().collect();
 
-        //         for dp in flattened_dependency_tree {
-        //             dependencies.insert(package.0.to_owned(), dp);
-        //         }
-        //     }
-        // }
+    Ok(find_vulnerabilities(lock_file, &advisories, &direct))
+}
 
-        // let audit = AuditObject {
-        //     name: package_json.name,
-        //     version: package_json.version,
-        //     install: vec![],
-        //     remove: vec![],
-        //     metadata: HashMap::new(),
-        //     requires: requires,
-        //     dependencies: dependencies,
-        // };
+fn registry_packages(lock_file: &LockFile) -> usize {
+    lock_file
+        .dependencies
+        .values()
+        .filter(|package| package.is_registry())
+        .count()
+}
 
-        // let mut response = chttp::post_async(
-        //     "http://registry.npmjs.org/-/npm/v1/security/audits",
-        //     format!("{:?}", audit),
-        // )
-        // .await
-        // .unwrap();
+/// Number of vulnerabilities per severity.
+fn summary(vulnerabilities: &[Vulnerability]) -> BTreeMap<Severity, usize> {
+    let mut summary = BTreeMap::new();
 
-        // let text = response.text_async().await.unwrap();
+    for vulnerability in vulnerabilities {
+        *summary.entry(vulnerability.advisory.severity).or_insert(0) += 1;
+    }
 
-        // let response: AuditResponse = serde_json::from_str(text.as_str()).unwrap();
+    summary
+}
 
-        Ok(())
+fn print_summary(vulnerabilities: &[Vulnerability], packages: usize) {
+    if vulnerabilities.is_empty() {
+        println!(
+            "{} found no vulnerabilities in {} packages",
+            "audit:".green().bold(),
+            packages
+        );
+
+        return;
+    }
+
+    let counts = summary(vulnerabilities)
+        .iter()
+        .rev()
+        .map(|(severity, count)| format!("{} {}", count, paint(*severity)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    println!(
+        "{} found {} vulnerabilities ({}) in {} packages",
+        "audit:".yellow().bold(),
+        vulnerabilities.len(),
+        counts,
+        packages
+    );
+}
+
+/// Print the vulnerabilities (sorted most severe first) under a heading per severity.
+fn print_report(project: &str, vulnerabilities: &[Vulnerability]) {
+    let mut severity = None;
+
+    for vulnerability in vulnerabilities {
+        let advisory = &vulnerability.advisory;
+
+        if severity != Some(advisory.severity) {
+            severity = Some(advisory.severity);
+            println!("{}", paint(advisory.severity).bold());
+        }
+
+        println!(
+            "  {}@{} {}",
+            vulnerability.name.truecolor(000, 255, 000),
+            vulnerability.version.truecolor(000, 155, 000),
+            advisory.title
+        );
+        println!(
+            "    vulnerable versions {}",
+            advisory.vulnerable_versions.truecolor(156, 156, 156)
+        );
+        println!("    {}", advisory.url.truecolor(196, 206, 255));
+
+        for chain in vulnerability.chains.iter().take(MAX_CHAINS) {
+            let chain: Vec<&str> = std::iter::once(project)
+                .chain(chain.iter().map(String::as_str))
+                .collect();
+
+            println!(
+                "    {}",
+                chain.join(&" > ".truecolor(156, 156, 156).to_string())
+            );
+        }
+
+        if vulnerability.chains.len() > MAX_CHAINS {
+            println!(
+                "    ... and {} more",
+                vulnerability.chains.len() - MAX_CHAINS
+            );
+        }
+
+        println!();
+    }
+}
+
+fn paint(severity: Severity) -> ColoredString {
+    let name = severity.to_string();
+
+    match severity {
+        Severity::Critical => name.magenta(),
+        Severity::High => name.red(),
+        Severity::Moderate => name.yellow(),
+        Severity::Low => name.cyan(),
+        Severity::Info => name.normal(),
     }
 }
@@ -0,0 +1,178 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Snapshot the dependencies of a project into one file, and install from it.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    bundle::{create_bundle, restore_bundle},
+    install::{install, project_dependencies, InstallScope, Resolution},
+    model::lock_file::LockFile,
+    reporter::{emit, Event},
+    signatures::verify_signatures,
+    utils::errors::{FilesystemError, ResolutionError},
+};
+
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::{IntoDiagnostic, Result};
+use serde_json::json;
+
+use std::path::PathBuf;
+
+/// Snapshot volt.lock and every tarball it needs into one file, to install without network
+#[derive(Debug, Parser)]
+pub struct Bundle {
+    #[clap(subcommand)]
+    cmd: BundleCommand,
+}
+
+#[async_trait]
+impl VoltCommand for Bundle {
+    /// Execute the `volt bundle` command
+    ///
+    /// Pack the lock file of the project with the tarballs of its registry packages, or
+    /// restore such a bundle and install it without reaching the registry.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Install the snapshot made on another machine
+    /// // .exec() is an async call so you need to await it
+    /// Bundle { cmd: BundleCommand::Restore(BundleRestore { file: "app.volt.tgz".into() }) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        match self.cmd {
+            BundleCommand::Create(x) => x.exec(config).await,
+            BundleCommand::Restore(x) => x.exec(config).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BundleCommand {
+    Create(BundleCreate),
+    Restore(BundleRestore),
+}
+
+/// Write the bundle of the project, from its up-to-date volt.lock
+#[derive(Debug, Parser)]
+pub struct BundleCreate {
+    /// File to write the bundle to
+    #[clap(long, short, default_value = "volt-bundle.tgz")]
+    output: PathBuf,
+}
+
+#[async_trait]
+impl VoltCommand for BundleCreate {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (dependencies, _) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        // a bundle holds exactly what is locked
+        let resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+            ResolutionError::LockFileOutdated {
+                path: config.lockfile()?.display().to_string(),
+            },
+        )?;
+
+        // restored bundles are installed without checking signatures
+        if let Some(signatures) = verify_signatures(&config, &resolution).await? {
+            if !signatures.unsigned.is_empty() {
+                config.reporter().warning(&format!(
+                    "no registry signature for {}",
+                    signatures.unsigned.join(", ")
+                ));
+            }
+        }
+
+        let bundle = create_bundle(&config, &resolution).await?;
+
+        let output = config.cwd()?.join(&self.output);
+
+        std::fs::write(&output, &bundle).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: output.display().to_string(),
+        })?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "file": output,
+                "packages": resolution.tree.len(),
+                "bytes": bundle.len(),
+            })));
+
+            return Ok(());
+        }
+
+        println!(
+            "{} {} packages into {} ({})",
+            "Bundled".green().bold(),
+            resolution.tree.len(),
+            self.output.display(),
+            HumanBytes(bundle.len() as u64)
+        );
+
+        Ok(())
+    }
+}
+
+/// Write the files of a bundle into the project and install them, without network
+#[derive(Debug, Parser)]
+pub struct BundleRestore {
+    /// The bundle, written by `volt bundle create`
+    file: PathBuf,
+}
+
+#[async_trait]
+impl VoltCommand for BundleRestore {
+    async fn exec(self, mut config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        let bundle = std::fs::read(&self.file).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: self.file.display().to_string(),
+        })?;
+
+        std::fs::create_dir_all(&cwd).map_err(|e| FilesystemError::CreateDir {
+            source: e,
+            path: cwd.display().to_string(),
+        })?;
+
+        restore_bundle(&config, &bundle)?;
+
+        // the project is now vendored: nothing is resolved or downloaded from the registry
+        config.vendored = true;
+        config.load_settings()?;
+
+        let (dependencies, _) = project_dependencies(&cwd, &[], InstallScope::All)?;
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+            ResolutionError::LockFileOutdated {
+                path: config.lockfile()?.display().to_string(),
+            },
+        )?;
+
+        install(&config, resolution).await
+    }
+}
@@ -1,113 +1,415 @@
 /*
-Copyright 2021 Volt Contributors
-Licensed under the Apache License, Version 2.0 (the "License");
-you may not use this file except in compliance with the License.
-You may obtain a copy of the License at
-    http://www.apache.org/licenses/LICENSE-2.0
-Unless required by applicable law or agreed to in writing, software
-distributed under the License is distributed on an "AS IS" BASIS,
-WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-See the License for the specific language governing permissions and
-limitations under the License.
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
 */
 
-//! Clean cached download files.
+//! Manage the package store, the metadata cache and the task cache.
 
-use crate::{
-    App,
-    Command,
-    coreutils::constants::PROGRESS_CHARS,
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    cache::{clean, entries, verify},
+    io::directory_size,
+    lock::{lock_store, LockMode},
+    metadata_cache,
+    remote_cache::RemoteCache,
+    reporter::{emit, Event},
+    task_cache::TaskCache,
 };
-use volt_core::VERSION;
 
 use async_trait::async_trait;
+use clap::{Parser, Subcommand};
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
+use futures::{stream, StreamExt};
+use indicatif::HumanBytes;
 use miette::Result;
+use serde_json::json;
 
-use std::{
-    env::temp_dir,
-    fs::remove_file,
-    process::exit,
-    sync::Arc,
-};
-
-/// Struct implementation for the `Add` command.
-#[derive(Clone)]
-pub struct Cache {}
+/// Manage the package store, the metadata cache and the task cache
+#[derive(Debug, Parser)]
+pub struct Cache {
+    #[clap(subcommand)]
+    cmd: CacheCommand,
+}
 
 #[async_trait]
-impl Command for Cache {
-    /// Display a help menu for the `volt cahe` command.
-    fn help() -> String {
-        format!(
-            r#"volt {}
-    
-Handle the volt cache files.
-Usage: {} {} {}
-
-Commands:
-  clean - Clean downloaded cache files and metadata. 
-
-Options: 
-    
-  {} {} Output verbose messages on internal operations.
-  {} {} Disable progress bar."#,
-            VERSION.bright_green().bold(),
-            "volt".bright_green().bold(),
-            "cache".bright_purple(),
-            "[command]".bright_purple(),
-            "--verbose".blue(),
-            "(-v)".yellow(),
-            "--no-progress".blue(),
-            "(-np)".yellow()
-        )
-    }
-
+impl VoltCommand for Cache {
     /// Execute the `volt cache` command
     ///
-    /// Clean your download cache.
+    /// Inspect, verify or clean the store packages are installed from, clear the package
+    /// documents cached from registries, or share the task cache of `volt run --recursive`
+    /// with the remote cache.
     /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Clean your download cache (does not break symlinks)
+    /// // Remove every version of react from the store
     /// // .exec() is an async call so you need to await it
-    /// Add.exec(app).await;
+    /// Cache { cmd: CacheCommand::Clean(CacheClean { package: Some("react".into()) }) }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(app: Arc<App>) -> Result<()> {
-        // if app.args.len() == 1 {
-        //     println!("{}", Self::help());
-        //     exit(1);
-        // }
-        // if app.args[1].as_str() == "clean" {
-        //     let files: Vec<_> = fs::read_dir(temp_dir().join("volt")).unwrap().collect();
-
-        //     let count = files.len();
-
-        //     let progress_bar = ProgressBar::new(count.to_owned() as u64);
-
-        //     progress_bar.set_style(
-        //         ProgressStyle::default_bar()
-        //             .progress_chars(PROGRESS_CHARS)
-        //             .template(&format!(
-        //                 "{} [{{bar:40.magenta/blue}}] {{msg:.blue}} {{len}} / {{pos}}",
-        //                 "Deleting Cache".bright_blue()
-        //             )),
-        //     );
-
-        //     for file in files {
-        //         let os_str = file.unwrap().file_name();
-        //         let f_name = format!(r"{}volt\{}", temp_dir().display(), os_str.to_str().unwrap());
-
-        //         remove_file(f_name).unwrap();
-        //         progress_bar.inc(1);
-        //     }
-
-        //     progress_bar.finish();
-        // }
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        match self.cmd {
+            CacheCommand::Verify(x) => x.exec(config).await,
+            CacheCommand::Clean(x) => x.exec(config).await,
+            CacheCommand::Dir(x) => x.exec(config).await,
+            CacheCommand::Ls(x) => x.exec(config).await,
+            CacheCommand::Push(x) => x.exec(config).await,
+            CacheCommand::Pull(x) => x.exec(config).await,
+            CacheCommand::Metadata(x) => x.exec(config).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommand {
+    Verify(CacheVerify),
+    Clean(CacheClean),
+    Dir(CacheDir),
+    Ls(CacheLs),
+    Push(CachePush),
+    Pull(CachePull),
+    Metadata(CacheMetadata),
+}
+
+/// Re-hash the store, removing corrupt packages and content nothing refers to
+#[derive(Debug, Parser)]
+pub struct CacheVerify {}
+
+#[async_trait]
+impl VoltCommand for CacheVerify {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let _lock = lock_store(&config, LockMode::Exclusive).await?;
+
+        let verified = verify(&config.store()?)?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "checked": verified.checked,
+                "corrupt": verified.corrupt,
+                "collectedFiles": verified.collected.files,
+                "collectedBytes": verified.collected.bytes,
+            })));
+
+            return Ok(());
+        }
+
+        println!(
+            "{} {} entries, {} corrupt",
+            "Verified".green().bold(),
+            verified.checked,
+            verified.corrupt.len()
+        );
+
+        println!(
+            "{} {} unreferenced files, reclaiming {}",
+            "Collected".green().bold(),
+            verified.collected.files,
+            HumanBytes(verified.collected.bytes)
+        );
+
+        Ok(())
+    }
+}
+
+/// Remove a package from the store, or everything in it
+#[derive(Debug, Parser)]
+pub struct CacheClean {
+    /// Name of the package to remove (every version of it)
+    package: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for CacheClean {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let _lock = lock_store(&config, LockMode::Exclusive).await?;
+
+        let cleaned = clean(&config.store()?, self.package.as_deref())?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "removed": cleaned.packages,
+                "bytes": cleaned.bytes,
+            })));
+
+            return Ok(());
+        }
+
+        match &self.package {
+            Some(package) if cleaned.packages.is_empty() => {
+                println!("{} is not in the store", package);
+            }
+            Some(_) => {
+                for package in &cleaned.packages {
+                    println!("{} {}", "-".red(), package);
+                }
+
+                println!(
+                    "{} {} packages, reclaiming {}",
+                    "Removed".green().bold(),
+                    cleaned.packages.len(),
+                    HumanBytes(cleaned.bytes)
+                );
+            }
+            None => println!(
+                "{} the store, reclaiming {}",
+                "Cleaned".green().bold(),
+                HumanBytes(cleaned.bytes)
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+/// Print the path of the store
+#[derive(Debug, Parser)]
+pub struct CacheDir {}
+
+#[async_trait]
+impl VoltCommand for CacheDir {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        println!("{}", config.store()?.display());
+
+        Ok(())
+    }
+}
+
+/// Manage the package documents cached from registries
+#[derive(Debug, Parser)]
+pub struct CacheMetadata {
+    #[clap(subcommand)]
+    cmd: MetadataCommand,
+}
+
+#[async_trait]
+impl VoltCommand for CacheMetadata {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        match self.cmd {
+            MetadataCommand::Clear(x) => x.exec(config).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MetadataCommand {
+    Clear(MetadataClear),
+}
+
+/// Remove the cached package documents and resolutions, so the next requests download
+/// and resolve them again
+#[derive(Debug, Parser)]
+pub struct MetadataClear {}
+
+#[async_trait]
+impl VoltCommand for MetadataClear {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let bytes = metadata_cache::clear(&config.store()?)?;
+
+        if config.json() {
+            emit(&Event::Result(json!({ "bytes": bytes })));
+
+            return Ok(());
+        }
+
+        println!(
+            "{} the metadata cache, reclaiming {}",
+            "Cleaned".green().bold(),
+            HumanBytes(bytes)
+        );
+
+        Ok(())
+    }
+}
+
+/// List the packages in the store
+#[derive(Debug, Parser)]
+pub struct CacheLs {
+    /// Show the size of each package and the total
+    #[clap(long)]
+    size: bool,
+}
+
+#[async_trait]
+impl VoltCommand for CacheLs {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let store = config.store()?;
+        let entries = entries(&store)?;
+
+        let sizes: Vec<Option<u64>> = entries
+            .iter()
+            .map(|entry| self.size.then(|| entry.size(&store)))
+            .collect();
+
+        if config.json() {
+            emit(&Event::Result(json!(entries
+                .iter()
+                .zip(&sizes)
+                .map(|(entry, size)| json!({
+                    "name": entry.name,
+                    "version": entry.version,
+                    "size": size,
+                }))
+                .collect::<Vec<_>>())));
+
+            return Ok(());
+        }
+
+        for (entry, size) in entries.iter().zip(&sizes) {
+            match size {
+                Some(size) => println!(
+                    "{} {}",
+                    entry.spec(),
+                    HumanBytes(*size).to_string().truecolor(156, 156, 156)
+                ),
+                None => println!("{}", entry.spec()),
+            }
+        }
+
+        if self.size {
+            // files shared between packages are stored once
+            let content = directory_size(&store.join("content-v2"));
+
+            println!(
+                "\n{} packages, {} on disk",
+                entries.len(),
+                HumanBytes(content)
+            );
+        }
+
         Ok(())
     }
 }
+
+/// Upload script results from the task cache to the remote cache
+#[derive(Debug, Parser)]
+pub struct CachePush {
+    /// Hashes of the results to upload (every result in the task cache by default)
+    hashes: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for CachePush {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let remote = remote_cache(&config)?;
+        let cache = TaskCache::new(&config.volt_home()?);
+
+        let hashes = if self.hashes.is_empty() {
+            cache.hashes()?
+        } else {
+            self.hashes
+        };
+
+        let pushes = hashes.iter().cloned().map(|hash| {
+            let (remote, cache) = (remote.clone(), cache.clone());
+
+            async move {
+                let pushed = remote.push(&cache, &hash).await;
+
+                (hash, pushed)
+            }
+        });
+
+        let results: Vec<_> = stream::iter(pushes)
+            .buffer_unordered(config.settings().concurrency)
+            .collect()
+            .await;
+
+        let mut uploaded = vec![];
+
+        for (hash, result) in results {
+            if result? {
+                uploaded.push(hash);
+            }
+        }
+
+        uploaded.sort();
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "uploaded": uploaded,
+                "present": hashes.len() - uploaded.len(),
+            })));
+
+            return Ok(());
+        }
+
+        for hash in &uploaded {
+            println!("{} {}", "+".green(), hash);
+        }
+
+        println!(
+            "{} {} results, {} already in the remote cache",
+            "Pushed".green().bold(),
+            uploaded.len(),
+            hashes.len() - uploaded.len()
+        );
+
+        Ok(())
+    }
+}
+
+/// Download script results from the remote cache into the task cache
+#[derive(Debug, Parser)]
+pub struct CachePull {
+    /// Hashes of the results to download
+    #[clap(required = true)]
+    hashes: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for CachePull {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let remote = remote_cache(&config)?;
+        let cache = TaskCache::new(&config.volt_home()?);
+
+        let mut missing = vec![];
+
+        for hash in &self.hashes {
+            if !remote.pull(&cache, hash).await? {
+                missing.push(hash);
+            }
+        }
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "pulled": self.hashes.len() - missing.len(),
+                "missing": missing,
+            })));
+
+            return Ok(());
+        }
+
+        for hash in &missing {
+            println!("{} is not in the remote cache", hash);
+        }
+
+        println!(
+            "{} {} results",
+            "Pulled".green().bold(),
+            self.hashes.len() - missing.len()
+        );
+
+        Ok(())
+    }
+}
+
+fn remote_cache(config: &VoltConfig) -> Result<RemoteCache> {
+    match RemoteCache::from_config(config)? {
+        Some(remote) => Ok(remote),
+        None => miette::bail!(
+            "no remote cache is configured, set `remote-cache-url` in .voltrc or ~/.volt/config.toml"
+        ),
+    }
+}
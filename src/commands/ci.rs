@@ -0,0 +1,99 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Clean install of a project from its lock file.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{
+        check_lock_file, clean_install, link_workspace_members, project_dependencies,
+        resolve_peers, InstallScope, Resolution,
+    },
+    local::local_packages_changed,
+    model::lock_file::LockFile,
+    utils::errors::ResolutionError,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::{IntoDiagnostic, Result};
+
+/// Install a project from scratch, exactly as pinned by volt.lock
+#[derive(Debug, Parser)]
+pub struct Ci {
+    /// Install the peer dependencies that nothing else provides, from volt.lock
+    #[clap(long)]
+    install_peers: bool,
+}
+
+#[async_trait]
+impl VoltCommand for Ci {
+    /// Execute the `volt ci` command
+    ///
+    /// Delete `node_modules` and install every package from `volt.lock` without resolving
+    /// anything, verifying the integrity of each tarball.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Clean install of the project
+    /// // .exec() is an async call so you need to await it
+    /// Ci { install_peers: false }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let lock_path = config.lockfile()?;
+
+        if !lock_path.exists() {
+            return Err(ResolutionError::LockFileMissing {
+                path: lock_path.display().to_string(),
+            }
+            .into());
+        }
+
+        let lock_file = LockFile::load(&lock_path, false).into_diagnostic()?;
+
+        let (dependencies, workspace) =
+            project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        let outdated = || ResolutionError::LockFileOutdated {
+            path: lock_path.display().to_string(),
+        };
+
+        let mut resolution =
+            Resolution::from_lock_file(&lock_file, &dependencies).ok_or_else(outdated)?;
+
+        if local_packages_changed(&config, &resolution)? {
+            return Err(outdated().into());
+        }
+
+        if self.install_peers {
+            resolve_peers(&config, &mut resolution, &lock_file, true).await?;
+        }
+
+        // a lock file with packages nothing uses anymore is out of date too
+        check_lock_file(&config, &resolution)?;
+
+        clean_install(&config, resolution).await?;
+
+        if let Some(workspace) = &workspace {
+            link_workspace_members(&config, workspace)?;
+        }
+
+        Ok(())
+    }
+}
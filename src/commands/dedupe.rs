@@ -0,0 +1,188 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Reduce the duplicate packages of a project.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    dedupe::{apply, duplicates, edges, plan},
+    install::{install, link_workspace_members, project_dependencies, InstallScope, Resolution},
+    io::directory_size,
+    model::lock_file::LockFile,
+    reporter::{emit, Event},
+    utils::errors::{FilesystemError, ResolutionError},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::{IntoDiagnostic, Result};
+use serde_json::json;
+
+use std::collections::BTreeMap;
+
+/// Use a single version of packages locked more than once wherever their ranges allow
+#[derive(Debug, Parser)]
+pub struct Dedupe {
+    /// Only list what would be removed, without changing volt.lock or node_modules
+    #[clap(long)]
+    dry_run: bool,
+}
+
+#[async_trait]
+impl VoltCommand for Dedupe {
+    /// Execute the `volt dedupe` command
+    ///
+    /// Move the dependents of duplicated packages to the locked version most of them
+    /// accept, then rewrite volt.lock and node_modules without the versions left unused.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Remove the duplicates of the project
+    /// // .exec() is an async call so you need to await it
+    /// Dedupe { dry_run: false }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (dependencies, workspace) =
+            project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        if !config.lockfile()?.exists() {
+            return Err(ResolutionError::LockFileMissing {
+                path: config.lockfile()?.display().to_string(),
+            }
+            .into());
+        }
+
+        let mut lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let duplicates = duplicates(&lock_file);
+        let edges = edges(&config, &lock_file, &dependencies, &duplicates).await?;
+        let planned = plan(&duplicates, &edges);
+
+        let removed = apply(&mut lock_file, &dependencies, &edges, &planned);
+
+        let node_modules = config.node_modules()?;
+        let size_before = directory_size(&node_modules);
+
+        if !self.dry_run && !removed.is_empty() {
+            lock_file.save()?;
+
+            let lockfile = config.lockfile()?;
+
+            let resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+                ResolutionError::LockFileOutdated {
+                    path: lockfile.display().to_string(),
+                },
+            )?;
+
+            install(&config, resolution).await?;
+
+            if let Some(workspace) = &workspace {
+                link_workspace_members(&config, workspace)?;
+            }
+
+            // the virtual store keeps a directory per version
+            for key in &removed {
+                let directory = node_modules.join(".volt").join(key.replace('/', "+"));
+
+                if directory.exists() {
+                    std::fs::remove_dir_all(&directory).map_err(|e| FilesystemError::Remove {
+                        source: e,
+                        path: directory.display().to_string(),
+                    })?;
+                }
+            }
+        }
+
+        let saved = size_before.saturating_sub(directory_size(&node_modules));
+
+        // name -> (versions before, versions after)
+        let deduped: BTreeMap<&String, (&Vec<String>, Vec<&String>)> = duplicates
+            .iter()
+            .map(|(name, versions)| {
+                let after: Vec<&String> = versions
+                    .iter()
+                    .filter(|version| {
+                        lock_file
+                            .dependencies
+                            .contains_key(&format!("{}@{}", name, version))
+                    })
+                    .collect();
+
+                (name, (versions, after))
+            })
+            .filter(|(_, (versions, after))| after.len() < versions.len())
+            .collect();
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "deduped": deduped
+                    .iter()
+                    .map(|(name, (versions, after))| (name, json!({ "before": versions, "after": after })))
+                    .collect::<BTreeMap<_, _>>(),
+                "removed": removed,
+                "saved": saved,
+                "dryRun": self.dry_run,
+            })));
+
+            return Ok(());
+        }
+
+        if removed.is_empty() {
+            if duplicates.is_empty() {
+                println!("No duplicate packages");
+            } else {
+                println!(
+                    "No duplicates can be removed: the ranges of their dependents don't overlap ({} packages have several versions)",
+                    duplicates.len()
+                );
+            }
+
+            return Ok(());
+        }
+
+        for (name, (versions, after)) in &deduped {
+            let after: Vec<&str> = after.iter().map(|version| version.as_str()).collect();
+
+            println!(
+                "{} {} {}",
+                name.truecolor(000, 255, 000),
+                versions.join(", ").truecolor(156, 156, 156),
+                format!("-> {}", after.join(", ")).truecolor(000, 155, 000)
+            );
+        }
+
+        if self.dry_run {
+            println!(
+                "\nWould remove {} packages, run without `--dry-run` to remove them",
+                removed.len()
+            );
+        } else {
+            println!(
+                "\n{} {} packages, saving {}",
+                "Removed".green().bold(),
+                removed.len(),
+                HumanBytes(saved)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Deprecate published versions of a package.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    deprecate::deprecate,
+    registry::RegistryClient,
+    reporter::{emit, Event},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+use node_semver::Range;
+use package_spec::{PackageSpec, VersionSpec};
+use serde_json::json;
+
+/// Deprecate published versions of a package
+#[derive(Debug, Parser)]
+pub struct Deprecate {
+    /// The versions to deprecate, as `name@range` (every version by default)
+    package: PackageSpec,
+
+    /// Why, shown to whoever installs them; an empty message lifts the deprecation
+    message: String,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication
+    #[clap(long)]
+    otp: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Deprecate {
+    /// Execute the `volt deprecate` command
+    ///
+    /// Set a deprecation message on the published versions of a package in a range. It
+    /// needs a token for the registry, and a one-time password for accounts with
+    /// two-factor authentication.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Deprecate every 1.x version of my-package
+    /// // .exec() is an async call so you need to await it
+    /// Deprecate { package: "my-package@^1".parse()?, message: "use 2.x".into(), otp: None }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (name, range) = match &self.package {
+            PackageSpec::Npm {
+                name, requested, ..
+            } => {
+                let range = match requested {
+                    None => "*".to_string(),
+                    Some(VersionSpec::Version(version)) => version.to_string(),
+                    Some(VersionSpec::Range(range)) => range.to_string(),
+                    Some(VersionSpec::Tag(_)) => {
+                        miette::bail!("{} names a dist-tag, not a range of versions", self.package)
+                    }
+                };
+
+                (name.clone(), range.parse::<Range>().into_diagnostic()?)
+            }
+            package => miette::bail!("{} is not a registry package, as `name@range`", package),
+        };
+
+        let client = RegistryClient::new(&config)?.with_otp(self.otp);
+
+        let versions =
+            deprecate(&client, &name, &range, &self.message, &*config.reporter()).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": name,
+                "versions": versions,
+                "message": self.message,
+            })));
+
+            return Ok(());
+        }
+
+        let verb = if self.message.is_empty() {
+            "Undeprecated"
+        } else {
+            "Deprecated"
+        };
+
+        println!(
+            "{} {} of {}: {}",
+            verb.green().bold(),
+            versions.len(),
+            name.bold(),
+            versions.join(", ")
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Compare two published versions of a package.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    diff::{
+        diff_files, fetch_files, manifest_changes, FileChange, FileDiff, ManifestChange,
+        INSTALL_SCRIPTS, SUMMARIZED,
+    },
+    registry::RegistryClient,
+    reporter::{emit, Event},
+    utils::{errors::ResolutionError, package::NpmPackage},
+    view::resolve_version,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+use serde_json::{json, Value};
+
+use std::collections::BTreeMap;
+
+/// Show what changed in the files, dependencies and scripts of a package between versions
+#[derive(Debug, Parser)]
+pub struct Diff {
+    /// Package to compare
+    package: String,
+
+    /// Version, range or tag to compare from
+    from: String,
+
+    /// Version, range or tag to compare to
+    to: String,
+
+    /// Only show the summary, without the changes of each file
+    #[clap(long)]
+    summary: bool,
+}
+
+#[async_trait]
+impl VoltCommand for Diff {
+    /// Execute the `volt diff` command
+    ///
+    /// Download the tarballs of both versions from the registry, print the unified diff of
+    /// every file that differs, then summarize the changed files, dependencies and scripts.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Review an upgrade of express before accepting it
+    /// // .exec() is an async call so you need to await it
+    /// Diff { package: "express".into(), from: "4.17.1".into(), to: "4.18.0".into(), summary: false }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let client = RegistryClient::new(&config)?;
+
+        let packument = client.packument(&self.package).await?;
+        let package: NpmPackage = serde_json::from_value(packument).into_diagnostic()?;
+
+        let version = |requested: &str| -> Result<String> {
+            let version = resolve_version(&package, Some(requested)).ok_or_else(|| {
+                ResolutionError::NoMatchingVersion {
+                    name: self.package.clone(),
+                    requested: requested.to_string(),
+                }
+            })?;
+
+            Ok(version)
+        };
+
+        let (from, to) = (version(&self.from)?, version(&self.to)?);
+
+        let (old, new) = futures::try_join!(
+            fetch_files(&config, &package, &from),
+            fetch_files(&config, &package, &to)
+        )?;
+
+        let files = diff_files(&old, &new);
+
+        // the tarball's package.json is the one installed, the registry's may be edited
+        let manifest = |files: &BTreeMap<String, Vec<u8>>| {
+            files
+                .get("package.json")
+                .and_then(|contents| serde_json::from_slice::<Value>(contents).ok())
+                .unwrap_or(Value::Null)
+        };
+
+        let changes = manifest_changes(&manifest(&old), &manifest(&new));
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": self.package,
+                "from": from,
+                "to": to,
+                "files": files,
+                "changes": changes,
+            })));
+
+            return Ok(());
+        }
+
+        if !self.summary {
+            for file in &files {
+                print_patch(file);
+            }
+        }
+
+        print_summary(&self.package, &from, &to, &files, &changes);
+
+        Ok(())
+    }
+}
+
+fn print_patch(file: &FileDiff) {
+    println!(
+        "{}",
+        format!("diff --git a/{} b/{}", file.path, file.path).bold()
+    );
+
+    let patch = match &file.patch {
+        Some(patch) => patch,
+        None => {
+            println!("Binary files differ");
+
+            return;
+        }
+    };
+
+    for line in patch.lines() {
+        if line.starts_with("---") || line.starts_with("+++") {
+            println!("{}", line.bold());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+fn print_summary(name: &str, from: &str, to: &str, files: &[FileDiff], changes: &[ManifestChange]) {
+    let count = |change: FileChange| files.iter().filter(|file| file.change == change).count();
+
+    println!(
+        "\n{} {} → {}: {} files changed ({} added, {} removed), {} insertions(+), {} deletions(-)",
+        name.bold(),
+        from,
+        to,
+        files.len(),
+        count(FileChange::Added),
+        count(FileChange::Removed),
+        files.iter().map(|file| file.added).sum::<usize>(),
+        files.iter().map(|file| file.removed).sum::<usize>(),
+    );
+
+    for field in SUMMARIZED {
+        let changes: Vec<&ManifestChange> = changes
+            .iter()
+            .filter(|change| change.field == field)
+            .collect();
+
+        if changes.is_empty() {
+            continue;
+        }
+
+        println!("\n{}", field.bold());
+
+        for change in changes {
+            let line = match (&change.from, &change.to) {
+                (None, Some(to)) => format!("  {} {} {}", "+".green(), change.name, to),
+                (Some(from), None) => format!("  {} {} {}", "-".red(), change.name, from),
+                (Some(from), Some(to)) => {
+                    format!("  {} {} {} → {}", "~".yellow(), change.name, from, to)
+                }
+                (None, None) => continue,
+            };
+
+            // new install scripts are what an upgrade most needs reviewing for
+            if field == "scripts"
+                && change.to.is_some()
+                && INSTALL_SCRIPTS.contains(&change.name.as_str())
+            {
+                println!("{} {}", line, "(runs on install)".red().bold());
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Manage the dist-tags of a package.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    dist_tags::{add, list, remove},
+    registry::RegistryClient,
+    reporter::{emit, Event},
+    utils::package::PackageJson,
+};
+
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use miette::Result;
+use package_spec::{PackageSpec, VersionSpec};
+use serde_json::json;
+
+/// Manage the dist-tags of a package on the registry
+#[derive(Debug, Parser)]
+pub struct DistTag {
+    #[clap(subcommand)]
+    cmd: DistTagCommand,
+}
+
+#[async_trait]
+impl VoltCommand for DistTag {
+    /// Execute the `volt dist-tag` command
+    ///
+    /// List the dist-tags of a package, point one to a published version, or remove one.
+    /// Changes need a token for the registry, and a one-time password for accounts with
+    /// two-factor authentication.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Point `next` to react@18.3.0-rc.0
+    /// // .exec() is an async call so you need to await it
+    /// DistTag { cmd: DistTagCommand::Add(DistTagAdd { package: "react@18.3.0-rc.0".parse()?, tag: "next".into(), otp: None }) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        match self.cmd {
+            DistTagCommand::Add(x) => x.exec(config).await,
+            DistTagCommand::Rm(x) => x.exec(config).await,
+            DistTagCommand::Ls(x) => x.exec(config).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DistTagCommand {
+    Add(DistTagAdd),
+    Rm(DistTagRm),
+    Ls(DistTagLs),
+}
+
+/// Point a dist-tag to a published version
+#[derive(Debug, Parser)]
+pub struct DistTagAdd {
+    /// The version to tag, as `name@version`
+    package: PackageSpec,
+
+    /// The dist-tag
+    #[clap(default_value = "latest")]
+    tag: String,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication
+    #[clap(long)]
+    otp: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for DistTagAdd {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (name, version) = match &self.package {
+            PackageSpec::Npm {
+                name,
+                requested: Some(VersionSpec::Version(version)),
+                ..
+            } => (name.clone(), version.to_string()),
+            package => miette::bail!("{} is not a published version, as `name@1.2.3`", package),
+        };
+
+        let client = RegistryClient::new(&config)?.with_otp(self.otp);
+
+        add(&client, &name, &version, &self.tag, &*config.reporter()).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": name,
+                "tag": self.tag,
+                "version": version,
+            })));
+
+            return Ok(());
+        }
+
+        println!(
+            "{} {}@{} as {}",
+            "Tagged".green().bold(),
+            name,
+            version,
+            self.tag.bold()
+        );
+
+        Ok(())
+    }
+}
+
+/// Remove a dist-tag
+#[derive(Debug, Parser)]
+pub struct DistTagRm {
+    /// The package
+    name: String,
+
+    /// The dist-tag
+    tag: String,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication
+    #[clap(long)]
+    otp: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for DistTagRm {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let client = RegistryClient::new(&config)?.with_otp(self.otp);
+
+        remove(&client, &self.name, &self.tag, &*config.reporter()).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": self.name,
+                "tag": self.tag,
+            })));
+
+            return Ok(());
+        }
+
+        println!(
+            "{} {} from {}",
+            "Removed".green().bold(),
+            self.tag.bold(),
+            self.name
+        );
+
+        Ok(())
+    }
+}
+
+/// List the dist-tags of a package
+#[derive(Debug, Parser)]
+pub struct DistTagLs {
+    /// The package (the one in the current directory by default)
+    name: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for DistTagLs {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let name = match self.name {
+            Some(name) => name,
+            None => PackageJson::get_from_dir(&config.cwd()?)?.0.name,
+        };
+
+        let dist_tags = list(&RegistryClient::new(&config)?, &name).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!(dist_tags)));
+
+            return Ok(());
+        }
+
+        for (tag, version) in dist_tags.iter() {
+            println!("{}: {}", tag.bold(), version);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check the environment volt runs in.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    doctor::{diagnose, Outcome},
+    lock::{lock_store, LockMode},
+    reporter::{emit, Event},
+    utils::errors::VoltError,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::json;
+
+/// Check the environment for problems, suggesting how to fix them
+#[derive(Debug, Parser)]
+pub struct Doctor {}
+
+#[async_trait]
+impl VoltCommand for Doctor {
+    /// Execute the `volt doctor` command
+    ///
+    /// Check that the volt directory is writable, the node shims are on PATH, node runs,
+    /// the registry answers, the clock is in sync, node_modules has no broken links and
+    /// the store is intact.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // .exec() is an async call so you need to await it
+    /// Doctor {}.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        // the store isn't read while another volt is writing it
+        let _lock = lock_store(&config, LockMode::Shared).await?;
+
+        let diagnoses = diagnose(&config).await;
+
+        let failed = diagnoses
+            .iter()
+            .filter(|diagnosis| diagnosis.outcome == Outcome::Fail)
+            .count();
+
+        if config.json() {
+            emit(&Event::Result(json!(diagnoses
+                .iter()
+                .map(|diagnosis| json!({
+                    "check": diagnosis.check,
+                    "outcome": match diagnosis.outcome {
+                        Outcome::Pass => "pass",
+                        Outcome::Fail => "fail",
+                        Outcome::Skip => "skip",
+                    },
+                    "detail": diagnosis.detail,
+                    "remedy": diagnosis.remedy,
+                }))
+                .collect::<Vec<_>>())));
+        } else {
+            for diagnosis in &diagnoses {
+                let mark = match diagnosis.outcome {
+                    Outcome::Pass => "✓".green().bold(),
+                    Outcome::Fail => "✗".red().bold(),
+                    Outcome::Skip => "-".truecolor(156, 156, 156),
+                };
+
+                println!(
+                    "{} {} {}",
+                    mark,
+                    diagnosis.check,
+                    format!("({})", diagnosis.detail).truecolor(156, 156, 156)
+                );
+
+                if let Some(remedy) = &diagnosis.remedy {
+                    println!("  {} {}", "→".yellow(), remedy);
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(VoltError::DoctorFailed { count: failed }.into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Run a command with the executables of the project.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    lifecycle::{bin_dirs, command_env, quote_arg, read_manifest, status_prefixed},
+    utils::errors::ScriptError,
+    workspace::{Filter, Workspace, WorkspaceMember},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::Value;
+
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+/// Run a command with node_modules/.bin on PATH, in the project or in every workspace member
+#[derive(Debug, Parser)]
+pub struct Exec {
+    /// Run the command in every workspace member, dependencies first
+    #[clap(long, short)]
+    recursive: bool,
+
+    /// Only run in the matching workspace members
+    /// (`name`, `@scope/*`, `./path`, `[git-ref]`, `...name`, `name...`).
+    #[clap(long, short = 'F', requires = "recursive")]
+    filter: Vec<Filter>,
+
+    /// Run in the members in name order instead of dependencies first
+    #[clap(long, requires = "recursive")]
+    no_sort: bool,
+
+    /// Run in every member at once, prefixing each line of output with the member's name
+    #[clap(long, requires = "recursive")]
+    parallel: bool,
+
+    /// Command to run, followed by its arguments
+    #[clap(last = true, required = true)]
+    command: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Exec {
+    /// Execute the `volt exec` command
+    ///
+    /// Run a command with the `node_modules/.bin` of the project first on `PATH` and the
+    /// variables npm gives scripts set, like `npm exec`. With `--recursive` it is run in
+    /// every workspace member one after another, stopping at the first failure, or with
+    /// `--parallel` in all of them at once, reporting every member it failed in.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Type check every workspace member
+    /// // .exec() is an async call so you need to await it
+    /// Exec { recursive: true, filter: vec![], no_sort: false, parallel: false, command: vec!["tsc".into(), "--noEmit".into()] }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        if !self.recursive {
+            let package_json = read_manifest(&cwd).unwrap_or(Value::Null);
+
+            let status = self
+                .command(&config, &cwd, &package_json)?
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .map_err(|e| self.spawn_error(e))?;
+
+            if !status.success() {
+                return Err(self.failure(status.code().unwrap_or(-1)));
+            }
+
+            return Ok(());
+        }
+
+        let workspace = match Workspace::discover(&cwd)? {
+            Some(workspace) => workspace,
+            None => miette::bail!("--recursive needs a project with workspaces"),
+        };
+
+        let members = workspace.filter(&self.filter)?;
+
+        if members.is_empty() {
+            tracing::warn!("No workspace members matched the filters");
+
+            return Ok(());
+        }
+
+        let members = if self.no_sort || self.parallel {
+            members
+        } else {
+            workspace.topological_order(&members)
+        };
+
+        if self.parallel {
+            return self.exec_parallel(&config, &members);
+        }
+
+        for member in members {
+            println!(
+                "{}",
+                format!("{}$ {}", member.name(), self.display()).bold()
+            );
+
+            let package_json = read_manifest(&member.path).unwrap_or(Value::Null);
+
+            let status = self
+                .command(&config, &member.path, &package_json)?
+                .stdin(Stdio::inherit())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .status()
+                .map_err(|e| self.spawn_error(e))?;
+
+            if !status.success() {
+                return Err(self.failure(status.code().unwrap_or(-1)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Exec {
+    /// Run the command in every member at once and wait for all of them.
+    fn exec_parallel(&self, config: &VoltConfig, members: &[&WorkspaceMember]) -> Result<()> {
+        let width = members
+            .iter()
+            .map(|member| member.name().len())
+            .max()
+            .unwrap_or(0);
+
+        let mut running = vec![];
+
+        for member in members {
+            let package_json = read_manifest(&member.path).unwrap_or(Value::Null);
+            let mut command = self.command(config, &member.path, &package_json)?;
+
+            let name = member.name().to_string();
+            let prefix = format!("{:width$} |", name, width = width)
+                .cyan()
+                .to_string();
+
+            running.push((
+                name,
+                thread::spawn(move || status_prefixed(&mut command, &prefix, false)),
+            ));
+        }
+
+        let mut failed = vec![];
+
+        for (name, handle) in running {
+            match handle.join() {
+                Ok(Ok((status, _))) if status.success() => {}
+                Ok(Ok(_)) | Err(_) => failed.push(name),
+                Ok(Err(e)) => {
+                    let error = self.spawn_error(e);
+                    tracing::error!("{}: {}", name, error);
+
+                    failed.push(name);
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(ScriptError::CommandsFailed {
+                command: self.display(),
+                count: failed.len(),
+                members: failed.join(", "),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// The command, run in `cwd` with the environment of its package.json.
+    fn command(&self, config: &VoltConfig, cwd: &Path, package_json: &Value) -> Result<Command> {
+        // clap makes sure there is at least one value
+        let (program, args) = self.command.split_first().unwrap();
+
+        let mut command = Command::new(resolve_program(&bin_dirs(config, cwd)?, program));
+
+        command
+            .args(args)
+            .current_dir(cwd)
+            .envs(command_env(config, cwd, package_json, &[])?);
+
+        Ok(command)
+    }
+
+    fn display(&self) -> String {
+        self.command
+            .iter()
+            .map(|arg| quote_arg(arg))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn spawn_error(&self, source: std::io::Error) -> miette::Report {
+        ScriptError::CommandSpawn {
+            source,
+            command: self.command[0].clone(),
+        }
+        .into()
+    }
+
+    fn failure(&self, code: i32) -> miette::Report {
+        ScriptError::CommandFailed {
+            command: self.display(),
+            code,
+        }
+        .into()
+    }
+}
+
+/// The executable `program` names in the first of `bin_dirs` that has it, or `program` to
+/// be looked up on `PATH`.
+fn resolve_program(bin_dirs: &[PathBuf], program: &str) -> PathBuf {
+    if program.contains('/') || program.contains('\\') {
+        return PathBuf::from(program);
+    }
+
+    // the shims are `.cmd` files on windows, which `Command` won't find on its own
+    let file_name = if cfg!(windows) {
+        format!("{}.cmd", program)
+    } else {
+        program.to_string()
+    };
+
+    bin_dirs
+        .iter()
+        .map(|directory| directory.join(&file_name))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| PathBuf::from(program))
+}
@@ -0,0 +1,122 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Export the dependency graph.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    graph::{DependencyGraph, GraphFormat},
+    install::{locked_resolution, project_dependencies, InstallScope},
+    reporter::{emit, Event},
+    utils::package::PackageJson,
+};
+
+use async_trait::async_trait;
+use clap::{ArgEnum, Parser};
+use miette::Result;
+
+/// The dependencies kept by `--only`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum Only {
+    /// `dependencies` and what they depend on
+    Prod,
+    /// `devDependencies` and what they depend on
+    Dev,
+}
+
+/// Print the dependency graph as Graphviz DOT, mermaid or JSON
+#[derive(Debug, Parser)]
+pub struct Graph {
+    /// Output format
+    #[clap(long, arg_enum, default_value = "dot")]
+    format: GraphFormat,
+
+    /// How many levels of transitive dependencies to include (all by default)
+    #[clap(long)]
+    depth: Option<usize>,
+
+    /// Only the production or development dependencies
+    #[clap(long, arg_enum)]
+    only: Option<Only>,
+
+    /// Only the packages with this name, what they depend on and what depends on them
+    #[clap(long)]
+    focus: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Graph {
+    /// Execute the `volt graph` command
+    ///
+    /// Build the graph of the packages of volt.lock and print it in the chosen format.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Draw what pulls in `lodash`: volt -q graph --focus lodash | dot -Tsvg > graph.svg
+    /// // .exec() is an async call so you need to await it
+    /// Graph { format: GraphFormat::Dot, depth: None, only: None, focus: Some("lodash".into()) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        let scope = match self.only {
+            Some(Only::Prod) => InstallScope::Production,
+            Some(Only::Dev) => InstallScope::Development,
+            None => InstallScope::All,
+        };
+
+        let (dependencies, _) = project_dependencies(&cwd, &[], scope)?;
+        let resolution = locked_resolution(&config, &dependencies)?;
+
+        let root = PackageJson::get_from_dir(&cwd)
+            .map(|(package_json, _)| package_json.name)
+            .unwrap_or_else(|_| "project".to_string());
+
+        let mut graph = DependencyGraph::new(&root, &resolution);
+
+        if let Some(focus) = &self.focus {
+            graph.focus(focus);
+
+            if graph.nodes.is_empty() {
+                miette::bail!("{} is not in volt.lock", focus);
+            }
+        }
+
+        if let Some(depth) = self.depth {
+            graph.limit_depth(depth);
+        }
+
+        if config.json() {
+            emit(&Event::Result(graph.to_json()));
+
+            return Ok(());
+        }
+
+        match self.format {
+            GraphFormat::Dot => print!("{}", graph.to_dot()),
+            GraphFormat::Mermaid => print!("{}", graph.to_mermaid()),
+            GraphFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&graph.to_json()).unwrap_or_default()
+            ),
+        }
+
+        Ok(())
+    }
+}
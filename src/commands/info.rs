@@ -17,264 +17,177 @@
 //! Display info about a package.
 
 use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    registry::RegistryClient,
+    reporter::{emit, Event},
+    utils::{
+        errors::ResolutionError,
+        package::{NpmPackage, Version},
+    },
+    view::{field, resolve_version},
+};
 
 use async_trait::async_trait;
 use clap::Parser;
-use miette::Result;
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::{IntoDiagnostic, Result};
+use serde_json::Value;
 
 /// Display information about a package
 #[derive(Debug, Parser)]
-pub struct Info {}
+pub struct Info {
+    /// Package to display, with an optional version, range or tag (`react@17`)
+    package: String,
+
+    /// Field to display, as a dot-path (`dist.tarball`, `dist-tags`, `versions`)
+    field: Option<String>,
+}
 
 #[async_trait]
 impl VoltCommand for Info {
     /// Execute the `volt info` command
     ///
-    /// Display info about a package
+    /// Fetch the package from the registry configured in `.npmrc` and display the version
+    /// requested (`latest` by default), or only one of its fields.
     /// ## Arguments
-    /// * `error` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Display info about a package
+    /// // Display the tarball url of the latest version of react
     /// // .exec() is an async call so you need to await it
-    /// Info.exec(app).await;
+    /// Info { package: "react".into(), field: Some("dist.tarball".into()) }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        // #[allow(unused_assignments)]
-        // let mut name = String::new();
-
-        // if !std::env::current_dir()
-        //     .unwrap()
-        //     .join("package.json")
-        //     .exists()
-        //     && app.args.len() == 1
-        // {
-        //     println!(
-        //         "{}: {}\n",
-        //         "warning".yellow().bold(),
-        //         "Could not find a package.json file in the current directory"
-        //     );
-        //     name = utils::get_basename(app.current_dir.to_str().unwrap()).to_string();
-        // }
-
-        // let mut field: String = String::new();
-
-        // if app.args.len() > 2 {
-        //     field = String::from(&app.args[2]);
-        //     name = String::from(&app.args[1]);
-        // } else if app.args.len() == 2 {
-        //     name = String::from(&app.args[1]);
-        // }
-
-        // let package: Package = get_package(&name).await.unwrap().unwrap();
-
-        // if field == String::new() {
-        //     let latest_version = package.dist_tags.get("latest").unwrap();
-        //     println!("{}\n", format!("v{}", latest_version).bright_blue());
-
-        //     if package.description != None {
-        //         println!("{}\n", package.description.unwrap());
-        //     }
-        //     if package.keywords != None {
-        //         print!("{}: ", "keywords".bright_blue().bold());
-        //         for keyword in package.keywords.unwrap().iter() {
-        //             print!("{} ", keyword.green())
-        //         }
-        //         print!("\n\n")
-        //     }
-
-        //     let latestpackage: &Version = &package.versions[latest_version];
-        //     println!("distribution:");
-        //     println!(
-        //         "  tarball: {}",
-        //         latestpackage.dist.tarball.bright_blue().underline()
-        //     );
-        //     println!("  shasum: {}", latestpackage.dist.shasum.bright_green());
-        //     if latestpackage.dist.integrity != "" {
-        //         println!(
-        //             "  integrity: {}",
-        //             latestpackage.dist.integrity.bright_blue()
-        //         );
-        //     }
-        //     if latestpackage.dist.unpacked_size != 0 {
-        //         println!(
-        //             "  unpackedSize: {}{}",
-        //             (latestpackage.dist.unpacked_size / 1024)
-        //                 .to_string()
-        //                 .bright_blue()
-        //                 .bold(),
-        //             "kb".bright_blue().bold()
-        //         );
-        //     }
-
-        //     let dependencies = latestpackage
-        //         .dependencies
-        //         .keys()
-        //         .cloned()
-        //         .collect::<Vec<String>>();
-
-        //     if dependencies.len() != 0 {
-        //         println!("\ndependencies:");
-        //         for dep in dependencies.iter() {
-        //             println!("{}{}", "  - ".bright_magenta(), dep);
-        //         }
-        //     }
-
-        //     println!("{}", "\nmaintainers:");
-        //     for maintainer in latestpackage.maintainers.iter() {
-        //         println!(
-        //             "  {} {}<{}>",
-        //             "-".bright_magenta(),
-        //             maintainer.email,
-        //             maintainer.name.yellow().bold()
-        //         )
-        //     }
-        //     print!("\n");
-        // } else {
-        //     match field.as_str() {
-        //         "readme" => {
-        //             let text: String;
-
-        //             if package.readme.is_some() && package.readme.as_ref().unwrap().trim() != "" {
-        //                 text = package.readme.unwrap();
-        //             } else {
-        //                 let latest_version = package.dist_tags.get("latest").unwrap();
-        //                 let current_version = package.versions.get(latest_version).unwrap();
-
-        //                 if current_version.readme.is_some() {
-        //                     text = current_version.readme.as_ref().unwrap().to_string();
-        //                 } else {
-        //                     text = String::new();
-        //                 }
-        //             }
-        //             if text != String::new() {
-        //                 PrettyPrinter::new()
-        //                     .input_from_bytes(text.as_bytes())
-        //                     .theme("Dracula")
-        //                     .language("markdown")
-        //                     .print()
-        //                     .unwrap();
-
-        //                 print!("\n");
-        //             } else {
-        //                 error!("could not find a readme for {}", name);
-        //             }
-        //         }
-        //         "version" => {
-        //             let mut table = Table::new();
-
-        //             let versions: HashMap<String, String> = package.dist_tags;
-
-        //             let mut labels: Vec<Cell> = vec![Cell::new("")];
-
-        //             let mut values: Vec<Cell> =
-        //                 vec![Cell::new(&name.bright_blue().to_string().as_str())];
-
-        //             for (k, v) in versions.iter() {
-        //                 labels.push(Cell::new(k.as_str()));
-        //                 values.push(Cell::new(v.bright_magenta().to_string().as_str()));
-        //             }
-
-        //             table.add_row(Row::new(labels));
-
-        //             table.add_row(Row::new(values));
-
-        //             table.printstd();
-        //             print!("\n");
-        //         }
-        //         "versions" => {
-        //             let versions = package.versions.keys().cloned().collect::<Vec<String>>();
-
-        //             println!("{}{}", "versions".bright_cyan(), ":".bright_magenta());
-        //             for v in versions {
-        //                 println!("  {} {}", "-".bright_magenta(), v.bright_green());
-        //             }
-        //             print!("\n");
-        //         }
-        //         "description" => {
-        //             let description = package.description;
-
-        //             if description.is_some() {
-        //                 println!("{}", description.unwrap());
-        //                 print!("\n");
-        //             } else {
-        //                 error!("could not find a description for {}", name);
-        //             }
-        //         }
-        //         "name" => {
-        //             println!("{}", name);
-        //             print!("\n");
-        //         }
-        //         "maintainers" => {
-        //             for maintainer in package.maintainers.iter() {
-        //                 println!(
-        //                     "{} <{}>",
-        //                     maintainer.name.bright_green().bold(),
-        //                     maintainer.email.bright_magenta(),
-        //                 )
-        //             }
-        //             print!("\n");
-        //         }
-        //         "time" => {
-        //             let times = package.time;
-
-        //             for (version, time) in times {
-        //                 println!(
-        //                     "{} {} {}",
-        //                     version.bright_cyan(),
-        //                     ":".bright_magenta(),
-        //                     time.bright_black()
-        //                 );
-        //             }
-
-        //             print!("\n");
-        //         }
-        //         "repository" => {
-        //             let repo = package.repository;
-
-        //             if repo.is_some() {
-        //                 let data = repo.unwrap();
-
-        //                 println!("{}: {}", "provider".bright_yellow(), data.type_field);
-        //                 println!("{}: {}", "url".bright_purple(), data.url.underline());
-        //                 print!("\n");
-        //             } else {
-        //                 error!("could not find a repository for {}", name);
-        //             }
-        //         }
-        //         "homepage" => {
-        //             let page = package.homepage;
-
-        //             if page.is_some() {
-        //                 let data = page.unwrap();
-
-        //                 println!("{}", data);
-        //                 print!("\n");
-        //             } else {
-        //                 error!("could not find a homepage for {}", name);
-        //             }
-        //         }
-        //         "keywords" => {
-        //             let keywords = package.keywords;
-        //             println!("{}{}", "keywords".bright_cyan(), ":".bright_cyan());
-        //             if keywords.is_some() {
-        //                 let keywords = keywords.unwrap();
-
-        //                 for kw in keywords {
-        //                     println!("{} {}", "-".bright_magenta(), kw);
-        //                 }
-        //                 print!("\n");
-        //             } else {
-        //                 error!("could not find keywords for {}", name);
-        //             }
-        //         }
-        //         "users" => {}
-        //         &_ => {}
-        //     }
-        // }
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        // the version starts at the last `@`, unless that's the one of the scope
+        let (name, requested) = match self.package.rfind('@') {
+            Some(index) if index > 0 => (&self.package[..index], Some(&self.package[index + 1..])),
+            _ => (self.package.as_str(), None),
+        };
+
+        let client = RegistryClient::new(&config)?;
+
+        let packument = client.packument(name).await?;
+        let package: NpmPackage = serde_json::from_value(packument.clone()).into_diagnostic()?;
+
+        let version = resolve_version(&package, requested).ok_or_else(|| {
+            ResolutionError::NoMatchingVersion {
+                name: name.to_string(),
+                requested: requested.unwrap_or("latest").to_string(),
+            }
+        })?;
+
+        let document = &packument["versions"][&version];
+
+        // like npm, `versions` lists the published versions rather than their documents
+        let versions = Value::from(published_versions(&package));
+
+        let selected = match self.field.as_deref() {
+            Some("versions") => &versions,
+            Some(path) => field(document, &packument, path).unwrap_or(&Value::Null),
+            None => document,
+        };
+
+        if config.json() {
+            emit(&Event::Result(selected.clone()));
+        } else if self.field.is_some() {
+            match selected {
+                Value::Null => {}
+                Value::String(value) => println!("{}", value),
+                value => println!("{}", serde_json::to_string_pretty(value).into_diagnostic()?),
+            }
+        } else {
+            print_summary(&package, &package.versions[&version]);
+        }
 
         Ok(())
     }
 }
+
+/// The versions of `package`, oldest first.
+fn published_versions(package: &NpmPackage) -> Vec<String> {
+    let mut versions: Vec<node_semver::Version> = package
+        .versions
+        .keys()
+        .filter_map(|version| version.parse().ok())
+        .collect();
+
+    versions.sort();
+
+    versions.iter().map(ToString::to_string).collect()
+}
+
+fn print_summary(package: &NpmPackage, version: &Version) {
+    println!(
+        "{}@{} | {} | deps: {} | versions: {}",
+        version.name.bright_blue().bold(),
+        version.version.bright_blue().bold(),
+        package.license.as_deref().unwrap_or("Proprietary").green(),
+        version.dependencies.len().to_string().cyan(),
+        package.versions.len().to_string().yellow()
+    );
+
+    if let Some(description) = &package.description {
+        println!("{}", description);
+    }
+
+    if let Some(homepage) = &package.homepage {
+        println!("{}", homepage.cyan());
+    }
+
+    if let Some(keywords) = package.keywords.as_ref().filter(|k| !k.is_empty()) {
+        println!("\nkeywords: {}", keywords.join(", ").yellow());
+    }
+
+    println!("\n{}", "dist".bold());
+    println!(".tarball: {}", version.dist.tarball.cyan());
+    println!(".shasum: {}", version.dist.shasum.yellow());
+
+    if !version.dist.integrity.is_empty() {
+        println!(".integrity: {}", version.dist.integrity.yellow());
+    }
+
+    if version.dist.unpacked_size > 0 {
+        println!(
+            ".unpackedSize: {}",
+            HumanBytes(version.dist.unpacked_size as u64)
+                .to_string()
+                .yellow()
+        );
+    }
+
+    if !version.dependencies.is_empty() {
+        let mut dependencies: Vec<_> = version.dependencies.iter().collect();
+        dependencies.sort();
+
+        println!("\n{}", "dependencies:".bold());
+
+        for (name, range) in dependencies {
+            println!("{}: {}", name.cyan(), range);
+        }
+    }
+
+    if !package.maintainers.is_empty() {
+        println!("\n{}", "maintainers:".bold());
+
+        for maintainer in &package.maintainers {
+            if maintainer.email.is_empty() {
+                println!("- {}", maintainer.name);
+            } else {
+                println!("- {} <{}>", maintainer.name, maintainer.email);
+            }
+        }
+    }
+
+    println!("\n{}", "dist-tags:".bold());
+
+    for (tag, version) in package.dist_tags.iter() {
+        println!("{}: {}", tag.bright_blue().bold(), version);
+    }
+
+    if let Some(published) = package.time.get(&version.version) {
+        println!("\npublished {}", published.truecolor(156, 156, 156));
+    }
+}
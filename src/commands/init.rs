@@ -14,14 +14,20 @@
     limitations under the License.
 */
 
-use crate::{
-    cli::{VoltCommand, VoltConfig},
-    core::{
-        classes::init_data::{InitData, License},
-        prompt::prompts::{Confirm, Input, Select},
-        utils,
-        utils::errors::VoltError,
-        utils::extensions::PathExtensions,
+use crate::cli::{
+    prompt::prompts::{Confirm, Input, Select},
+    VoltCommand, VoltConfig,
+};
+use volt_core::{
+    classes::{
+        init_data::{InitData, License},
+        init_template::{substitute_placeholders, substitute_value_placeholders, InitTemplate},
+    },
+    git,
+    utils::{
+        errors::{FilesystemError, VoltError},
+        extensions::PathExtensions,
+        package_editor::PackageJsonEditor,
     },
 };
 
@@ -29,8 +35,17 @@ use async_trait::async_trait;
 use clap::Parser;
 use colored::Colorize;
 use miette::{IntoDiagnostic, Result};
+use node_semver::Version;
+use package_spec::PackageSpec;
 use regex::Regex;
-use std::{fs::File, io::Write, time::Instant};
+use serde_json::{Map, Value};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 const PACKAGE_JSON: &str = "package.json";
 
@@ -40,6 +55,10 @@ pub struct Init {
     /// Use default options
     #[clap(short, long)]
     yes: bool,
+
+    /// Scaffold the project from a built-in template (node-cli, library, typescript) or a git repository
+    #[clap(short, long)]
+    template: Option<String>,
 }
 
 #[async_trait]
@@ -62,6 +81,37 @@ impl VoltCommand for Init {
     async fn exec(self, config: VoltConfig) -> Result<()> {
         let _start = Instant::now();
 
+        let package_json_path = config.cwd()?.join(PACKAGE_JSON);
+
+        // values from an existing package.json are used as defaults and kept as-is
+        let mut existing = read_existing(&package_json_path)?;
+
+        let mut template_files = match &self.template {
+            Some(template) => load_template(template)?,
+            None => vec![],
+        };
+
+        // fields from the template's package.json fill in whatever the project doesn't set
+        let mut template_fields = Map::new();
+
+        if let Some(index) = template_files
+            .iter()
+            .position(|(path, _)| path == Path::new(PACKAGE_JSON))
+        {
+            let (_, contents) = template_files.remove(index);
+
+            if let Value::Object(fields) = serde_json::from_slice(&contents).into_diagnostic()? {
+                for (key, value) in fields {
+                    if !["name", "version", "author"].contains(&key.as_str())
+                        && !existing.contains_key(&key)
+                    {
+                        template_fields.insert(key.clone(), value.clone());
+                        existing.insert(key, value);
+                    }
+                }
+            }
+        }
+
         // get name of cwd
         let cwd_name = config
             .cwd()?
@@ -70,21 +120,56 @@ impl VoltCommand for Init {
 
         let data = if self.yes {
             // Set name to current directory name
-            automatic_initialization(cwd_name, &config)?
+            automatic_initialization(sanitize_name(&cwd_name), &existing, &config)?
         } else {
-            manual_initialization(cwd_name, &config)?
-        };
+            manual_initialization(sanitize_name(&cwd_name), &existing, &config)?
+        }
+        .with_existing(existing);
 
-        let mut file = File::create(PACKAGE_JSON).map_err(|e| VoltError::WriteFileError {
-            source: e,
-            name: String::from(PACKAGE_JSON),
-        })?;
+        let name = data.name.clone();
+        let author = data.author.clone().unwrap_or_default();
+
+        write_template_files(&config.cwd()?, template_files, &name, &author)?;
+
+        // only the fields from the template hold placeholders, the project's stay as written
+        let mut fields: Map<String, Value> =
+            serde_json::from_str(&data.into_string()).into_diagnostic()?;
+
+        for (key, value) in template_fields {
+            if let Some(field) = fields.get_mut(&key).filter(|field| **field == value) {
+                *field = substitute_value_placeholders(value, &name, &author);
+            }
+        }
+
+        let contents = format!(
+            "{}\n",
+            serde_json::to_string_pretty(&fields).into_diagnostic()?
+        );
+
+        if package_json_path.exists() {
+            // only the fields that changed are rewritten, the rest of the file stays as is
+            let mut editor = PackageJsonEditor::open(&package_json_path)?;
 
-        file.write(data.into_string().as_bytes())
-            .map_err(|e| VoltError::WriteFileError {
-                source: e,
-                name: String::from(PACKAGE_JSON),
-            })?;
+            for (key, value) in &fields {
+                if editor.get(&[key]).as_ref() != Some(value) {
+                    editor.set(&[key], value)?;
+                }
+            }
+
+            editor.save()?;
+        } else {
+            let mut file =
+                File::create(&package_json_path).map_err(|e| FilesystemError::Write {
+                    source: e,
+                    path: String::from(PACKAGE_JSON),
+                })?;
+
+            file.write_all(contents.as_bytes())
+                .map_err(|e| FilesystemError::Write {
+                    source: e,
+                    path: String::from(PACKAGE_JSON),
+                })?;
+        }
 
         println!("{}", "Successfully Initialized package.json".bright_green());
 
@@ -92,52 +177,213 @@ impl VoltCommand for Init {
     }
 }
 
-fn automatic_initialization(name: String, config: &VoltConfig) -> Result<InitData> {
-    let version = "0.1.0".to_string();
+/// Read the fields of an existing package.json (empty if there isn't one)
+fn read_existing(path: &Path) -> Result<Map<String, Value>> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
 
-    let description = None;
+    let data = std::fs::read_to_string(path).map_err(|e| FilesystemError::Read {
+        source: e,
+        path: String::from(PACKAGE_JSON),
+    })?;
 
-    let main = "index.js".to_string();
+    match serde_json::from_str(&data).into_diagnostic()? {
+        Value::Object(map) => Ok(map),
+        _ => miette::bail!("{} is not a JSON object", PACKAGE_JSON),
+    }
+}
 
-    let author = {
-        let git_user_name = utils::get_git_config(config, "user.name")?;
-        let git_email = utils::get_git_config(config, "user.email")?;
+/// Load the files of a built-in template, or clone them from a git repository.
+fn load_template(template: &str) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    if let Some(template) = InitTemplate::from_name(template) {
+        return Ok(template
+            .files()
+            .iter()
+            .map(|(path, contents)| (PathBuf::from(path), contents.as_bytes().to_vec()))
+            .collect());
+    }
 
-        if let (Some(git_user_name), Some(git_email)) = (git_user_name, git_email) {
-            Some(format!("{} <{}>", git_user_name, git_email))
-        } else {
-            None
+    let info = match template.parse() {
+        Ok(PackageSpec::Git(info)) => info,
+        _ => {
+            return Err(VoltError::UnknownTemplate {
+                name: template.to_string(),
+                templates: InitTemplate::OPTIONS.join(", "),
+            }
+            .into())
         }
     };
 
-    let license = License::default();
+    let dir = tempfile::tempdir().into_diagnostic()?;
+
+    println!("Cloning {}...", git::clone_url(&info).bright_cyan());
+
+    git::clone(&git::clone_url(&info), git::committish(&info), dir.path())?;
+
+    let mut files = vec![];
+
+    for entry in jwalk::WalkDir::new(dir.path()).skip_hidden(false) {
+        let path = entry.into_diagnostic()?.path();
+        let relative = path
+            .strip_prefix(dir.path())
+            .into_diagnostic()?
+            .to_path_buf();
+
+        if !path.is_file() || relative.starts_with(".git") {
+            continue;
+        }
+
+        let contents = std::fs::read(&path).map_err(|e| FilesystemError::Read {
+            source: e,
+            path: relative.display().to_string(),
+        })?;
+
+        files.push((relative, contents));
+    }
+
+    Ok(files)
+}
+
+/// Write the template files into the project, leaving files that already exist untouched.
+fn write_template_files(
+    cwd: &Path,
+    files: Vec<(PathBuf, Vec<u8>)>,
+    name: &str,
+    author: &str,
+) -> Result<()> {
+    for (relative, contents) in files {
+        let path = cwd.join(&relative);
+
+        if path.exists() {
+            tracing::warn!("{} already exists, skipping", relative.display());
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        // only text files have their placeholders replaced
+        let contents = match String::from_utf8(contents) {
+            Ok(text) => substitute_placeholders(&text, name, author).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+
+        std::fs::write(&path, contents).map_err(|e| FilesystemError::Write {
+            source: e,
+            path: relative.display().to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+fn existing_string(existing: &Map<String, Value>, key: &str) -> Option<String> {
+    existing
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+/// Turn a directory name into a valid package name (`My Project` -> `my-project`)
+fn sanitize_name(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_whitespace() { '-' } else { c })
+        .filter(|c| c.is_ascii_alphanumeric() || "-._~".contains(*c))
+        .collect();
+
+    let name = name.trim_start_matches(['.', '_']);
+
+    if name.is_empty() {
+        String::from("package")
+    } else {
+        name.to_string()
+    }
+}
 
+fn is_valid_name(name: &str) -> bool {
+    Regex::new("^(?:@[a-z0-9-*~][a-z0-9-*._~]*/)?[a-z0-9-~][a-z0-9-._~]*$")
+        .expect("Valid regex")
+        .is_match(name)
+}
+
+fn default_scripts(existing: &Map<String, Value>) -> BTreeMap<String, String> {
+    existing
+        .get("scripts")
+        .cloned()
+        .and_then(|scripts| serde_json::from_value(scripts).ok())
+        .unwrap_or_else(|| {
+            BTreeMap::from([(
+                String::from("test"),
+                String::from("echo \"Error: no test specified\" && exit 1"),
+            )])
+        })
+}
+
+fn default_author(existing: &Map<String, Value>, config: &VoltConfig) -> Result<Option<String>> {
+    if let Some(author) = existing_string(existing, "author") {
+        return Ok(Some(author));
+    }
+
+    let git_user_name = utils::get_git_config(config, "user.name")?;
+    let git_email = utils::get_git_config(config, "user.email")?;
+
+    Ok(git_user_name
+        .zip(git_email)
+        .map(|(git_user_name, git_email)| format!("{} <{}>", git_user_name, git_email)))
+}
+
+fn default_license(existing: &Map<String, Value>) -> License {
+    existing_string(existing, "license")
+        .and_then(|license| License::try_from(license.as_str()).ok())
+        .unwrap_or_default()
+}
+
+fn automatic_initialization(
+    name: String,
+    existing: &Map<String, Value>,
+    config: &VoltConfig,
+) -> Result<InitData> {
     Ok(InitData {
-        name,
-        version,
-        description,
-        main,
-        author,
-        license,
-        private: None,
+        name: existing_string(existing, "name").unwrap_or(name),
+        version: existing_string(existing, "version").unwrap_or_else(|| "1.0.0".to_string()),
+        description: existing_string(existing, "description"),
+        main: existing_string(existing, "main").unwrap_or_else(|| "index.js".to_string()),
+        scripts: Some(default_scripts(existing)),
+        repository: utils::get_git_config(config, "repository.url")?,
+        author: default_author(existing, config)?,
+        license: default_license(existing),
+        private: existing.get("private").and_then(Value::as_bool),
+        extra: Map::new(),
     })
 }
 
-fn manual_initialization(default_name: String, config: &VoltConfig) -> Result<InitData> {
+fn manual_initialization(
+    default_name: String,
+    existing: &Map<String, Value>,
+    config: &VoltConfig,
+) -> Result<InitData> {
     // Get "name"
     let input = Input {
         message: "name".into(),
-        default: Some(default_name.into()),
+        default: Some(
+            existing_string(existing, "name")
+                .unwrap_or(default_name)
+                .into(),
+        ),
         allow_empty: false,
     };
 
-    let re_name = Regex::new("^(?:@[a-z0-9-*~][a-z0-9-*._~]*/)?[a-z0-9-~][a-z0-9-._~]*$")
-        .expect("Valid regex");
     let mut name;
     loop {
         name = input.run().into_diagnostic()?;
 
-        if re_name.is_match(&name) {
+        if is_valid_name(&name) {
             break;
         }
 
@@ -147,16 +393,29 @@ fn manual_initialization(default_name: String, config: &VoltConfig) -> Result<In
     // Get "version"
     let input = Input {
         message: "version".into(),
-        default: Some("1.0.0".into()),
+        default: Some(
+            existing_string(existing, "version")
+                .unwrap_or_else(|| "1.0.0".to_string())
+                .into(),
+        ),
         allow_empty: false,
     };
 
-    let version = input.run().into_diagnostic()?;
+    let mut version;
+    loop {
+        version = input.run().into_diagnostic()?;
+
+        if Version::parse(&version).is_ok() {
+            break;
+        }
+
+        println!("{}", "Version must be a valid semver version".red());
+    }
 
     // Get "description"
     let input = Input {
         message: "description".into(),
-        default: None,
+        default: existing_string(existing, "description").map(Into::into),
         allow_empty: true,
     };
 
@@ -165,42 +424,56 @@ fn manual_initialization(default_name: String, config: &VoltConfig) -> Result<In
     // Get "main"
     let input = Input {
         message: "main".into(),
-        default: Some("index.js".into()),
+        default: Some(
+            existing_string(existing, "main")
+                .unwrap_or_else(|| "index.js".to_string())
+                .into(),
+        ),
         allow_empty: false,
     };
 
     let main = input.run().into_diagnostic()?;
 
-    // Get "author"
-    let git_user_name = utils::get_git_config(config, "user.name")?;
+    // Get "repository"
+    let input = Input {
+        message: "git repository".into(),
+        default: utils::get_git_config(config, "repository.url")?.map(Into::into),
+        allow_empty: true,
+    };
 
-    let git_email = utils::get_git_config(config, "user.email")?;
+    let repository = input.run().into_diagnostic()?;
 
+    // Get "author"
     let input = Input {
         message: "author".into(),
-        default: git_user_name
-            .zip(git_email)
-            .map(|(git_user_name, git_email)| format!("{} <{}>", git_user_name, git_email).into()),
+        default: default_author(existing, config)?.map(Into::into),
         allow_empty: true,
     };
 
     let author = input.run().into_diagnostic()?;
 
     // Get "license"
+    let default_license = default_license(existing);
+
     let select = Select {
         message: "License".into(),
         paged: true,
-        selected: Some(1),
+        // `selected` is 1-based
+        selected: License::OPTIONS
+            .iter()
+            .position(|&l| l == default_license.as_str())
+            .map(|i| i + 1),
         items: License::OPTIONS.iter().map(|&l| l.into()).collect(),
     };
 
-    select.run().into_diagnostic()?;
-
-    let license = License::from_index(select.selected.unwrap()).unwrap();
+    let license = License::from_index(select.run().into_diagnostic()?).unwrap_or_default();
 
     let input = Confirm {
         message: "private".into(),
-        default: false,
+        default: existing
+            .get("private")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
     };
 
     let private = input.run().into_diagnostic()?;
@@ -208,10 +481,27 @@ fn manual_initialization(default_name: String, config: &VoltConfig) -> Result<In
     Ok(InitData {
         name,
         version,
-        description: Some(description),
+        description: Some(description).filter(|d| !d.is_empty()),
         main,
-        author: Some(author),
+        scripts: Some(default_scripts(existing)),
+        repository: Some(repository).filter(|r| !r.is_empty()),
+        author: Some(author).filter(|a| !a.is_empty()),
         license,
         private: Some(private),
+        extra: Map::new(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize_name;
+
+    #[test]
+    fn turns_directory_names_into_package_names() {
+        assert_eq!(sanitize_name("My Project"), "my-project");
+        assert_eq!(sanitize_name("  volt.js "), "volt.js");
+        assert_eq!(sanitize_name("_private"), "private");
+        assert_eq!(sanitize_name("café (2)"), "caf-2");
+        assert_eq!(sanitize_name("..."), "package");
+    }
+}
@@ -16,13 +16,85 @@
 
 //! Installs dependencies for a project.
 
-use crate::cli::{VoltCommand, VoltConfig};
+use crate::cli::{reporter::TerminalReporter, VoltCommand, VoltConfig};
+use volt_core::{
+    global::install_global,
+    install::{
+        check_lock_file, dependency_specs, install, link_workspace_members, project_dependencies,
+        resolve, resolve_peers, write_lock_file, InstallScope, Resolution,
+    },
+    install_state::InstallState,
+    local::local_packages_changed,
+    model::lock_file::LockFile,
+    reporter::{emit, Event, PlainReporter, Reporter},
+    utils::errors::ResolutionError,
+    workspace::{find_projects, Filter},
+};
 
 use async_trait::async_trait;
-use miette::Result;
+use clap::Parser;
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+use serde_json::json;
 
-/// Struct implementation for the `Install` command.
-pub struct Install;
+use std::{path::PathBuf, sync::Arc};
+
+/// Projects installed at once by `--recursive`.
+const RECURSIVE_CONCURRENCY: usize = 4;
+
+/// Install the dependencies of a project
+#[derive(Debug, Parser)]
+pub struct Install {
+    /// Packages to install globally (requires `--global`)
+    #[clap(requires = "global")]
+    packages: Vec<PackageSpec>,
+
+    /// Install packages into the global prefix (`~/.volt/global`)
+    #[clap(long, short)]
+    global: bool,
+
+    /// Only install the dependencies of the matching workspace members
+    /// (`name`, `@scope/*`, `./path`, `[git-ref]`, `...name`, `name...`).
+    #[clap(long, short = 'F')]
+    filter: Vec<Filter>,
+
+    /// Fail instead of updating volt.lock when it doesn't match package.json
+    #[clap(long, conflicts_with = "lockfile-only")]
+    frozen_lockfile: bool,
+
+    /// Only update volt.lock, without installing anything into node_modules
+    #[clap(long)]
+    lockfile_only: bool,
+
+    /// Install the peer dependencies that nothing else provides
+    #[clap(long)]
+    install_peers: bool,
+
+    /// Skip the devDependencies and what only they depend on
+    #[clap(long, alias = "prod", conflicts_with = "dev-only")]
+    production: bool,
+
+    /// Only install the devDependencies and what they depend on
+    #[clap(long)]
+    dev_only: bool,
+
+    /// Install every project below the current directory (each directory with a
+    /// package.json outside of node_modules, except workspace members)
+    #[clap(long, short, conflicts_with_all = &["global", "filter"])]
+    recursive: bool,
+
+    /// Check every file of the packages already in node_modules against the store, and
+    /// extract the modified ones again
+    #[clap(long)]
+    check_files: bool,
+
+    /// Write the time spent on every package to a file of folded stacks, for
+    /// `flamegraph.pl` or `inferno-flamegraph`
+    #[clap(long, value_name = "FILE", conflicts_with_all = &["global", "recursive"])]
+    profile: Option<PathBuf>,
+}
 
 #[async_trait]
 impl VoltCommand for Install {
@@ -30,19 +102,202 @@ impl VoltCommand for Install {
     ///
     /// Install dependencies for a project.
     /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
-    /// * `packages` - List of packages to add (`Vec<String>`)
-    /// * `flags` - List of flags passed in through the CLI (`Vec<String>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Install dependencies for a project with logging level verbose
+    /// // Install the dependencies of every workspace member affected since origin/main
     /// // .exec() is an async call so you need to await it
-    /// Install.exec(app, vec![], vec!["--verbose"]).await;
+    /// Install { filter: vec!["...[origin/main]".parse()?] }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        // let package_file
+    async fn exec(self, mut config: VoltConfig) -> Result<()> {
+        config.check_files = self.check_files;
+
+        if self.global {
+            if self.packages.is_empty() {
+                miette::bail!(
+                    "specify the packages to install globally, e.g. `volt install -g typescript`"
+                );
+            }
+
+            return install_global(&config, &self.packages).await;
+        }
+
+        if self.recursive {
+            return self.install_recursive(config).await;
+        }
+
+        if self.profile.is_some() {
+            config.stats().start_profiling();
+        }
+
+        self.install_project(&config).await?;
+
+        if let Some(profile) = &self.profile {
+            config.stats().write_profile(profile)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Install {
+    /// Install the project of `config`.
+    async fn install_project(&self, config: &VoltConfig) -> Result<()> {
+        let (dependencies, workspace) =
+            project_dependencies(&config.cwd()?, &self.filter, InstallScope::All)?;
+
+        // the lock file keeps every dependency, only the installed graph is pruned
+        let scope = InstallScope::from_flags(self.production, self.dev_only);
+
+        let installed = match scope {
+            InstallScope::All => dependencies.clone(),
+            scope => project_dependencies(&config.cwd()?, &self.filter, scope)?.0,
+        };
+
+        let state = |config: &VoltConfig| {
+            InstallState::new(
+                config,
+                &installed,
+                workspace.as_ref(),
+                scope,
+                self.install_peers,
+            )
+        };
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        // reuse the locked versions when they still satisfy package.json
+        let locked = Resolution::from_lock_file(&lock_file, &dependencies);
+
+        // local `file:` packages may have changed since they were locked, which neither
+        // volt.lock nor package.json tell
+        let local_changed = match &locked {
+            Some(resolution) => local_packages_changed(config, resolution)?,
+            None => false,
+        };
+
+        // `--check-files` looks into node_modules even when nothing changed
+        let can_skip = !self.lockfile_only && !self.check_files && !local_changed;
+
+        if can_skip && state(config)?.is_installed(config)? {
+            if !config.json() {
+                println!("{} node_modules is up to date", "install:".green().bold());
+            }
+
+            return Ok(());
+        }
+
+        let mut resolution = match locked {
+            Some(resolution) if !local_changed => resolution,
+            _ if self.frozen_lockfile => {
+                return Err(ResolutionError::LockFileOutdated {
+                    path: config.lockfile()?.display().to_string(),
+                }
+                .into());
+            }
+            _ => resolve(config, &dependency_specs(&dependencies)).await?,
+        };
+
+        if self.install_peers {
+            resolve_peers(config, &mut resolution, &lock_file, self.frozen_lockfile).await?;
+        }
+
+        if self.frozen_lockfile {
+            check_lock_file(config, &resolution)?;
+        } else {
+            write_lock_file(config, &resolution)?;
+        }
+
+        if self.lockfile_only {
+            return Ok(());
+        }
+
+        if scope != InstallScope::All {
+            resolution = resolution.scoped(&installed);
+        }
+
+        install(config, resolution).await?;
+
+        if let Some(workspace) = &workspace {
+            link_workspace_members(config, workspace)?;
+        }
+
+        // volt.lock may have just been written
+        state(config)?.record(config)?;
+
+        Ok(())
+    }
+
+    /// Install the projects below the directory of `config` a few at a time, sharing the
+    /// store, the metadata cache and the http client.
+    async fn install_recursive(&self, mut config: VoltConfig) -> Result<()> {
+        let root = config.cwd()?;
+        let projects = find_projects(&root)?;
+
+        if projects.is_empty() {
+            miette::bail!("no package.json found below {}", root.display());
+        }
+
+        // progress bars of projects installing at once would draw over each other
+        config.set_reporter(|| -> Arc<dyn Reporter> {
+            Arc::new(TerminalReporter::new(PlainReporter::default()))
+        });
+
+        let total = projects.len();
+
+        let results: Vec<(PathBuf, Result<()>)> = stream::iter(projects)
+            .map(|project| {
+                let config = config.with_cwd(project.clone());
+
+                async move {
+                    let result = self.install_project(&config).await;
+
+                    (project, result)
+                }
+            })
+            .buffer_unordered(RECURSIVE_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut failed = vec![];
+
+        for (project, result) in &results {
+            let path = match project.strip_prefix(&root) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+                _ => ".".to_string(),
+            };
+
+            match result {
+                Ok(()) if !config.json() => println!("{} {}", "Installed".green().bold(), path),
+                Ok(()) => {}
+                Err(error) => {
+                    if !config.json() {
+                        eprintln!("{} {}: {:?}", "Failed".red().bold(), path, error);
+                    }
+
+                    failed.push(path);
+                }
+            }
+        }
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "installed": total - failed.len(),
+                "failed": failed,
+            })));
+        }
+
+        if !failed.is_empty() {
+            miette::bail!(
+                "{} of {} projects failed to install: {}",
+                failed.len(),
+                total,
+                failed.join(", ")
+            );
+        }
+
         Ok(())
     }
 }
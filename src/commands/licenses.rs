@@ -0,0 +1,149 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! List the licenses of the installed packages and check them against a policy.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{locked_resolution, project_dependencies, InstallScope},
+    licenses::{license_of, Policy, UNKNOWN},
+    linker::linker,
+    reporter::{emit, Event},
+    utils::{errors::VoltError, package::PackageJson},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::json;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+/// Policy file read from the project when `--policy` isn't given.
+const DEFAULT_POLICY: &str = "license-policy.toml";
+
+/// List the licenses of the installed packages, failing on those the policy denies
+#[derive(Debug, Parser)]
+pub struct Licenses {
+    /// Policy file with `deny` and `allow` lists (defaults to `license-policy.toml`)
+    #[clap(long)]
+    policy: Option<PathBuf>,
+}
+
+#[async_trait]
+impl VoltCommand for Licenses {
+    /// Execute the `volt licenses` command
+    ///
+    /// Read the license of every package of volt.lock from node_modules, print the
+    /// packages grouped by license and fail if any breaks the policy.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Check the licenses in CI
+    /// // .exec() is an async call so you need to await it
+    /// Licenses { policy: Some("ci/licenses.toml".into()) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        let policy = match &self.policy {
+            Some(path) if !path.is_file() => miette::bail!("{} doesn't exist", path.display()),
+            Some(path) => Policy::load(path)?,
+            None => Policy::load(&cwd.join(DEFAULT_POLICY))?,
+        };
+
+        let (dependencies, _) = project_dependencies(&cwd, &[], InstallScope::All)?;
+        let resolution = locked_resolution(&config, &dependencies)?;
+        let linker = linker(&config, &resolution)?;
+
+        // license -> `name@version`
+        let mut licenses: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut missing = 0;
+
+        for key in resolution.tree.keys() {
+            let package_json = linker
+                .directories(key)
+                .first()
+                .and_then(|directory| PackageJson::get_from_dir(directory).ok());
+
+            let license = match package_json {
+                Some((package_json, _)) => license_of(&package_json),
+                None => {
+                    missing += 1;
+
+                    UNKNOWN.to_string()
+                }
+            };
+
+            licenses.entry(license).or_default().insert(key.clone());
+        }
+
+        let denied: Vec<&String> = licenses
+            .iter()
+            .filter(|(license, _)| !policy.permits(license))
+            .flat_map(|(_, packages)| packages)
+            .collect();
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "licenses": licenses,
+                "denied": denied,
+            })));
+        } else {
+            for (license, packages) in &licenses {
+                let heading = if policy.permits(license) {
+                    license.bold()
+                } else {
+                    license.red().bold()
+                };
+
+                println!(
+                    "{} {}",
+                    heading,
+                    format!("({})", packages.len()).truecolor(156, 156, 156)
+                );
+
+                for package in packages {
+                    println!("  {}", package.truecolor(156, 156, 156));
+                }
+            }
+
+            if missing > 0 {
+                println!(
+                    "\n{} {} packages aren't installed, run `volt install` to read their licenses",
+                    "note:".cyan().bold(),
+                    missing
+                );
+            }
+        }
+
+        if !denied.is_empty() {
+            return Err(VoltError::LicensePolicy {
+                count: denied.len(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
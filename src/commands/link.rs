@@ -0,0 +1,101 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Link packages under development into projects.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    links::{link_into, register},
+    lock::lock_project,
+    reporter::{emit, Event},
+    utils::errors::VoltError,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::json;
+
+/// Register the current package for linking, or link registered packages into the project
+#[derive(Debug, Parser)]
+pub struct Link {
+    /// Registered packages to link into node_modules
+    packages: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Link {
+    /// Execute the `volt link` command
+    ///
+    /// Without packages, register the package in the current directory and link it into
+    /// the global prefix; with packages, symlink the registered ones into node_modules.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Use the local checkout of `my-lib` in this project
+    /// // .exec() is an async call so you need to await it
+    /// Link { packages: vec!["my-lib".to_string()] }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        if self.packages.is_empty() {
+            let name = register(&config, &config.cwd()?)?;
+
+            if config.json() {
+                emit(&Event::Result(json!({ "registered": name })));
+            } else {
+                println!(
+                    "{} {} {}",
+                    "Registered".green().bold(),
+                    name,
+                    format!("(link it into a project with `volt link {}`)", name)
+                        .truecolor(156, 156, 156)
+                );
+            }
+
+            return Ok(());
+        }
+
+        let _locks = lock_project(&config).await?;
+
+        let mut linked = vec![];
+
+        for name in &self.packages {
+            let directory = link_into(&config, name)?
+                .ok_or_else(|| VoltError::NotLinked { name: name.clone() })?;
+
+            if !config.json() {
+                println!(
+                    "{} {} → {}",
+                    "Linked".green().bold(),
+                    name,
+                    directory.display().to_string().truecolor(156, 156, 156)
+                );
+            }
+
+            linked.push(json!({ "name": name, "path": directory }));
+        }
+
+        if config.json() {
+            emit(&Event::Result(json!({ "linked": linked })));
+        }
+
+        Ok(())
+    }
+}
@@ -14,22 +14,41 @@
     limitations under the License.
 */
 
-//! Compress node_modules into node_modules.pack.
+//! List the dependencies installed in your project.
 
 use async_trait::async_trait;
 use clap::Parser;
 use colored::Colorize;
-use miette::Result;
-//use node_semver::Version;
+use miette::{IntoDiagnostic, Result};
+use node_semver::{Range, Version};
+use serde_json::{json, Map, Value};
 
-use crate::{
-    cli::{VoltCommand, VoltConfig},
-    core::utils::package::PackageJson,
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    links::Links,
+    model::lock_file::{LockFile, LockedPackage},
+    utils::package::PackageJson,
 };
 
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+/// List installed packages as a dependency tree
 #[derive(Debug, Parser)]
 pub struct List {
-    depth: Option<usize>,
+    /// How many levels of transitive dependencies to show
+    #[clap(long, default_value = "0")]
+    depth: usize,
+
+    /// Print the tree as JSON
+    #[clap(long)]
+    json: bool,
+
+    /// List globally installed packages
+    #[clap(long, short)]
+    global: bool,
 }
 
 // CREDIT:
@@ -37,213 +56,354 @@ pub struct List {
 // Repo: cargo-tree (tree.rs)
 // ------------------------------------------------
 pub struct Symbols {
-    _down: &'static str,
+    down: &'static str,
     tee: &'static str,
     ell: &'static str,
     right: &'static str,
 }
 
 pub static UTF8_SYMBOLS: Symbols = Symbols {
-    _down: "│",
+    down: "│",
     tee: "├",
     ell: "└",
     right: "─",
 };
 
 pub static _ASCII_SYMBOLS: Symbols = Symbols {
-    _down: "|",
+    down: "|",
     tee: "|",
     ell: "`",
     right: "-",
 };
 // ------------------------------------------------
 
+/// Why a package in the tree isn't what it should be.
+enum Problem {
+    /// Not installed at all
+    Missing,
+    /// Installed with a version outside of the requested range
+    Invalid { expected: String },
+}
+
+/// A package in the printed dependency tree.
+struct TreeNode {
+    name: String,
+    version: Option<String>,
+    problem: Option<Problem>,
+    /// The package's dependencies were already printed earlier in the tree
+    deduped: bool,
+    /// The directory of the package under development it is linked to with `volt link`
+    linked: Option<PathBuf>,
+    children: Vec<TreeNode>,
+}
+
 #[async_trait]
 impl VoltCommand for List {
     /// Execute the `volt list` command
     ///
-    /// List node_modules into node_modules.pack.
+    /// Print the dependency tree of the project from `volt.lock` and `node_modules`.
     /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // List node_modules into node_modules.pack
+    /// // List dependencies two levels deep
     /// // .exec() is an async call so you need to await it
-    /// Add.exec(app).await;
+    /// List { depth: 2, json: false, global: false }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        let symbols = &UTF8_SYMBOLS;
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let config = if self.global {
+            config.with_cwd(config.global_prefix()?)
+        } else {
+            config
+        };
+
+        let directory = config.cwd()?;
 
         // grab the project's package.json file to get primary dependencies
-        let (pkg_json, pkg_json_path) = match PackageJson::get() {
-            Ok(p) => (Some(p.0), Some(p.1)),
-            Err(_) => (None, None),
-        };
+        let (package_json, _) = PackageJson::get_from_dir(&directory)?;
 
-        if let (Some(pkg_json), Some(pkg_json_path)) = (pkg_json, pkg_json_path) {
-            // package.json exists
-            let mut node_modules = pkg_json_path;
-
-            let project_info = format!(
-                "{}@{} {}",
-                &pkg_json.name,
-                &pkg_json.version,
-                &node_modules
-                    .parent()
-                    .unwrap()
-                    .canonicalize()
-                    .unwrap()
-                    .to_string_lossy()
-            );
-            println!("{}", project_info);
-
-            let last = pkg_json.dependencies.clone();
-
-            if let Some(packages) = pkg_json.dependencies {
-                node_modules.pop();
-                node_modules.push("node_modules");
-
-                // unsure if this is necessary?
-                if !packages.is_empty() {
-                    let last = last.unwrap().into_keys().last().unwrap();
-
-                    for package in packages {
-                        if node_modules.join(&package.0).exists() {
-                            let base_pkg =
-                                PackageJson::get_from_dir(&node_modules.join(&package.0))
-                                    .unwrap()
-                                    .0;
-                            // let current: Version = base_pkg.version.parse().unwrap();
-                            let current = base_pkg.version;
-
-                            let output = format!(
-                                "{}@{}",
-                                &base_pkg.name.truecolor(000, 255, 000),
-                                &current.to_string().truecolor(000, 155, 000),
-                            );
-
-                            if last.eq(&package.0) {
-                                println!("{}{} {}\n", symbols.ell, symbols.right, output);
-                            } else {
-                                println!("{}{} {}", symbols.tee, symbols.right, output);
-                            }
-                        } else {
-                            let output = format!(
-                                "{} {}@{}",
-                                "MISSING".to_string().truecolor(255, 000, 000),
-                                &package.0.truecolor(000, 255, 000),
-                                &package.1.truecolor(000, 155, 000)
-                            );
-
-                            if last.eq(&package.0) {
-                                println!("{}{} {}\n", symbols.ell, symbols.right, output);
-                            } else {
-                                println!("{}{} {}", symbols.tee, symbols.right, output);
-                            }
-                        }
-                    }
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+        let node_modules = config.node_modules()?;
+
+        let mut seen = HashSet::new();
+
+        let mut roots: Vec<TreeNode> = package_json
+            .dependencies
+            .iter()
+            .chain(package_json.dev_dependencies.iter())
+            .flatten()
+            .map(|(name, range)| {
+                root_node(
+                    &lock_file,
+                    &node_modules,
+                    name,
+                    range,
+                    self.depth,
+                    &mut seen,
+                )
+            })
+            .collect();
+
+        let links = Links::load(&config)?;
+
+        // packages linked in without being dependencies are listed too
+        if let Some(linked) = directory
+            .canonicalize()
+            .ok()
+            .and_then(|project| links.projects.get(&project))
+        {
+            for name in linked {
+                if !roots.iter().any(|root| root.name == *name) {
+                    roots.push(root_node(
+                        &lock_file,
+                        &node_modules,
+                        name,
+                        "*",
+                        self.depth,
+                        &mut seen,
+                    ));
                 }
-            } else {
-                // package.json exists without any packages
-                let output = "(No dependencies)".to_string();
-                println!("{}{} {}\n", symbols.ell, symbols.right, output);
             }
-        } else {
-            // package.json just doesn't exist
-            let output = "Missing 'package.json' file!".to_string();
-            println!("{}", output);
         }
 
-        // let flags = &app.flags;
-
-        // let mut depth: u64 = 2;
-
-        // // if flags.contains(&"--depth".to_string()) {
-        // //     depth = app.args.iter().find_map(|s| s.parse().ok()).unwrap_or(2);
-        // // }
-
-        // let dirs = WalkDir::new("node_modules");
-
-        // let dependency_paths: Vec<_> = dirs
-        //     .into_iter()
-        //     .filter_map(Result::ok)
-        //     .filter(|entry| entry.file_type().is_dir() || entry.file_type().is_symlink())
-        //     .collect();
-
-        // if dependency_paths.len() == 1 {
-        //     println!("{}", "No Dependencies Found!".bright_cyan());
-        //     return Ok(());
-        // } else if dependency_paths.is_empty() {
-        //     println!(
-        //         "{} {} {}",
-        //         "Failed to find".bright_cyan(),
-        //         "node_modules".bright_yellow().bold(),
-        //         "folder".bright_cyan(),
-        //     );
-        //     return Ok(());
-        // }
-
-        // for dep in dependency_paths {
-        //     let dep_path = dep.path().to_str().unwrap();
-        //     let dep_path_split: Vec<&str> = dep_path.split('\\').collect();
-        //     let dep_name: &str = dep_path_split[dep_path_split.len() - 1];
-        //     if dep_name != "node_modules"
-        //         && dep_name != "scripts"
-        //         && !dep_name.starts_with("node_modules")
-        //     {
-        //         println!("{} {}", "-".bright_cyan(), dep_name.bright_blue().bold());
-        //         let dirs = WalkDir::new(format!("node_modules/{}/node_modules", dep_name))
-        //             .follow_links(true)
-        //             .max_depth((depth - 1) as usize);
-        //         let dependency_paths: Vec<_> = dirs
-        //             .into_iter()
-        //             .filter_map(Result::ok)
-        //             .filter(|entry| entry.file_type().is_dir() || entry.file_type().is_symlink())
-        //             .collect();
-
-        //         for dep in dependency_paths {
-        //             let dep_path = dep.path().to_str().unwrap();
-        //             let dep_path_split: Vec<&str> = dep_path.split('\\').collect();
-        //             let dep_name: &str = dep_path_split[dep_path_split.len() - 1];
-        //             if dep_name != "node_modules"
-        //                 && dep_name != "scripts"
-        //                 && !dep_path.contains("lib")
-        //                 && !dep_path.contains("src")
-        //                 && !dep_path.contains("dist")
-        //                 && !dep_path.contains("test")
-        //                 && !dep_name.starts_with("node_modules")
-        //             {
-        //                 for _ in 0..dep_path_split.len() {
-        //                     print!("  ");
-        //                 }
-        //                 let mut version = "".to_owned();
-        //                 for file in read_dir(std::env::temp_dir().join("volt")).unwrap() {
-        //                     let file_path: PathBuf = file.unwrap().path();
-        //                     let file_name: &str = file_path.to_str().unwrap();
-        //                     let file_split: Vec<&str> = file_name.split('\\').collect();
-        //                     let name: &str = file_split[file_split.len() - 1];
-        //                     if name.starts_with(dep_name) {
-        //                         let file_split: Vec<&str> = name.split('@').collect();
-        //                         let file_end = file_split[1];
-        //                         let file_split: Vec<&str> = file_end.split(".tgz").collect();
-        //                         version = file_split[0].to_owned();
-        //                     }
-        //                 }
-        //                 let padding = 50 - (dep_path_split.len() * 2);
-        //                 print!(
-        //                     "{} {:<width$}",
-        //                     "-".bright_purple(),
-        //                     dep_name,
-        //                     width = padding
-        //                 );
-        //                 println!("{}", version.clone().truecolor(190, 190, 190));
-        //             }
-        //         }
-        //     }
-        // }
+        for root in &mut roots {
+            root.linked = links.linked(&directory, &root.name).cloned();
+        }
+
+        if self.json {
+            let output = json!({
+                "name": package_json.name,
+                "version": package_json.version,
+                "path": directory.display().to_string(),
+                "dependencies": json_nodes(&roots),
+            });
+
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&output).into_diagnostic()?
+            );
+
+            return Ok(());
+        }
+
+        println!(
+            "{}@{} {}",
+            package_json.name,
+            package_json.version,
+            directory.display().to_string().truecolor(156, 156, 156)
+        );
+
+        if roots.is_empty() {
+            println!(
+                "{}{} (No dependencies)",
+                UTF8_SYMBOLS.ell, UTF8_SYMBOLS.right
+            );
+        }
+
+        print_nodes(&roots, "", &UTF8_SYMBOLS);
 
         Ok(())
     }
 }
+
+/// Build the node for a dependency declared in package.json, comparing it against what's
+/// installed in `node_modules`.
+fn root_node(
+    lock_file: &LockFile,
+    node_modules: &Path,
+    name: &str,
+    range: &str,
+    max_depth: usize,
+    seen: &mut HashSet<String>,
+) -> TreeNode {
+    let installed = PackageJson::get_from_dir(&node_modules.join(name))
+        .ok()
+        .map(|(package_json, _)| package_json.version);
+
+    let version = match installed {
+        Some(version) => version,
+        None => {
+            return TreeNode {
+                name: name.to_string(),
+                version: Some(range.to_string()),
+                problem: Some(Problem::Missing),
+                deduped: false,
+                linked: None,
+                children: vec![],
+            }
+        }
+    };
+
+    let satisfies = match (version.parse::<Version>(), range.parse::<Range>()) {
+        (Ok(version), Ok(range)) => version.satisfies(&range),
+        // tags, urls and the like can't be checked
+        _ => true,
+    };
+
+    let problem = (!satisfies).then(|| Problem::Invalid {
+        expected: range.to_string(),
+    });
+
+    match lock_file
+        .get(name, &version)
+        .or_else(|| lock_file.find(name, range))
+    {
+        Some(package) => TreeNode {
+            version: Some(version),
+            problem,
+            ..package_node(lock_file, package, 0, max_depth, seen)
+        },
+        None => TreeNode {
+            name: name.to_string(),
+            version: Some(version),
+            problem,
+            deduped: false,
+            linked: None,
+            children: vec![],
+        },
+    }
+}
+
+/// Build the node of a locked package and its dependencies up to `max_depth`.
+fn package_node(
+    lock_file: &LockFile,
+    package: &LockedPackage,
+    depth: usize,
+    max_depth: usize,
+    seen: &mut HashSet<String>,
+) -> TreeNode {
+    let mut node = TreeNode {
+        name: package.name.clone(),
+        version: Some(package.version.clone()),
+        problem: None,
+        deduped: false,
+        linked: None,
+        children: vec![],
+    };
+
+    let dependencies: Vec<(&String, &String)> = package.edges().collect();
+
+    if depth >= max_depth || dependencies.is_empty() {
+        return node;
+    }
+
+    if !seen.insert(package.key()) {
+        node.deduped = true;
+        return node;
+    }
+
+    for (name, version) in dependencies {
+        let child = match lock_file.get(name, version) {
+            Some(child) => package_node(lock_file, child, depth + 1, max_depth, seen),
+            None => TreeNode {
+                name: name.to_string(),
+                version: Some(version.to_string()),
+                problem: Some(Problem::Missing),
+                deduped: false,
+                linked: None,
+                children: vec![],
+            },
+        };
+
+        node.children.push(child);
+    }
+
+    node
+}
+
+fn print_nodes(nodes: &[TreeNode], prefix: &str, symbols: &Symbols) {
+    for (index, node) in nodes.iter().enumerate() {
+        let last = index == nodes.len() - 1;
+
+        let branch = if last { symbols.ell } else { symbols.tee };
+
+        println!("{}{}{} {}", prefix, branch, symbols.right, describe(node));
+
+        let child_prefix = if last {
+            format!("{}   ", prefix)
+        } else {
+            format!("{}{}  ", prefix, symbols.down)
+        };
+
+        print_nodes(&node.children, &child_prefix, symbols);
+    }
+}
+
+fn describe(node: &TreeNode) -> String {
+    let version = node.version.clone().unwrap_or_default();
+
+    let description = match &node.problem {
+        Some(Problem::Missing) => format!(
+            "{} {}@{}",
+            "MISSING".truecolor(255, 000, 000),
+            node.name,
+            version
+        ),
+        Some(Problem::Invalid { expected }) => format!(
+            "{}@{} {}",
+            node.name.truecolor(000, 255, 000),
+            version.truecolor(255, 000, 000),
+            format!("invalid: expected {}", expected).truecolor(255, 000, 000)
+        ),
+        None if node.deduped => format!(
+            "{}@{} {}",
+            node.name.truecolor(000, 255, 000),
+            version.truecolor(000, 155, 000),
+            "deduped".truecolor(156, 156, 156)
+        ),
+        None => format!(
+            "{}@{}",
+            node.name.truecolor(000, 255, 000),
+            version.truecolor(000, 155, 000)
+        ),
+    };
+
+    match &node.linked {
+        Some(linked) => format!(
+            "{} {}",
+            description,
+            format!("linked → {}", linked.display()).truecolor(156, 156, 156)
+        ),
+        None => description,
+    }
+}
+
+fn json_nodes(nodes: &[TreeNode]) -> Value {
+    let mut map = Map::new();
+
+    for node in nodes {
+        let mut value = Map::new();
+
+        match &node.problem {
+            Some(Problem::Missing) => {
+                value.insert("required".to_string(), json!(node.version));
+                value.insert("missing".to_string(), json!(true));
+            }
+            Some(Problem::Invalid { expected }) => {
+                value.insert("version".to_string(), json!(node.version));
+                value.insert("invalid".to_string(), json!(expected));
+            }
+            None => {
+                value.insert("version".to_string(), json!(node.version));
+            }
+        }
+
+        if node.deduped {
+            value.insert("deduped".to_string(), json!(true));
+        }
+
+        if let Some(linked) = &node.linked {
+            value.insert("linked".to_string(), json!(linked));
+        }
+
+        if !node.children.is_empty() {
+            value.insert("dependencies".to_string(), json_nodes(&node.children));
+        }
+
+        map.insert(node.name.clone(), Value::Object(value));
+    }
+
+    Value::Object(map)
+}
@@ -14,103 +14,172 @@
     limitations under the License.
 */
 
-//! Login for a package.
+//! Log in to a registry.
 
-use crate::{
-    cli::{VoltCommand, VoltConfig},
-    core::prompt::prompts::{Input, Secret},
+use crate::cli::{
+    prompt::prompts::{Input, Secret},
+    VoltCommand, VoltConfig,
+};
+use volt_core::{
+    login::{finish_web_login, legacy_login, save_token, start_web_login, AuthType},
+    registry::RegistryClient,
+    reporter::{emit, Event},
 };
 
 use async_trait::async_trait;
 use clap::Parser;
 use colored::Colorize;
-use miette::Result;
+use miette::{IntoDiagnostic, Result};
+use serde_json::json;
 use urlencoding::encode;
 
-/// Login to the npm registry
+/// Log in to a registry, saving its token in ~/.npmrc
 #[derive(Debug, Parser)]
-pub struct Login {}
+pub struct Login {
+    /// Registry to log in to (the configured one by default)
+    #[clap(long)]
+    registry: Option<String>,
+
+    /// Log in in a browser, or with a username and password
+    #[clap(long, arg_enum, default_value = "web")]
+    auth_type: AuthType,
+}
 
 #[async_trait]
 impl VoltCommand for Login {
     /// Execute the `volt login` command
     ///
-    /// Login to the npm registry
+    /// Log in to the registry in a browser (or with a username and password on registries
+    /// without web login, or with `--auth-type legacy`), and save the token it hands out
+    /// in the user's `.npmrc`, readable only by the user.
     /// ## Arguments
-    /// * `error` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Login to the npm registry
+    /// // Log in to a private registry with a username and password
     /// // .exec() is an async call so you need to await it
-    /// Login.exec(app).await;
+    /// Login { registry: Some("https://npm.example.com/".into()), auth_type: AuthType::Legacy }
+    ///     .exec(config)
+    ///     .await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        loop {
-            let username_input = Input {
-                message: "Username".into(),
-                default: None,
-                allow_empty: false,
-            };
-
-            let password_input = Secret {
-                message: "Password".into(),
-                allow_empty: false,
-                confirm: None,
-                error: None,
-            };
-
-            // Get Username and Password
-            let username = username_input.run().unwrap_or_else(|err| {
-                eprintln!("{}", err);
-                std::process::exit(1);
-            });
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let client = match &self.registry {
+            Some(registry) => RegistryClient::for_registry(&config, registry)?,
+            None => RegistryClient::new(&config)?,
+        };
+
+        let web_login = match self.auth_type {
+            AuthType::Web => start_web_login(&client, &hostname()).await?,
+            AuthType::Legacy => None,
+        };
+
+        let token = match web_login {
+            Some(login) => {
+                eprintln!(
+                    "{} {}",
+                    "Log in at".green().bold(),
+                    login.login_url.underline()
+                );
+
+                if webbrowser::open(&login.login_url).is_err() {
+                    eprintln!("{} open the url above in a browser", "note:".cyan().bold());
+                }
+
+                finish_web_login(&client, &login).await?
+            }
+            None => {
+                if self.auth_type == AuthType::Web {
+                    eprintln!(
+                        "{} {} has no web login, logging in with a username and password",
+                        "note:".cyan().bold(),
+                        client.url
+                    );
+                }
+
+                let (username, password, email) = credentials()?;
+
+                legacy_login(&client, &username, &password, email.as_deref()).await?
+            }
+        };
 
-            let password = password_input.run().unwrap_or_else(|err| {
-                eprintln!("{}", err);
-                std::process::exit(1);
-            });
+        let npmrc = config.home()?.join(".npmrc");
 
-            // Log Error
-            if let Err(e) = validate_username(&username) {
-                println!("{}", e);
-                continue;
-            }
+        save_token(&npmrc, &client.url, &token)?;
 
-            if let Err(e) = validate_password(&username, &password) {
-                println!("{}", e);
-                continue;
-            }
+        if config.json() {
+            emit(&Event::Result(json!({
+                "registry": client.url,
+                "npmrc": npmrc,
+            })));
 
-            break;
+            return Ok(());
         }
 
+        println!(
+            "{} to {}, the token is saved in {}",
+            "Logged in".green().bold(),
+            client.url,
+            npmrc.display()
+        );
+
         Ok(())
     }
 }
 
-fn validate_username(username: &str) -> Result<(), String> {
-    if username.to_lowercase() == username {
-        Ok(())
-    } else {
-        Err(format!(
-            "\n{}: your username must be lowercase.\n",
-            error_header(),
-        ))
+/// Ask for a username, a password and an optional email.
+fn credentials() -> Result<(String, String, Option<String>)> {
+    let username = loop {
+        let username = Input {
+            message: "Username".into(),
+            default: None,
+            allow_empty: false,
+        }
+        .run()
+        .into_diagnostic()?;
+
+        match validate_username(&username) {
+            Ok(()) => break username,
+            Err(e) => println!("{}", e),
+        }
+    };
+
+    let password = Secret {
+        message: "Password".into(),
+        allow_empty: false,
+        confirm: None,
+        error: None,
+    }
+    .run()
+    .into_diagnostic()?;
+
+    let email = Input {
+        message: "Email (only needed for new accounts)".into(),
+        default: None,
+        allow_empty: true,
     }
+    .run()
+    .into_diagnostic()?;
+
+    Ok((
+        username,
+        password,
+        Some(email).filter(|email| !email.is_empty()),
+    ))
 }
 
-fn validate_password(username: &str, password: &str) -> Result<(), String> {
-    if password.len() < 10 {
-        Err(format!(
-            "\n{}: your password must be 10 characters or more.\n",
-            error_header(),
-        ))
-    } else if password.contains(&username) || password.contains(&username.to_lowercase()) {
+/// The name of this machine, shown by the registry next to the token.
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "volt".to_string())
+}
+
+fn validate_username(username: &str) -> Result<(), String> {
+    if username.to_lowercase() != username {
         Err(format!(
-            "\n{}: your password should not match or significantly contain your username, e.g. do \
-             not use 'username123'\n",
+            "\n{}: your username must be lowercase.\n",
             error_header(),
         ))
     } else if encode(username) != username {
@@ -118,13 +187,6 @@ fn validate_password(username: &str, password: &str) -> Result<(), String> {
             "\n{}: your username must be url-safe",
             error_header()
         ))
-    } else if UNACCEPTABLE_PASSWORDS.contains(&password) {
-        Err(format!(
-            "\n{}: your password matches one of npm's unacceptable passwords \
-             (https://www.npmjs.com/signup/common-passwords).\nFor more details, check out \
-             https://docs.npmjs.com/creating-a-strong-password\n",
-            error_header(),
-        ))
     } else {
         Ok(())
     }
@@ -134,6169 +196,3 @@ fn validate_password(username: &str, password: &str) -> Result<(), String> {
 fn error_header() -> colored::ColoredString {
     " ERROR ".black().on_bright_red()
 }
-
-static UNACCEPTABLE_PASSWORDS: [&str; 6163] = [
-    "123456",
-    "12345",
-    "123456789",
-    "password",
-    "iloveyou",
-    "princess",
-    "1234567",
-    "12345678",
-    "abc123",
-    "nicole",
-    "daniel",
-    "babygirl",
-    "monkey",
-    "lovely",
-    "jessica",
-    "654321",
-    "michael",
-    "ashley",
-    "qwerty",
-    "111111",
-    "iloveu",
-    "000000",
-    "michelle",
-    "tigger",
-    "sunshine",
-    "chocolate",
-    "password1",
-    "soccer",
-    "anthony",
-    "friends",
-    "butterfly",
-    "purple",
-    "angel",
-    "jordan",
-    "liverpool",
-    "justin",
-    "loveme",
-    "fuckyou",
-    "123123",
-    "football",
-    "secret",
-    "andrea",
-    "carlos",
-    "jennifer",
-    "joshua",
-    "bubbles",
-    "1234567890",
-    "superman",
-    "hannah",
-    "amanda",
-    "loveyou",
-    "pretty",
-    "basketball",
-    "andrew",
-    "angels",
-    "tweety",
-    "flower",
-    "playboy",
-    "hello",
-    "elizabeth",
-    "hottie",
-    "tinkerbell",
-    "charlie",
-    "samantha",
-    "barbie",
-    "chelsea",
-    "lovers",
-    "teamo",
-    "jasmine",
-    "brandon",
-    "666666",
-    "shadow",
-    "melissa",
-    "eminem",
-    "matthew",
-    "robert",
-    "danielle",
-    "forever",
-    "family",
-    "jonathan",
-    "987654321",
-    "computer",
-    "whatever",
-    "dragon",
-    "vanessa",
-    "cookie",
-    "naruto",
-    "summer",
-    "sweety",
-    "spongebob",
-    "joseph",
-    "junior",
-    "softball",
-    "taylor",
-    "yellow",
-    "daniela",
-    "lauren",
-    "mickey",
-    "princesa",
-    "alexandra",
-    "alexis",
-    "jesus",
-    "estrella",
-    "miguel",
-    "william",
-    "thomas",
-    "beautiful",
-    "mylove",
-    "angela",
-    "poohbear",
-    "patrick",
-    "iloveme",
-    "sakura",
-    "adrian",
-    "alexander",
-    "destiny",
-    "christian",
-    "121212",
-    "sayang",
-    "america",
-    "dancer",
-    "monica",
-    "richard",
-    "112233",
-    "princess1",
-    "555555",
-    "diamond",
-    "carolina",
-    "steven",
-    "rangers",
-    "louise",
-    "orange",
-    "789456",
-    "999999",
-    "shorty",
-    "11111",
-    "nathan",
-    "snoopy",
-    "gabriel",
-    "hunter",
-    "cherry",
-    "killer",
-    "sandra",
-    "alejandro",
-    "buster",
-    "george",
-    "brittany",
-    "alejandra",
-    "patricia",
-    "rachel",
-    "tequiero",
-    "7777777",
-    "cheese",
-    "159753",
-    "arsenal",
-    "dolphin",
-    "antonio",
-    "heather",
-    "david",
-    "ginger",
-    "stephanie",
-    "peanut",
-    "blink182",
-    "sweetie",
-    "222222",
-    "beauty",
-    "987654",
-    "victoria",
-    "honey",
-    "00000",
-    "fernando",
-    "pokemon",
-    "maggie",
-    "corazon",
-    "chicken",
-    "pepper",
-    "cristina",
-    "rainbow",
-    "kisses",
-    "manuel",
-    "myspace",
-    "rebelde",
-    "angel1",
-    "ricardo",
-    "babygurl",
-    "heaven",
-    "55555",
-    "baseball",
-    "martin",
-    "greenday",
-    "november",
-    "alyssa",
-    "madison",
-    "mother",
-    "123321",
-    "123abc",
-    "mahalkita",
-    "batman",
-    "september",
-    "december",
-    "morgan",
-    "mariposa",
-    "maria",
-    "gabriela",
-    "iloveyou2",
-    "bailey",
-    "jeremy",
-    "pamela",
-    "kimberly",
-    "gemini",
-    "shannon",
-    "pictures",
-    "asshole",
-    "sophie",
-    "jessie",
-    "hellokitty",
-    "claudia",
-    "babygirl1",
-    "angelica",
-    "austin",
-    "mahalko",
-    "victor",
-    "horses",
-    "tiffany",
-    "mariana",
-    "eduardo",
-    "andres",
-    "courtney",
-    "booboo",
-    "kissme",
-    "harley",
-    "ronaldo",
-    "iloveyou1",
-    "precious",
-    "october",
-    "inuyasha",
-    "peaches",
-    "veronica",
-    "chris",
-    "888888",
-    "adriana",
-    "cutie",
-    "james",
-    "banana",
-    "prince",
-    "friend",
-    "jesus1",
-    "crystal",
-    "celtic",
-    "zxcvbnm",
-    "edward",
-    "oliver",
-    "diana",
-    "samsung",
-    "freedom",
-    "angelo",
-    "kenneth",
-    "master",
-    "scooby",
-    "carmen",
-    "456789",
-    "sebastian",
-    "rebecca",
-    "jackie",
-    "spiderman",
-    "christopher",
-    "karina",
-    "johnny",
-    "hotmail",
-    "0123456789",
-    "school",
-    "barcelona",
-    "august",
-    "orlando",
-    "samuel",
-    "cameron",
-    "slipknot",
-    "cutiepie",
-    "monkey1",
-    "50cent",
-    "bonita",
-    "kevin",
-    "bitch",
-    "maganda",
-    "babyboy",
-    "casper",
-    "brenda",
-    "adidas",
-    "kitten",
-    "karen",
-    "mustang",
-    "isabel",
-    "natalie",
-    "cuteako",
-    "javier",
-    "789456123",
-    "123654",
-    "sarah",
-    "bowwow",
-    "portugal",
-    "laura",
-    "777777",
-    "marvin",
-    "denise",
-    "tigers",
-    "volleyball",
-    "jasper",
-    "rockstar",
-    "january",
-    "fuckoff",
-    "alicia",
-    "nicholas",
-    "flowers",
-    "cristian",
-    "tintin",
-    "bianca",
-    "chrisbrown",
-    "chester",
-    "101010",
-    "smokey",
-    "silver",
-    "internet",
-    "sweet",
-    "strawberry",
-    "garfield",
-    "dennis",
-    "panget",
-    "francis",
-    "cassie",
-    "benfica",
-    "love123",
-    "696969",
-    "asdfgh",
-    "lollipop",
-    "olivia",
-    "cancer",
-    "camila",
-    "qwertyuiop",
-    "superstar",
-    "harrypotter",
-    "ihateyou",
-    "charles",
-    "monique",
-    "midnight",
-    "vincent",
-    "christine",
-    "apples",
-    "scorpio",
-    "jordan23",
-    "lorena",
-    "andreea",
-    "mercedes",
-    "katherine",
-    "charmed",
-    "abigail",
-    "rafael",
-    "icecream",
-    "mexico",
-    "brianna",
-    "nirvana",
-    "aaliyah",
-    "pookie",
-    "johncena",
-    "lovelove",
-    "fucker",
-    "abcdef",
-    "benjamin",
-    "131313",
-    "gangsta",
-    "brooke",
-    "333333",
-    "hiphop",
-    "aaaaaa",
-    "mybaby",
-    "sergio",
-    "welcome",
-    "metallica",
-    "julian",
-    "travis",
-    "myspace1",
-    "babyblue",
-    "sabrina",
-    "michael1",
-    "jeffrey",
-    "stephen",
-    "love",
-    "dakota",
-    "catherine",
-    "badboy",
-    "fernanda",
-    "westlife",
-    "blondie",
-    "sasuke",
-    "smiley",
-    "jackson",
-    "simple",
-    "melanie",
-    "steaua",
-    "dolphins",
-    "roberto",
-    "fluffy",
-    "teresa",
-    "piglet",
-    "ronald",
-    "slideshow",
-    "asdfghjkl",
-    "minnie",
-    "newyork",
-    "jason",
-    "raymond",
-    "santiago",
-    "jayson",
-    "88888888",
-    "5201314",
-    "jerome",
-    "gandako",
-    "muffin",
-    "gatita",
-    "babyko",
-    "246810",
-    "sweetheart",
-    "chivas",
-    "ladybug",
-    "kitty",
-    "popcorn",
-    "alberto",
-    "valeria",
-    "cookies",
-    "leslie",
-    "jenny",
-    "nicole1",
-    "12345678910",
-    "leonardo",
-    "jayjay",
-    "liliana",
-    "dexter",
-    "sexygirl",
-    "232323",
-    "amores",
-    "rockon",
-    "christ",
-    "babydoll",
-    "anthony1",
-    "marcus",
-    "bitch1",
-    "fatima",
-    "miamor",
-    "lover",
-    "chris1",
-    "single",
-    "eeyore",
-    "lalala",
-    "252525",
-    "scooter",
-    "natasha",
-    "skittles",
-    "brooklyn",
-    "colombia",
-    "159357",
-    "teddybear",
-    "winnie",
-    "happy",
-    "manutd",
-    "123456a",
-    "britney",
-    "katrina",
-    "christina",
-    "pasaway",
-    "cocacola",
-    "mahal",
-    "grace",
-    "linda",
-    "albert",
-    "tatiana",
-    "london",
-    "cantik",
-    "0123456",
-    "lakers",
-    "marie",
-    "teiubesc",
-    "147258369",
-    "charlotte",
-    "natalia",
-    "francisco",
-    "amorcito",
-    "smile",
-    "paola",
-    "angelito",
-    "manchester",
-    "hahaha",
-    "elephant",
-    "mommy1",
-    "shelby",
-    "147258",
-    "kelsey",
-    "genesis",
-    "amigos",
-    "snickers",
-    "xavier",
-    "turtle",
-    "marlon",
-    "linkinpark",
-    "claire",
-    "stupid",
-    "147852",
-    "marina",
-    "garcia",
-    "fuckyou1",
-    "diego",
-    "brandy",
-    "letmein",
-    "hockey",
-    "444444",
-    "sharon",
-    "bonnie",
-    "spider",
-    "iverson",
-    "andrei",
-    "justine",
-    "frankie",
-    "pimpin",
-    "disney",
-    "rabbit",
-    "54321",
-    "fashion",
-    "soccer1",
-    "red123",
-    "bestfriend",
-    "england",
-    "hermosa",
-    "456123",
-    "qazwsx",
-    "bandit",
-    "danny",
-    "allison",
-    "emily",
-    "102030",
-    "lucky1",
-    "sporting",
-    "miranda",
-    "dallas",
-    "hearts",
-    "camille",
-    "wilson",
-    "potter",
-    "pumpkin",
-    "iloveu2",
-    "number1",
-    "katie",
-    "guitar",
-    "212121",
-    "truelove",
-    "jayden",
-    "savannah",
-    "hottie1",
-    "phoenix",
-    "monster",
-    "player",
-    "ganda",
-    "people",
-    "scotland",
-    "nelson",
-    "jasmin",
-    "timothy",
-    "onelove",
-    "ilovehim",
-    "shakira",
-    "estrellita",
-    "bubble",
-    "smiles",
-    "brandon1",
-    "sparky",
-    "barney",
-    "sweets",
-    "parola",
-    "evelyn",
-    "familia",
-    "love12",
-    "nikki",
-    "motorola",
-    "florida",
-    "omarion",
-    "monkeys",
-    "loverboy",
-    "elijah",
-    "joanna",
-    "canada",
-    "ronnie",
-    "mamita",
-    "emmanuel",
-    "thunder",
-    "999999999",
-    "broken",
-    "rodrigo",
-    "maryjane",
-    "westside",
-    "california",
-    "lucky",
-    "mauricio",
-    "yankees",
-    "jackass",
-    "jamaica",
-    "justin1",
-    "amigas",
-    "preciosa",
-    "shopping",
-    "flores",
-    "mariah",
-    "matrix",
-    "isabella",
-    "tennis",
-    "trinity",
-    "jorge",
-    "sunflower",
-    "kathleen",
-    "bradley",
-    "cupcake",
-    "hector",
-    "martinez",
-    "elaine",
-    "robbie",
-    "friendster",
-    "cheche",
-    "gracie",
-    "connor",
-    "hello1",
-    "valentina",
-    "melody",
-    "darling",
-    "sammy",
-    "jamie",
-    "santos",
-    "abcdefg",
-    "joanne",
-    "candy",
-    "fuckyou2",
-    "loser",
-    "dominic",
-    "pebbles",
-    "sunshine1",
-    "swimming",
-    "millie",
-    "loving",
-    "gangster",
-    "blessed",
-    "compaq",
-    "taurus",
-    "gloria",
-    "tyler",
-    "aaron",
-    "darkangel",
-    "kitkat",
-    "megan",
-    "dreams",
-    "sweetpea",
-    "bettyboop",
-    "jessica1",
-    "cynthia",
-    "cheyenne",
-    "ferrari",
-    "dustin",
-    "iubire",
-    "a123456",
-    "snowball",
-    "purple1",
-    "violet",
-    "darren",
-    "starwars",
-    "bestfriends",
-    "inlove",
-    "kelly",
-    "batista",
-    "karla",
-    "sophia",
-    "chacha",
-    "biteme",
-    "marian",
-    "sydney",
-    "sexyme",
-    "pogiako",
-    "gerald",
-    "jordan1",
-    "010203",
-    "daddy1",
-    "zachary",
-    "daddysgirl",
-    "billabong",
-    "carebear",
-    "froggy",
-    "pinky",
-    "erika",
-    "oscar",
-    "skater",
-    "raiders",
-    "nenita",
-    "tigger1",
-    "ashley1",
-    "charlie1",
-    "gatito",
-    "lokita",
-    "maldita",
-    "buttercup",
-    "nichole",
-    "bambam",
-    "nothing",
-    "glitter",
-    "bella",
-    "amber",
-    "apple",
-    "123789",
-    "sister",
-    "zacefron",
-    "tokiohotel",
-    "loveya",
-    "lindsey",
-    "money",
-    "lovebug",
-    "bubblegum",
-    "marissa",
-    "dreamer",
-    "darkness",
-    "cecilia",
-    "lollypop",
-    "nicolas",
-    "google",
-    "lindsay",
-    "cooper",
-    "passion",
-    "kristine",
-    "green",
-    "puppies",
-    "ariana",
-    "fuckme",
-    "chubby",
-    "raquel",
-    "lonely",
-    "anderson",
-    "sammie",
-    "sexybitch",
-    "mario",
-    "butter",
-    "willow",
-    "roxana",
-    "mememe",
-    "caroline",
-    "susana",
-    "kristen",
-    "baller",
-    "hotstuff",
-    "carter",
-    "stacey",
-    "babylove",
-    "angelina",
-    "miller",
-    "scorpion",
-    "sierra",
-    "playgirl",
-    "sweet16",
-    "012345",
-    "rocker",
-    "bhebhe",
-    "gustavo",
-    "marcos",
-    "chance",
-    "123qwe",
-    "kayla",
-    "james1",
-    "football1",
-    "eagles",
-    "loveme1",
-    "milagros",
-    "stella",
-    "lilmama",
-    "beyonce",
-    "lovely1",
-    "rocky",
-    "daddy",
-    "catdog",
-    "armando",
-    "margarita",
-    "151515",
-    "loves",
-    "lolita",
-    "202020",
-    "gerard",
-    "undertaker",
-    "amistad",
-    "williams",
-    "qwerty1",
-    "freddy",
-    "capricorn",
-    "caitlin",
-    "bryan",
-    "delfin",
-    "dance",
-    "cheerleader",
-    "password2",
-    "PASSWORD",
-    "martha",
-    "lizzie",
-    "georgia",
-    "matthew1",
-    "enrique",
-    "zxcvbn",
-    "badgirl",
-    "andrew1",
-    "141414",
-    "11111111",
-    "dancing",
-    "cuteme",
-    "booger",
-    "amelia",
-    "vampire",
-    "skyline",
-    "chiquita",
-    "angeles",
-    "scoobydoo",
-    "janine",
-    "tamara",
-    "carlitos",
-    "money1",
-    "sheila",
-    "justme",
-    "ireland",
-    "kittycat",
-    "hotdog",
-    "yamaha",
-    "tristan",
-    "harvey",
-    "israel",
-    "legolas",
-    "michelle1",
-    "maddie",
-    "angie",
-    "cinderella",
-    "jesuschrist",
-    "lester",
-    "ashton",
-    "ilovejesus",
-    "tazmania",
-    "remember",
-    "xxxxxx",
-    "tekiero",
-    "thebest",
-    "princesita",
-    "lucky7",
-    "jesucristo",
-    "peewee",
-    "paloma",
-    "buddy1",
-    "deedee",
-    "miriam",
-    "april",
-    "patches",
-    "regina",
-    "janice",
-    "cowboys",
-    "myself",
-    "lipgloss",
-    "jazmin",
-    "rosita",
-    "happy1",
-    "felipe",
-    "chichi",
-    "pangit",
-    "mierda",
-    "genius",
-    "741852963",
-    "hernandez",
-    "awesome",
-    "walter",
-    "tinker",
-    "arturo",
-    "silvia",
-    "melvin",
-    "celeste",
-    "pussycat",
-    "gorgeous",
-    "david1",
-    "molly",
-    "honeyko",
-    "mylife",
-    "animal",
-    "penguin",
-    "babyboo",
-    "loveu",
-    "simpsons",
-    "lupita",
-    "boomer",
-    "panthers",
-    "hollywood",
-    "alfredo",
-    "musica",
-    "johnson",
-    "ilovegod",
-    "hawaii",
-    "sparkle",
-    "kristina",
-    "sexymama",
-    "crazy",
-    "valerie",
-    "spencer",
-    "scarface",
-    "hardcore",
-    "098765",
-    "00000000",
-    "winter",
-    "hailey",
-    "trixie",
-    "hayden",
-    "micheal",
-    "wesley",
-    "242424",
-    "0987654321",
-    "marisol",
-    "nikita",
-    "daisy",
-    "jeremiah",
-    "pineapple",
-    "mhine",
-    "isaiah",
-    "christmas",
-    "cesar",
-    "lolipop",
-    "butterfly1",
-    "chloe",
-    "lawrence",
-    "xbox360",
-    "sheena",
-    "murphy",
-    "madalina",
-    "anamaria",
-    "gateway",
-    "debbie",
-    "yourmom",
-    "blonde",
-    "jasmine1",
-    "please",
-    "bubbles1",
-    "jimmy",
-    "beatriz",
-    "poopoo",
-    "diamonds",
-    "whitney",
-    "friendship",
-    "sweetness",
-    "pauline",
-    "desiree",
-    "trouble",
-    "741852",
-    "united",
-    "marley",
-    "brian",
-    "barbara",
-    "hannah1",
-    "bananas",
-    "julius",
-    "leanne",
-    "sandy",
-    "marie1",
-    "anita",
-    "lover1",
-    "chicago",
-    "twinkle",
-    "pantera",
-    "february",
-    "birthday",
-    "shadow1",
-    "qwert",
-    "bebita",
-    "87654321",
-    "twilight",
-    "imissyou",
-    "pollito",
-    "ashlee",
-    "tucker",
-    "cookie1",
-    "shelly",
-    "catalina",
-    "147852369",
-    "beckham",
-    "simone",
-    "nursing",
-    "iloveyou!",
-    "eugene",
-    "torres",
-    "damian",
-    "123123123",
-    "joshua1",
-    "bobby",
-    "babyface",
-    "andre",
-    "donald",
-    "daniel1",
-    "panther",
-    "dinamo",
-    "mommy",
-    "juliana",
-    "cassandra",
-    "trustno1",
-    "sexylady",
-    "14344",
-    "autumn",
-    "mendoza",
-    "animals",
-    "perfect",
-    "mariel",
-    "bullshit",
-    "bitches",
-    "852456",
-    "marcela",
-    "drpepper",
-    "gerardo",
-    "titanic",
-    "robert1",
-    "alison",
-    "moomoo",
-    "paulina",
-    "blossom",
-    "simpleplan",
-    "cutie1",
-    "abcd1234",
-    "bethany",
-    "kucing",
-    "bulldogs",
-    "buddy",
-    "fabian",
-    "ILOVEYOU",
-    "hollister",
-    "samson",
-    "harold",
-    "serenity",
-    "lovehurts",
-    "tyrone",
-    "rodriguez",
-    "amanda1",
-    "willie",
-    "kaylee",
-    "pisces",
-    "patito",
-    "paradise",
-    "1111111",
-    "piolin",
-    "reggie",
-    "diesel",
-    "salvador",
-    "dianne",
-    "cowboy",
-    "143143",
-    "chanel",
-    "joseluis",
-    "sanchez",
-    "peterpan",
-    "diosesamor",
-    "stars",
-    "dancer1",
-    "element",
-    "derrick",
-    "coffee",
-    "grandma",
-    "always",
-    "ashleigh",
-    "181818",
-    "marshall",
-    "jason1",
-    "helena",
-    "tanner",
-    "theresa",
-    "sweet1",
-    "chinita",
-    "alexandru",
-    "mihaela",
-    "trisha",
-    "mitchell",
-    "love4ever",
-    "1234",
-    "shane",
-    "bulldog",
-    "brownie",
-    "thuglife",
-    "amormio",
-    "margaret",
-    "calvin",
-    "aquarius",
-    "phillip",
-    "kitty1",
-    "morena",
-    "heart",
-    "rosario",
-    "thumper",
-    "marius",
-    "sammy1",
-    "aaaaa",
-    "yvonne",
-    "panda",
-    "lorraine",
-    "babycakes",
-    "kaitlyn",
-    "hayley",
-    "bigboy",
-    "esther",
-    "gerrard",
-    "frances",
-    "sexy123",
-    "catarina",
-    "archie",
-    "tweety1",
-    "danger",
-    "rockme",
-    "pickles",
-    "marco",
-    "arnold",
-    "gregory",
-    "taylor1",
-    "brittney",
-    "superman1",
-    "babies",
-    "lorenzo",
-    "hamster",
-    "sofia",
-    "rocku",
-    "dominique",
-    "jenjen",
-    "donkey",
-    "11223344",
-    "yasmin",
-    "trevor",
-    "roxanne",
-    "audrey",
-    "happiness",
-    "creative",
-    "virginia",
-    "castillo",
-    "godisgood",
-    "chrissy",
-    "police",
-    "joyjoy",
-    "giggles",
-    "shorty1",
-    "speedy",
-    "parker",
-    "sports",
-    "italia",
-    "cuddles",
-    "nadine",
-    "tyler1",
-    "1q2w3e4r",
-    "pelusa",
-    "beautiful1",
-    "boston",
-    "summer1",
-    "753951",
-    "black",
-    "fabiola",
-    "kittykat",
-    "jocelyn",
-    "darwin",
-    "junjun",
-    "cristo",
-    "cheer",
-    "mickey1",
-    "ghetto",
-    "asdfg",
-    "rosebud",
-    "julio",
-    "cuteko",
-    "madison1",
-    "russell",
-    "bloods",
-    "maxwell",
-    "maverick",
-    "babygurl1",
-    "wildcats",
-    "rochelle",
-    "fantasy",
-    "faith",
-    "sarita",
-    "rooney",
-    "gabrielle",
-    "alisha",
-    "kayleigh",
-    "macmac",
-    "mississippi",
-    "gilbert",
-    "college",
-    "justice",
-    "mickeymouse",
-    "curtis",
-    "12341234",
-    "toyota",
-    "william1",
-    "soledad",
-    "rascal",
-    "golden",
-    "1q2w3e",
-    "naughty",
-    "gwapako",
-    "prettygirl",
-    "friends1",
-    "camilo",
-    "busted",
-    "singer",
-    "pretty1",
-    "jellybean",
-    "yolanda",
-    "pinkie",
-    "love13",
-    "123654789",
-    "johanna",
-    "hershey",
-    "elizabeth1",
-    "philip",
-    "holas",
-    "marilyn",
-    "buttons",
-    "ramona",
-    "carla",
-    "australia",
-    "bismillah",
-    "7654321",
-    "bigdaddy",
-    "9876543210",
-    "photos",
-    "franklin",
-    "pink123",
-    "erick",
-    "vanilla",
-    "briana",
-    "hello123",
-    "jacob",
-    "hilary",
-    "pedro",
-    "loveme2",
-    "callum",
-    "watermelon",
-    "lourdes",
-    "janelle",
-    "PRINCESS",
-    "juancarlos",
-    "tania",
-    "unicorn",
-    "rahasia",
-    "ihateu",
-    "lovergirl",
-    "latina",
-    "cheer1",
-    "shirley",
-    "dragons",
-    "breanna",
-    "sapphire",
-    "papito",
-    "diablo",
-    "montana",
-    "asshole1",
-    "22222",
-    "lucky13",
-    "dylan",
-    "cricket",
-    "warren",
-    "scotty",
-    "teamomucho",
-    "passw0rd",
-    "lavender",
-    "iluvme",
-    "yellow1",
-    "emerald",
-    "douglas",
-    "therock",
-    "aurora",
-    "hunter1",
-    "emanuel",
-    "yoyoyo",
-    "krystal",
-    "flower1",
-    "belinda",
-    "tiger",
-    "hotgirl",
-    "010101",
-    "poohbear1",
-    "cuties",
-    "valentine",
-    "idontknow",
-    "pikachu",
-    "little",
-    "diamond1",
-    "iloveu1",
-    "babyphat",
-    "peanut1",
-    "kittens",
-    "goddess",
-    "ballet",
-    "damien",
-    "nascar",
-    "171717",
-    "rangers1",
-    "winston",
-    "0000000000",
-    "rocky1",
-    "coolgirl",
-    "maymay",
-    "charlene",
-    "caramelo",
-    "selena",
-    "lucero",
-    "wendy",
-    "volcom",
-    "1435254",
-    "copper",
-    "cindy",
-    "baby123",
-    "slayer",
-    "angelita",
-    "love1",
-    "alexa",
-    "kingkong",
-    "pickle",
-    "guadalupe",
-    "lovelife",
-    "142536",
-    "esmeralda",
-    "houston",
-    "dayana",
-    "kissmyass",
-    "handsome",
-    "paula",
-    "wicked",
-    "shithead",
-    "iloveme1",
-    "sexybabe",
-    "nathaniel",
-    "incubus",
-    "crazy1",
-    "candy1",
-    "loulou",
-    "buster1",
-    "ramirez",
-    "falloutboy",
-    "richie",
-    "converse",
-    "2cute4u",
-    "shaggy",
-    "rayray",
-    "phoebe",
-    "teacher",
-    "spongebob1",
-    "boogie",
-    "marisa",
-    "madonna",
-    "eunice",
-    "dianita",
-    "special",
-    "norman",
-    "connie",
-    "myname",
-    "hotchick",
-    "1111111111",
-    "chelsea1",
-    "124578",
-    "080808",
-    "music",
-    "sagitario",
-    "sassy1",
-    "family1",
-    "yahoo",
-    "sexy12",
-    "danica",
-    "angel123",
-    "lacoste",
-    "cutegirl",
-    "campanita",
-    "billy",
-    "kristin",
-    "linkin",
-    "161616",
-    "realmadrid",
-    "jesse",
-    "iceman",
-    "a12345",
-    "spanky",
-    "liberty",
-    "molly1",
-    "ronaldinho",
-    "password123",
-    "windows",
-    "peter",
-    "kelvin",
-    "gothic",
-    "walker",
-    "maribel",
-    "goldfish",
-    "atlanta",
-    "moises",
-    "chicken1",
-    "0000000",
-    "tommy",
-    "juventus",
-    "mahalkoh",
-    "esteban",
-    "mookie",
-    "fresita",
-    "leelee",
-    "tequieromucho",
-    "harry",
-    "giovanni",
-    "ranger",
-    "celticfc",
-    "tagged",
-    "snuggles",
-    "preston",
-    "newcastle",
-    "austin1",
-    "sniper",
-    "erica",
-    "stefan",
-    "ecuador",
-    "hotpink",
-    "soulmate",
-    "shutup",
-    "1qaz2wsx",
-    "taytay",
-    "sassy",
-    "iverson3",
-    "playboy1",
-    "lunita",
-    "honey1",
-    "951753",
-    "thomas1",
-    "bernard",
-    "peace",
-    "arthur",
-    "12345a",
-    "marlboro",
-    "merlin",
-    "southside",
-    "loser1",
-    "brandi",
-    "arlene",
-    "blueeyes",
-    "michel",
-    "rachelle",
-    "mackenzie",
-    "ernesto",
-    "champion",
-    "missy",
-    "mamapapa",
-    "fatboy",
-    "darius",
-    "282828",
-    "edgar",
-    "alexia",
-    "landon",
-    "nicola",
-    "99999",
-    "nancy",
-    "hermione",
-    "cosita",
-    "nissan",
-    "michele",
-    "starlight",
-    "unique",
-    "tiger1",
-    "rivera",
-    "morales",
-    "coolcat",
-    "steelers",
-    "judith",
-    "dimples",
-    "chocolate1",
-    "viviana",
-    "rodney",
-    "iluvu",
-    "maurice",
-    "katelyn",
-    "carrie",
-    "111222",
-    "gonzalez",
-    "softball1",
-    "random",
-    "kennedy",
-    "esperanza",
-    "pierre",
-    "moonlight",
-    "baby12",
-    "spirit",
-    "love22",
-    "nintendo",
-    "marlene",
-    "234567",
-    "shasha",
-    "snowflake",
-    "children",
-    "stanley",
-    "newlife",
-    "goober",
-    "doraemon",
-    "ingrid",
-    "father",
-    "77777",
-    "geraldine",
-    "dimple",
-    "dillon",
-    "romance",
-    "bunny",
-    "bhaby",
-    "winner",
-    "tweetybird",
-    "kathryn",
-    "paramore",
-    "allstar",
-    "abcde",
-    "something",
-    "runescape",
-    "jermaine",
-    "jefferson",
-    "pitbull",
-    "seventeen",
-    "romania",
-    "france",
-    "emotional",
-    "nigger",
-    "mariela",
-    "fucku",
-    "bitchy",
-    "ballin",
-    "loveless",
-    "smallville",
-    "ricky",
-    "peluche",
-    "godbless",
-    "blue123",
-    "alonso",
-    "meghan",
-    "garrett",
-    "mykids",
-    "mexico1",
-    "clover",
-    "vanesa",
-    "smudge",
-    "cooldude",
-    "chopper",
-    "cassidy",
-    "andreita",
-    "134679",
-    "cherries",
-    "070707",
-    "skippy",
-    "kaykay",
-    "domino",
-    "ximena",
-    "julie",
-    "goldie",
-    "daisy1",
-    "bella1",
-    "thailand",
-    "puppy",
-    "gladys",
-    "computer1",
-    "boricua",
-    "karate",
-    "janjan",
-    "freddie",
-    "acuario",
-    "262626",
-    "yugioh",
-    "marjorie",
-    "maggie1",
-    "blueberry",
-    "joyce",
-    "basket",
-    "sunset",
-    "hummer",
-    "destiny1",
-    "annie",
-    "angelbaby",
-    "amber1",
-    "pakistan",
-    "negrita",
-    "kendra",
-    "blue22",
-    "dipset",
-    "coconut",
-    "kirsty",
-    "danilo",
-    "alexis1",
-    "whatever1",
-    "cameron1",
-    "booboo1",
-    "aileen",
-    "191919",
-    "samantha1",
-    "sponge",
-    "abraham",
-    "ilovemyself",
-    "guillermo",
-    "groovy",
-    "cheeky",
-    "swordfish",
-    "kevin1",
-    "dragon1",
-    "blahblah",
-    "babyboy1",
-    "granny",
-    "bintang",
-    "harmony",
-    "wrestling",
-    "poopie",
-    "green1",
-    "cheryl",
-    "alfonso",
-    "nathan1",
-    "dragonfly",
-    "yourock",
-    "ragnarok",
-    "jazmine",
-    "bonbon",
-    "michaela",
-    "carlo",
-    "theone",
-    "serena",
-    "rock you",
-    "manunited",
-    "iloveboys",
-    "blacky",
-    "karlita",
-    "bogdan",
-    "mikey",
-    "love69",
-    "jillian",
-    "eclipse",
-    "catalin",
-    "punkrock",
-    "mollie",
-    "bugsbunny",
-    "patrick1",
-    "supergirl",
-    "melisa",
-    "lilwayne",
-    "miracle",
-    "alianza",
-    "warrior",
-    "christy",
-    "harley1",
-    "jennifer1",
-    "hollie",
-    "violeta",
-    "puppylove",
-    "munchkin",
-    "fender",
-    "moreno",
-    "maureen",
-    "makayla",
-    "emilio",
-    "brother",
-    "ilovechris",
-    "gymnastics",
-    "helpme",
-    "doggie",
-    "mmmmmm",
-    "bailey1",
-    "milkshake",
-    "rachael",
-    "goodgirl",
-    "athena",
-    "kenzie",
-    "john316",
-    "blabla",
-    "mathew",
-    "virgin",
-    "159951",
-    "juanita",
-    "ingeras",
-    "pepper1",
-    "mckenzie",
-    "katkat",
-    "caramel",
-    "heyhey",
-    "estrela",
-    "steven1",
-    "kenny",
-    "love14",
-    "holly",
-    "estefania",
-    "bullet",
-    "manuela",
-    "baseball1",
-    "lenlen",
-    "elena",
-    "alfred",
-    "geminis",
-    "1password",
-    "redrose",
-    "keisha",
-    "danny1",
-    "casey",
-    "chandler",
-    "aubrey",
-    "godislove",
-    "lasvegas",
-    "jajaja",
-    "florin",
-    "eternity",
-    "danielle1",
-    "seven7",
-    "harrison",
-    "felicia",
-    "success",
-    "starfish",
-    "scrappy",
-    "santana",
-    "jupiter",
-    "duncan",
-    "butthead",
-    "wallace",
-    "holiday",
-    "ariel",
-    "america1",
-    "mayra",
-    "asdfjkl;",
-    "ginger1",
-    "chelle",
-    "luisa",
-    "rihanna",
-    "myangel",
-    "lampard",
-    "wizard",
-    "fiorella",
-    "argentina",
-    "2hot4u",
-    "yesenia",
-    "denisa",
-    "stinky",
-    "joseph1",
-    "badass",
-    "online",
-    "joana",
-    "blackie",
-    "aaron1",
-    "sailormoon",
-    "killer1",
-    "deanna",
-    "junior1",
-    "golfinho",
-    "freaky",
-    "futbol",
-    "dragoste",
-    "colleen",
-    "popeye",
-    "gonzales",
-    "billie",
-    "jaguar",
-    "hehehe",
-    "love11",
-    "kathy",
-    "dramaqueen",
-    "josephine",
-    "sarah1",
-    "mermaid",
-    "carito",
-    "welcome1",
-    "redsox",
-    "carina",
-    "arianna",
-    "sexylove",
-    "monika",
-    "computadora",
-    "ludacris",
-    "kirsten",
-    "clayton",
-    "ilovematt",
-    "asdasd",
-    "sugar",
-    "holden",
-    "baxter",
-    "yankee",
-    "melissa1",
-    "jonjon",
-    "honduras",
-    "carol",
-    "ABC123",
-    "yvette",
-    "liverpoolfc",
-    "ilovejosh",
-    "fucku2",
-    "dwayne",
-    "berenice",
-    "teddy",
-    "momdad",
-    "lovesucks",
-    "joejonas",
-    "forever1",
-    "divina",
-    "clifford",
-    "tracey",
-    "sasha",
-    "rebeca",
-    "frank",
-    "1212312121",
-    "nacional",
-    "mibebe",
-    "scott",
-    "ronaldo7",
-    "password12",
-    "kisskiss",
-    "fergie",
-    "conejo",
-    "272727",
-    "hotmama",
-    "evanescence",
-    "stevie",
-    "fishing",
-    "callie",
-    "bruno",
-    "vivian",
-    "thegame",
-    "steph",
-    "richard1",
-    "coolio",
-    "aventura",
-    "cristi",
-    "simona",
-    "love101",
-    "eastside",
-    "cristiano",
-    "manson",
-    "loveu2",
-    "brayan",
-    "88888",
-    "valentin",
-    "sublime",
-    "redneck",
-    "fernandez",
-    "corona",
-    "cleopatra",
-    "mahalq",
-    "luisito",
-    "dalton",
-    "gordon",
-    "monday",
-    "change",
-    "blanca",
-    "963852",
-    "maxine",
-    "knight",
-    "fabulous",
-    "roland",
-    "qwe123",
-    "pablo",
-    "marcelo",
-    "henry",
-    "alvin",
-    "love21",
-    "cuttie",
-    "annette",
-    "Password",
-    "maryann",
-    "friday",
-    "yanyan",
-    "logitech",
-    "stewart",
-    "prettyme",
-    "motherfucker",
-    "jersey",
-    "roberta",
-    "raiders1",
-    "martina",
-    "gabby",
-    "morris",
-    "cherry1",
-    "carlos1",
-    "alaska",
-    "sexy69",
-    "orange1",
-    "dolphin1",
-    "wolves",
-    "warriors",
-    "microsoft",
-    "medina",
-    "agosto",
-    "555666",
-    "smelly",
-    "jennie",
-    "noodles",
-    "missy1",
-    "marines",
-    "candice",
-    "single1",
-    "lizard",
-    "brendan",
-    "romeo",
-    "cheetah",
-    "090909",
-    "tequila",
-    "8675309",
-    "leonard",
-    "iluvyou",
-    "heather1",
-    "filipa",
-    "castro",
-    "tricia",
-    "saints",
-    "grandad",
-    "love23",
-    "pirates",
-    "kimkim",
-    "kieran",
-    "kawasaki",
-    "vegeta",
-    "messenger",
-    "juliet",
-    "jetaime",
-    "howard",
-    "denver",
-    "cintaku",
-    "randy",
-    "maximus",
-    "jesus7",
-    "jacqueline",
-    "hercules",
-    "amore",
-    "everton",
-    "legend",
-    "kendall",
-    "nightmare",
-    "charmaine",
-    "jonas",
-    "drowssap",
-    "alvaro",
-    "456456",
-    "suzanne",
-    "mystuff",
-    "mother1",
-    "honeybee",
-    "drummer",
-    "bubba1",
-    "antonia",
-    "shiela",
-    "shalom",
-    "ruben",
-    "platinum",
-    "leticia",
-    "german",
-    "snowman",
-    "romero",
-    "madeline",
-    "dulce",
-    "turkey",
-    "sweetgirl",
-    "sayangku",
-    "pancho",
-    "mandy",
-    "lollol",
-    "edison",
-    "ashanti",
-    "angel12",
-    "rocknroll",
-    "andreia",
-    "Princess",
-    "freckles",
-    "yousuck",
-    "juanito",
-    "ismael",
-    "chingy",
-    "taekwondo",
-    "freedom1",
-    "ericka",
-    "020202",
-    "franco",
-    "celine",
-    "trinidad",
-    "smokey1",
-    "promise",
-    "jeremy1",
-    "angel2",
-    "alexandre",
-    "ganteng",
-    "arcangel",
-    "julia",
-    "precious1",
-    "asd123",
-    "jacob1",
-    "cowgirl",
-    "peaches1",
-    "melinda",
-    "sixteen",
-    "qazwsxedc",
-    "mustang1",
-    "budlight",
-    "newyork1",
-    "loveyou2",
-    "iluvu2",
-    "cracker",
-    "renato",
-    "kenshin",
-    "phantom",
-    "loveable",
-    "laptop",
-    "hamilton",
-    "fuckit",
-    "blessed1",
-    "virgo",
-    "1bitch",
-    "shauna",
-    "private",
-    "isabelle",
-    "biatch",
-    "surfer",
-    "passport",
-    "lynlyn",
-    "kayla1",
-    "bobmarley",
-    "babykoh",
-    "picture",
-    "logan",
-    "jesusfreak",
-    "harris",
-    "doodle",
-    "sexybaby",
-    "pink",
-    "lizbeth",
-    "bubba",
-    "triskelion",
-    "tootsie",
-    "thalia",
-    "rolando",
-    "ilovemike",
-    "apple1",
-    "valencia",
-    "shawn",
-    "rommel",
-    "maemae",
-    "felicidad",
-    "cheese1",
-    "student",
-    "rainbow1",
-    "confused",
-    "cellphone",
-    "aldrin",
-    "paris",
-    "minime",
-    "marion",
-    "ewanko",
-    "stormy",
-    "sisters",
-    "rocket",
-    "queen",
-    "lovers1",
-    "slimshady",
-    "steve",
-    "poison",
-    "florence",
-    "rey619",
-    "delete",
-    "cristal",
-    "yomama",
-    "spooky",
-    "pimpin1",
-    "maricel",
-    "gizmo1",
-    "dondon",
-    "divine",
-    "chucky",
-    "aries",
-    "rowena",
-    "nokia",
-    "stitch",
-    "jerry",
-    "hazel",
-    "maimai",
-    "jackson1",
-    "nataly",
-    "emily1",
-    "swimmer",
-    "pussy",
-    "loveyou1",
-    "liverpool1",
-    "lauren1",
-    "chloe1",
-    "jonathan1",
-    "soloyo",
-    "jonasbrothers",
-    "eminem1",
-    "anything",
-    "nickjonas",
-    "vodafone",
-    "punkin",
-    "mnbvcxz",
-    "forget",
-    "babes",
-    "tasha",
-    "magandaako",
-    "madrid",
-    "scruffy",
-    "paige",
-    "motocross",
-    "colorado",
-    "321654",
-    "skyler",
-    "sidney",
-    "bryant",
-    "malibu",
-    "galaxy",
-    "cruzazul",
-    "paolita",
-    "getmoney",
-    "filipe",
-    "applepie",
-    "454545",
-    "insane",
-    "princes",
-    "morado",
-    "laurita",
-    "country",
-    "theused",
-    "beatrice",
-    "gunner",
-    "greenday1",
-    "edwin",
-    "cinta",
-    "ROCKYOU",
-    "shanice",
-    "kagome",
-    "sherry",
-    "penelope",
-    "password3",
-    "morgan1",
-    "kakashi",
-    "falcon",
-    "alabama",
-    "irock",
-    "dorothy",
-    "beverly",
-    "avril",
-    "hanson",
-    "redhead",
-    "germany",
-    "regine",
-    "marijuana",
-    "121314",
-    "rakista",
-    "jenna",
-    "escorpion",
-    "weed420",
-    "oscar1",
-    "love15",
-    "hihihi",
-    "amazing",
-    "nevaeh",
-    "marimar",
-    "jamie1",
-    "DANIEL",
-    "mypassword",
-    "philips",
-    "johana",
-    "ilove",
-    "hitman",
-    "trandafir",
-    "shannon1",
-    "myfamily",
-    "monalisa",
-    "bonjovi",
-    "xander",
-    "scooby1",
-    "robinson",
-    "church",
-    "wonderful",
-    "potpot",
-    "lucas",
-    "password!",
-    "zoey101",
-    "qwerty123",
-    "georgina",
-    "bigred",
-    "tonton",
-    "telefon",
-    "stuart",
-    "pavilion",
-    "chivas1",
-    "jenifer",
-    "jaime",
-    "dance1",
-    "aishiteru",
-    "stardust",
-    "grapes",
-    "fatcat",
-    "angel13",
-    "milton",
-    "bowwow1",
-    "fofinha",
-    "eddie",
-    "tinker1",
-    "doctor",
-    "abercrombie",
-    "pink12",
-    "georgiana",
-    "conner",
-    "astig",
-    "system",
-    "candyfloss",
-    "alondra",
-    "winniethepooh",
-    "love143",
-    "g-unit",
-    "iloveme2",
-    "gizmo",
-    "donnie",
-    "brittany1",
-    "bamboo",
-    "katie1",
-    "devils",
-    "cathy",
-    "bobby1",
-    "benson",
-    "a1b2c3",
-    "tattoo",
-    "skipper",
-    "eleven",
-    "cinnamon",
-    "bernie",
-    "psycho",
-    "mypics",
-    "mobile",
-    "langga",
-    "iloveryan",
-    "falcons",
-    "cowboys1",
-    "alyssa1",
-    "emilia",
-    "050505",
-    "spoiled",
-    "antony",
-    "allen",
-    "respect",
-    "princess2",
-    "angeleyes",
-    "american",
-    "nemesis",
-    "nathalie",
-    "famous",
-    "cedric",
-    "wolverine",
-    "snoopy1",
-    "nelly",
-    "madden",
-    "13579",
-    "shawty",
-    "payton",
-    "lifesucks",
-    "broncos",
-    "amalia",
-    "alina",
-    "monkey2",
-    "mikaela",
-    "emogirl",
-    "963852741",
-    "poncho",
-    "gibson",
-    "denisse",
-    "303030",
-    "292929",
-    "sheryl",
-    "super",
-    "sonia",
-    "pinky1",
-    "chantelle",
-    "puertorico",
-    "meandyou",
-    "marcel",
-    "krissy",
-    "kittie",
-    "sprite",
-    "manman",
-    "kimmie",
-    "hotboy",
-    "emerson",
-    "elamor",
-    "capricornio",
-    "black1",
-    "misty",
-    "lillian",
-    "fuckoff1",
-    "chelsey",
-    "suzuki",
-    "octubre",
-    "magdalena",
-    "bratz",
-    "tomtom",
-    "noodle",
-    "carebears",
-    "flamingo",
-    "deborah",
-    "tiffany1",
-    "renee",
-    "noviembre",
-    "420420",
-    "kenken",
-    "gabriella",
-    "dietcoke",
-    "behappy",
-    "1123581321",
-    "marianne",
-    "brianna1",
-    "abcdefgh",
-    "skyblue",
-    "irene",
-    "bombon",
-    "anjing",
-    "raven",
-    "donna",
-    "wedding",
-    "sexy13",
-    "chikita",
-    "asawako",
-    "wordpass",
-    "naynay",
-    "mariajose",
-    "lionking",
-    "devil",
-    "xiomara",
-    "soccer12",
-    "music1",
-    "moocow",
-    "maritza",
-    "samsam",
-    "ivonne",
-    "rodolfo",
-    "lovable",
-    "ilovepink",
-    "hannahmontana",
-    "girlfriend",
-    "soccer10",
-    "skateboard",
-    "personal",
-    "newport",
-    "RockYou",
-    "perrito",
-    "nikki1",
-    "mitch",
-    "marine",
-    "jesse1",
-    "ilovemom",
-    "pillow",
-    "duckie",
-    "brasil",
-    "window",
-    "rockers",
-    "jamjam",
-    "conejita",
-    "mylove1",
-    "lopez",
-    "josue",
-    "BABYGIRL",
-    "sexyboy",
-    "makaveli",
-    "ilovejoe",
-    "marcia",
-    "007007",
-    "southpark",
-    "sherwin",
-    "lestat",
-    "desire",
-    "445566",
-    "pencil",
-    "denden",
-    "scooter1",
-    "brazil",
-    "boobies",
-    "yankees1",
-    "scarlet",
-    "powers",
-    "killua",
-    "leandro",
-    "burbuja",
-    "bonjour",
-    "armani",
-    "poop",
-    "nadia",
-    "michigan",
-    "astrid",
-    "billybob",
-    "theman",
-    "sunday",
-    "loquita",
-    "kristy",
-    "believe",
-    "missyou",
-    "english",
-    "timmy",
-    "simpson",
-    "lennon",
-    "frogger",
-    "dingdong",
-    "ssssss",
-    "philly",
-    "pepito",
-    "naruto1",
-    "lilman",
-    "chelseafc",
-    "blingbling",
-    "batman1",
-    "135790",
-    "sparkles",
-    "honeys",
-    "graham",
-    "flaquita",
-    "dalejr",
-    "smackdown",
-    "iforgot",
-    "barbie1",
-    "wisdom",
-    "queenie",
-    "priscilla",
-    "jenny1",
-    "dakota1",
-    "crystal1",
-    "camaro",
-    "lilbit",
-    "jimenez",
-    "babygirl2",
-    "danielita",
-    "bobbie",
-    "789789",
-    "sexygurl",
-    "jjjjjj",
-    "george1",
-    "brayden",
-    "boyfriend",
-    "arizona",
-    "savage",
-    "noelle",
-    "longhorns",
-    "leonel",
-    "celtic1888",
-    "anastasia",
-    "subaru",
-    "lol123",
-    "kellie",
-    "coldplay",
-    "terrell",
-    "playstation",
-    "kkkkkk",
-    "delacruz",
-    "charity",
-    "borboleta",
-    "johnpaul",
-    "jimena",
-    "gwapa",
-    "estrellas",
-    "donovan",
-    "muhammad",
-    "libertad",
-    "jewels",
-    "corina",
-    "carmelo",
-    "little1",
-    "johnnydepp",
-    "gordita",
-    "diciembre",
-    "racing",
-    "dylan1",
-    "krista",
-    "eileen",
-    "778899",
-    "soccer13",
-    "peachy",
-    "princess12",
-    "lamejor",
-    "898989",
-    "565656",
-    "surfing",
-    "ladybug1",
-    "johnny1",
-    "jeanette",
-    "campbell",
-    "amizade",
-    "sucker",
-    "sexsex",
-    "senior",
-    "pinklady",
-    "losers",
-    "dickhead",
-    "classof08",
-    "bluesky",
-    "030303",
-    "zzzzzz",
-    "zidane",
-    "sophie1",
-    "player1",
-    "gangsta1",
-    "aol123",
-    "soccer7",
-    "rammstein",
-    "louie",
-    "cotton",
-    "althea",
-    "shamrock",
-    "pandora",
-    "netball",
-    "mittens",
-    "knights",
-    "kimberley",
-    "bigdog",
-    "sexy101",
-    "romina",
-    "beatles",
-    "baby",
-    "1478963",
-    "060606",
-    "sexyback",
-    "paulo",
-    "jeffhardy",
-    "secret1",
-    "panasonic",
-    "enigma",
-    "cristy",
-    "thankyou",
-    "teodio",
-    "porter",
-    "maria1",
-    "biscuit",
-    "becky",
-    "bautista",
-    "allan",
-    "spring",
-    "malcolm",
-    "francesca",
-    "canela",
-    "victory",
-    "toshiba",
-    "poppy",
-    "mariam",
-    "love16",
-    "latoya",
-    "killme",
-    "bernardo",
-    "renata",
-    "password7",
-    "laguna",
-    "nofear",
-    "megan1",
-    "jimmy1",
-    "jared",
-    "12344321",
-    "twister",
-    "rosemary",
-    "rooster",
-    "retard",
-    "daniella",
-    "cosmin",
-    "alexandria",
-    "sweetie1",
-    "soccer11",
-    "pookie1",
-    "joejoe",
-    "guatemala",
-    "corey",
-    "celular",
-    "scarlett",
-    "hassan",
-    "gators",
-    "cloud9",
-    "vicky",
-    "rosie",
-    "jakarta",
-    "gillian",
-    "flakita",
-    "darlene",
-    "tabitha",
-    "russel",
-    "nibbles",
-    "mohamed",
-    "margarida",
-    "lemons",
-    "johnjohn",
-    "smile1",
-    "manzana",
-    "apollo",
-    "angeline",
-    "lilly",
-    "dawson",
-    "manolo",
-    "jeffery",
-    "brooklyn1",
-    "bloodz",
-    "tarzan",
-    "halloween",
-    "dandan",
-    "sugar1",
-    "lacrosse",
-    "kiara",
-    "keith",
-    "star123",
-    "senior06",
-    "mamacita",
-    "jazzy",
-    "classof09",
-    "belle",
-    "patty",
-    "mallory",
-    "lottie",
-    "lizeth",
-    "hollister1",
-    "carson",
-    "bluemoon",
-    "shibby",
-    "libra",
-    "jimbob",
-    "declan",
-    "5555555",
-    "traviesa",
-    "toffee",
-    "skylar",
-    "charly",
-    "avatar",
-    "alice",
-    "987456",
-    "titans",
-    "suckit",
-    "shelley",
-    "rhiannon",
-    "hallo",
-    "daredevil",
-    "christian1",
-    "alvarez",
-    "spunky",
-    "ilovejohn",
-    "brian1",
-    "kickass",
-    "ilovenick",
-    "blood",
-    "trigger",
-    "pinkpink",
-    "maddog",
-    "9999999",
-    "44444",
-    "stupid1",
-    "patrice",
-    "lilfizz",
-    "indian",
-    "cartoon",
-    "saturn",
-    "magic",
-    "hammer",
-    "emopunk",
-    "eliana",
-    "daphne",
-    "carmela",
-    "666999",
-    "yandel",
-    "pippin",
-    "peyton",
-    "heartbreaker",
-    "rockstar1",
-    "loredana",
-    "ilovehim1",
-    "girlie",
-    "acmilan",
-    "shayne",
-    "redred",
-    "mamasita",
-    "love08",
-    "lilian",
-    "jehova",
-    "dragonball",
-    "avrillavigne",
-    "pokemon1",
-    "logan1",
-    "jester",
-    "ilovesam",
-    "valentino",
-    "married",
-    "joaquin",
-    "azerty",
-    "africa",
-    "tootie",
-    "nigga",
-    "kingdom",
-    "female",
-    "desmond",
-    "corvette",
-    "stefania",
-    "pepsi1",
-    "patricio",
-    "mivida",
-    "attitude",
-    "sadie1",
-    "nugget",
-    "my3kids",
-    "lanena",
-    "12121212",
-    "pandas",
-    "nicoleta",
-    "misty1",
-    "ethan",
-    "diane",
-    "tigers1",
-    "skate",
-    "lucia",
-    "angels1",
-    "159159",
-    "squirt",
-    "salazar",
-    "papamama",
-    "manila",
-    "glamorous",
-    "evolution",
-    "ellie",
-    "travis1",
-    "samurai",
-    "pinkpanther",
-    "mumdad",
-    "lavigne",
-    "bernadette",
-    "tommy1",
-    "sexy1",
-    "qwertyu",
-    "mexican",
-    "girlpower",
-    "septiembre",
-    "peanuts",
-    "florida1",
-    "eastenders",
-    "death",
-    "clarinet",
-    "bridget",
-    "456852",
-    "razvan",
-    "pasword",
-    "nellie",
-    "larissa",
-    "kimmy",
-    "gymnast",
-    "betty",
-    "prissy",
-    "outlaw",
-    "onlyme",
-    "nayeli",
-    "mygirl",
-    "kisses1",
-    "kermit",
-    "indonesia",
-    "collin",
-    "angelic",
-    "love07",
-    "battle",
-    "backspace",
-    "33333",
-    "mattie",
-    "cheesecake",
-    "carmel",
-    "bhabes",
-    "bebito",
-    "skeeter",
-    "hellomoto",
-    "shania",
-    "sandy1",
-    "nicholas1",
-    "hilaryduff",
-    "soldier",
-    "sadie",
-    "hinata",
-    "JESSICA",
-    "naomi",
-    "matias",
-    "jeanne",
-    "elliot",
-    "carolyn",
-    "puppy1",
-    "natalie1",
-    "happy123",
-    "faithful",
-    "catcat",
-    "pornstar",
-    "pizza",
-    "herman",
-    "faith1",
-    "simon",
-    "ilovealex",
-    "georgie",
-    "forest",
-    "candace",
-    "brooke1",
-    "victoria1",
-    "meagan",
-    "jamesbond",
-    "sylvester",
-    "shortie",
-    "iloveben",
-    "fuckers",
-    "dougie",
-    "chrisb",
-    "andrea1",
-    "siobhan",
-    "manchesterunited",
-    "charley",
-    "pollo",
-    "packers",
-    "mercury",
-    "j123456",
-    "miguelito",
-    "kristian",
-    "kawaii",
-    "alexander1",
-    "CARLOS",
-    "teddy1",
-    "sunny",
-    "keyboard",
-    "isaac",
-    "febrero",
-    "detroit",
-    "charmed1",
-    "renee1",
-    "rainbows",
-    "melina",
-    "medicina",
-    "pirate",
-    "love01",
-    "chemical",
-    "vikings",
-    "telephone",
-    "habibi",
-    "fucking",
-    "frosty",
-    "bumblebee",
-    "NICOLE",
-    "tamahome",
-    "goodies",
-    "258456",
-    "tigger2",
-    "rocio",
-    "joker",
-    "enamorada",
-    "brutus",
-    "babyangel",
-    "7894561230",
-    "1lover",
-    "thompson",
-    "thirteen",
-    "nenalinda",
-    "margie",
-    "giselle",
-    "cat123",
-    "amarillo",
-    "yadira",
-    "qwaszx",
-    "perros",
-    "jaypee",
-    "hacker",
-    "yahooo",
-    "soccer2",
-    "louise1",
-    "jericho",
-    "jackie1",
-    "domingo",
-    "derek",
-    "clarence",
-    "benjie",
-    "55555555",
-    "megaman",
-    "dallas1",
-    "daddyyankee",
-    "cutiepie1",
-    "adelina",
-    "westham",
-    "sexy14",
-    "randall",
-    "playmate",
-    "matematica",
-    "littleman",
-    "1234qwer",
-    "together",
-    "texas1",
-    "power",
-    "paolo",
-    "nestor",
-    "lemonade",
-    "blondie1",
-    "ANGEL",
-    "soccer9",
-    "skater1",
-    "secrets",
-    "morrison",
-    "alex123",
-    "wassup",
-    "mikey1",
-    "girlsrule",
-    "fucklove",
-    "bunny1",
-    "mamamia",
-    "lassie",
-    "i love you",
-    "gerardway",
-    "deftones",
-    "annabelle",
-    "monkey123",
-    "jessie1",
-    "firefly",
-    "bombom",
-    "renren",
-    "qweasd",
-    "mommy2",
-    "kaitlin",
-    "iloveyou3",
-    "clarissa",
-    "cassie1",
-    "bleach",
-    "perro",
-    "mybaby1",
-    "julieta",
-    "salome",
-    "rebecca1",
-    "malachi",
-    "lexmark",
-    "killers",
-    "gracia",
-    "captain",
-    "123456j",
-    "vicente",
-    "unknown",
-    "runner",
-    "osito",
-    "nicky",
-    "murray",
-    "floricienta",
-    "bastard",
-    "angelz",
-    "porkchop",
-    "popstar",
-    "paopao",
-    "oranges",
-    "lancer",
-    "flames",
-    "solomon",
-    "raluca",
-    "malaysia",
-    "lewis",
-    "fucker1",
-    "honda",
-    "forgot",
-    "darrell",
-    "blackrose",
-    "123456789a",
-    "pumas",
-    "kikay",
-    "janeth",
-    "irish",
-    "beanie",
-    "roseann",
-    "redskins",
-    "potato",
-    "maricar",
-    "extreme",
-    "casanova",
-    "vanessa1",
-    "stonecold",
-    "holland",
-    "tobias",
-    "teamobb",
-    "strong",
-    "sexy",
-    "reynaldo",
-    "mumanddad",
-    "lebron",
-    "iamthebest",
-    "flowerpower",
-    "carrot",
-    "anime",
-    "mommie",
-    "darryl",
-    "blue12",
-    "poochie",
-    "glenda",
-    "classof07",
-    "bobesponja",
-    "14789632",
-    "lance",
-    "josiah",
-    "dumbass",
-    "caitlyn",
-    "whiskers",
-    "watson",
-    "water",
-    "therese",
-    "monster1",
-    "forever21",
-    "crazygirl",
-    "china",
-    "567890",
-    "1princess",
-    "terry",
-    "pangga",
-    "klapaucius",
-    "gemma",
-    "froggie",
-    "felix",
-    "washington",
-    "reading",
-    "qqqqqq",
-    "pinkgirl",
-    "latino",
-    "blazer",
-    "babybaby",
-    "amylee",
-    "airforce",
-    "sooners",
-    "poopy",
-    "colton",
-    "blessing",
-    "angel01",
-    "summer06",
-    "spitfire",
-    "samara",
-    "pudding",
-    "penguins",
-    "cortez",
-    "mushroom",
-    "damaris",
-    "chipper",
-    "butterflies",
-    "babybear",
-    "susan",
-    "master1",
-    "love06",
-    "iamcool",
-    "goodbye",
-    "cherokee",
-    "araceli",
-    "wildcat",
-    "mustangs",
-    "love10",
-    "digger",
-    "abc1234",
-    "sabina",
-    "pazaway",
-    "gordito",
-    "gabriel1",
-    "digimon",
-    "central",
-    "roses",
-    "qwertyui",
-    "powerpuff",
-    "midnight1",
-    "1loveyou",
-    "richmond",
-    "oklahoma",
-    "janet",
-    "dalejr8",
-    "chaparra",
-    "angel7",
-    "simba",
-    "osiris",
-    "napoleon",
-    "freak",
-    "frankie1",
-    "dayday",
-    "753159",
-    "redbull",
-    "mychemicalromance",
-    "memories",
-    "lamont",
-    "laloca",
-    "junebug",
-    "guerrero",
-    "elvis",
-    "ASHLEY",
-    "spike",
-    "security",
-    "pepsi",
-    "lucifer",
-    "horses1",
-    "dangerous",
-    "adriano",
-    "ANTHONY",
-    "theking",
-    "sasha1",
-    "molina",
-    "milena",
-    "love1234",
-    "lesley",
-    "fanny",
-    "baby13",
-    "22222222",
-    "sterling",
-    "rachel1",
-    "mystery",
-    "m123456",
-    "locura",
-    "hillary",
-    "arsenal1",
-    "LOVELY",
-    "zombie",
-    "monse",
-    "jesus777",
-    "future",
-    "carpediem",
-    "bunnies",
-    "brebre",
-    "1122334455",
-    "werty",
-    "sheldon",
-    "robin",
-    "penny",
-    "monique1",
-    "marita",
-    "julissa",
-    "browneyes",
-    "youandme",
-    "warcraft",
-    "matilda",
-    "britt",
-    "babygirl12",
-    "stoner",
-    "sally",
-    "rebekah",
-    "kelly1",
-    "kaiser",
-    "jessy",
-    "hanna",
-    "braves",
-    "sharks",
-    "magnolia",
-    "infinity",
-    "humberto",
-    "calculator",
-    "becca",
-    "mikayla",
-    "maniez",
-    "magodeoz",
-    "koolaid",
-    "chris123",
-    "tottenham",
-    "reyes",
-    "olimpia",
-    "dream",
-    "candycane",
-    "billy1",
-    "snowwhite",
-    "johnathan",
-    "goodluck",
-    "eduard",
-    "chuckie",
-    "catwoman",
-    "banana1",
-    "angelique",
-    "rangersfc",
-    "ilovejames",
-    "explorer",
-    "chester1",
-    "789123",
-    "5555555555",
-    "whatsup",
-    "starbucks",
-    "maddison",
-    "limegreen",
-    "clinton",
-    "chickens",
-    "adrienne",
-    "aaliyah1",
-    "2sexy4u",
-    "123456m",
-    "terrance",
-    "sandiego",
-    "metal",
-    "lightning",
-    "lesbian",
-    "iloveadam",
-    "heaven1",
-    "grecia",
-    "dolores",
-    "charles1",
-    "siemens",
-    "rogelio",
-    "redhot",
-    "poodle",
-    "lebron23",
-    "dollar",
-    "chino",
-    "aguilar",
-    "66666",
-    "te amo",
-    "rebels",
-    "lincoln",
-    "jhonatan",
-    "fireman",
-    "dragonballz",
-    "chavez",
-    "123457",
-    "slipknot1",
-    "shayshay",
-    "seven",
-    "rusty1",
-    "223344",
-    "mirela",
-    "claudio",
-    "casey1",
-    "yourmom1",
-    "micaela",
-    "mariano",
-    "imissu",
-    "yazmin",
-    "whiskey",
-    "singing",
-    "loveko",
-    "bluebird",
-    "aberdeen",
-    "violin",
-    "marcus1",
-    "kristel",
-    "jojojo",
-    "gareth",
-    "fallen",
-    "verito",
-    "vargas",
-    "sleepy",
-    "school1",
-    "hooters",
-    "flipper",
-    "deathnote",
-    "chobits",
-    "carajo",
-    "scottie",
-    "misterio",
-    "holahola",
-    "edward1",
-    "danielito",
-    "cookies1",
-    "bentley",
-    "yahoo1",
-    "xxxxx",
-    "wanted",
-    "shelby1",
-    "love24",
-    "hamish",
-    "evelin",
-    "MICHAEL",
-    "wanker",
-    "tantan",
-    "shiloh",
-    "kissme1",
-    "hotrod",
-    "fatass",
-    "eleanor",
-    "chase",
-    "rosemarie",
-    "porsche",
-    "monkey12",
-    "mahalcoh",
-    "haley",
-    "celtic1",
-    "99999999",
-    "shayla",
-    "selene",
-    "rosado",
-    "jazzy1",
-    "charming",
-    "363636",
-    "******",
-    "starburst",
-    "sam123",
-    "rakizta",
-    "password.",
-    "jeter2",
-    "alucard",
-    "147896325",
-    "rootbeer",
-    "mafalda",
-    "livestrong",
-    "jayden1",
-    "gunners",
-    "warning",
-    "viridiana",
-    "jesica",
-    "bowling",
-    "blake",
-    "sylvia",
-    "seanpaul",
-    "lovehate",
-    "lololo",
-    "haters",
-    "fuckyou!",
-    "cashmoney",
-    "blablabla",
-    "98765",
-    "tyson",
-    "nevermind",
-    "juancho",
-    "ulises",
-    "sinead",
-    "popcorn1",
-    "jaycee",
-    "gatinha",
-    "friendly",
-    "digital",
-    "denise1",
-    "antonio1",
-    "zachary1",
-    "password5",
-    "larisa",
-    "josefina",
-    "jomblo",
-    "boobie",
-    "analyn",
-    "040404",
-    "tacobell",
-    "ilovesex",
-    "courtney1",
-    "agustin",
-    "webster",
-    "pussy1",
-    "pucca",
-    "penis",
-    "button",
-    "billiejoe",
-    "bandit1",
-    "56789",
-    "123456789123456",
-    "turner",
-    "sexychick",
-    "queens",
-    "iminlove",
-    "greeneyes",
-    "cinthia",
-    "usa123",
-    "smarties",
-    "holly1",
-    "cartman",
-    "asdf1234",
-    "watever",
-    "oliver1",
-    "mildred",
-    "emiliano",
-    "camera",
-    "minnie1",
-    "maroon5",
-    "burton",
-    "boycrazy",
-    "boomboom",
-    "philippines",
-    "memory",
-    "kenny1",
-    "gameboy",
-    "funny",
-    "elisha",
-    "Jessica",
-    "science",
-    "laura1",
-    "dannyboy",
-    "ciara",
-    "blackcat",
-    "909090",
-    "soccer3",
-    "iloveyou7",
-    "angel11",
-    "786786",
-    "umbrella",
-    "sk8ter",
-    "quincy",
-    "hottie101",
-    "hellboy",
-    "323232",
-    "212224",
-    "rocks",
-    "iloveyou.",
-    "gracie1",
-    "fercho",
-    "elliott",
-    "casper1",
-    "teadoro",
-    "ronron",
-    "riley",
-    "mylene",
-    "jingjing",
-    "badminton",
-    "yenyen",
-    "pacman",
-    "monkey7",
-    "krishna",
-    "juggalo",
-    "blonde1",
-    "baller1",
-    "369369",
-    "soccer4",
-    "sexybeast",
-    "samira",
-    "love18",
-    "johncena1",
-    "amoremio",
-    "wayne",
-    "vagina",
-    "stargirl",
-    "serendipity",
-    "prayer",
-    "patriots",
-    "orlando1",
-    "heartbroken",
-    "dookie",
-    "clouds",
-    "chiqui",
-    "1234abcd",
-    "wonder",
-    "versace",
-    "rukawa",
-    "rolltide",
-    "rebelde1",
-    "flowers1",
-    "angeli",
-    "123698745",
-    "timberlake",
-    "sampson",
-    "puppys",
-    "lipstick",
-    "cougars",
-    "ILOVEU",
-    "tiesto",
-    "raider",
-    "myspace2",
-    "khulet",
-    "holla",
-    "eliza",
-    "survivor",
-    "secreto",
-    "lynette",
-    "jordyn",
-    "helen",
-    "bookie",
-    "321321",
-    "pelota",
-    "inuyasha1",
-    "foster",
-    "compaq1",
-    "bradpitt",
-    "someone",
-    "rupert",
-    "rugrats",
-    "reebok",
-    "kobe24",
-    "hurley",
-    "fluffy1",
-    "cupcake1",
-    "chucho",
-    "787878",
-    "yummy",
-    "trenton",
-    "teamobebe",
-    "shawna",
-    "meredith",
-    "love09",
-    "estefany",
-    "airforce1",
-    "TEAMO",
-    "147147",
-    "pleasure",
-    "mountain",
-    "lakers1",
-    "girls",
-    "bob123",
-    "babypink",
-    "12369874",
-    "tiago",
-    "shanna",
-    "monroe",
-    "leilani",
-    "larry",
-    "kontol",
-    "hogwarts",
-    "asakapa",
-    "neopets",
-    "meowmeow",
-    "loveit",
-    "kipper",
-    "ilovedan",
-    "313131",
-    "trunks",
-    "playboy123",
-    "myhoney",
-    "justdoit",
-    "gutierrez",
-    "elijah1",
-    "beaver",
-    "my2kids",
-    "mendez",
-    "maximo",
-    "loveforever",
-    "kitten1",
-    "jonalyn",
-    "gundam",
-    "fotos",
-    "biggie",
-    "aimee",
-    "yasmine",
-    "walmart",
-    "treasure",
-    "thesims",
-    "shawn1",
-    "rusty",
-    "pablito",
-    "marquis",
-    "freestyle",
-    "candygirl",
-    "blackjack",
-    "amsterdam",
-    "alone",
-    "Password1",
-    "wateva",
-    "villanueva",
-    "placebo",
-    "martin1",
-    "lilangel",
-    "kingston",
-    "killa",
-    "dustin1",
-    "angelface",
-    "andrey",
-    "amote",
-    "stefanie",
-    "herbert",
-    "felicity",
-    "dracula",
-    "cougar",
-    "cheerleading",
-    "aussie",
-    "adonis",
-    "JORDAN",
-    "1234560",
-    "simba1",
-    "redrum",
-    "griffin",
-    "fisher",
-    "dddddd",
-    "blades",
-    "soccer14",
-    "skinny",
-    "prince1",
-    "nolove",
-    "indiana",
-    "gateway1",
-    "collins",
-    "chantal",
-    "bbbbbb",
-    "superstar1",
-    "maryjoy",
-    "marihuana",
-    "engineer",
-    "dulcemaria",
-    "boricua1",
-    "patience",
-    "ilovedavid",
-    "honda1",
-    "1314520",
-    "01234",
-    "webcam",
-    "spikey",
-    "solange",
-    "romeo1",
-    "mister",
-    "highschool",
-    "gonzalo",
-    "emelec",
-    "brandy1",
-    "andreas",
-    "aliyah",
-    "25252525",
-    "123456k",
-    "roscoe",
-    "roger",
-    "princess13",
-    "penny1",
-    "pa55word",
-    "juanjose",
-    "cherish",
-    "789654",
-    "sweetiepie",
-    "summer07",
-    "snoopdogg",
-    "snickers1",
-    "raphael",
-    "panama",
-    "mummy",
-    "maryrose",
-    "jumong",
-    "imcute",
-    "fresa",
-    "energy",
-    "bacardi",
-    "yumyum",
-    "underground",
-    "shane1",
-    "olivia1",
-    "navarro",
-    "brodie",
-    "bribri",
-    "anabel",
-    "12qwaszx",
-    "sexy11",
-    "pppppp",
-    "party",
-    "mario1",
-    "juicy",
-    "corazones",
-    "smarty",
-    "selina",
-    "rebel",
-    "ferreira",
-    "bitch123",
-    "tomboy",
-    "sweetlove",
-    "skittles1",
-    "sirena",
-    "sexy15",
-    "jhonny",
-    "freeman",
-    "elvira",
-    "dieguito",
-    "devin",
-    "turtle1",
-    "sexbomb",
-    "pink11",
-    "oswaldo",
-    "morangos",
-    "lavinia",
-    "carlita",
-    "adrian1",
-    "619619",
-    "woaini",
-    "paintball",
-    "love4u",
-    "lilone",
-    "kaycee",
-    "ethan1",
-    "beauty1",
-    "angelgirl",
-    "alegria",
-    "vladimir",
-    "tulips",
-    "pebbles1",
-    "mason",
-    "kathmandu",
-    "jonathon",
-    "johndeere",
-    "harry1",
-    "gwapo",
-    "grandma1",
-    "blueangel",
-    "ANDREA",
-    "7895123",
-    "654123",
-    "19871987",
-    "waters",
-    "vampires",
-    "pink13",
-    "myheart",
-    "myboys",
-    "lovegod",
-    "herrera",
-    "gianna",
-    "claudiu",
-    "business",
-    "angela1",
-    "venezuela",
-    "twins2",
-    "rovers",
-    "puppydog",
-    "memphis",
-    "jackass1",
-    "imsexy",
-    "apples1",
-    "aerosmith",
-    "trinity1",
-    "superpets",
-    "sunrise",
-    "stephen1",
-    "rashad",
-    "pringles",
-    "poppop",
-    "lillie",
-    "leeann",
-    "ilove?",
-    "icecream1",
-    "doggy",
-    "cheekymonkey",
-    "candle",
-    "because",
-    "alinutza",
-    "weezer",
-    "raven1",
-    "raerae",
-    "pereira",
-    "pendejo",
-    "mygirls",
-    "muffin1",
-    "love17",
-    "franky",
-    "dog123",
-    "caleb",
-    "angel3",
-    "19891989",
-    "thanks",
-    "spiderman1",
-    "shitface",
-    "scott1",
-    "march",
-    "honesty",
-    "education",
-    "chinito",
-    "chantel",
-    "butter1",
-    "benji",
-    "artist",
-    "redman",
-    "misael",
-    "minerva",
-    "karolina",
-    "joaninha",
-    "hunnie",
-    "giraffe",
-    "angel5",
-    "vinnie",
-    "tangina",
-    "snoopdog",
-    "senior07",
-    "pumpkin1",
-    "my2girls",
-    "miguelangel",
-    "makeup",
-    "looney",
-    "francine",
-    "ernest",
-    "beaner",
-    "badboys",
-    "andromeda",
-    "amethyst",
-    "queen1",
-    "miley",
-    "isabela",
-    "homero",
-    "gwapoko",
-    "guitar1",
-    "goodboy",
-    "general",
-    "bloody",
-    "sunny1",
-    "street",
-    "stephy",
-    "singapore",
-    "pisica",
-    "lashay",
-    "diogo",
-    "darnell",
-    "aguila",
-    "321654987",
-    "wazzup",
-    "snakes",
-    "poiuyt",
-    "markie",
-    "kamote",
-    "imcool",
-    "federico",
-    "angel22",
-    "tyson1",
-    "sweetangel",
-    "summer08",
-    "pompom",
-    "papichulo",
-    "ironmaiden",
-    "eighteen",
-    "bishop",
-    "antonella",
-    "yuliana",
-    "victor1",
-    "sexual",
-    "panda1",
-    "jesus123",
-    "ivette",
-    "happydays",
-    "deadman",
-    "craig",
-    "brokenheart",
-    "terrence",
-    "q1w2e3",
-    "max123",
-    "francia",
-    "account",
-    "villevalo",
-    "striker",
-    "smiley1",
-    "silvana",
-    "peluchin",
-    "muppet",
-    "monita",
-    "firebird",
-    "chippy",
-    "boxing",
-    "adolfo",
-    "soccer5",
-    "reggae",
-    "negrito",
-    "muerte",
-    "martini",
-    "jarule",
-    "ilovemymom",
-    "godzilla",
-    "fucku1",
-    "dante",
-    "babydoll1",
-    "juanpablo",
-    "jaylen",
-    "jasper1",
-    "ionutz",
-    "hotmail.com",
-    "honeykoh",
-    "homies",
-    "gretchen",
-    "goodcharlotte",
-    "glamour",
-    "bounce",
-    "bigdick",
-    "stefany",
-    "soylamejor",
-    "iubita",
-    "19921992",
-    "vince",
-    "texas",
-    "password11",
-    "nelly1",
-    "metoyou",
-    "buddha",
-    "april1",
-    "alesana",
-    "trooper",
-    "timothy1",
-    "smirnoff",
-    "smile4me",
-    "sherman",
-    "glenn",
-    "gabby1",
-    "family5",
-    "eddie1",
-    "dodgers",
-    "cheska",
-    "bradley1",
-    "annmarie",
-    "1a2b3c",
-    "zxcvb",
-    "slide",
-    "printer",
-    "laurence",
-    "ilovemyfamily",
-    "ilovejake",
-    "fighter",
-    "classof06",
-    "class09",
-    "breezy",
-    "MICHELLE",
-    "yahoo.com",
-    "tropical",
-    "peaceout",
-    "nigga1",
-    "movies",
-    "mouse",
-    "marquez",
-    "karito",
-    "hendrix",
-    "floppy",
-    "dodong",
-    "calderon",
-    "astonvilla",
-    "asdfasdf",
-    "angel14",
-    "sexygirl1",
-    "malagu",
-    "leigh",
-    "julieann",
-    "dipset1",
-    "chico",
-    "caballo",
-    "ashlyn",
-    "stephanie1",
-    "sexyass",
-    "princess7",
-    "my2boys",
-    "lizzy",
-    "ionela",
-    "bratz1",
-    "Michael",
-    "zxcvbnm,./",
-    "tripleh",
-    "tammy",
-    "ricky1",
-    "qazxsw",
-    "marianita",
-    "keegan",
-    "eagles1",
-    "bumbum",
-    "winxclub",
-    "nguyen",
-    "luciana",
-    "liezel",
-    "kambal",
-    "hearty",
-    "contraseña",
-    "cinthya",
-    "changeme",
-    "zamora",
-    "temple",
-    "tanya",
-    "peanutbutter",
-    "mafer",
-    "ichigo",
-    "davids",
-    "christie",
-    "buddie",
-    "tyrell",
-    "reagan",
-    "mydear",
-    "leonor",
-    "garden",
-    "cornelia",
-    "cherie",
-    "savannah1",
-    "photo",
-    "milkyway",
-    "idunno",
-    "chunky",
-    "benny",
-    "benito",
-    "allie",
-    "addison",
-    "revenge",
-    "kentucky",
-    "kangaroo",
-    "jesussaves",
-    "jessa",
-    "finalfantasy",
-    "delicious",
-    "buffy",
-    "badman",
-    "angel101",
-    "adrianna",
-    "sporty",
-    "password13",
-    "pamelita",
-    "naenae",
-    "llllll",
-    "jologs",
-    "estela",
-    "bigbird",
-    "auburn",
-    "arianne",
-    "1jesus",
-    "stargate",
-    "spartan",
-    "savanna",
-    "riley1",
-    "ramones",
-    "playboi",
-    "pink22",
-    "mason1",
-    "maddie1",
-    "lilred",
-    "itachi",
-    "herbie",
-    "fiesta",
-    "boobear",
-    "babycoh",
-    "xoxoxo",
-    "tarheels",
-    "silent",
-    "luisteamo",
-    "ironman",
-    "ilovemark",
-    "friends4ever",
-    "escape",
-    "daughter",
-    "alissa",
-    "aguilas",
-    "369852",
-    "trouble1",
-    "tribal",
-    "thunder1",
-    "lovingyou",
-    "jhenny",
-    "baby14",
-    "123",
-    "youtube",
-    "silver1",
-    "rosie1",
-    "ritinha",
-    "jaiden",
-    "haylee",
-    "crazy4u",
-    "chinchin",
-    "candyman",
-    "burger",
-    "backstreet",
-    "19861986",
-    "woohoo",
-    "timmy1",
-    "summertime",
-    "shasta",
-    "roxygirl",
-    "rosales",
-    "narnia",
-    "monkey3",
-    "mileycyrus",
-    "maxmax",
-    "journey",
-    "green123",
-    "delfines",
-    "bhabie",
-    "badger",
-    "alex",
-    "543210",
-    "sparky1",
-    "salinas",
-    "nichole1",
-    "neneng",
-    "walalang",
-    "soccer15",
-    "sixers",
-    "oliveira",
-    "maradona",
-    "jessika",
-    "irving",
-    "ilovetom",
-    "emokid",
-    "bobbob",
-    "anakin",
-    "PASSWORD1",
-    "sparrow",
-    "pokpok",
-    "musical",
-    "jackson5",
-    "ilovejason",
-    "grace1",
-    "fcporto",
-    "damion",
-    "alanna",
-    "whisper",
-    "patches1",
-    "mayang",
-    "livelife",
-    "lilmama1",
-    "james123",
-    "ioana",
-    "gordon24",
-    "godfather",
-    "prinsesa",
-    "principe",
-    "lucian",
-    "kassandra",
-    "jesusc",
-    "cielo",
-    "brown",
-    "broken1",
-    "ariane",
-    "angel21",
-    "23456",
-    "thatshot",
-    "souljaboy",
-    "ramon",
-    "punker",
-    "oldnavy",
-    "mom123",
-    "magaly",
-    "froggy1",
-    "french",
-    "fourteen",
-    "coolman",
-    "cheers",
-    "bennett",
-    "111213",
-    "smooth",
-    "ranita",
-    "princess3",
-    "momanddad",
-    "madmax",
-    "innocent",
-    "horse",
-    "fireball",
-    "elmejor",
-    "coolness",
-    "chihuahua",
-    "cabbage",
-    "voodoo",
-    "soccer8",
-    "sebas",
-    "pampam",
-    "lorenz",
-    "fabio",
-    "dublin",
-    "cancel",
-    "bigman",
-    "samanta",
-    "oooooo",
-    "newzealand",
-    "mozart",
-    "jungle",
-    "immortal",
-    "dominick",
-    "doglover",
-    "cristiana",
-    "candies",
-    "bellota",
-    "505050",
-    "123789456",
-    "zander",
-    "tierra",
-    "rhonda",
-    "rastaman",
-    "poppy1",
-    "pancakes",
-    "monkeybutt",
-    "jacky",
-    "itzel",
-    "hongkong",
-    "harlem",
-    "dwight",
-    "denzel",
-    "cierra",
-    "bigbrother",
-    "barbiegirl",
-    "123987",
-    "soccer6",
-    "salvation",
-    "polaris",
-    "pancake",
-    "marygrace",
-    "lorenita",
-    "karencita",
-    "james23",
-    "hawaiian",
-    "gunit",
-    "geronimo",
-    "babygirl13",
-    "twinkie",
-    "running",
-    "pegasus",
-    "kittys",
-    "katty",
-    "iulian",
-    "grumpy",
-    "debora",
-    "colombia1",
-    "chevelle",
-    "chase1",
-    "booger1",
-    "beloved",
-    "69696969",
-    "spike1",
-    "ralph",
-    "presario",
-    "gotohell",
-    "emachines",
-    "disturbed",
-    "complicated",
-    "celina",
-    "buffalo",
-    "angell",
-    "pothead",
-    "popopo",
-    "morenita",
-    "midget",
-    "kristi",
-    "information",
-    "gisela",
-    "giants",
-    "estrada",
-    "cooper1",
-    "choclate",
-    "baby08",
-    "asdfghj",
-    "alessandro",
-    "afrodita",
-    "1angel",
-    "sharpay",
-    "perlita",
-    "paige1",
-    "kelley",
-    "hudson",
-    "hitler",
-    "history",
-    "graciela",
-    "devil666",
-    "cookiemonster",
-    "class07",
-    "benedict",
-    "258963",
-    "xavier1",
-    "star12",
-    "ravens",
-    "my3sons",
-    "iloveyou12",
-    "hotbabe",
-    "dodger",
-    "cowboy1",
-    "angel16",
-    "alicia1",
-    "abcd123",
-    "topher",
-    "sandrita",
-    "pineda",
-    "passwords",
-    "kamila",
-    "jesuss",
-    "jesuslovesme",
-    "imagine",
-    "gerrard8",
-    "dutchess",
-    "demons",
-    "baby1",
-    "awesome1",
-    "silvestre",
-    "poptart",
-    "melany",
-    "makulit",
-    "lionel",
-    "kahitano",
-    "jensen",
-    "ilovedogs",
-    "gwapoako",
-    "frederick",
-    "blue13",
-    "222333",
-    "19931993",
-    "waterfall",
-    "thinkpink",
-    "monopoly",
-    "monica1",
-    "molly123",
-    "miguel1",
-    "mcdonalds",
-    "jonny",
-    "hotshot",
-    "allyson",
-    "1blood",
-    "123456s",
-    "turtles",
-    "sexy01",
-    "pollita",
-    "people1",
-    "omarion1",
-    "groovychick",
-    "angel10",
-    "tortuga",
-    "topgun",
-    "teresita",
-    "siempre",
-    "piggy",
-    "madness",
-    "lollies",
-    "hibernian",
-    "edwards",
-    "coleman",
-    "chelsie",
-    "access",
-    "111111111",
-    "tennis1",
-    "solotu",
-    "sandoval",
-    "sailor",
-    "redroses",
-    "millwall",
-    "mifamilia",
-    "marielle",
-    "jelly",
-    "fatman",
-    "clueless",
-    "cabrera",
-    "whocares",
-    "rafaela",
-    "praise",
-    "pingpong",
-    "mommy3",
-    "mission",
-    "marta",
-    "jomar",
-    "hamtaro",
-    "fuckface",
-    "erwin",
-    "dudley",
-    "chris12",
-    "bighead",
-    "s123456",
-    "nicole2",
-    "mercado",
-    "mango",
-    "ilovekyle",
-    "godlovesme",
-    "garnet",
-    "brendon",
-    "star",
-    "sexybitch1",
-    "monitor",
-    "keekee",
-    "honeyz",
-    "elisa",
-    "doggies",
-    "cannabis",
-    "bertha",
-    "alvarado",
-    "Nicole",
-    "JASMINE",
-    "usher",
-    "pinkish",
-    "monyet",
-    "mandy1",
-    "linda1",
-    "ezekiel",
-    "sassygirl",
-    "richelle",
-    "mysterio",
-    "maricris",
-    "labebe",
-    "happyfeet",
-    "guitarra",
-    "family4",
-    "doodles",
-    "delgado",
-    "blue32",
-    "biology",
-    "000001",
-    "wordlife",
-    "timber",
-    "tanisha",
-    "steph1",
-    "sosexy",
-    "princess123",
-    "pizza1",
-    "pisicuta",
-    "pioneer",
-    "looser",
-    "friendsforever",
-    "estefani",
-    "create",
-    "aquino",
-    "19941994",
-    "wachtwoord",
-    "sahara",
-    "qweasdzxc",
-    "peter1",
-    "monkeys1",
-    "matty",
-    "littlebit",
-    "island",
-    "elefante",
-    "valery",
-    "sweety1",
-    "predator",
-    "pearl",
-    "nineteen",
-    "mybabies",
-    "monkey13",
-    "marsha",
-    "lovebug1",
-    "hailey1",
-    "glasgow",
-    "georgia1",
-    "elisabeth",
-    "budweiser",
-    "bubulina",
-    "belleza",
-    "asdfghjk",
-    "aquamarine",
-    "apple123",
-    "QWERTY",
-    "Daniel",
-    "q1w2e3r4",
-    "negro",
-    "momof3",
-    "maricela",
-    "mariah1",
-    "luckydog",
-    "latrice",
-    "hunnybunny",
-    "fabolous",
-    "chico1",
-    "bearbear",
-    "annie1",
-    "akatsuki",
-    "venice",
-    "original",
-    "meggie",
-    "manny",
-    "maisie",
-    "independent",
-    "carissa",
-    "bryan1",
-    "bitch69",
-    "baybee",
-    "alessandra",
-    "aleja",
-    "MONKEY",
-    "reaper",
-    "lalito",
-    "eloisa",
-    "dejavu",
-    "141516",
-    "trumpet",
-    "poiuytrewq",
-    "juanes",
-    "jones",
-    "gotmilk",
-    "fairies",
-    "corbin",
-    "cool123",
-    "carebear1",
-    "brooks",
-    "bessie",
-    "ash123",
-    "arielle",
-    "antoine",
-    "tornado",
-    "teetee",
-    "priscila",
-    "password4",
-    "ortega",
-    "nicolle",
-    "minniemouse",
-    "grandpa",
-    "flipflop",
-    "eragon",
-    "cutify",
-    "classic",
-    "candie",
-    "bernice",
-    "angelus",
-    "water1",
-    "ripcurl",
-    "ninja",
-    "nicole12",
-    "hilton",
-    "florentina",
-    "dorian",
-    "dionne",
-    "chicky",
-    "catdog1",
-    "carnell",
-    "bball",
-    "amorsito",
-    "LOVEME",
-    "AMERICA",
-    "19851985",
-    "tasha1",
-    "search",
-    "sandra1",
-    "palmtree",
-    "padilla",
-    "oakland",
-    "nature",
-    "nanita",
-    "mohammed",
-    "manager",
-    "lovemom",
-    "kimerald",
-    "hondacivic",
-    "gordo",
-    "gilberto",
-    "fantastic",
-    "devon",
-    "boobs",
-    "a1b2c3d4",
-    "987456321",
-    "shaun",
-    "lovelygirl",
-    "lilly1",
-    "hello12",
-    "escola",
-    "disneyland",
-    "deandre",
-    "connor1",
-    "willy",
-    "teddybear1",
-    "savior",
-    "ramiro",
-    "paixao",
-    "mmmmm",
-    "memyselfandi",
-    "hello!",
-    "guzman",
-    "gracey",
-    "corey1",
-    "burberry",
-    "buffy1",
-    "bubble1",
-    "birdie",
-    "baby07",
-    "TWEETY",
-    "456321",
-    "143444",
-    "wonderland",
-    "twiggy",
-    "telefono",
-    "subway",
-    "skywalker",
-    "putangina",
-    "packard",
-    "nickolas",
-    "nessa",
-    "killbill",
-    "jamielee",
-    "ignacio",
-    "happybunny",
-    "deejay",
-    "angel15",
-    "viking",
-    "snowball1",
-    "sexy16",
-    "luckyme",
-    "laurie",
-    "joselyn",
-    "fresas",
-    "courage",
-    "cheyanne",
-    "cancun",
-    "bunnyboo",
-    "bigmama",
-    "beach",
-    "baby11",
-    "tenten",
-    "snowboard",
-    "nookie",
-    "minina",
-    "madman",
-    "loraine",
-    "kameron",
-    "josie",
-    "duchess",
-    "chiquito",
-    "bitches1",
-    "babygal",
-    "alonzo",
-    "abegail",
-    "qwerty12",
-    "pandabear",
-    "newton",
-    "maxpower",
-    "mamama",
-    "lovehim",
-    "kassie",
-    "kamikaze",
-    "joselito",
-    "hustler",
-    "hellow",
-    "hello2",
-    "gavin",
-    "crackers",
-    "camelia",
-    "bhabycoh",
-    "JOSHUA",
-    "1q2w3e4r5t",
-    "muneca",
-    "mike",
-    "metalica",
-    "maiden",
-    "k123456",
-    "henderson",
-    "hellothere",
-    "giovanna",
-    "control",
-    "computador",
-    "baby01",
-    "sierra1",
-    "qwer1234",
-    "onepiece",
-    "nobody",
-    "lowrider",
-    "lissette",
-    "jamila",
-    "hotmail1",
-    "hoover",
-    "guinness",
-    "gerson",
-    "edgardo",
-    "chuchu",
-    "capslock",
-    "candys",
-    "bonnie1",
-    "1truelove",
-    "volume",
-    "velvet",
-    "sexymama1",
-    "ojitos",
-    "assassin",
-    "nikolas",
-    "lilkim",
-    "kansas",
-    "jazzie",
-    "ibanez",
-    "dottie",
-    "diamante",
-    "crybaby",
-    "angel07",
-    "anahi",
-    "alohomora",
-    "JUNIOR",
-    "volley",
-    "soccer16",
-    "smokie",
-    "notebook",
-    "louis",
-    "lamborghini",
-    "ibrahim",
-    "dixie1",
-    "coyote",
-    "class08",
-    "Liverpool",
-    "terminator",
-    "sparks",
-    "smitty",
-    "loveis",
-    "honest",
-    "deleon",
-    "ESTRELLA",
-    "twins",
-    "stephany",
-    "roberts",
-    "pizzas",
-    "monmon",
-    "jeffrey1",
-    "ilovecats",
-    "cutie123",
-    "cobain",
-    "chargers",
-    "blanco",
-    "bigfoot",
-    "beebee",
-    "balong",
-    "09876",
-    "young1",
-    "smile123",
-    "pacheco",
-    "lianne",
-    "leopard",
-    "julian1",
-    "escorpio",
-    "elvis1",
-    "einstein",
-    "carola",
-    "benjamin1",
-    "alfie",
-    "alex12",
-    "abigail1",
-    "Danielle",
-    "shadmoss",
-    "monserrat",
-    "monkey11",
-    "jesusislord",
-    "jerson",
-    "hardrock",
-    "catfish",
-    "braveheart",
-    "bluebell",
-    "blink",
-    "JUSTIN",
-    "JESUS",
-    "vernon",
-    "tanner1",
-    "rosalie",
-    "princess11",
-    "marvel",
-    "chicks",
-    "carlito",
-    "booty",
-    "blizzard",
-    "Michelle",
-    "BRANDON",
-    "784512",
-    "224466",
-    "tucker1",
-    "suicide",
-    "southern",
-    "sexyred",
-    "raymond1",
-    "rancid",
-    "queenbee",
-    "preety",
-    "micah",
-    "marques",
-    "lucille",
-    "loved1",
-    "june23",
-    "isaiah1",
-    "fraser",
-    "dolphins1",
-    "counter",
-    "bobcat",
-    "angel23",
-    "Anthony",
-    "university",
-    "tracy",
-    "noelia",
-    "lilromeo",
-    "jehovah",
-    "januari",
-    "italian",
-    "integra",
-    "horse1",
-    "heyheyhey",
-    "campos",
-    "bond007",
-    "alemania",
-    "77777777",
-    "......",
-    "xtreme",
-    "trojans",
-    "thelma",
-    "stronger",
-    "steve1",
-    "spongecola",
-    "splash",
-    "nightwish",
-    "lloyd",
-    "jerico",
-    "hotlips",
-    "chronic",
-    "bluestar",
-    "PRINCESA",
-    "6543210",
-    "336699",
-    "123456c",
-    "teamomiamor",
-    "semperfi",
-    "scream",
-    "sandro",
-    "miller1",
-    "labrador",
-    "kitty123",
-    "honeyq",
-    "ferret",
-    "chicago1",
-    "bigboy1",
-    "andreina",
-    "aaaaaaaa",
-    "venus",
-    "universal",
-    "tekieromucho",
-    "smithy",
-    "peace1",
-    "newman",
-    "lacrimosa",
-    "jonatan",
-    "jellybeans",
-    "iuliana",
-    "gorillaz",
-    "foxylady",
-    "darkside",
-    "angie1",
-    "321456",
-    "summer05",
-    "sabrina1",
-    "rosalinda",
-    "roderick",
-    "muslim",
-    "matilde",
-    "indigo",
-    "eugenia",
-    "casino",
-    "bball1",
-    "amerika",
-    "abbie",
-    "888999",
-    "1qazxsw2",
-    "1234554321",
-    "1029384756",
-    "wwwwww",
-    "wertyu",
-    "teodora",
-    "sweetypie",
-    "satan666",
-    "orlandobloom",
-    "natali",
-    "magnum",
-    "june28",
-    "eatshit",
-    "chandra",
-    "bling",
-    "andre1",
-    "456654",
-    "212224236",
-    "yomomma",
-    "thesims2",
-    "target",
-    "squall",
-    "soccer22",
-    "sagitarius",
-    "redsox1",
-    "qaz123",
-    "passions",
-    "kendrick",
-    "jamal",
-    "francisca",
-    "colt45",
-    "chikis",
-    "bonethugs",
-    "badboy1",
-    "525252",
-    "2222222",
-    "yessica",
-    "steelers1",
-    "sonny",
-    "polarbear",
-    "peekaboo",
-    "newpassword",
-    "metallica1",
-    "lokito",
-    "j12345",
-    "helloo",
-    "estrelinha",
-    "enter",
-    "chinese",
-    "chance1",
-    "anaconda",
-    "sexygal",
-    "sexkitten",
-    "nextel",
-    "missie",
-    "lights",
-    "koolkat",
-    "juliocesar",
-    "godsmack",
-    "element1",
-    "brianne",
-    "235689",
-    "19951995",
-    "spencer1",
-    "smith",
-    "rasta",
-    "onelove1",
-    "nathaly",
-    "jokers",
-    "janina",
-    "houston1",
-    "disney1",
-    "crips",
-    "butterfly2",
-    "2sweet",
-    "1babygirl",
-    "123456b",
-    "spotty",
-    "rockandroll",
-    "rabbits",
-    "michell",
-    "labtec",
-    "heroes",
-    "gogirl",
-    "ferguson",
-    "dirtbike",
-    "derick",
-    "brenda1",
-    "#1bitch",
-    "yankees2",
-    "sirenita",
-    "playboy69",
-    "murder",
-    "mongoose",
-    "love33",
-    "losangeles",
-    "leavemealone",
-    "iloveluke",
-    "denis",
-    "daryl",
-    "TIGGER",
-    "wiggles",
-    "sexysexy",
-    "rasmus",
-    "privacy",
-    "princess01",
-    "petewentz",
-    "ladybird",
-    "jayjay1",
-    "inlove1",
-    "holler",
-    "heavenly",
-    "gusanito",
-    "fernandes",
-    "ducky",
-    "camilita",
-    "MIGUEL",
-    "ventura",
-    "tomato",
-    "revolution",
-    "powder",
-    "pooper",
-    "piscis",
-    "lilsexy",
-    "isabella1",
-    "honeypie",
-    "felicidade",
-    "fantasia",
-    "cutie12",
-    "castle",
-    "asasas",
-    "FUCKYOU",
-    "19881988",
-    "123456t",
-    "tigger12",
-    "stewie",
-    "spears",
-    "sister1",
-    "sissy1",
-    "sausage",
-    "perla",
-    "louisa",
-    "joselin",
-    "edith",
-    "durango",
-    "cccccc",
-    "biteme1",
-    "annann",
-    "usher1",
-    "nigger1",
-    "magic1",
-    "jayvee",
-    "japanese",
-    "ineedyou",
-    "ihateyou1",
-    "harriet",
-    "consuelo",
-    "brennan",
-    "aracely",
-    "19911991",
-    "19841984",
-    "10203040",
-    "western",
-    "universidad",
-    "timberland",
-    "teamare",
-    "roselyn",
-    "quiksilver",
-    "newnew",
-    "milano",
-    "mateo",
-    "malena",
-    "henry14",
-    "dickies",
-    "blondy",
-    "aragorn",
-    "LIVERPOOL",
-    "Brandon",
-    "squirrel",
-    "soccer17",
-    "shaina",
-    "ronnel",
-    "lobster",
-    "class06",
-    "ciocolata",
-    "candy123",
-    "bouncer",
-    "bandung",
-    "angelie",
-    "ALEXIS",
-    "softball12",
-    "robbie1",
-    "redwings",
-    "pascual",
-    "latina1",
-    "jimmie",
-    "chiquis",
-    "cardinals",
-    "camara",
-    "bunbury",
-    "brujita",
-    "ayesha",
-    "valdez",
-    "super1",
-    "slamdunk",
-    "pasion",
-    "manuelito",
-    "limpbizkit",
-    "lickme",
-    "iloveyou4",
-    "ilovecody",
-    "ichliebedich",
-    "hellohello",
-    "destinee",
-    "chango",
-    "bambam1",
-    "ballerina",
-    "zxcvbnm1",
-    "swansea",
-    "smoking",
-    "ramos",
-    "marlen",
-    "june24",
-    "honeybun",
-    "fossil",
-    "emolove",
-    "david123",
-    "christina1",
-    "chevy1",
-    "blake1",
-    "april22",
-    "anarchy",
-    "369258",
-    "1a2b3c4d",
-    "speaker",
-    "raprap",
-    "marlyn",
-    "lingling",
-    "jarvis",
-    "heidi",
-    "hateyou",
-    "familyguy",
-    "dogdog",
-    "charm",
-    "123456l",
-    "tyrese",
-    "teamomuxo",
-    "sexy23",
-    "rockets",
-    "rapper",
-    "mudvayne",
-    "mostwanted",
-    "letmein1",
-    "landon1",
-    "karen1",
-    "june21",
-    "imthebest",
-    "flirty",
-    "dumnezeu",
-    "angel18",
-    "24681012",
-    "toledo",
-    "sugarplum",
-    "playgurl",
-    "planet",
-    "june13",
-    "iloveyou123",
-    "gidget",
-    "dominic1",
-    "cutie101",
-    "caliente",
-    "Ashley",
-    "2girls",
-    "1andonly",
-    "twisted",
-    "salman",
-    "sahabat",
-    "purple2",
-    "printesa",
-    "powell",
-    "mike123",
-    "matahari",
-    "martins",
-    "luisangel",
-    "lacey",
-    "kookie",
-    "kaulitz",
-    "gizzmo",
-    "fiona",
-    "efrain",
-    "dominicana",
-    "bambino",
-    "april21",
-    "antoinette",
-    "LOVERS",
-    "teamoluis",
-    "tazmanian",
-    "supernova",
-    "shaira",
-    "prettyboy",
-    "paris1",
-    "ositos",
-    "oicu812",
-    "nicole123",
-    "lalaland",
-    "jessi",
-    "harryp",
-    "andrade",
-    "ALEJANDRO",
-    "852963",
-    "zanessa",
-    "tupac",
-    "toronto",
-    "tigger123",
-    "shayna",
-    "piglet1",
-    "october1",
-    "octavio",
-    "monkey5",
-    "mercedes1",
-    "jordon",
-    "iloveu!",
-    "elsalvador",
-    "damnit",
-    "brothers",
-    "bangbang",
-    "avalon",
-    "SUPERMAN",
-    "MELISSA",
-    "Jordan",
-    "JONATHAN",
-    "19901990",
-    "tekieromuxo",
-    "starwars1",
-    "starfire",
-    "sexy07",
-    "sarahjane",
-    "samsung1",
-    "roxy123",
-    "randy1",
-    "prettyricky",
-    "popular",
-    "mirian",
-    "marky",
-    "manzanita",
-    "june12",
-    "jorgito",
-    "jodie",
-    "happyday",
-    "grease",
-    "gabita",
-    "florecita",
-    "cristine",
-    "celica",
-    "babygrl",
-    "allison1",
-    "VANESSA",
-    "Tigger",
-    "78945",
-    "wolfpack",
-    "sweetpea1",
-    "sammie1",
-    "poetry",
-    "paupau",
-    "love4life",
-    "lonnie",
-    "kelsey1",
-    "kambing",
-    "insert",
-    "hhhhhh",
-    "flaca",
-    "cheyenne1",
-    "angel08",
-    "Jennifer",
-    "ALEJANDRA",
-    "123asd",
-    "1234567a",
-    "tekelomuxo",
-    "snapple",
-    "rrrrrr",
-    "queenb",
-    "noemi",
-    "luvbug",
-    "love25",
-    "joker1",
-    "hottie12",
-    "flossy",
-    "etnies",
-    "dreaming",
-    "corrie",
-    "chemistry",
-    "catch22",
-    "bonito",
-    "babyblue1",
-    "apache",
-    "addicted",
-    "963258",
-    "44444444",
-    "punisher",
-    "luvyou",
-    "kristal",
-    "just4me",
-    "dynasty",
-    "dixie",
-    "azucena",
-    "april23",
-    "aiden",
-    "123456d",
-    "000000000",
-    "twelve",
-    "strawberries",
-    "ruthie",
-    "pass123",
-    "madagascar",
-    "love20",
-    "imelda",
-    "hockey1",
-    "giggle",
-    "fabricio",
-    "chaparrita",
-    "caleb1",
-    "bonkers",
-    "blueblue",
-    "alianzalima",
-    "2cool4u",
-    "25262526",
-    "01234567",
-    "sakuragi",
-    "reddog",
-    "picasso",
-    "phillips",
-    "manchas",
-    "maddy",
-    "luther",
-    "jerry1",
-    "gagoka",
-    "duarte",
-    "caballero",
-    "butthole",
-    "blue",
-    "JENNIFER",
-    "225588",
-    "wayne1",
-    "spanish",
-    "softball2",
-    "saprissa",
-    "password8",
-    "marie123",
-    "mancity",
-    "lolly",
-    "lildevil",
-    "launica",
-    "johan",
-    "hooker",
-    "hikaru",
-    "derek1",
-    "coolboy",
-    "cardenas",
-    "bubblez",
-    "badgurl",
-    "aaaaaaa",
-    "wrangler",
-    "willie1",
-    "trivium",
-    "seattle",
-    "radcliffe",
-    "pwincess",
-    "pochacco",
-    "pissoff",
-    "neenee",
-    "native",
-    "mummy1",
-    "michael2",
-    "markus",
-    "mamako",
-    "korean",
-    "kenneth1",
-    "keepout",
-    "coolkid",
-    "camron",
-    "bulldog1",
-    "bright",
-    "beetle",
-    "badbitch",
-    "allah",
-    "9999999999",
-    "4ever",
-    "159632",
-    "varsity",
-    "urmom",
-    "teamo1",
-    "soraya",
-    "puddin",
-    "porto",
-    "pink14",
-    "oktober",
-    "mommy123",
-    "june16",
-    "jimboy",
-    "jellyfish",
-    "ilovejustin",
-    "hottie123",
-    "chevrolet",
-    "breeze",
-    "boomer1",
-    "arcoiris",
-    "angel06",
-    "123465",
-    "100000",
-    "tuning",
-    "shantel",
-    "sassie",
-    "redneck1",
-    "nikkie",
-    "ilovetim",
-    "honeybunch",
-    "hernan",
-    "fairy",
-    "ellen",
-    "elephant1",
-    "bangsat",
-    "anggandako",
-    "369258147",
-    "woodstock",
-    "wishbone",
-    "westwood",
-    "tiger123",
-    "techno",
-    "special1",
-    "shandy",
-    "sexy22",
-    "roseanne",
-    "rayray1",
-    "princess!",
-    "nabila",
-    "lollipop1",
-    "kkkkk",
-    "june18",
-    "homework",
-    "gogogo",
-    "enrico",
-    "davinci",
-    "crimson",
-    "coolbeans",
-    "conejito",
-    "ciprian",
-    "chapis",
-    "baby06",
-    "atreyu",
-    "aloha",
-    "accord",
-    "a1234567",
-    "ROBERT",
-    "DANIELA",
-    "1monkey",
-    "143445254",
-    "trance",
-    "soccer21",
-    "scarface1",
-    "polopolo",
-    "pirata",
-    "paredes",
-    "morango",
-    "lightbulb",
-    "kelsie",
-    "ilovejay",
-    "fruity",
-    "evangelion",
-    "donomar",
-    "dindin",
-    "ciaran",
-    "buttercup1",
-    "butler",
-    "boston1",
-    "bhabykoh",
-    "baby23",
-    "baby15",
-    "343434",
-    "yahoomail",
-    "wednesday",
-    "peewee1",
-    "melani",
-    "maryland",
-    "marmar",
-    "kurama",
-    "kayden",
-    "katerina",
-    "katarina",
-    "jewel",
-    "idontcare",
-    "hearts1",
-    "dolly",
-    "dianna",
-    "costarica",
-    "coqueta",
-    "conrad",
-    "carolina1",
-    "britt1",
-    "babyboi",
-    "baby16",
-    "b123456",
-    "24682468",
-    "000111",
-    "!@#$%^",
-    "soleil",
-    "sapito",
-    "niggas",
-    "nickcarter",
-    "husband",
-    "greece",
-    "ginuwine",
-    "ghetto1",
-    "gggggg",
-    "gamecube",
-    "freaks",
-    "czarina",
-    "bitch2",
-    "ballin1",
-    "babyphat1",
-    "anubis",
-    "a123456789",
-    "vasquez",
-    "tomcat",
-    "sanjose",
-    "ramses",
-    "pussycatdolls",
-    "pimp123",
-    "oxford",
-    "norma",
-    "minicooper",
-    "loved",
-    "june22",
-    "indira",
-    "ilove1",
-    "huevos",
-    "hayden1",
-    "doudou",
-    "bigdaddy1",
-    "alexus",
-    "ADRIAN",
-    "101112",
-    "ursulet",
-    "senior08",
-    "racecar",
-    "parrot",
-    "osvaldo",
-    "nicaragua",
-    "maxima",
-    "massiel",
-    "lisbeth",
-    "lalita",
-    "khalil",
-    "gaara",
-    "dundee",
-    "chica",
-    "carmen1",
-    "byebye",
-    "buddyboy",
-    "buddy123",
-    "boneca",
-    "bettyboo",
-    "babymama",
-    "babyboo1",
-    "Elizabeth",
-    "998877",
-    "wright",
-    "wilmer",
-    "tesoro",
-    "shine",
-    "palmer",
-    "natnat",
-    "marias",
-    "lovebird",
-    "lalaine",
-    "john123",
-    "jarrod",
-    "jackjack",
-    "honney",
-    "greens",
-    "faggot",
-    "denmark",
-    "degrassi",
-    "clarisse",
-    "chispita",
-    "buckfast",
-    "bookworm",
-    "shotgun",
-    "perez",
-    "moose",
-    "money123",
-    "lonely1",
-    "ilovejamie",
-    "hottie2",
-    "henry1",
-    "guadalajara",
-    "bebeko",
-    "333666",
-    "someday",
-    "quicksilver",
-    "pizzahut",
-    "pedrito",
-    "moonstar",
-    "masina",
-    "maniac",
-    "lovealways",
-    "july14",
-    "ilovejack",
-    "ilovedanny",
-    "hithere",
-    "fletcher",
-    "fabregas",
-    "fabiana",
-    "dolfijn",
-    "contreras",
-    "concepcion",
-    "colegio",
-    "bruce",
-    "arsenalfc",
-    "PRINCESS1",
-    "147369",
-    "theodore",
-    "tanginamo",
-    "softball7",
-    "shannen",
-    "shampoo",
-    "sarahi",
-    "rockie",
-    "penguin1",
-    "nikole",
-    "montse",
-    "jamaica1",
-    "jack123",
-    "iluvhim",
-    "ilovepaul",
-    "iloveeric",
-    "gandalf",
-    "fuckme1",
-    "cuteaq",
-    "cocoa",
-    "cadillac",
-    "blacks",
-    "bertie",
-    "989898",
-    "1236987",
-    "1234569",
-    "wendell",
-    "terence",
-    "stars1",
-    "skating",
-    "reggaeton",
-    "princess10",
-    "pimping",
-    "papasito",
-    "love05",
-    "jingle",
-    "janette",
-    "iliana",
-    "google1",
-    "eternal",
-    "dadada",
-    "barney1",
-    "april15",
-    "alanis",
-    "12345j",
-    "zipper",
-    "wilson1",
-    "sharmaine",
-    "quentin",
-    "pinkprincess",
-    "oakley",
-    "marino",
-    "margera",
-    "manuel1",
-    "kimberly1",
-    "jethro",
-    "eureka",
-    "cucumber",
-    "colors",
-    "chris2",
-    "chopper1",
-    "PRETTY",
-    "123455",
-    "twenty",
-    "tommie",
-    "sandman",
-    "panchito",
-    "nicole3",
-    "munchie",
-    "marcella",
-    "lemuel",
-    "katelynn",
-    "jumper",
-    "jerick",
-    "happygirl",
-    "guardian",
-    "foreverlove",
-    "firdaus",
-    "eeyore1",
-    "doctorwho",
-    "cristovive",
-    "baby22",
-    "weirdo",
-    "theboss",
-    "spartans",
-    "rodrigues",
-    "roadrunner",
-    "radical",
-    "poohead",
-    "pawpaw",
-    "patita",
-    "norton",
-    "mydaddy",
-    "laurentiu",
-    "hurricane",
-    "hammers",
-    "frank1",
-    "dragos",
-    "d123456",
-    "ceejay",
-    "brownsugar",
-    "CHOCOLATE",
-    "545454",
-    "2good4u",
-    "tigerlily",
-    "sonic",
-    "soccer123",
-    "skeptron",
-    "scorpio1",
-    "ronnie1",
-    "pimp12",
-    "magical",
-    "lovekoto",
-    "love88",
-    "kirstie",
-    "inferno",
-    "hateme",
-    "empire",
-    "dusty1",
-    "doreen",
-    "coneja",
-    "ashton1",
-    "3girls",
-    "willis",
-    "tamtam",
-    "ryan",
-    "register",
-    "password01",
-    "magali",
-    "larsson",
-    "kimbum",
-    "killa1",
-    "kidrock",
-    "july23",
-    "july15",
-    "emogurl",
-    "callalily",
-    "baby21",
-    "25282528",
-    "vincent1",
-    "tsunami",
-    "roldan",
-    "pipoca",
-    "password9",
-    "mexican1",
-    "maicol",
-    "kissme2",
-    "katana",
-    "june10",
-    "jamess",
-    "ilovejon",
-    "hunting",
-    "haley1",
-    "goaway",
-    "gayboy",
-    "fishes",
-    "devin1",
-    "countdown",
-    "cooler",
-    "blood1",
-    "anthony2",
-    "utopia",
-    "pocahontas",
-    "pistons",
-    "password22",
-    "papillon",
-    "muffins",
-    "millie1",
-    "lifesux",
-    "june17",
-    "emilie",
-    "dreamer1",
-    "dennis1",
-    "daddys",
-    "coklat",
-    "christ1",
-    "chriss",
-    "charger",
-    "braden",
-    "blowjob",
-    "archer",
-    "andresito",
-    "anakaren",
-    "Jasmine",
-    "808080",
-    "wicked1",
-    "volcom1",
-    "television",
-    "superwoman",
-    "smart",
-    "shaman",
-    "pogiko",
-    "marcelita",
-    "lindsey1",
-    "lilchris",
-    "kareem",
-    "june19",
-    "gordis",
-    "gabbie",
-    "fullhouse",
-    "ferdinand",
-    "espinoza",
-    "dusty",
-    "drpepper1",
-    "dreamgirl",
-    "dinosaur",
-    "deshawn",
-    "darkstar",
-    "carbon",
-    "boogers",
-    "bigmac",
-    "794613",
-    "woody",
-    "thursday",
-    "tekelo",
-    "sugars",
-    "soccer18",
-    "mollydog",
-    "melanie1",
-    "littleone",
-    "katerine",
-    "indians",
-    "gatitos",
-    "derrick1",
-    "dalila",
-    "andrada",
-    "aggies",
-    "DAVID",
-    "ANDREW",
-    "123love",
-    "tumadre",
-    "tomas",
-    "symone",
-    "ryan123",
-    "roswell",
-    "pinkfloyd",
-    "motorbike",
-    "milan",
-    "lover123",
-    "lavidaesbella",
-    "keith1",
-    "katita",
-    "jose123",
-    "jerald",
-    "ilovesean",
-    "ilovelucy",
-    "golfer",
-    "girlygirl",
-    "camilla",
-    "atlantis",
-    "RANGERS",
-    "1234567891",
-    "zxc123",
-    "veronica1",
-    "vangie",
-    "slides",
-    "pontiac",
-    "mynameis",
-    "march17",
-    "larry1",
-    "karlos",
-    "justice1",
-    "july22",
-    "jolene",
-    "girasol",
-    "desember",
-    "cronaldo",
-    "crazybitch",
-    "claudine",
-    "bennie",
-    "angel8",
-    "angel69",
-    "toluca",
-    "sheridan",
-    "pink101",
-    "palacios",
-    "myboo",
-    "mirella",
-    "miami",
-    "leeryan",
-    "ilovemykids",
-    "feather",
-    "daisy123",
-    "cindy1",
-    "chanelle",
-    "champions",
-    "brunette",
-    "brett",
-    "babygirl3",
-    "awsome",
-    "akira",
-    "4815162342",
-    "1475369",
-    "123000",
-    "zeppelin",
-    "wilfredo",
-    "trevor1",
-    "soriano",
-    "smoke420",
-    "shakur",
-    "sexything",
-    "saxophone",
-    "rockin",
-    "nodoubt",
-    "mireya",
-    "mikejones",
-    "maynard",
-    "loveya1",
-    "lavander",
-    "homer",
-    "gunsnroses",
-    "gorilla",
-    "friends2",
-    "friend1",
-    "diosmeama",
-    "cleveland",
-    "clave",
-    "c123456",
-    "bobcats",
-    "blaine",
-    "waterpolo",
-    "tianna",
-    "sk84life",
-    "saskia",
-    "power1",
-    "nicegirl",
-    "myworld",
-    "moomoo1",
-    "moneys",
-    "miles",
-    "luismiguel",
-    "lucky123",
-    "lasalle",
-    "josemanuel",
-    "iloveme!",
-    "ilovekevin",
-    "gallardo",
-    "cuteangel",
-    "crissy",
-    "chocolat",
-    "changes",
-    "blue42",
-    "angelika",
-    "anfield",
-    "amo-te",
-    "acosta",
-    "AMANDA",
-    "zigzag",
-    "wildchild",
-    "tomorrow",
-    "stacie",
-    "sexy08",
-    "princess14",
-    "primavera",
-    "papaya",
-    "ovidiu",
-    "lucerito",
-    "london1",
-    "jenkins",
-    "goldberg",
-    "gandakoh",
-    "fuckyou69",
-    "footie",
-    "cuddles1",
-    "carlton",
-    "cachorro",
-    "brookie",
-    "ANDRES",
-    "345678",
-    "111222333",
-    "youknow",
-    "vaughn",
-    "tractor",
-    "thumper1",
-    "softball11",
-    "romantic",
-    "presley",
-    "parishilton",
-    "nipper",
-    "montero",
-    "kylie",
-    "johnson1",
-    "hotsauce",
-    "hotdog1",
-    "gregorio",
-    "everything",
-    "cutie2",
-    "charms",
-    "broadway",
-    "armstrong",
-    "weeman",
-    "sunderland",
-    "sheeba",
-    "shante",
-    "sanjuan",
-    "sally1",
-    "pimpette",
-    "newport1",
-    "manhater",
-    "lotlot",
-    "jerwin",
-    "ilovetyler",
-    "fashion1",
-    "chispa",
-    "chasity",
-    "charlie2",
-    "bluebear",
-    "billyboy",
-    "arvin",
-    "121288",
-    "warhammer",
-    "veronika",
-    "starry",
-    "soccer23",
-    "shygirl",
-    "samuel1",
-    "rogers",
-    "renegade",
-    "polska",
-    "number",
-    "march23",
-    "mangos",
-    "makenzie",
-    "jarell",
-    "iloveyou5",
-    "ilovemusic",
-    "iamsexy",
-    "house",
-    "finger",
-    "farmer",
-    "elmer",
-    "demon",
-    "corinne",
-    "claude",
-    "carmina",
-    "brent",
-    "bramble",
-    "bolton",
-    "bettyboop1",
-    "betito",
-    "amoramor",
-    "SAMANTHA",
-    "143445",
-    "tolentino",
-    "tennessee",
-    "sexy21",
-    "poohpooh",
-    "pandita",
-    "nicole13",
-    "natasha1",
-    "my1love",
-    "maricon",
-    "josh",
-    "hotstuff1",
-    "hannah123",
-    "dominican",
-    "cristhian",
-    "cavalier",
-    "campeon",
-    "baylee",
-    "ariadna",
-    "angola",
-    "albastru",
-    "yahoo!",
-    "vickie",
-    "sunshine2",
-    "qwerty7",
-    "prettyinpink",
-    "precioso",
-    "possum",
-    "playa",
-    "pisicutza",
-    "paraiso",
-    "opensesame",
-    "northside",
-    "mystic",
-    "mustafa",
-    "m12345",
-    "ladies",
-    "hotties",
-    "gotcha",
-    "godslove",
-    "fullmoon",
-    "fathead",
-    "fatboy1",
-    "ebony",
-    "chipmunk",
-    "carrots",
-    "blue23",
-    "belanova",
-    "1234566",
-    "tuyyo",
-    "teamo123",
-    "shanny",
-    "plymouth",
-    "perfect1",
-    "moranguita",
-    "modelo",
-    "lampard8",
-    "lakers24",
-    "kaylee1",
-    "karol",
-    "julien",
-    "ilovehim2",
-    "gavin1",
-    "florina",
-    "diana1",
-    "cielito",
-    "bollocks",
-    "benben",
-    "april20",
-    "MANUEL",
-    "LOVEYOU",
-    "10101010",
-    "sundance",
-    "solitario",
-    "softball3",
-    "shortcake",
-    "redhorse",
-    "murcielago",
-    "million",
-    "mikemike",
-    "meatball",
-    "lover12",
-    "lambert",
-    "kramer",
-    "ionut",
-    "ilovetony",
-    "holaa",
-    "happy2",
-    "elephants",
-    "country1",
-    "carter1",
-    "bunnie",
-    "bulldogs1",
-    "bubblegum1",
-    "abril",
-    "tttttt",
-    "stacy",
-    "skate1",
-    "sammy123",
-    "purple7",
-    "pompey",
-    "pickles1",
-    "nnnnnn",
-    "nick",
-    "moneymaker",
-    "lover2",
-    "lovee",
-    "lemon",
-    "kurtcobain",
-    "june25",
-    "june14",
-    "juandavid",
-    "jake123",
-    "hibees",
-    "giancarlo",
-    "galleta",
-    "freebird",
-    "fredrick",
-    "ericsson",
-    "doodoo",
-    "doodlebug",
-    "coolchick",
-    "christine1",
-    "chachi",
-    "candyshop",
-    "bruiser",
-    "bitch!",
-    "belen",
-    "travel",
-    "stardoll",
-    "saphire",
-    "rastafari",
-    "nutmeg",
-    "natural",
-    "maldonado",
-    "madelyn",
-    "leanna",
-    "lala123",
-    "kronos",
-    "jjjjj",
-    "gandah",
-    "elias",
-    "cupcakes",
-    "copper1",
-    "clara",
-    "cheeks",
-    "c.ronaldo",
-    "beachbum",
-    "bambi",
-    "april18",
-    "allmine",
-    "allen1",
-    "DIAMOND",
-    "5254143",
-    "332211",
-    "258258",
-    "135792468",
-    "12345t",
-    "yessenia",
-    "vania",
-    "thedoors",
-    "samiam",
-    "roosters",
-    "rebel1",
-    "rabbit1",
-    "mensuck",
-    "marifer",
-    "june26",
-    "juicy1",
-    "ilikeyou",
-    "famous1",
-    "chrisb1",
-    "carly",
-    "cadbury",
-    "aspirine",
-    "FRIENDS",
-    "1234321",
-    "sprint",
-    "qwert1",
-    "psicologia",
-    "princess21",
-    "poknat",
-    "panther1",
-    "monito",
-    "mackie",
-    "love19",
-    "lala",
-    "july13",
-    "javier1",
-    "geneva",
-    "fresh",
-    "feyenoord",
-    "easter",
-    "computers",
-    "cedrick",
-    "camprock",
-    "camden",
-    "bronco",
-    "braxton",
-    "RockYou!",
-    "HELLO",
-    "ultraman",
-    "thisisme",
-    "sweetbaby",
-    "spidey",
-    "shooter",
-    "shelly1",
-    "serrano",
-    "radiohead",
-    "ponies",
-    "milly",
-    "march22",
-    "march11",
-    "maldito",
-    "kerry",
-    "june27",
-    "july21",
-    "iheartyou",
-    "horoscope",
-    "honeybear",
-    "gemini1",
-    "dollface",
-    "bonita1",
-    "blue11",
-    "bitchs",
-    "asdf123",
-    "asawaq",
-    "april16",
-    "april14",
-    "angel17",
-    "969696",
-    "741258",
-    "tumama",
-    "sultan",
-    "sullivan",
-    "shana",
-    "shadows",
-    "saturday",
-    "sanders",
-    "quaresma",
-    "plastic",
-    "montoya",
-];
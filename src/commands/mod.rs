@@ -15,16 +15,28 @@
 */
 pub mod add;
 pub mod audit;
+pub mod bundle;
+pub mod cache;
 pub mod check;
+pub mod ci;
 pub mod clean;
 pub mod clone;
 pub mod create;
+pub mod dedupe;
 pub mod deploy;
+pub mod deprecate;
+pub mod diff;
 pub mod discord;
+pub mod dist_tag;
+pub mod doctor;
+pub mod exec;
 pub mod fix;
+pub mod graph;
 pub mod info;
 pub mod init;
 pub mod install;
+pub mod licenses;
+pub mod link;
 pub mod list;
 pub mod login;
 pub mod logout;
@@ -32,13 +44,27 @@ pub mod migrate;
 pub mod node;
 pub mod outdated;
 pub mod owner;
+pub mod pack;
+pub mod patch;
+pub mod pin;
+pub mod prune;
 pub mod publish;
+pub mod rebuild;
 pub mod remove;
 pub mod run;
 pub mod search;
 pub mod set;
+pub mod size;
 pub mod stat;
+pub mod store;
 pub mod tag;
 pub mod team;
+pub mod unlink;
+pub mod unpublish;
 pub mod update;
+pub mod upgrade_self;
+pub mod vendor;
+pub mod verify;
 pub mod watch;
+pub mod why;
+pub mod x;
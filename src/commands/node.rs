@@ -18,53 +18,22 @@
 
 mod node_install;
 mod node_list;
+mod node_pin;
 mod node_remove;
 mod node_use;
 
 pub use node_install::*;
 pub use node_list::*;
+pub use node_pin::*;
 pub use node_remove::*;
 pub use node_use::*;
 
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
 use miette::Result;
-use node_semver::Version;
-use serde::{Deserialize, Deserializer};
 
 use crate::cli::{VoltCommand, VoltConfig};
 
-#[derive(Deserialize)]
-#[serde(untagged)]
-enum Lts {
-    No(bool),
-    Yes(String),
-}
-
-impl From<Lts> for Option<String> {
-    fn from(val: Lts) -> Self {
-        match val {
-            Lts::No(_) => None,
-            Lts::Yes(x) => Some(x),
-        }
-    }
-}
-
-pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    Ok(Lts::deserialize(deserializer)?.into())
-}
-
-#[derive(Deserialize, Debug)]
-pub struct NodeVersion {
-    pub version: Version,
-    #[serde(deserialize_with = "deserialize")]
-    pub lts: Option<String>,
-    pub files: Vec<String>,
-}
-
 /// Manage node versions
 #[derive(Debug, Parser)]
 pub struct Node {
@@ -74,12 +43,27 @@ pub struct Node {
 
 #[async_trait]
 impl VoltCommand for Node {
+    /// Execute the `volt node` command
+    ///
+    /// Install official node builds into `~/.volt/node` and select the version the
+    /// `node`, `npm` and `npx` shims in `~/.volt/bin` run.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Install the newest LTS release and run it from the shims
+    /// // .exec() is an async call so you need to await it
+    /// Node { cmd: NodeCommand::Use(NodeUse { version: "lts".into() }) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
     async fn exec(self, config: VoltConfig) -> Result<()> {
         match self.cmd {
             NodeCommand::Use(x) => x.exec(config).await,
             NodeCommand::Install(x) => x.exec(config).await,
             NodeCommand::Remove(x) => x.exec(config).await,
             NodeCommand::List(x) => x.exec(config).await,
+            NodeCommand::Pin(x) => x.exec(config).await,
         }
     }
 }
@@ -90,4 +74,5 @@ pub enum NodeCommand {
     Install(NodeInstall),
     Remove(NodeRemove),
     List(NodeList),
+    Pin(NodePin),
 }
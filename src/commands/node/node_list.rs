@@ -1,42 +1,63 @@
 use async_trait::async_trait;
 use clap::Parser;
+use colored::Colorize;
 use miette::Result;
+use serde_json::json;
 
 use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    reporter::{emit, Event},
+    toolchain::{Pin, Toolchain},
+};
 
-/// List available NodeJS versions
+/// List the installed node versions
 #[derive(Debug, Parser)]
 pub struct NodeList {}
 
 #[async_trait]
 impl VoltCommand for NodeList {
-    // On windows, versions install to C:\Users\[name]\AppData\Roaming\volt\node\[version]
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        let node_path = {
-            let datadir = dirs::data_dir().unwrap().join("volt").join("node");
-            if !datadir.exists() {
-                eprintln!("No NodeJS versions installed!");
-                std::process::exit(1);
-            };
-            datadir
-        };
-
-        let mut versions = std::fs::read_dir(&node_path)
-            .unwrap()
-            .map(|d| d.unwrap().file_name().to_str().unwrap().to_owned())
-            .filter(|f| f != "current")
-            .collect::<Vec<String>>();
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let toolchain = Toolchain::from_config(&config)?;
 
-        if versions.is_empty() {
-            eprintln!("No NodeJS versions installed!");
-            std::process::exit(0);
+        let versions = toolchain.installed();
+        let default = toolchain.default_version();
+
+        let pinned = Pin::find(&config.cwd()?)
+            .and_then(|pin| pin.node)
+            .and_then(|request| toolchain.select_installed(&request));
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "installed": versions.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                "default": default.map(|version| version.to_string()),
+                "pinned": pinned.map(|version| version.to_string()),
+            })));
+
+            return Ok(());
         }
 
-        // Sort in descending order
-        versions.sort_by(|a, b| b.cmp(a));
+        if versions.is_empty() {
+            println!("No node versions installed, run `volt node install lts`");
+        }
 
         for version in versions {
-            println!("{version}");
+            let marks: Vec<&str> = [
+                (default.as_ref() == Some(&version)).then(|| "default"),
+                (pinned.as_ref() == Some(&version)).then(|| "pinned here"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            if marks.is_empty() {
+                println!("v{}", version);
+            } else {
+                println!(
+                    "{} ({})",
+                    format!("v{}", version).green().bold(),
+                    marks.join(", ")
+                );
+            }
         }
 
         Ok(())
@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    commands::node::ensure_version,
+};
+use volt_core::{
+    reporter::{emit, Event},
+    toolchain::{install_npm, Toolchain},
+    utils::package_editor::PackageJsonEditor,
+};
+
+/// Pin the node and npm versions of the project in its package.json, installing them
+#[derive(Debug, Parser)]
+pub struct NodePin {
+    /// Tools to pin: `node@18`, `npm@9`, or a node version alone (`lts`, `18.17.0`)
+    #[clap(required = true)]
+    tools: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for NodePin {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let toolchain = Toolchain::from_config(&config)?;
+
+        let mut editor = PackageJsonEditor::open(&config.cwd()?.join("package.json"))?;
+
+        let mut pinned = Map::new();
+
+        for request in &self.tools {
+            let (tool, request) = match request.split_once('@') {
+                Some(("npm", version)) => ("npm", version),
+                Some(("node", version)) => ("node", version),
+                _ => ("node", request.as_str()),
+            };
+
+            let version = match tool {
+                "npm" => {
+                    let (version, fresh) = install_npm(&config, &toolchain, request).await?;
+
+                    if fresh && !config.json() {
+                        println!("{} npm v{}", "Installed".green().bold(), version);
+                    }
+
+                    version
+                }
+                _ => ensure_version(&config, &toolchain, request).await?,
+            };
+
+            // pinned exactly, so everyone working on the project runs the same version
+            editor.set(&["volt", tool], &json!(version.to_string()))?;
+
+            pinned.insert(tool.to_string(), json!(version.to_string()));
+        }
+
+        editor.save()?;
+
+        if config.json() {
+            emit(&Event::Result(Value::Object(pinned)));
+
+            return Ok(());
+        }
+
+        for (tool, version) in &pinned {
+            println!(
+                "{} {} v{} in package.json",
+                "Pinned".green().bold(),
+                tool,
+                version.as_str().unwrap_or_default()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,91 +1,56 @@
-use std::path::PathBuf;
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use node_semver::Version;
 
 use crate::cli::{VoltCommand, VoltConfig};
-use async_trait::async_trait;
-use clap::{CommandFactory, ErrorKind, Parser};
-use miette::{IntoDiagnostic, Result};
+use volt_core::{toolchain::Toolchain, utils::errors::VoltError};
 
-/// Uninstall a specified version of node
+/// Remove one or more installed versions of node
 #[derive(Debug, Parser)]
 pub struct NodeRemove {
     /// Versions to remove
+    #[clap(required = true)]
     versions: Vec<String>,
 }
 
-fn get_node_dir() -> PathBuf {
-    dirs::data_dir().unwrap().join("volt").join("node")
-}
-
-// #[cfg(unix)]
 #[async_trait]
 impl VoltCommand for NodeRemove {
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        if self.versions.is_empty() {
-            NodeRemove::command()
-                .error(
-                    ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand,
-                    "Must have at least one version",
-                )
-                .exit();
-        }
-
-        let node_dir = get_node_dir();
-
-        let current_dir = if node_dir.join("current").exists() {
-            let curr = std::fs::canonicalize(node_dir.join("current"))
-                .unwrap()
-                .parent()
-                .unwrap()
-                .to_owned();
-            Some(curr)
-        } else {
-            None
-        };
-
-        let current_version = current_dir
-            .as_ref()
-            .map(|dir| dir.file_name().unwrap().to_str().unwrap());
-
-        for v in self.versions {
-            let version_dir = node_dir.join(&v);
-
-            if !version_dir.exists() {
-                eprintln!("Version {v} not installed");
-                continue;
-            }
-
-            if matches!(current_version, Some(ver) if ver == v) {
-                let current_dir = current_dir.as_ref().unwrap();
-                let current_bin = std::fs::read_dir(current_dir.join("bin")).unwrap();
-
-                // Remove all the installed symlinks
-                for binary in current_bin {
-                    let b = binary.unwrap();
-                    let result =
-                        std::fs::remove_file(dirs::executable_dir().unwrap().join(b.file_name()));
-
-                    match result {
-                        Ok(_) => {}
-                        Err(e) => return Err(e).into_diagnostic(),
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let toolchain = Toolchain::from_config(&config)?;
+
+        for request in &self.versions {
+            let version = request
+                .trim_start_matches('v')
+                .parse::<Version>()
+                .ok()
+                .filter(|version| toolchain.is_installed(version));
+
+            let version = match version {
+                Some(version) => version,
+                None => {
+                    return Err(VoltError::NodeVersionNotInstalled {
+                        version: request.clone(),
                     }
+                    .into())
                 }
+            };
 
-                let result = std::fs::remove_file(node_dir.join("current"));
-
-                match result {
-                    Ok(_) => {}
-                    Err(e) => return Err(e).into_diagnostic(),
-                }
+            if toolchain.default_version().as_ref() == Some(&version) {
+                tracing::warn!(
+                    "node v{} was the default, select another with `volt node use`",
+                    version
+                );
             }
 
-            // Always remove the version directory, regardless of current version status
-            let result = std::fs::remove_dir_all(node_dir.join(v));
+            toolchain.remove(&version)?;
 
-            match result {
-                Ok(_) => {}
-                Err(e) => return Err(e).into_diagnostic(),
+            if !config.quiet() {
+                println!("{} node v{}", "Removed".green().bold(), version);
             }
         }
+
         Ok(())
     }
 }
@@ -1,142 +1,59 @@
-use std::path::PathBuf;
-
 use async_trait::async_trait;
-
 use clap::Parser;
+use colored::Colorize;
 use miette::Result;
-
-use crate::cli::{VoltCommand, VoltConfig};
-
-/// Switch current node version
+use serde_json::json;
+
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    commands::node::ensure_version,
+};
+use volt_core::{
+    reporter::{emit, Event},
+    toolchain::Toolchain,
+};
+
+/// Select the node version the `node`, `npm` and `npx` shims run, installing it if needed
 #[derive(Debug, Parser)]
 pub struct NodeUse {
-    /// Version to use
+    /// Version to use (`18.17.0`, `18`, `lts`, `hydrogen`, `latest`)
     version: String,
 }
 
-fn get_node_dir() -> PathBuf {
-    dirs::data_dir().unwrap().join("volt").join("node")
-}
-
 #[async_trait]
 impl VoltCommand for NodeUse {
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        #[cfg(target_family = "windows")]
-        {
-            use_windows(self.version).await;
-        }
-
-        #[cfg(target_family = "unix")]
-        {
-            let node_path = get_node_dir().join(&self.version);
-
-            if node_path.exists() {
-                let link_dir = dirs::home_dir().unwrap().join(".local").join("bin");
-
-                let to_install = node_path.join("bin");
-                let current = node_path.parent().unwrap().join("current");
-
-                // TODO: Handle file deletion errors
-                if current.exists() {
-                    // Remove all the currently installed links
-                    for f in std::fs::read_dir(&current).unwrap() {
-                        let original = f.unwrap().file_name();
-                        let installed = link_dir.join(&original);
-                        if installed.exists() {
-                            std::fs::remove_file(installed).unwrap();
-                        }
-                    }
-
-                    // Remove the old link
-                    std::fs::remove_file(&current).unwrap();
-
-                    // Make a new one to the currently installed version
-                    std::os::unix::fs::symlink(&to_install, current).unwrap();
-                } else {
-                    println!("Installing first version");
-                    std::os::unix::fs::symlink(&to_install, current).unwrap();
-                }
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let toolchain = Toolchain::from_config(&config)?;
 
-                // Install all the links for the new version
-                for f in std::fs::read_dir(&to_install).unwrap() {
-                    let original = f.unwrap().path();
-                    let fname = original.file_name().unwrap();
-                    let link = link_dir.join(fname);
+        let version = ensure_version(&config, &toolchain, &self.version).await?;
 
-                    // INFO: DOC: Need to run `rehash` in zsh for the changes to take effect
-                    println!("Linking to {:?} from {:?}", link, original);
+        toolchain.set_default(Some(&version))?;
+        toolchain.link_shims()?;
 
-                    // TODO: Do something with this error
-                    let _ = std::fs::remove_file(&link);
+        if config.json() {
+            emit(&Event::Result(json!({
+                "version": version.to_string(),
+                "bin": toolchain.bin_dir(),
+            })));
 
-                    // maybe ship `vnm` as a shell function to run `volt node use ... && rehash` on
-                    // zsh?
-                    let _symlink = std::os::unix::fs::symlink(original, link).unwrap();
-                }
-            } else {
-                println!("That version of node is not installed!\nTry \"volt node install {}\" to install that version.", self.version)
-            }
+            return Ok(());
         }
-        Ok(())
-    }
-}
-
-#[cfg(windows)]
-async fn use_windows(version: String) {
-    use std::{env, path::Path, process::Command};
-    use tokio::fs;
 
-    let node_path = get_node_dir().join(&version).join("node.exe");
-    let path = Path::new(&node_path);
+        println!("{} node v{}", "Using".green().bold(), version);
 
-    if path.exists() {
-        println!("Using version {}", version);
+        let bin_dir = toolchain.bin_dir();
 
-        let link_dir = dirs::data_dir()
-            .unwrap()
-            .join("volt")
-            .join("bin")
-            .into_os_string()
-            .into_string()
-            .unwrap();
+        let on_path = std::env::var_os("PATH").map_or(false, |path| {
+            std::env::split_paths(&path).any(|p| p == bin_dir)
+        });
 
-        let link_file = dirs::data_dir()
-            .unwrap()
-            .join("volt")
-            .join("bin")
-            .join("node.exe");
-        let link_file = Path::new(&link_file);
-
-        if link_file.exists() {
-            fs::remove_file(link_file).await.unwrap();
+        if !on_path {
+            println!(
+                "add {} to your PATH to run it as `node`",
+                bin_dir.display().to_string().cyan()
+            );
         }
 
-        let newfile = std::fs::copy(node_path, link_file);
-
-        match newfile {
-            Ok(_) => {}
-            Err(_) => {
-                println!("Sorry, something went wrong.");
-                return;
-            }
-        }
-
-        let vfpath = dirs::data_dir().unwrap().join("volt").join("current");
-        let vfpath = Path::new(&vfpath);
-        let vfile = std::fs::write(vfpath, version);
-
-        let path = env::var("PATH").unwrap();
-        if !path.contains(&link_dir) {
-            let command = format!("[Environment]::SetEnvironmentVariable('Path', [Environment]::GetEnvironmentVariable('Path', 'User') + '{}', 'User')", &link_dir);
-
-            Command::new("Powershell")
-                .args(&["-Command", &command])
-                .output()
-                .unwrap();
-
-            println!("PATH environment variable updated.\nYou will need to restart your terminal for changes to apply.");
-        }
-    } else {
-        println!("That version of node is not installed!\nTry \"volt node install {}\" to install that version.", version);
+        Ok(())
     }
 }
@@ -25,7 +25,7 @@ use node_semver::Version;
 use serde::Deserialize;
 
 use crate::cli::{VoltCommand, VoltConfig};
-use crate::core::utils::package::PackageJson;
+use volt_core::utils::package::PackageJson;
 
 // https://github.com/npm/registry/blob/master/docs/REGISTRY-API.md#version
 // abbreviated version uses "Accept: application/vnd.npm.install-v1+json" in header
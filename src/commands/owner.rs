@@ -14,31 +14,187 @@
     limitations under the License.
 */
 
-//! Check for outdated packages.
+//! Manage the owners of a package.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    owners::{add, list, remove},
+    registry::RegistryClient,
+    reporter::{emit, Event},
+    utils::package::PackageJson,
+};
 
 use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
 use miette::Result;
+use serde_json::json;
 
-use crate::cli::{VoltCommand, VoltConfig};
-
-pub struct Owner {}
+/// Manage the owners of a package on the registry
+#[derive(Debug, Parser)]
+pub struct Owner {
+    #[clap(subcommand)]
+    cmd: OwnerCommand,
+}
 
 #[async_trait]
 impl VoltCommand for Owner {
-    /// Execute the `volt outdated` command
+    /// Execute the `volt owner` command
     ///
-    /// Check for outdated packages
+    /// List the users who can publish a package, add one, or remove one. Changes need a
+    /// token for the registry, and a one-time password for accounts with two-factor
+    /// authentication.
     /// ## Arguments
-    /// * `error` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Check for outdated packages
+    /// // Let alice publish react
     /// // .exec() is an async call so you need to await it
-    /// Owner.exec(app).await;
+    /// Owner { cmd: OwnerCommand::Add(OwnerAdd { user: "alice".into(), package: "react".into(), otp: None }) }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        match self.cmd {
+            OwnerCommand::Add(x) => x.exec(config).await,
+            OwnerCommand::Rm(x) => x.exec(config).await,
+            OwnerCommand::Ls(x) => x.exec(config).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OwnerCommand {
+    Add(OwnerAdd),
+    Rm(OwnerRm),
+    Ls(OwnerLs),
+}
+
+/// Make a user an owner of a package
+#[derive(Debug, Parser)]
+pub struct OwnerAdd {
+    /// The user
+    user: String,
+
+    /// The package
+    package: String,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication
+    #[clap(long)]
+    otp: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for OwnerAdd {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let client = RegistryClient::new(&config)?.with_otp(self.otp);
+
+        let added = add(&client, &self.package, &self.user, &*config.reporter()).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": self.package,
+                "user": self.user,
+                "added": added,
+            })));
+
+            return Ok(());
+        }
+
+        if added {
+            println!(
+                "{} {} to the owners of {}",
+                "Added".green().bold(),
+                self.user.bold(),
+                self.package
+            );
+        } else {
+            println!("{} already owns {}", self.user.bold(), self.package);
+        }
+
+        Ok(())
+    }
+}
+
+/// Take a user off the owners of a package
+#[derive(Debug, Parser)]
+pub struct OwnerRm {
+    /// The user
+    user: String,
+
+    /// The package
+    package: String,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication
+    #[clap(long)]
+    otp: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for OwnerRm {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let client = RegistryClient::new(&config)?.with_otp(self.otp);
+
+        let removed = remove(&client, &self.package, &self.user, &*config.reporter()).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": self.package,
+                "user": self.user,
+                "removed": removed,
+            })));
+
+            return Ok(());
+        }
+
+        if removed {
+            println!(
+                "{} {} from the owners of {}",
+                "Removed".green().bold(),
+                self.user.bold(),
+                self.package
+            );
+        } else {
+            println!("{} doesn't own {}", self.user.bold(), self.package);
+        }
+
+        Ok(())
+    }
+}
+
+/// List the owners of a package
+#[derive(Debug, Parser)]
+pub struct OwnerLs {
+    /// The package (the one in the current directory by default)
+    package: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for OwnerLs {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let name = match self.package {
+            Some(name) => name,
+            None => PackageJson::get_from_dir(&config.cwd()?)?.0.name,
+        };
+
+        let owners = list(&RegistryClient::new(&config)?, &name).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!(owners)));
+
+            return Ok(());
+        }
+
+        for owner in owners {
+            if owner.email.is_empty() {
+                println!("{}", owner.name.bold());
+            } else {
+                println!("{} <{}>", owner.name.bold(), owner.email);
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,115 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Pack the current package into a tarball.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    lifecycle::run_project_scripts,
+    pack::{pack, Packed},
+    utils::errors::FilesystemError,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::Result;
+
+use std::path::PathBuf;
+
+/// Pack the current package into a tarball, as it would be published
+#[derive(Debug, Parser)]
+pub struct Pack {
+    /// Directory to write the tarball to (the current directory by default)
+    #[clap(long)]
+    pack_destination: Option<PathBuf>,
+
+    /// Report what would be packed, without writing the tarball
+    #[clap(long)]
+    dry_run: bool,
+}
+
+#[async_trait]
+impl VoltCommand for Pack {
+    /// Execute the `volt pack` command
+    ///
+    /// Run the `prepack` and `prepare` scripts, pack the package the way `volt publish`
+    /// does and write `<name>-<version>.tgz`, then run the `postpack` script.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Pack the current package into ./dist
+    /// // .exec() is an async call so you need to await it
+    /// Pack { pack_destination: Some("dist".into()), dry_run: false }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        run_project_scripts(&config, &["prepack", "prepare"])?;
+
+        let packed = pack(&cwd)?;
+
+        print_contents(&packed);
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        let destination = cwd.join(self.pack_destination.unwrap_or_default());
+        let path = destination.join(packed.filename());
+
+        std::fs::create_dir_all(&destination)
+            .and_then(|_| std::fs::write(&path, &packed.tarball))
+            .map_err(|e| FilesystemError::Write {
+                source: e,
+                path: path.display().to_string(),
+            })?;
+
+        run_project_scripts(&config, &["postpack"])?;
+
+        println!("{} {}", "Packed".green().bold(), path.display());
+
+        Ok(())
+    }
+}
+
+/// Print the packed files and the details of the tarball.
+pub fn print_contents(packed: &Packed) {
+    for (file, size) in &packed.files {
+        println!(
+            "{:>10} {}",
+            HumanBytes(*size).to_string().truecolor(156, 156, 156),
+            file.display()
+        );
+    }
+
+    let details = [
+        ("name", format!("{}@{}", packed.name, packed.version)),
+        ("filename", packed.filename()),
+        ("size", HumanBytes(packed.tarball.len() as u64).to_string()),
+        ("files", packed.files.len().to_string()),
+        ("integrity", packed.integrity.clone()),
+        ("shasum", packed.shasum.clone()),
+    ];
+
+    for (label, value) in details {
+        println!("{:>10} {}", label.truecolor(156, 156, 156), value);
+    }
+}
@@ -0,0 +1,176 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Patch installed packages.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{install, link_workspace_members, project_dependencies, InstallScope, Resolution},
+    model::lock_file::LockFile,
+    patches::{commit, prepare},
+    reporter::{emit, Event},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+use serde_json::json;
+
+use std::path::PathBuf;
+
+/// Write the files of a package to a directory to change them
+#[derive(Debug, Parser)]
+pub struct Patch {
+    /// The package, optionally with a version (the one installed by default)
+    package: PackageSpec,
+}
+
+#[async_trait]
+impl VoltCommand for Patch {
+    /// Execute the `volt patch` command
+    ///
+    /// Write the published files of a package, with the project's patch of it applied, to
+    /// a temporary directory. Once they're changed, `volt patch-commit <dir>` saves the
+    /// changes to `patches/`, and every install applies them.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Fix a bug in the installed version of left-pad
+    /// // .exec() is an async call so you need to await it
+    /// Patch { package: "left-pad".parse()? }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (name, requested) = match &self.package {
+            PackageSpec::Npm {
+                name, requested, ..
+            } => (name.clone(), requested.as_ref().map(ToString::to_string)),
+            package => miette::bail!("{} is not a registry package", package),
+        };
+
+        let (version, directory) = prepare(&config, &name, requested.as_deref()).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": name,
+                "version": version,
+                "directory": directory,
+            })));
+
+            return Ok(());
+        }
+
+        println!(
+            "{} {}@{} to {}",
+            "Wrote".green().bold(),
+            name,
+            version,
+            directory.display()
+        );
+        println!(
+            "Change its files, then save the patch with `volt patch-commit {}`",
+            directory.display()
+        );
+
+        Ok(())
+    }
+}
+
+/// Save the changes made to a package after `volt patch`
+#[derive(Debug, Parser)]
+pub struct PatchCommit {
+    /// The directory `volt patch` wrote the package to
+    directory: PathBuf,
+}
+
+#[async_trait]
+impl VoltCommand for PatchCommit {
+    /// Execute the `volt patch-commit` command
+    ///
+    /// Save the changes made in the directory to the published files of the package as
+    /// `patches/<name>@<version>.patch`, and install the project again to apply it. A
+    /// directory without changes removes the patch.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Save the fix made to left-pad
+    /// // .exec() is an async call so you need to await it
+    /// PatchCommit { directory: "/tmp/volt-patch/left-pad@1.3.0".into() }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let committed = commit(&config, &self.directory).await?;
+
+        // node_modules catches up when the project is installed as locked
+        let cwd = config.cwd()?;
+        let (dependencies, workspace) = project_dependencies(&cwd, &[], InstallScope::All)?;
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let installed = match Resolution::from_lock_file(&lock_file, &dependencies) {
+            Some(resolution) if config.node_modules()?.is_dir() => {
+                install(&config, resolution).await?;
+
+                if let Some(workspace) = &workspace {
+                    link_workspace_members(&config, workspace)?;
+                }
+
+                true
+            }
+            _ => false,
+        };
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": committed.name,
+                "version": committed.version,
+                "patch": committed.path,
+                "files": committed.files,
+                "installed": installed,
+            })));
+
+            return Ok(());
+        }
+
+        let package = format!("{}@{}", committed.name, committed.version);
+
+        match &committed.path {
+            Some(path) => println!(
+                "{} {} ({} changed {})",
+                "Saved".green().bold(),
+                path.strip_prefix(&cwd).unwrap_or(path).display(),
+                committed.files,
+                if committed.files == 1 {
+                    "file"
+                } else {
+                    "files"
+                }
+            ),
+            None => println!("No changes to {}, its patch was removed", package),
+        }
+
+        if !installed {
+            println!("Run `volt install` to bring node_modules up to date");
+        }
+
+        Ok(())
+    }
+}
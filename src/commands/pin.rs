@@ -0,0 +1,125 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Pin dependencies to the versions they're locked at.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{project_dependencies, InstallScope, Resolution},
+    model::lock_file::LockFile,
+    reporter::{emit, Event},
+    update::update_ranges,
+    utils::{errors::ResolutionError, package::PackageJson},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+use serde_json::json;
+
+use std::collections::BTreeMap;
+
+/// Replace the ranges of dependencies with the versions they're locked at
+#[derive(Debug, Parser)]
+pub struct Pin {
+    /// Names of the dependencies to pin (every registry dependency by default)
+    packages: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Pin {
+    /// Execute the `volt pin` command
+    ///
+    /// Save the exact versions volt.lock holds for dependencies to package.json, so that
+    /// updates only happen with `volt update`. node_modules doesn't change.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Pin react at the version installed
+    /// // .exec() is an async call so you need to await it
+    /// Pin { packages: vec!["react".into()] }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        let (dependencies, _) = project_dependencies(&cwd, &[], InstallScope::All)?;
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+            ResolutionError::LockFileOutdated {
+                path: config.lockfile()?.display().to_string(),
+            },
+        )?;
+
+        let pinned = resolution.pinned_ranges();
+
+        for name in &self.packages {
+            if !pinned.contains_key(name) {
+                tracing::warn!("{} is not a registry dependency of the project", name);
+            }
+        }
+
+        // (range, pinned range) of the dependencies that change
+        let changed: BTreeMap<String, (String, String)> = pinned
+            .into_iter()
+            .filter(|(name, _)| self.packages.is_empty() || self.packages.contains(name))
+            .filter_map(|(name, range)| {
+                let current = dependencies.get(&name)?;
+
+                (*current != range).then(|| (name, (current.clone(), range)))
+            })
+            .collect();
+
+        let ranges: BTreeMap<String, String> = changed
+            .iter()
+            .map(|(name, (_, range))| (name.clone(), range.clone()))
+            .collect();
+
+        let (_, manifest_path) = PackageJson::get_from_dir(&cwd)?;
+
+        update_ranges(&manifest_path, &ranges)?;
+
+        if config.json() {
+            emit(&Event::Result(json!({ "pinned": ranges })));
+
+            return Ok(());
+        }
+
+        if changed.is_empty() {
+            println!(
+                "{} every dependency is already pinned",
+                "pin:".green().bold()
+            );
+        }
+
+        for (name, (from, to)) in changed {
+            println!(
+                "{} {} {} → {}",
+                "Pinned".green().bold(),
+                name,
+                from.truecolor(156, 156, 156),
+                to
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Remove extraneous packages from node_modules.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{project_dependencies, InstallScope, Resolution},
+    lock::lock_project,
+    model::lock_file::LockFile,
+    prune::prune_project,
+    reporter::{emit, Event},
+    utils::errors::ResolutionError,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::{IntoDiagnostic, Result};
+use serde_json::json;
+
+/// Remove the packages in node_modules that volt.lock doesn't reach
+#[derive(Debug, Parser)]
+pub struct Prune {
+    /// Also remove the devDependencies and what only they depend on
+    #[clap(long)]
+    production: bool,
+}
+
+#[async_trait]
+impl VoltCommand for Prune {
+    /// Execute the `volt prune` command
+    ///
+    /// Compare node_modules with the layout of the packages the lock file reaches from
+    /// package.json, and remove everything else.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Leave only what's needed to run the project
+    /// // .exec() is an async call so you need to await it
+    /// Prune { production: true }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+        let (dependencies, workspace) = project_dependencies(&cwd, &[], InstallScope::All)?;
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let mut resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+            ResolutionError::LockFileOutdated {
+                path: config.lockfile()?.display().to_string(),
+            },
+        )?;
+
+        if self.production {
+            let (production, _) = project_dependencies(&cwd, &[], InstallScope::Production)?;
+
+            resolution = resolution.scoped(&production);
+        }
+
+        let _locks = lock_project(&config).await?;
+
+        let pruned = prune_project(&config, &resolution, workspace.as_ref())?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "removed": pruned.paths,
+                "bytes": pruned.bytes,
+            })));
+
+            return Ok(());
+        }
+
+        if pruned.paths.is_empty() {
+            println!("Nothing to prune");
+
+            return Ok(());
+        }
+
+        for path in &pruned.paths {
+            let relative = path.strip_prefix(&cwd).unwrap_or(path);
+
+            println!("{} {}", "-".red(), relative.display());
+        }
+
+        println!(
+            "{} {} directories, reclaiming {}",
+            "Removed".green().bold(),
+            pruned.paths.len(),
+            HumanBytes(pruned.bytes)
+        );
+
+        Ok(())
+    }
+}
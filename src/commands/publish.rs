@@ -14,31 +14,146 @@
     limitations under the License.
 */
 
-//! Check for outdated packages.
+//! Publish the current package to the registry.
+
+use crate::{
+    cli::{VoltCommand, VoltConfig},
+    commands::pack::print_contents,
+};
+use volt_core::{
+    lifecycle::run_project_scripts,
+    pack::pack,
+    publish::{publish, Access},
+    registry::RegistryClient,
+    utils::{
+        errors::{FilesystemError, VoltError},
+        package::PackageJson,
+    },
+};
 
 use async_trait::async_trait;
-use miette::Result;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+
+/// Publish the current package to the registry
+#[derive(Debug, Parser)]
+pub struct Publish {
+    /// Dist-tag pointing to the published version
+    #[clap(long, default_value = "latest")]
+    tag: String,
 
-use crate::cli::{VoltCommand, VoltConfig};
+    /// Who can install the package (scoped packages are restricted by default)
+    #[clap(long, arg_enum)]
+    access: Option<Access>,
 
-pub struct Publish {}
+    /// Pack the package and report what would be published, without publishing it
+    #[clap(long)]
+    dry_run: bool,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication (asked for when it's needed and missing)
+    #[clap(long)]
+    otp: Option<String>,
+}
 
 #[async_trait]
 impl VoltCommand for Publish {
-    /// Execute the `volt outdated` command
+    /// Execute the `volt publish` command
     ///
-    /// Check for outdated packages
+    /// Run the `prepublishOnly`, `prepack` and `prepare` scripts, pack the package and
+    /// upload it to the registry (`publishConfig.registry` or the one configured in
+    /// `.npmrc`), then run the `publish` and `postpublish` scripts.
     /// ## Arguments
-    /// * `error` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Check for outdated packages
+    /// // Publish a prerelease under the `next` tag
     /// // .exec() is an async call so you need to await it
-    /// Publish.exec(app).await;
+    /// Publish { tag: "next".into(), access: None, dry_run: false, otp: None }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (package_json, manifest_path) = PackageJson::get_from_dir(&config.cwd()?)?;
+
+        if package_json.private == Some(true) {
+            return Err(VoltError::PrivatePackage {
+                name: package_json.name,
+            }
+            .into());
+        }
+
+        // the manifest is published as written, with the fields volt doesn't know about
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&manifest_path).map_err(|e| {
+                FilesystemError::Read {
+                    source: e,
+                    path: manifest_path.display().to_string(),
+                }
+            })?)
+            .into_diagnostic()?;
+
+        let publish_config = &manifest["publishConfig"];
+
+        let client = match publish_config["registry"].as_str() {
+            Some(registry) => RegistryClient::for_registry(&config, registry)?,
+            None => RegistryClient::new(&config)?,
+        }
+        .with_otp(self.otp);
+
+        let access = self.access.or(match publish_config["access"].as_str() {
+            Some("public") => Some(Access::Public),
+            Some("restricted") => Some(Access::Restricted),
+            _ => None,
+        });
+
+        run_project_scripts(&config, &["prepublishOnly", "prepack", "prepare"])?;
+
+        let packed = pack(&config.cwd()?)?;
+
+        run_project_scripts(&config, &["postpack"])?;
+
+        print_contents(&packed);
+
+        println!(
+            "{} {}@{} to {} with tag {}",
+            if self.dry_run {
+                "Would publish"
+            } else {
+                "Publishing"
+            }
+            .green()
+            .bold(),
+            packed.name,
+            packed.version,
+            client.url,
+            self.tag
+        );
+
+        if self.dry_run {
+            return Ok(());
+        }
+
+        publish(
+            &client,
+            &manifest,
+            &packed,
+            &self.tag,
+            access,
+            &*config.reporter(),
+        )
+        .await?;
+
+        run_project_scripts(&config, &["publish", "postpublish"])?;
+
+        println!(
+            "{} {}@{}",
+            "Published".green().bold(),
+            packed.name,
+            packed.version
+        );
+
         Ok(())
     }
 }
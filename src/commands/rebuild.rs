@@ -0,0 +1,108 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Rebuild installed packages.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{link_executables, locked_resolution, project_dependencies, InstallScope},
+    lifecycle::{allowed_scripts, run_dependency_scripts},
+    linker::linker,
+    lock::lock_project,
+    utils::voltapi::VoltPackage,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::Result;
+
+use std::{collections::HashMap, time::Instant};
+
+/// Run the install scripts of installed packages again and recreate their executables
+#[derive(Debug, Parser)]
+pub struct Rebuild {
+    /// Packages to rebuild (all of them by default)
+    packages: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Rebuild {
+    /// Execute the `volt rebuild` command
+    ///
+    /// Recreate the `node_modules/.bin` shims of the packages and run their install scripts
+    /// in place, dependencies first, without downloading or extracting anything. Native
+    /// addons need this after switching to another node version.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Build the native addon of sharp for the current node version
+    /// // .exec() is an async call so you need to await it
+    /// Rebuild { packages: vec!["sharp".into()] }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let start = Instant::now();
+
+        let (dependencies, _) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+        let resolution = locked_resolution(&config, &dependencies)?;
+        let linker = linker(&config, &resolution)?;
+
+        for name in &self.packages {
+            if !resolution
+                .tree
+                .values()
+                .any(|package| &package.name == name)
+            {
+                miette::bail!("{} is not in volt.lock", name);
+            }
+        }
+
+        let tree: HashMap<String, VoltPackage> = resolution
+            .tree
+            .iter()
+            .filter(|(_, package)| {
+                self.packages.is_empty() || self.packages.contains(&package.name)
+            })
+            .map(|(key, package)| (key.clone(), package.clone()))
+            .collect();
+
+        for key in tree.keys() {
+            if !linker
+                .directories(key)
+                .iter()
+                .any(|directory| directory.exists())
+            {
+                miette::bail!("{} is not installed, run `volt install` first", key);
+            }
+        }
+
+        let _locks = lock_project(&config).await?;
+
+        link_executables(&config, &resolution, linker.as_ref())?;
+
+        let allowed = allowed_scripts(&config, &tree, linker.as_ref())?;
+
+        run_dependency_scripts(&config, &tree, &allowed, linker.as_ref()).await?;
+
+        config
+            .reporter()
+            .done("Rebuilt", tree.len(), start.elapsed());
+
+        Ok(())
+    }
+}
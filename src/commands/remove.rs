@@ -17,111 +17,113 @@
 //! Remove a package from your direct dependencies.
 
 use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    global::remove_global,
+    install::{install, link_workspace_members, project_dependencies, InstallScope, Resolution},
+    lock::lock_project,
+    model::lock_file::LockFile,
+    prune::prune_project,
+    utils::{
+        errors::ResolutionError,
+        package_editor::{PackageJsonEditor, DEPENDENCY_FIELDS},
+    },
+};
 
 use async_trait::async_trait;
-use miette::Result;
-
-/// Struct implementation for the `Remove` command.
-pub struct Remove;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+
+/// Remove a package from your direct dependencies
+#[derive(Debug, Parser)]
+pub struct Remove {
+    /// Names of the packages to remove
+    #[clap(required = true)]
+    packages: Vec<String>,
+
+    /// Remove globally installed packages (`~/.volt/global`)
+    #[clap(long, short)]
+    global: bool,
+}
 
 #[async_trait]
 impl VoltCommand for Remove {
     /// Execute the `volt remove` command
     ///
-    /// Removes a package from your direct dependencies.
+    /// Removes packages from package.json and node_modules, along with what only they
+    /// depended on.
     /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
-    /// * `packages` - List of packages to add (`Vec<String>`)
-    /// * `flags` - List of flags passed in through the CLI (`Vec<String>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Remove a package from your direct dependencies with logging level verbose
+    /// // Remove a dependency of the project
     /// // .exec() is an async call so you need to await it
-    /// Remove.exec(app, vec![], vec!["--verbose"]).await;
+    /// Remove { packages: vec!["lodash".into()], global: false }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        // if app.args.len() == 1 {
-        //     println!("{}", Self::help());
-        //     process::exit(1);
-        // }
-
-        // let mut packages = vec![];
-        // for arg in &app.args {
-        //     if arg != "remove" {
-        //         packages.push(arg.clone());
-        //     }
-        // }
-
-        // let package_file = Arc::new(Mutex::new(PackageJson::from("package.json")));
-
-        // // let mut handles = vec![];
-
-        // println!("{}", "Removing dependencies".bright_purple());
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        if self.global {
+            return remove_global(&config, &self.packages);
+        }
 
-        // for package in packages {
-        //     let package_file = package_file.clone();
-        //     let app_new = app.clone();
+        let cwd = config.cwd()?;
+        let mut editor = PackageJsonEditor::open(&cwd.join("package.json"))?;
 
-        //     // handles.push(tokio::spawn(async move {
-        //     let mut package_json_file = package_file.lock().await;
+        let mut removed = vec![];
 
-        //     package_json_file.dependencies.remove(&package);
+        for name in &self.packages {
+            let mut found = false;
 
-        //     package_json_file.save();
+            for field in DEPENDENCY_FIELDS {
+                found |= editor.remove(&[field, name])?;
+            }
 
-        //     let mut lock_file = LockFile::load(app_new.lock_file_path.to_path_buf())
-        //         .unwrap_or_else(|_| LockFile::new(app_new.lock_file_path.to_path_buf()));
+            if found {
+                removed.push(name);
+            } else {
+                tracing::warn!("{} is not a dependency of the project", name);
+            }
+        }
 
-        //     // let response = get_volt_response(&package).await?;
+        if removed.is_empty() {
+            return Ok(());
+        }
 
-        //     // let current_version = response.versions.get(&response.version).unwrap();
+        editor.save()?;
 
-        //     // for object in current_version.values() {
-        //     //     // This is doing nothing? I guess it's still WIP?...
-        //     //     // let mut lock_dependencies: HashMap<String, String> = HashMap::new();
+        let (dependencies, workspace) = project_dependencies(&cwd, &[], InstallScope::All)?;
 
-        //     //     // if object.dependencies.is_some() {
-        //     //     //     for dep in object.clone().dependencies.unwrap().iter() {
-        //     //     //         // TODO: Change this to real version
-        //     //     //         lock_dependencies.insert(dep.clone(), String::new());
-        //     //     //     }
-        //     //     // }
+        let mut lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
 
-        //     //     lock_file
-        //     //         .dependencies
-        //     //         .remove(&DependencyID(object.clone().name, object.clone().version));
+        let resolution = Resolution::from_lock_file(&lock_file, &dependencies).ok_or(
+            ResolutionError::LockFileOutdated {
+                path: config.lockfile()?.display().to_string(),
+            },
+        )?;
 
-        //     //     let scripts = Path::new("node_modules/scripts")
-        //     //         .join(format!("{}.cmd", object.clone().name).as_str());
+        // what only the removed packages needed leaves the lock file too
+        lock_file
+            .dependencies
+            .retain(|key, _| resolution.tree.contains_key(key));
+        lock_file.save()?;
 
-        //     //     if scripts.exists() {
-        //     //         remove_file(format!(
-        //     //             "node_modules/scripts/{}",
-        //     //             scripts.file_name().unwrap().to_str().unwrap()
-        //     //         ))
-        //     //         .await
-        //     //         .unwrap_and_handle_error();
-        //     //     }
-        //     // }
+        install(&config, resolution).await?;
 
-        //     // lock_file.save().unwrap();
+        if let Some(workspace) = &workspace {
+            link_workspace_members(&config, workspace)?;
+        }
 
-        //     // let node_modules_dir = std::env::current_dir().unwrap().join("node_modules");
-        //     // let dep_dir = node_modules_dir.join(&package);
-        //     // if dep_dir.exists() {
-        //     //     remove_dir_all(dep_dir).await.unwrap_and_handle_error();
-        //     // }
-        // }
+        // `install` has no idea what was there before
+        if let Some(resolution) = Resolution::from_lock_file(&lock_file, &dependencies) {
+            let _locks = lock_project(&config).await?;
 
-        // // if handles.len() > 0 {
-        // //     for handle in handles {
-        // //         handle.await?;
-        // //     }
-        // // }
+            prune_project(&config, &resolution, workspace.as_ref())?;
+        }
 
-        // println!("{}", "Successfully Removed Packages".bright_blue());
+        for name in removed {
+            println!("{} {}", "Removed".green().bold(), name);
+        }
 
         Ok(())
     }
@@ -15,54 +15,371 @@
 */
 
 use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    lifecycle::{quote_arg, run_script, run_script_prefixed, ScriptRun},
+    remote_cache::RemoteCache,
+    task_cache::{TaskCache, TaskConfig, TaskHasher},
+    tasks::{run_graph, task_graph, TaskOutcome},
+    utils::{errors::ScriptError, package::PackageJson},
+    workspace::{Filter, Workspace, WorkspaceMember},
+};
 
 use async_trait::async_trait;
 use clap::Parser;
 use colored::Colorize;
 use miette::Result;
+use tokio::runtime::Handle;
+
 use std::path::Path;
-use std::process::{Command, Stdio};
 
 /// Run a pre-defined package script
 #[derive(Debug, Parser)]
 pub struct Run {
-    /// Name of the script to run
-    script: String,
+    /// Name of the script to run (lists the available scripts if omitted)
+    script: Option<String>,
+
+    /// Arguments passed through to the script
+    #[clap(last = true)]
+    args: Vec<String>,
+
+    /// Run the script in every workspace member that has it, dependencies first
+    #[clap(long, short, requires = "script")]
+    recursive: bool,
+
+    /// Only run in the matching workspace members
+    /// (`name`, `@scope/*`, `./path`, `[git-ref]`, `...name`, `name...`).
+    #[clap(long, short = 'F', requires = "recursive")]
+    filter: Vec<Filter>,
+
+    /// Most members whose scripts run at once (the `workspace-concurrency` setting by default)
+    #[clap(long, requires = "recursive")]
+    workspace_concurrency: Option<usize>,
+
+    /// Run every script, even those with their results in the task cache
+    #[clap(long, requires = "recursive")]
+    no_cache: bool,
 }
 
 #[async_trait]
 impl VoltCommand for Run {
-    /// Display a help menu for the `volt run` command.
     /// Execute the `volt run` command
     ///
-    /// Interactively create or update a package.json file for a project.
+    /// Run a script defined in package.json, along with its `pre` and `post` scripts.
+    ///
+    /// With `--recursive` the script runs in every workspace member that defines it, each
+    /// once the members it depends on are done and members that don't depend on each other
+    /// at once, every line of output after the name of its member. A failure skips the
+    /// members depending on the one that failed, and every failure is reported at the end.
+    /// Scripts described in the `volt.tasks` field of package.json are skipped when their
+    /// inputs haven't changed since they last succeeded, their outputs restored from the
+    /// task cache, or from the remote cache it is shared through, instead.
     /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
-    /// * `packages` - List of packages to add (`Vec<String>`)
-    /// * `flags` - List of flags passed in through the CLI (`Vec<String>`)
+    /// * `config` - Global volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Run a defined script.
+    /// // Run the `build` script, passing `--watch` through to it
     /// // .exec() is an async call so you need to await it
-    /// Run.exec(app, vec![], vec!["--yes"]).await;
+    /// Run { script: Some("build".into()), args: vec!["--watch".into()], recursive: false, filter: vec![], workspace_concurrency: None, no_cache: false }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        if cfg!(target_os = "windows") {
-            Command::new("cmd").args(&["/C", "babel"]).spawn().unwrap();
-        } else if cfg!(target_os = "linux") {
-            println!("{}", format!("$ {}", self.script).truecolor(156, 156, 156));
-
-            let mut child =
-                Command::new(Path::new("node_modules/").join(".bin/").join(self.script))
-                    .stdout(Stdio::inherit())
-                    .stderr(Stdio::inherit())
-                    .spawn()
-                    .expect("failed to execute child");
-
-            child.wait().unwrap();
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let cwd = config.cwd()?;
+
+        if self.recursive {
+            return self.exec_recursive(&config, &cwd);
+        }
+
+        let (package_json, _) = PackageJson::get_from_dir(&cwd)?;
+        let scripts = package_json.scripts.clone().unwrap_or_default();
+
+        let name = match self.script {
+            Some(name) => name,
+            None => {
+                list_scripts(&package_json);
+                return Ok(());
+            }
+        };
+
+        // fall back to executables in `node_modules/.bin` (like `yarn run`), which are on PATH
+        let script = match scripts.get(&name) {
+            Some(script) => script,
+            None if config.node_modules()?.join(".bin").join(&name).exists() => &name,
+            None => return Err(ScriptError::NotFound { name }.into()),
+        };
+
+        // arguments are only passed to the requested script, not its pre/post scripts
+        let script = self.args.iter().fold(script.clone(), |script, arg| {
+            format!("{} {}", script, quote_arg(arg))
+        });
+
+        // an executable isn't a script of the package, so it has no pre/post scripts
+        let events = if scripts.contains_key(&name) {
+            vec![
+                format!("pre{}", name),
+                name.clone(),
+                format!("post{}", name),
+            ]
+        } else {
+            vec![name.clone()]
+        };
+
+        for event in &events {
+            let script = if *event == name {
+                &script
+            } else {
+                match scripts.get(event) {
+                    Some(script) => script,
+                    None => continue,
+                }
+            };
+
+            run_script(
+                &config,
+                &ScriptRun {
+                    name: &package_json.name,
+                    version: &package_json.version,
+                    cwd: &cwd,
+                    event,
+                    script,
+                    node_gyp: None,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Run {
+    /// Run the script across the workspace, in the order of its dependency graph.
+    fn exec_recursive(&self, config: &VoltConfig, cwd: &Path) -> Result<()> {
+        // clap makes sure there is a script with `--recursive`
+        let name = self.script.as_deref().unwrap_or_default();
+
+        let workspace = match Workspace::discover(cwd)? {
+            Some(workspace) => workspace,
+            None => miette::bail!("--recursive needs a project with workspaces"),
+        };
+
+        let members: Vec<&WorkspaceMember> = workspace
+            .filter(&self.filter)?
+            .into_iter()
+            .filter(|member| {
+                member
+                    .package_json
+                    .scripts
+                    .as_ref()
+                    .map_or(false, |scripts| scripts.contains_key(name))
+            })
+            .collect();
+
+        if members.is_empty() {
+            miette::bail!(
+                "none of the selected workspace members has a `{}` script",
+                name
+            );
+        }
+
+        let width = members
+            .iter()
+            .map(|member| member.name().len())
+            .max()
+            .unwrap_or(0);
+
+        let concurrency = self
+            .workspace_concurrency
+            .unwrap_or(config.settings().workspace_concurrency);
+
+        let tasks = Tasks {
+            workspace: &workspace,
+            hasher: TaskHasher::new(&workspace, name),
+            cache: TaskCache::new(&config.volt_home()?),
+            remote: RemoteCache::from_config(config)?,
+            runtime: Handle::current(),
+        };
+
+        // the scripts block their threads, the remote cache is reached through the runtime
+        let outcomes = tokio::task::block_in_place(|| {
+            run_graph(&task_graph(&workspace, &members), concurrency, |member| {
+                // every member of the graph is in the workspace
+                let member = workspace.member(member).unwrap();
+                let prefix = format!("{:width$} |", member.name(), width = width)
+                    .cyan()
+                    .to_string();
+
+                self.run_member(config, &tasks, member, &prefix)
+            })
+        })?;
+
+        let failed: Vec<&str> = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, TaskOutcome::Failed(_)))
+            .map(|(member, _)| member.as_str())
+            .collect();
+
+        if failed.is_empty() {
+            return Ok(());
+        }
+
+        for (member, outcome) in &outcomes {
+            if let TaskOutcome::Failed(error) = outcome {
+                tracing::error!("{}: {}", member, error);
+            }
+        }
+
+        Err(ScriptError::WorkspaceScripts {
+            script: name.to_string(),
+            count: failed.len(),
+            members: failed.join(", "),
+            skipped: outcomes
+                .values()
+                .filter(|outcome| matches!(outcome, TaskOutcome::Skipped))
+                .count(),
+        }
+        .into())
+    }
+
+    /// Run the script of a member with its `pre` and `post` scripts, passing the arguments
+    /// to the script itself, or replay it from the task cache.
+    fn run_member(
+        &self,
+        config: &VoltConfig,
+        tasks: &Tasks,
+        member: &WorkspaceMember,
+        prefix: &str,
+    ) -> Result<()> {
+        let name = tasks.hasher.script();
+        let scripts = member.package_json.scripts.clone().unwrap_or_default();
+
+        let runs: Vec<(String, String)> = [
+            format!("pre{}", name),
+            name.to_string(),
+            format!("post{}", name),
+        ]
+        .into_iter()
+        .filter_map(|event| {
+            let script = match scripts.get(&event) {
+                Some(script) if event == name => {
+                    self.args.iter().fold(script.clone(), |script, arg| {
+                        format!("{} {}", script, quote_arg(arg))
+                    })
+                }
+                Some(script) => script.clone(),
+                None => return None,
+            };
+
+            Some((event, script))
+        })
+        .collect();
+
+        let cached = match TaskConfig::find(tasks.workspace, member, name) {
+            Some(task) => {
+                let text: Vec<&str> = runs.iter().map(|(_, script)| script.as_str()).collect();
+
+                Some((tasks.hasher.hash(member, &task, &text.join("\n"))?, task))
+            }
+            None => None,
+        };
+
+        if let (Some((hash, _)), false) = (&cached, self.no_cache) {
+            if let Some(remote) = &tasks.remote {
+                if let Err(error) = tasks.runtime.block_on(remote.pull(&tasks.cache, hash)) {
+                    tracing::warn!("failed to read the remote task cache: {}", error);
+                }
+            }
+
+            if let Some(output) = tasks.cache.restore(hash, &member.path)? {
+                let print = |line: &str| {
+                    if config.json() {
+                        eprintln!("{} {}", prefix, line);
+                    } else {
+                        println!("{} {}", prefix, line);
+                    }
+                };
+
+                print(
+                    &format!("cache hit ({}), replaying output", &hash[..16])
+                        .truecolor(156, 156, 156)
+                        .to_string(),
+                );
+
+                for line in output.lines() {
+                    print(line);
+                }
+
+                return Ok(());
+            }
+        }
+
+        let mut output = String::new();
+
+        for (event, script) in &runs {
+            output.push_str(&run_script_prefixed(
+                config,
+                &ScriptRun {
+                    name: member.name(),
+                    version: &member.package_json.version,
+                    cwd: &member.path,
+                    event,
+                    script,
+                    node_gyp: None,
+                },
+                prefix,
+            )?);
         }
+
+        if let Some((hash, task)) = cached {
+            if let Err(error) = tasks.cache.save(&hash, member, name, &task, &output) {
+                tracing::warn!(
+                    "failed to cache the `{}` script of {}: {}",
+                    name,
+                    member.name(),
+                    error
+                );
+            }
+
+            if let Some(remote) = tasks.remote.as_ref().filter(|remote| !remote.read_only) {
+                if let Err(error) = tasks.runtime.block_on(remote.push(&tasks.cache, &hash)) {
+                    tracing::warn!("failed to write to the remote task cache: {}", error);
+                }
+            }
+        }
+
         Ok(())
     }
 }
+
+/// What the scripts of a recursive run share.
+struct Tasks<'a> {
+    workspace: &'a Workspace,
+    hasher: TaskHasher<'a>,
+    cache: TaskCache,
+    remote: Option<RemoteCache>,
+    runtime: Handle,
+}
+
+/// Print every script defined in package.json
+fn list_scripts(package_json: &PackageJson) {
+    let scripts = match &package_json.scripts {
+        Some(scripts) if !scripts.is_empty() => scripts,
+        _ => {
+            println!(
+                "{} has no scripts defined.",
+                package_json.name.bright_cyan()
+            );
+            return;
+        }
+    };
+
+    println!(
+        "Scripts available in {} via `{}`:",
+        package_json.name.bright_cyan(),
+        "volt run".bright_green()
+    );
+
+    for (name, script) in scripts {
+        println!("  {}", name.bold());
+        println!("    {}", script.truecolor(156, 156, 156));
+    }
+}
@@ -13,8 +13,8 @@
 
 //! Handle an unknown command (can be listed in scripts).
 
-// use crate::core::utils::errors;
-// use crate::core::utils::package::PackageJson;
+// use volt_core::utils::errors;
+// use volt_core::utils::package::PackageJson;
 use crate::App;
 use crate::Command;
 
@@ -17,112 +17,169 @@
 //! Search for a package.
 
 use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    registry::RegistryClient,
+    reporter::{emit, Event},
+    search::{search, SearchResult, MAX_PAGE_SIZE},
+};
 
 use async_trait::async_trait;
 use clap::Parser;
+use colored::Colorize;
 use comfy_table::{
     modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement,
     Table,
 };
-use isahc::AsyncReadResponseExt;
-use miette::Result;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize)]
-pub struct Objects {
-    objects: Vec<SearchResults>,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct SearchResults {
-    package: SearchResult,
-}
-
-#[derive(Serialize, Deserialize)]
-pub struct SearchResult {
-    name: String,
-    version: String,
-    description: String,
-}
+use miette::{IntoDiagnostic, Result};
 
 /// Searches for a package
 #[derive(Debug, Parser)]
 pub struct Search {
     /// Search query
     query: String,
-}
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct SearchData {
-    pub name: String,
-    pub version: String,
-    pub description: Option<String>,
+    /// Number of results per page (at most 250)
+    #[clap(long, default_value = "20")]
+    limit: usize,
+
+    /// Page of results to show, starting at 1
+    #[clap(long, default_value = "1")]
+    page: usize,
 }
 
 #[async_trait]
 impl VoltCommand for Search {
     /// Execute the `volt search` command
     ///
-    /// Search for a package
+    /// Search the registry configured in `.npmrc` and list the best matches first.
     /// ## Arguments
-    /// * `error` - Instance of the command (`Arc<App>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // Search for a package
+    /// // Show the second page of results for `react router`
     /// // .exec() is an async call so you need to await it
-    /// Search.exec(app).await;
+    /// Search { query: "react router".into(), limit: 20, page: 2 }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
-        let response = isahc::get_async(format!(
-            "https://registry.npmjs.org/-/v1/search?text={}&popularity=1.0",
-            self.query
-        ))
-        .await
-        .unwrap()
-        .text()
-        .await
-        .unwrap();
-
-        let s: Objects = serde_json::from_str(&response).unwrap();
-
-        let mut table = Table::new();
-
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_content_arrangement(ContentArrangement::DynamicFullWidth);
-
-        table.set_header(vec![
-            Cell::new("Name")
-                .fg(Color::Green)
-                .add_attribute(Attribute::Bold),
-            Cell::new("Version")
-                .fg(Color::Blue)
-                .add_attribute(Attribute::Bold),
-            Cell::new("Description")
-                .fg(Color::Yellow)
-                .add_attribute(Attribute::Bold),
-        ]);
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let client = RegistryClient::new(&config)?;
+
+        let limit = self.limit.clamp(1, MAX_PAGE_SIZE);
+        let from = (self.page.max(1) - 1) * limit;
 
-        for i in &s.objects {
-            let mut description: String = i.package.description.clone();
+        let results = search(&client, &self.query, from, limit).await?;
 
-            if i.package.description.len() > 150 {
-                description.truncate(147);
-                description = format!("{}...", description);
-            }
+        if config.json() {
+            emit(&Event::Result(
+                serde_json::to_value(&results).into_diagnostic()?,
+            ));
 
-            table.add_row(vec![
-                Cell::new(&i.package.name),
-                Cell::new(&i.package.version),
-                Cell::new(description),
-            ]);
+            return Ok(());
         }
 
-        println!("{}", table);
+        if results.objects.is_empty() {
+            println!("No packages found for {}", self.query.bold());
+
+            return Ok(());
+        }
+
+        println!("{}", results_table(&results.objects, from));
+
+        let shown = from + results.objects.len();
+
+        println!(
+            "{}",
+            format!(
+                "Showing {}-{} of {} results",
+                from + 1,
+                shown,
+                results.total
+            )
+            .truecolor(156, 156, 156)
+        );
+
+        if shown < results.total {
+            println!(
+                "{}",
+                format!("Run with `--page {}` for more", self.page.max(1) + 1)
+                    .truecolor(156, 156, 156)
+            );
+        }
 
         Ok(())
     }
 }
+
+fn results_table(results: &[SearchResult], from: usize) -> Table {
+    let mut table = Table::new();
+
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::DynamicFullWidth);
+
+    // not every registry reports downloads
+    let downloads = results.iter().any(|result| result.downloads.is_some());
+
+    let mut header = vec![
+        Cell::new("#").add_attribute(Attribute::Bold),
+        Cell::new("Name")
+            .fg(Color::Green)
+            .add_attribute(Attribute::Bold),
+        Cell::new("Version")
+            .fg(Color::Blue)
+            .add_attribute(Attribute::Bold),
+        Cell::new("Description")
+            .fg(Color::Yellow)
+            .add_attribute(Attribute::Bold),
+    ];
+
+    if downloads {
+        header.push(
+            Cell::new("Weekly downloads")
+                .fg(Color::Cyan)
+                .add_attribute(Attribute::Bold),
+        );
+    }
+
+    table.set_header(header);
+
+    for (index, result) in results.iter().enumerate() {
+        let mut description = result.package.description.clone().unwrap_or_default();
+
+        if description.chars().count() > 150 {
+            description = format!("{}...", description.chars().take(147).collect::<String>());
+        }
+
+        let mut row = vec![
+            Cell::new(from + index + 1),
+            Cell::new(&result.package.name).fg(Color::Green),
+            Cell::new(&result.package.version),
+            Cell::new(description),
+        ];
+
+        if downloads {
+            row.push(Cell::new(
+                result
+                    .downloads
+                    .as_ref()
+                    .map_or_else(String::new, |downloads| count(downloads.weekly)),
+            ));
+        }
+
+        table.add_row(row);
+    }
+
+    table
+}
+
+/// A download count, shortened: `1.2k`, `14.3M`.
+fn count(downloads: u64) -> String {
+    match downloads {
+        0..=999 => downloads.to_string(),
+        1_000..=999_999 => format!("{:.1}k", downloads as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1}M", downloads as f64 / 1e6),
+        _ => format!("{:.1}B", downloads as f64 / 1e9),
+    }
+}
@@ -0,0 +1,249 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Report the install footprint of the project's dependencies.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{locked_resolution, project_dependencies, InstallScope},
+    linker::linker,
+    reporter::{emit, Event},
+    size::{footprints, subtree, Footprint},
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use comfy_table::{
+    modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Attribute, Cell, CellAlignment, Color,
+    ContentArrangement, Table,
+};
+use indicatif::HumanBytes;
+use miette::Result;
+use serde_json::json;
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashSet},
+};
+
+/// Rows flagged as the heaviest.
+const HEAVIEST: usize = 3;
+
+/// Report the unpacked size and file count of the dependencies and what they pull in
+#[derive(Debug, Parser)]
+pub struct Size {
+    /// Break down the subtree of this package instead of the whole project
+    package: Option<String>,
+}
+
+/// A row of the report: a package and the packages it accounts for.
+struct Row {
+    key: String,
+    packages: usize,
+    footprint: Footprint,
+}
+
+#[async_trait]
+impl VoltCommand for Size {
+    /// Execute the `volt size` command
+    ///
+    /// Total the footprint of every top-level dependency with its own dependencies, the
+    /// heaviest first; with a package, list the packages of its subtree instead.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // What makes `jest` so big
+    /// // .exec() is an async call so you need to await it
+    /// Size { package: Some("jest".into()) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (dependencies, _) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+        let resolution = locked_resolution(&config, &dependencies)?;
+        let linker = linker(&config, &resolution)?;
+
+        let footprints = footprints(&config, &resolution, linker.as_ref()).await?;
+
+        let total = |keys: &BTreeSet<String>| {
+            let mut footprint = Footprint::default();
+
+            for key in keys {
+                footprint += footprints.get(key).copied().unwrap_or_default();
+            }
+
+            footprint
+        };
+
+        let mut rows: Vec<Row> = match &self.package {
+            Some(name) => {
+                let roots: BTreeSet<String> = resolution
+                    .tree
+                    .iter()
+                    .filter(|(_, package)| package.name == *name)
+                    .map(|(key, _)| key.clone())
+                    .collect();
+
+                if roots.is_empty() {
+                    miette::bail!("{} is not in volt.lock", name);
+                }
+
+                roots
+                    .iter()
+                    .flat_map(|root| subtree(&resolution, root))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(|key| Row {
+                        footprint: footprints.get(&key).copied().unwrap_or_default(),
+                        key,
+                        packages: 1,
+                    })
+                    .collect()
+            }
+            None => {
+                let direct: BTreeSet<&String> = resolution
+                    .direct
+                    .iter()
+                    .chain(resolution.aliases.values())
+                    .collect();
+
+                direct
+                    .into_iter()
+                    .map(|key| {
+                        let keys = subtree(&resolution, key);
+
+                        Row {
+                            key: key.clone(),
+                            packages: keys.len(),
+                            footprint: total(&keys),
+                        }
+                    })
+                    .collect()
+            }
+        };
+
+        rows.sort_by_key(|row| Reverse(row.footprint.size));
+
+        // packages shared by several dependencies are only counted once here
+        let counted: BTreeSet<String> = match &self.package {
+            Some(_) => rows.iter().map(|row| row.key.clone()).collect(),
+            None => resolution.tree.keys().cloned().collect(),
+        };
+
+        let overall = total(&counted);
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "total": {
+                    "packages": counted.len(),
+                    "size": overall.size,
+                    "files": overall.files,
+                },
+                "packages": rows
+                    .iter()
+                    .map(|row| json!({
+                        "package": row.key,
+                        "packages": row.packages,
+                        "size": row.footprint.size,
+                        "files": row.footprint.files,
+                    }))
+                    .collect::<Vec<_>>(),
+            })));
+
+            return Ok(());
+        }
+
+        println!("{}", size_table(&rows, self.package.is_none()));
+
+        println!(
+            "{} {} in {} files across {} packages",
+            "Total".bold(),
+            HumanBytes(overall.size)
+                .to_string()
+                .truecolor(196, 206, 255)
+                .bold(),
+            overall.files,
+            counted.len()
+        );
+
+        if self.package.is_none() {
+            println!(
+                "{}",
+                "dependencies that share packages each count them".truecolor(156, 156, 156)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn size_table(rows: &[Row], subtrees: bool) -> Table {
+    let mut table = Table::new();
+
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic);
+
+    let mut header = vec![Cell::new(if subtrees { "Dependency" } else { "Package" })
+        .fg(Color::Green)
+        .add_attribute(Attribute::Bold)];
+
+    if subtrees {
+        header.push(Cell::new("Packages").add_attribute(Attribute::Bold));
+    }
+
+    header.push(Cell::new("Files").add_attribute(Attribute::Bold));
+    header.push(
+        Cell::new("Size")
+            .fg(Color::Cyan)
+            .add_attribute(Attribute::Bold),
+    );
+
+    table.set_header(header);
+
+    // rows come heaviest first
+    let heaviest: HashSet<&str> = rows
+        .iter()
+        .take(HEAVIEST)
+        .filter(|row| row.footprint.size > 0)
+        .map(|row| row.key.as_str())
+        .collect();
+
+    for row in rows {
+        let mut size =
+            Cell::new(HumanBytes(row.footprint.size)).set_alignment(CellAlignment::Right);
+
+        if heaviest.contains(row.key.as_str()) {
+            size = size.fg(Color::Yellow).add_attribute(Attribute::Bold);
+        }
+
+        let mut cells = vec![Cell::new(&row.key)];
+
+        if subtrees {
+            cells.push(Cell::new(row.packages).set_alignment(CellAlignment::Right));
+        }
+
+        cells.push(Cell::new(row.footprint.files).set_alignment(CellAlignment::Right));
+        cells.push(size);
+
+        table.add_row(cells);
+    }
+
+    table
+}
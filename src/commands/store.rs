@@ -0,0 +1,125 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Manage the package store across projects.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    cache::collect_unreferenced,
+    lock::{lock_store, LockMode},
+    reporter::{emit, Event},
+};
+
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use colored::Colorize;
+use indicatif::HumanBytes;
+use miette::Result;
+use serde_json::json;
+
+/// Manage the package store across projects
+#[derive(Debug, Parser)]
+pub struct Store {
+    #[clap(subcommand)]
+    cmd: StoreCommand,
+}
+
+#[async_trait]
+impl VoltCommand for Store {
+    /// Execute the `volt store` command
+    ///
+    /// Maintain the store shared by the projects installed on this machine.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Remove the packages no project uses anymore
+    /// // .exec() is an async call so you need to await it
+    /// Store { cmd: StoreCommand::Gc(StoreGc { dry_run: false }) }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        match self.cmd {
+            StoreCommand::Gc(x) => x.exec(config).await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StoreCommand {
+    Gc(StoreGc),
+}
+
+/// Remove the packages no known project uses anymore
+///
+/// Projects are known once volt installed them; projects whose node_modules is gone are
+/// forgotten.
+#[derive(Debug, Parser)]
+pub struct StoreGc {
+    /// Only list what would be removed
+    #[clap(long)]
+    dry_run: bool,
+}
+
+#[async_trait]
+impl VoltCommand for StoreGc {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let _lock = lock_store(&config, LockMode::Exclusive).await?;
+
+        let unreferenced = collect_unreferenced(&config.store()?, self.dry_run)?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "forgotten": unreferenced.projects,
+                "removed": unreferenced.packages,
+                "bytes": unreferenced.bytes,
+                "dryRun": self.dry_run,
+            })));
+
+            return Ok(());
+        }
+
+        for project in &unreferenced.projects {
+            println!(
+                "{}",
+                format!("forgot {}", project.display()).truecolor(156, 156, 156)
+            );
+        }
+
+        for package in &unreferenced.packages {
+            println!("{} {}", "-".red(), package);
+        }
+
+        if unreferenced.packages.is_empty() {
+            println!("Every package in the store is used");
+        } else if self.dry_run {
+            println!(
+                "\nWould remove {} packages, run without `--dry-run` to remove them",
+                unreferenced.packages.len()
+            );
+        } else {
+            println!(
+                "\n{} {} packages, reclaiming {}",
+                "Removed".green().bold(),
+                unreferenced.packages.len(),
+                HumanBytes(unreferenced.bytes)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Undo `volt link`.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    links::{unlink_from, unregister},
+    lock::lock_project,
+    reporter::{emit, Event},
+    utils::package::PackageJson,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::json;
+
+/// Unregister the current package, or remove linked packages from the project
+#[derive(Debug, Parser)]
+pub struct Unlink {
+    /// Linked packages to remove from node_modules
+    packages: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Unlink {
+    /// Execute the `volt unlink` command
+    ///
+    /// Without packages, unregister the package in the current directory; with packages,
+    /// remove their links from node_modules.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Go back to the published `my-lib`
+    /// // .exec() is an async call so you need to await it
+    /// Unlink { packages: vec!["my-lib".to_string()] }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        if self.packages.is_empty() {
+            let (package_json, _) = PackageJson::get_from_dir(&config.cwd()?)?;
+
+            let projects = unregister(&config, &package_json.name)?;
+
+            if config.json() {
+                emit(&Event::Result(json!({
+                    "unregistered": package_json.name,
+                    "stillLinkedInto": projects,
+                })));
+
+                return Ok(());
+            }
+
+            println!("{} {}", "Unregistered".green().bold(), package_json.name);
+
+            for project in projects {
+                println!(
+                    "{} still linked into {}, run `volt unlink {}` there",
+                    "note:".cyan().bold(),
+                    project.display(),
+                    package_json.name
+                );
+            }
+
+            return Ok(());
+        }
+
+        let _locks = lock_project(&config).await?;
+
+        let mut unlinked = vec![];
+
+        for name in &self.packages {
+            if unlink_from(&config, name)? {
+                unlinked.push(name.clone());
+            } else {
+                tracing::warn!("{} is not linked into this project", name);
+            }
+        }
+
+        if config.json() {
+            emit(&Event::Result(json!({ "unlinked": unlinked })));
+        } else if !unlinked.is_empty() {
+            for name in &unlinked {
+                println!("{} {}", "Unlinked".green().bold(), name);
+            }
+
+            println!(
+                "{}",
+                "run `volt install` to install them from the registry again"
+                    .truecolor(156, 156, 156)
+            );
+        }
+
+        Ok(())
+    }
+}
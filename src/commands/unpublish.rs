@@ -0,0 +1,123 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Unpublish a package or one of its versions.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    registry::RegistryClient,
+    reporter::{emit, Event},
+    unpublish::{unpublish, Unpublished},
+    utils::package::PackageJson,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use package_spec::{PackageSpec, VersionSpec};
+use serde_json::json;
+
+/// Remove a package, or one of its versions, from the registry
+#[derive(Debug, Parser)]
+pub struct Unpublish {
+    /// The version to unpublish, as `name@version`, or the whole package with only its
+    /// name (the version in the current directory by default)
+    package: Option<PackageSpec>,
+
+    /// Unpublish the whole package, or its last version
+    #[clap(long)]
+    force: bool,
+
+    /// One-time password from your authenticator app, for accounts with two-factor
+    /// authentication
+    #[clap(long)]
+    otp: Option<String>,
+}
+
+#[async_trait]
+impl VoltCommand for Unpublish {
+    /// Execute the `volt unpublish` command
+    ///
+    /// Remove a published version from the registry, or the whole package with `--force`.
+    /// The public registry only unpublishes versions older than 72 hours when nothing
+    /// depends on them, and a name whose package was unpublished can't be published again
+    /// for 24 hours.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Unpublish a broken release
+    /// // .exec() is an async call so you need to await it
+    /// Unpublish { package: Some("my-package@1.2.3".parse()?), force: false, otp: None }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (name, version) = match &self.package {
+            Some(PackageSpec::Npm {
+                name,
+                requested: Some(VersionSpec::Version(version)),
+                ..
+            }) => (name.clone(), Some(version.to_string())),
+            Some(PackageSpec::Npm {
+                name,
+                requested: None,
+                ..
+            }) => (name.clone(), None),
+            Some(package) => {
+                miette::bail!("{} is not a published version, as `name@1.2.3`", package)
+            }
+            None => {
+                let package_json = PackageJson::get_from_dir(&config.cwd()?)?.0;
+
+                (package_json.name, Some(package_json.version))
+            }
+        };
+
+        let client = RegistryClient::new(&config)?.with_otp(self.otp);
+
+        let unpublished = unpublish(
+            &client,
+            &name,
+            version.as_deref(),
+            self.force,
+            &*config.reporter(),
+        )
+        .await?;
+
+        let package = match &unpublished {
+            Unpublished::Package => name.clone(),
+            Unpublished::Version(version) => format!("{}@{}", name, version),
+        };
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "name": name,
+                "version": match unpublished {
+                    Unpublished::Package => None,
+                    Unpublished::Version(version) => Some(version),
+                },
+            })));
+
+            return Ok(());
+        }
+
+        println!("{} {}", "Unpublished".green().bold(), package);
+
+        Ok(())
+    }
+}
@@ -14,53 +14,253 @@
     limitations under the License.
 */
 
-use crate::cli::{VoltCommand, VoltConfig};
+//! Update dependencies to newer versions.
+
+use crate::cli::{prompt::prompts::MultiSelect, VoltCommand, VoltConfig};
+use volt_core::{
+    install::{
+        dependency_specs, install, link_workspace_members, project_dependencies, resolve,
+        write_lock_file, InstallScope, Resolution,
+    },
+    model::lock_file::LockFile,
+    reporter::{emit, Event},
+    update::{outdated_dependencies, update_ranges, updated_range, Outdated},
+    utils::package::PackageJson,
+};
 
 use async_trait::async_trait;
-use miette::Result;
+use clap::Parser;
+use colored::Colorize;
+use dialoguer::console::Term;
+use miette::{IntoDiagnostic, Result};
+use node_semver::Version;
+use serde_json::json;
+
+use std::collections::BTreeMap;
+
+/// Update dependencies to the newest versions their ranges allow, or to the latest
+#[derive(Debug, Parser)]
+pub struct Update {
+    /// Dependencies to update (all of them by default)
+    packages: Vec<String>,
 
-/// Struct implementation for the `Update` command.
-pub struct Update;
+    /// Pick the dependencies to update from a list
+    #[clap(long, short)]
+    interactive: bool,
+
+    /// Update to the `latest` versions, even outside of the ranges in package.json
+    #[clap(long)]
+    latest: bool,
+}
 
 #[async_trait]
 impl VoltCommand for Update {
-    /// Display a help menu for the `volt update` command.
-    //     fn help() -> String {
-    //         format!(
-    //             r#"volt {}
-    //
-    // Update project dependencies
-    //
-    // Usage: {} {} {}
-    //
-    // Options:
-    //
-    //   {} {} Output verbose messages on internal operations."#,
-    //             VERSION.bright_green().bold(),
-    //             "volt".bright_green().bold(),
-    //             "update".bright_purple(),
-    //             "file-name".white(),
-    //             "--verbose".blue(),
-    //             "(-v)".yellow()
-    //         )
-    //     }
-
     /// Execute the `volt update` command
     ///
-    /// update project dependencies
+    /// Find the dependencies with newer versions, let the user pick some with
+    /// `--interactive`, save the new ranges to package.json and install them, leaving the
+    /// rest of volt.lock as it is.
     /// ## Arguments
-    /// * `app` - Instance of the command (`Arc<App>`)
-    /// * `packages` - List of packages to add (`Vec<String>`)
-    /// * `flags` - List of flags passed in through the CLI (`Vec<String>`)
+    /// * `config` - Volt configuration (`VoltConfig`)
     /// ## Examples
     /// ```
-    /// // update project dependencies
+    /// // Choose what to move to the latest versions
     /// // .exec() is an async call so you need to await it
-    /// update.exec(app, vec![], vec!["--yes"]).await;
+    /// Update { packages: vec![], interactive: true, latest: true }.exec(config).await;
     /// ```
     /// ## Returns
     /// * `Result<()>`
-    async fn exec(self, _config: VoltConfig) -> Result<()> {
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let outdated: Vec<Outdated> = outdated_dependencies(&config, &self.packages)
+            .await?
+            .into_iter()
+            .filter(|outdated| outdated.current.as_deref() != Some(outdated.target(self.latest)))
+            .collect();
+
+        if outdated.is_empty() {
+            if config.json() {
+                emit(&Event::Result(json!({ "updated": {} })));
+            } else {
+                println!(
+                    "{} every dependency is up to date",
+                    "update:".green().bold()
+                );
+            }
+
+            return Ok(());
+        }
+
+        let chosen: Vec<&Outdated> = if self.interactive {
+            if config.json() || !Term::stdout().is_term() {
+                miette::bail!("`volt update --interactive` needs a terminal");
+            }
+
+            pick(&outdated, self.latest)?
+        } else {
+            outdated.iter().collect()
+        };
+
+        if chosen.is_empty() {
+            return Ok(());
+        }
+
+        let ranges: BTreeMap<String, String> = chosen
+            .iter()
+            .map(|outdated| {
+                (
+                    outdated.name.clone(),
+                    updated_range(&outdated.range, outdated.target(self.latest)),
+                )
+            })
+            .collect();
+
+        let (_, manifest_path) = PackageJson::get_from_dir(&config.cwd()?)?;
+
+        update_ranges(&manifest_path, &ranges)?;
+
+        let (dependencies, workspace) =
+            project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        // only the updated dependencies move, the rest stays as locked
+        let (updated, unchanged): (BTreeMap<_, _>, BTreeMap<_, _>) = dependencies
+            .into_iter()
+            .partition(|(name, _)| ranges.contains_key(name));
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let mut resolution = match Resolution::from_lock_file(&lock_file, &unchanged) {
+            Some(resolution) => resolution,
+            None => resolve(&config, &dependency_specs(&unchanged)).await?,
+        };
+
+        resolution.merge(resolve(&config, &dependency_specs(&updated)).await?);
+
+        write_lock_file(&config, &resolution)?;
+
+        install(&config, resolution).await?;
+
+        if let Some(workspace) = &workspace {
+            link_workspace_members(&config, workspace)?;
+        }
+
+        if config.json() {
+            emit(&Event::Result(json!({ "updated": ranges })));
+
+            return Ok(());
+        }
+
+        for outdated in chosen {
+            println!(
+                "{} {} {} → {}",
+                "Updated".green().bold(),
+                outdated.name,
+                outdated
+                    .current
+                    .as_deref()
+                    .unwrap_or("-")
+                    .truecolor(156, 156, 156),
+                outdated.target(self.latest)
+            );
+        }
+
         Ok(())
     }
 }
+
+/// Show the outdated dependencies as a table to check rows of.
+fn pick(outdated: &[Outdated], latest: bool) -> Result<Vec<&Outdated>> {
+    let current = |outdated: &Outdated| outdated.current.clone().unwrap_or_else(|| "-".into());
+
+    let width = |header: &str, column: &dyn Fn(&Outdated) -> String| {
+        outdated
+            .iter()
+            .map(|outdated| column(outdated).len())
+            .chain(std::iter::once(header.len()))
+            .max()
+            .unwrap_or_default()
+    };
+
+    let widths = [
+        width("Package", &|outdated| outdated.name.clone()),
+        width("Current", &current),
+        width("Wanted", &|outdated| outdated.wanted.clone()),
+        width("Latest", &|outdated| outdated.latest.clone()),
+    ];
+
+    // lines up with the items, after the prompt's two characters of prefix
+    println!(
+        "    {:<a$}  {:<b$}  {:<c$}  {:<d$}  {}",
+        "Package".bold(),
+        "Current".bold(),
+        "Wanted".bold(),
+        "Latest".bold(),
+        "Changelog".bold(),
+        a = widths[0],
+        b = widths[1],
+        c = widths[2],
+        d = widths[3],
+    );
+
+    let items = outdated
+        .iter()
+        .map(|outdated| {
+            let target = outdated.target(latest);
+
+            format!(
+                "{:<a$}  {:<b$}  {:<c$}  {:<d$}  {}",
+                outdated.name,
+                current(outdated),
+                paint(outdated.current.as_deref(), &outdated.wanted, target),
+                paint(outdated.current.as_deref(), &outdated.latest, target),
+                outdated
+                    .changelog
+                    .as_deref()
+                    .unwrap_or_default()
+                    .truecolor(156, 156, 156),
+                a = widths[0],
+                b = widths[1],
+                c = widths[2],
+                d = widths[3],
+            )
+            .into()
+        })
+        .collect();
+
+    let prompt = MultiSelect {
+        message: if latest {
+            "Update to the latest versions".into()
+        } else {
+            "Update to the wanted versions".into()
+        },
+        items,
+    };
+
+    let checked = prompt.run().into_diagnostic()?;
+
+    Ok(checked.into_iter().map(|index| &outdated[index]).collect())
+}
+
+/// A version colored by how big a change it is from `current`: red for a new major
+/// version, yellow for a minor one, green for a patch. Only the version updated to is
+/// colored.
+fn paint(current: Option<&str>, version: &str, target: &str) -> String {
+    if version != target {
+        return version.to_string();
+    }
+
+    let (current, next) = match (
+        current.and_then(|current| current.parse::<Version>().ok()),
+        version.parse::<Version>(),
+    ) {
+        (Some(current), Ok(next)) => (current, next),
+        _ => return version.to_string(),
+    };
+
+    if next.major != current.major {
+        version.red().to_string()
+    } else if next.minor != current.minor {
+        version.yellow().to_string()
+    } else {
+        version.green().to_string()
+    }
+}
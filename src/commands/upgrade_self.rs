@@ -0,0 +1,110 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Upgrade volt to its latest release.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    reporter::{emit, Event},
+    upgrade::{current_version, download, latest_release, replace_executable},
+    utils::errors::VoltError,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::json;
+
+/// Upgrade volt to its latest release
+#[derive(Debug, Parser)]
+pub struct UpgradeSelf {
+    /// Only check whether a newer release is out
+    #[clap(long)]
+    check: bool,
+}
+
+#[async_trait]
+impl VoltCommand for UpgradeSelf {
+    /// Execute the `volt upgrade-self` command
+    ///
+    /// Replace the running volt with the build of the latest release for this platform,
+    /// once its checksum matches the release's.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // .exec() is an async call so you need to await it
+    /// UpgradeSelf { check: false }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let current = current_version();
+
+        let release = latest_release(&config).await?;
+
+        let latest = release.version().ok_or_else(|| VoltError::InvalidRelease {
+            url: release.tag_name.clone(),
+            reason: "the tag isn't a version".to_string(),
+        })?;
+
+        let outdated = latest > current;
+
+        if !outdated || self.check {
+            if config.json() {
+                emit(&Event::Result(json!({
+                    "current": current.to_string(),
+                    "latest": latest.to_string(),
+                    "upgraded": false,
+                })));
+            } else if outdated {
+                println!(
+                    "volt {} is out, run `volt upgrade-self` to upgrade from {}",
+                    latest.to_string().green().bold(),
+                    current
+                );
+            } else {
+                println!("volt {} is the latest release", current);
+            }
+
+            return Ok(());
+        }
+
+        let binary = download(&config, &release).await?;
+
+        let executable = replace_executable(&binary)?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "current": current.to_string(),
+                "latest": latest.to_string(),
+                "upgraded": true,
+                "path": executable,
+            })));
+        } else {
+            println!(
+                "{} volt {} → {} {}",
+                "Upgraded".green().bold(),
+                current,
+                latest,
+                format!("({})", executable.display()).truecolor(156, 156, 156)
+            );
+        }
+
+        Ok(())
+    }
+}
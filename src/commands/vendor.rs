@@ -0,0 +1,130 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Copy the registry tarballs of a project into `./vendor`.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    install::{
+        check_lock_file, dependency_specs, project_dependencies, resolve, write_lock_file,
+        InstallScope, Resolution,
+    },
+    model::lock_file::LockFile,
+    reporter::{emit, Event},
+    signatures::verify_signatures,
+    utils::errors::ResolutionError,
+    vendor::vendor,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+use serde_json::json;
+
+/// Copy the tarball of every registry package into ./vendor, to install with `--vendored`
+#[derive(Debug, Parser)]
+pub struct Vendor {
+    /// Fail instead of updating volt.lock when it doesn't match package.json
+    #[clap(long)]
+    frozen_lockfile: bool,
+}
+
+#[async_trait]
+impl VoltCommand for Vendor {
+    /// Execute the `volt vendor` command
+    ///
+    /// Write the tarballs of the packages volt.lock resolves into the `vendor` directory,
+    /// and remove the ones it no longer does.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Vendor the dependencies, then `volt install --vendored` without the registry
+    /// // .exec() is an async call so you need to await it
+    /// Vendor { frozen_lockfile: true }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (dependencies, _) = project_dependencies(&config.cwd()?, &[], InstallScope::All)?;
+
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        let resolution = match Resolution::from_lock_file(&lock_file, &dependencies) {
+            Some(resolution) if self.frozen_lockfile => {
+                check_lock_file(&config, &resolution)?;
+
+                resolution
+            }
+            Some(resolution) => resolution,
+            None if self.frozen_lockfile => {
+                return Err(ResolutionError::LockFileOutdated {
+                    path: config.lockfile()?.display().to_string(),
+                }
+                .into());
+            }
+            None => {
+                let resolution = resolve(&config, &dependency_specs(&dependencies)).await?;
+
+                write_lock_file(&config, &resolution)?;
+
+                resolution
+            }
+        };
+
+        // vendored installs don't check signatures, so they are checked before vendoring
+        let signatures = verify_signatures(&config, &resolution).await?;
+
+        let directory = config.vendor_directory()?;
+
+        let report = vendor(&config, &resolution, &directory).await?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "directory": directory,
+                "added": report.added,
+                "kept": report.kept,
+                "removed": report.removed,
+            })));
+
+            return Ok(());
+        }
+
+        if let Some(signatures) = signatures {
+            if !signatures.unsigned.is_empty() {
+                config.reporter().warning(&format!(
+                    "no registry signature for {}",
+                    signatures.unsigned.join(", ")
+                ));
+            }
+        }
+
+        println!(
+            "{} {} packages into {} ({} added, {} removed)",
+            "Vendored".green().bold(),
+            report.added + report.kept,
+            directory
+                .strip_prefix(config.cwd()?)
+                .unwrap_or(&directory)
+                .display(),
+            report.added,
+            report.removed
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Check that package.json, volt.lock and node_modules agree.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    lock::{lock_store, LockMode},
+    reporter::{emit, Event},
+    utils::errors::VoltError,
+    verify::verify_project,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::Result;
+use serde_json::json;
+
+/// Check that package.json, volt.lock and node_modules agree, failing when they don't
+#[derive(Debug, Parser)]
+pub struct Verify {}
+
+#[async_trait]
+impl VoltCommand for Verify {
+    /// Execute the `volt verify` command
+    ///
+    /// Report the dependencies volt.lock doesn't have, the packages missing from
+    /// node_modules, modified or extra, and the `.bin` shims pointing to nothing. Nothing
+    /// is changed, and any problem fails the command, for CI.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // .exec() is an async call so you need to await it
+    /// Verify {}.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        // the store isn't read while another volt is writing it
+        let _lock = lock_store(&config, LockMode::Shared).await?;
+
+        let problems = verify_project(&config)?;
+
+        if config.json() {
+            emit(&Event::Result(json!({
+                "ok": problems.is_empty(),
+                "problems": problems
+                    .iter()
+                    .map(|problem| json!({
+                        "kind": problem.kind.to_string(),
+                        "subject": problem.subject,
+                        "detail": problem.detail,
+                    }))
+                    .collect::<Vec<_>>(),
+            })));
+        } else if problems.is_empty() {
+            println!(
+                "{} package.json, volt.lock and node_modules agree",
+                "✓".green().bold()
+            );
+        } else {
+            for problem in &problems {
+                println!(
+                    "{} {} {} {}",
+                    "✗".red().bold(),
+                    format!("{:<12}", problem.kind.to_string()).yellow(),
+                    problem.subject,
+                    format!("({})", problem.detail).truecolor(156, 156, 156)
+                );
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(VoltError::VerifyFailed {
+                count: problems.len(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Explain why a package is installed.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::{
+    model::lock_file::{LockFile, LockedPackage},
+    utils::package::PackageJson,
+};
+
+use async_trait::async_trait;
+use clap::Parser;
+use colored::Colorize;
+use miette::{IntoDiagnostic, Result};
+use node_semver::Version;
+
+use std::collections::BTreeMap;
+
+/// Chains printed per version before the rest are summarized.
+const MAX_CHAINS: usize = 25;
+
+/// Show the chains of dependents that caused a package to be installed
+#[derive(Debug, Parser)]
+pub struct Why {
+    /// Name of the package, optionally with an exact version (`ms@2.0.0`)
+    package: String,
+}
+
+#[async_trait]
+impl VoltCommand for Why {
+    /// Execute the `volt why` command
+    ///
+    /// Walk the lock file backwards from every locked version of a package to the
+    /// project's own dependencies.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Explain why `ms` is installed
+    /// // .exec() is an async call so you need to await it
+    /// Why { package: "ms".into() }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        let (package_json, _) = PackageJson::get_from_dir(&config.cwd()?)?;
+        let lock_file = LockFile::load(config.lockfile()?, false).into_diagnostic()?;
+
+        // `@scope/name@1.0.0` -> (`@scope/name`, Some(`1.0.0`))
+        let (name, version) = match self.package.rfind('@') {
+            Some(index) if index > 0 => (&self.package[..index], Some(&self.package[index + 1..])),
+            _ => (self.package.as_str(), None),
+        };
+
+        let mut matches: Vec<&LockedPackage> = lock_file
+            .dependencies
+            .values()
+            .filter(|package| package.name == name)
+            .filter(|package| version.map_or(true, |version| package.version == version))
+            .collect();
+
+        if matches.is_empty() {
+            miette::bail!("{} is not in volt.lock", self.package);
+        }
+
+        // by version rather than as strings (`1.10.0` after `1.9.0`), unparsable ones last
+        matches.sort_by_key(|package| {
+            let version = package.version.parse::<Version>().ok();
+
+            (version.is_none(), version, package.version.clone())
+        });
+
+        let direct: BTreeMap<&String, &String> = package_json
+            .dependencies
+            .iter()
+            .chain(package_json.dev_dependencies.iter())
+            .flatten()
+            .collect();
+
+        for package in matches {
+            let key = package.key();
+
+            println!(
+                "{} {}@{}",
+                "=>".truecolor(156, 156, 156),
+                package.name.truecolor(000, 255, 000),
+                package.version.truecolor(000, 155, 000)
+            );
+
+            // one more than shown, to tell whether there are others
+            let chains = lock_file.chains(&key, &direct, MAX_CHAINS + 1);
+
+            if chains.is_empty() {
+                println!("   not required by anything, run `volt install` to remove it");
+            }
+
+            for chain in chains.iter().take(MAX_CHAINS) {
+                let chain: Vec<&str> = std::iter::once(package_json.name.as_str())
+                    .chain(chain.iter().rev().map(String::as_str))
+                    .collect();
+
+                println!(
+                    "   {}",
+                    chain.join(&" > ".truecolor(156, 156, 156).to_string())
+                );
+            }
+
+            if chains.len() > MAX_CHAINS {
+                println!("   ... and more");
+            }
+
+            println!();
+        }
+
+        Ok(())
+    }
+}
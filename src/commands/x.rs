@@ -0,0 +1,75 @@
+/*
+    Copyright 2021 Volt Contributors
+
+    Licensed under the Apache License, Version 2.0 (the "License");
+    you may not use this file except in compliance with the License.
+    You may obtain a copy of the License at
+
+        http://www.apache.org/licenses/LICENSE-2.0
+
+    Unless required by applicable law or agreed to in writing, software
+    distributed under the License is distributed on an "AS IS" BASIS,
+    WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+    See the License for the specific language governing permissions and
+    limitations under the License.
+*/
+
+//! Run the executable of a package without installing it into the project.
+
+use crate::cli::{VoltCommand, VoltConfig};
+use volt_core::dlx::{run_command, TemporaryPrefix};
+
+use async_trait::async_trait;
+use clap::Parser;
+use miette::{IntoDiagnostic, Result};
+use package_spec::PackageSpec;
+
+/// Run the executable of a package without installing it into the project
+#[derive(Debug, Parser)]
+pub struct X {
+    /// Package providing the command, instead of the command itself (can be repeated)
+    #[clap(short, long = "package", number_of_values = 1)]
+    packages: Vec<PackageSpec>,
+
+    /// Package to run (`create-react-app@5`), or the command to run with `--package`,
+    /// followed by the arguments passed through to it
+    #[clap(required = true, multiple_values = true)]
+    command: Vec<String>,
+}
+
+#[async_trait]
+impl VoltCommand for X {
+    /// Execute the `volt x` command
+    ///
+    /// Install the package into a temporary prefix, run its executable from the current
+    /// directory and remove the prefix.
+    /// ## Arguments
+    /// * `config` - Volt configuration (`VoltConfig`)
+    /// ## Examples
+    /// ```
+    /// // Run `tsc --version` from the typescript package
+    /// // .exec() is an async call so you need to await it
+    /// X { packages: vec!["typescript".parse()?], command: vec!["tsc".into(), "--version".into()] }.exec(config).await;
+    /// ```
+    /// ## Returns
+    /// * `Result<()>`
+    async fn exec(self, config: VoltConfig) -> Result<()> {
+        // clap makes sure there is at least one value
+        let (command, args) = self.command.split_first().unwrap();
+
+        let (packages, command) = if self.packages.is_empty() {
+            (vec![command.parse().into_diagnostic()?], None)
+        } else {
+            (self.packages, Some(command.clone()))
+        };
+
+        let prefix = TemporaryPrefix::install(&config, &packages).await?;
+
+        let command = match command {
+            Some(command) => command,
+            None => prefix.default_command()?,
+        };
+
+        run_command(&config, prefix.bin_dir(), &command, args)
+    }
+}
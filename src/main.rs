@@ -21,41 +21,111 @@ static GLOBAL: MiMalloc = MiMalloc;
 
 mod cli;
 mod commands;
-mod core;
 
-use std::{str::FromStr, time::Instant};
+use std::time::Instant;
 
-use tracing::Level;
-use tracing_subscriber::EnvFilter;
+use colored::Colorize;
+use futures::FutureExt;
 
-use crate::cli::{VoltCli, VoltCommand};
+use crate::cli::{logging, trace::chrome_trace, VoltCli, VoltCommand, VoltSubCmd};
+use volt_core::{
+    reporter::{emit, Event},
+    toolchain, upgrade,
+};
 
 //#[tokio::main(worker_threads = 6)]
 //#[tokio::main(flavor = "current_thread")]
 fn main() -> miette::Result<()> {
-    let body = async {
-        tracing_subscriber::fmt()
-            .with_max_level(Level::TRACE)
-            .with_env_filter(
-                EnvFilter::try_from_default_env()
-                    .unwrap_or_else(|_| EnvFilter::from_str("volt=info").unwrap()),
-            )
-            .without_time()
-            .init();
+    // started through the `node`, `npm` or `npx` shim rather than as volt
+    if let Some(tool) = toolchain::shim_name() {
+        std::process::exit(toolchain::run_shim(&tool)?);
+    }
 
+    let body = async {
         if cfg!(windows) {
-            core::utils::enable_ansi_support().unwrap();
+            volt_core::utils::enable_ansi_support().unwrap();
         }
 
         let start = Instant::now();
 
         let app = VoltCli::new();
 
-        app.cmd.exec(app.config).await?;
+        let mut config = app.options.config();
+
+        let (trace, trace_file) = match app.options.trace_file() {
+            Some(path) => {
+                let (layer, file) = chrome_trace(path.clone());
+
+                (Some(layer), Some(file))
+            }
+            None => (None, None),
+        };
+
+        let log_file = logging::init(&config, trace);
+
+        config.load_settings()?;
+
+        let json = config.json();
+        let quiet = config.quiet();
+
+        // runs alongside the command, and is only mentioned if it is done by the end
+        let update = if json || quiet || matches!(app.cmd, VoltSubCmd::UpgradeSelf(_)) {
+            None
+        } else {
+            upgrade::check_for_update(&config)
+        };
+
+        let connections = config.connections();
+
+        let result = app.cmd.exec(config).await;
 
-        println!("Finished in {:.2}s", start.elapsed().as_secs_f32());
+        // shown with --verbose
+        for host in connections.stats() {
+            tracing::debug!("{}", host);
+        }
+
+        // the spans of the command are closed by now
+        if let Some(trace_file) = &trace_file {
+            if let Err(error) = trace_file.write() {
+                tracing::warn!("the trace wasn't written: {}", error);
+            }
+        }
+
+        if json {
+            // the diagnostic is still printed to stderr for whoever runs the tool
+            match &result {
+                Ok(()) => emit(&Event::Finished {
+                    elapsed: start.elapsed().as_secs_f32(),
+                }),
+                Err(report) => emit(&Event::Error {
+                    code: report.code().map(|code| code.to_string()),
+                    message: report.to_string(),
+                }),
+            }
+        } else if result.is_ok() && !quiet {
+            println!("Finished in {:.2}s", start.elapsed().as_secs_f32());
+
+            if let Some(Ok(Some(version))) = update.and_then(|check| check.now_or_never()) {
+                eprintln!(
+                    "{} volt {} is out, run `volt upgrade-self` to upgrade from {}",
+                    "note:".cyan().bold(),
+                    version,
+                    upgrade::current_version()
+                );
+            }
+        }
+
+        if let (Err(report), Some(log_file)) = (&result, &log_file) {
+            tracing::debug!("failed: {:?}", report);
+
+            eprintln!(
+                "{} a complete log of this run is in {}",
+                "note:".cyan().bold(),
+                log_file.display()
+            );
+        }
 
-        Ok(())
+        result
     };
 
     tokio::runtime::Builder::new_multi_thread()